@@ -0,0 +1,35 @@
+-- Weekly community impact rollups per geo area, written by the impact report worker.
+-- One row per (geo_boundary_key, week_start); reruns overwrite the same row.
+
+create table if not exists impact_reports (
+  id uuid primary key default gen_random_uuid(),
+  geo_boundary_key text not null,
+  week_start date not null,
+  completed_claim_count integer not null default 0,
+  quantity_shared numeric(14,3) not null default 0,
+  unique_growers integer not null default 0,
+  unique_gatherers integer not null default 0,
+  top_crops jsonb not null default '[]'::jsonb,
+  generated_at timestamptz not null default now(),
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint impact_reports_geo_boundary_format check (
+    geo_boundary_key ~ '^[0-9b-hjkmnp-z]{1,12}$'
+  ),
+  constraint impact_reports_week_start_monday check (extract(isodow from week_start) = 1),
+  constraint impact_reports_counts_nonnegative check (
+    completed_claim_count >= 0 and unique_growers >= 0 and unique_gatherers >= 0
+  ),
+  constraint impact_reports_quantity_nonnegative check (quantity_shared >= 0)
+);
+
+create unique index if not exists idx_impact_reports_geo_week
+  on impact_reports (geo_boundary_key, week_start);
+
+create index if not exists idx_impact_reports_week_start
+  on impact_reports (week_start desc);
+
+create index if not exists idx_claims_completed_at
+  on claims (completed_at)
+  where status = 'completed';
@@ -0,0 +1,17 @@
+-- Durable progress markers for chunked pipeline runs (e.g. derived signal replay),
+-- so a run can resume from Lambda/Step Functions without local state.
+
+create table if not exists pipeline_checkpoints (
+  pipeline_name text not null,
+  mode text not null,
+  cursor text,
+  run_started_at timestamptz not null,
+  processed_scopes integer not null default 0,
+  status text not null default 'running',
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  primary key (pipeline_name, mode),
+  constraint pipeline_checkpoints_status_values check (status in ('running', 'completed')),
+  constraint pipeline_checkpoints_processed_nonnegative check (processed_scopes >= 0)
+);
@@ -0,0 +1,21 @@
+-- Tracks how often each derived feed scope is read so the scheduled baseline
+-- sweep can refresh the busiest quiet geos first.
+
+create table if not exists feed_geo_access (
+  geo_boundary_key text not null,
+  window_days smallint not null,
+  access_count bigint not null default 0,
+  last_accessed_at timestamptz not null default now(),
+
+  primary key (geo_boundary_key, window_days),
+  constraint feed_geo_access_geo_boundary_format check (
+    geo_boundary_key ~ '^[0-9b-hjkmnp-z]{1,12}$'
+  ),
+  constraint feed_geo_access_window_days_allowed check (window_days in (7, 14, 30))
+);
+
+create index if not exists idx_feed_geo_access_count
+  on feed_geo_access (access_count desc, last_accessed_at desc);
+
+create index if not exists idx_derived_supply_signals_computed_at
+  on derived_supply_signals (computed_at);
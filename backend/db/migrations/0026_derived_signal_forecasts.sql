@@ -0,0 +1,43 @@
+-- Projected scarcity/abundance per geo scope, written daily by the forecast worker
+-- from the history in derived_supply_signals.
+
+create table if not exists derived_signal_forecasts (
+  id bigserial primary key,
+  schema_version integer not null default 1,
+  geo_boundary_key text not null,
+  crop_id uuid references crops(id) on delete cascade,
+  crop_scope_id uuid generated always as (
+    coalesce(crop_id, '00000000-0000-0000-0000-000000000000'::uuid)
+  ) stored,
+  window_days smallint not null,
+  horizon_days smallint not null,
+  target_date date not null,
+  projected_scarcity_score numeric(8,4) not null,
+  projected_abundance_score numeric(8,4) not null,
+  model text not null,
+  sample_count integer not null,
+  generated_at timestamptz not null default now(),
+  expires_at timestamptz not null,
+
+  constraint derived_signal_forecasts_geo_boundary_format check (
+    geo_boundary_key ~ '^[0-9b-hjkmnp-z]{1,12}$'
+  ),
+  constraint derived_signal_forecasts_window_days_allowed check (window_days in (7, 14, 30)),
+  constraint derived_signal_forecasts_horizon_allowed check (horizon_days in (7, 14)),
+  constraint derived_signal_forecasts_scores_nonnegative check (
+    projected_scarcity_score >= 0 and projected_abundance_score >= 0
+  ),
+  constraint derived_signal_forecasts_expiry_check check (expires_at > generated_at)
+);
+
+create unique index if not exists idx_derived_signal_forecasts_identity
+  on derived_signal_forecasts (
+    schema_version,
+    geo_boundary_key,
+    crop_scope_id,
+    window_days,
+    horizon_days
+  );
+
+create index if not exists idx_derived_signal_forecasts_expires_at
+  on derived_signal_forecasts (expires_at);
@@ -0,0 +1,30 @@
+-- Sudden week-over-week swings in derived signals, recorded by the rolling
+-- aggregation worker. At most one row per scope/metric/day.
+
+create table if not exists signal_anomalies (
+  id bigserial primary key,
+  geo_boundary_key text not null,
+  crop_id uuid references crops(id) on delete cascade,
+  crop_scope_id uuid generated always as (
+    coalesce(crop_id, '00000000-0000-0000-0000-000000000000'::uuid)
+  ) stored,
+  window_days smallint not null,
+  metric text not null,
+  previous_value numeric(8,4) not null,
+  current_value numeric(8,4) not null,
+  ratio numeric(10,4),
+  detected_on date not null,
+  detected_at timestamptz not null default now(),
+
+  constraint signal_anomalies_geo_boundary_format check (
+    geo_boundary_key ~ '^[0-9b-hjkmnp-z]{1,12}$'
+  ),
+  constraint signal_anomalies_metric_values check (metric in ('scarcity', 'abundance')),
+  constraint signal_anomalies_window_days_allowed check (window_days in (7, 14, 30))
+);
+
+create unique index if not exists idx_signal_anomalies_identity
+  on signal_anomalies (geo_boundary_key, crop_scope_id, window_days, metric, detected_on);
+
+create index if not exists idx_signal_anomalies_geo_detected
+  on signal_anomalies (geo_boundary_key text_pattern_ops, detected_at desc);
@@ -0,0 +1,35 @@
+-- Partner organizations (e.g. food banks) and the API keys they use to call the
+-- API without a human Cognito session. Each organization acts through a
+-- non-human service user so existing ownership columns keep working.
+
+create table if not exists organizations (
+  id uuid primary key default gen_random_uuid(),
+  name text not null,
+  service_user_id uuid not null unique references users(id) on delete restrict,
+  created_by uuid references users(id) on delete set null,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+  deleted_at timestamptz,
+
+  constraint organizations_name_not_blank check (btrim(name) <> '')
+);
+
+create table if not exists api_keys (
+  id uuid primary key default gen_random_uuid(),
+  organization_id uuid not null references organizations(id) on delete cascade,
+  name text not null,
+  key_prefix text not null,
+  key_hash text not null unique,
+  scopes text[] not null default '{}',
+  created_by uuid references users(id) on delete set null,
+  created_at timestamptz not null default now(),
+  last_used_at timestamptz,
+  expires_at timestamptz,
+  revoked_at timestamptz,
+
+  constraint api_keys_name_not_blank check (btrim(name) <> ''),
+  constraint api_keys_key_hash_format check (key_hash ~ '^[0-9a-f]{64}$')
+);
+
+create index if not exists idx_api_keys_organization
+  on api_keys (organization_id, created_at desc);
@@ -0,0 +1,49 @@
+-- 0029_audit_log.sql
+-- Append-only record of sensitive actions (admin operations, address changes,
+-- disclosure-policy overrides) for moderator and support review.
+
+begin;
+
+create table if not exists audit_log (
+  id bigserial primary key,
+  occurred_at timestamptz not null default now(),
+  -- No foreign keys: referential actions would update rows, and entries must
+  -- outlive the users and keys they mention.
+  actor_user_id uuid,
+  actor_api_key_id uuid,
+  action text not null,
+  target_type text not null,
+  target_id text not null,
+  before_snapshot jsonb,
+  after_snapshot jsonb,
+  correlation_id text,
+
+  constraint audit_log_action_not_blank check (btrim(action) <> ''),
+  constraint audit_log_target_type_not_blank check (btrim(target_type) <> '')
+);
+
+create index if not exists idx_audit_log_occurred
+  on audit_log (occurred_at desc, id desc);
+
+create index if not exists idx_audit_log_actor
+  on audit_log (actor_user_id, occurred_at desc)
+  where actor_user_id is not null;
+
+create index if not exists idx_audit_log_target
+  on audit_log (target_type, target_id, occurred_at desc);
+
+create or replace function audit_log_reject_mutation()
+returns trigger
+language plpgsql
+as $$
+begin
+  raise exception 'audit_log is append-only';
+end;
+$$;
+
+drop trigger if exists audit_log_append_only on audit_log;
+create trigger audit_log_append_only
+  before update or delete on audit_log
+  for each row execute function audit_log_reject_mutation();
+
+commit;
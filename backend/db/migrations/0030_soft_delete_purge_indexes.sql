@@ -0,0 +1,16 @@
+-- 0030_soft_delete_purge_indexes.sql
+-- Purpose: let the soft-delete purge worker find rows past retention without
+-- scanning live data. Partial indexes stay small because only deleted rows
+-- are included.
+
+begin;
+
+create index if not exists idx_surplus_listings_soft_deleted
+  on surplus_listings (deleted_at)
+  where deleted_at is not null;
+
+create index if not exists idx_requests_soft_deleted
+  on requests (deleted_at)
+  where deleted_at is not null;
+
+commit;
@@ -0,0 +1,56 @@
+-- Direct messages between participants about a listing or request, for
+-- questions asked before (or without) a claim. Each conversation pairs the
+-- owner of the listing/request with exactly one other participant.
+
+create table if not exists conversations (
+  id uuid primary key default gen_random_uuid(),
+  listing_id uuid references surplus_listings(id) on delete cascade,
+  request_id uuid references requests(id) on delete cascade,
+  owner_id uuid not null references users(id) on delete cascade,
+  participant_id uuid not null references users(id) on delete cascade,
+  created_at timestamptz not null default now(),
+  last_message_at timestamptz not null default now(),
+
+  constraint conversations_single_subject check (
+    (listing_id is not null and request_id is null) or
+    (listing_id is null and request_id is not null)
+  ),
+  constraint conversations_distinct_members check (owner_id <> participant_id)
+);
+
+create unique index if not exists uq_conversations_listing_participant
+  on conversations (listing_id, participant_id)
+  where listing_id is not null;
+
+create unique index if not exists uq_conversations_request_participant
+  on conversations (request_id, participant_id)
+  where request_id is not null;
+
+create index if not exists idx_conversations_owner_recent
+  on conversations (owner_id, last_message_at desc, id desc);
+
+create index if not exists idx_conversations_participant_recent
+  on conversations (participant_id, last_message_at desc, id desc);
+
+create table if not exists messages (
+  id uuid primary key default gen_random_uuid(),
+  conversation_id uuid not null references conversations(id) on delete cascade,
+  sender_id uuid not null references users(id) on delete cascade,
+  body text not null,
+  created_at timestamptz not null default now(),
+
+  constraint messages_body_length check (char_length(btrim(body)) between 1 and 2000)
+);
+
+create index if not exists idx_messages_conversation_created
+  on messages (conversation_id, created_at desc, id desc);
+
+-- Read cursor per member; unread = messages from the other member newer than
+-- last_read_at.
+create table if not exists conversation_reads (
+  conversation_id uuid not null references conversations(id) on delete cascade,
+  user_id uuid not null references users(id) on delete cascade,
+  last_read_at timestamptz not null default now(),
+
+  primary key (conversation_id, user_id)
+);
@@ -0,0 +1,44 @@
+-- Community groups (shared gardens, allotments, school plots) that members
+-- can post surplus listings under. Members join with the group's invite code.
+
+do $$
+begin
+  create type group_member_role as enum ('owner', 'admin', 'member');
+exception
+  when duplicate_object then null;
+end $$;
+
+create table if not exists groups (
+  id uuid primary key default gen_random_uuid(),
+  name text not null,
+  description text,
+  invite_code text not null unique,
+  created_by uuid references users(id) on delete set null,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+  deleted_at timestamptz,
+
+  constraint groups_name_not_blank check (btrim(name) <> ''),
+  constraint groups_invite_code_format check (invite_code ~ '^[A-Z0-9]{8}$')
+);
+
+create table if not exists group_members (
+  group_id uuid not null references groups(id) on delete cascade,
+  user_id uuid not null references users(id) on delete cascade,
+  role group_member_role not null default 'member',
+  joined_at timestamptz not null default now(),
+
+  primary key (group_id, user_id)
+);
+
+create index if not exists idx_group_members_user
+  on group_members (user_id, joined_at desc);
+
+-- Listings posted under a group stay owned by the posting member; group_id
+-- only changes how the listing is attributed.
+alter table surplus_listings
+  add column if not exists group_id uuid references groups(id) on delete set null;
+
+create index if not exists idx_surplus_listings_group_active
+  on surplus_listings (group_id, created_at desc, id desc)
+  where group_id is not null and deleted_at is null;
@@ -0,0 +1,52 @@
+-- Community events (harvest days, gleaning parties, seed swaps) with a
+-- geocoded location, a time window, optional capacity, and RSVPs. Named
+-- community_events to keep clear of the EventBridge "events" vocabulary.
+
+create table if not exists community_events (
+  id uuid primary key default gen_random_uuid(),
+  organizer_id uuid not null references users(id) on delete cascade,
+  group_id uuid references groups(id) on delete set null,
+  title text not null,
+  description text,
+  event_type text not null,
+  location_text text,
+  address text not null,
+  geo_key text not null,
+  lat double precision not null,
+  lng double precision not null,
+  starts_at timestamptz not null,
+  ends_at timestamptz not null,
+  capacity integer,
+  status text not null default 'scheduled',
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+  deleted_at timestamptz,
+
+  constraint community_events_title_not_blank check (btrim(title) <> ''),
+  constraint community_events_type_check check (
+    event_type in ('harvest_day', 'gleaning', 'seed_swap', 'workshop', 'other')
+  ),
+  constraint community_events_status_check check (status in ('scheduled', 'cancelled')),
+  constraint community_events_window check (ends_at > starts_at),
+  constraint community_events_capacity_positive check (capacity is null or capacity > 0)
+);
+
+-- Discovery scans by geohash prefix over events that have not ended yet.
+create index if not exists idx_community_events_discover_geo
+  on community_events (geo_key text_pattern_ops, starts_at, id)
+  where deleted_at is null
+    and status = 'scheduled';
+
+create index if not exists idx_community_events_organizer
+  on community_events (organizer_id, starts_at desc);
+
+create table if not exists community_event_rsvps (
+  event_id uuid not null references community_events(id) on delete cascade,
+  user_id uuid not null references users(id) on delete cascade,
+  created_at timestamptz not null default now(),
+
+  primary key (event_id, user_id)
+);
+
+create index if not exists idx_community_event_rsvps_user
+  on community_event_rsvps (user_id, created_at desc);
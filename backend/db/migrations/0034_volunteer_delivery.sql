@@ -0,0 +1,33 @@
+-- Volunteer delivery for confirmed claims. Gatherers who cannot travel flag
+-- their claim, opted-in volunteer drivers offer to carry it, and the claimer
+-- accepts. One delivery row per claim; a cancelled offer can be replaced.
+
+alter table claims
+  add column if not exists delivery_requested boolean not null default false;
+
+alter table users
+  add column if not exists volunteer_driver boolean not null default false;
+
+create table if not exists claim_deliveries (
+  claim_id uuid primary key references claims(id) on delete cascade,
+  driver_id uuid not null references users(id) on delete cascade,
+  status text not null default 'offered',
+  offered_at timestamptz not null default now(),
+  accepted_at timestamptz,
+  picked_up_at timestamptz,
+  delivered_at timestamptz,
+  cancelled_at timestamptz,
+
+  constraint claim_deliveries_status_check check (
+    status in ('offered', 'accepted', 'picked_up', 'delivered', 'cancelled')
+  )
+);
+
+create index if not exists idx_claim_deliveries_driver
+  on claim_deliveries (driver_id, offered_at desc);
+
+-- Open-delivery board: confirmed claims still waiting for a driver.
+create index if not exists idx_claims_delivery_requested
+  on claims (listing_id)
+  where delivery_requested = true
+    and status = 'confirmed';
@@ -0,0 +1,49 @@
+-- Donation receipts for completed claims made by organization-affiliated
+-- gatherers (partner organizations' service users, or gatherers who list an
+-- organization affiliation). Receipts snapshot names and quantities so they
+-- stay valid for tax documentation after listings or users are purged.
+
+-- Reference prices used for the fair-market value estimate, maintained by
+-- operators. `unit` is matched against the listing unit, trimmed and
+-- lowercased; claims without a matching price get no estimate.
+create table if not exists crop_market_values (
+  crop_id uuid not null references crops(id) on delete cascade,
+  unit text not null,
+  cents_per_unit integer not null,
+  source text not null,
+  updated_at timestamptz not null default now(),
+
+  primary key (crop_id, unit),
+  constraint crop_market_values_unit_normalized check (unit = lower(btrim(unit)) and unit <> ''),
+  constraint crop_market_values_cents_nonnegative check (cents_per_unit >= 0)
+);
+
+create table if not exists donation_receipts (
+  id uuid primary key default gen_random_uuid(),
+  claim_id uuid unique references claims(id) on delete set null,
+  organization_id uuid references organizations(id) on delete set null,
+  organization_name text not null,
+  claimer_id uuid references users(id) on delete set null,
+  grower_id uuid references users(id) on delete set null,
+  grower_name text,
+  crop_id uuid references crops(id) on delete set null,
+  crop_name text not null,
+  quantity numeric(12,3) not null,
+  unit text,
+  estimated_value_cents bigint,
+  value_source text,
+  donated_at timestamptz not null,
+  created_at timestamptz not null default now(),
+
+  constraint donation_receipts_quantity_positive check (quantity > 0),
+  constraint donation_receipts_value_nonnegative check (
+    estimated_value_cents is null or estimated_value_cents >= 0
+  )
+);
+
+create index if not exists idx_donation_receipts_organization_donated
+  on donation_receipts (organization_id, donated_at, id)
+  where organization_id is not null;
+
+create index if not exists idx_donation_receipts_claimer_donated
+  on donation_receipts (claimer_id, donated_at, id);
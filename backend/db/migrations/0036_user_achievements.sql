@@ -0,0 +1,25 @@
+-- Sharing and pickup milestones, written by the achievements worker from
+-- claim events and shown on public profiles. Milestones are earned once per
+-- user; seasonal achievements (season_year set) are earned once per season.
+
+create table if not exists user_achievements (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  achievement_key text not null,
+  season_year integer,
+  earned_at timestamptz not null default now(),
+  progress_snapshot jsonb not null default '{}'::jsonb,
+
+  constraint user_achievements_key_check check (
+    achievement_key in ('first_share', 'shared_100_lb', 'ten_pickups', 'no_show_free_season')
+  ),
+  constraint user_achievements_season_check check (
+    (achievement_key = 'no_show_free_season') = (season_year is not null)
+  )
+);
+
+create unique index if not exists idx_user_achievements_unique
+  on user_achievements (user_id, achievement_key, coalesce(season_year, 0));
+
+create index if not exists idx_user_achievements_user_earned
+  on user_achievements (user_id, earned_at desc);
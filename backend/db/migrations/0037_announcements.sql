@@ -0,0 +1,28 @@
+-- Neighborhood announcements board (frost warnings, seed swaps). Posted by
+-- verified growers or admins for a geohash area and shown in the derived feed
+-- of everyone inside it until they expire.
+
+create table if not exists announcements (
+  id uuid primary key default gen_random_uuid(),
+  author_id uuid not null references users(id) on delete cascade,
+  geo_key text not null,
+  category text not null default 'general',
+  title text not null,
+  body text not null,
+  expires_at timestamptz not null,
+  created_at timestamptz not null default now(),
+  deleted_at timestamptz,
+
+  constraint announcements_geo_key_format check (geo_key ~ '^[0-9b-hjkmnp-z]{1,12}$'),
+  constraint announcements_category_check check (
+    category in ('weather', 'swap', 'safety', 'general')
+  ),
+  constraint announcements_expiry_after_creation check (expires_at > created_at)
+);
+
+create index if not exists idx_announcements_geo_active
+  on announcements (geo_key text_pattern_ops, expires_at)
+  where deleted_at is null;
+
+create index if not exists idx_announcements_author
+  on announcements (author_id, created_at desc);
@@ -0,0 +1,15 @@
+-- Gatherers (or other growers) following a grower. The follower alert worker
+-- fans out a notification event to every follower when the grower posts an
+-- active listing.
+
+create table if not exists user_follows (
+  follower_id uuid not null references users(id) on delete cascade,
+  grower_id uuid not null references users(id) on delete cascade,
+  created_at timestamptz not null default now(),
+
+  primary key (follower_id, grower_id),
+  constraint user_follows_not_self check (follower_id <> grower_id)
+);
+
+create index if not exists idx_user_follows_grower
+  on user_follows (grower_id, created_at);
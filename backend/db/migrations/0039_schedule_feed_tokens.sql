@@ -0,0 +1,6 @@
+-- Calendar apps subscribe to GET /me/schedule.ics without credentials, so the
+-- feed URL carries an HMAC token over (user id, version). Bumping the version
+-- revokes every link handed out before it.
+
+alter table users
+  add column if not exists schedule_feed_version integer not null default 1;
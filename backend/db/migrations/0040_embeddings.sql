@@ -0,0 +1,25 @@
+-- Semantic matching between requests and listings. The embedding worker
+-- stores one vector per listing (title, crop, variety) and per request (crop,
+-- variety, notes) so free-text notes can match listings whose catalog crop
+-- differs. content_hash lets the worker skip unchanged text on update events.
+
+create extension if not exists vector;
+
+create table if not exists listing_embeddings (
+  listing_id uuid primary key references surplus_listings(id) on delete cascade,
+  model_id text not null,
+  content_hash text not null,
+  embedding vector(512) not null,
+  embedded_at timestamptz not null default now()
+);
+
+create index if not exists idx_listing_embeddings_hnsw
+  on listing_embeddings using hnsw (embedding vector_cosine_ops);
+
+create table if not exists request_embeddings (
+  request_id uuid primary key references requests(id) on delete cascade,
+  model_id text not null,
+  content_hash text not null,
+  embedding vector(512) not null,
+  embedded_at timestamptz not null default now()
+);
@@ -0,0 +1,36 @@
+-- Automated text moderation. The moderation worker checks listing titles and
+-- pickup notes and chat messages, and records anything it flags here for an
+-- admin to review. Listings it holds are hidden from discovery, feeds, and
+-- claims via moderation_held_at until a clean edit or a reviewer clears them.
+
+create table if not exists moderation_queue (
+  id uuid primary key default gen_random_uuid(),
+  subject_type text not null,
+  subject_id uuid not null,
+  user_id uuid not null references users(id) on delete cascade,
+  categories text[] not null,
+  action text not null,
+  provider text not null,
+  excerpt text not null,
+  content_hash text not null,
+  status text not null default 'pending',
+  created_at timestamptz not null default now(),
+  resolved_at timestamptz,
+
+  constraint moderation_queue_subject_type_check check (subject_type in ('listing', 'message')),
+  constraint moderation_queue_action_check check (action in ('flag', 'hold')),
+  constraint moderation_queue_status_check check (
+    status in ('pending', 'approved', 'rejected', 'superseded')
+  ),
+  constraint moderation_queue_categories_nonempty check (cardinality(categories) > 0),
+  unique (subject_type, subject_id, content_hash)
+);
+
+create index if not exists idx_moderation_queue_pending
+  on moderation_queue (created_at)
+  where status = 'pending';
+
+create index if not exists idx_moderation_queue_subject
+  on moderation_queue (subject_type, subject_id);
+
+alter table surplus_listings add column if not exists moderation_held_at timestamptz;
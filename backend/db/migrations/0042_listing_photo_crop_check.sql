@@ -0,0 +1,12 @@
+-- Optional photo check: the crop photo worker identifies the crop in listing
+-- photos and records its best guess on the listing. Listings whose guess
+-- differs from crop_id show a mismatch warning to the grower; fixing either
+-- the crop or the photo clears it.
+
+alter table listing_images
+  add column if not exists crop_checked_at timestamptz;
+
+alter table surplus_listings
+  add column if not exists photo_crop_id uuid references crops(id) on delete set null,
+  add column if not exists photo_crop_confidence double precision,
+  add column if not exists photo_crop_checked_at timestamptz;
@@ -0,0 +1,25 @@
+-- Thumbs up/down on AI feed summaries and deterministic grower guidance.
+-- Each row keeps the model id and a snapshot of what the rating was about,
+-- because summaries are overwritten in place when they refresh.
+
+create table if not exists ai_feedback (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  target_type text not null,
+  target_id text not null,
+  rating text not null,
+  reason text,
+  model_id text not null,
+  model_version text not null,
+  snapshot jsonb not null default '{}'::jsonb,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint ai_feedback_target_type_check check (target_type in ('summary', 'guidance')),
+  constraint ai_feedback_rating_check check (rating in ('up', 'down')),
+  constraint ai_feedback_reason_length check (reason is null or char_length(reason) <= 500),
+  unique (user_id, target_type, target_id)
+);
+
+create index if not exists idx_ai_feedback_model
+  on ai_feedback (model_id, created_at desc);
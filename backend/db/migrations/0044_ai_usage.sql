@@ -0,0 +1,28 @@
+-- Per-call accounting for AI summary generation. Unlike ai_usage_events,
+-- which records guardrail decisions per user, this records what each model
+-- call actually cost, including failed and skipped attempts, so a cache miss
+-- storm shows up as a spike in generations per day.
+
+create table if not exists ai_usage (
+  id bigserial primary key,
+  feature_key text not null,
+  provider text not null,
+  model_id text not null,
+  geo_boundary_key text,
+  status text not null,
+  input_tokens integer not null default 0,
+  output_tokens integer not null default 0,
+  latency_ms integer not null default 0,
+  estimated_cost_usd numeric(12, 6) not null default 0,
+  created_at timestamptz not null default now(),
+
+  constraint ai_usage_status_check check (
+    status in ('success', 'error', 'timeout', 'circuit_open')
+  ),
+  constraint ai_usage_counts_nonnegative check (
+    input_tokens >= 0 and output_tokens >= 0 and latency_ms >= 0
+  )
+);
+
+create index if not exists idx_ai_usage_created on ai_usage (created_at desc);
+create index if not exists idx_ai_usage_feature_created on ai_usage (feature_key, created_at desc);
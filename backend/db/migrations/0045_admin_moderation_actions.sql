@@ -0,0 +1,15 @@
+-- Manual moderation by admins. A hidden listing also sets moderation_held_at
+-- so every read path that already skips held listings skips it too, while
+-- admin_hidden_at keeps the moderation worker's clean-edit path from lifting
+-- an admin's hide. Suspended users keep read access but cannot write.
+
+alter table surplus_listings
+  add column if not exists admin_hidden_at timestamptz;
+
+alter table users
+  add column if not exists suspended_at timestamptz,
+  add column if not exists suspension_reason text;
+
+create index if not exists idx_users_suspended
+  on users (suspended_at)
+  where suspended_at is not null;
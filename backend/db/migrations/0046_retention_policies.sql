@@ -0,0 +1,48 @@
+-- How long each kind of stored row is kept. The retention worker reads every
+-- enabled policy on each run and deletes rows whose timestamp_column is older
+-- than retention_days. For expires_at columns the row already carries its own
+-- lifetime, so retention_days is the grace period past expiry.
+
+create table if not exists retention_policies (
+  entity text primary key,
+  table_name text not null,
+  timestamp_column text not null,
+  retention_days integer not null,
+  enabled boolean not null default true,
+  updated_at timestamptz not null default now(),
+
+  -- The worker interpolates these into SQL, so only plain identifiers pass.
+  constraint retention_policies_table_identifier check (table_name ~ '^[a-z_][a-z0-9_]*$'),
+  constraint retention_policies_column_identifier check (timestamp_column ~ '^[a-z_][a-z0-9_]*$'),
+  constraint retention_policies_days_nonnegative check (retention_days >= 0)
+);
+
+insert into retention_policies (entity, table_name, timestamp_column, retention_days, enabled)
+values
+  ('messages', 'messages', 'created_at', 365, true),
+  ('audit_log', 'audit_log', 'occurred_at', 2555, true),
+  ('derived_supply_signals', 'derived_supply_signals', 'expires_at', 0, true),
+  ('derived_signal_summaries', 'derived_signal_summaries', 'expires_at', 0, true),
+  ('derived_signal_forecasts', 'derived_signal_forecasts', 'expires_at', 0, true),
+  -- Geocoding results are not cached in the database yet; the policy is
+  -- recorded so the cache table picks it up when it lands.
+  ('geocode_cache', 'geocode_cache', 'created_at', 90, false)
+on conflict (entity) do nothing;
+
+-- audit_log stays append-only for everything except the retention worker,
+-- which sets app.retention_purge for the one transaction that deletes
+-- entries past their seven years.
+create or replace function audit_log_reject_mutation()
+returns trigger
+language plpgsql
+as $$
+begin
+  if tg_op = 'DELETE' and current_setting('app.retention_purge', true) = 'on' then
+    return old;
+  end if;
+  raise exception 'audit_log is append-only';
+end;
+$$;
+
+create index if not exists idx_messages_created
+  on messages (created_at);
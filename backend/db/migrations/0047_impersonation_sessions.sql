@@ -0,0 +1,23 @@
+-- Time-boxed, read-only support sessions. An admin opens a session for one
+-- user and receives a token; the authorizer accepts the token alongside the
+-- admin's own credentials until expires_at or ended_at. Only the token's
+-- SHA-256 is stored, the same as partner API keys.
+
+create table if not exists impersonation_sessions (
+  id uuid primary key default gen_random_uuid(),
+  admin_user_id uuid not null references users(id) on delete cascade,
+  target_user_id uuid not null references users(id) on delete cascade,
+  reason text not null,
+  token_hash text not null unique,
+  created_at timestamptz not null default now(),
+  expires_at timestamptz not null,
+  ended_at timestamptz,
+
+  constraint impersonation_sessions_reason_not_blank check (btrim(reason) <> ''),
+  constraint impersonation_sessions_token_hash_format check (token_hash ~ '^[0-9a-f]{64}$'),
+  constraint impersonation_sessions_not_self check (admin_user_id <> target_user_id),
+  constraint impersonation_sessions_expiry_after_start check (expires_at > created_at)
+);
+
+create index if not exists idx_impersonation_sessions_target
+  on impersonation_sessions (target_user_id, created_at desc);
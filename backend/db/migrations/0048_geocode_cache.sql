@@ -0,0 +1,29 @@
+-- Forward geocoding results, keyed by the SHA-256 of the lowercased,
+-- whitespace-normalized address so the table never holds an address in
+-- clear. Lookups ignore rows past expires_at; the provider is asked again
+-- and the row is overwritten in place.
+
+create table if not exists geocode_cache (
+  address_hash text primary key,
+  provider text not null,
+  lat double precision not null,
+  lng double precision not null,
+  created_at timestamptz not null default now(),
+  expires_at timestamptz not null,
+
+  constraint geocode_cache_address_hash_format check (address_hash ~ '^[0-9a-f]{64}$'),
+  constraint geocode_cache_lat_range check (lat between -90 and 90),
+  constraint geocode_cache_lng_range check (lng between -180 and 180)
+);
+
+create index if not exists idx_geocode_cache_expires
+  on geocode_cache (expires_at);
+
+-- The retention policy seeded in 0046 was waiting for this table. Entries
+-- carry their own lifetime, so they are purged once expired.
+update retention_policies
+   set timestamp_column = 'expires_at',
+       retention_days = 0,
+       enabled = true,
+       updated_at = now()
+ where entity = 'geocode_cache';
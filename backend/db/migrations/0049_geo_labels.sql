@@ -0,0 +1,21 @@
+-- Display names for geohash prefixes ("Elmwood, Buffalo"), from a reverse
+-- lookup at the cell center. A null label records that the provider had no
+-- name for the cell, so it is not asked again until the row expires.
+
+create table if not exists geo_labels (
+  geo_prefix text primary key,
+  label text,
+  provider text not null,
+  created_at timestamptz not null default now(),
+  expires_at timestamptz not null,
+
+  constraint geo_labels_prefix_format check (geo_prefix ~ '^[0-9b-hjkmnp-z]{1,12}$'),
+  constraint geo_labels_label_not_blank check (label is null or btrim(label) <> '')
+);
+
+create index if not exists idx_geo_labels_expires
+  on geo_labels (expires_at);
+
+insert into retention_policies (entity, table_name, timestamp_column, retention_days, enabled)
+values ('geo_labels', 'geo_labels', 'expires_at', 0, true)
+on conflict (entity) do nothing;
@@ -0,0 +1,8 @@
+-- Match confidence (0 to 1) alongside each cached geocode, so listing and
+-- grower addresses that need a precise match can reject an area-level one
+-- cached by a lenient caller. Rows cached before this were accepted
+-- without a confidence check and are treated as precise.
+
+alter table geocode_cache
+  add column if not exists confidence double precision not null default 1
+    check (confidence between 0 and 1);
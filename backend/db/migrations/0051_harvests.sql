@@ -0,0 +1,34 @@
+-- What growers actually picked, logged per crop in their library. Harvests
+-- are private to the grower; the signal aggregation reads them by geo_key as
+-- ground truth for local supply alongside what was listed. geo_key and
+-- crop_id are copied at write time so history survives a profile move or the
+-- library entry being removed.
+
+create table if not exists harvests (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  grower_crop_id uuid references grower_crop_library(id) on delete set null,
+  crop_id uuid not null references crops(id),
+  harvested_on date not null,
+  quantity numeric(12,3) not null,
+  unit text not null,
+  notes text,
+  geo_key text,
+  listing_id uuid references surplus_listings(id) on delete set null,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint harvests_quantity_positive check (quantity > 0),
+  constraint harvests_unit_not_blank check (btrim(unit) <> '')
+);
+
+create index if not exists idx_harvests_user_harvested
+  on harvests (user_id, harvested_on desc, created_at desc);
+
+create index if not exists idx_harvests_geo_key_harvested
+  on harvests (geo_key text_pattern_ops, harvested_on)
+  where geo_key is not null;
+
+create unique index if not exists idx_harvests_listing
+  on harvests (listing_id)
+  where listing_id is not null;
@@ -0,0 +1,117 @@
+-- 0052_garden_plots.sql
+-- Where a grower's crops are planted: plots (a yard, a community garden
+-- allotment) split into beds. Crop library entries point at a bed, and every
+-- stretch an entry spends in a bed is kept as rotation history. Bed area is
+-- recorded now so yield estimates can use it later.
+
+begin;
+
+create table if not exists garden_plots (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  name text not null,
+  notes text,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint garden_plots_name_not_blank check (btrim(name) <> '')
+);
+
+create unique index if not exists idx_garden_plots_user_name
+  on garden_plots (user_id, lower(name));
+
+create table if not exists garden_beds (
+  id uuid primary key default gen_random_uuid(),
+  plot_id uuid not null references garden_plots(id) on delete cascade,
+  user_id uuid not null references users(id) on delete cascade,
+  name text not null,
+  area_sq_ft numeric(10,2),
+  notes text,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint garden_beds_name_not_blank check (btrim(name) <> ''),
+  constraint garden_beds_area_positive check (area_sq_ft is null or area_sq_ft > 0)
+);
+
+create unique index if not exists idx_garden_beds_plot_name
+  on garden_beds (plot_id, lower(name));
+
+create index if not exists idx_garden_beds_user
+  on garden_beds (user_id);
+
+alter table grower_crop_library
+  add column if not exists bed_id uuid references garden_beds(id) on delete set null;
+
+create index if not exists idx_grower_crop_library_bed
+  on grower_crop_library (bed_id)
+  where bed_id is not null;
+
+-- One row per stretch a library entry spent in a bed. crop_id and variety_id
+-- are copied so the history still reads after the entry is removed.
+create table if not exists garden_bed_plantings (
+  id bigserial primary key,
+  bed_id uuid not null references garden_beds(id) on delete cascade,
+  grower_crop_id uuid references grower_crop_library(id) on delete set null,
+  crop_id uuid not null references crops(id),
+  variety_id uuid references crop_varieties(id) on delete set null,
+  started_at timestamptz not null default now(),
+  ended_at timestamptz,
+
+  constraint garden_bed_plantings_range check (ended_at is null or ended_at >= started_at)
+);
+
+create index if not exists idx_garden_bed_plantings_bed
+  on garden_bed_plantings (bed_id, started_at desc, id desc);
+
+create unique index if not exists idx_garden_bed_plantings_open
+  on garden_bed_plantings (grower_crop_id)
+  where ended_at is null;
+
+-- Kept in the database so every write path to grower_crop_library, including
+-- the bed_id set-null when a bed is deleted, records history the same way.
+create or replace function grower_crop_library_track_bed()
+returns trigger
+language plpgsql
+as $$
+begin
+  if tg_op = 'DELETE' then
+    update garden_bed_plantings
+       set ended_at = now()
+     where grower_crop_id = old.id
+       and ended_at is null;
+    return old;
+  end if;
+
+  if tg_op = 'UPDATE'
+     and new.bed_id is not distinct from old.bed_id
+     and new.crop_id = old.crop_id
+     and new.variety_id is not distinct from old.variety_id then
+    return new;
+  end if;
+
+  update garden_bed_plantings
+     set ended_at = now()
+   where grower_crop_id = new.id
+     and ended_at is null;
+
+  if new.bed_id is not null then
+    insert into garden_bed_plantings (bed_id, grower_crop_id, crop_id, variety_id)
+    values (new.bed_id, new.id, new.crop_id, new.variety_id);
+  end if;
+
+  return new;
+end;
+$$;
+
+drop trigger if exists grower_crop_library_bed_history on grower_crop_library;
+create trigger grower_crop_library_bed_history
+  after insert or update of bed_id, crop_id, variety_id on grower_crop_library
+  for each row execute function grower_crop_library_track_bed();
+
+drop trigger if exists grower_crop_library_bed_history_delete on grower_crop_library;
+create trigger grower_crop_library_bed_history_delete
+  before delete on grower_crop_library
+  for each row execute function grower_crop_library_track_bed();
+
+commit;
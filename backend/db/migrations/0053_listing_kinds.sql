@@ -0,0 +1,30 @@
+-- What a listing offers. Every listing so far is produce, which stays the
+-- default. Seeds and seedlings still name a catalog crop; tool loans and
+-- compost do not, so crop_id becomes optional for those kinds only. Tool
+-- loans carry the date the borrower is expected to return the tool.
+
+do $$
+begin
+  create type listing_kind as enum ('produce', 'seeds', 'seedlings', 'tools_loan', 'compost');
+exception
+  when duplicate_object then null;
+end $$;
+
+alter table surplus_listings
+  add column if not exists listing_kind listing_kind not null default 'produce',
+  add column if not exists return_by timestamptz;
+
+alter table surplus_listings
+  alter column crop_id drop not null;
+
+alter table surplus_listings
+  drop constraint if exists surplus_listings_kind_crop;
+alter table surplus_listings
+  add constraint surplus_listings_kind_crop
+  check (crop_id is not null or listing_kind in ('tools_loan', 'compost'));
+
+alter table surplus_listings
+  drop constraint if exists surplus_listings_kind_return_by;
+alter table surplus_listings
+  add constraint surplus_listings_kind_return_by
+  check ((listing_kind = 'tools_loan') = (return_by is not null));
@@ -0,0 +1,42 @@
+-- Soft holds for claims awaiting the grower's review. A pending claim may
+-- reserve its quantity until hold_expires_at; reserved_quantity is the sum
+-- of live holds on the listing and is subtracted from quantity_remaining
+-- when checking a new claim. quantity_remaining itself now only moves when
+-- a claim is confirmed, cancelled after confirmation, or marked no-show.
+-- The claim auto-cancel worker releases holds that outlive their expiry.
+
+alter table surplus_listings
+  add column if not exists reserved_quantity numeric(12,3) not null default 0;
+
+alter table surplus_listings
+  drop constraint if exists surplus_listings_reserved_quantity_nonnegative;
+alter table surplus_listings
+  add constraint surplus_listings_reserved_quantity_nonnegative
+  check (reserved_quantity >= 0);
+
+alter table claims
+  add column if not exists hold_expires_at timestamptz;
+
+create index if not exists idx_claims_pending_hold_expiry
+  on claims (hold_expires_at)
+  where status = 'pending' and hold_expires_at is not null;
+
+-- Pending claims used to take their quantity off the listing at creation
+-- and again on confirmation. Give it back so confirming them counts once.
+with pending as (
+  select listing_id, sum(quantity_claimed) as quantity
+  from claims
+  where status = 'pending'
+  group by listing_id
+)
+update surplus_listings l
+set quantity_remaining = least(l.quantity_remaining + p.quantity, l.quantity_total),
+    status = case
+      when l.status = 'claimed' and least(l.quantity_remaining + p.quantity, l.quantity_total) > 0
+        then 'active'::listing_status
+      else l.status
+    end
+from pending p
+where l.id = p.listing_id
+  and l.quantity_remaining is not null
+  and l.quantity_total is not null;
@@ -0,0 +1,16 @@
+-- Substring search for GET /search. The handler matches with ilike '%term%',
+-- which a btree cannot serve; trigram indexes let Postgres use an index for
+-- the listing titles, crop names and grower display names it searches.
+
+create extension if not exists pg_trgm;
+
+create index if not exists idx_surplus_listings_title_trgm
+  on surplus_listings using gin (title gin_trgm_ops)
+  where deleted_at is null and status = 'active';
+
+create index if not exists idx_crops_common_name_trgm
+  on crops using gin (common_name gin_trgm_ops);
+
+create index if not exists idx_users_display_name_trgm
+  on users using gin (display_name gin_trgm_ops)
+  where deleted_at is null and display_name is not null;
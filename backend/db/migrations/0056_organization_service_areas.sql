@@ -0,0 +1,36 @@
+-- Service areas for partner organizations. An organization that serves more
+-- than one neighborhood defines its reach as polygons or sets of geohash
+-- prefixes instead of a single point. Both kinds are also stored as the
+-- geohash cells that cover them, so "is this listing in the area" is a
+-- prefix match on organization_service_area_cells.
+
+create table if not exists organization_service_areas (
+  id uuid primary key default gen_random_uuid(),
+  organization_id uuid not null references organizations(id) on delete cascade,
+  name text not null,
+  position integer not null,
+  kind text not null,
+  polygon jsonb,
+  geo_keys text[],
+  anchor_lat double precision not null,
+  anchor_lng double precision not null,
+  anchor_geo_key text not null,
+  created_at timestamptz not null default now(),
+
+  constraint organization_service_areas_name_not_blank check (btrim(name) <> ''),
+  constraint organization_service_areas_kind check (kind in ('polygon', 'geo_set')),
+  constraint organization_service_areas_shape check (
+    (kind = 'polygon' and polygon is not null and geo_keys is null)
+    or (kind = 'geo_set' and geo_keys is not null and polygon is null)
+  ),
+  constraint organization_service_areas_position_unique unique (organization_id, position)
+);
+
+create table if not exists organization_service_area_cells (
+  service_area_id uuid not null references organization_service_areas(id) on delete cascade,
+  geo_prefix text not null,
+  primary key (service_area_id, geo_prefix)
+);
+
+create index if not exists idx_organization_service_area_cells_prefix
+  on organization_service_area_cells (geo_prefix);
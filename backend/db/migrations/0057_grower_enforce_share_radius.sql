@@ -0,0 +1,6 @@
+-- Lets a grower limit claims to gatherers within their share radius. Off by
+-- default: share_radius_km has only ever shaped discovery, and existing
+-- listings stay claimable from anywhere until the grower opts in.
+
+alter table grower_profiles
+  add column if not exists enforce_share_radius boolean not null default false;
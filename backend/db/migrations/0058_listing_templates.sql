@@ -0,0 +1,18 @@
+-- Named listing defaults a grower saves once ("Friday egg share") and turns
+-- into a listing by sending only dates. `defaults` holds the listing payload
+-- minus its availability window, as accepted by POST /me/listing-templates.
+
+create table if not exists listing_templates (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  name text not null,
+  defaults jsonb not null,
+  last_used_at timestamptz,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint listing_templates_name_not_blank check (btrim(name) <> '')
+);
+
+create unique index if not exists idx_listing_templates_user_name
+  on listing_templates (user_id, lower(name));
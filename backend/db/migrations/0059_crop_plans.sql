@@ -0,0 +1,50 @@
+-- What a grower intends to plant in a coming season, with the quantity they
+-- expect to harvest. Plans feed the signal aggregation as anticipated supply
+-- and let the feed warn when many neighbors plan the same crop. geo_key is
+-- copied at write time, as for harvests.
+--
+-- Seasons follow the tips framework: spring is March-May, summer June-August,
+-- fall September-November, and winter December-February. Winter takes the
+-- year of the December it starts in.
+
+create table if not exists crop_plans (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  crop_id uuid not null references crops(id),
+  variety_id uuid references crop_varieties(id) on delete set null,
+  variety_scope_id uuid generated always as (
+    coalesce(variety_id, '00000000-0000-0000-0000-000000000000'::uuid)
+  ) stored,
+  season_year smallint not null,
+  season text not null,
+  season_starts_on date generated always as (
+    make_date(
+      season_year,
+      case season
+        when 'spring' then 3
+        when 'summer' then 6
+        when 'fall' then 9
+        else 12
+      end,
+      1
+    )
+  ) stored,
+  expected_quantity numeric(12,3) not null,
+  unit text not null,
+  notes text,
+  geo_key text,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint crop_plans_season_allowed check (season in ('spring', 'summer', 'fall', 'winter')),
+  constraint crop_plans_season_year_range check (season_year between 2000 and 2100),
+  constraint crop_plans_expected_quantity_positive check (expected_quantity > 0),
+  constraint crop_plans_unit_not_blank check (btrim(unit) <> '')
+);
+
+create unique index if not exists idx_crop_plans_user_season_crop
+  on crop_plans (user_id, season_year, season, crop_id, variety_scope_id);
+
+create index if not exists idx_crop_plans_geo_key_season
+  on crop_plans (geo_key text_pattern_ops, season_starts_on)
+  where geo_key is not null;
@@ -0,0 +1,31 @@
+-- How a grower hears about claims on their listings. 'immediate' keeps the
+-- per-claim notification; 'daily_digest' replaces it with one daily summary
+-- of pending claims, listings about to close, and unread messages; 'off'
+-- sends neither, for growers who check the app themselves.
+
+alter table grower_profiles
+  add column if not exists claim_alerts text not null default 'immediate';
+
+alter table grower_profiles
+  drop constraint if exists grower_profiles_claim_alerts_allowed;
+
+alter table grower_profiles
+  add constraint grower_profiles_claim_alerts_allowed
+  check (claim_alerts in ('immediate', 'daily_digest', 'off'));
+
+create index if not exists idx_grower_profiles_claim_alerts_digest
+  on grower_profiles (user_id)
+  where claim_alerts = 'daily_digest';
+
+-- One row per grower per day a digest was sent, so a retried or overlapping
+-- run cannot send the same day's digest twice.
+create table if not exists claim_digests (
+  user_id uuid not null references users(id) on delete cascade,
+  digest_date date not null,
+  pending_claim_count integer not null,
+  expiring_listing_count integer not null,
+  unread_message_count integer not null,
+  sent_at timestamptz not null default now(),
+
+  primary key (user_id, digest_date)
+);
@@ -0,0 +1,44 @@
+-- Endpoints a partner organization registers to hear about its own claims.
+-- The secret is kept in the clear because the worker signs each delivery
+-- with it; it is returned to the organization only when created.
+create table if not exists organization_webhooks (
+  id uuid primary key default gen_random_uuid(),
+  organization_id uuid not null references organizations(id) on delete cascade,
+  url text not null,
+  secret text not null,
+  event_types text[] not null,
+  created_at timestamptz not null default now(),
+
+  constraint organization_webhooks_url_https check (url like 'https://%'),
+  constraint organization_webhooks_event_types_allowed check (
+    cardinality(event_types) > 0
+    and event_types <@ array['claim.confirmed', 'claim.completed']::text[]
+  )
+);
+
+create index if not exists idx_organization_webhooks_organization
+  on organization_webhooks (organization_id, created_at);
+
+-- One row per webhook, claim and event. The unique key keeps a redelivered
+-- claim event from posting twice once a delivery has succeeded.
+create table if not exists organization_webhook_deliveries (
+  id uuid primary key default gen_random_uuid(),
+  webhook_id uuid not null references organization_webhooks(id) on delete cascade,
+  claim_id uuid not null references claims(id) on delete cascade,
+  event_type text not null,
+  payload jsonb not null,
+  status text not null default 'pending',
+  attempt_count integer not null default 0,
+  response_status integer,
+  last_error text,
+  created_at timestamptz not null default now(),
+  delivered_at timestamptz,
+
+  constraint organization_webhook_deliveries_status_allowed
+    check (status in ('pending', 'delivered', 'failed')),
+  constraint organization_webhook_deliveries_unique
+    unique (webhook_id, claim_id, event_type)
+);
+
+create index if not exists idx_organization_webhook_deliveries_claim
+  on organization_webhook_deliveries (claim_id);
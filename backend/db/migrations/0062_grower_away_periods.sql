@@ -0,0 +1,41 @@
+-- Vacation mode. While a grower is away their active and pending listings
+-- are set to 'paused' so they drop out of discovery and cannot be claimed;
+-- when the period ends each listing goes back to the status it had.
+alter type listing_status add value if not exists 'paused';
+
+create table if not exists grower_away_periods (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  starts_at timestamptz not null,
+  ends_at timestamptz not null,
+  note text,
+  -- Set when the grower's listings were paused, and when they were
+  -- reactivated (at ends_at, or earlier if the grower came back early).
+  started_at timestamptz,
+  ended_at timestamptz,
+  created_at timestamptz not null default now(),
+
+  constraint grower_away_periods_range check (ends_at > starts_at),
+  constraint grower_away_periods_note_length check (note is null or char_length(note) <= 500)
+);
+
+-- One period at a time: a grower schedules a new one once the last has ended.
+create unique index if not exists idx_grower_away_periods_open
+  on grower_away_periods (user_id)
+  where ended_at is null;
+
+create index if not exists idx_grower_away_periods_due_start
+  on grower_away_periods (starts_at)
+  where started_at is null and ended_at is null;
+
+create index if not exists idx_grower_away_periods_due_end
+  on grower_away_periods (ends_at)
+  where ended_at is null;
+
+alter table surplus_listings
+  add column if not exists away_period_id uuid references grower_away_periods(id) on delete set null,
+  add column if not exists status_before_away listing_status;
+
+create index if not exists idx_surplus_listings_away_period
+  on surplus_listings (away_period_id)
+  where away_period_id is not null;
@@ -0,0 +1,34 @@
+-- Public questions on listings. Any participant who can see a listing can
+-- ask; the listing owner answers, and both are shown to everyone viewing the
+-- listing so the same question is not asked again in private messages.
+-- Questions and answers go through the moderation worker like listing text;
+-- a hold hides the question, or just the answer, until a reviewer clears it.
+
+create table if not exists listing_questions (
+  id uuid primary key default gen_random_uuid(),
+  listing_id uuid not null references surplus_listings(id) on delete cascade,
+  asker_id uuid not null references users(id) on delete cascade,
+  body text not null,
+  answer text,
+  answered_at timestamptz,
+  moderation_held_at timestamptz,
+  answer_held_at timestamptz,
+  created_at timestamptz not null default now(),
+
+  constraint listing_questions_body_length check (char_length(body) between 1 and 500),
+  constraint listing_questions_answer_length check (
+    answer is null or char_length(answer) between 1 and 1000
+  ),
+  constraint listing_questions_answered check ((answer is null) = (answered_at is null))
+);
+
+create index if not exists idx_listing_questions_listing
+  on listing_questions (listing_id, created_at);
+
+create index if not exists idx_listing_questions_unanswered
+  on listing_questions (listing_id, asker_id)
+  where answer is null;
+
+alter table moderation_queue drop constraint if exists moderation_queue_subject_type_check;
+alter table moderation_queue add constraint moderation_queue_subject_type_check
+  check (subject_type in ('listing', 'message', 'listing_question', 'listing_answer'));
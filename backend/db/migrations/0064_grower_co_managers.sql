@@ -0,0 +1,22 @@
+-- Household accounts. A grower can invite another grower to co-manage their
+-- garden: once accepted, the co-manager can edit the owner's listings and act
+-- on claims against them as the owner would. Listings stay owned by the
+-- inviting grower; changes a co-manager makes are audited under their own id.
+
+create table if not exists grower_co_managers (
+  id uuid primary key default gen_random_uuid(),
+  owner_id uuid not null references users(id) on delete cascade,
+  co_manager_id uuid not null references users(id) on delete cascade,
+  status text not null default 'pending',
+  created_at timestamptz not null default now(),
+  accepted_at timestamptz,
+
+  constraint grower_co_managers_status_check check (status in ('pending', 'accepted')),
+  constraint grower_co_managers_not_self check (owner_id <> co_manager_id),
+  constraint grower_co_managers_accepted_at check ((status = 'accepted') = (accepted_at is not null)),
+  unique (owner_id, co_manager_id)
+);
+
+create index if not exists idx_grower_co_managers_co_manager
+  on grower_co_managers (co_manager_id, owner_id)
+  where status = 'accepted';
@@ -0,0 +1,56 @@
+-- Localized crop and variety names. Responses that carry a crop_id or
+-- variety_id also return the name in the caller's Accept-Language, falling
+-- back from the full tag ("es-mx") to its language ("es") and then to the
+-- catalog's own common_name / name. Locales are stored lowercased.
+
+create table if not exists crop_name_translations (
+  crop_id uuid not null references crops(id) on delete cascade,
+  locale text not null,
+  name text not null,
+  created_at timestamptz not null default now(),
+
+  constraint crop_name_translations_locale_check check (locale ~ '^[a-z]{2,3}(-[a-z0-9]{2,8})*$'),
+  constraint crop_name_translations_name_check check (char_length(btrim(name)) > 0),
+  primary key (crop_id, locale)
+);
+
+create table if not exists crop_variety_name_translations (
+  variety_id uuid not null references crop_varieties(id) on delete cascade,
+  locale text not null,
+  name text not null,
+  created_at timestamptz not null default now(),
+
+  constraint crop_variety_name_translations_locale_check check (locale ~ '^[a-z]{2,3}(-[a-z0-9]{2,8})*$'),
+  constraint crop_variety_name_translations_name_check check (char_length(btrim(name)) > 0),
+  primary key (variety_id, locale)
+);
+
+-- Read-through helpers so any query can select a display name next to the
+-- id it already has. A null locale reads the catalog name.
+create or replace function crop_display_name(p_crop_id uuid, p_locale text)
+returns text
+language sql
+stable
+as $$
+  select coalesce(
+    (select t.name from crop_name_translations t
+      where t.crop_id = p_crop_id and t.locale = lower(p_locale)),
+    (select t.name from crop_name_translations t
+      where t.crop_id = p_crop_id and t.locale = split_part(lower(p_locale), '-', 1)),
+    (select c.common_name from crops c where c.id = p_crop_id)
+  )
+$$;
+
+create or replace function variety_display_name(p_variety_id uuid, p_locale text)
+returns text
+language sql
+stable
+as $$
+  select coalesce(
+    (select t.name from crop_variety_name_translations t
+      where t.variety_id = p_variety_id and t.locale = lower(p_locale)),
+    (select t.name from crop_variety_name_translations t
+      where t.variety_id = p_variety_id and t.locale = split_part(lower(p_locale), '-', 1)),
+    (select v.name from crop_varieties v where v.id = p_variety_id)
+  )
+$$;
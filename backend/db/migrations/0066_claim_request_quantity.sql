@@ -0,0 +1,14 @@
+-- A claim linked to a request records how much of the request it covers, in
+-- the request's unit. It is the claimed quantity itself when the units match,
+-- converted when the units catalog relates them (kg and lb), or given by the
+-- claimer when they do not (dozen against lb). Null when either side has no
+-- unit or the claimer linked mismatched units without a conversion.
+
+alter table claims
+  add column if not exists request_quantity numeric(12,3);
+
+alter table claims
+  drop constraint if exists claims_request_quantity_nonnegative;
+alter table claims
+  add constraint claims_request_quantity_nonnegative
+  check (request_quantity >= 0);
@@ -0,0 +1,48 @@
+-- When a claim last changed, so clients polling GET /claims can ask only for
+-- what changed since their last sync. Kept by triggers rather than by each
+-- writer, since the API, the auto-cancel worker and delivery updates all
+-- write claims. A delivery's status is part of the claim response, so a
+-- delivery change bumps its claim as well.
+
+alter table claims
+  add column if not exists updated_at timestamptz;
+
+update claims
+   set updated_at = greatest(claimed_at, confirmed_at, completed_at, cancelled_at)
+ where updated_at is null;
+
+alter table claims
+  alter column updated_at set default now(),
+  alter column updated_at set not null;
+
+create index if not exists idx_claims_updated_at on claims (updated_at, id);
+
+create or replace function claims_touch_updated_at()
+returns trigger
+language plpgsql
+as $$
+begin
+  new.updated_at := now();
+  return new;
+end;
+$$;
+
+drop trigger if exists claims_updated_at on claims;
+create trigger claims_updated_at
+  before update on claims
+  for each row execute function claims_touch_updated_at();
+
+create or replace function claim_deliveries_touch_claim()
+returns trigger
+language plpgsql
+as $$
+begin
+  update claims set updated_at = now() where id = new.claim_id;
+  return new;
+end;
+$$;
+
+drop trigger if exists claim_deliveries_touch_claim on claim_deliveries;
+create trigger claim_deliveries_touch_claim
+  after insert or update of status on claim_deliveries
+  for each row execute function claim_deliveries_touch_claim();
@@ -0,0 +1,43 @@
+-- Broad nutritional groups for catalog crops, so impact reports can say how
+-- much of what was shared was leafy greens, proteins, fruits and so on, as
+-- food banks report to funders. Crops are grouped from their common name;
+-- anything the patterns miss stays null and reports as "other".
+
+alter table crops
+  add column if not exists nutrition_group text;
+
+alter table crops
+  drop constraint if exists crops_nutrition_group_check;
+alter table crops
+  add constraint crops_nutrition_group_check check (
+    nutrition_group in ('leafy_greens', 'vegetables', 'fruits', 'proteins', 'grains', 'herbs')
+  );
+
+-- Earlier groups win: "pea shoots" are greens, "lemon thyme" is an herb.
+update crops set nutrition_group = 'leafy_greens'
+ where nutrition_group is null
+   and common_name ~* '\m(lettuces?|spinach|kale|chard|collards?|arugula|rocket|cabbages?|bok choy|pak choi|mustard greens?|endive|escarole|radicchio|mizuna|watercress|sorrel|tatsoi|shoots|microgreens)\M';
+
+update crops set nutrition_group = 'proteins'
+ where nutrition_group is null
+   and common_name ~* '\m(beans?|peas|pea|lentils?|chickpeas?|garbanzos?|soybeans?|edamame|peanuts?|cowpeas?|walnuts?|almonds?|hazelnuts?|pecans?|chestnuts?|eggs?)\M';
+
+update crops set nutrition_group = 'grains'
+ where nutrition_group is null
+   and common_name ~* '\m(corn|maize|wheat|oats?|barley|rye|rice|quinoa|amaranth|millet|sorghum|buckwheat)\M';
+
+update crops set nutrition_group = 'herbs'
+ where nutrition_group is null
+   and common_name ~* '\m(basil|parsley|cilantro|coriander|dill|mint|oregano|thyme|rosemary|sage|chives?|tarragon|lemongrass|lemon balm|marjoram)\M';
+
+update crops set nutrition_group = 'fruits'
+ where nutrition_group is null
+   and common_name ~* '\m(apples?|pears?|peach(es)?|plums?|cherry|cherries|\w*berry|\w*berries|grapes?|\w*melons?|figs?|oranges?|lemons?|limes?|apricots?|nectarines?|quinces?|persimmons?|pomegranates?|kiwis?|currants?)\M';
+
+update crops set nutrition_group = 'vegetables'
+ where nutrition_group is null
+   and common_name ~* '\m(tomato(es)?|peppers?|cucumbers?|squash(es)?|zucchinis?|pumpkins?|eggplants?|carrots?|beets?|radish(es)?|turnips?|potato(es)?|onions?|garlic|leeks?|broccoli|cauliflower|celery|asparagus|okra|artichokes?|parsnips?|rutabagas?|kohlrabi|brussels sprouts?)\M';
+
+-- One entry per group per area and week: {"group", "quantity", "claimCount"}.
+alter table impact_reports
+  add column if not exists nutrition_groups jsonb not null default '[]'::jsonb;
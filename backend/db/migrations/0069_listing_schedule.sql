@@ -0,0 +1,24 @@
+-- Listing scheduling. A listing created with a future publish_at starts out
+-- 'scheduled', out of discovery and unclaimable, until the listing schedule
+-- worker makes it active. expire_at, separate from the availability window,
+-- is when the same worker expires a listing that is still active or pending.
+alter type listing_status add value if not exists 'scheduled';
+
+alter table surplus_listings
+  add column if not exists publish_at timestamptz,
+  add column if not exists expire_at timestamptz;
+
+alter table surplus_listings
+  drop constraint if exists surplus_listings_schedule_order;
+alter table surplus_listings
+  add constraint surplus_listings_schedule_order check (
+    publish_at is null or expire_at is null or expire_at > publish_at
+  );
+
+create index if not exists idx_surplus_listings_publish_due
+  on surplus_listings (publish_at)
+  where publish_at is not null and deleted_at is null;
+
+create index if not exists idx_surplus_listings_expire_due
+  on surplus_listings (expire_at)
+  where expire_at is not null and deleted_at is null;
@@ -0,0 +1,19 @@
+-- Named request defaults a gatherer or organization saves once ("weekly soup
+-- kitchen order") and turns into a request by sending only a neededBy date.
+-- `defaults` holds the request payload minus neededBy and status, as accepted
+-- by POST /me/request-templates.
+
+create table if not exists request_templates (
+  id uuid primary key default gen_random_uuid(),
+  user_id uuid not null references users(id) on delete cascade,
+  name text not null,
+  defaults jsonb not null,
+  last_used_at timestamptz,
+  created_at timestamptz not null default now(),
+  updated_at timestamptz not null default now(),
+
+  constraint request_templates_name_not_blank check (btrim(name) <> '')
+);
+
+create unique index if not exists idx_request_templates_user_name
+  on request_templates (user_id, lower(name));
@@ -0,0 +1,6 @@
+-- Supports GET /me/dashboard, which counts and previews the pending and
+-- confirmed claims on a grower's listings on every home screen load.
+
+create index if not exists idx_claims_listing_open
+  on claims (listing_id, status, claimed_at)
+  where status in ('pending', 'confirmed');
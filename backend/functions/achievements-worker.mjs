@@ -0,0 +1,157 @@
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const SHARED_POUNDS_TARGET = 100;
+const PICKUPS_TARGET = 10;
+// A season only counts toward no_show_free_season with real activity behind it.
+const MIN_SEASON_PICKUPS = 5;
+
+const POUNDS_PER_UNIT = {
+  lb: 1, lbs: 1, pound: 1, pounds: 1,
+  oz: 1 / 16, ounce: 1 / 16, ounces: 1 / 16,
+  kg: 2.20462, kilogram: 2.20462, kilograms: 2.20462,
+  g: 0.00220462, gram: 0.00220462, grams: 0.00220462,
+};
+
+const TRIGGER_STATUSES = new Set(["completed", "no_show"]);
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+// Listings in counts, bunches, or volumes have no reliable weight, so only
+// weight units contribute to the pounds-shared milestone.
+function toPounds(quantity, unit) {
+  const factor = POUNDS_PER_UNIT[String(unit ?? "").trim().toLowerCase()];
+  const amount = Number(quantity);
+  if (factor === undefined || !Number.isFinite(amount)) return 0;
+  return amount * factor;
+}
+
+function sumPounds(unitRows) {
+  const total = unitRows.reduce((acc, row) => acc + toPounds(row.quantity, row.unit), 0);
+  return Math.round(total * 1000) / 1000;
+}
+
+// Seasons are calendar years of claimed_at. Only finished seasons qualify, so
+// a late no-show cannot contradict an award already on a public profile.
+function evaluateAchievements({ completedShares, poundsShared, completedPickups, seasons }, currentYear) {
+  const earned = [];
+  if (completedShares >= 1) {
+    earned.push({ key: "first_share", seasonYear: null, snapshot: { completedShares } });
+  }
+  if (poundsShared >= SHARED_POUNDS_TARGET) {
+    earned.push({ key: "shared_100_lb", seasonYear: null, snapshot: { poundsShared } });
+  }
+  if (completedPickups >= PICKUPS_TARGET) {
+    earned.push({ key: "ten_pickups", seasonYear: null, snapshot: { completedPickups } });
+  }
+  for (const season of seasons) {
+    if (season.seasonYear < currentYear && season.noShows === 0 && season.completed >= MIN_SEASON_PICKUPS) {
+      earned.push({
+        key: "no_show_free_season",
+        seasonYear: season.seasonYear,
+        snapshot: { completedPickups: season.completed, noShows: 0 },
+      });
+    }
+  }
+  return earned;
+}
+
+function extractUserIds(detail) {
+  return [...new Set([detail.claimerId, detail.listingOwnerId].filter(Boolean))];
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+async function loadStats(client, userId) {
+  const { rows: shareRows } = await client.query(
+    `select c.quantity_claimed::text as quantity, sl.unit
+     from claims c
+     join surplus_listings sl on sl.id = c.listing_id
+     where sl.user_id = $1 and c.claimer_id <> $1 and c.status = 'completed'`,
+    [userId]
+  );
+
+  const { rows: seasonRows } = await client.query(
+    `select extract(year from c.claimed_at at time zone 'utc')::int as season_year,
+            count(*) filter (where c.status = 'completed')::int as completed,
+            count(*) filter (where c.status = 'no_show')::int as no_shows
+     from claims c
+     join surplus_listings sl on sl.id = c.listing_id
+     where c.claimer_id = $1 and sl.user_id <> $1
+     group by 1
+     order by 1 asc`,
+    [userId]
+  );
+
+  return {
+    completedShares: shareRows.length,
+    poundsShared: sumPounds(shareRows),
+    completedPickups: seasonRows.reduce((acc, row) => acc + row.completed, 0),
+    seasons: seasonRows.map((row) => ({
+      seasonYear: row.season_year,
+      completed: row.completed,
+      noShows: row.no_shows,
+    })),
+  };
+}
+
+async function recordAchievement(client, userId, achievement) {
+  const { rowCount } = await client.query(
+    `insert into user_achievements (user_id, achievement_key, season_year, earned_at, progress_snapshot)
+     values ($1, $2, $3, now(), $4::jsonb)
+     on conflict (user_id, achievement_key, (coalesce(season_year, 0))) do nothing`,
+    [userId, achievement.key, achievement.seasonYear, JSON.stringify(achievement.snapshot)]
+  );
+  return rowCount > 0;
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+
+  if (!TRIGGER_STATUSES.has(detail.status)) {
+    return { statusCode: 200, body: "skipped: status" };
+  }
+
+  const userIds = extractUserIds(detail);
+  if (userIds.length === 0) {
+    console.warn("No user ids in claim event detail, skipping", JSON.stringify(event));
+    return { statusCode: 200, body: "skipped: no userId" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  try {
+    const currentYear = new Date().getUTCFullYear();
+    let awarded = 0;
+
+    for (const userId of userIds) {
+      const stats = await loadStats(client, userId);
+      for (const achievement of evaluateAchievements(stats, currentYear)) {
+        if (await recordAchievement(client, userId, achievement)) {
+          awarded += 1;
+          console.log(
+            JSON.stringify({
+              level: "INFO",
+              message: "Achievement earned",
+              userId,
+              achievementKey: achievement.key,
+              seasonYear: achievement.seasonYear,
+              correlationId,
+            })
+          );
+        }
+      }
+    }
+
+    emitMetrics("achievements-worker", { AchievementsAwarded: awarded }, { properties: { correlationId } });
+    return { statusCode: 200, body: "ok" };
+  } finally {
+    await client.end();
+  }
+}
@@ -0,0 +1,159 @@
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const DEFAULT_BATCH_SIZE = 200;
+const DEFAULT_MAX_BATCHES = 20;
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.AWAY_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.AWAY_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+  };
+}
+
+// ── sweeps ───────────────────────────────────────────────────────────────────
+
+// Periods that have run out put back the listings they paused, each to the
+// status it had. Matches RESUME_LISTINGS_SQL in the API's away handler.
+const END_DUE_SQL = `
+  WITH due AS (
+    SELECT id
+    FROM grower_away_periods
+    WHERE ended_at IS NULL
+      AND ends_at <= $1
+    ORDER BY ends_at
+    LIMIT $2
+    FOR UPDATE SKIP LOCKED
+  ),
+  ended AS (
+    UPDATE grower_away_periods a
+    SET ended_at = now()
+    FROM due d
+    WHERE a.id = d.id
+    RETURNING a.id
+  ),
+  resumed AS (
+    UPDATE surplus_listings l
+    SET status = coalesce(l.status_before_away, 'active'::listing_status),
+        status_before_away = NULL,
+        away_period_id = NULL
+    FROM due d
+    WHERE l.away_period_id = d.id
+      AND l.status = 'paused'::listing_status
+    RETURNING l.id
+  )
+  SELECT (SELECT count(*) FROM ended)::int AS affected,
+         (SELECT count(*) FROM resumed)::int AS listings`;
+
+// Periods scheduled ahead pause the grower's claimable listings once they
+// begin. Matches PAUSE_LISTINGS_SQL in the API's away handler; a period that
+// is already over by the time it is seen is left to END_DUE_SQL.
+const START_DUE_SQL = `
+  WITH due AS (
+    SELECT id, user_id
+    FROM grower_away_periods
+    WHERE started_at IS NULL
+      AND ended_at IS NULL
+      AND starts_at <= $1
+      AND ends_at > $1
+    ORDER BY starts_at
+    LIMIT $2
+    FOR UPDATE SKIP LOCKED
+  ),
+  started AS (
+    UPDATE grower_away_periods a
+    SET started_at = now()
+    FROM due d
+    WHERE a.id = d.id
+    RETURNING a.id
+  ),
+  paused AS (
+    UPDATE surplus_listings l
+    SET status_before_away = l.status,
+        status = 'paused'::listing_status,
+        away_period_id = d.id
+    FROM due d
+    WHERE l.user_id = d.user_id
+      AND l.deleted_at IS NULL
+      AND l.status IN ('active'::listing_status, 'pending'::listing_status)
+    RETURNING l.id
+  )
+  SELECT (SELECT count(*) FROM started)::int AS affected,
+         (SELECT count(*) FROM paused)::int AS listings`;
+
+// Ending runs first so a grower whose period ran out is never paused again.
+const SWEEPS = [
+  { name: "periods_ended", sql: END_DUE_SQL, metric: "PeriodsEnded", listingMetric: "ListingsResumed" },
+  { name: "periods_started", sql: START_DUE_SQL, metric: "PeriodsStarted", listingMetric: "ListingsPaused" },
+];
+
+async function runSweep(client, sweep, now, batchSize, maxBatches) {
+  let affected = 0;
+  let listings = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rows } = await client.query(sweep.sql, [now, batchSize]);
+    const count = rows[0]?.affected ?? 0;
+    batches += 1;
+    affected += count;
+    listings += rows[0]?.listings ?? 0;
+    if (count < batchSize) {
+      return { affected, listings, batches, exhausted: true };
+    }
+  }
+
+  return { affected, listings, batches, exhausted: false };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `away-mode-${Date.now()}`;
+  const config = resolveConfig(process.env, event.detail ?? {});
+  const now = new Date();
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    for (const sweep of SWEEPS) {
+      const result = await runSweep(client, sweep, now, config.batchSize, config.maxBatches);
+
+      console.log(
+        JSON.stringify({
+          level: result.exhausted ? "INFO" : "WARN",
+          message: result.exhausted
+            ? "Finished away mode sweep"
+            : "Stopped away mode sweep at batch limit; remaining periods will be picked up next run",
+          correlationId,
+          sweep: sweep.name,
+          batches: result.batches,
+          listings: result.listings,
+          metricName: `away_mode.${sweep.name}`,
+          metricValue: result.affected,
+        })
+      );
+      emitMetrics(
+        "away-mode-worker",
+        { [sweep.metric]: result.affected, [sweep.listingMetric]: result.listings },
+        { properties: { correlationId } }
+      );
+    }
+  } finally {
+    await client.end();
+  }
+}
@@ -0,0 +1,168 @@
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const DEFAULT_BATCH_SIZE = 500;
+const DEFAULT_MAX_BATCHES = 20;
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.AUTO_CANCEL_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.AUTO_CANCEL_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+  };
+}
+
+// ── sweeps ───────────────────────────────────────────────────────────────────
+
+// Pending claims past their hold stay pending; only their reservation goes.
+// The API releases a listing's lapsed holds itself whenever someone claims
+// from it, so this catches listings nobody has claimed from since.
+const RELEASE_EXPIRED_HOLDS_SQL = `
+  WITH expired AS (
+    SELECT id, listing_id, quantity_claimed
+    FROM claims
+    WHERE status = 'pending'
+      AND hold_expires_at IS NOT NULL
+      AND hold_expires_at <= $1
+    ORDER BY hold_expires_at
+    LIMIT $2
+    FOR UPDATE SKIP LOCKED
+  ),
+  released AS (
+    UPDATE claims c
+    SET hold_expires_at = NULL
+    FROM expired e
+    WHERE c.id = e.id
+    RETURNING c.id
+  ),
+  per_listing AS (
+    SELECT listing_id, sum(quantity_claimed) AS quantity
+    FROM expired
+    GROUP BY listing_id
+  ),
+  listings AS (
+    UPDATE surplus_listings l
+    SET reserved_quantity = greatest(l.reserved_quantity - p.quantity, 0)
+    FROM per_listing p
+    WHERE l.id = p.listing_id
+    RETURNING l.id
+  )
+  SELECT (SELECT count(*) FROM released)::int AS affected`;
+
+// A pending claim whose pickup window has closed can no longer be honoured,
+// so it is cancelled along with any delivery offer and its hold, if any.
+const CANCEL_STALE_PENDING_SQL = `
+  WITH stale AS (
+    SELECT c.id, c.listing_id, c.quantity_claimed, c.hold_expires_at IS NOT NULL AS held
+    FROM claims c
+    JOIN surplus_listings l ON l.id = c.listing_id
+    WHERE c.status = 'pending'
+      AND l.available_end < $1
+    ORDER BY l.available_end
+    LIMIT $2
+    FOR UPDATE OF c SKIP LOCKED
+  ),
+  cancelled AS (
+    UPDATE claims c
+    SET status = 'cancelled',
+        cancelled_at = coalesce(c.cancelled_at, now()),
+        hold_expires_at = NULL
+    FROM stale s
+    WHERE c.id = s.id
+    RETURNING c.id
+  ),
+  deliveries AS (
+    UPDATE claim_deliveries d
+    SET status = 'cancelled',
+        cancelled_at = now()
+    FROM stale s
+    WHERE d.claim_id = s.id
+      AND d.status IN ('offered', 'accepted', 'picked_up')
+    RETURNING d.claim_id
+  ),
+  per_listing AS (
+    SELECT listing_id, sum(quantity_claimed) AS quantity
+    FROM stale
+    WHERE held
+    GROUP BY listing_id
+  ),
+  listings AS (
+    UPDATE surplus_listings l
+    SET reserved_quantity = greatest(l.reserved_quantity - p.quantity, 0)
+    FROM per_listing p
+    WHERE l.id = p.listing_id
+    RETURNING l.id
+  )
+  SELECT (SELECT count(*) FROM cancelled)::int AS affected`;
+
+const SWEEPS = [
+  { name: "holds_released", sql: RELEASE_EXPIRED_HOLDS_SQL, metric: "HoldsReleased" },
+  { name: "claims_cancelled", sql: CANCEL_STALE_PENDING_SQL, metric: "ClaimsCancelled" },
+];
+
+async function runSweep(client, sweep, now, batchSize, maxBatches) {
+  let affected = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rows } = await client.query(sweep.sql, [now, batchSize]);
+    const count = rows[0]?.affected ?? 0;
+    batches += 1;
+    affected += count;
+    if (count < batchSize) {
+      return { affected, batches, exhausted: true };
+    }
+  }
+
+  return { affected, batches, exhausted: false };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `claim-auto-cancel-${Date.now()}`;
+  const config = resolveConfig(process.env, event.detail ?? {});
+  const now = new Date();
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    for (const sweep of SWEEPS) {
+      const result = await runSweep(client, sweep, now, config.batchSize, config.maxBatches);
+
+      console.log(
+        JSON.stringify({
+          level: result.exhausted ? "INFO" : "WARN",
+          message: result.exhausted
+            ? "Finished claim auto-cancel sweep"
+            : "Stopped claim auto-cancel sweep at batch limit; remaining rows will be picked up next run",
+          correlationId,
+          sweep: sweep.name,
+          batches: result.batches,
+          metricName: `claim_auto_cancel.${sweep.name}`,
+          metricValue: result.affected,
+        })
+      );
+      emitMetrics(
+        "claim-auto-cancel",
+        { [sweep.metric]: result.affected },
+        { properties: { correlationId } }
+      );
+    }
+  } finally {
+    await client.end();
+  }
+}
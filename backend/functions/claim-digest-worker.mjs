@@ -0,0 +1,263 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
+
+const DEFAULT_BATCH_SIZE = 200;
+const DEFAULT_MAX_BATCHES = 50;
+// PutEvents accepts at most 10 entries per call.
+const PUT_EVENTS_BATCH_SIZE = 10;
+// Enough ids for the email to deep-link the first few; the counts carry the rest.
+const MAX_IDS_PER_DIGEST = 20;
+// Listings whose pickup window closes within this long count as expiring.
+const EXPIRING_WITHIN_HOURS = 24;
+
+const eventBridge = new EventBridgeClient({});
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.DIGEST_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.DIGEST_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+  };
+}
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+// The UTC day a run belongs to; one digest per grower per day.
+function digestDateFor(now) {
+  return now.toISOString().slice(0, 10);
+}
+
+// A grower with nothing to report gets no digest that day.
+function hasActivity(summary) {
+  return (
+    summary.pending_claim_count > 0 ||
+    summary.expiring_listing_count > 0 ||
+    summary.unread_message_count > 0
+  );
+}
+
+// One email event per grower carrying counts and routing ids only; the
+// sender looks up the grower's address and renders the summary itself.
+function buildDigestEntry(summary, digestDate, correlationId, occurredAt) {
+  return {
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "notification.claim_digest",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      channel: "email",
+      recipientId: summary.user_id,
+      digestDate,
+      pendingClaimCount: summary.pending_claim_count,
+      expiringListingCount: summary.expiring_listing_count,
+      unreadMessageCount: summary.unread_message_count,
+      pendingClaimIds: (summary.pending_claim_ids ?? []).slice(0, MAX_IDS_PER_DIGEST),
+      expiringListingIds: (summary.expiring_listing_ids ?? []).slice(0, MAX_IDS_PER_DIGEST),
+      correlationId,
+      occurredAt,
+    }),
+  };
+}
+
+function chunk(items, size) {
+  const batches = [];
+  for (let i = 0; i < items.length; i += size) {
+    batches.push(items.slice(i, i + size));
+  }
+  return batches;
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+// Growers on the daily digest who have not had today's yet, keyset-paged by
+// user id so each batch moves past the last.
+async function loadRecipients(client, digestDate, afterUserId, batchSize) {
+  const { rows } = await client.query(
+    `SELECT gp.user_id
+     FROM grower_profiles gp
+     JOIN users u ON u.id = gp.user_id
+     WHERE gp.claim_alerts = 'daily_digest'
+       AND u.deleted_at IS NULL
+       AND ($2::uuid IS NULL OR gp.user_id > $2)
+       AND NOT EXISTS (
+         SELECT 1 FROM claim_digests d
+         WHERE d.user_id = gp.user_id AND d.digest_date = $1::date
+       )
+     ORDER BY gp.user_id
+     LIMIT $3`,
+    [digestDate, afterUserId, batchSize]
+  );
+  return rows.map((row) => row.user_id);
+}
+
+// Unread counting matches GET /conversations: messages from the other member
+// newer than the grower's read cursor.
+async function loadSummaries(client, userIds, now) {
+  const { rows } = await client.query(
+    `WITH recipients AS (
+       SELECT unnest($1::uuid[]) AS user_id
+     ),
+     pending AS (
+       SELECT l.user_id,
+              count(*)::int AS pending_claim_count,
+              array_agg(c.id ORDER BY c.claimed_at, c.id) AS pending_claim_ids
+       FROM claims c
+       JOIN surplus_listings l ON l.id = c.listing_id
+       WHERE l.user_id = ANY($1::uuid[])
+         AND l.deleted_at IS NULL
+         AND c.status = 'pending'
+       GROUP BY l.user_id
+     ),
+     expiring AS (
+       SELECT user_id,
+              count(*)::int AS expiring_listing_count,
+              array_agg(id ORDER BY available_end, id) AS expiring_listing_ids
+       FROM surplus_listings
+       WHERE user_id = ANY($1::uuid[])
+         AND deleted_at IS NULL
+         AND status = 'active'
+         AND available_end > $2
+         AND available_end <= $2 + make_interval(hours => $3)
+       GROUP BY user_id
+     ),
+     unread AS (
+       SELECT r.user_id, count(m.id)::int AS unread_message_count
+       FROM recipients r
+       JOIN conversations cv ON r.user_id IN (cv.owner_id, cv.participant_id)
+       LEFT JOIN conversation_reads cr
+         ON cr.conversation_id = cv.id AND cr.user_id = r.user_id
+       JOIN messages m
+         ON m.conversation_id = cv.id
+        AND m.sender_id <> r.user_id
+        AND m.created_at > coalesce(cr.last_read_at, '-infinity'::timestamptz)
+       GROUP BY r.user_id
+     )
+     SELECT r.user_id,
+            coalesce(p.pending_claim_count, 0) AS pending_claim_count,
+            p.pending_claim_ids,
+            coalesce(e.expiring_listing_count, 0) AS expiring_listing_count,
+            e.expiring_listing_ids,
+            coalesce(un.unread_message_count, 0) AS unread_message_count
+     FROM recipients r
+     LEFT JOIN pending p ON p.user_id = r.user_id
+     LEFT JOIN expiring e ON e.user_id = r.user_id
+     LEFT JOIN unread un ON un.user_id = r.user_id`,
+    [userIds, now, EXPIRING_WITHIN_HOURS]
+  );
+  return rows;
+}
+
+// Recorded before publishing so an overlapping or retried run cannot send a
+// second digest for the day; a failed publish is logged rather than resent.
+async function recordDigest(client, summary, digestDate) {
+  const { rowCount } = await client.query(
+    `INSERT INTO claim_digests
+       (user_id, digest_date, pending_claim_count, expiring_listing_count, unread_message_count)
+     VALUES ($1, $2::date, $3, $4, $5)
+     ON CONFLICT (user_id, digest_date) DO NOTHING`,
+    [
+      summary.user_id,
+      digestDate,
+      summary.pending_claim_count,
+      summary.expiring_listing_count,
+      summary.unread_message_count,
+    ]
+  );
+  return rowCount > 0;
+}
+
+async function publishDigests(entries) {
+  let failed = 0;
+  for (const batch of chunk(entries, PUT_EVENTS_BATCH_SIZE)) {
+    const result = await eventBridge.send(new PutEventsCommand({ Entries: batch }));
+    failed += result.FailedEntryCount ?? 0;
+  }
+  return failed;
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `claim-digest-${Date.now()}`;
+  const config = resolveConfig(process.env, event.detail ?? {});
+  const now = new Date();
+  const digestDate = digestDateFor(now);
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  let considered = 0;
+  let sent = 0;
+  let failed = 0;
+  let batches = 0;
+  let exhausted = false;
+  let afterUserId = null;
+
+  try {
+    while (batches < config.maxBatches) {
+      const userIds = await loadRecipients(client, digestDate, afterUserId, config.batchSize);
+      batches += 1;
+      if (userIds.length === 0) {
+        exhausted = true;
+        break;
+      }
+      considered += userIds.length;
+      afterUserId = userIds[userIds.length - 1];
+
+      const occurredAt = new Date().toISOString();
+      const entries = [];
+      for (const summary of await loadSummaries(client, userIds, now)) {
+        if (!hasActivity(summary)) continue;
+        if (await recordDigest(client, summary, digestDate)) {
+          entries.push(buildDigestEntry(summary, digestDate, correlationId, occurredAt));
+        }
+      }
+
+      const batchFailed = entries.length > 0 ? await publishDigests(entries) : 0;
+      sent += entries.length - batchFailed;
+      failed += batchFailed;
+
+      if (userIds.length < config.batchSize) {
+        exhausted = true;
+        break;
+      }
+    }
+  } finally {
+    await client.end();
+  }
+
+  console.log(
+    JSON.stringify({
+      level: exhausted && failed === 0 ? "INFO" : "WARN",
+      message: exhausted
+        ? "Finished claim digest run"
+        : "Stopped claim digest run at batch limit; remaining growers will be picked up next run",
+      correlationId,
+      digestDate,
+      batches,
+      considered,
+      sent,
+      failedCount: failed,
+    })
+  );
+  emitMetrics(
+    "claim-digest-worker",
+    { DigestsSent: sent, DigestsFailed: failed, GrowersConsidered: considered },
+    { properties: { correlationId, digestDate } }
+  );
+
+  return { statusCode: 200, body: "ok" };
+}
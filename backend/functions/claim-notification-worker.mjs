@@ -0,0 +1,129 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
+
+const eventBridge = new EventBridgeClient({});
+
+// How a grower hears about a new claim, by the listing's contact_pref. A
+// grower who asked for a call or a knock may never open the app, so those
+// listings reach them outside it; app_message growers get the usual email
+// and push.
+const CHANNELS_BY_CONTACT_PREF = {
+  phone: ["sms"],
+  knock: ["email"],
+  app_message: ["email", "push"],
+};
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+function shouldNotify(detail) {
+  return (
+    Boolean(detail.claimId && detail.listingId && detail.listingOwnerId) &&
+    detail.status === "pending"
+  );
+}
+
+// Growers on the daily digest, or with claim alerts off, hear about pending
+// claims from claim-digest-worker or not at all.
+function wantsImmediateAlert(claimAlerts) {
+  return (claimAlerts ?? "immediate") === "immediate";
+}
+
+// Unknown preferences fall back to app_message, the column's default.
+function channelsFor(contactPref) {
+  return CHANNELS_BY_CONTACT_PREF[contactPref] ?? CHANNELS_BY_CONTACT_PREF.app_message;
+}
+
+// One event per channel so each sender subscribes to its own. Routing ids
+// only; senders look up the grower's address or device themselves.
+function buildNotificationEntries(detail, contactPref, occurredAt) {
+  return channelsFor(contactPref).map((channel) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "notification.claim_pending",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      channel,
+      contactPref,
+      recipientId: detail.listingOwnerId,
+      claimId: detail.claimId,
+      listingId: detail.listingId,
+      claimerId: detail.claimerId ?? null,
+      correlationId: detail.correlationId ?? "unknown",
+      occurredAt,
+    }),
+  }));
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+// Null when the listing or its owner has since been deleted. Owners without
+// a grower profile keep the default immediate alerts.
+async function loadRecipient(client, listingId) {
+  const { rows } = await client.query(
+    `select l.contact_pref::text as contact_pref, gp.claim_alerts
+     from surplus_listings l
+     join users u on u.id = l.user_id
+     left join grower_profiles gp on gp.user_id = l.user_id
+     where l.id = $1 and l.deleted_at is null and u.deleted_at is null`,
+    [listingId]
+  );
+  if (rows.length === 0) return null;
+  return { contactPref: rows[0].contact_pref, claimAlerts: rows[0].claim_alerts ?? null };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+
+  if (!shouldNotify(detail)) {
+    return { statusCode: 200, body: "skipped: not a pending claim" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let recipient;
+  try {
+    recipient = await loadRecipient(client, detail.listingId);
+  } finally {
+    await client.end();
+  }
+
+  if (recipient === null) {
+    return { statusCode: 200, body: "skipped: listing no longer available" };
+  }
+  if (!wantsImmediateAlert(recipient.claimAlerts)) {
+    return { statusCode: 200, body: `skipped: claim alerts set to ${recipient.claimAlerts}` };
+  }
+
+  const { contactPref } = recipient;
+
+  const entries = buildNotificationEntries(detail, contactPref, new Date().toISOString());
+  const result = await eventBridge.send(new PutEventsCommand({ Entries: entries }));
+  const failed = result.FailedEntryCount ?? 0;
+
+  console.log(
+    JSON.stringify({
+      level: failed > 0 ? "WARN" : "INFO",
+      message: "Routed claim notification to the listing owner",
+      claimId: detail.claimId,
+      listingId: detail.listingId,
+      contactPref,
+      channels: channelsFor(contactPref),
+      failedCount: failed,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "claim-notification-worker",
+    { NotificationsRouted: entries.length - failed, NotificationsFailed: failed },
+    { properties: { correlationId, contactPref } }
+  );
+
+  return { statusCode: 200, body: "ok" };
+}
@@ -0,0 +1,247 @@
+import { createHmac } from "node:crypto";
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, WEBHOOK_TIMEOUT_MS = "5000" } = process.env;
+
+// Claim statuses an organization can subscribe to, and the webhook event
+// each one is delivered as.
+const EVENT_TYPES_BY_STATUS = {
+  confirmed: "claim.confirmed",
+  completed: "claim.completed",
+};
+
+// Response bodies are only kept for debugging a failed delivery.
+const MAX_ERROR_CHARS = 500;
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+function eventTypeFor(detail) {
+  if (!detail.claimId || !detail.claimerId) return null;
+  return EVENT_TYPES_BY_STATUS[detail.status] ?? null;
+}
+
+// The body an organization's inventory system receives: the claim with its
+// quantity and unit, and the crop it is for. Built once per delivery and
+// stored so a retry sends exactly the same bytes.
+function buildPayload(claim, organizationId, eventType, occurredAt) {
+  return {
+    schemaVersion: 1,
+    eventType,
+    occurredAt,
+    organizationId,
+    claim: {
+      id: claim.id,
+      listingId: claim.listing_id,
+      requestId: claim.request_id ?? null,
+      status: eventType.slice("claim.".length),
+      quantityClaimed: claim.quantity_claimed,
+      unit: claim.unit ?? null,
+      confirmedAt: claim.confirmed_at ? new Date(claim.confirmed_at).toISOString() : null,
+      completedAt: claim.completed_at ? new Date(claim.completed_at).toISOString() : null,
+    },
+    crop: {
+      id: claim.crop_id,
+      name: claim.crop_name,
+      varietyId: claim.variety_id ?? null,
+      varietyName: claim.variety_name ?? null,
+    },
+  };
+}
+
+// Receivers recompute the HMAC over `${timestamp}.${body}` with their secret
+// and reject stale timestamps to stop replays.
+function signatureHeader(secret, timestamp, body) {
+  const digest = createHmac("sha256", secret).update(`${timestamp}.${body}`).digest("hex");
+  return `t=${timestamp},v1=${digest}`;
+}
+
+function buildRequest(delivery, secret, now) {
+  const body = JSON.stringify(delivery.payload);
+  const timestamp = Math.floor(now.getTime() / 1000);
+  return {
+    body,
+    headers: {
+      "Content-Type": "application/json",
+      "User-Agent": "community-garden-webhooks/1",
+      "X-Garden-Event": delivery.event_type,
+      "X-Garden-Delivery": delivery.id,
+      "X-Garden-Signature": signatureHeader(secret, timestamp, body),
+    },
+  };
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+// Webhooks belong to organizations, and an organization claims through its
+// service user, so only the claimer's own organization is notified.
+async function loadWebhooks(client, claimerId, eventType) {
+  const { rows } = await client.query(
+    `SELECT w.id, w.url, w.secret, w.organization_id
+     FROM organization_webhooks w
+     JOIN organizations o ON o.id = w.organization_id
+     WHERE o.service_user_id = $1
+       AND o.deleted_at IS NULL
+       AND $2 = ANY(w.event_types)
+     ORDER BY w.created_at, w.id`,
+    [claimerId, eventType]
+  );
+  return rows;
+}
+
+async function loadClaim(client, claimId) {
+  const { rows } = await client.query(
+    `SELECT c.id, c.listing_id, c.request_id,
+            c.quantity_claimed::text AS quantity_claimed,
+            c.confirmed_at, c.completed_at,
+            l.unit, l.crop_id, cr.common_name AS crop_name,
+            l.variety_id, v.name AS variety_name
+     FROM claims c
+     JOIN surplus_listings l ON l.id = c.listing_id
+     JOIN crops cr ON cr.id = l.crop_id
+     LEFT JOIN crop_varieties v ON v.id = l.variety_id
+     WHERE c.id = $1`,
+    [claimId]
+  );
+  return rows[0] ?? null;
+}
+
+// Returns the stored delivery for this webhook, claim and event, creating it
+// on first sight. A redelivered event finds the existing row and its payload.
+async function upsertDelivery(client, webhookId, claimId, eventType, payload) {
+  const { rows } = await client.query(
+    `INSERT INTO organization_webhook_deliveries (webhook_id, claim_id, event_type, payload)
+     VALUES ($1, $2, $3, $4)
+     ON CONFLICT (webhook_id, claim_id, event_type)
+     DO UPDATE SET payload = organization_webhook_deliveries.payload
+     RETURNING id, event_type, status, payload`,
+    [webhookId, claimId, eventType, payload]
+  );
+  return rows[0];
+}
+
+async function recordAttempt(client, deliveryId, outcome) {
+  await client.query(
+    `UPDATE organization_webhook_deliveries
+     SET status = $2,
+         attempt_count = attempt_count + 1,
+         response_status = $3,
+         last_error = $4,
+         delivered_at = CASE WHEN $2 = 'delivered' THEN now() ELSE delivered_at END
+     WHERE id = $1`,
+    [deliveryId, outcome.status, outcome.responseStatus, outcome.error]
+  );
+}
+
+// ── delivery ─────────────────────────────────────────────────────────────────
+
+async function post(url, request) {
+  try {
+    const response = await fetch(url, {
+      method: "POST",
+      headers: request.headers,
+      body: request.body,
+      redirect: "manual",
+      signal: AbortSignal.timeout(Number(WEBHOOK_TIMEOUT_MS)),
+    });
+    if (response.ok) {
+      return { status: "delivered", responseStatus: response.status, error: null };
+    }
+    const text = await response.text().catch(() => "");
+    return {
+      status: "failed",
+      responseStatus: response.status,
+      error: text.slice(0, MAX_ERROR_CHARS) || `HTTP ${response.status}`,
+    };
+  } catch (err) {
+    return { status: "failed", responseStatus: null, error: String(err.message ?? err) };
+  }
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+  const eventType = eventTypeFor(detail);
+
+  if (eventType === null) {
+    return { statusCode: 200, body: "skipped: not a confirmed or completed claim" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let delivered = 0;
+  let failed = 0;
+  let skipped = 0;
+
+  try {
+    const webhooks = await loadWebhooks(client, detail.claimerId, eventType);
+    if (webhooks.length === 0) {
+      return { statusCode: 200, body: "skipped: no subscribed webhooks" };
+    }
+    const claim = await loadClaim(client, detail.claimId);
+    if (claim === null) {
+      return { statusCode: 200, body: "skipped: claim no longer exists" };
+    }
+
+    const occurredAt = detail.occurredAt ?? new Date().toISOString();
+    for (const webhook of webhooks) {
+      const payload = buildPayload(claim, webhook.organization_id, eventType, occurredAt);
+      const delivery = await upsertDelivery(client, webhook.id, claim.id, eventType, payload);
+      if (delivery.status === "delivered") {
+        skipped += 1;
+        continue;
+      }
+
+      const outcome = await post(webhook.url, buildRequest(delivery, webhook.secret, new Date()));
+      await recordAttempt(client, delivery.id, outcome);
+      if (outcome.status === "delivered") {
+        delivered += 1;
+      } else {
+        failed += 1;
+        console.log(
+          JSON.stringify({
+            level: "WARN",
+            message: "Webhook delivery failed",
+            deliveryId: delivery.id,
+            webhookId: webhook.id,
+            claimId: claim.id,
+            eventType,
+            responseStatus: outcome.responseStatus,
+            correlationId,
+          })
+        );
+      }
+    }
+  } finally {
+    await client.end();
+  }
+
+  console.log(
+    JSON.stringify({
+      level: failed > 0 ? "WARN" : "INFO",
+      message: "Delivered claim webhooks",
+      claimId: detail.claimId,
+      eventType,
+      delivered,
+      failedCount: failed,
+      skipped,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "claim-webhook-worker",
+    { WebhooksDelivered: delivered, WebhooksFailed: failed },
+    { properties: { correlationId, eventType } }
+  );
+
+  // Throwing hands the event back to Lambda's async retries; deliveries that
+  // already succeeded are skipped on the next attempt.
+  if (failed > 0) {
+    throw new Error(`${failed} webhook deliveries failed for claim ${detail.claimId}`);
+  }
+
+  return { statusCode: 200, body: "ok" };
+}
@@ -0,0 +1,187 @@
+import pg from "pg";
+import { BedrockRuntimeClient, ConverseCommand } from "@aws-sdk/client-bedrock-runtime";
+import { emitMetrics } from "./lib/metrics.mjs";
+import {
+  IDENTIFY_PROMPT,
+  MAX_IMAGE_BYTES,
+  chooseIdentification,
+  cropSlugCandidates,
+  imageFormat,
+  parseIdentification,
+} from "./lib/crop-photo.mjs";
+
+const {
+  DATABASE_URL,
+  CROP_PHOTO_CHECK_ENABLED = "0",
+  CROP_PHOTO_MODEL_ID = "amazon.nova-lite-v1:0",
+  CROP_PHOTO_TIMEOUT_MS = "8000",
+} = process.env;
+
+// Each photo is one model call; later photos rarely change the answer.
+const MAX_PHOTOS_PER_LISTING = 3;
+
+const bedrock = new BedrockRuntimeClient({});
+
+// ── model ────────────────────────────────────────────────────────────────────
+
+async function fetchImage(url) {
+  const response = await fetch(url, { signal: AbortSignal.timeout(Number(CROP_PHOTO_TIMEOUT_MS)) });
+  if (!response.ok) throw new Error(`Image fetch returned ${response.status}`);
+  const format = imageFormat(response.headers.get("content-type"));
+  if (!format) throw new Error("Unsupported image type");
+  const bytes = new Uint8Array(await response.arrayBuffer());
+  if (bytes.byteLength > MAX_IMAGE_BYTES) throw new Error("Image too large");
+  return { format, bytes };
+}
+
+async function identifyCrop(url) {
+  const { format, bytes } = await fetchImage(url);
+  const result = await bedrock.send(
+    new ConverseCommand({
+      modelId: CROP_PHOTO_MODEL_ID,
+      messages: [
+        {
+          role: "user",
+          content: [{ image: { format, source: { bytes } } }, { text: IDENTIFY_PROMPT }],
+        },
+      ],
+      inferenceConfig: { maxTokens: 60, temperature: 0 },
+    }),
+    { abortSignal: AbortSignal.timeout(Number(CROP_PHOTO_TIMEOUT_MS)) }
+  );
+  const reply = result.output?.message?.content?.find((block) => block.text)?.text;
+  return parseIdentification(reply);
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+async function loadUncheckedPhotos(client, listingId) {
+  const { rows } = await client.query(
+    `select i.id, i.url
+     from listing_images i
+     join surplus_listings l on l.id = i.listing_id
+     where i.listing_id = $1
+       and i.crop_checked_at is null
+       and l.deleted_at is null
+     order by i.sort_order asc, i.created_at asc
+     limit $2`,
+    [listingId, MAX_PHOTOS_PER_LISTING]
+  );
+  return rows;
+}
+
+async function resolveCropId(client, crop) {
+  const { rows } = await client.query(
+    `select id from crops
+     where slug = any($1) or lower(common_name) = $2
+     order by slug = any($1) desc
+     limit 1`,
+    [cropSlugCandidates(crop), crop]
+  );
+  return rows[0]?.id ?? null;
+}
+
+async function recordCheck(client, listingId, photoIds, cropId, confidence) {
+  await client.query("begin");
+  try {
+    await client.query(
+      `update listing_images set crop_checked_at = now() where id = any($1)`,
+      [photoIds]
+    );
+    await client.query(
+      `update surplus_listings
+       set photo_crop_id = $2,
+           photo_crop_confidence = $3,
+           photo_crop_checked_at = now()
+       where id = $1`,
+      [listingId, cropId, confidence]
+    );
+    await client.query("commit");
+  } catch (error) {
+    await client.query("rollback");
+    throw error;
+  }
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+
+  if (CROP_PHOTO_CHECK_ENABLED !== "1") {
+    return { statusCode: 200, body: "skipped: photo check disabled" };
+  }
+  if (!detail.listingId) {
+    return { statusCode: 200, body: "skipped: no listing id" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let photos = [];
+  let best = null;
+  let cropId = null;
+  let failures = 0;
+  try {
+    photos = await loadUncheckedPhotos(client, detail.listingId);
+    if (photos.length > 0) {
+      const identifications = [];
+      for (const photo of photos) {
+        try {
+          identifications.push(await identifyCrop(photo.url));
+        } catch (error) {
+          failures += 1;
+          console.log(
+            JSON.stringify({
+              level: "WARN",
+              message: "Crop photo identification failed",
+              listingId: detail.listingId,
+              imageId: photo.id,
+              error: error.message,
+              correlationId,
+            })
+          );
+        }
+      }
+
+      // Leave photos unchecked when every call failed so the next listing
+      // update retries them.
+      if (failures < photos.length) {
+        best = chooseIdentification(identifications);
+        cropId = best ? await resolveCropId(client, best.crop) : null;
+        await recordCheck(
+          client,
+          detail.listingId,
+          photos.map((photo) => photo.id),
+          cropId,
+          cropId ? best.confidence : null
+        );
+      }
+    }
+  } finally {
+    await client.end();
+  }
+
+  const mismatch = Boolean(cropId && detail.cropId && cropId !== detail.cropId);
+  console.log(
+    JSON.stringify({
+      level: mismatch ? "WARN" : "INFO",
+      message: "Checked listing photos for crop",
+      listingId: detail.listingId,
+      photoCount: photos.length,
+      failedCount: failures,
+      identifiedCrop: best?.crop ?? null,
+      photoCropId: cropId,
+      mismatch,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "crop-photo-worker",
+    { PhotosChecked: photos.length - failures, PhotoCheckFailures: failures, CropMismatches: mismatch ? 1 : 0 },
+    { properties: { correlationId, listingId: detail.listingId } }
+  );
+
+  return { statusCode: 200, body: "ok" };
+}
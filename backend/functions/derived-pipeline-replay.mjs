@@ -0,0 +1,207 @@
+import pg from "pg";
+import {
+  GEO_PRECISIONS,
+  SUPPORTED_WINDOWS_DAYS,
+  computeBucketStart,
+  recomputeAndUpsert,
+} from "./lib/signals.mjs";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const PIPELINE_NAME = "derived_supply_signals";
+const REPLAY_MODES = ["full", "backfill"];
+const DEFAULT_CHUNK_SIZE = 200;
+const MAX_CHUNK_SIZE = 2000;
+
+// ── chunk input ──────────────────────────────────────────────────────────────
+
+// Each invocation recomputes one page of scopes and returns the cursor for
+// the next page, so the replay can run as a Step Functions loop/map or as
+// repeated Lambda invocations without holding every scope in memory.
+function resolveChunkInput(input = {}) {
+  const chunkSize = Number.parseInt(String(input.chunkSize ?? DEFAULT_CHUNK_SIZE), 10);
+  if (!Number.isInteger(chunkSize) || chunkSize < 1 || chunkSize > MAX_CHUNK_SIZE) {
+    throw new Error(`chunkSize must be between 1 and ${MAX_CHUNK_SIZE}`);
+  }
+
+  const windows = input.windows ?? SUPPORTED_WINDOWS_DAYS;
+  for (const windowDays of windows) {
+    if (!SUPPORTED_WINDOWS_DAYS.includes(windowDays)) {
+      throw new Error(`Unsupported window: ${windowDays}`);
+    }
+  }
+
+  const mode = input.mode ?? "full";
+  if (!REPLAY_MODES.includes(mode)) {
+    throw new Error(`mode must be one of ${REPLAY_MODES.join("|")}`);
+  }
+
+  return {
+    mode,
+    chunkSize,
+    windows,
+    cursor: decodeCursor(input.cursor ?? null),
+    runStartedAt: input.runStartedAt ?? new Date().toISOString(),
+  };
+}
+
+function encodeCursor(scope) {
+  if (!scope) return null;
+  return `${scope.geoBoundaryKey}|${scope.cropId ?? ""}`;
+}
+
+function decodeCursor(cursor) {
+  if (!cursor) return { geoBoundaryKey: "", cropKey: "" };
+  const [geoBoundaryKey, cropKey = ""] = String(cursor).split("|");
+  if (!/^[0-9b-hjkmnp-z]{1,12}$/.test(geoBoundaryKey)) {
+    throw new Error(`Invalid replay cursor: ${cursor}`);
+  }
+  return { geoBoundaryKey, cropKey };
+}
+
+// ── scope pagination ─────────────────────────────────────────────────────────
+
+async function loadScopePage(client, cursor, chunkSize) {
+  const { rows } = await client.query(
+    `WITH sources AS (
+       SELECT lower(geo_key) AS geo_key, crop_id FROM surplus_listings
+       WHERE deleted_at IS NULL AND geo_key IS NOT NULL AND listing_kind = 'produce'
+       UNION
+       SELECT lower(geo_key), crop_id FROM requests
+       WHERE deleted_at IS NULL AND geo_key IS NOT NULL
+     ),
+     scopes AS (
+       SELECT DISTINCT left(s.geo_key, p) AS geo_boundary_key, s.crop_id
+       FROM sources s CROSS JOIN unnest($4::int[]) AS p
+       WHERE char_length(s.geo_key) >= p
+       UNION
+       SELECT DISTINCT left(s.geo_key, p), NULL::uuid
+       FROM sources s CROSS JOIN unnest($4::int[]) AS p
+       WHERE char_length(s.geo_key) >= p
+     )
+     SELECT geo_boundary_key, crop_id
+     FROM scopes
+     WHERE (geo_boundary_key, coalesce(crop_id::text, '')) > ($1, $2)
+     ORDER BY geo_boundary_key, coalesce(crop_id::text, '')
+     LIMIT $3`,
+    [cursor.geoBoundaryKey, cursor.cropKey, chunkSize, GEO_PRECISIONS]
+  );
+  return rows.map((row) => ({ geoBoundaryKey: row.geo_boundary_key, cropId: row.crop_id }));
+}
+
+// ── checkpoints ──────────────────────────────────────────────────────────────
+
+async function loadCheckpoint(client, mode) {
+  const { rows } = await client.query(
+    `SELECT cursor, run_started_at, processed_scopes, status
+     FROM pipeline_checkpoints
+     WHERE pipeline_name = $1 AND mode = $2`,
+    [PIPELINE_NAME, mode]
+  );
+  return rows[0] ?? null;
+}
+
+async function saveCheckpoint(client, mode, checkpoint) {
+  await client.query(
+    `INSERT INTO pipeline_checkpoints (
+       pipeline_name, mode, cursor, run_started_at, processed_scopes, status
+     )
+     VALUES ($1, $2, $3, $4, $5, $6)
+     ON CONFLICT (pipeline_name, mode) DO UPDATE
+       SET cursor = excluded.cursor,
+           run_started_at = excluded.run_started_at,
+           processed_scopes = excluded.processed_scopes,
+           status = excluded.status,
+           updated_at = now()`,
+    [
+      PIPELINE_NAME,
+      mode,
+      checkpoint.cursor,
+      checkpoint.runStartedAt,
+      checkpoint.processedScopes,
+      checkpoint.done ? "completed" : "running",
+    ]
+  );
+}
+
+// An explicit cursor in the payload wins (Step Functions passes it along);
+// otherwise a running checkpoint is resumed, and a completed one starts over.
+function resumeFromCheckpoint(event, checkpoint) {
+  if (event.cursor !== undefined || !checkpoint || checkpoint.status !== "running") {
+    return { ...event, processedScopes: event.processedScopes ?? 0 };
+  }
+  return {
+    ...event,
+    cursor: checkpoint.cursor,
+    runStartedAt: new Date(checkpoint.run_started_at).toISOString(),
+    processedScopes: checkpoint.processed_scopes,
+  };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const checkpoint = await loadCheckpoint(client, event.mode ?? "full");
+    const resumed = resumeFromCheckpoint(event, checkpoint);
+    const input = resolveChunkInput(resumed);
+    const bucketStart = computeBucketStart(input.runStartedAt);
+
+    const scopes = await loadScopePage(client, input.cursor, input.chunkSize);
+
+    for (const scope of scopes) {
+      for (const windowDays of input.windows) {
+        await recomputeAndUpsert(client, scope, windowDays, bucketStart);
+      }
+    }
+
+    const done = scopes.length < input.chunkSize;
+    const nextCursor = done ? null : encodeCursor(scopes[scopes.length - 1]);
+    const processedScopes = resumed.processedScopes + scopes.length;
+
+    await saveCheckpoint(client, input.mode, {
+      cursor: nextCursor,
+      runStartedAt: input.runStartedAt,
+      processedScopes,
+      done,
+    });
+
+    console.log(
+      JSON.stringify({
+        level: "INFO",
+        message: "Replayed derived signal chunk",
+        mode: input.mode,
+        cursor: resumed.cursor ?? null,
+        nextCursor,
+        processedScopes,
+        done,
+        metricName: "derived_pipeline_replay.scopes_processed",
+        metricValue: scopes.length,
+      })
+    );
+    emitMetrics(
+      "derived-pipeline-replay",
+      { ScopesProcessed: scopes.length },
+      { properties: { mode: input.mode } }
+    );
+
+    return {
+      mode: input.mode,
+      chunkSize: input.chunkSize,
+      windows: input.windows,
+      runStartedAt: input.runStartedAt,
+      processedScopes,
+      cursor: nextCursor,
+      done,
+    };
+  } finally {
+    await client.end();
+  }
+}
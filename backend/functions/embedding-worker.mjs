@@ -0,0 +1,175 @@
+import pg from "pg";
+import { createHash } from "node:crypto";
+import { BedrockRuntimeClient, InvokeModelCommand } from "@aws-sdk/client-bedrock-runtime";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EMBEDDING_MODEL_ID = "amazon.titan-embed-text-v2:0" } = process.env;
+
+// Must match the vector(512) columns in 0040_embeddings.sql.
+const EMBEDDING_DIMENSIONS = 512;
+
+const bedrock = new BedrockRuntimeClient({});
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+// Which row an event refers to, or null for events this worker ignores.
+function embeddingTarget(detailType, detail) {
+  if (detailType?.startsWith("listing.") && detail.listingId) {
+    return { kind: "listing", id: detail.listingId };
+  }
+  if (detailType?.startsWith("request.") && detail.requestId) {
+    return { kind: "request", id: detail.requestId };
+  }
+  return null;
+}
+
+// Listings embed what a gatherer would search for; requests add their
+// free-text notes, which is where catalog mismatches usually hide.
+function buildEmbeddingText(kind, row) {
+  const parts = kind === "listing"
+    ? [row.title, row.crop_name, row.variety_name]
+    : [row.crop_name, row.variety_name, row.notes];
+  return parts
+    .map((part) => (part ?? "").trim())
+    .filter(Boolean)
+    .join(". ");
+}
+
+function contentHash(modelId, text) {
+  return createHash("sha256").update(`${modelId}:${text}`).digest("hex");
+}
+
+function toVectorLiteral(values) {
+  return `[${values.join(",")}]`;
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+const TARGETS = {
+  listing: {
+    table: "listing_embeddings",
+    key: "listing_id",
+    source: `select l.title, c.common_name as crop_name, v.name as variety_name
+             from surplus_listings l
+             join crops c on c.id = l.crop_id
+             left join crop_varieties v on v.id = l.variety_id
+             where l.id = $1 and l.deleted_at is null`,
+  },
+  request: {
+    table: "request_embeddings",
+    key: "request_id",
+    source: `select r.notes, c.common_name as crop_name, v.name as variety_name
+             from requests r
+             join crops c on c.id = r.crop_id
+             left join crop_varieties v on v.id = r.variety_id
+             where r.id = $1 and r.deleted_at is null`,
+  },
+};
+
+async function loadSource(client, target) {
+  const { rows } = await client.query(TARGETS[target.kind].source, [target.id]);
+  return rows[0] ?? null;
+}
+
+async function loadStoredHash(client, target) {
+  const { table, key } = TARGETS[target.kind];
+  const { rows } = await client.query(
+    `select content_hash from ${table} where ${key} = $1 and model_id = $2`,
+    [target.id, EMBEDDING_MODEL_ID]
+  );
+  return rows[0]?.content_hash ?? null;
+}
+
+async function deleteEmbedding(client, target) {
+  const { table, key } = TARGETS[target.kind];
+  await client.query(`delete from ${table} where ${key} = $1`, [target.id]);
+}
+
+async function upsertEmbedding(client, target, hash, embedding) {
+  const { table, key } = TARGETS[target.kind];
+  await client.query(
+    `insert into ${table} (${key}, model_id, content_hash, embedding, embedded_at)
+     values ($1, $2, $3, $4::vector, now())
+     on conflict (${key}) do update
+       set model_id = excluded.model_id,
+           content_hash = excluded.content_hash,
+           embedding = excluded.embedding,
+           embedded_at = now()`,
+    [target.id, EMBEDDING_MODEL_ID, hash, toVectorLiteral(embedding)]
+  );
+}
+
+async function embed(text) {
+  const result = await bedrock.send(
+    new InvokeModelCommand({
+      modelId: EMBEDDING_MODEL_ID,
+      contentType: "application/json",
+      accept: "application/json",
+      body: JSON.stringify({ inputText: text, dimensions: EMBEDDING_DIMENSIONS, normalize: true }),
+    })
+  );
+  const { embedding } = JSON.parse(new TextDecoder().decode(result.body));
+  if (!Array.isArray(embedding) || embedding.length !== EMBEDDING_DIMENSIONS) {
+    throw new Error(`Unexpected embedding length ${embedding?.length ?? 0}`);
+  }
+  return embedding;
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+  const target = embeddingTarget(event["detail-type"], detail);
+
+  if (!target) {
+    return { statusCode: 200, body: "skipped: not a listing or request event" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let outcome;
+  try {
+    const source = await loadSource(client, target);
+    const text = source ? buildEmbeddingText(target.kind, source) : "";
+
+    if (!text) {
+      await deleteEmbedding(client, target);
+      outcome = "removed";
+    } else {
+      const hash = contentHash(EMBEDDING_MODEL_ID, text);
+      if ((await loadStoredHash(client, target)) === hash) {
+        outcome = "unchanged";
+      } else {
+        await upsertEmbedding(client, target, hash, await embed(text));
+        outcome = "embedded";
+      }
+    }
+  } finally {
+    await client.end();
+  }
+
+  console.log(
+    JSON.stringify({
+      level: "INFO",
+      message: "Processed embedding",
+      kind: target.kind,
+      id: target.id,
+      outcome,
+      modelId: EMBEDDING_MODEL_ID,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "embedding-worker",
+    {
+      EmbeddingsWritten: outcome === "embedded" ? 1 : 0,
+      EmbeddingsUnchanged: outcome === "unchanged" ? 1 : 0,
+      EmbeddingsRemoved: outcome === "removed" ? 1 : 0,
+    },
+    { properties: { correlationId, kind: target.kind } }
+  );
+
+  return { statusCode: 200, body: outcome };
+}
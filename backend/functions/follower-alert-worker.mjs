@@ -0,0 +1,111 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
+
+// PutEvents accepts at most 10 entries per call.
+const PUT_EVENTS_BATCH_SIZE = 10;
+
+const eventBridge = new EventBridgeClient({});
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+// Only listings that go live, on creation or when a scheduled listing
+// publishes, alert followers; drafts and other statuses stay quiet.
+function shouldAlert(detail) {
+  return Boolean(detail.listingId && detail.userId) && detail.status === "active";
+}
+
+// One event per follower, carrying routing ids only, so notification
+// consumers can fan out without reading listing contents.
+function buildAlertEntries(detail, followerIds, occurredAt) {
+  return followerIds.map((followerId) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "follower.listing_alert",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      listingId: detail.listingId,
+      growerId: detail.userId,
+      followerId,
+      cropId: detail.cropId ?? null,
+      correlationId: detail.correlationId ?? "unknown",
+      occurredAt,
+    }),
+  }));
+}
+
+function chunk(items, size) {
+  const batches = [];
+  for (let i = 0; i < items.length; i += size) {
+    batches.push(items.slice(i, i + size));
+  }
+  return batches;
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+async function loadFollowerIds(client, growerId) {
+  const { rows } = await client.query(
+    `select f.follower_id
+     from user_follows f
+     join users u on u.id = f.follower_id
+     where f.grower_id = $1 and u.deleted_at is null
+     order by f.created_at asc`,
+    [growerId]
+  );
+  return rows.map((row) => row.follower_id);
+}
+
+async function publishAlerts(entries) {
+  let failed = 0;
+  for (const batch of chunk(entries, PUT_EVENTS_BATCH_SIZE)) {
+    const result = await eventBridge.send(new PutEventsCommand({ Entries: batch }));
+    failed += result.FailedEntryCount ?? 0;
+  }
+  return failed;
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+
+  if (!shouldAlert(detail)) {
+    return { statusCode: 200, body: "skipped: not an active listing" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let followerIds;
+  try {
+    followerIds = await loadFollowerIds(client, detail.userId);
+  } finally {
+    await client.end();
+  }
+
+  const entries = buildAlertEntries(detail, followerIds, new Date().toISOString());
+  const failed = entries.length > 0 ? await publishAlerts(entries) : 0;
+
+  console.log(
+    JSON.stringify({
+      level: failed > 0 ? "WARN" : "INFO",
+      message: "Published follower listing alerts",
+      listingId: detail.listingId,
+      growerId: detail.userId,
+      alertCount: entries.length,
+      failedCount: failed,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "follower-alert-worker",
+    { AlertsPublished: entries.length - failed, AlertsFailed: failed },
+    { properties: { correlationId, listingId: detail.listingId } }
+  );
+
+  return { statusCode: 200, body: "ok" };
+}
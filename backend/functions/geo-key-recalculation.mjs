@@ -0,0 +1,333 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { encodeGeohash } from "./lib/geohash.mjs";
+import { GEO_PRECISIONS } from "./lib/signals.mjs";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default", GEO_KEY_PRECISION = "7" } = process.env;
+
+const PIPELINE_NAME = "geo_key_recalculation";
+const MODES = ["apply", "dry_run"];
+const DEFAULT_CHUNK_SIZE = 500;
+const MAX_CHUNK_SIZE = 5000;
+// PutEvents accepts at most 10 entries per call.
+const PUT_EVENTS_BATCH_SIZE = 10;
+
+// Tables with a stored geo_key derived from their own lat/lng, in the order
+// a run walks them. Listings and requests feed the derived signals, so a
+// moved key there means the scopes it left and joined need recomputing.
+const TARGETS = [
+  { name: "listings", table: "surplus_listings", idColumn: "id", live: true, signals: true },
+  { name: "requests", table: "requests", idColumn: "id", live: true, signals: true },
+  { name: "grower_profiles", table: "grower_profiles", idColumn: "user_id", live: false, signals: false },
+  { name: "gatherer_profiles", table: "gatherer_profiles", idColumn: "user_id", live: false, signals: false },
+];
+
+const eventBridge = new EventBridgeClient({});
+
+// ── chunk input ──────────────────────────────────────────────────────────────
+
+// Each invocation re-derives one page of one table and returns the cursor for
+// the next, like the derived pipeline replay, so a run can be driven by
+// repeated invocations or a Step Functions loop.
+function resolveChunkInput(input = {}, env = {}) {
+  const chunkSize = Number.parseInt(String(input.chunkSize ?? DEFAULT_CHUNK_SIZE), 10);
+  if (!Number.isInteger(chunkSize) || chunkSize < 1 || chunkSize > MAX_CHUNK_SIZE) {
+    throw new Error(`chunkSize must be between 1 and ${MAX_CHUNK_SIZE}`);
+  }
+
+  const mode = input.mode ?? "apply";
+  if (!MODES.includes(mode)) {
+    throw new Error(`mode must be one of ${MODES.join("|")}`);
+  }
+
+  const precision = Number.parseInt(String(env.GEO_KEY_PRECISION ?? GEO_KEY_PRECISION), 10);
+  if (!Number.isInteger(precision) || precision < Math.max(...GEO_PRECISIONS) || precision > 12) {
+    throw new Error(`GEO_KEY_PRECISION must be between ${Math.max(...GEO_PRECISIONS)} and 12`);
+  }
+
+  return {
+    mode,
+    chunkSize,
+    precision,
+    cursor: decodeCursor(input.cursor ?? null),
+    runStartedAt: input.runStartedAt ?? new Date().toISOString(),
+  };
+}
+
+function encodeCursor(cursor) {
+  if (!cursor) return null;
+  return cursor.afterId ? `${cursor.target}|${cursor.afterId}` : cursor.target;
+}
+
+function decodeCursor(cursor) {
+  if (!cursor) return { target: TARGETS[0].name, afterId: null };
+  const [target, afterId = null] = String(cursor).split("|");
+  if (!TARGETS.some((t) => t.name === target)) {
+    throw new Error(`Invalid geo key cursor: ${cursor}`);
+  }
+  return { target, afterId };
+}
+
+// Where the next chunk starts: later in the same table while it returned a
+// full page, otherwise the start of the next table, or null when done.
+function nextCursor(cursor, rows, chunkSize) {
+  if (rows.length === chunkSize) {
+    return { target: cursor.target, afterId: rows[rows.length - 1].id };
+  }
+  const index = TARGETS.findIndex((t) => t.name === cursor.target);
+  const next = TARGETS[index + 1];
+  return next ? { target: next.name, afterId: null } : null;
+}
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+function planChanges(rows, precision) {
+  const changes = [];
+  for (const row of rows) {
+    const geoKey = encodeGeohash(row.lat, row.lng, precision);
+    if (geoKey !== row.geo_key) {
+      changes.push({ id: row.id, oldGeoKey: row.geo_key ?? null, newGeoKey: geoKey, cropId: row.crop_id ?? null });
+    }
+  }
+  return changes;
+}
+
+// Signal scopes a move touches: at each aggregated precision where the old
+// and new prefixes differ, both cells, for the crop and for all crops.
+function affectedScopes(changes) {
+  const seen = new Set();
+  const scopes = [];
+  const add = (geoPrefix, cropId) => {
+    const key = `${geoPrefix}|${cropId ?? ""}`;
+    if (seen.has(key)) return;
+    seen.add(key);
+    scopes.push({ geoPrefix, cropId });
+  };
+
+  for (const change of changes) {
+    for (const precision of GEO_PRECISIONS) {
+      const before = change.oldGeoKey?.toLowerCase().slice(0, precision) ?? null;
+      const after = change.newGeoKey.slice(0, precision);
+      if (before === after) continue;
+      for (const prefix of [before, after]) {
+        if (!prefix || prefix.length < precision) continue;
+        add(prefix, change.cropId);
+        add(prefix, null);
+      }
+    }
+  }
+  return scopes;
+}
+
+function buildRecomputeEntries(scopes, correlationId, occurredAt) {
+  return scopes.map((scope) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "signal.recompute_requested",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      geoPrefix: scope.geoPrefix,
+      cropId: scope.cropId,
+      reason: "geo_key_recalculated",
+      correlationId,
+      occurredAt,
+    }),
+  }));
+}
+
+function chunk(items, size) {
+  const batches = [];
+  for (let i = 0; i < items.length; i += size) {
+    batches.push(items.slice(i, i + size));
+  }
+  return batches;
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+async function loadPage(client, target, afterId, chunkSize) {
+  const { rows } = await client.query(
+    `SELECT ${target.idColumn} AS id, geo_key, lat, lng
+            ${target.signals ? ", crop_id" : ""}
+     FROM ${target.table}
+     WHERE lat IS NOT NULL AND lng IS NOT NULL
+       ${target.live ? "AND deleted_at IS NULL" : ""}
+       AND ($1::uuid IS NULL OR ${target.idColumn} > $1)
+     ORDER BY ${target.idColumn}
+     LIMIT $2`,
+    [afterId, chunkSize]
+  );
+  return rows;
+}
+
+// updated_at is left alone: the row's content did not change, and bumping it
+// would resurface old listings in anything ordered by recency.
+async function applyChanges(client, target, changes) {
+  const { rowCount } = await client.query(
+    `UPDATE ${target.table} AS t
+     SET geo_key = v.geo_key
+     FROM unnest($1::uuid[], $2::text[]) AS v(id, geo_key)
+     WHERE t.${target.idColumn} = v.id
+       AND t.geo_key IS DISTINCT FROM v.geo_key`,
+    [changes.map((c) => c.id), changes.map((c) => c.newGeoKey)]
+  );
+
+  // Crop plans copy the grower's geo_key when saved.
+  if (target.name === "grower_profiles") {
+    await client.query(
+      `UPDATE crop_plans AS cp
+       SET geo_key = gp.geo_key
+       FROM grower_profiles gp
+       WHERE gp.user_id = ANY($1::uuid[])
+         AND cp.user_id = gp.user_id
+         AND cp.geo_key IS DISTINCT FROM gp.geo_key`,
+      [changes.map((c) => c.id)]
+    );
+  }
+  return rowCount;
+}
+
+async function publishRecomputes(entries) {
+  let failed = 0;
+  for (const batch of chunk(entries, PUT_EVENTS_BATCH_SIZE)) {
+    const result = await eventBridge.send(new PutEventsCommand({ Entries: batch }));
+    failed += result.FailedEntryCount ?? 0;
+  }
+  return failed;
+}
+
+// ── checkpoints ──────────────────────────────────────────────────────────────
+
+async function loadCheckpoint(client, mode) {
+  const { rows } = await client.query(
+    `SELECT cursor, run_started_at, processed_scopes, status
+     FROM pipeline_checkpoints
+     WHERE pipeline_name = $1 AND mode = $2`,
+    [PIPELINE_NAME, mode]
+  );
+  return rows[0] ?? null;
+}
+
+// processed_scopes counts rows examined for this pipeline.
+async function saveCheckpoint(client, mode, checkpoint) {
+  await client.query(
+    `INSERT INTO pipeline_checkpoints (
+       pipeline_name, mode, cursor, run_started_at, processed_scopes, status
+     )
+     VALUES ($1, $2, $3, $4, $5, $6)
+     ON CONFLICT (pipeline_name, mode) DO UPDATE
+       SET cursor = excluded.cursor,
+           run_started_at = excluded.run_started_at,
+           processed_scopes = excluded.processed_scopes,
+           status = excluded.status,
+           updated_at = now()`,
+    [
+      PIPELINE_NAME,
+      mode,
+      checkpoint.cursor,
+      checkpoint.runStartedAt,
+      checkpoint.processedRows,
+      checkpoint.done ? "completed" : "running",
+    ]
+  );
+}
+
+// Same resume rules as the derived pipeline replay: an explicit cursor wins,
+// a running checkpoint is picked up, and a completed one starts over.
+function resumeFromCheckpoint(event, checkpoint) {
+  if (event.cursor !== undefined || !checkpoint || checkpoint.status !== "running") {
+    return { ...event, processedRows: event.processedRows ?? 0 };
+  }
+  return {
+    ...event,
+    cursor: checkpoint.cursor,
+    runStartedAt: new Date(checkpoint.run_started_at).toISOString(),
+    processedRows: checkpoint.processed_scopes,
+  };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.correlationId ?? `geo-key-recalculation-${Date.now()}`;
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const checkpoint = await loadCheckpoint(client, event.mode ?? "apply");
+    const resumed = resumeFromCheckpoint(event, checkpoint);
+    const input = resolveChunkInput(resumed, process.env);
+    const target = TARGETS.find((t) => t.name === input.cursor.target);
+
+    const rows = await loadPage(client, target, input.cursor.afterId, input.chunkSize);
+    const changes = planChanges(rows, input.precision);
+    const scopes = target.signals ? affectedScopes(changes) : [];
+
+    let updatedRows = 0;
+    let failedEvents = 0;
+    if (input.mode === "apply" && changes.length > 0) {
+      updatedRows = await applyChanges(client, target, changes);
+      if (scopes.length > 0) {
+        failedEvents = await publishRecomputes(
+          buildRecomputeEntries(scopes, correlationId, new Date().toISOString())
+        );
+      }
+    }
+
+    const next = encodeCursor(nextCursor(input.cursor, rows, input.chunkSize));
+    const done = next === null;
+    const processedRows = resumed.processedRows + rows.length;
+
+    await saveCheckpoint(client, input.mode, {
+      cursor: next,
+      runStartedAt: input.runStartedAt,
+      processedRows,
+      done,
+    });
+
+    console.log(
+      JSON.stringify({
+        level: failedEvents > 0 ? "WARN" : "INFO",
+        message: "Recalculated geo key chunk",
+        correlationId,
+        mode: input.mode,
+        target: target.name,
+        precision: input.precision,
+        cursor: encodeCursor(input.cursor),
+        nextCursor: next,
+        examined: rows.length,
+        mismatched: changes.length,
+        updatedRows,
+        scopesQueued: input.mode === "apply" ? scopes.length - failedEvents : 0,
+        failedCount: failedEvents,
+        processedRows,
+        done,
+      })
+    );
+    emitMetrics(
+      "geo-key-recalculation",
+      { RowsExamined: rows.length, RowsUpdated: updatedRows, RecomputesFailed: failedEvents },
+      { properties: { mode: input.mode, target: target.name, correlationId } }
+    );
+
+    return {
+      mode: input.mode,
+      chunkSize: input.chunkSize,
+      precision: input.precision,
+      runStartedAt: input.runStartedAt,
+      target: target.name,
+      mismatched: changes.length,
+      updatedRows,
+      affectedScopes: scopes.length,
+      processedRows,
+      cursor: next,
+      done,
+    };
+  } finally {
+    await client.end();
+  }
+}
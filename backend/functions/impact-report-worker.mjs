@@ -0,0 +1,236 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
+
+const REPORT_GEO_PRECISION = 4;
+const TOP_CROPS_LIMIT = 5;
+// Produce from crops without a nutrition group is reported under this name.
+const UNGROUPED = "other";
+
+const eventBridge = new EventBridgeClient({});
+
+// ── week helpers ─────────────────────────────────────────────────────────────
+
+// Reports cover the last fully completed ISO week (Monday 00:00 UTC onward)
+// unless the invocation payload pins a specific weekStart for backfills.
+function resolveWeekStart(now, override) {
+  if (override) {
+    const pinned = new Date(`${override}T00:00:00Z`);
+    if (Number.isNaN(pinned.getTime()) || pinned.getUTCDay() !== 1) {
+      throw new Error(`weekStart must be a Monday in YYYY-MM-DD format: ${override}`);
+    }
+    return pinned;
+  }
+
+  const today = Date.UTC(now.getUTCFullYear(), now.getUTCMonth(), now.getUTCDate());
+  const daysSinceMonday = (new Date(today).getUTCDay() + 6) % 7;
+  return new Date(today - (daysSinceMonday + 7) * 86_400_000);
+}
+
+function formatDate(date) {
+  return date.toISOString().slice(0, 10);
+}
+
+// ── report assembly ──────────────────────────────────────────────────────────
+
+function buildReports(totalRows, cropRows, groupRows = []) {
+  const cropsByGeo = new Map();
+  for (const row of cropRows) {
+    const crops = cropsByGeo.get(row.geo_boundary_key) ?? [];
+    crops.push({
+      cropId: row.crop_id,
+      cropName: row.crop_name,
+      quantity: row.quantity,
+      claimCount: row.claim_count,
+    });
+    cropsByGeo.set(row.geo_boundary_key, crops);
+  }
+
+  const groupsByGeo = new Map();
+  for (const row of groupRows) {
+    const groups = groupsByGeo.get(row.geo_boundary_key) ?? [];
+    groups.push({
+      group: row.nutrition_group ?? UNGROUPED,
+      quantity: row.quantity,
+      claimCount: row.claim_count,
+    });
+    groupsByGeo.set(row.geo_boundary_key, groups);
+  }
+
+  return totalRows.map((row) => ({
+    geoBoundaryKey: row.geo_boundary_key,
+    completedClaimCount: row.completed_claim_count,
+    quantityShared: row.quantity_shared,
+    uniqueGrowers: row.unique_growers,
+    uniqueGatherers: row.unique_gatherers,
+    topCrops: (cropsByGeo.get(row.geo_boundary_key) ?? [])
+      .sort((a, b) => b.quantity - a.quantity || b.claimCount - a.claimCount)
+      .slice(0, TOP_CROPS_LIMIT),
+    nutritionGroups: (groupsByGeo.get(row.geo_boundary_key) ?? []).sort(
+      (a, b) => b.quantity - a.quantity || a.group.localeCompare(b.group)
+    ),
+  }));
+}
+
+// ── persistence ──────────────────────────────────────────────────────────────
+
+async function loadWeeklyTotals(client, weekStart, weekEnd) {
+  const { rows } = await client.query(
+    `SELECT left(l.geo_key, $3) AS geo_boundary_key,
+            count(*)::int AS completed_claim_count,
+            coalesce(sum(c.quantity_claimed), 0)::float AS quantity_shared,
+            count(DISTINCT c.listing_owner_id)::int AS unique_growers,
+            count(DISTINCT c.claimer_id)::int AS unique_gatherers
+     FROM claims c
+     JOIN surplus_listings l ON l.id = c.listing_id
+     WHERE c.status = 'completed'
+       AND c.completed_at >= $1
+       AND c.completed_at < $2
+       AND char_length(l.geo_key) >= $3
+     GROUP BY 1`,
+    [weekStart, weekEnd, REPORT_GEO_PRECISION]
+  );
+  return rows;
+}
+
+async function loadWeeklyCrops(client, weekStart, weekEnd) {
+  const { rows } = await client.query(
+    `SELECT left(l.geo_key, $3) AS geo_boundary_key,
+            l.crop_id,
+            cr.common_name AS crop_name,
+            coalesce(sum(c.quantity_claimed), 0)::float AS quantity,
+            count(*)::int AS claim_count
+     FROM claims c
+     JOIN surplus_listings l ON l.id = c.listing_id
+     JOIN crops cr ON cr.id = l.crop_id
+     WHERE c.status = 'completed'
+       AND c.completed_at >= $1
+       AND c.completed_at < $2
+       AND char_length(l.geo_key) >= $3
+     GROUP BY 1, 2, 3`,
+    [weekStart, weekEnd, REPORT_GEO_PRECISION]
+  );
+  return rows;
+}
+
+// Produce only: seeds, seedlings, tool loans and compost are not food.
+async function loadWeeklyNutritionGroups(client, weekStart, weekEnd) {
+  const { rows } = await client.query(
+    `SELECT left(l.geo_key, $3) AS geo_boundary_key,
+            cr.nutrition_group,
+            coalesce(sum(c.quantity_claimed), 0)::float AS quantity,
+            count(*)::int AS claim_count
+     FROM claims c
+     JOIN surplus_listings l ON l.id = c.listing_id
+     JOIN crops cr ON cr.id = l.crop_id
+     WHERE c.status = 'completed'
+       AND c.completed_at >= $1
+       AND c.completed_at < $2
+       AND char_length(l.geo_key) >= $3
+       AND l.listing_kind = 'produce'
+     GROUP BY 1, 2`,
+    [weekStart, weekEnd, REPORT_GEO_PRECISION]
+  );
+  return rows;
+}
+
+async function upsertReport(client, weekStart, report) {
+  await client.query(
+    `INSERT INTO impact_reports (
+       geo_boundary_key, week_start, completed_claim_count, quantity_shared,
+       unique_growers, unique_gatherers, top_crops, nutrition_groups, generated_at
+     )
+     VALUES ($1, $2::date, $3, $4, $5, $6, $7::jsonb, $8::jsonb, now())
+     ON CONFLICT (geo_boundary_key, week_start) DO UPDATE
+       SET completed_claim_count = excluded.completed_claim_count,
+           quantity_shared = excluded.quantity_shared,
+           unique_growers = excluded.unique_growers,
+           unique_gatherers = excluded.unique_gatherers,
+           top_crops = excluded.top_crops,
+           nutrition_groups = excluded.nutrition_groups,
+           generated_at = excluded.generated_at,
+           updated_at = now()`,
+    [
+      report.geoBoundaryKey,
+      weekStart,
+      report.completedClaimCount,
+      report.quantityShared,
+      report.uniqueGrowers,
+      report.uniqueGatherers,
+      JSON.stringify(report.topCrops),
+      JSON.stringify(report.nutritionGroups),
+    ]
+  );
+}
+
+async function emitReportGenerated(weekStart, reports, correlationId) {
+  await eventBridge.send(
+    new PutEventsCommand({
+      Entries: [
+        {
+          EventBusName: EVENT_BUS_NAME,
+          Source: "community-garden.workers",
+          DetailType: "report.generated",
+          Detail: JSON.stringify({
+            schemaVersion: 1,
+            reportType: "weekly_impact",
+            weekStart,
+            geoBoundaryKeys: reports.map((r) => r.geoBoundaryKey),
+            reportCount: reports.length,
+            correlationId,
+            occurredAt: new Date().toISOString(),
+          }),
+        },
+      ],
+    })
+  );
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `impact-report-${Date.now()}`;
+  const weekStartDate = resolveWeekStart(new Date(), event.detail?.weekStart ?? event.weekStart);
+  const weekEndDate = new Date(weekStartDate.getTime() + 7 * 86_400_000);
+  const weekStart = formatDate(weekStartDate);
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const totals = await loadWeeklyTotals(client, weekStartDate, weekEndDate);
+    const crops = await loadWeeklyCrops(client, weekStartDate, weekEndDate);
+    const groups = await loadWeeklyNutritionGroups(client, weekStartDate, weekEndDate);
+    const reports = buildReports(totals, crops, groups);
+
+    for (const report of reports) {
+      await upsertReport(client, weekStart, report);
+    }
+
+    await emitReportGenerated(weekStart, reports, correlationId);
+
+    console.log(
+      JSON.stringify({
+        level: "INFO",
+        message: "Generated weekly impact reports",
+        correlationId,
+        weekStart,
+        reportCount: reports.length,
+        metricName: "impact_report.reports_generated",
+        metricValue: reports.length,
+      })
+    );
+    emitMetrics(
+      "impact-report-worker",
+      { ReportsGenerated: reports.length },
+      { properties: { correlationId, weekStart } }
+    );
+  } finally {
+    await client.end();
+  }
+}
@@ -0,0 +1,69 @@
+// Week-over-week spike detection for derived signals. A metric is anomalous
+// when it at least triples versus the value computed about a week earlier and
+// the new value is large enough to matter (tiny scores triple on noise).
+
+export const ANOMALY_RATIO_THRESHOLD = 3;
+export const ANOMALY_MIN_SCORE = 1;
+export const ANOMALY_LOOKBACK_DAYS = 7;
+
+export function detectAnomalies(baseline, current) {
+  if (!baseline) return [];
+
+  const anomalies = [];
+  for (const metric of ["scarcity", "abundance"]) {
+    const previous = baseline[`${metric}Score`];
+    const value = current[`${metric}Score`];
+    if (value < ANOMALY_MIN_SCORE || value < previous * ANOMALY_RATIO_THRESHOLD) continue;
+
+    anomalies.push({
+      metric,
+      previousValue: previous,
+      currentValue: value,
+      // A zero baseline has no meaningful ratio.
+      ratio: previous > 0 ? Number((value / previous).toFixed(4)) : null,
+    });
+  }
+  return anomalies;
+}
+
+export async function loadBaseline(client, scope, windowDays, asOf) {
+  const before = new Date(asOf.getTime() - ANOMALY_LOOKBACK_DAYS * 86_400_000);
+  const { rows } = await client.query(
+    `SELECT scarcity_score::float8 AS scarcity_score,
+            abundance_score::float8 AS abundance_score
+     FROM derived_supply_signals
+     WHERE schema_version = 1
+       AND geo_boundary_key = $1
+       AND crop_scope_id = coalesce($2::uuid, '00000000-0000-0000-0000-000000000000'::uuid)
+       AND window_days = $3
+       AND computed_at <= $4
+     ORDER BY computed_at DESC, id DESC
+     LIMIT 1`,
+    [scope.geoBoundaryKey, scope.cropId, windowDays, before]
+  );
+  if (rows.length === 0) return null;
+  return { scarcityScore: rows[0].scarcity_score, abundanceScore: rows[0].abundance_score };
+}
+
+// Returns true only when the row is new, so callers emit one event per day.
+export async function recordAnomaly(client, scope, windowDays, anomaly, detectedAt) {
+  const { rowCount } = await client.query(
+    `INSERT INTO signal_anomalies (
+       geo_boundary_key, crop_id, window_days, metric,
+       previous_value, current_value, ratio, detected_on, detected_at
+     )
+     VALUES ($1, $2, $3, $4, $5, $6, $7, $8::date, $8)
+     ON CONFLICT (geo_boundary_key, crop_scope_id, window_days, metric, detected_on) DO NOTHING`,
+    [
+      scope.geoBoundaryKey,
+      scope.cropId,
+      windowDays,
+      anomaly.metric,
+      anomaly.previousValue,
+      anomaly.currentValue,
+      anomaly.ratio,
+      detectedAt,
+    ]
+  );
+  return rowCount > 0;
+}
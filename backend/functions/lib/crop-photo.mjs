@@ -0,0 +1,63 @@
+// Crop identification from listing photos. The worker asks a vision model
+// what crop a photo shows and keeps the most confident answer; these helpers
+// hold the parts that do not touch the network or database.
+
+// Below this the guess is recorded as "checked, no opinion" so a blurry photo
+// never warns the grower.
+export const MIN_CONFIDENCE = 0.7;
+
+// Bedrock Converse accepts images up to 3.75 MB.
+export const MAX_IMAGE_BYTES = 3_750_000;
+
+const IMAGE_FORMATS = {
+  "image/jpeg": "jpeg",
+  "image/jpg": "jpeg",
+  "image/png": "png",
+  "image/webp": "webp",
+  "image/gif": "gif",
+};
+
+export function imageFormat(contentType) {
+  const mime = (contentType ?? "").split(";")[0].trim().toLowerCase();
+  return IMAGE_FORMATS[mime] ?? null;
+}
+
+export const IDENTIFY_PROMPT = `Identify the single fruit, vegetable, or herb this photo mostly shows.
+Reply with JSON only: {"crop":"<common name, singular, e.g. tomato>","confidence":<0 to 1>}.
+Reply {"crop":null,"confidence":0} when no produce is visible.`;
+
+// Normalizes a model reply to { crop, confidence }, or null when unusable.
+export function parseIdentification(reply) {
+  const match = /\{[\s\S]*\}/.exec(reply ?? "");
+  if (!match) return null;
+  let parsed;
+  try {
+    parsed = JSON.parse(match[0]);
+  } catch {
+    return null;
+  }
+  const crop = typeof parsed.crop === "string" ? parsed.crop.trim().toLowerCase() : "";
+  const confidence = Number(parsed.confidence);
+  if (!crop || !Number.isFinite(confidence)) return null;
+  return { crop, confidence: Math.min(Math.max(confidence, 0), 1) };
+}
+
+// Best identification across a listing's photos, or null when none clears
+// MIN_CONFIDENCE.
+export function chooseIdentification(identifications) {
+  return identifications
+    .filter((identification) => identification && identification.confidence >= MIN_CONFIDENCE)
+    .reduce((best, next) => (best && best.confidence >= next.confidence ? best : next), null);
+}
+
+// Catalog slugs are lowercase and hyphenated ("swiss-chard"); model answers
+// are free text ("Swiss chard", "tomatoes").
+export function cropSlugCandidates(crop) {
+  const slug = crop.toLowerCase().trim().replace(/[^a-z0-9]+/g, "-").replace(/^-|-$/g, "");
+  if (!slug) return [];
+  const candidates = [slug];
+  if (slug.endsWith("oes")) candidates.push(slug.slice(0, -2));
+  else if (slug.endsWith("ies")) candidates.push(`${slug.slice(0, -3)}y`);
+  else if (slug.endsWith("s")) candidates.push(slug.slice(0, -1));
+  return candidates;
+}
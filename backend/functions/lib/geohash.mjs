@@ -0,0 +1,47 @@
+// Geohash encoding matching the API's `geohash::encode`, so workers can
+// re-derive stored geo keys from lat/lng without a round trip through it.
+
+const BASE32 = "0123456789bcdefghjkmnpqrstuvwxyz";
+
+export const MAX_GEOHASH_PRECISION = 12;
+
+export function encodeGeohash(lat, lng, precision) {
+  if (!Number.isFinite(lat) || lat < -90 || lat > 90) {
+    throw new Error(`lat out of range: ${lat}`);
+  }
+  if (!Number.isFinite(lng) || lng < -180 || lng > 180) {
+    throw new Error(`lng out of range: ${lng}`);
+  }
+  if (!Number.isInteger(precision) || precision < 1 || precision > MAX_GEOHASH_PRECISION) {
+    throw new Error(`precision must be between 1 and ${MAX_GEOHASH_PRECISION}`);
+  }
+
+  let [latMin, latMax] = [-90, 90];
+  let [lngMin, lngMax] = [-180, 180];
+  let hash = "";
+  let bits = 0;
+  let value = 0;
+  let lngBit = true;
+
+  while (hash.length < precision) {
+    if (lngBit) {
+      const mid = (lngMin + lngMax) / 2;
+      value = value * 2 + (lng >= mid ? 1 : 0);
+      if (lng >= mid) lngMin = mid;
+      else lngMax = mid;
+    } else {
+      const mid = (latMin + latMax) / 2;
+      value = value * 2 + (lat >= mid ? 1 : 0);
+      if (lat >= mid) latMin = mid;
+      else latMax = mid;
+    }
+    lngBit = !lngBit;
+    bits += 1;
+    if (bits === 5) {
+      hash += BASE32[value];
+      bits = 0;
+      value = 0;
+    }
+  }
+  return hash;
+}
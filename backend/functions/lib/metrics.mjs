@@ -0,0 +1,44 @@
+// CloudWatch Embedded Metric Format helper. Writing the record to stdout is
+// enough: Lambda ships it to CloudWatch Logs, which extracts the metrics.
+
+const DEFAULT_NAMESPACE = "CommunityGarden";
+
+export function buildMetricRecord({
+  namespace = DEFAULT_NAMESPACE,
+  dimensions = {},
+  metrics = {},
+  units = {},
+  properties = {},
+  timestamp = Date.now(),
+}) {
+  return {
+    ...properties,
+    ...dimensions,
+    ...metrics,
+    _aws: {
+      Timestamp: timestamp,
+      CloudWatchMetrics: [
+        {
+          Namespace: namespace,
+          Dimensions: [Object.keys(dimensions)],
+          Metrics: Object.keys(metrics).map((name) => ({
+            Name: name,
+            Unit: units[name] ?? "Count",
+          })),
+        },
+      ],
+    },
+  };
+}
+
+// Extra `dimensions` split a worker's metrics further, e.g. per entity.
+export function emitMetrics(worker, metrics, { units = {}, properties = {}, dimensions = {} } = {}) {
+  const record = buildMetricRecord({
+    namespace: process.env.METRICS_NAMESPACE ?? DEFAULT_NAMESPACE,
+    dimensions: { Service: "workers", Worker: worker, ...dimensions },
+    metrics,
+    units,
+    properties,
+  });
+  console.log(JSON.stringify(record));
+}
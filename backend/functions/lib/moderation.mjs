@@ -0,0 +1,74 @@
+// Text moderation for listings, listing Q&A and messages. Providers return a list of
+// categories; decideAction turns categories into what the worker does. The
+// "rules" provider is deterministic and needs no network; "bedrock" asks a
+// model and falls back to rules when the model is unavailable.
+
+export const CATEGORIES = ["contact_info", "solicitation", "abuse"];
+
+// Asking for or posting off-platform contact details bypasses the pickup
+// address disclosure policy, so it is worth a look but not a hold.
+const RULES = {
+  contact_info: [
+    /[a-z0-9._%+-]+@[a-z0-9.-]+\.[a-z]{2,}/i,
+    /(?:\+?1[\s.-]?)?\(?\d{3}\)?[\s.-]?\d{3}[\s.-]?\d{4}/,
+    /\b(?:text|call|dm|email|whatsapp|telegram)\s+me\b/i,
+    /\b(?:send|give)\s+(?:me\s+)?your\s+(?:number|phone|email|address)\b/i,
+  ],
+  solicitation: [
+    /\$\s?\d/,
+    /\b\d+\s?(?:dollars|bucks)\b/i,
+    /\b(?:venmo|cash\s?app|paypal|zelle)\b/i,
+    /\bfor\s+sale\b/i,
+    /\b(?:price|priced)\s+(?:at|is)\b/i,
+  ],
+  abuse: [
+    /\b(?:idiot|moron|stupid|loser)\b/i,
+    /\b(?:kill|hurt)\s+you\b/i,
+    /\bscam(?:mer)?s?\b/i,
+  ],
+};
+
+export function classifyWithRules(text) {
+  return CATEGORIES.filter((category) => RULES[category].some((pattern) => pattern.test(text)));
+}
+
+// Listings and their public Q&A stay up for contact info but are held for
+// selling or abuse. Messages are already delivered, so they are only ever
+// flagged.
+export function decideAction(subjectType, categories) {
+  if (categories.length === 0) return null;
+  if (subjectType === "message") return "flag";
+  return categories.some((category) => category !== "contact_info") ? "hold" : "flag";
+}
+
+// Joins the moderated fields, skipping empty ones.
+export function moderationText(parts) {
+  return parts
+    .map((part) => (part ?? "").trim())
+    .filter(Boolean)
+    .join("\n");
+}
+
+// Short excerpt for reviewers, so the queue does not copy whole messages.
+export function excerpt(text, maxLength = 280) {
+  return text.length <= maxLength ? text : `${text.slice(0, maxLength - 1)}…`;
+}
+
+export const MODEL_PROMPT = `You moderate posts on a free community produce-sharing app.
+Classify the text into zero or more of these categories:
+- contact_info: posts or asks for phone numbers, emails, or other off-platform contact details
+- solicitation: offers to sell, prices, or payment apps
+- abuse: insults, threats, or harassment
+Reply with JSON only, for example {"categories":["solicitation"]}. Reply {"categories":[]} when none apply.`;
+
+// Keeps only known categories from a model reply; anything unparseable is an
+// error so the caller can fall back to rules.
+export function parseModelCategories(reply) {
+  const match = /\{[\s\S]*\}/.exec(reply ?? "");
+  if (!match) throw new Error("Moderation model reply had no JSON object");
+  const parsed = JSON.parse(match[0]);
+  if (!Array.isArray(parsed.categories)) {
+    throw new Error("Moderation model reply had no categories array");
+  }
+  return CATEGORIES.filter((category) => parsed.categories.includes(category));
+}
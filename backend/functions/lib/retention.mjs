@@ -0,0 +1,64 @@
+// Retention policy helpers for the retention worker. Policies live in the
+// retention_policies table; everything here is pure so it can be tested
+// without a database.
+
+export const DEFAULT_BATCH_SIZE = 1000;
+export const DEFAULT_MAX_BATCHES = 50;
+
+const DAY_MS = 86_400_000;
+const IDENTIFIER = /^[a-z_][a-z0-9_]*$/;
+
+// Tables whose triggers reject deletes unless the purge flag is set for the
+// transaction.
+const GUARDED_TABLES = new Set(["audit_log"]);
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+export function resolveRetentionConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.PURGE_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.PURGE_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+    // Restricts a manual run to some entities, e.g. { entities: ["messages"] }.
+    entities: Array.isArray(overrides.entities) ? overrides.entities : null,
+  };
+}
+
+// Turns a retention_policies row into a purge plan, or returns the reason it
+// cannot run. The table constraints already enforce identifiers; this is the
+// second check before they are interpolated into SQL.
+export function planPurge(policy, now) {
+  const { entity, table_name: table, timestamp_column: column, retention_days: days } = policy;
+  if (!IDENTIFIER.test(table ?? "") || !IDENTIFIER.test(column ?? "")) {
+    return { entity, skip: "invalid_identifier" };
+  }
+  if (!Number.isInteger(days) || days < 0) {
+    return { entity, skip: "invalid_retention_days" };
+  }
+  return {
+    entity,
+    table,
+    column,
+    retentionDays: days,
+    cutoff: new Date(now.getTime() - days * DAY_MS),
+    guarded: GUARDED_TABLES.has(table),
+  };
+}
+
+export function purgeBatchSql(plan) {
+  return `DELETE FROM ${plan.table}
+       WHERE ctid = ANY(ARRAY(
+         SELECT ctid FROM ${plan.table}
+         WHERE ${plan.column} < $1
+         ORDER BY ${plan.column}
+         LIMIT $2
+       ))`;
+}
+
+export function selectPolicies(policies, entities) {
+  const enabled = policies.filter((policy) => policy.enabled);
+  return entities ? enabled.filter((policy) => entities.includes(policy.entity)) : enabled;
+}
@@ -0,0 +1,254 @@
+// Shared derived-signal computation used by the rolling aggregation worker, the
+// replay pipeline, and the baseline sweep. Keep formulas and SQL here so the
+// callers cannot drift.
+
+export const SUPPORTED_WINDOWS_DAYS = [7, 14, 30];
+export const GEO_PRECISIONS = [4, 5, 6];
+export const SCHEMA_VERSION = 1;
+
+// ── geo helpers ──────────────────────────────────────────────────────────────
+
+export function geoPrefixes(geoKey) {
+  const normalized = geoKey.trim().toLowerCase();
+  return GEO_PRECISIONS.filter((p) => normalized.length >= p).map((p) =>
+    normalized.slice(0, p)
+  );
+}
+
+export function expandGeoScopes(sourcePairs) {
+  const seen = new Set();
+  const scopes = [];
+  for (const { geoKey, cropId } of sourcePairs) {
+    for (const prefix of geoPrefixes(geoKey)) {
+      for (const cid of [cropId, null]) {
+        const key = `${prefix}|${cid ?? ""}`;
+        if (!seen.has(key)) {
+          seen.add(key);
+          scopes.push({ geoBoundaryKey: prefix, cropId: cid });
+        }
+      }
+    }
+  }
+  return scopes;
+}
+
+const GEOHASH_PATTERN = /^[0-9b-hjkmnp-z]+$/;
+const UUID_PATTERN = /^[0-9a-f]{8}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{4}-[0-9a-f]{12}$/i;
+
+// Validates a single targeted scope, e.g. from the admin recompute endpoint.
+// Only prefix lengths the aggregation writes are accepted, since a scope at
+// any other precision would never be read.
+export function normalizeRecomputeScope(input = {}) {
+  const geoBoundaryKey = String(input.geoPrefix ?? "").trim().toLowerCase();
+  if (!GEOHASH_PATTERN.test(geoBoundaryKey) || !GEO_PRECISIONS.includes(geoBoundaryKey.length)) {
+    throw new Error(`geoPrefix must be a geohash of ${GEO_PRECISIONS.join(", ")} characters`);
+  }
+
+  const cropId = input.cropId ?? null;
+  if (cropId !== null && !UUID_PATTERN.test(String(cropId))) {
+    throw new Error("cropId must be a UUID");
+  }
+
+  const windows = [...new Set(input.windows ?? SUPPORTED_WINDOWS_DAYS)];
+  if (windows.length === 0) {
+    throw new Error("windows must not be empty");
+  }
+  for (const windowDays of windows) {
+    if (!SUPPORTED_WINDOWS_DAYS.includes(windowDays)) {
+      throw new Error(`Unsupported window: ${windowDays}`);
+    }
+  }
+
+  return { scope: { geoBoundaryKey, cropId }, windows: windows.sort((a, b) => a - b) };
+}
+
+// ── aggregation ──────────────────────────────────────────────────────────────
+
+export function computeBucketStart(occurredAt) {
+  const ts = Math.floor(new Date(occurredAt).getTime() / 1000);
+  const bucket = 5 * 60;
+  const floored = ts - ((ts % bucket) + bucket) % bucket; // euclid mod
+  return new Date(floored * 1000);
+}
+
+export function retentionDays(windowDays) {
+  if (windowDays === 7) return 35;
+  if (windowDays === 14) return 49;
+  return 90;
+}
+
+// Supply is what was listed minus what is already promised (confirmed) or
+// handed over (completed); pending claims are still negotiable. Logged
+// harvests are reported alongside but stay out of supply: most are eaten or
+// given away off-platform, so counting them would overstate what gatherers
+// can claim. Crop plans for seasons not yet over are reported the same way
+// as anticipated supply: intent, not produce anyone can pick up yet.
+export function computeSignal(
+  listingRow,
+  requestRow,
+  claimRow,
+  windowDays,
+  harvestRow = { harvest_count: 0, harvested_quantity: 0 },
+  plannedRow = { planned_grower_count: 0, planned_quantity: 0 }
+) {
+  const listingCount = listingRow.listing_count;
+  const requestCount = requestRow.request_count;
+  const committedQuantity = claimRow.confirmed_quantity + claimRow.completed_quantity;
+  const supplyQuantity = Math.max(0, listingRow.listed_quantity - committedQuantity);
+  const demandQuantity = requestRow.demand_quantity;
+  const resolvedClaims = claimRow.completed_count + claimRow.failed_count;
+  const fulfillmentRate =
+    resolvedClaims === 0 ? null : Number((claimRow.completed_count / resolvedClaims).toFixed(4));
+
+  return {
+    listingCount,
+    requestCount,
+    supplyQuantity,
+    demandQuantity,
+    scarcityScore: demandQuantity / (supplyQuantity + 1),
+    abundanceScore: supplyQuantity / (demandQuantity + 1),
+    signalPayload: {
+      listingCount,
+      requestCount,
+      windowDays,
+      claimCount: claimRow.claim_count,
+      completedClaimCount: claimRow.completed_count,
+      confirmedQuantity: claimRow.confirmed_quantity,
+      completedQuantity: claimRow.completed_quantity,
+      fulfillmentRate,
+      harvestCount: harvestRow.harvest_count,
+      harvestedQuantity: harvestRow.harvested_quantity,
+      plannedGrowerCount: plannedRow.planned_grower_count,
+      plannedQuantity: plannedRow.planned_quantity,
+    },
+  };
+}
+
+export async function recomputeAndUpsert(client, scope, windowDays, bucketStart) {
+  const now = new Date();
+  const windowStart = new Date(now.getTime() - windowDays * 86_400_000);
+  const expiresAt = new Date(now.getTime() + retentionDays(windowDays) * 86_400_000);
+  const likePattern = `${scope.geoBoundaryKey}%`;
+
+  const listingRow = (
+    await client.query(
+      `SELECT count(*)::int AS listing_count,
+              coalesce(sum(coalesce(quantity_total, quantity_remaining)), 0)::float AS listed_quantity
+       FROM surplus_listings
+       WHERE deleted_at IS NULL
+         AND listing_kind = 'produce'
+         AND status IN ('active', 'pending', 'claimed')
+         AND created_at >= $1
+         AND geo_key LIKE $2
+         AND ($3::uuid IS NULL OR crop_id = $3)`,
+      [windowStart, likePattern, scope.cropId]
+    )
+  ).rows[0];
+
+  const requestRow = (
+    await client.query(
+      `SELECT count(*)::int AS request_count,
+              coalesce(sum(quantity), 0)::float AS demand_quantity
+       FROM requests
+       WHERE deleted_at IS NULL
+         AND status = 'open'
+         AND created_at >= $1
+         AND geo_key LIKE $2
+         AND ($3::uuid IS NULL OR crop_id = $3)`,
+      [windowStart, likePattern, scope.cropId]
+    )
+  ).rows[0];
+
+  const claimRow = (
+    await client.query(
+      `SELECT count(*)::int AS claim_count,
+              count(*) FILTER (WHERE c.status = 'completed')::int AS completed_count,
+              count(*) FILTER (WHERE c.status IN ('cancelled', 'no_show'))::int AS failed_count,
+              coalesce(sum(c.quantity_claimed) FILTER (WHERE c.status = 'confirmed'), 0)::float
+                AS confirmed_quantity,
+              coalesce(sum(c.quantity_claimed) FILTER (WHERE c.status = 'completed'), 0)::float
+                AS completed_quantity
+       FROM claims c
+       JOIN surplus_listings l ON l.id = c.listing_id
+       WHERE l.deleted_at IS NULL
+         AND l.listing_kind = 'produce'
+         AND l.status IN ('active', 'pending', 'claimed')
+         AND l.created_at >= $1
+         AND l.geo_key LIKE $2
+         AND ($3::uuid IS NULL OR l.crop_id = $3)`,
+      [windowStart, likePattern, scope.cropId]
+    )
+  ).rows[0];
+
+  const harvestRow = (
+    await client.query(
+      `SELECT count(*)::int AS harvest_count,
+              coalesce(sum(quantity), 0)::float AS harvested_quantity
+       FROM harvests
+       WHERE harvested_on >= $1::date
+         AND geo_key LIKE $2
+         AND ($3::uuid IS NULL OR crop_id = $3)`,
+      [windowStart, likePattern, scope.cropId]
+    )
+  ).rows[0];
+
+  // Seasons run three months from season_starts_on; plans starting more
+  // than a year out are left for later recomputes.
+  const plannedRow = (
+    await client.query(
+      `SELECT count(DISTINCT user_id)::int AS planned_grower_count,
+              coalesce(sum(expected_quantity), 0)::float AS planned_quantity
+       FROM crop_plans
+       WHERE season_starts_on + interval '3 months' > $1::timestamptz
+         AND season_starts_on <= $1::timestamptz + interval '1 year'
+         AND geo_key LIKE $2
+         AND ($3::uuid IS NULL OR crop_id = $3)`,
+      [now, likePattern, scope.cropId]
+    )
+  ).rows[0];
+
+  const {
+    listingCount,
+    requestCount,
+    supplyQuantity,
+    demandQuantity,
+    scarcityScore,
+    abundanceScore,
+    signalPayload,
+  } = computeSignal(listingRow, requestRow, claimRow, windowDays, harvestRow, plannedRow);
+
+  await client.query(
+    `SELECT upsert_derived_supply_signal(
+       $1, $2, $3, $4, $5,
+       $6, $7, $8, $9,
+       $10, $11, $12::jsonb,
+       $13, $14
+     )`,
+    [
+      SCHEMA_VERSION,
+      scope.geoBoundaryKey,
+      windowDays,
+      bucketStart,
+      scope.cropId,
+      listingCount,
+      requestCount,
+      supplyQuantity,
+      demandQuantity,
+      scarcityScore,
+      abundanceScore,
+      JSON.stringify(signalPayload),
+      now,
+      expiresAt,
+    ]
+  );
+
+  return {
+    listingCount,
+    requestCount,
+    supplyQuantity,
+    demandQuantity,
+    scarcityScore,
+    abundanceScore,
+    computedAt: now,
+  };
+}
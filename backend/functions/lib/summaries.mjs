@@ -0,0 +1,100 @@
+// Derived feed AI summaries, mirrored from src/api/ai so the pre-generation
+// worker writes rows the API treats as ordinary cache hits. Keep the prompt,
+// template text, TTL, and snapshot shape in step with the Rust side.
+
+export const SUMMARY_TTL_HOURS = 6;
+
+// Prompts include at most this many signals, strongest scarcity first.
+const MAX_PROMPT_SIGNALS = 10;
+
+export const TEMPLATE_MODEL_ID = "mock.derived-signal-summarizer";
+
+export const SYSTEM_PROMPT =
+  "You summarize local produce supply and demand for a community " +
+  "garden app. Write two or three plain sentences for neighbors. Mention which crops are scarce or " +
+  "abundant when crop ids are given, and never invent numbers that are not in the data. When " +
+  "fulfillment data is given, suggest one concrete thing neighbors can do, such as growing an unmet " +
+  "crop or confirming pickups to cut no-shows.";
+
+function isFulfillmentEmpty(fulfillment) {
+  return fulfillment.resolvedClaimCount === 0 && fulfillment.topUnfulfilledCrops.length === 0;
+}
+
+function percent(rate) {
+  return rate == null ? "n/a" : `${(rate * 100).toFixed(0)}%`;
+}
+
+export function claimRate(count, resolved) {
+  return resolved > 0 ? count / resolved : null;
+}
+
+export function buildPrompt(geoBoundaryKey, windowDays, signals, fulfillment) {
+  const ranked = [...signals].sort((a, b) => b.scarcityScore - a.scarcityScore);
+
+  let prompt = `Area ${geoBoundaryKey}, last ${windowDays} days. Signals (crop, listings, requests, supply, demand, scarcity, abundance):\n`;
+  for (const signal of ranked.slice(0, MAX_PROMPT_SIGNALS)) {
+    prompt +=
+      `- ${signal.cropId ?? "all crops"}: ${signal.listingCount} listings, ${signal.requestCount} requests, ` +
+      `supply ${signal.supplyQuantity}, demand ${signal.demandQuantity}, ` +
+      `scarcity ${signal.scarcityScore.toFixed(2)}, abundance ${signal.abundanceScore.toFixed(2)}\n`;
+  }
+
+  if (!isFulfillmentEmpty(fulfillment)) {
+    prompt +=
+      `Claims: ${fulfillment.resolvedClaimCount} resolved, fulfillment rate ${percent(fulfillment.fulfillmentRate)}, ` +
+      `no-show rate ${percent(fulfillment.noShowRate)}.\n`;
+    if (fulfillment.topUnfulfilledCrops.length > 0) {
+      const crops = fulfillment.topUnfulfilledCrops.map(
+        (crop) => `${crop.cropName} (${crop.openRequestCount} open)`
+      );
+      prompt += `Top unfulfilled requests: ${crops.join(", ")}.\n`;
+    }
+  }
+  return prompt;
+}
+
+export function templateSummary(geoBoundaryKey, windowDays, signals, fulfillment) {
+  const strongest = signals.reduce(
+    (top, signal) => (top == null || signal.scarcityScore > top.scarcityScore ? signal : top),
+    null
+  );
+
+  let text = strongest
+    ? `Derived signal summary for ${geoBoundaryKey} (${windowDays}d): ${strongest.listingCount} listings, ` +
+      `${strongest.requestCount} requests, scarcity ${strongest.scarcityScore.toFixed(2)}, ` +
+      `abundance ${strongest.abundanceScore.toFixed(2)}.`
+    : `Derived signal summary for ${geoBoundaryKey} (${windowDays}d): no signal rows available.`;
+  if (fulfillment.fulfillmentRate != null) {
+    text += ` ${(fulfillment.fulfillmentRate * 100).toFixed(0)}% of resolved claims were picked up.`;
+  }
+  const unmet = fulfillment.topUnfulfilledCrops[0];
+  if (unmet) {
+    text += ` Most requested without a match: ${unmet.cropName} (${unmet.openRequestCount} open).`;
+  }
+  return text;
+}
+
+// A scope is worth generating when it has no live summary, when the live one
+// expires before the next run, or when signals were recomputed after it was
+// written and it is at least `minAgeMinutes` old.
+export function needsSummary(scope, now, { leadMinutes, minAgeMinutes }) {
+  if (!scope.summaryExpiresAt) return true;
+  if (scope.summaryExpiresAt.getTime() <= now.getTime() + leadMinutes * 60_000) return true;
+  return (
+    scope.signalsComputedAt != null &&
+    scope.signalsComputedAt > scope.summaryGeneratedAt &&
+    now.getTime() - scope.summaryGeneratedAt.getTime() >= minAgeMinutes * 60_000
+  );
+}
+
+// Token pricing matches TokenPricing::from_env; the template is always free.
+export function estimateUsd(provider, inputTokens, outputTokens, env = {}) {
+  if (provider === "template") return 0;
+  const rate = (value, fallback) => {
+    const parsed = Number.parseFloat(value);
+    return Number.isFinite(parsed) && parsed >= 0 ? parsed : fallback;
+  };
+  const inputPer1k = rate(env.AI_COST_PER_1K_INPUT_TOKENS_USD, 0.00006);
+  const outputPer1k = rate(env.AI_COST_PER_1K_OUTPUT_TOKENS_USD, 0.00024);
+  return (Math.max(inputTokens, 0) * inputPer1k + Math.max(outputTokens, 0) * outputPer1k) / 1000;
+}
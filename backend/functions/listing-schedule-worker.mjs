@@ -0,0 +1,210 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
+
+const DEFAULT_BATCH_SIZE = 200;
+const DEFAULT_MAX_BATCHES = 20;
+// PutEvents accepts at most 10 entries per call.
+const PUT_EVENTS_BATCH_SIZE = 10;
+
+const eventBridge = new EventBridgeClient({});
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(
+      overrides.batchSize ?? env.LISTING_SCHEDULE_BATCH_SIZE,
+      DEFAULT_BATCH_SIZE
+    ),
+    maxBatches: parsePositiveInt(
+      overrides.maxBatches ?? env.LISTING_SCHEDULE_MAX_BATCHES,
+      DEFAULT_MAX_BATCHES
+    ),
+  };
+}
+
+// ── sweeps ───────────────────────────────────────────────────────────────────
+
+// Scheduled listings whose publish_at has come go active. A grower who is
+// away gets the listing paused for their period instead, as if it had been
+// active when the period began, so it comes back when they do.
+const PUBLISH_DUE_SQL = `
+  WITH due AS (
+    SELECT l.id,
+           (SELECT a.id
+            FROM grower_away_periods a
+            WHERE a.user_id = l.user_id
+              AND a.started_at IS NOT NULL
+              AND a.ended_at IS NULL
+            LIMIT 1) AS away_period_id
+    FROM surplus_listings l
+    WHERE l.status = 'scheduled'::listing_status
+      AND l.publish_at <= $1
+      AND l.deleted_at IS NULL
+    ORDER BY l.publish_at
+    LIMIT $2
+    FOR UPDATE OF l SKIP LOCKED
+  )
+  UPDATE surplus_listings l
+  SET status = CASE WHEN d.away_period_id IS NULL
+                    THEN 'active'::listing_status
+                    ELSE 'paused'::listing_status END,
+      status_before_away = CASE WHEN d.away_period_id IS NULL
+                                THEN NULL
+                                ELSE 'active'::listing_status END,
+      away_period_id = d.away_period_id
+  FROM due d
+  WHERE l.id = d.id
+  RETURNING l.id, l.user_id, l.crop_id, l.status::text AS status, l.geo_key,
+            'scheduled' AS previous_status`;
+
+// Active and pending listings whose expire_at has passed expire, whatever
+// their availability window says. Claimed listings are left to the claim
+// flow.
+const EXPIRE_DUE_SQL = `
+  WITH due AS (
+    SELECT id, status::text AS previous_status
+    FROM surplus_listings
+    WHERE status IN ('active'::listing_status, 'pending'::listing_status)
+      AND expire_at <= $1
+      AND deleted_at IS NULL
+    ORDER BY expire_at
+    LIMIT $2
+    FOR UPDATE SKIP LOCKED
+  )
+  UPDATE surplus_listings l
+  SET status = 'expired'::listing_status
+  FROM due d
+  WHERE l.id = d.id
+  RETURNING l.id, l.user_id, l.crop_id, l.status::text AS status, l.geo_key,
+            d.previous_status`;
+
+// Publishing runs first so a listing whose whole schedule passed between runs
+// is published, and announced, before it expires.
+const SWEEPS = [
+  { name: "published", sql: PUBLISH_DUE_SQL, metric: "ListingsPublished" },
+  { name: "expired", sql: EXPIRE_DUE_SQL, metric: "ListingsExpired" },
+];
+
+// ── events ───────────────────────────────────────────────────────────────────
+
+// Same shape as the API's listing.status_changed, so alert and aggregation
+// workers treat a scheduled change like a grower's.
+function buildStatusChangedEntries(rows, correlationId, occurredAt) {
+  return rows.map((row) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "listing.status_changed",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      listingId: row.id,
+      userId: row.user_id,
+      cropId: row.crop_id ?? null,
+      status: row.status,
+      previousStatus: row.previous_status,
+      ...(row.geo_key ? { geoKey: row.geo_key } : {}),
+      correlationId,
+      occurredAt,
+    }),
+  }));
+}
+
+function chunk(items, size) {
+  const batches = [];
+  for (let i = 0; i < items.length; i += size) {
+    batches.push(items.slice(i, i + size));
+  }
+  return batches;
+}
+
+async function publishEntries(entries) {
+  let failed = 0;
+  for (const batch of chunk(entries, PUT_EVENTS_BATCH_SIZE)) {
+    const result = await eventBridge.send(new PutEventsCommand({ Entries: batch }));
+    failed += result.FailedEntryCount ?? 0;
+  }
+  return failed;
+}
+
+// Events go out after each batch commits; a failed publish is logged and
+// counted rather than retried, as with the API's best-effort events.
+async function runSweep(client, sweep, now, batchSize, maxBatches, publish) {
+  let affected = 0;
+  let failedEvents = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rows } = await client.query(sweep.sql, [now, batchSize]);
+    batches += 1;
+    affected += rows.length;
+    if (rows.length > 0) {
+      failedEvents += await publish(rows);
+    }
+    if (rows.length < batchSize) {
+      return { affected, failedEvents, batches, exhausted: true };
+    }
+  }
+
+  return { affected, failedEvents, batches, exhausted: false };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `listing-schedule-${Date.now()}`;
+  const config = resolveConfig(process.env, event.detail ?? {});
+  const now = new Date();
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  const publish = (rows) =>
+    publishEntries(buildStatusChangedEntries(rows, correlationId, new Date().toISOString()));
+
+  try {
+    for (const sweep of SWEEPS) {
+      const result = await runSweep(
+        client,
+        sweep,
+        now,
+        config.batchSize,
+        config.maxBatches,
+        publish
+      );
+
+      console.log(
+        JSON.stringify({
+          level: result.exhausted && result.failedEvents === 0 ? "INFO" : "WARN",
+          message: result.exhausted
+            ? "Finished listing schedule sweep"
+            : "Stopped listing schedule sweep at batch limit; remaining listings will be picked up next run",
+          correlationId,
+          sweep: sweep.name,
+          batches: result.batches,
+          failedEvents: result.failedEvents,
+          metricName: `listing_schedule.${sweep.name}`,
+          metricValue: result.affected,
+        })
+      );
+      emitMetrics(
+        "listing-schedule-worker",
+        { [sweep.metric]: result.affected, StatusEventsFailed: result.failedEvents },
+        { properties: { correlationId } }
+      );
+    }
+  } finally {
+    await client.end();
+  }
+}
@@ -0,0 +1,247 @@
+import pg from "pg";
+import { createHash } from "node:crypto";
+import { BedrockRuntimeClient, ConverseCommand } from "@aws-sdk/client-bedrock-runtime";
+import { emitMetrics } from "./lib/metrics.mjs";
+import {
+  MODEL_PROMPT,
+  classifyWithRules,
+  decideAction,
+  excerpt,
+  moderationText,
+  parseModelCategories,
+} from "./lib/moderation.mjs";
+
+const {
+  DATABASE_URL,
+  MODERATION_PROVIDER = "rules",
+  MODERATION_MODEL_ID = "amazon.nova-micro-v1:0",
+  MODERATION_TIMEOUT_MS = "3000",
+} = process.env;
+
+const bedrock = new BedrockRuntimeClient({});
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+function moderationSubject(detailType, detail) {
+  if (detailType?.startsWith("listing.") && detail.listingId) {
+    return { type: "listing", id: detail.listingId };
+  }
+  if (detailType === "message.created" && detail.messageId) {
+    return { type: "message", id: detail.messageId };
+  }
+  // A question and its answer are written by different people and held
+  // separately, so each is its own subject keyed by the question id.
+  if (detailType === "listing_question.created" && detail.questionId) {
+    return { type: "listing_question", id: detail.questionId };
+  }
+  if (detailType === "listing_question.answered" && detail.questionId) {
+    return { type: "listing_answer", id: detail.questionId };
+  }
+  return null;
+}
+
+// Where a hold is recorded for each subject that can be held.
+const HOLD_COLUMNS = {
+  listing: { table: "surplus_listings", column: "moderation_held_at" },
+  listing_question: { table: "listing_questions", column: "moderation_held_at" },
+  listing_answer: { table: "listing_questions", column: "answer_held_at" },
+};
+
+function contentHash(text) {
+  return createHash("sha256").update(text).digest("hex");
+}
+
+// ── providers ────────────────────────────────────────────────────────────────
+
+async function classifyWithBedrock(text) {
+  const result = await bedrock.send(
+    new ConverseCommand({
+      modelId: MODERATION_MODEL_ID,
+      system: [{ text: MODEL_PROMPT }],
+      messages: [{ role: "user", content: [{ text }] }],
+      inferenceConfig: { maxTokens: 100, temperature: 0 },
+    }),
+    { abortSignal: AbortSignal.timeout(Number(MODERATION_TIMEOUT_MS)) }
+  );
+  const reply = result.output?.message?.content?.find((block) => block.text)?.text;
+  return parseModelCategories(reply);
+}
+
+// Returns the categories and which provider produced them. A failing model
+// never blocks moderation: rules still run.
+async function classify(text, correlationId) {
+  if (MODERATION_PROVIDER === "bedrock") {
+    try {
+      return { categories: await classifyWithBedrock(text), provider: "bedrock" };
+    } catch (error) {
+      console.log(
+        JSON.stringify({
+          level: "WARN",
+          message: "Moderation model failed; using rules",
+          error: error.message,
+          correlationId,
+        })
+      );
+    }
+  }
+  return { categories: classifyWithRules(text), provider: "rules" };
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+async function loadSubject(client, subject) {
+  if (subject.type === "listing") {
+    const { rows } = await client.query(
+      `select user_id, title, pickup_notes
+       from surplus_listings
+       where id = $1 and deleted_at is null`,
+      [subject.id]
+    );
+    const row = rows[0];
+    return row ? { userId: row.user_id, text: moderationText([row.title, row.pickup_notes]) } : null;
+  }
+
+  if (subject.type === "listing_question" || subject.type === "listing_answer") {
+    const { rows } = await client.query(
+      `select q.asker_id, q.body, q.answer, l.user_id as owner_id
+       from listing_questions q
+       join surplus_listings l on l.id = q.listing_id
+       where q.id = $1`,
+      [subject.id]
+    );
+    const row = rows[0];
+    if (!row) return null;
+    return subject.type === "listing_question"
+      ? { userId: row.asker_id, text: moderationText([row.body]) }
+      : { userId: row.owner_id, text: moderationText([row.answer]) };
+  }
+
+  const { rows } = await client.query(`select sender_id, body from messages where id = $1`, [subject.id]);
+  const row = rows[0];
+  return row ? { userId: row.sender_id, text: moderationText([row.body]) } : null;
+}
+
+async function recordFinding(client, subject, finding) {
+  await client.query(
+    `insert into moderation_queue
+       (subject_type, subject_id, user_id, categories, action, provider, excerpt, content_hash)
+     values ($1, $2, $3, $4, $5, $6, $7, $8)
+     on conflict (subject_type, subject_id, content_hash) do nothing`,
+    [
+      subject.type,
+      subject.id,
+      finding.userId,
+      finding.categories,
+      finding.action,
+      finding.provider,
+      excerpt(finding.text),
+      finding.hash,
+    ]
+  );
+  const hold = HOLD_COLUMNS[subject.type];
+  if (finding.action === "hold" && hold) {
+    await client.query(
+      `update ${hold.table}
+       set ${hold.column} = coalesce(${hold.column}, now())
+       where id = $1`,
+      [subject.id]
+    );
+  }
+}
+
+// A clean edit supersedes earlier automated findings and lifts their hold,
+// unless an admin hid the listing by hand. Questions cannot be edited, so in
+// practice only listings and answers are ever cleared this way.
+async function clearFindings(client, subject) {
+  await client.query(
+    `update moderation_queue
+     set status = 'superseded', resolved_at = now()
+     where subject_type = $1 and subject_id = $2 and status = 'pending'`,
+    [subject.type, subject.id]
+  );
+  if (subject.type === "listing") {
+    await client.query(
+      `update surplus_listings set moderation_held_at = null
+       where id = $1 and moderation_held_at is not null and admin_hidden_at is null`,
+      [subject.id]
+    );
+  }
+  if (subject.type === "listing_answer") {
+    await client.query(
+      `update listing_questions set answer_held_at = null
+       where id = $1 and answer_held_at is not null`,
+      [subject.id]
+    );
+  }
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+  const subject = moderationSubject(event["detail-type"], detail);
+
+  if (!subject) {
+    return { statusCode: 200, body: "skipped: not a listing, question or message event" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let outcome = "missing";
+  let result = { categories: [], provider: "none" };
+  try {
+    const loaded = await loadSubject(client, subject);
+    if (loaded?.text) {
+      result = await classify(loaded.text, correlationId);
+      const action = decideAction(subject.type, result.categories);
+
+      await client.query("begin");
+      try {
+        if (action) {
+          await recordFinding(client, subject, {
+            ...loaded,
+            ...result,
+            action,
+            hash: contentHash(loaded.text),
+          });
+          outcome = action;
+        } else {
+          await clearFindings(client, subject);
+          outcome = "clean";
+        }
+        await client.query("commit");
+      } catch (error) {
+        await client.query("rollback");
+        throw error;
+      }
+    }
+  } finally {
+    await client.end();
+  }
+
+  console.log(
+    JSON.stringify({
+      level: outcome === "hold" || outcome === "flag" ? "WARN" : "INFO",
+      message: "Moderated content",
+      subjectType: subject.type,
+      subjectId: subject.id,
+      outcome,
+      categories: result.categories,
+      provider: result.provider,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "moderation-worker",
+    {
+      ContentFlagged: outcome === "flag" ? 1 : 0,
+      ContentHeld: outcome === "hold" ? 1 : 0,
+      ContentClean: outcome === "clean" ? 1 : 0,
+    },
+    { properties: { correlationId, subjectType: subject.type, provider: result.provider } }
+  );
+
+  return { statusCode: 200, body: outcome };
+}
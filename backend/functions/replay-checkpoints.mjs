@@ -0,0 +1,71 @@
+#!/usr/bin/env node
+// Inspect or reset derived pipeline replay checkpoints.
+//
+//   node functions/replay-checkpoints.mjs --inspect [--pipeline derived_supply_signals] [--mode full]
+//   node functions/replay-checkpoints.mjs --reset --mode full
+import pg from "pg";
+import { pathToFileURL } from "node:url";
+
+const DEFAULT_PIPELINE = "derived_supply_signals";
+
+function parseArgs(argv) {
+  const args = { action: null, pipeline: DEFAULT_PIPELINE, mode: null };
+
+  for (let i = 0; i < argv.length; i += 1) {
+    const arg = argv[i];
+    if (arg === "--inspect" || arg === "--reset") {
+      if (args.action) throw new Error("Use only one of --inspect or --reset");
+      args.action = arg.slice(2);
+    } else if (arg === "--pipeline" || arg === "--mode") {
+      const value = argv[i + 1];
+      if (!value || value.startsWith("--")) throw new Error(`${arg} requires a value`);
+      args[arg.slice(2)] = value;
+      i += 1;
+    } else {
+      throw new Error(`Unknown argument: ${arg}`);
+    }
+  }
+
+  if (!args.action) throw new Error("One of --inspect or --reset is required");
+  if (args.action === "reset" && !args.mode) throw new Error("--reset requires --mode");
+  return args;
+}
+
+async function main() {
+  const args = parseArgs(process.argv.slice(2));
+  const client = new pg.Client({
+    connectionString: process.env.DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    if (args.action === "inspect") {
+      const { rows } = await client.query(
+        `SELECT pipeline_name, mode, cursor, run_started_at, processed_scopes, status, updated_at
+         FROM pipeline_checkpoints
+         WHERE pipeline_name = $1 AND ($2::text IS NULL OR mode = $2)
+         ORDER BY mode`,
+        [args.pipeline, args.mode]
+      );
+      console.log(JSON.stringify(rows, null, 2));
+    } else {
+      const { rowCount } = await client.query(
+        "DELETE FROM pipeline_checkpoints WHERE pipeline_name = $1 AND mode = $2",
+        [args.pipeline, args.mode]
+      );
+      console.log(`Removed ${rowCount} checkpoint(s) for ${args.pipeline}/${args.mode}`);
+    }
+  } finally {
+    await client.end();
+  }
+}
+
+if (import.meta.url === pathToFileURL(process.argv[1] ?? "").href) {
+  main().catch((error) => {
+    console.error(error.message);
+    process.exit(1);
+  });
+}
+
+export { parseArgs };
@@ -0,0 +1,137 @@
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+import { planPurge, purgeBatchSql, resolveRetentionConfig, selectPolicies } from "./lib/retention.mjs";
+
+const { DATABASE_URL } = process.env;
+
+// ── purge ────────────────────────────────────────────────────────────────────
+
+async function tableExists(client, table) {
+  const { rows } = await client.query("SELECT to_regclass($1) IS NOT NULL AS present", [table]);
+  return rows[0]?.present === true;
+}
+
+async function deleteBatch(client, plan, batchSize) {
+  const sql = purgeBatchSql(plan);
+  if (!plan.guarded) {
+    const { rowCount } = await client.query(sql, [plan.cutoff, batchSize]);
+    return rowCount;
+  }
+
+  // set local keeps the purge flag scoped to this one transaction.
+  await client.query("BEGIN");
+  try {
+    await client.query("SET LOCAL app.retention_purge = 'on'");
+    const { rowCount } = await client.query(sql, [plan.cutoff, batchSize]);
+    await client.query("COMMIT");
+    return rowCount;
+  } catch (error) {
+    await client.query("ROLLBACK");
+    throw error;
+  }
+}
+
+async function purge(client, plan, batchSize, maxBatches) {
+  let removed = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const rowCount = await deleteBatch(client, plan, batchSize);
+    batches += 1;
+    removed += rowCount;
+    if (rowCount < batchSize) {
+      return { removed, batches, exhausted: true };
+    }
+  }
+
+  return { removed, batches, exhausted: false };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `retention-${Date.now()}`;
+  const config = resolveRetentionConfig(process.env, event.detail ?? {});
+  const now = new Date();
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const { rows } = await client.query(
+      `SELECT entity, table_name, timestamp_column, retention_days, enabled
+       FROM retention_policies
+       ORDER BY entity`
+    );
+
+    for (const policy of selectPolicies(rows, config.entities)) {
+      const plan = planPurge(policy, now);
+      if (!plan.skip && !(await tableExists(client, plan.table))) {
+        plan.skip = "missing_table";
+      }
+      if (plan.skip) {
+        console.log(
+          JSON.stringify({
+            level: "WARN",
+            message: "Skipped retention policy",
+            correlationId,
+            entity: plan.entity,
+            reason: plan.skip,
+          })
+        );
+        continue;
+      }
+
+      const startedAt = Date.now();
+      let result;
+      try {
+        result = await purge(client, plan, config.batchSize, config.maxBatches);
+      } catch (error) {
+        // One broken policy must not stop the others from running.
+        console.log(
+          JSON.stringify({
+            level: "ERROR",
+            message: "Retention purge failed",
+            correlationId,
+            entity: plan.entity,
+            error: error.message,
+          })
+        );
+        emitMetrics("retention-worker", { PurgeFailures: 1 }, {
+          dimensions: { Entity: plan.entity },
+          properties: { correlationId },
+        });
+        continue;
+      }
+
+      console.log(
+        JSON.stringify({
+          level: result.exhausted ? "INFO" : "WARN",
+          message: result.exhausted
+            ? "Purged rows past retention"
+            : "Stopped purge at batch limit; remaining rows will be picked up next run",
+          correlationId,
+          entity: plan.entity,
+          table: plan.table,
+          retentionDays: plan.retentionDays,
+          cutoff: plan.cutoff.toISOString(),
+          batches: result.batches,
+        })
+      );
+      emitMetrics(
+        "retention-worker",
+        { RowsRemoved: result.removed, PurgeDuration: Date.now() - startedAt },
+        {
+          units: { PurgeDuration: "Milliseconds" },
+          dimensions: { Entity: plan.entity },
+          properties: { correlationId, exhausted: result.exhausted },
+        }
+      );
+    }
+  } finally {
+    await client.end();
+  }
+}
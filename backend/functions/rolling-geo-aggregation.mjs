@@ -1,20 +1,38 @@
 import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { detectAnomalies, loadBaseline, recordAnomaly } from "./lib/anomalies.mjs";
+import { emitMetrics } from "./lib/metrics.mjs";
+import {
+  SUPPORTED_WINDOWS_DAYS,
+  computeBucketStart,
+  expandGeoScopes,
+  recomputeAndUpsert,
+} from "./lib/signals.mjs";
 
-const { DATABASE_URL } = process.env;
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
 
-const SUPPORTED_WINDOWS_DAYS = [7, 14, 30];
-const GEO_PRECISIONS = [4, 5, 6];
-const SCHEMA_VERSION = 1;
+const eventBridge = new EventBridgeClient({});
+
+// Week-over-week comparisons only make sense on the shortest window.
+const ANOMALY_WINDOW_DAYS = 7;
 
 // ── event parsing ────────────────────────────────────────────────────────────
 
+// Schema v1 events carry geoKey/cropId so the worker can skip the re-query.
+// Older (unversioned) events fall back to loading scope from the database.
+function payloadScope(detail) {
+  if (!detail.schemaVersion || !detail.geoKey || !detail.cropId) return null;
+  return { geoKey: detail.geoKey, cropId: detail.cropId };
+}
+
 function parseEvent(detailType, detail) {
   switch (detailType) {
     case "listing.created":
     case "listing.updated":
+    case "listing.status_changed":
       if (!detail.listingId) throw new Error(`Missing listingId in ${detailType}`);
       return {
-        domain: { type: "listing", listingId: detail.listingId },
+        domain: { type: "listing", listingId: detail.listingId, scope: payloadScope(detail) },
         occurredAt: detail.occurredAt ?? new Date().toISOString(),
         correlationId: detail.correlationId ?? "unknown-correlation-id",
       };
@@ -22,7 +40,7 @@ function parseEvent(detailType, detail) {
     case "request.updated":
       if (!detail.requestId) throw new Error(`Missing requestId in ${detailType}`);
       return {
-        domain: { type: "request", requestId: detail.requestId },
+        domain: { type: "request", requestId: detail.requestId, scope: payloadScope(detail) },
         occurredAt: detail.occurredAt ?? new Date().toISOString(),
         correlationId: detail.correlationId ?? "unknown-correlation-id",
       };
@@ -42,32 +60,6 @@ function parseEvent(detailType, detail) {
   }
 }
 
-// ── geo helpers ──────────────────────────────────────────────────────────────
-
-function geoPrefixes(geoKey) {
-  const normalized = geoKey.trim().toLowerCase();
-  return GEO_PRECISIONS.filter((p) => normalized.length >= p).map((p) =>
-    normalized.slice(0, p)
-  );
-}
-
-function expandGeoScopes(sourcePairs) {
-  const seen = new Set();
-  const scopes = [];
-  for (const { geoKey, cropId } of sourcePairs) {
-    for (const prefix of geoPrefixes(geoKey)) {
-      for (const cid of [cropId, null]) {
-        const key = `${prefix}|${cid ?? ""}`;
-        if (!seen.has(key)) {
-          seen.add(key);
-          scopes.push({ geoBoundaryKey: prefix, cropId: cid });
-        }
-      }
-    }
-  }
-  return scopes;
-}
-
 // ── scope resolution ─────────────────────────────────────────────────────────
 
 async function loadListingScope(client, listingId) {
@@ -93,10 +85,10 @@ async function loadRequestScope(client, requestId) {
 async function resolveScopes(client, domain) {
   const pairs = [];
   if (domain.type === "listing") {
-    const s = await loadListingScope(client, domain.listingId);
+    const s = domain.scope ?? (await loadListingScope(client, domain.listingId));
     if (s) pairs.push(s);
   } else if (domain.type === "request") {
-    const s = await loadRequestScope(client, domain.requestId);
+    const s = domain.scope ?? (await loadRequestScope(client, domain.requestId));
     if (s) pairs.push(s);
   } else if (domain.type === "claim") {
     if (domain.listingId) {
@@ -111,93 +103,58 @@ async function resolveScopes(client, domain) {
   return expandGeoScopes(pairs);
 }
 
-// ── aggregation ──────────────────────────────────────────────────────────────
-
-function computeBucketStart(occurredAt) {
-  const ts = Math.floor(new Date(occurredAt).getTime() / 1000);
-  const bucket = 5 * 60;
-  const floored = ts - ((ts % bucket) + bucket) % bucket; // euclid mod
-  return new Date(floored * 1000);
-}
-
-function retentionDays(windowDays) {
-  if (windowDays === 7) return 35;
-  if (windowDays === 14) return 49;
-  return 90;
-}
-
-async function recomputeAndUpsert(client, scope, windowDays, bucketStart) {
-  const now = new Date();
-  const windowStart = new Date(now.getTime() - windowDays * 86_400_000);
-  const expiresAt = new Date(now.getTime() + retentionDays(windowDays) * 86_400_000);
-  const likePattern = `${scope.geoBoundaryKey}%`;
-
-  const listingRow = (
-    await client.query(
-      `SELECT count(*)::int AS listing_count,
-              coalesce(sum(quantity_remaining), 0)::float AS supply_quantity
-       FROM surplus_listings
-       WHERE deleted_at IS NULL
-         AND status IN ('active', 'pending', 'claimed')
-         AND created_at >= $1
-         AND geo_key LIKE $2
-         AND ($3::uuid IS NULL OR crop_id = $3)`,
-      [windowStart, likePattern, scope.cropId]
-    )
-  ).rows[0];
-
-  const requestRow = (
-    await client.query(
-      `SELECT count(*)::int AS request_count,
-              coalesce(sum(quantity), 0)::float AS demand_quantity
-       FROM requests
-       WHERE deleted_at IS NULL
-         AND status = 'open'
-         AND created_at >= $1
-         AND geo_key LIKE $2
-         AND ($3::uuid IS NULL OR crop_id = $3)`,
-      [windowStart, likePattern, scope.cropId]
-    )
-  ).rows[0];
-
-  const listingCount = listingRow.listing_count;
-  const requestCount = requestRow.request_count;
-  const supplyQuantity = listingRow.supply_quantity;
-  const demandQuantity = requestRow.demand_quantity;
-  const scarcityScore = demandQuantity / (supplyQuantity + 1);
-  const abundanceScore = supplyQuantity / (demandQuantity + 1);
+// ── anomalies ────────────────────────────────────────────────────────────────
+
+async function checkForAnomalies(client, scope, windowDays, current, correlationId) {
+  const baseline = await loadBaseline(client, scope, windowDays, current.computedAt);
+  const anomalies = detectAnomalies(baseline, current);
+  const entries = [];
+
+  for (const anomaly of anomalies) {
+    const isNew = await recordAnomaly(client, scope, windowDays, anomaly, current.computedAt);
+    if (!isNew) continue;
+    entries.push({
+      EventBusName: EVENT_BUS_NAME,
+      Source: "community-garden.workers",
+      DetailType: "signal.anomaly",
+      Detail: JSON.stringify({
+        schemaVersion: 1,
+        geoBoundaryKey: scope.geoBoundaryKey,
+        cropId: scope.cropId,
+        windowDays,
+        ...anomaly,
+        correlationId,
+        occurredAt: current.computedAt.toISOString(),
+      }),
+    });
+  }
 
-  const signalPayload = JSON.stringify({ listingCount, requestCount, windowDays });
+  if (entries.length === 0) return;
 
-  await client.query(
-    `SELECT upsert_derived_supply_signal(
-       $1, $2, $3, $4, $5,
-       $6, $7, $8, $9,
-       $10, $11, $12::jsonb,
-       $13, $14
-     )`,
-    [
-      SCHEMA_VERSION,
-      scope.geoBoundaryKey,
-      windowDays,
-      bucketStart,
-      scope.cropId,
-      listingCount,
-      requestCount,
-      supplyQuantity,
-      demandQuantity,
-      scarcityScore,
-      abundanceScore,
-      signalPayload,
-      now,
-      expiresAt,
-    ]
-  );
+  try {
+    await eventBridge.send(new PutEventsCommand({ Entries: entries }));
+  } catch (error) {
+    emitMetrics(
+      "rolling-geo-aggregation",
+      { EventEmitFailures: 1 },
+      { properties: { correlationId } }
+    );
+    console.log(
+      JSON.stringify({
+        level: "ERROR",
+        message: "Failed to emit signal.anomaly events",
+        correlationId,
+        geoBoundaryKey: scope.geoBoundaryKey,
+        error: error.message,
+      })
+    );
+  }
 }
 
 // ── handler ──────────────────────────────────────────────────────────────────
 
 export async function handler(event) {
+  const startedAt = Date.now();
   const detailType = event["detail-type"];
   const { domain, occurredAt, correlationId } = parseEvent(detailType, event.detail);
 
@@ -236,10 +193,15 @@ export async function handler(event) {
     }
 
     const bucketStart = computeBucketStart(occurredAt);
+    let recomputeCount = 0;
 
     for (const scope of scopes) {
       for (const windowDays of SUPPORTED_WINDOWS_DAYS) {
-        await recomputeAndUpsert(client, scope, windowDays, bucketStart);
+        const current = await recomputeAndUpsert(client, scope, windowDays, bucketStart);
+        recomputeCount += 1;
+        if (windowDays === ANOMALY_WINDOW_DAYS) {
+          await checkForAnomalies(client, scope, windowDays, current, correlationId);
+        }
       }
     }
 
@@ -252,6 +214,20 @@ export async function handler(event) {
         processingLagSeconds: lagSeconds,
       })
     );
+
+    emitMetrics(
+      "rolling-geo-aggregation",
+      {
+        Invocations: 1,
+        RecomputeCount: recomputeCount,
+        ProcessingLag: lagSeconds,
+        Latency: Date.now() - startedAt,
+      },
+      {
+        units: { ProcessingLag: "Seconds", Latency: "Milliseconds" },
+        properties: { correlationId, detailType },
+      }
+    );
   } finally {
     await client.end();
   }
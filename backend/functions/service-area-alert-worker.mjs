@@ -0,0 +1,134 @@
+import pg from "pg";
+import { EventBridgeClient, PutEventsCommand } from "@aws-sdk/client-eventbridge";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL, EVENT_BUS_NAME = "default" } = process.env;
+
+// PutEvents accepts at most 10 entries per call.
+const PUT_EVENTS_BATCH_SIZE = 10;
+
+const eventBridge = new EventBridgeClient({});
+
+// ── pure logic ───────────────────────────────────────────────────────────────
+
+// A listing without a geo key has no place to match against service areas.
+function shouldAlert(detail) {
+  return (
+    Boolean(detail.listingId && detail.userId && detail.geoKey) && detail.status === "active"
+  );
+}
+
+// Every prefix of the geo key, shortest first. Service areas are stored as
+// covering cells, so an area matches when one of its cells is a prefix.
+function geoPrefixes(geoKey) {
+  const key = String(geoKey).toLowerCase();
+  const prefixes = [];
+  for (let length = 1; length <= key.length; length++) {
+    prefixes.push(key.slice(0, length));
+  }
+  return prefixes;
+}
+
+// One event per organization, listing every one of its areas that matched.
+// Routing ids only; webhook and notification consumers read the listing
+// themselves.
+function buildAlertEntries(detail, matches, occurredAt) {
+  return matches.map((match) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "organization.service_area_listing",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      organizationId: match.organizationId,
+      serviceAreaIds: match.serviceAreaIds,
+      listingId: detail.listingId,
+      growerId: detail.userId,
+      cropId: detail.cropId ?? null,
+      geoKey: detail.geoKey,
+      correlationId: detail.correlationId ?? "unknown",
+      occurredAt,
+    }),
+  }));
+}
+
+function chunk(items, size) {
+  const batches = [];
+  for (let i = 0; i < items.length; i += size) {
+    batches.push(items.slice(i, i + size));
+  }
+  return batches;
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+// Organizations never hear about their own listings.
+async function loadMatchingAreas(client, geoKey, growerId) {
+  const { rows } = await client.query(
+    `select a.organization_id, array_agg(distinct a.id) as service_area_ids
+     from organization_service_area_cells c
+     join organization_service_areas a on a.id = c.service_area_id
+     join organizations o on o.id = a.organization_id
+     where o.deleted_at is null
+       and o.service_user_id <> $2
+       and c.geo_prefix = any($1::text[])
+     group by a.organization_id
+     order by a.organization_id`,
+    [geoPrefixes(geoKey), growerId]
+  );
+  return rows.map((row) => ({
+    organizationId: row.organization_id,
+    serviceAreaIds: row.service_area_ids,
+  }));
+}
+
+async function publishAlerts(entries) {
+  let failed = 0;
+  for (const batch of chunk(entries, PUT_EVENTS_BATCH_SIZE)) {
+    const result = await eventBridge.send(new PutEventsCommand({ Entries: batch }));
+    failed += result.FailedEntryCount ?? 0;
+  }
+  return failed;
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event) {
+  const detail = event.detail ?? {};
+  const correlationId = detail.correlationId ?? "unknown";
+
+  if (!shouldAlert(detail)) {
+    return { statusCode: 200, body: "skipped: not an active listing with a location" };
+  }
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  let matches;
+  try {
+    matches = await loadMatchingAreas(client, detail.geoKey, detail.userId);
+  } finally {
+    await client.end();
+  }
+
+  const entries = buildAlertEntries(detail, matches, new Date().toISOString());
+  const failed = entries.length > 0 ? await publishAlerts(entries) : 0;
+
+  console.log(
+    JSON.stringify({
+      level: failed > 0 ? "WARN" : "INFO",
+      message: "Published service area listing alerts",
+      listingId: detail.listingId,
+      geoKey: detail.geoKey,
+      organizationCount: entries.length,
+      failedCount: failed,
+      correlationId,
+    })
+  );
+  emitMetrics(
+    "service-area-alert-worker",
+    { AlertsPublished: entries.length - failed, AlertsFailed: failed },
+    { properties: { correlationId, listingId: detail.listingId } }
+  );
+
+  return { statusCode: 200, body: "ok" };
+}
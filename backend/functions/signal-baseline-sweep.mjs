@@ -0,0 +1,107 @@
+import pg from "pg";
+import { SUPPORTED_WINDOWS_DAYS, computeBucketStart, recomputeAndUpsert } from "./lib/signals.mjs";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const DEFAULT_STALE_AFTER_MINUTES = 6 * 60;
+const DEFAULT_MAX_SCOPES = 500;
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function resolveSweepConfig(env, overrides = {}) {
+  const staleAfterMinutes = Number.parseInt(
+    String(overrides.staleAfterMinutes ?? env.SIGNAL_STALE_AFTER_MINUTES ?? DEFAULT_STALE_AFTER_MINUTES),
+    10
+  );
+  const maxScopes = Number.parseInt(
+    String(overrides.maxScopes ?? env.SIGNAL_SWEEP_MAX_SCOPES ?? DEFAULT_MAX_SCOPES),
+    10
+  );
+
+  return {
+    staleAfterMinutes:
+      Number.isInteger(staleAfterMinutes) && staleAfterMinutes > 0
+        ? staleAfterMinutes
+        : DEFAULT_STALE_AFTER_MINUTES,
+    maxScopes: Number.isInteger(maxScopes) && maxScopes > 0 ? maxScopes : DEFAULT_MAX_SCOPES,
+  };
+}
+
+// ── stale scope selection ────────────────────────────────────────────────────
+
+// Busiest feed prefixes first; a stale 6-char scope inherits the access count of
+// the 4-char feed prefix it rolls up into, since the feed reads by 4-char prefix.
+async function loadStaleScopes(client, staleBefore, maxScopes) {
+  const { rows } = await client.query(
+    `WITH latest AS (
+       SELECT DISTINCT ON (geo_boundary_key, crop_scope_id)
+              geo_boundary_key, crop_id, computed_at
+       FROM derived_supply_signals
+       WHERE schema_version = 1
+       ORDER BY geo_boundary_key, crop_scope_id, computed_at DESC, id DESC
+     ),
+     access AS (
+       SELECT geo_boundary_key, sum(access_count)::bigint AS access_count
+       FROM feed_geo_access
+       GROUP BY geo_boundary_key
+     )
+     SELECT l.geo_boundary_key, l.crop_id, coalesce(a.access_count, 0)::bigint AS access_count
+     FROM latest l
+     LEFT JOIN access a ON a.geo_boundary_key = left(l.geo_boundary_key, 4)
+     WHERE l.computed_at < $1
+     ORDER BY access_count DESC, l.computed_at ASC
+     LIMIT $2`,
+    [staleBefore, maxScopes]
+  );
+  return rows.map((row) => ({
+    geoBoundaryKey: row.geo_boundary_key,
+    cropId: row.crop_id,
+    accessCount: Number(row.access_count),
+  }));
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `signal-sweep-${Date.now()}`;
+  const config = resolveSweepConfig(process.env, event.detail ?? {});
+  const now = new Date();
+  const staleBefore = new Date(now.getTime() - config.staleAfterMinutes * 60_000);
+  const bucketStart = computeBucketStart(now.toISOString());
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const scopes = await loadStaleScopes(client, staleBefore, config.maxScopes);
+
+    for (const scope of scopes) {
+      for (const windowDays of SUPPORTED_WINDOWS_DAYS) {
+        await recomputeAndUpsert(client, scope, windowDays, bucketStart);
+      }
+    }
+
+    console.log(
+      JSON.stringify({
+        level: "INFO",
+        message: "Refreshed stale derived signal scopes",
+        correlationId,
+        staleBefore: staleBefore.toISOString(),
+        maxScopes: config.maxScopes,
+        metricName: "signal_baseline_sweep.scopes_refreshed",
+        metricValue: scopes.length,
+      })
+    );
+    emitMetrics(
+      "signal-baseline-sweep",
+      { ScopesRefreshed: scopes.length },
+      { properties: { correlationId } }
+    );
+  } finally {
+    await client.end();
+  }
+}
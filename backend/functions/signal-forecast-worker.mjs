@@ -0,0 +1,194 @@
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const SCHEMA_VERSION = 1;
+const FORECAST_GEO_PRECISION = 4;
+const HISTORY_DAYS = 56;
+const MIN_SAMPLES = 14;
+const HORIZONS_DAYS = [7, 14];
+const FORECAST_TTL_DAYS = 2;
+const MODEL = "linear_trend_weekday_seasonal_v1";
+
+// ── model ────────────────────────────────────────────────────────────────────
+
+// Least-squares trend over the daily series plus an additive day-of-week index
+// (mean residual per weekday). Good enough to show direction a week or two out
+// without pulling in a stats dependency.
+function fitSeasonalTrend(points) {
+  const n = points.length;
+  // Days since the first sample, so gaps in the history don't skew the slope.
+  const xs = points.map((p) => Math.round((p.date - points[0].date) / 86_400_000));
+  const meanX = xs.reduce((a, b) => a + b, 0) / n;
+  const meanY = points.reduce((a, p) => a + p.value, 0) / n;
+
+  let num = 0;
+  let den = 0;
+  for (let i = 0; i < n; i += 1) {
+    num += (xs[i] - meanX) * (points[i].value - meanY);
+    den += (xs[i] - meanX) ** 2;
+  }
+  const slope = den === 0 ? 0 : num / den;
+  const intercept = meanY - slope * meanX;
+
+  const residuals = Array.from({ length: 7 }, () => []);
+  points.forEach((p, i) => {
+    residuals[p.date.getUTCDay()].push(p.value - (intercept + slope * xs[i]));
+  });
+  const seasonal = residuals.map((r) => (r.length === 0 ? 0 : r.reduce((a, b) => a + b, 0) / r.length));
+
+  return { slope, intercept, seasonal, lastIndex: xs[n - 1], lastDate: points[n - 1].date };
+}
+
+function project(model, horizonDays) {
+  const target = new Date(model.lastDate.getTime() + horizonDays * 86_400_000);
+  const value =
+    model.intercept + model.slope * (model.lastIndex + horizonDays) + model.seasonal[target.getUTCDay()];
+  return { targetDate: target, value: Math.max(0, Number(value.toFixed(4))) };
+}
+
+function forecastScope(history, horizons = HORIZONS_DAYS) {
+  if (history.length < MIN_SAMPLES) return [];
+
+  const scarcity = fitSeasonalTrend(history.map((h) => ({ date: h.date, value: h.scarcity })));
+  const abundance = fitSeasonalTrend(history.map((h) => ({ date: h.date, value: h.abundance })));
+
+  return horizons.map((horizonDays) => {
+    const s = project(scarcity, horizonDays);
+    const a = project(abundance, horizonDays);
+    return {
+      horizonDays,
+      targetDate: s.targetDate,
+      projectedScarcity: s.value,
+      projectedAbundance: a.value,
+      sampleCount: history.length,
+    };
+  });
+}
+
+function groupHistory(rows) {
+  const scopes = new Map();
+  for (const row of rows) {
+    const key = `${row.geo_boundary_key}|${row.crop_id ?? ""}|${row.window_days}`;
+    if (!scopes.has(key)) {
+      scopes.set(key, {
+        geoBoundaryKey: row.geo_boundary_key,
+        cropId: row.crop_id,
+        windowDays: row.window_days,
+        history: [],
+      });
+    }
+    scopes.get(key).history.push({
+      date: new Date(row.day),
+      scarcity: Number(row.scarcity_score),
+      abundance: Number(row.abundance_score),
+    });
+  }
+  return [...scopes.values()];
+}
+
+// ── persistence ──────────────────────────────────────────────────────────────
+
+async function loadDailyHistory(client, since) {
+  const { rows } = await client.query(
+    `SELECT DISTINCT ON (geo_boundary_key, crop_scope_id, window_days, date_trunc('day', computed_at))
+            geo_boundary_key, crop_id, window_days::int AS window_days,
+            date_trunc('day', computed_at) AS day,
+            scarcity_score::float8 AS scarcity_score,
+            abundance_score::float8 AS abundance_score
+     FROM derived_supply_signals
+     WHERE schema_version = $1
+       AND geo_precision = $2
+       AND computed_at >= $3
+     ORDER BY geo_boundary_key, crop_scope_id, window_days, date_trunc('day', computed_at),
+              computed_at DESC, id DESC`,
+    [SCHEMA_VERSION, FORECAST_GEO_PRECISION, since]
+  );
+  return rows;
+}
+
+async function upsertForecast(client, scope, forecast, now, expiresAt) {
+  await client.query(
+    `INSERT INTO derived_signal_forecasts (
+       schema_version, geo_boundary_key, crop_id, window_days, horizon_days, target_date,
+       projected_scarcity_score, projected_abundance_score, model, sample_count,
+       generated_at, expires_at
+     )
+     VALUES ($1, $2, $3, $4, $5, $6::date, $7, $8, $9, $10, $11, $12)
+     ON CONFLICT (schema_version, geo_boundary_key, crop_scope_id, window_days, horizon_days)
+     DO UPDATE SET target_date = excluded.target_date,
+                   projected_scarcity_score = excluded.projected_scarcity_score,
+                   projected_abundance_score = excluded.projected_abundance_score,
+                   model = excluded.model,
+                   sample_count = excluded.sample_count,
+                   generated_at = excluded.generated_at,
+                   expires_at = excluded.expires_at`,
+    [
+      SCHEMA_VERSION,
+      scope.geoBoundaryKey,
+      scope.cropId,
+      scope.windowDays,
+      forecast.horizonDays,
+      forecast.targetDate.toISOString().slice(0, 10),
+      forecast.projectedScarcity,
+      forecast.projectedAbundance,
+      MODEL,
+      forecast.sampleCount,
+      now,
+      expiresAt,
+    ]
+  );
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `signal-forecast-${Date.now()}`;
+  const now = new Date();
+  const since = new Date(now.getTime() - HISTORY_DAYS * 86_400_000);
+  const expiresAt = new Date(now.getTime() + FORECAST_TTL_DAYS * 86_400_000);
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const scopes = groupHistory(await loadDailyHistory(client, since));
+    let written = 0;
+    let skipped = 0;
+
+    for (const scope of scopes) {
+      const forecasts = forecastScope(scope.history);
+      if (forecasts.length === 0) {
+        skipped += 1;
+        continue;
+      }
+      for (const forecast of forecasts) {
+        await upsertForecast(client, scope, forecast, now, expiresAt);
+        written += 1;
+      }
+    }
+
+    console.log(
+      JSON.stringify({
+        level: "INFO",
+        message: "Generated derived signal forecasts",
+        correlationId,
+        scopeCount: scopes.length,
+        skippedScopes: skipped,
+        metricName: "signal_forecast.rows_written",
+        metricValue: written,
+      })
+    );
+    emitMetrics(
+      "signal-forecast-worker",
+      { ForecastRowsWritten: written },
+      { properties: { correlationId } }
+    );
+  } finally {
+    await client.end();
+  }
+}
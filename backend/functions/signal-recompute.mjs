@@ -0,0 +1,57 @@
+import pg from "pg";
+import { computeBucketStart, normalizeRecomputeScope, recomputeAndUpsert } from "./lib/signals.mjs";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+// Invoked synchronously by the admin recompute endpoint to refresh one scope
+// through the same path as the aggregation worker and replay, and hand the
+// refreshed values back to the caller. Maintenance workers ask for the same
+// thing with a `signal.recompute_requested` event, whose detail carries the
+// scope.
+export async function handler(event = {}) {
+  const input = event["detail-type"] === "signal.recompute_requested" ? event.detail ?? {} : event;
+  const correlationId = input.correlationId ?? `signal-recompute-${Date.now()}`;
+  const { scope, windows } = normalizeRecomputeScope(input);
+  const now = new Date();
+  const bucketStart = computeBucketStart(now.toISOString());
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    const signals = [];
+    for (const windowDays of windows) {
+      const current = await recomputeAndUpsert(client, scope, windowDays, bucketStart);
+      signals.push({
+        windowDays,
+        bucketStart: bucketStart.toISOString(),
+        ...current,
+        computedAt: current.computedAt.toISOString(),
+      });
+    }
+
+    console.log(
+      JSON.stringify({
+        level: "INFO",
+        message: "Recomputed derived signal scope on request",
+        correlationId,
+        geoBoundaryKey: scope.geoBoundaryKey,
+        cropId: scope.cropId,
+        windows,
+      })
+    );
+    emitMetrics(
+      "signal-recompute",
+      { WindowsRecomputed: signals.length },
+      { properties: { correlationId } }
+    );
+
+    return { geoPrefix: scope.geoBoundaryKey, cropId: scope.cropId, signals };
+  } finally {
+    await client.end();
+  }
+}
@@ -0,0 +1,128 @@
+import pg from "pg";
+import { emitMetrics } from "./lib/metrics.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const DEFAULT_BATCH_SIZE = 500;
+const DEFAULT_MAX_BATCHES = 20;
+const DEFAULT_RETENTION_DAYS = 90;
+
+// Listings and requests go first so a purged user's content is already gone
+// before the user row cascades. Claims, ratings, and profile rows follow the
+// existing `on delete cascade` / `set null` foreign keys. Service users still
+// owned by an organization are skipped because that key is `on delete restrict`.
+const PURGE_TARGETS = [
+  { table: "surplus_listings", retentionEnv: "LISTINGS_RETENTION_DAYS" },
+  { table: "requests", retentionEnv: "REQUESTS_RETENTION_DAYS" },
+  {
+    table: "users",
+    retentionEnv: "USERS_RETENTION_DAYS",
+    extraPredicate: "AND NOT EXISTS (SELECT 1 FROM organizations o WHERE o.service_user_id = users.id)",
+  },
+];
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolvePurgeConfig(env, overrides = {}) {
+  const defaultRetentionDays = parsePositiveInt(
+    overrides.retentionDays ?? env.SOFT_DELETE_RETENTION_DAYS,
+    DEFAULT_RETENTION_DAYS
+  );
+
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.PURGE_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.PURGE_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+    targets: PURGE_TARGETS.map(({ table, retentionEnv, extraPredicate = "" }) => ({
+      table,
+      extraPredicate,
+      retentionDays: parsePositiveInt(
+        overrides.tableRetentionDays?.[table] ?? env[retentionEnv],
+        defaultRetentionDays
+      ),
+    })),
+  };
+}
+
+function retentionCutoff(now, retentionDays) {
+  return new Date(now.getTime() - retentionDays * 86_400_000);
+}
+
+// ── purge ────────────────────────────────────────────────────────────────────
+
+async function purgeTable(client, target, cutoff, batchSize, maxBatches) {
+  const { table, extraPredicate } = target;
+  let removed = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rowCount } = await client.query(
+      `DELETE FROM ${table}
+       WHERE id IN (
+         SELECT id FROM ${table}
+         WHERE deleted_at IS NOT NULL
+           AND deleted_at < $1
+           ${extraPredicate}
+         ORDER BY deleted_at
+         LIMIT $2
+       )`,
+      [cutoff, batchSize]
+    );
+    batches += 1;
+    removed += rowCount;
+    if (rowCount < batchSize) {
+      return { removed, batches, exhausted: true };
+    }
+  }
+
+  return { removed, batches, exhausted: false };
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `soft-delete-purge-${Date.now()}`;
+  const config = resolvePurgeConfig(process.env, event.detail ?? {});
+  const now = new Date();
+
+  const client = new pg.Client({
+    connectionString: DATABASE_URL,
+    ssl: { rejectUnauthorized: false },
+  });
+  await client.connect();
+
+  try {
+    for (const target of config.targets) {
+      const cutoff = retentionCutoff(now, target.retentionDays);
+      const result = await purgeTable(client, target, cutoff, config.batchSize, config.maxBatches);
+
+      console.log(
+        JSON.stringify({
+          level: result.exhausted ? "INFO" : "WARN",
+          message: result.exhausted
+            ? "Purged soft-deleted rows past retention"
+            : "Stopped purge at batch limit; remaining rows will be picked up next run",
+          correlationId,
+          table: target.table,
+          retentionDays: target.retentionDays,
+          cutoff: cutoff.toISOString(),
+          batches: result.batches,
+          metricName: "soft_delete_purge.rows_removed",
+          metricValue: result.removed,
+        })
+      );
+      emitMetrics(
+        "soft-delete-purge",
+        { RowsRemoved: result.removed },
+        { properties: { correlationId, table: target.table } }
+      );
+    }
+  } finally {
+    await client.end();
+  }
+}
@@ -0,0 +1,369 @@
+import pg from "pg";
+import { BedrockRuntimeClient, ConverseCommand } from "@aws-sdk/client-bedrock-runtime";
+import { emitMetrics } from "./lib/metrics.mjs";
+import {
+  SUMMARY_TTL_HOURS,
+  SYSTEM_PROMPT,
+  TEMPLATE_MODEL_ID,
+  buildPrompt,
+  claimRate,
+  estimateUsd,
+  needsSummary,
+  templateSummary,
+} from "./lib/summaries.mjs";
+
+const { DATABASE_URL } = process.env;
+
+const DEFAULT_MAX_SCOPES = 50;
+const DEFAULT_ACTIVE_DAYS = 7;
+const DEFAULT_LEAD_MINUTES = 75;
+const DEFAULT_MIN_AGE_MINUTES = 60;
+const DEFAULT_TIMEOUT_MS = 10_000;
+const MAX_OUTPUT_TOKENS = 300;
+
+// Stop early once the model looks down rather than paying a timeout per scope.
+const MAX_CONSECUTIVE_FAILURES = 3;
+
+// Recorded apart from on-demand feed generations so the usage rollup shows
+// what pre-generation costs.
+const FEATURE_KEY = "ai.feed_insights.pregenerate";
+
+const bedrock = new BedrockRuntimeClient({});
+
+// ── config ───────────────────────────────────────────────────────────────────
+
+function positiveInt(value, fallback) {
+  const parsed = Number.parseInt(String(value ?? fallback), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+// Mirrors provider_for in src/api/ai: the OpenAI-compatible provider is only
+// wired into the API, so the worker leaves those deployments alone.
+function resolveProvider(env) {
+  const name = (env.AI_SUMMARY_PROVIDER ?? "bedrock").trim().toLowerCase();
+  if (name === "template" || name === "mock") {
+    return { name: "template", modelId: TEMPLATE_MODEL_ID, modelVersion: "v1" };
+  }
+  if (name !== "bedrock" || env.BEDROCK_SUMMARY_ENABLED !== "1") return null;
+  return {
+    name: "bedrock",
+    modelId: env.BEDROCK_MODEL_PRIMARY ?? env.BEDROCK_MODEL_ID ?? "amazon.nova-lite-v1:0",
+    modelVersion: `${env.AI_RESPONSE_MODE ?? "tool_first_json"}-${env.AI_RESPONSE_SCHEMA_VERSION ?? "v1"}`,
+  };
+}
+
+function resolvePregenConfig(env, overrides = {}) {
+  return {
+    maxScopes: positiveInt(overrides.maxScopes ?? env.SUMMARY_PREGEN_MAX_SCOPES, DEFAULT_MAX_SCOPES),
+    activeDays: positiveInt(env.SUMMARY_PREGEN_ACTIVE_DAYS, DEFAULT_ACTIVE_DAYS),
+    leadMinutes: positiveInt(env.SUMMARY_PREGEN_LEAD_MINUTES, DEFAULT_LEAD_MINUTES),
+    minAgeMinutes: positiveInt(env.SUMMARY_PREGEN_MIN_AGE_MINUTES, DEFAULT_MIN_AGE_MINUTES),
+    timeoutMs: positiveInt(env.SUMMARY_PREGEN_TIMEOUT_MS, DEFAULT_TIMEOUT_MS),
+  };
+}
+
+// ── data access ──────────────────────────────────────────────────────────────
+
+// Most-read feed scopes first, with their live summary (if any) and the newest
+// signal recompute under the prefix.
+async function loadHotScopes(client, config) {
+  const { rows } = await client.query(
+    `WITH hot AS (
+       SELECT geo_boundary_key, window_days::int AS window_days, access_count
+       FROM feed_geo_access
+       WHERE last_accessed_at >= now() - make_interval(days => $1)
+       ORDER BY access_count DESC, last_accessed_at DESC
+       LIMIT $2
+     )
+     SELECT h.geo_boundary_key,
+            h.window_days,
+            s.generated_at AS summary_generated_at,
+            s.expires_at AS summary_expires_at,
+            (SELECT max(d.computed_at)
+             FROM derived_supply_signals d
+             WHERE d.schema_version = 1
+               AND d.window_days = h.window_days
+               AND d.geo_boundary_key LIKE h.geo_boundary_key || '%') AS signals_computed_at
+     FROM hot h
+     LEFT JOIN derived_signal_summaries s
+       ON s.schema_version = 1
+      AND s.geo_boundary_key = h.geo_boundary_key
+      AND s.window_days = h.window_days
+      AND s.expires_at > now()
+     ORDER BY h.access_count DESC`,
+    [config.activeDays, config.maxScopes]
+  );
+  return rows.map((row) => ({
+    geoBoundaryKey: row.geo_boundary_key,
+    windowDays: row.window_days,
+    summaryGeneratedAt: row.summary_generated_at,
+    summaryExpiresAt: row.summary_expires_at,
+    signalsComputedAt: row.signals_computed_at,
+  }));
+}
+
+// Same rows the feed passes to the summarizer on a cache miss.
+async function loadSignals(client, scope) {
+  const { rows } = await client.query(
+    `SELECT geo_boundary_key,
+            crop_id,
+            window_days::int AS window_days,
+            listing_count,
+            request_count,
+            supply_quantity::text AS supply_quantity,
+            demand_quantity::text AS demand_quantity,
+            scarcity_score::float8 AS scarcity_score,
+            abundance_score::float8 AS abundance_score,
+            computed_at,
+            expires_at
+     FROM list_latest_derived_supply_signals($1, $2, 1, 50, now())
+     ORDER BY scarcity_score DESC, abundance_score DESC, geo_boundary_key ASC`,
+    [scope.geoBoundaryKey, scope.windowDays]
+  );
+  return rows.map((row) => ({
+    geoBoundaryKey: row.geo_boundary_key,
+    cropId: row.crop_id,
+    windowDays: row.window_days,
+    listingCount: row.listing_count,
+    requestCount: row.request_count,
+    supplyQuantity: row.supply_quantity,
+    demandQuantity: row.demand_quantity,
+    scarcityScore: row.scarcity_score,
+    abundanceScore: row.abundance_score,
+    computedAt: row.computed_at.toISOString(),
+    expiresAt: row.expires_at.toISOString(),
+  }));
+}
+
+async function loadFulfillment(client, scope) {
+  const claimRow = (
+    await client.query(
+      `SELECT count(*) FILTER (WHERE c.status IN ('completed', 'cancelled', 'no_show'))::int AS resolved_count,
+              count(*) FILTER (WHERE c.status = 'completed')::int AS completed_count,
+              count(*) FILTER (WHERE c.status = 'no_show')::int AS no_show_count
+       FROM claims c
+       JOIN surplus_listings l ON l.id = c.listing_id
+       WHERE l.geo_key LIKE $1 || '%'
+         AND c.claimed_at >= now() - make_interval(days => $2)`,
+      [scope.geoBoundaryKey, scope.windowDays]
+    )
+  ).rows[0];
+
+  const { rows } = await client.query(
+    `SELECT r.crop_id, c.common_name, count(*)::int AS open_request_count
+     FROM requests r
+     JOIN crops c ON c.id = r.crop_id
+     WHERE r.status = 'open'
+       AND r.deleted_at IS NULL
+       AND r.geo_key LIKE $1 || '%'
+       AND r.created_at >= now() - make_interval(days => $2)
+     GROUP BY r.crop_id, c.common_name
+     ORDER BY open_request_count DESC, c.common_name ASC
+     LIMIT 3`,
+    [scope.geoBoundaryKey, scope.windowDays]
+  );
+
+  return {
+    resolvedClaimCount: claimRow.resolved_count,
+    fulfillmentRate: claimRate(claimRow.completed_count, claimRow.resolved_count),
+    noShowRate: claimRate(claimRow.no_show_count, claimRow.resolved_count),
+    topUnfulfilledCrops: rows.map((row) => ({
+      cropId: row.crop_id,
+      cropName: row.common_name,
+      openRequestCount: row.open_request_count,
+    })),
+  };
+}
+
+async function persistSummary(client, scope, signals, fulfillment, artifact) {
+  await client.query(
+    `INSERT INTO derived_signal_summaries (
+       schema_version, geo_boundary_key, window_days, summary_text, model_id, model_version,
+       signal_snapshot, generated_at, expires_at, created_at, updated_at
+     )
+     VALUES (1, $1, $2, $3, $4, $5, $6, $7, $8, now(), now())
+     ON CONFLICT (schema_version, geo_boundary_key, window_days)
+     DO UPDATE
+       SET summary_text = excluded.summary_text,
+           model_id = excluded.model_id,
+           model_version = excluded.model_version,
+           signal_snapshot = excluded.signal_snapshot,
+           generated_at = excluded.generated_at,
+           expires_at = excluded.expires_at,
+           updated_at = now()`,
+    [
+      scope.geoBoundaryKey,
+      scope.windowDays,
+      artifact.summaryText,
+      artifact.modelId,
+      artifact.modelVersion,
+      JSON.stringify({ signals, fulfillment }),
+      artifact.generatedAt,
+      new Date(artifact.generatedAt.getTime() + SUMMARY_TTL_HOURS * 3_600_000),
+    ]
+  );
+}
+
+// Accounting must never stop the run.
+async function recordUsage(client, usage) {
+  try {
+    await client.query(
+      `INSERT INTO ai_usage (
+         feature_key, provider, model_id, geo_boundary_key, status,
+         input_tokens, output_tokens, latency_ms, estimated_cost_usd
+       )
+       VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)`,
+      [
+        FEATURE_KEY,
+        usage.provider,
+        usage.modelId,
+        usage.geoBoundaryKey,
+        usage.status,
+        usage.inputTokens,
+        usage.outputTokens,
+        usage.latencyMs,
+        estimateUsd(usage.provider, usage.inputTokens, usage.outputTokens, process.env),
+      ]
+    );
+  } catch (error) {
+    console.log(
+      JSON.stringify({ level: "WARN", message: "Failed to record AI usage", error: error.message })
+    );
+  }
+}
+
+// ── generation ───────────────────────────────────────────────────────────────
+
+async function summarize(provider, scope, signals, fulfillment, timeoutMs) {
+  if (provider.name === "template") {
+    return {
+      summaryText: templateSummary(scope.geoBoundaryKey, scope.windowDays, signals, fulfillment),
+      inputTokens: 0,
+      outputTokens: 0,
+    };
+  }
+
+  const result = await bedrock.send(
+    new ConverseCommand({
+      modelId: provider.modelId,
+      system: [{ text: SYSTEM_PROMPT }],
+      messages: [
+        {
+          role: "user",
+          content: [{ text: buildPrompt(scope.geoBoundaryKey, scope.windowDays, signals, fulfillment) }],
+        },
+      ],
+      inferenceConfig: { maxTokens: MAX_OUTPUT_TOKENS, temperature: 0.2 },
+    }),
+    { abortSignal: AbortSignal.timeout(timeoutMs) }
+  );
+  const summaryText = result.output?.message?.content?.find((block) => block.text)?.text?.trim();
+  if (!summaryText) throw new Error("Bedrock returned no summary text");
+  return {
+    summaryText,
+    inputTokens: result.usage?.inputTokens ?? 0,
+    outputTokens: result.usage?.outputTokens ?? 0,
+  };
+}
+
+async function pregenerate(client, provider, scope, config) {
+  const signals = await loadSignals(client, scope);
+  if (signals.length === 0) return "empty";
+
+  const fulfillment = await loadFulfillment(client, scope);
+  const started = Date.now();
+  const usage = {
+    provider: provider.name,
+    modelId: provider.modelId,
+    geoBoundaryKey: scope.geoBoundaryKey,
+    status: "success",
+    inputTokens: 0,
+    outputTokens: 0,
+    latencyMs: 0,
+  };
+
+  try {
+    const output = await summarize(provider, scope, signals, fulfillment, config.timeoutMs);
+    usage.inputTokens = output.inputTokens;
+    usage.outputTokens = output.outputTokens;
+    usage.latencyMs = Date.now() - started;
+    await recordUsage(client, usage);
+    await persistSummary(client, scope, signals, fulfillment, {
+      summaryText: output.summaryText,
+      modelId: provider.modelId,
+      modelVersion: provider.modelVersion,
+      generatedAt: new Date(),
+    });
+    return "generated";
+  } catch (error) {
+    usage.status = error.name === "TimeoutError" || error.name === "AbortError" ? "timeout" : "error";
+    usage.latencyMs = Date.now() - started;
+    await recordUsage(client, usage);
+    console.log(
+      JSON.stringify({
+        level: "WARN",
+        message: "Summary pre-generation failed",
+        geoBoundaryKey: scope.geoBoundaryKey,
+        windowDays: scope.windowDays,
+        status: usage.status,
+        error: error.message,
+      })
+    );
+    return "failed";
+  }
+}
+
+// ── handler ──────────────────────────────────────────────────────────────────
+
+export async function handler(event = {}) {
+  const correlationId = event.id ?? `summary-pregen-${Date.now()}`;
+  const provider = resolveProvider(process.env);
+  if (!provider) {
+    return { statusCode: 200, body: "skipped: summaries disabled" };
+  }
+  const config = resolvePregenConfig(process.env, event.detail ?? {});
+  const now = new Date();
+
+  const client = new pg.Client({ connectionString: DATABASE_URL, ssl: { rejectUnauthorized: false } });
+  await client.connect();
+
+  const counts = { generated: 0, failed: 0, empty: 0, fresh: 0 };
+  try {
+    const scopes = await loadHotScopes(client, config);
+    let consecutiveFailures = 0;
+
+    for (const scope of scopes) {
+      if (!needsSummary(scope, now, config)) {
+        counts.fresh += 1;
+        continue;
+      }
+      const outcome = await pregenerate(client, provider, scope, config);
+      counts[outcome] += 1;
+      consecutiveFailures = outcome === "failed" ? consecutiveFailures + 1 : 0;
+      if (consecutiveFailures >= MAX_CONSECUTIVE_FAILURES) break;
+    }
+  } finally {
+    await client.end();
+  }
+
+  console.log(
+    JSON.stringify({
+      level: counts.failed > 0 ? "WARN" : "INFO",
+      message: "Pre-generated derived feed summaries",
+      correlationId,
+      provider: provider.name,
+      ...counts,
+    })
+  );
+  emitMetrics(
+    "summary-pregen-worker",
+    {
+      SummariesGenerated: counts.generated,
+      SummariesFailed: counts.failed,
+      SummariesAlreadyFresh: counts.fresh,
+    },
+    { properties: { correlationId, provider: provider.name } }
+  );
+
+  return { statusCode: 200, body: JSON.stringify(counts) };
+}
@@ -0,0 +1,129 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+const SHARED_POUNDS_TARGET = 100;
+const PICKUPS_TARGET = 10;
+const MIN_SEASON_PICKUPS = 5;
+
+const POUNDS_PER_UNIT = {
+  lb: 1, lbs: 1, pound: 1, pounds: 1,
+  oz: 1 / 16, ounce: 1 / 16, ounces: 1 / 16,
+  kg: 2.20462, kilogram: 2.20462, kilograms: 2.20462,
+  g: 0.00220462, gram: 0.00220462, grams: 0.00220462,
+};
+
+function toPounds(quantity, unit) {
+  const factor = POUNDS_PER_UNIT[String(unit ?? "").trim().toLowerCase()];
+  const amount = Number(quantity);
+  if (factor === undefined || !Number.isFinite(amount)) return 0;
+  return amount * factor;
+}
+
+function sumPounds(unitRows) {
+  const total = unitRows.reduce((acc, row) => acc + toPounds(row.quantity, row.unit), 0);
+  return Math.round(total * 1000) / 1000;
+}
+
+function evaluateAchievements({ completedShares, poundsShared, completedPickups, seasons }, currentYear) {
+  const earned = [];
+  if (completedShares >= 1) {
+    earned.push({ key: "first_share", seasonYear: null, snapshot: { completedShares } });
+  }
+  if (poundsShared >= SHARED_POUNDS_TARGET) {
+    earned.push({ key: "shared_100_lb", seasonYear: null, snapshot: { poundsShared } });
+  }
+  if (completedPickups >= PICKUPS_TARGET) {
+    earned.push({ key: "ten_pickups", seasonYear: null, snapshot: { completedPickups } });
+  }
+  for (const season of seasons) {
+    if (season.seasonYear < currentYear && season.noShows === 0 && season.completed >= MIN_SEASON_PICKUPS) {
+      earned.push({
+        key: "no_show_free_season",
+        seasonYear: season.seasonYear,
+        snapshot: { completedPickups: season.completed, noShows: 0 },
+      });
+    }
+  }
+  return earned;
+}
+
+function extractUserIds(detail) {
+  return [...new Set([detail.claimerId, detail.listingOwnerId].filter(Boolean))];
+}
+
+const NO_ACTIVITY = { completedShares: 0, poundsShared: 0, completedPickups: 0, seasons: [] };
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("toPounds", () => {
+  it("converts weight units case-insensitively", () => {
+    assert.equal(toPounds("3", "lb"), 3);
+    assert.equal(toPounds("32", " OZ "), 2);
+    assert.ok(Math.abs(toPounds("10", "kg") - 22.0462) < 1e-9);
+    assert.ok(Math.abs(toPounds("1000", "grams") - 2.20462) < 1e-9);
+  });
+
+  it("ignores counts, volumes, and missing units", () => {
+    assert.equal(toPounds("12", "bunches"), 0);
+    assert.equal(toPounds("2", "liters"), 0);
+    assert.equal(toPounds("5", null), 0);
+    assert.equal(toPounds("abc", "lb"), 0);
+  });
+});
+
+describe("sumPounds", () => {
+  it("sums mixed units and rounds to three decimals", () => {
+    const rows = [
+      { quantity: "40.000", unit: "lb" },
+      { quantity: "16.000", unit: "oz" },
+      { quantity: "6.000", unit: "each" },
+    ];
+    assert.equal(sumPounds(rows), 41);
+  });
+});
+
+describe("evaluateAchievements", () => {
+  it("awards nothing without activity", () => {
+    assert.deepEqual(evaluateAchievements(NO_ACTIVITY, 2026), []);
+  });
+
+  it("awards sharing milestones at their thresholds", () => {
+    const keys = evaluateAchievements({ ...NO_ACTIVITY, completedShares: 1, poundsShared: 100 }, 2026)
+      .map((a) => a.key);
+    assert.deepEqual(keys, ["first_share", "shared_100_lb"]);
+
+    const below = evaluateAchievements({ ...NO_ACTIVITY, completedShares: 3, poundsShared: 99.9 }, 2026)
+      .map((a) => a.key);
+    assert.deepEqual(below, ["first_share"]);
+  });
+
+  it("awards ten_pickups at ten completed pickups", () => {
+    assert.deepEqual(evaluateAchievements({ ...NO_ACTIVITY, completedPickups: 9 }, 2026), []);
+    const [achievement] = evaluateAchievements({ ...NO_ACTIVITY, completedPickups: 10 }, 2026);
+    assert.equal(achievement.key, "ten_pickups");
+    assert.equal(achievement.seasonYear, null);
+  });
+
+  it("awards no_show_free_season only for finished, active, clean seasons", () => {
+    const seasons = [
+      { seasonYear: 2024, completed: 6, noShows: 0 },
+      { seasonYear: 2025, completed: 8, noShows: 1 },
+      { seasonYear: 2025, completed: 4, noShows: 0 },
+      { seasonYear: 2026, completed: 12, noShows: 0 },
+    ];
+    const earned = evaluateAchievements({ ...NO_ACTIVITY, seasons }, 2026)
+      .filter((a) => a.key === "no_show_free_season");
+    assert.deepEqual(earned.map((a) => a.seasonYear), [2024]);
+    assert.deepEqual(earned[0].snapshot, { completedPickups: 6, noShows: 0 });
+  });
+});
+
+describe("extractUserIds", () => {
+  it("returns claimer and listing owner once each", () => {
+    assert.deepEqual(extractUserIds({ claimerId: "a", listingOwnerId: "b" }), ["a", "b"]);
+    assert.deepEqual(extractUserIds({ claimerId: "a", listingOwnerId: "a" }), ["a"]);
+    assert.deepEqual(extractUserIds({}), []);
+  });
+});
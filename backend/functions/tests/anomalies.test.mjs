@@ -0,0 +1,63 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The anomalies module has no pg dependency, so it is imported directly.
+import {
+  ANOMALY_MIN_SCORE,
+  ANOMALY_RATIO_THRESHOLD,
+  detectAnomalies,
+  recordAnomaly,
+} from "../lib/anomalies.mjs";
+
+describe("detectAnomalies", () => {
+  it("returns nothing without a baseline", () => {
+    assert.deepEqual(detectAnomalies(null, { scarcityScore: 9, abundanceScore: 0 }), []);
+  });
+
+  it("flags scarcity that tripled week over week", () => {
+    const anomalies = detectAnomalies(
+      { scarcityScore: 1, abundanceScore: 2 },
+      { scarcityScore: 3.5, abundanceScore: 2 }
+    );
+    assert.equal(anomalies.length, 1);
+    assert.equal(anomalies[0].metric, "scarcity");
+    assert.equal(anomalies[0].ratio, 3.5);
+  });
+
+  it("ignores spikes below the minimum score", () => {
+    const anomalies = detectAnomalies(
+      { scarcityScore: 0.1, abundanceScore: 0 },
+      { scarcityScore: ANOMALY_MIN_SCORE - 0.01, abundanceScore: 0 }
+    );
+    assert.deepEqual(anomalies, []);
+  });
+
+  it("ignores growth under the ratio threshold", () => {
+    const anomalies = detectAnomalies(
+      { scarcityScore: 2, abundanceScore: 2 },
+      { scarcityScore: 2 * ANOMALY_RATIO_THRESHOLD - 0.1, abundanceScore: 2 }
+    );
+    assert.deepEqual(anomalies, []);
+  });
+
+  it("reports a null ratio when the baseline was zero", () => {
+    const [anomaly] = detectAnomalies(
+      { scarcityScore: 0, abundanceScore: 0 },
+      { scarcityScore: 0, abundanceScore: 4 }
+    );
+    assert.equal(anomaly.metric, "abundance");
+    assert.equal(anomaly.ratio, null);
+  });
+});
+
+describe("recordAnomaly", () => {
+  it("reports whether the anomaly row was newly inserted", async () => {
+    const scope = { geoBoundaryKey: "9q8y", cropId: null };
+    const anomaly = { metric: "scarcity", previousValue: 1, currentValue: 3, ratio: 3 };
+    const inserted = { query: async () => ({ rowCount: 1 }) };
+    const duplicate = { query: async () => ({ rowCount: 0 }) };
+
+    assert.equal(await recordAnomaly(inserted, scope, 7, anomaly, new Date()), true);
+    assert.equal(await recordAnomaly(duplicate, scope, 7, anomaly, new Date()), false);
+  });
+});
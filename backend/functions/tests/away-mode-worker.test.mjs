@@ -0,0 +1,90 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const DEFAULT_BATCH_SIZE = 200;
+const DEFAULT_MAX_BATCHES = 20;
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.AWAY_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.AWAY_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+  };
+}
+
+async function runSweep(client, sweep, now, batchSize, maxBatches) {
+  let affected = 0;
+  let listings = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rows } = await client.query(sweep.sql, [now, batchSize]);
+    const count = rows[0]?.affected ?? 0;
+    batches += 1;
+    affected += count;
+    listings += rows[0]?.listings ?? 0;
+    if (count < batchSize) {
+      return { affected, listings, batches, exhausted: true };
+    }
+  }
+
+  return { affected, listings, batches, exhausted: false };
+}
+
+function fakeClient(results) {
+  const calls = [];
+  return {
+    calls,
+    async query(sql, params) {
+      calls.push(params);
+      const [affected, listings] = results[calls.length - 1] ?? [0, 0];
+      return { rows: [{ affected, listings }] };
+    },
+  };
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveConfig", () => {
+  it("uses defaults when nothing is configured", () => {
+    assert.deepEqual(resolveConfig({}), { batchSize: 200, maxBatches: 20 });
+  });
+
+  it("lets invocation overrides win over env and ignores invalid values", () => {
+    const config = resolveConfig({ AWAY_BATCH_SIZE: "50", AWAY_MAX_BATCHES: "x" }, { batchSize: 5 });
+    assert.equal(config.batchSize, 5);
+    assert.equal(config.maxBatches, DEFAULT_MAX_BATCHES);
+  });
+});
+
+describe("runSweep", () => {
+  const sweep = { name: "periods_started", sql: "select 1" };
+  const now = new Date("2026-07-01T12:00:00Z");
+
+  it("counts periods and the listings they touched until a short batch", async () => {
+    const client = fakeClient([
+      [2, 7],
+      [1, 0],
+    ]);
+    const result = await runSweep(client, sweep, now, 2, 20);
+    assert.deepEqual(result, { affected: 3, listings: 7, batches: 2, exhausted: true });
+    assert.deepEqual(client.calls[0], [now, 2]);
+  });
+
+  it("reports when the batch limit leaves periods behind", async () => {
+    const client = fakeClient([
+      [2, 1],
+      [2, 1],
+      [2, 1],
+    ]);
+    const result = await runSweep(client, sweep, now, 2, 2);
+    assert.deepEqual(result, { affected: 4, listings: 2, batches: 2, exhausted: false });
+  });
+});
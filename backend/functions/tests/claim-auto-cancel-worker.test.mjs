@@ -0,0 +1,80 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const DEFAULT_BATCH_SIZE = 500;
+const DEFAULT_MAX_BATCHES = 20;
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.AUTO_CANCEL_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.AUTO_CANCEL_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+  };
+}
+
+async function runSweep(client, sweep, now, batchSize, maxBatches) {
+  let affected = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rows } = await client.query(sweep.sql, [now, batchSize]);
+    const count = rows[0]?.affected ?? 0;
+    batches += 1;
+    affected += count;
+    if (count < batchSize) {
+      return { affected, batches, exhausted: true };
+    }
+  }
+
+  return { affected, batches, exhausted: false };
+}
+
+function fakeClient(counts) {
+  const calls = [];
+  return {
+    calls,
+    async query(sql, params) {
+      calls.push(params);
+      return { rows: [{ affected: counts[calls.length - 1] ?? 0 }] };
+    },
+  };
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveConfig", () => {
+  it("uses defaults when nothing is configured", () => {
+    assert.deepEqual(resolveConfig({}), { batchSize: 500, maxBatches: 20 });
+  });
+
+  it("lets invocation overrides win over env and ignores invalid values", () => {
+    const config = resolveConfig({ AUTO_CANCEL_BATCH_SIZE: "50", AUTO_CANCEL_MAX_BATCHES: "0" }, { batchSize: 5 });
+    assert.equal(config.batchSize, 5);
+    assert.equal(config.maxBatches, DEFAULT_MAX_BATCHES);
+  });
+});
+
+describe("runSweep", () => {
+  const sweep = { name: "holds_released", sql: "select 1" };
+  const now = new Date("2026-05-01T12:00:00Z");
+
+  it("stops at the first short batch", async () => {
+    const client = fakeClient([10, 10, 3]);
+    const result = await runSweep(client, sweep, now, 10, 20);
+    assert.deepEqual(result, { affected: 23, batches: 3, exhausted: true });
+    assert.deepEqual(client.calls[0], [now, 10]);
+  });
+
+  it("reports when the batch limit leaves rows behind", async () => {
+    const client = fakeClient([10, 10, 10]);
+    const result = await runSweep(client, sweep, now, 10, 2);
+    assert.deepEqual(result, { affected: 20, batches: 2, exhausted: false });
+  });
+});
@@ -0,0 +1,139 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+const EVENT_BUS_NAME = "default";
+const DEFAULT_BATCH_SIZE = 200;
+const DEFAULT_MAX_BATCHES = 50;
+const MAX_IDS_PER_DIGEST = 20;
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.DIGEST_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.DIGEST_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+  };
+}
+
+function digestDateFor(now) {
+  return now.toISOString().slice(0, 10);
+}
+
+function hasActivity(summary) {
+  return (
+    summary.pending_claim_count > 0 ||
+    summary.expiring_listing_count > 0 ||
+    summary.unread_message_count > 0
+  );
+}
+
+function buildDigestEntry(summary, digestDate, correlationId, occurredAt) {
+  return {
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "notification.claim_digest",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      channel: "email",
+      recipientId: summary.user_id,
+      digestDate,
+      pendingClaimCount: summary.pending_claim_count,
+      expiringListingCount: summary.expiring_listing_count,
+      unreadMessageCount: summary.unread_message_count,
+      pendingClaimIds: (summary.pending_claim_ids ?? []).slice(0, MAX_IDS_PER_DIGEST),
+      expiringListingIds: (summary.expiring_listing_ids ?? []).slice(0, MAX_IDS_PER_DIGEST),
+      correlationId,
+      occurredAt,
+    }),
+  };
+}
+
+const QUIET = {
+  user_id: "u-1",
+  pending_claim_count: 0,
+  pending_claim_ids: null,
+  expiring_listing_count: 0,
+  expiring_listing_ids: null,
+  unread_message_count: 0,
+};
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveConfig", () => {
+  it("falls back to defaults for missing or invalid values", () => {
+    assert.deepEqual(resolveConfig({}), { batchSize: 200, maxBatches: 50 });
+    assert.deepEqual(resolveConfig({ DIGEST_BATCH_SIZE: "0", DIGEST_MAX_BATCHES: "x" }), {
+      batchSize: 200,
+      maxBatches: 50,
+    });
+  });
+
+  it("prefers event overrides over the environment", () => {
+    assert.deepEqual(resolveConfig({ DIGEST_BATCH_SIZE: "100" }, { batchSize: 25 }), {
+      batchSize: 25,
+      maxBatches: 50,
+    });
+  });
+});
+
+describe("digestDateFor", () => {
+  it("uses the UTC calendar day", () => {
+    assert.equal(digestDateFor(new Date("2026-10-16T23:59:59-07:00")), "2026-10-17");
+  });
+});
+
+describe("hasActivity", () => {
+  it("skips growers with nothing to report", () => {
+    assert.equal(hasActivity(QUIET), false);
+    assert.equal(hasActivity({ ...QUIET, pending_claim_count: 1 }), true);
+    assert.equal(hasActivity({ ...QUIET, expiring_listing_count: 2 }), true);
+    assert.equal(hasActivity({ ...QUIET, unread_message_count: 3 }), true);
+  });
+});
+
+describe("buildDigestEntry", () => {
+  it("builds one routing-only email event with counts", () => {
+    const entry = buildDigestEntry(
+      { ...QUIET, pending_claim_count: 2, pending_claim_ids: ["c-1", "c-2"], unread_message_count: 4 },
+      "2026-10-16",
+      "corr-1",
+      "2026-10-16T14:00:00.000Z"
+    );
+    assert.equal(entry.DetailType, "notification.claim_digest");
+    assert.equal(entry.Source, "community-garden.workers");
+    assert.deepEqual(JSON.parse(entry.Detail), {
+      schemaVersion: 1,
+      channel: "email",
+      recipientId: "u-1",
+      digestDate: "2026-10-16",
+      pendingClaimCount: 2,
+      expiringListingCount: 0,
+      unreadMessageCount: 4,
+      pendingClaimIds: ["c-1", "c-2"],
+      expiringListingIds: [],
+      correlationId: "corr-1",
+      occurredAt: "2026-10-16T14:00:00.000Z",
+    });
+  });
+
+  it("caps the ids while keeping the full counts", () => {
+    const ids = Array.from({ length: 30 }, (_, i) => `l-${i}`);
+    const detail = JSON.parse(
+      buildDigestEntry(
+        { ...QUIET, expiring_listing_count: 30, expiring_listing_ids: ids },
+        "2026-10-16",
+        "corr-1",
+        "2026-10-16T14:00:00.000Z"
+      ).Detail
+    );
+    assert.equal(detail.expiringListingCount, 30);
+    assert.equal(detail.expiringListingIds.length, MAX_IDS_PER_DIGEST);
+    assert.equal(detail.expiringListingIds[0], "l-0");
+  });
+});
@@ -0,0 +1,112 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+const EVENT_BUS_NAME = "default";
+
+const CHANNELS_BY_CONTACT_PREF = {
+  phone: ["sms"],
+  knock: ["email"],
+  app_message: ["email", "push"],
+};
+
+function shouldNotify(detail) {
+  return (
+    Boolean(detail.claimId && detail.listingId && detail.listingOwnerId) &&
+    detail.status === "pending"
+  );
+}
+
+function wantsImmediateAlert(claimAlerts) {
+  return (claimAlerts ?? "immediate") === "immediate";
+}
+
+function channelsFor(contactPref) {
+  return CHANNELS_BY_CONTACT_PREF[contactPref] ?? CHANNELS_BY_CONTACT_PREF.app_message;
+}
+
+function buildNotificationEntries(detail, contactPref, occurredAt) {
+  return channelsFor(contactPref).map((channel) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "notification.claim_pending",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      channel,
+      contactPref,
+      recipientId: detail.listingOwnerId,
+      claimId: detail.claimId,
+      listingId: detail.listingId,
+      claimerId: detail.claimerId ?? null,
+      correlationId: detail.correlationId ?? "unknown",
+      occurredAt,
+    }),
+  }));
+}
+
+const CLAIM = {
+  claimId: "c-1",
+  listingId: "l-1",
+  claimerId: "u-2",
+  listingOwnerId: "u-1",
+  status: "pending",
+  correlationId: "corr-1",
+};
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("shouldNotify", () => {
+  it("notifies only for pending claims with routing ids", () => {
+    assert.equal(shouldNotify(CLAIM), true);
+    assert.equal(shouldNotify({ ...CLAIM, status: "confirmed" }), false);
+    assert.equal(shouldNotify({ ...CLAIM, listingOwnerId: undefined }), false);
+    assert.equal(shouldNotify({}), false);
+  });
+});
+
+describe("wantsImmediateAlert", () => {
+  it("alerts per claim unless the grower chose the digest or no alerts", () => {
+    assert.equal(wantsImmediateAlert("immediate"), true);
+    assert.equal(wantsImmediateAlert(null), true);
+    assert.equal(wantsImmediateAlert("daily_digest"), false);
+    assert.equal(wantsImmediateAlert("off"), false);
+  });
+});
+
+describe("channelsFor", () => {
+  it("follows the listing's contact preference", () => {
+    assert.deepEqual(channelsFor("phone"), ["sms"]);
+    assert.deepEqual(channelsFor("knock"), ["email"]);
+    assert.deepEqual(channelsFor("app_message"), ["email", "push"]);
+  });
+
+  it("treats unknown preferences as app_message", () => {
+    assert.deepEqual(channelsFor("carrier_pigeon"), ["email", "push"]);
+  });
+});
+
+describe("buildNotificationEntries", () => {
+  it("builds one routing-only event per channel addressed to the owner", () => {
+    const entries = buildNotificationEntries(CLAIM, "app_message", "2026-10-16T00:00:00.000Z");
+    assert.equal(entries.length, 2);
+    assert.equal(entries[0].DetailType, "notification.claim_pending");
+    assert.equal(entries[0].Source, "community-garden.workers");
+    assert.deepEqual(JSON.parse(entries[1].Detail), {
+      schemaVersion: 1,
+      channel: "push",
+      contactPref: "app_message",
+      recipientId: "u-1",
+      claimId: "c-1",
+      listingId: "l-1",
+      claimerId: "u-2",
+      correlationId: "corr-1",
+      occurredAt: "2026-10-16T00:00:00.000Z",
+    });
+  });
+
+  it("sends phone listings by SMS only", () => {
+    const entries = buildNotificationEntries(CLAIM, "phone", "2026-10-16T00:00:00.000Z");
+    assert.deepEqual(entries.map((entry) => JSON.parse(entry.Detail).channel), ["sms"]);
+  });
+});
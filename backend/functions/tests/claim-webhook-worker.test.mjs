@@ -0,0 +1,133 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+import { createHmac } from "node:crypto";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+const EVENT_TYPES_BY_STATUS = {
+  confirmed: "claim.confirmed",
+  completed: "claim.completed",
+};
+
+function eventTypeFor(detail) {
+  if (!detail.claimId || !detail.claimerId) return null;
+  return EVENT_TYPES_BY_STATUS[detail.status] ?? null;
+}
+
+function buildPayload(claim, organizationId, eventType, occurredAt) {
+  return {
+    schemaVersion: 1,
+    eventType,
+    occurredAt,
+    organizationId,
+    claim: {
+      id: claim.id,
+      listingId: claim.listing_id,
+      requestId: claim.request_id ?? null,
+      status: eventType.slice("claim.".length),
+      quantityClaimed: claim.quantity_claimed,
+      unit: claim.unit ?? null,
+      confirmedAt: claim.confirmed_at ? new Date(claim.confirmed_at).toISOString() : null,
+      completedAt: claim.completed_at ? new Date(claim.completed_at).toISOString() : null,
+    },
+    crop: {
+      id: claim.crop_id,
+      name: claim.crop_name,
+      varietyId: claim.variety_id ?? null,
+      varietyName: claim.variety_name ?? null,
+    },
+  };
+}
+
+function signatureHeader(secret, timestamp, body) {
+  const digest = createHmac("sha256", secret).update(`${timestamp}.${body}`).digest("hex");
+  return `t=${timestamp},v1=${digest}`;
+}
+
+function buildRequest(delivery, secret, now) {
+  const body = JSON.stringify(delivery.payload);
+  const timestamp = Math.floor(now.getTime() / 1000);
+  return {
+    body,
+    headers: {
+      "Content-Type": "application/json",
+      "User-Agent": "community-garden-webhooks/1",
+      "X-Garden-Event": delivery.event_type,
+      "X-Garden-Delivery": delivery.id,
+      "X-Garden-Signature": signatureHeader(secret, timestamp, body),
+    },
+  };
+}
+
+const CLAIM = {
+  id: "c-1",
+  listing_id: "l-1",
+  request_id: null,
+  quantity_claimed: "12.500",
+  unit: "lb",
+  confirmed_at: new Date("2026-10-16T14:00:00Z"),
+  completed_at: null,
+  crop_id: "crop-1",
+  crop_name: "Tomato",
+  variety_id: "v-1",
+  variety_name: "Sungold",
+};
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("eventTypeFor", () => {
+  it("maps confirmed and completed claims to webhook events", () => {
+    const base = { claimId: "c-1", claimerId: "u-1" };
+    assert.equal(eventTypeFor({ ...base, status: "confirmed" }), "claim.confirmed");
+    assert.equal(eventTypeFor({ ...base, status: "completed" }), "claim.completed");
+  });
+
+  it("ignores other statuses and incomplete details", () => {
+    assert.equal(eventTypeFor({ claimId: "c-1", claimerId: "u-1", status: "cancelled" }), null);
+    assert.equal(eventTypeFor({ claimId: "c-1", claimerId: "u-1", status: "pending" }), null);
+    assert.equal(eventTypeFor({ claimId: "c-1", status: "confirmed" }), null);
+  });
+});
+
+describe("buildPayload", () => {
+  it("carries the claim quantity and crop", () => {
+    assert.deepEqual(buildPayload(CLAIM, "org-1", "claim.confirmed", "2026-10-16T14:00:01.000Z"), {
+      schemaVersion: 1,
+      eventType: "claim.confirmed",
+      occurredAt: "2026-10-16T14:00:01.000Z",
+      organizationId: "org-1",
+      claim: {
+        id: "c-1",
+        listingId: "l-1",
+        requestId: null,
+        status: "confirmed",
+        quantityClaimed: "12.500",
+        unit: "lb",
+        confirmedAt: "2026-10-16T14:00:00.000Z",
+        completedAt: null,
+      },
+      crop: { id: "crop-1", name: "Tomato", varietyId: "v-1", varietyName: "Sungold" },
+    });
+  });
+
+  it("uses the event's status rather than the claim's current one", () => {
+    const payload = buildPayload(CLAIM, "org-1", "claim.completed", "2026-10-16T15:00:00.000Z");
+    assert.equal(payload.claim.status, "completed");
+  });
+});
+
+describe("buildRequest", () => {
+  it("signs the exact body with the webhook secret", () => {
+    const delivery = { id: "d-1", event_type: "claim.confirmed", payload: { hello: "world" } };
+    const request = buildRequest(delivery, "whsec_test", new Date("2026-10-16T14:00:00Z"));
+
+    assert.equal(request.body, '{"hello":"world"}');
+    assert.equal(request.headers["X-Garden-Event"], "claim.confirmed");
+    assert.equal(request.headers["X-Garden-Delivery"], "d-1");
+
+    const expected = createHmac("sha256", "whsec_test")
+      .update(`1792159200.${request.body}`)
+      .digest("hex");
+    assert.equal(request.headers["X-Garden-Signature"], `t=1792159200,v1=${expected}`);
+  });
+});
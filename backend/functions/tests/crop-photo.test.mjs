@@ -0,0 +1,66 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The crop photo module has no pg dependency, so it is imported directly.
+import {
+  MIN_CONFIDENCE,
+  chooseIdentification,
+  cropSlugCandidates,
+  imageFormat,
+  parseIdentification,
+} from "../lib/crop-photo.mjs";
+
+describe("imageFormat", () => {
+  it("maps supported content types", () => {
+    assert.equal(imageFormat("image/jpeg"), "jpeg");
+    assert.equal(imageFormat("IMAGE/PNG; charset=binary"), "png");
+    assert.equal(imageFormat("application/pdf"), null);
+    assert.equal(imageFormat(undefined), null);
+  });
+});
+
+describe("parseIdentification", () => {
+  it("reads crop and clamps confidence", () => {
+    assert.deepEqual(parseIdentification('{"crop":"Tomato","confidence":0.92}'), {
+      crop: "tomato",
+      confidence: 0.92,
+    });
+    assert.deepEqual(parseIdentification('Here: {"crop":"kale","confidence":1.4}'), {
+      crop: "kale",
+      confidence: 1,
+    });
+  });
+
+  it("rejects empty or malformed replies", () => {
+    assert.equal(parseIdentification('{"crop":null,"confidence":0}'), null);
+    assert.equal(parseIdentification("{not json}"), null);
+    assert.equal(parseIdentification(undefined), null);
+  });
+});
+
+describe("chooseIdentification", () => {
+  it("keeps the most confident answer above the threshold", () => {
+    const best = chooseIdentification([
+      { crop: "tomato", confidence: 0.75 },
+      null,
+      { crop: "pepper", confidence: 0.9 },
+      { crop: "kale", confidence: MIN_CONFIDENCE - 0.1 },
+    ]);
+    assert.deepEqual(best, { crop: "pepper", confidence: 0.9 });
+  });
+
+  it("returns null when nothing is confident", () => {
+    assert.equal(chooseIdentification([{ crop: "kale", confidence: 0.2 }]), null);
+    assert.equal(chooseIdentification([]), null);
+  });
+});
+
+describe("cropSlugCandidates", () => {
+  it("produces slug forms with simple singulars", () => {
+    assert.deepEqual(cropSlugCandidates("Swiss chard"), ["swiss-chard"]);
+    assert.deepEqual(cropSlugCandidates("tomatoes"), ["tomatoes", "tomato"]);
+    assert.deepEqual(cropSlugCandidates("strawberries"), ["strawberries", "strawberry"]);
+    assert.deepEqual(cropSlugCandidates("beans"), ["beans", "bean"]);
+    assert.deepEqual(cropSlugCandidates("  "), []);
+  });
+});
@@ -0,0 +1,87 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const SUPPORTED_WINDOWS_DAYS = [7, 14, 30];
+const DEFAULT_CHUNK_SIZE = 200;
+const MAX_CHUNK_SIZE = 2000;
+
+function resolveChunkInput(input = {}) {
+  const chunkSize = Number.parseInt(String(input.chunkSize ?? DEFAULT_CHUNK_SIZE), 10);
+  if (!Number.isInteger(chunkSize) || chunkSize < 1 || chunkSize > MAX_CHUNK_SIZE) {
+    throw new Error(`chunkSize must be between 1 and ${MAX_CHUNK_SIZE}`);
+  }
+
+  const windows = input.windows ?? SUPPORTED_WINDOWS_DAYS;
+  for (const windowDays of windows) {
+    if (!SUPPORTED_WINDOWS_DAYS.includes(windowDays)) {
+      throw new Error(`Unsupported window: ${windowDays}`);
+    }
+  }
+
+  return {
+    chunkSize,
+    windows,
+    cursor: decodeCursor(input.cursor ?? null),
+    runStartedAt: input.runStartedAt ?? new Date().toISOString(),
+  };
+}
+
+function encodeCursor(scope) {
+  if (!scope) return null;
+  return `${scope.geoBoundaryKey}|${scope.cropId ?? ""}`;
+}
+
+function decodeCursor(cursor) {
+  if (!cursor) return { geoBoundaryKey: "", cropKey: "" };
+  const [geoBoundaryKey, cropKey = ""] = String(cursor).split("|");
+  if (!/^[0-9b-hjkmnp-z]{1,12}$/.test(geoBoundaryKey)) {
+    throw new Error(`Invalid replay cursor: ${cursor}`);
+  }
+  return { geoBoundaryKey, cropKey };
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveChunkInput", () => {
+  it("starts from the beginning with defaults", () => {
+    const input = resolveChunkInput({ runStartedAt: "2026-03-01T00:00:00Z" });
+    assert.equal(input.chunkSize, DEFAULT_CHUNK_SIZE);
+    assert.deepEqual(input.windows, SUPPORTED_WINDOWS_DAYS);
+    assert.deepEqual(input.cursor, { geoBoundaryKey: "", cropKey: "" });
+  });
+
+  it("rejects out-of-range chunk sizes", () => {
+    assert.throws(() => resolveChunkInput({ chunkSize: 0 }), /chunkSize/);
+    assert.throws(() => resolveChunkInput({ chunkSize: MAX_CHUNK_SIZE + 1 }), /chunkSize/);
+  });
+
+  it("rejects unsupported windows", () => {
+    assert.throws(() => resolveChunkInput({ windows: [5] }), /Unsupported window/);
+  });
+
+  it("keeps runStartedAt stable across chunks", () => {
+    const input = resolveChunkInput({ runStartedAt: "2026-03-01T00:00:00Z", cursor: "9q8y|" });
+    assert.equal(input.runStartedAt, "2026-03-01T00:00:00Z");
+  });
+});
+
+describe("cursor encoding", () => {
+  it("round-trips crop-specific scopes", () => {
+    const scope = { geoBoundaryKey: "9q8yy", cropId: "11111111-1111-1111-1111-111111111111" };
+    assert.deepEqual(decodeCursor(encodeCursor(scope)), {
+      geoBoundaryKey: "9q8yy",
+      cropKey: "11111111-1111-1111-1111-111111111111",
+    });
+  });
+
+  it("encodes all-crops scopes with an empty crop key", () => {
+    assert.equal(encodeCursor({ geoBoundaryKey: "9q8y", cropId: null }), "9q8y|");
+    assert.deepEqual(decodeCursor("9q8y|"), { geoBoundaryKey: "9q8y", cropKey: "" });
+  });
+
+  it("rejects malformed cursors", () => {
+    assert.throws(() => decodeCursor("NOT-A-GEOHASH|x"), /Invalid replay cursor/);
+  });
+});
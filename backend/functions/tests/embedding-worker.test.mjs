@@ -0,0 +1,85 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+import { createHash } from "node:crypto";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+function embeddingTarget(detailType, detail) {
+  if (detailType?.startsWith("listing.") && detail.listingId) {
+    return { kind: "listing", id: detail.listingId };
+  }
+  if (detailType?.startsWith("request.") && detail.requestId) {
+    return { kind: "request", id: detail.requestId };
+  }
+  return null;
+}
+
+function buildEmbeddingText(kind, row) {
+  const parts = kind === "listing"
+    ? [row.title, row.crop_name, row.variety_name]
+    : [row.crop_name, row.variety_name, row.notes];
+  return parts
+    .map((part) => (part ?? "").trim())
+    .filter(Boolean)
+    .join(". ");
+}
+
+function contentHash(modelId, text) {
+  return createHash("sha256").update(`${modelId}:${text}`).digest("hex");
+}
+
+function toVectorLiteral(values) {
+  return `[${values.join(",")}]`;
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("embeddingTarget", () => {
+  it("maps listing and request events to their row", () => {
+    assert.deepEqual(embeddingTarget("listing.created", { listingId: "l-1" }), { kind: "listing", id: "l-1" });
+    assert.deepEqual(embeddingTarget("request.updated", { requestId: "r-1" }), { kind: "request", id: "r-1" });
+  });
+
+  it("ignores other events and missing ids", () => {
+    assert.equal(embeddingTarget("claim.created", { listingId: "l-1" }), null);
+    assert.equal(embeddingTarget("listing.updated", {}), null);
+    assert.equal(embeddingTarget(undefined, { listingId: "l-1" }), null);
+  });
+});
+
+describe("buildEmbeddingText", () => {
+  it("uses title and crop names for listings", () => {
+    const text = buildEmbeddingText("listing", {
+      title: " Sungold cherry tomatoes ",
+      crop_name: "Tomato",
+      variety_name: null,
+    });
+    assert.equal(text, "Sungold cherry tomatoes. Tomato");
+  });
+
+  it("includes free-text notes for requests", () => {
+    const text = buildEmbeddingText("request", {
+      crop_name: "Pepper",
+      variety_name: "Jalapeño",
+      notes: "anything spicy for salsa",
+    });
+    assert.equal(text, "Pepper. Jalapeño. anything spicy for salsa");
+  });
+
+  it("returns empty text when nothing is set", () => {
+    assert.equal(buildEmbeddingText("request", { crop_name: "", notes: null }), "");
+  });
+});
+
+describe("contentHash", () => {
+  it("changes with the model so a model switch re-embeds", () => {
+    assert.equal(contentHash("m1", "kale"), contentHash("m1", "kale"));
+    assert.notEqual(contentHash("m1", "kale"), contentHash("m2", "kale"));
+  });
+});
+
+describe("toVectorLiteral", () => {
+  it("formats a pgvector literal", () => {
+    assert.equal(toVectorLiteral([0.1, -0.25, 1]), "[0.1,-0.25,1]");
+  });
+});
@@ -0,0 +1,78 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+const EVENT_BUS_NAME = "default";
+
+function shouldAlert(detail) {
+  return Boolean(detail.listingId && detail.userId) && detail.status === "active";
+}
+
+function buildAlertEntries(detail, followerIds, occurredAt) {
+  return followerIds.map((followerId) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "follower.listing_alert",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      listingId: detail.listingId,
+      growerId: detail.userId,
+      followerId,
+      cropId: detail.cropId ?? null,
+      correlationId: detail.correlationId ?? "unknown",
+      occurredAt,
+    }),
+  }));
+}
+
+function chunk(items, size) {
+  const batches = [];
+  for (let i = 0; i < items.length; i += size) {
+    batches.push(items.slice(i, i + size));
+  }
+  return batches;
+}
+
+const LISTING = { listingId: "l-1", userId: "g-1", cropId: "c-1", status: "active", correlationId: "corr-1" };
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("shouldAlert", () => {
+  it("alerts only for active listings with ids", () => {
+    assert.equal(shouldAlert(LISTING), true);
+    assert.equal(shouldAlert({ ...LISTING, status: "pending" }), false);
+    assert.equal(shouldAlert({ ...LISTING, userId: undefined }), false);
+    assert.equal(shouldAlert({}), false);
+  });
+});
+
+describe("buildAlertEntries", () => {
+  it("builds one routing-only event per follower", () => {
+    const entries = buildAlertEntries(LISTING, ["f-1", "f-2"], "2026-10-16T00:00:00.000Z");
+    assert.equal(entries.length, 2);
+    assert.equal(entries[0].DetailType, "follower.listing_alert");
+    assert.equal(entries[0].Source, "community-garden.workers");
+    assert.deepEqual(JSON.parse(entries[1].Detail), {
+      schemaVersion: 1,
+      listingId: "l-1",
+      growerId: "g-1",
+      followerId: "f-2",
+      cropId: "c-1",
+      correlationId: "corr-1",
+      occurredAt: "2026-10-16T00:00:00.000Z",
+    });
+  });
+
+  it("returns no entries without followers", () => {
+    assert.deepEqual(buildAlertEntries(LISTING, [], "2026-10-16T00:00:00.000Z"), []);
+  });
+});
+
+describe("chunk", () => {
+  it("splits into PutEvents-sized batches", () => {
+    const batches = chunk(Array.from({ length: 23 }, (_, i) => i), 10);
+    assert.deepEqual(batches.map((b) => b.length), [10, 10, 3]);
+    assert.deepEqual(chunk([], 10), []);
+  });
+});
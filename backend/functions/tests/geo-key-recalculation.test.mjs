@@ -0,0 +1,156 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The geohash and signals modules have no pg dependency, so they are imported
+// directly; the handler's own pure functions are mirrored below.
+import { encodeGeohash } from "../lib/geohash.mjs";
+import { GEO_PRECISIONS } from "../lib/signals.mjs";
+
+const TARGETS = [
+  { name: "listings" },
+  { name: "requests" },
+  { name: "grower_profiles" },
+  { name: "gatherer_profiles" },
+];
+
+function encodeCursor(cursor) {
+  if (!cursor) return null;
+  return cursor.afterId ? `${cursor.target}|${cursor.afterId}` : cursor.target;
+}
+
+function decodeCursor(cursor) {
+  if (!cursor) return { target: TARGETS[0].name, afterId: null };
+  const [target, afterId = null] = String(cursor).split("|");
+  if (!TARGETS.some((t) => t.name === target)) {
+    throw new Error(`Invalid geo key cursor: ${cursor}`);
+  }
+  return { target, afterId };
+}
+
+function nextCursor(cursor, rows, chunkSize) {
+  if (rows.length === chunkSize) {
+    return { target: cursor.target, afterId: rows[rows.length - 1].id };
+  }
+  const index = TARGETS.findIndex((t) => t.name === cursor.target);
+  const next = TARGETS[index + 1];
+  return next ? { target: next.name, afterId: null } : null;
+}
+
+function planChanges(rows, precision) {
+  const changes = [];
+  for (const row of rows) {
+    const geoKey = encodeGeohash(row.lat, row.lng, precision);
+    if (geoKey !== row.geo_key) {
+      changes.push({ id: row.id, oldGeoKey: row.geo_key ?? null, newGeoKey: geoKey, cropId: row.crop_id ?? null });
+    }
+  }
+  return changes;
+}
+
+function affectedScopes(changes) {
+  const seen = new Set();
+  const scopes = [];
+  const add = (geoPrefix, cropId) => {
+    const key = `${geoPrefix}|${cropId ?? ""}`;
+    if (seen.has(key)) return;
+    seen.add(key);
+    scopes.push({ geoPrefix, cropId });
+  };
+
+  for (const change of changes) {
+    for (const precision of GEO_PRECISIONS) {
+      const before = change.oldGeoKey?.toLowerCase().slice(0, precision) ?? null;
+      const after = change.newGeoKey.slice(0, precision);
+      if (before === after) continue;
+      for (const prefix of [before, after]) {
+        if (!prefix || prefix.length < precision) continue;
+        add(prefix, change.cropId);
+        add(prefix, null);
+      }
+    }
+  }
+  return scopes;
+}
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("cursor encoding", () => {
+  it("starts at the first table and round-trips", () => {
+    assert.deepEqual(decodeCursor(null), { target: "listings", afterId: null });
+    const cursor = { target: "requests", afterId: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca" };
+    assert.deepEqual(decodeCursor(encodeCursor(cursor)), cursor);
+    assert.deepEqual(decodeCursor("grower_profiles"), { target: "grower_profiles", afterId: null });
+  });
+
+  it("rejects unknown tables", () => {
+    assert.throws(() => decodeCursor("users|abc"), /Invalid geo key cursor/);
+  });
+});
+
+describe("nextCursor", () => {
+  const rows = [{ id: "a" }, { id: "b" }];
+
+  it("stays on a table while pages are full", () => {
+    assert.deepEqual(nextCursor({ target: "listings", afterId: null }, rows, 2), {
+      target: "listings",
+      afterId: "b",
+    });
+  });
+
+  it("moves to the next table after a short page and finishes after the last", () => {
+    assert.deepEqual(nextCursor({ target: "listings", afterId: "x" }, rows, 3), {
+      target: "requests",
+      afterId: null,
+    });
+    assert.equal(nextCursor({ target: "gatherer_profiles", afterId: null }, [], 3), null);
+  });
+});
+
+describe("planChanges", () => {
+  it("keeps rows whose stored key already matches and flags the rest", () => {
+    const rows = [
+      { id: "ok", lat: 40.71277, lng: -74.00597, geo_key: "dr5regw", crop_id: "c-1" },
+      { id: "stale", lat: 40.71277, lng: -74.00597, geo_key: "dr5reg", crop_id: "c-1" },
+      { id: "missing", lat: 40.71277, lng: -74.00597, geo_key: null },
+    ];
+    assert.deepEqual(planChanges(rows, 7), [
+      { id: "stale", oldGeoKey: "dr5reg", newGeoKey: "dr5regw", cropId: "c-1" },
+      { id: "missing", oldGeoKey: null, newGeoKey: "dr5regw", cropId: null },
+    ]);
+  });
+});
+
+describe("affectedScopes", () => {
+  it("skips precisions where the prefix did not move", () => {
+    const scopes = affectedScopes([
+      { id: "l-1", oldGeoKey: "dr5reg", newGeoKey: "dr5regw", cropId: "c-1" },
+    ]);
+    assert.deepEqual(scopes, []);
+  });
+
+  it("queues both the old and new cells for the crop and for all crops", () => {
+    const scopes = affectedScopes([
+      { id: "l-1", oldGeoKey: "dr5rsxx", newGeoKey: "dr5ruab", cropId: "c-1" },
+    ]);
+    assert.deepEqual(scopes, [
+      { geoPrefix: "dr5rs", cropId: "c-1" },
+      { geoPrefix: "dr5rs", cropId: null },
+      { geoPrefix: "dr5ru", cropId: "c-1" },
+      { geoPrefix: "dr5ru", cropId: null },
+      { geoPrefix: "dr5rsx", cropId: "c-1" },
+      { geoPrefix: "dr5rsx", cropId: null },
+      { geoPrefix: "dr5rua", cropId: "c-1" },
+      { geoPrefix: "dr5rua", cropId: null },
+    ]);
+  });
+
+  it("only queues the new cells when there was no stored key", () => {
+    const scopes = affectedScopes([
+      { id: "l-1", oldGeoKey: null, newGeoKey: "dr5regw", cropId: null },
+    ]);
+    assert.deepEqual(
+      scopes.map((s) => s.geoPrefix),
+      ["dr5r", "dr5re", "dr5reg"]
+    );
+  });
+});
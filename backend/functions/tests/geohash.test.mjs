@@ -0,0 +1,27 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The geohash module has no dependencies, so it is imported directly.
+import { encodeGeohash } from "../lib/geohash.mjs";
+
+describe("encodeGeohash", () => {
+  it("matches reference geohashes", () => {
+    assert.equal(encodeGeohash(57.64911, 10.40744, 11), "u4pruydqqvj");
+    assert.equal(encodeGeohash(42.605, -5.603, 5), "ezs42");
+    assert.equal(encodeGeohash(40.71277, -74.00597, 7), "dr5regw");
+  });
+
+  it("truncates consistently across precisions", () => {
+    const full = encodeGeohash(42.88645, -78.87837, 9);
+    for (let precision = 1; precision <= 9; precision += 1) {
+      assert.equal(encodeGeohash(42.88645, -78.87837, precision), full.slice(0, precision));
+    }
+  });
+
+  it("rejects out-of-range input", () => {
+    assert.throws(() => encodeGeohash(91, 0, 7));
+    assert.throws(() => encodeGeohash(0, -181, 7));
+    assert.throws(() => encodeGeohash(0, 0, 0));
+    assert.throws(() => encodeGeohash(Number.NaN, 0, 7));
+  });
+});
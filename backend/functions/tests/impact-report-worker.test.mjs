@@ -0,0 +1,148 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const TOP_CROPS_LIMIT = 5;
+const UNGROUPED = "other";
+
+function resolveWeekStart(now, override) {
+  if (override) {
+    const pinned = new Date(`${override}T00:00:00Z`);
+    if (Number.isNaN(pinned.getTime()) || pinned.getUTCDay() !== 1) {
+      throw new Error(`weekStart must be a Monday in YYYY-MM-DD format: ${override}`);
+    }
+    return pinned;
+  }
+
+  const today = Date.UTC(now.getUTCFullYear(), now.getUTCMonth(), now.getUTCDate());
+  const daysSinceMonday = (new Date(today).getUTCDay() + 6) % 7;
+  return new Date(today - (daysSinceMonday + 7) * 86_400_000);
+}
+
+function buildReports(totalRows, cropRows, groupRows = []) {
+  const cropsByGeo = new Map();
+  for (const row of cropRows) {
+    const crops = cropsByGeo.get(row.geo_boundary_key) ?? [];
+    crops.push({
+      cropId: row.crop_id,
+      cropName: row.crop_name,
+      quantity: row.quantity,
+      claimCount: row.claim_count,
+    });
+    cropsByGeo.set(row.geo_boundary_key, crops);
+  }
+
+  const groupsByGeo = new Map();
+  for (const row of groupRows) {
+    const groups = groupsByGeo.get(row.geo_boundary_key) ?? [];
+    groups.push({
+      group: row.nutrition_group ?? UNGROUPED,
+      quantity: row.quantity,
+      claimCount: row.claim_count,
+    });
+    groupsByGeo.set(row.geo_boundary_key, groups);
+  }
+
+  return totalRows.map((row) => ({
+    geoBoundaryKey: row.geo_boundary_key,
+    completedClaimCount: row.completed_claim_count,
+    quantityShared: row.quantity_shared,
+    uniqueGrowers: row.unique_growers,
+    uniqueGatherers: row.unique_gatherers,
+    topCrops: (cropsByGeo.get(row.geo_boundary_key) ?? [])
+      .sort((a, b) => b.quantity - a.quantity || b.claimCount - a.claimCount)
+      .slice(0, TOP_CROPS_LIMIT),
+    nutritionGroups: (groupsByGeo.get(row.geo_boundary_key) ?? []).sort(
+      (a, b) => b.quantity - a.quantity || a.group.localeCompare(b.group)
+    ),
+  }));
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveWeekStart", () => {
+  it("returns the previous Monday's week when run on a Monday", () => {
+    const weekStart = resolveWeekStart(new Date("2026-03-09T06:00:00Z"), undefined);
+    assert.equal(weekStart.toISOString(), "2026-03-02T00:00:00.000Z");
+  });
+
+  it("returns the last completed week mid-week", () => {
+    const weekStart = resolveWeekStart(new Date("2026-03-12T23:59:00Z"), undefined);
+    assert.equal(weekStart.toISOString(), "2026-03-02T00:00:00.000Z");
+  });
+
+  it("handles Sunday as the end of the current week", () => {
+    const weekStart = resolveWeekStart(new Date("2026-03-08T12:00:00Z"), undefined);
+    assert.equal(weekStart.toISOString(), "2026-02-23T00:00:00.000Z");
+  });
+
+  it("honours a pinned Monday override", () => {
+    const weekStart = resolveWeekStart(new Date(), "2026-01-05");
+    assert.equal(weekStart.toISOString(), "2026-01-05T00:00:00.000Z");
+  });
+
+  it("rejects overrides that are not Mondays", () => {
+    assert.throws(() => resolveWeekStart(new Date(), "2026-01-06"), /must be a Monday/);
+  });
+});
+
+describe("buildReports", () => {
+  const totals = [
+    {
+      geo_boundary_key: "9q8y",
+      completed_claim_count: 4,
+      quantity_shared: 12.5,
+      unique_growers: 2,
+      unique_gatherers: 3,
+    },
+    {
+      geo_boundary_key: "dr5r",
+      completed_claim_count: 1,
+      quantity_shared: 1,
+      unique_growers: 1,
+      unique_gatherers: 1,
+    },
+  ];
+
+  it("attaches top crops ordered by quantity to each geo", () => {
+    const crops = [
+      { geo_boundary_key: "9q8y", crop_id: "a", crop_name: "Kale", quantity: 2, claim_count: 1 },
+      { geo_boundary_key: "9q8y", crop_id: "b", crop_name: "Tomato", quantity: 10.5, claim_count: 3 },
+    ];
+    const [first, second] = buildReports(totals, crops);
+    assert.deepEqual(
+      first.topCrops.map((c) => c.cropName),
+      ["Tomato", "Kale"]
+    );
+    assert.deepEqual(second.topCrops, []);
+  });
+
+  it("caps top crops per geo", () => {
+    const crops = Array.from({ length: 8 }, (_, i) => ({
+      geo_boundary_key: "dr5r",
+      crop_id: `c${i}`,
+      crop_name: `Crop ${i}`,
+      quantity: i,
+      claim_count: 1,
+    }));
+    const report = buildReports(totals, crops).find((r) => r.geoBoundaryKey === "dr5r");
+    assert.equal(report.topCrops.length, TOP_CROPS_LIMIT);
+    assert.equal(report.topCrops[0].cropId, "c7");
+  });
+
+  it("rolls produce up by nutrition group, ungrouped crops as other", () => {
+    const groups = [
+      { geo_boundary_key: "9q8y", nutrition_group: "fruits", quantity: 3, claim_count: 1 },
+      { geo_boundary_key: "9q8y", nutrition_group: "leafy_greens", quantity: 8, claim_count: 2 },
+      { geo_boundary_key: "9q8y", nutrition_group: null, quantity: 1.5, claim_count: 1 },
+    ];
+    const [first, second] = buildReports(totals, [], groups);
+    assert.deepEqual(first.nutritionGroups, [
+      { group: "leafy_greens", quantity: 8, claimCount: 2 },
+      { group: "fruits", quantity: 3, claimCount: 1 },
+      { group: "other", quantity: 1.5, claimCount: 1 },
+    ]);
+    assert.deepEqual(second.nutritionGroups, []);
+  });
+});
@@ -0,0 +1,175 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const EVENT_BUS_NAME = "default";
+const DEFAULT_BATCH_SIZE = 200;
+const DEFAULT_MAX_BATCHES = 20;
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolveConfig(env, overrides = {}) {
+  return {
+    batchSize: parsePositiveInt(
+      overrides.batchSize ?? env.LISTING_SCHEDULE_BATCH_SIZE,
+      DEFAULT_BATCH_SIZE
+    ),
+    maxBatches: parsePositiveInt(
+      overrides.maxBatches ?? env.LISTING_SCHEDULE_MAX_BATCHES,
+      DEFAULT_MAX_BATCHES
+    ),
+  };
+}
+
+function buildStatusChangedEntries(rows, correlationId, occurredAt) {
+  return rows.map((row) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "listing.status_changed",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      listingId: row.id,
+      userId: row.user_id,
+      cropId: row.crop_id ?? null,
+      status: row.status,
+      previousStatus: row.previous_status,
+      ...(row.geo_key ? { geoKey: row.geo_key } : {}),
+      correlationId,
+      occurredAt,
+    }),
+  }));
+}
+
+async function runSweep(client, sweep, now, batchSize, maxBatches, publish) {
+  let affected = 0;
+  let failedEvents = 0;
+  let batches = 0;
+
+  while (batches < maxBatches) {
+    const { rows } = await client.query(sweep.sql, [now, batchSize]);
+    batches += 1;
+    affected += rows.length;
+    if (rows.length > 0) {
+      failedEvents += await publish(rows);
+    }
+    if (rows.length < batchSize) {
+      return { affected, failedEvents, batches, exhausted: true };
+    }
+  }
+
+  return { affected, failedEvents, batches, exhausted: false };
+}
+
+function listingRow(id, overrides = {}) {
+  return {
+    id,
+    user_id: "grower-1",
+    crop_id: "crop-1",
+    status: "active",
+    geo_key: "9q8yyk8",
+    previous_status: "scheduled",
+    ...overrides,
+  };
+}
+
+function fakeClient(batches) {
+  const calls = [];
+  return {
+    calls,
+    async query(sql, params) {
+      calls.push(params);
+      return { rows: batches[calls.length - 1] ?? [] };
+    },
+  };
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveConfig", () => {
+  it("uses defaults when nothing is configured", () => {
+    assert.deepEqual(resolveConfig({}), { batchSize: 200, maxBatches: 20 });
+  });
+
+  it("lets invocation overrides win over env and ignores invalid values", () => {
+    const config = resolveConfig(
+      { LISTING_SCHEDULE_BATCH_SIZE: "50", LISTING_SCHEDULE_MAX_BATCHES: "0" },
+      { batchSize: 5 }
+    );
+    assert.equal(config.batchSize, 5);
+    assert.equal(config.maxBatches, DEFAULT_MAX_BATCHES);
+  });
+});
+
+describe("buildStatusChangedEntries", () => {
+  it("mirrors the API's listing.status_changed detail", () => {
+    const [entry] = buildStatusChangedEntries(
+      [listingRow("listing-1")],
+      "corr-1",
+      "2026-07-01T12:00:00.000Z"
+    );
+    assert.equal(entry.Source, "community-garden.workers");
+    assert.equal(entry.DetailType, "listing.status_changed");
+    assert.deepEqual(JSON.parse(entry.Detail), {
+      schemaVersion: 1,
+      listingId: "listing-1",
+      userId: "grower-1",
+      cropId: "crop-1",
+      status: "active",
+      previousStatus: "scheduled",
+      geoKey: "9q8yyk8",
+      correlationId: "corr-1",
+      occurredAt: "2026-07-01T12:00:00.000Z",
+    });
+  });
+
+  it("leaves out a missing geo key and nulls a missing crop", () => {
+    const [entry] = buildStatusChangedEntries(
+      [listingRow("listing-2", { crop_id: null, geo_key: null, status: "expired", previous_status: "pending" })],
+      "corr-1",
+      "2026-07-01T12:00:00.000Z"
+    );
+    const detail = JSON.parse(entry.Detail);
+    assert.equal(detail.cropId, null);
+    assert.equal("geoKey" in detail, false);
+    assert.equal(detail.previousStatus, "pending");
+  });
+});
+
+describe("runSweep", () => {
+  const sweep = { name: "published", sql: "select 1" };
+  const now = new Date("2026-07-01T12:00:00Z");
+
+  it("publishes each batch's changes until a short batch", async () => {
+    const client = fakeClient([[listingRow("a"), listingRow("b")], [listingRow("c")]]);
+    const published = [];
+    const result = await runSweep(client, sweep, now, 2, 20, async (rows) => {
+      published.push(rows.map((row) => row.id));
+      return 0;
+    });
+    assert.deepEqual(result, { affected: 3, failedEvents: 0, batches: 2, exhausted: true });
+    assert.deepEqual(published, [["a", "b"], ["c"]]);
+    assert.deepEqual(client.calls[0], [now, 2]);
+  });
+
+  it("skips publishing an empty batch and counts failed events", async () => {
+    const client = fakeClient([[listingRow("a")], []]);
+    let publishes = 0;
+    const result = await runSweep(client, sweep, now, 1, 20, async () => {
+      publishes += 1;
+      return 1;
+    });
+    assert.deepEqual(result, { affected: 1, failedEvents: 1, batches: 2, exhausted: true });
+    assert.equal(publishes, 1);
+  });
+
+  it("reports when the batch limit leaves listings behind", async () => {
+    const client = fakeClient([[listingRow("a")], [listingRow("b")], [listingRow("c")]]);
+    const result = await runSweep(client, sweep, now, 1, 2, async () => 0);
+    assert.deepEqual(result, { affected: 2, failedEvents: 0, batches: 2, exhausted: false });
+  });
+});
@@ -0,0 +1,42 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The metrics module has no pg dependency, so it is imported directly.
+import { buildMetricRecord, emitMetrics } from "../lib/metrics.mjs";
+
+describe("buildMetricRecord", () => {
+  it("produces an EMF directive for every metric", () => {
+    const record = buildMetricRecord({
+      dimensions: { Service: "workers", Worker: "rolling-geo-aggregation" },
+      metrics: { Invocations: 1, Latency: 42 },
+      units: { Latency: "Milliseconds" },
+      properties: { correlationId: "corr-1" },
+      timestamp: 1_700_000_000_000,
+    });
+
+    assert.equal(record.Worker, "rolling-geo-aggregation");
+    assert.equal(record.Latency, 42);
+    assert.equal(record.correlationId, "corr-1");
+    const [directive] = record._aws.CloudWatchMetrics;
+    assert.equal(directive.Namespace, "CommunityGarden");
+    assert.deepEqual(directive.Dimensions, [["Service", "Worker"]]);
+    assert.deepEqual(directive.Metrics, [
+      { Name: "Invocations", Unit: "Count" },
+      { Name: "Latency", Unit: "Milliseconds" },
+    ]);
+  });
+});
+
+describe("emitMetrics", () => {
+  it("writes a single JSON line tagged with the worker", (t) => {
+    const lines = [];
+    t.mock.method(console, "log", (line) => lines.push(line));
+
+    emitMetrics("impact-report-worker", { ReportsGenerated: 3 });
+
+    assert.equal(lines.length, 1);
+    const record = JSON.parse(lines[0]);
+    assert.equal(record.Worker, "impact-report-worker");
+    assert.equal(record.ReportsGenerated, 3);
+  });
+});
@@ -0,0 +1,81 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The moderation module has no pg dependency, so it is imported directly.
+import {
+  classifyWithRules,
+  decideAction,
+  excerpt,
+  moderationText,
+  parseModelCategories,
+} from "../lib/moderation.mjs";
+
+describe("classifyWithRules", () => {
+  it("passes ordinary listing text", () => {
+    assert.deepEqual(classifyWithRules("Extra zucchini, pick up by the side gate after 5pm"), []);
+  });
+
+  it("detects contact details", () => {
+    assert.deepEqual(classifyWithRules("email me at grower@example.com"), ["contact_info"]);
+    assert.deepEqual(classifyWithRules("call 555-123-4567 for pickup"), ["contact_info"]);
+    assert.deepEqual(classifyWithRules("send me your number"), ["contact_info"]);
+  });
+
+  it("detects sales solicitations", () => {
+    assert.deepEqual(classifyWithRules("Tomatoes $3/lb"), ["solicitation"]);
+    assert.deepEqual(classifyWithRules("venmo accepted"), ["solicitation"]);
+  });
+
+  it("detects abuse and reports every matching category", () => {
+    assert.deepEqual(classifyWithRules("you idiot, venmo me"), ["solicitation", "abuse"]);
+  });
+});
+
+describe("decideAction", () => {
+  it("does nothing for clean text", () => {
+    assert.equal(decideAction("listing", []), null);
+  });
+
+  it("flags contact info and holds other listing problems", () => {
+    assert.equal(decideAction("listing", ["contact_info"]), "flag");
+    assert.equal(decideAction("listing", ["contact_info", "solicitation"]), "hold");
+    assert.equal(decideAction("listing", ["abuse"]), "hold");
+  });
+
+  it("holds listing questions and answers like listings", () => {
+    assert.equal(decideAction("listing_question", ["contact_info"]), "flag");
+    assert.equal(decideAction("listing_question", ["solicitation"]), "hold");
+    assert.equal(decideAction("listing_answer", ["abuse"]), "hold");
+  });
+
+  it("only flags messages", () => {
+    assert.equal(decideAction("message", ["abuse"]), "flag");
+  });
+});
+
+describe("moderationText and excerpt", () => {
+  it("joins non-empty fields", () => {
+    assert.equal(moderationText([" Kale ", null, "", "side gate"]), "Kale\nside gate");
+  });
+
+  it("truncates long text", () => {
+    const text = "a".repeat(300);
+    assert.equal(excerpt(text).length, 280);
+    assert.equal(excerpt("short"), "short");
+  });
+});
+
+describe("parseModelCategories", () => {
+  it("keeps known categories from a JSON reply", () => {
+    assert.deepEqual(
+      parseModelCategories('Sure: {"categories":["abuse","spam","contact_info"]}'),
+      ["contact_info", "abuse"]
+    );
+    assert.deepEqual(parseModelCategories('{"categories":[]}'), []);
+  });
+
+  it("rejects replies without categories", () => {
+    assert.throws(() => parseModelCategories("no json here"));
+    assert.throws(() => parseModelCategories('{"labels":[]}'));
+  });
+});
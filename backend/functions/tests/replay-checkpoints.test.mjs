@@ -0,0 +1,92 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions so we can test without pg ──────────────────────
+
+const DEFAULT_PIPELINE = "derived_supply_signals";
+
+function parseArgs(argv) {
+  const args = { action: null, pipeline: DEFAULT_PIPELINE, mode: null };
+
+  for (let i = 0; i < argv.length; i += 1) {
+    const arg = argv[i];
+    if (arg === "--inspect" || arg === "--reset") {
+      if (args.action) throw new Error("Use only one of --inspect or --reset");
+      args.action = arg.slice(2);
+    } else if (arg === "--pipeline" || arg === "--mode") {
+      const value = argv[i + 1];
+      if (!value || value.startsWith("--")) throw new Error(`${arg} requires a value`);
+      args[arg.slice(2)] = value;
+      i += 1;
+    } else {
+      throw new Error(`Unknown argument: ${arg}`);
+    }
+  }
+
+  if (!args.action) throw new Error("One of --inspect or --reset is required");
+  if (args.action === "reset" && !args.mode) throw new Error("--reset requires --mode");
+  return args;
+}
+
+function resumeFromCheckpoint(event, checkpoint) {
+  if (event.cursor !== undefined || !checkpoint || checkpoint.status !== "running") {
+    return { ...event, processedScopes: event.processedScopes ?? 0 };
+  }
+  return {
+    ...event,
+    cursor: checkpoint.cursor,
+    runStartedAt: new Date(checkpoint.run_started_at).toISOString(),
+    processedScopes: checkpoint.processed_scopes,
+  };
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("parseArgs", () => {
+  it("inspects every mode of the default pipeline", () => {
+    assert.deepEqual(parseArgs(["--inspect"]), {
+      action: "inspect",
+      pipeline: DEFAULT_PIPELINE,
+      mode: null,
+    });
+  });
+
+  it("requires a mode to reset", () => {
+    assert.throws(() => parseArgs(["--reset"]), /requires --mode/);
+    assert.equal(parseArgs(["--reset", "--mode", "full"]).mode, "full");
+  });
+
+  it("rejects conflicting or unknown flags", () => {
+    assert.throws(() => parseArgs(["--inspect", "--reset"]), /only one/);
+    assert.throws(() => parseArgs(["--inspect", "--force"]), /Unknown argument/);
+    assert.throws(() => parseArgs(["--inspect", "--mode"]), /requires a value/);
+  });
+});
+
+describe("resumeFromCheckpoint", () => {
+  const running = {
+    cursor: "9q8y|",
+    run_started_at: "2026-03-01T00:00:00Z",
+    processed_scopes: 400,
+    status: "running",
+  };
+
+  it("resumes a running checkpoint when no cursor is passed", () => {
+    const resumed = resumeFromCheckpoint({ mode: "full" }, running);
+    assert.equal(resumed.cursor, "9q8y|");
+    assert.equal(resumed.processedScopes, 400);
+    assert.equal(resumed.runStartedAt, "2026-03-01T00:00:00.000Z");
+  });
+
+  it("prefers an explicit cursor from the payload", () => {
+    const resumed = resumeFromCheckpoint({ cursor: "dr5r|", processedScopes: 10 }, running);
+    assert.equal(resumed.cursor, "dr5r|");
+    assert.equal(resumed.processedScopes, 10);
+  });
+
+  it("starts over after a completed run", () => {
+    const resumed = resumeFromCheckpoint({}, { ...running, status: "completed" });
+    assert.equal(resumed.cursor, undefined);
+    assert.equal(resumed.processedScopes, 0);
+  });
+});
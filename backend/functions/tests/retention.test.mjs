@@ -0,0 +1,92 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The retention module has no pg dependency, so it is imported directly.
+import {
+  DEFAULT_BATCH_SIZE,
+  DEFAULT_MAX_BATCHES,
+  planPurge,
+  purgeBatchSql,
+  resolveRetentionConfig,
+  selectPolicies,
+} from "../lib/retention.mjs";
+
+const NOW = new Date("2026-06-01T00:00:00Z");
+
+function policy(overrides = {}) {
+  return {
+    entity: "messages",
+    table_name: "messages",
+    timestamp_column: "created_at",
+    retention_days: 365,
+    enabled: true,
+    ...overrides,
+  };
+}
+
+describe("resolveRetentionConfig", () => {
+  it("uses defaults when nothing is configured", () => {
+    const config = resolveRetentionConfig({});
+    assert.equal(config.batchSize, DEFAULT_BATCH_SIZE);
+    assert.equal(config.maxBatches, DEFAULT_MAX_BATCHES);
+    assert.equal(config.entities, null);
+  });
+
+  it("prefers event overrides over env and ignores invalid values", () => {
+    const config = resolveRetentionConfig(
+      { PURGE_BATCH_SIZE: "250", PURGE_MAX_BATCHES: "-1" },
+      { batchSize: 10, entities: ["audit_log"] }
+    );
+    assert.equal(config.batchSize, 10);
+    assert.equal(config.maxBatches, DEFAULT_MAX_BATCHES);
+    assert.deepEqual(config.entities, ["audit_log"]);
+  });
+});
+
+describe("planPurge", () => {
+  it("computes the cutoff from retention days", () => {
+    const plan = planPurge(policy(), NOW);
+    assert.equal(plan.cutoff.toISOString(), "2025-06-01T00:00:00.000Z");
+    assert.equal(plan.guarded, false);
+  });
+
+  it("treats zero days on expires_at as purge on expiry", () => {
+    const plan = planPurge(
+      policy({ entity: "derived_supply_signals", table_name: "derived_supply_signals", timestamp_column: "expires_at", retention_days: 0 }),
+      NOW
+    );
+    assert.equal(plan.cutoff.getTime(), NOW.getTime());
+  });
+
+  it("marks the append-only audit log as guarded", () => {
+    const plan = planPurge(policy({ entity: "audit_log", table_name: "audit_log", timestamp_column: "occurred_at", retention_days: 2555 }), NOW);
+    assert.equal(plan.guarded, true);
+  });
+
+  it("refuses identifiers that are not plain names", () => {
+    assert.equal(planPurge(policy({ table_name: "messages; drop table users" }), NOW).skip, "invalid_identifier");
+    assert.equal(planPurge(policy({ timestamp_column: "Created_At" }), NOW).skip, "invalid_identifier");
+    assert.equal(planPurge(policy({ retention_days: -1 }), NOW).skip, "invalid_retention_days");
+  });
+});
+
+describe("purgeBatchSql", () => {
+  it("deletes the oldest rows past the cutoff in one bounded batch", () => {
+    const sql = purgeBatchSql(planPurge(policy(), NOW));
+    assert.match(sql, /DELETE FROM messages/);
+    assert.match(sql, /WHERE created_at < \$1/);
+    assert.match(sql, /LIMIT \$2/);
+  });
+});
+
+describe("selectPolicies", () => {
+  const policies = [policy(), policy({ entity: "geocode_cache", enabled: false }), policy({ entity: "audit_log" })];
+
+  it("keeps only enabled policies", () => {
+    assert.deepEqual(selectPolicies(policies, null).map((p) => p.entity), ["messages", "audit_log"]);
+  });
+
+  it("narrows to the requested entities", () => {
+    assert.deepEqual(selectPolicies(policies, ["audit_log", "geocode_cache"]).map((p) => p.entity), ["audit_log"]);
+  });
+});
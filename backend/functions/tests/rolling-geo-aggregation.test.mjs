@@ -42,13 +42,53 @@ function retentionDays(windowDays) {
   return 90;
 }
 
+// Schema v1 events carry geoKey/cropId so the worker can skip the re-query.
+// Older (unversioned) events fall back to loading scope from the database.
+function payloadScope(detail) {
+  if (!detail.schemaVersion || !detail.geoKey || !detail.cropId) return null;
+  return { geoKey: detail.geoKey, cropId: detail.cropId };
+}
+
+// Supply is what was listed minus what is already promised (confirmed) or
+// handed over (completed); pending claims are still negotiable.
+function computeSignal(listingRow, requestRow, claimRow, windowDays) {
+  const listingCount = listingRow.listing_count;
+  const requestCount = requestRow.request_count;
+  const committedQuantity = claimRow.confirmed_quantity + claimRow.completed_quantity;
+  const supplyQuantity = Math.max(0, listingRow.listed_quantity - committedQuantity);
+  const demandQuantity = requestRow.demand_quantity;
+  const resolvedClaims = claimRow.completed_count + claimRow.failed_count;
+  const fulfillmentRate =
+    resolvedClaims === 0 ? null : Number((claimRow.completed_count / resolvedClaims).toFixed(4));
+
+  return {
+    listingCount,
+    requestCount,
+    supplyQuantity,
+    demandQuantity,
+    scarcityScore: demandQuantity / (supplyQuantity + 1),
+    abundanceScore: supplyQuantity / (demandQuantity + 1),
+    signalPayload: {
+      listingCount,
+      requestCount,
+      windowDays,
+      claimCount: claimRow.claim_count,
+      completedClaimCount: claimRow.completed_count,
+      confirmedQuantity: claimRow.confirmed_quantity,
+      completedQuantity: claimRow.completed_quantity,
+      fulfillmentRate,
+    },
+  };
+}
+
 function parseEvent(detailType, detail) {
   switch (detailType) {
     case "listing.created":
     case "listing.updated":
+    case "listing.status_changed":
       if (!detail.listingId) throw new Error(`Missing listingId in ${detailType}`);
       return {
-        domain: { type: "listing", listingId: detail.listingId },
+        domain: { type: "listing", listingId: detail.listingId, scope: payloadScope(detail) },
         occurredAt: detail.occurredAt ?? new Date().toISOString(),
         correlationId: detail.correlationId ?? "unknown-correlation-id",
       };
@@ -56,7 +96,7 @@ function parseEvent(detailType, detail) {
     case "request.updated":
       if (!detail.requestId) throw new Error(`Missing requestId in ${detailType}`);
       return {
-        domain: { type: "request", requestId: detail.requestId },
+        domain: { type: "request", requestId: detail.requestId, scope: payloadScope(detail) },
         occurredAt: detail.occurredAt ?? new Date().toISOString(),
         correlationId: detail.correlationId ?? "unknown-correlation-id",
       };
@@ -120,6 +160,44 @@ describe("parseEvent", () => {
     assert.throws(() => parseEvent("unknown.event", {}), /Unsupported detail type/);
   });
 
+  it("uses geoKey and cropId from a versioned listing payload", () => {
+    const detail = {
+      schemaVersion: 1,
+      listingId: "8b5a1a3e-d7ad-4ca4-9f56-2f188db4e6ef",
+      cropId: "33333333-3333-3333-3333-333333333333",
+      geoKey: "9q8yyk8",
+    };
+    const { domain } = parseEvent("listing.updated", detail);
+    assert.deepEqual(domain.scope, {
+      geoKey: "9q8yyk8",
+      cropId: "33333333-3333-3333-3333-333333333333",
+    });
+  });
+
+  it("treats a listing status change as a listing event", () => {
+    const detail = {
+      schemaVersion: 1,
+      listingId: "8b5a1a3e-d7ad-4ca4-9f56-2f188db4e6ef",
+      cropId: "33333333-3333-3333-3333-333333333333",
+      status: "completed",
+      previousStatus: "active",
+      geoKey: "9q8yyk8",
+    };
+    const { domain } = parseEvent("listing.status_changed", detail);
+    assert.equal(domain.type, "listing");
+    assert.equal(domain.listingId, "8b5a1a3e-d7ad-4ca4-9f56-2f188db4e6ef");
+  });
+
+  it("falls back to a re-query for unversioned request payloads", () => {
+    const detail = {
+      requestId: "aaaaaaaa-bbbb-cccc-dddd-eeeeeeeeeeee",
+      cropId: "33333333-3333-3333-3333-333333333333",
+      geoKey: "9q8yyk8",
+    };
+    const { domain } = parseEvent("request.created", detail);
+    assert.equal(domain.scope, null);
+  });
+
   it("defaults correlationId when missing", () => {
     const detail = { listingId: "8b5a1a3e-d7ad-4ca4-9f56-2f188db4e6ef" };
     const { correlationId } = parseEvent("listing.created", detail);
@@ -197,3 +275,58 @@ describe("retentionDays", () => {
     assert.equal(retentionDays(30), 90);
   });
 });
+
+describe("computeSignal", () => {
+  const listingRow = { listing_count: 2, listed_quantity: 10 };
+  const requestRow = { request_count: 1, demand_quantity: 4 };
+
+  it("subtracts confirmed and completed claim quantities from supply", () => {
+    const claimRow = {
+      claim_count: 3,
+      completed_count: 1,
+      failed_count: 0,
+      confirmed_quantity: 2,
+      completed_quantity: 3,
+    };
+    const signal = computeSignal(listingRow, requestRow, claimRow, 7);
+    assert.equal(signal.supplyQuantity, 5);
+    assert.equal(signal.scarcityScore, 4 / 6);
+    assert.equal(signal.abundanceScore, 1);
+  });
+
+  it("never reports negative supply", () => {
+    const claimRow = {
+      claim_count: 1,
+      completed_count: 1,
+      failed_count: 0,
+      confirmed_quantity: 0,
+      completed_quantity: 25,
+    };
+    assert.equal(computeSignal(listingRow, requestRow, claimRow, 7).supplyQuantity, 0);
+  });
+
+  it("reports fulfillment rate over resolved claims only", () => {
+    const claimRow = {
+      claim_count: 5,
+      completed_count: 3,
+      failed_count: 1,
+      confirmed_quantity: 0,
+      completed_quantity: 0,
+    };
+    const { signalPayload } = computeSignal(listingRow, requestRow, claimRow, 14);
+    assert.equal(signalPayload.fulfillmentRate, 0.75);
+    assert.equal(signalPayload.claimCount, 5);
+    assert.equal(signalPayload.windowDays, 14);
+  });
+
+  it("leaves fulfillment rate null when no claims have resolved", () => {
+    const claimRow = {
+      claim_count: 2,
+      completed_count: 0,
+      failed_count: 0,
+      confirmed_quantity: 1,
+      completed_quantity: 0,
+    };
+    assert.equal(computeSignal(listingRow, requestRow, claimRow, 30).signalPayload.fulfillmentRate, null);
+  });
+});
@@ -0,0 +1,96 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── pure logic mirrored from worker ──────────────────────────────────────────
+
+const EVENT_BUS_NAME = "default";
+
+function shouldAlert(detail) {
+  return (
+    Boolean(detail.listingId && detail.userId && detail.geoKey) && detail.status === "active"
+  );
+}
+
+function geoPrefixes(geoKey) {
+  const key = String(geoKey).toLowerCase();
+  const prefixes = [];
+  for (let length = 1; length <= key.length; length++) {
+    prefixes.push(key.slice(0, length));
+  }
+  return prefixes;
+}
+
+function buildAlertEntries(detail, matches, occurredAt) {
+  return matches.map((match) => ({
+    EventBusName: EVENT_BUS_NAME,
+    Source: "community-garden.workers",
+    DetailType: "organization.service_area_listing",
+    Detail: JSON.stringify({
+      schemaVersion: 1,
+      organizationId: match.organizationId,
+      serviceAreaIds: match.serviceAreaIds,
+      listingId: detail.listingId,
+      growerId: detail.userId,
+      cropId: detail.cropId ?? null,
+      geoKey: detail.geoKey,
+      correlationId: detail.correlationId ?? "unknown",
+      occurredAt,
+    }),
+  }));
+}
+
+const LISTING = {
+  listingId: "l-1",
+  userId: "g-1",
+  cropId: "c-1",
+  status: "active",
+  geoKey: "dr5ru7k",
+  correlationId: "corr-1",
+};
+
+// ── tests ────────────────────────────────────────────────────────────────────
+
+describe("shouldAlert", () => {
+  it("alerts only for active listings with a geo key", () => {
+    assert.equal(shouldAlert(LISTING), true);
+    assert.equal(shouldAlert({ ...LISTING, status: "pending" }), false);
+    assert.equal(shouldAlert({ ...LISTING, geoKey: undefined }), false);
+    assert.equal(shouldAlert({ ...LISTING, listingId: undefined }), false);
+    assert.equal(shouldAlert({}), false);
+  });
+});
+
+describe("geoPrefixes", () => {
+  it("lists every prefix shortest first", () => {
+    assert.deepEqual(geoPrefixes("DR5R"), ["d", "dr", "dr5", "dr5r"]);
+    assert.deepEqual(geoPrefixes(""), []);
+  });
+});
+
+describe("buildAlertEntries", () => {
+  it("builds one event per matching organization", () => {
+    const matches = [
+      { organizationId: "o-1", serviceAreaIds: ["a-1", "a-2"] },
+      { organizationId: "o-2", serviceAreaIds: ["a-3"] },
+    ];
+    const entries = buildAlertEntries(LISTING, matches, "2026-10-16T00:00:00.000Z");
+    assert.equal(entries.length, 2);
+    assert.equal(entries[0].DetailType, "organization.service_area_listing");
+    assert.equal(entries[0].Source, "community-garden.workers");
+    assert.deepEqual(JSON.parse(entries[0].Detail), {
+      schemaVersion: 1,
+      organizationId: "o-1",
+      serviceAreaIds: ["a-1", "a-2"],
+      listingId: "l-1",
+      growerId: "g-1",
+      cropId: "c-1",
+      geoKey: "dr5ru7k",
+      correlationId: "corr-1",
+      occurredAt: "2026-10-16T00:00:00.000Z",
+    });
+  });
+
+  it("returns no entries without matches", () => {
+    assert.deepEqual(buildAlertEntries(LISTING, [], "2026-10-16T00:00:00.000Z"), []);
+  });
+});
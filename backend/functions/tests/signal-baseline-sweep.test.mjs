@@ -0,0 +1,55 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const DEFAULT_STALE_AFTER_MINUTES = 6 * 60;
+const DEFAULT_MAX_SCOPES = 500;
+
+function resolveSweepConfig(env, overrides = {}) {
+  const staleAfterMinutes = Number.parseInt(
+    String(overrides.staleAfterMinutes ?? env.SIGNAL_STALE_AFTER_MINUTES ?? DEFAULT_STALE_AFTER_MINUTES),
+    10
+  );
+  const maxScopes = Number.parseInt(
+    String(overrides.maxScopes ?? env.SIGNAL_SWEEP_MAX_SCOPES ?? DEFAULT_MAX_SCOPES),
+    10
+  );
+
+  return {
+    staleAfterMinutes:
+      Number.isInteger(staleAfterMinutes) && staleAfterMinutes > 0
+        ? staleAfterMinutes
+        : DEFAULT_STALE_AFTER_MINUTES,
+    maxScopes: Number.isInteger(maxScopes) && maxScopes > 0 ? maxScopes : DEFAULT_MAX_SCOPES,
+  };
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolveSweepConfig", () => {
+  it("uses defaults when nothing is configured", () => {
+    assert.deepEqual(resolveSweepConfig({}), {
+      staleAfterMinutes: DEFAULT_STALE_AFTER_MINUTES,
+      maxScopes: DEFAULT_MAX_SCOPES,
+    });
+  });
+
+  it("reads thresholds from env", () => {
+    const config = resolveSweepConfig({
+      SIGNAL_STALE_AFTER_MINUTES: "90",
+      SIGNAL_SWEEP_MAX_SCOPES: "25",
+    });
+    assert.equal(config.staleAfterMinutes, 90);
+    assert.equal(config.maxScopes, 25);
+  });
+
+  it("lets invocation overrides win and ignores invalid values", () => {
+    const config = resolveSweepConfig(
+      { SIGNAL_STALE_AFTER_MINUTES: "90", SIGNAL_SWEEP_MAX_SCOPES: "nope" },
+      { staleAfterMinutes: 15 }
+    );
+    assert.equal(config.staleAfterMinutes, 15);
+    assert.equal(config.maxScopes, DEFAULT_MAX_SCOPES);
+  });
+});
@@ -0,0 +1,145 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const MIN_SAMPLES = 14;
+const HORIZONS_DAYS = [7, 14];
+
+// Least-squares trend over the daily series plus an additive day-of-week index
+// (mean residual per weekday). Good enough to show direction a week or two out
+// without pulling in a stats dependency.
+function fitSeasonalTrend(points) {
+  const n = points.length;
+  // Days since the first sample, so gaps in the history don't skew the slope.
+  const xs = points.map((p) => Math.round((p.date - points[0].date) / 86_400_000));
+  const meanX = xs.reduce((a, b) => a + b, 0) / n;
+  const meanY = points.reduce((a, p) => a + p.value, 0) / n;
+
+  let num = 0;
+  let den = 0;
+  for (let i = 0; i < n; i += 1) {
+    num += (xs[i] - meanX) * (points[i].value - meanY);
+    den += (xs[i] - meanX) ** 2;
+  }
+  const slope = den === 0 ? 0 : num / den;
+  const intercept = meanY - slope * meanX;
+
+  const residuals = Array.from({ length: 7 }, () => []);
+  points.forEach((p, i) => {
+    residuals[p.date.getUTCDay()].push(p.value - (intercept + slope * xs[i]));
+  });
+  const seasonal = residuals.map((r) => (r.length === 0 ? 0 : r.reduce((a, b) => a + b, 0) / r.length));
+
+  return { slope, intercept, seasonal, lastIndex: xs[n - 1], lastDate: points[n - 1].date };
+}
+
+function project(model, horizonDays) {
+  const target = new Date(model.lastDate.getTime() + horizonDays * 86_400_000);
+  const value =
+    model.intercept + model.slope * (model.lastIndex + horizonDays) + model.seasonal[target.getUTCDay()];
+  return { targetDate: target, value: Math.max(0, Number(value.toFixed(4))) };
+}
+
+function forecastScope(history, horizons = HORIZONS_DAYS) {
+  if (history.length < MIN_SAMPLES) return [];
+
+  const scarcity = fitSeasonalTrend(history.map((h) => ({ date: h.date, value: h.scarcity })));
+  const abundance = fitSeasonalTrend(history.map((h) => ({ date: h.date, value: h.abundance })));
+
+  return horizons.map((horizonDays) => {
+    const s = project(scarcity, horizonDays);
+    const a = project(abundance, horizonDays);
+    return {
+      horizonDays,
+      targetDate: s.targetDate,
+      projectedScarcity: s.value,
+      projectedAbundance: a.value,
+      sampleCount: history.length,
+    };
+  });
+}
+
+function groupHistory(rows) {
+  const scopes = new Map();
+  for (const row of rows) {
+    const key = `${row.geo_boundary_key}|${row.crop_id ?? ""}|${row.window_days}`;
+    if (!scopes.has(key)) {
+      scopes.set(key, {
+        geoBoundaryKey: row.geo_boundary_key,
+        cropId: row.crop_id,
+        windowDays: row.window_days,
+        history: [],
+      });
+    }
+    scopes.get(key).history.push({
+      date: new Date(row.day),
+      scarcity: Number(row.scarcity_score),
+      abundance: Number(row.abundance_score),
+    });
+  }
+  return [...scopes.values()];
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+function dailySeries(days, fn) {
+  const start = Date.UTC(2026, 1, 2); // Monday
+  return Array.from({ length: days }, (_, i) => {
+    const date = new Date(start + i * 86_400_000);
+    return { date, ...fn(i, date) };
+  });
+}
+
+describe("fitSeasonalTrend", () => {
+  it("recovers a straight-line trend", () => {
+    const points = dailySeries(21, (i) => ({ value: 1 + 0.5 * i }));
+    const model = fitSeasonalTrend(points);
+    assert.ok(Math.abs(model.slope - 0.5) < 1e-9);
+    assert.ok(Math.abs(model.intercept - 1) < 1e-9);
+  });
+
+  it("captures a weekday bump in the seasonal index", () => {
+    const points = dailySeries(28, (_, date) => ({ value: date.getUTCDay() === 6 ? 3 : 1 }));
+    const model = fitSeasonalTrend(points);
+    assert.ok(model.seasonal[6] > model.seasonal[2]);
+  });
+});
+
+describe("forecastScope", () => {
+  it("skips scopes without enough history", () => {
+    const history = dailySeries(MIN_SAMPLES - 1, () => ({ scarcity: 1, abundance: 1 }));
+    assert.deepEqual(forecastScope(history), []);
+  });
+
+  it("projects each horizon from the last sample", () => {
+    const history = dailySeries(28, (i) => ({ scarcity: 0.1 * i, abundance: 2 }));
+    const forecasts = forecastScope(history);
+    assert.deepEqual(
+      forecasts.map((f) => f.horizonDays),
+      HORIZONS_DAYS
+    );
+    assert.ok(forecasts[1].projectedScarcity > forecasts[0].projectedScarcity);
+    assert.equal(forecasts[0].projectedAbundance, 2);
+    assert.equal(forecasts[0].targetDate.toISOString().slice(0, 10), "2026-03-08");
+  });
+
+  it("never projects negative scores", () => {
+    const history = dailySeries(21, (i) => ({ scarcity: 5 - i * 0.5, abundance: 0 }));
+    const [, twoWeeks] = forecastScope(history);
+    assert.equal(twoWeeks.projectedScarcity, 0);
+  });
+});
+
+describe("groupHistory", () => {
+  it("groups rows by geo, crop, and window", () => {
+    const rows = [
+      { geo_boundary_key: "9q8y", crop_id: null, window_days: 7, day: "2026-02-01", scarcity_score: 1, abundance_score: 0 },
+      { geo_boundary_key: "9q8y", crop_id: null, window_days: 7, day: "2026-02-02", scarcity_score: 2, abundance_score: 0 },
+      { geo_boundary_key: "9q8y", crop_id: "c1", window_days: 7, day: "2026-02-01", scarcity_score: 1, abundance_score: 0 },
+    ];
+    const scopes = groupHistory(rows);
+    assert.equal(scopes.length, 2);
+    assert.equal(scopes[0].history.length, 2);
+  });
+});
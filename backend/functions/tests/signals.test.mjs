@@ -0,0 +1,178 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The shared signals module has no pg dependency, so it is imported directly.
+import {
+  GEO_PRECISIONS,
+  SUPPORTED_WINDOWS_DAYS,
+  computeBucketStart,
+  computeSignal,
+  expandGeoScopes,
+  geoPrefixes,
+  normalizeRecomputeScope,
+  recomputeAndUpsert,
+  retentionDays,
+} from "../lib/signals.mjs";
+
+function fakeClient(rows) {
+  const calls = [];
+  return {
+    calls,
+    async query(text, params) {
+      calls.push({ text, params });
+      if (text.includes("FROM surplus_listings") && !text.includes("JOIN")) {
+        return { rows: [rows.listing] };
+      }
+      if (text.includes("FROM requests")) return { rows: [rows.request] };
+      if (text.includes("FROM claims")) return { rows: [rows.claim] };
+      if (text.includes("FROM harvests") && rows.harvest) return { rows: [rows.harvest] };
+      if (text.includes("FROM crop_plans") && rows.planned) return { rows: [rows.planned] };
+      return { rows: [] };
+    },
+  };
+}
+
+describe("shared signal constants", () => {
+  it("exposes the supported windows and precisions", () => {
+    assert.deepEqual(SUPPORTED_WINDOWS_DAYS, [7, 14, 30]);
+    assert.deepEqual(GEO_PRECISIONS, [4, 5, 6]);
+  });
+
+  it("keeps a retention period longer than every window", () => {
+    for (const windowDays of SUPPORTED_WINDOWS_DAYS) {
+      assert.ok(retentionDays(windowDays) > windowDays);
+    }
+  });
+});
+
+describe("geo scope helpers", () => {
+  it("derives prefixes and scopes consistently", () => {
+    assert.deepEqual(geoPrefixes("9Q8YYK8"), ["9q8y", "9q8yy", "9q8yyk"]);
+    assert.equal(expandGeoScopes([{ geoKey: "9q8yyk8", cropId: "c1" }]).length, 6);
+  });
+
+  it("buckets timestamps to five minutes", () => {
+    assert.equal(
+      computeBucketStart("2026-02-20T21:07:59Z").toISOString(),
+      "2026-02-20T21:05:00.000Z"
+    );
+  });
+});
+
+describe("normalizeRecomputeScope", () => {
+  it("defaults to every window and an all-crops scope", () => {
+    assert.deepEqual(normalizeRecomputeScope({ geoPrefix: " 9Q8Y " }), {
+      scope: { geoBoundaryKey: "9q8y", cropId: null },
+      windows: [7, 14, 30],
+    });
+  });
+
+  it("dedupes and sorts requested windows", () => {
+    const { windows } = normalizeRecomputeScope({ geoPrefix: "9q8yy", windows: [30, 7, 30] });
+    assert.deepEqual(windows, [7, 30]);
+  });
+
+  it("rejects prefixes the aggregation never writes", () => {
+    assert.throws(() => normalizeRecomputeScope({ geoPrefix: "9q8" }), /geoPrefix/);
+    assert.throws(() => normalizeRecomputeScope({ geoPrefix: "9q8yyk8" }), /geoPrefix/);
+    assert.throws(() => normalizeRecomputeScope({ geoPrefix: "9q8a" }), /geoPrefix/);
+  });
+
+  it("rejects bad crop ids and windows", () => {
+    assert.throws(() => normalizeRecomputeScope({ geoPrefix: "9q8y", cropId: "nope" }), /cropId/);
+    assert.throws(() => normalizeRecomputeScope({ geoPrefix: "9q8y", windows: [] }), /windows/);
+    assert.throws(() => normalizeRecomputeScope({ geoPrefix: "9q8y", windows: [3] }), /Unsupported/);
+  });
+});
+
+describe("recomputeAndUpsert", () => {
+  it("writes the computed signal through the upsert function", async () => {
+    const rows = {
+      listing: { listing_count: 2, listed_quantity: 10 },
+      request: { request_count: 1, demand_quantity: 4 },
+      claim: {
+        claim_count: 1,
+        completed_count: 1,
+        failed_count: 0,
+        confirmed_quantity: 0,
+        completed_quantity: 4,
+      },
+    };
+    const client = fakeClient(rows);
+    const bucketStart = computeBucketStart("2026-02-20T21:00:00Z");
+
+    await recomputeAndUpsert(client, { geoBoundaryKey: "9q8y", cropId: null }, 7, bucketStart);
+
+    const upsert = client.calls.find((c) => c.text.includes("upsert_derived_supply_signal"));
+    assert.ok(upsert, "expected an upsert call");
+    const expected = computeSignal(rows.listing, rows.request, rows.claim, 7);
+    assert.equal(upsert.params[1], "9q8y");
+    assert.equal(upsert.params[2], 7);
+    assert.equal(upsert.params[7], expected.supplyQuantity);
+    assert.equal(upsert.params[9], expected.scarcityScore);
+    assert.deepEqual(JSON.parse(upsert.params[11]), expected.signalPayload);
+  });
+
+  it("reports logged harvests without counting them as supply", async () => {
+    const rows = {
+      listing: { listing_count: 1, listed_quantity: 5 },
+      request: { request_count: 1, demand_quantity: 4 },
+      claim: {
+        claim_count: 0,
+        completed_count: 0,
+        failed_count: 0,
+        confirmed_quantity: 0,
+        completed_quantity: 0,
+      },
+      harvest: { harvest_count: 3, harvested_quantity: 42.5 },
+    };
+    const client = fakeClient(rows);
+
+    await recomputeAndUpsert(
+      client,
+      { geoBoundaryKey: "9q8y", cropId: null },
+      14,
+      computeBucketStart("2026-07-15T12:00:00Z")
+    );
+
+    const harvestQuery = client.calls.find((c) => c.text.includes("FROM harvests"));
+    assert.equal(harvestQuery.params[1], "9q8y%");
+    const upsert = client.calls.find((c) => c.text.includes("upsert_derived_supply_signal"));
+    assert.equal(upsert.params[7], 5);
+    const payload = JSON.parse(upsert.params[11]);
+    assert.equal(payload.harvestCount, 3);
+    assert.equal(payload.harvestedQuantity, 42.5);
+  });
+
+  it("reports planned crops as anticipated supply only", async () => {
+    const rows = {
+      listing: { listing_count: 1, listed_quantity: 5 },
+      request: { request_count: 2, demand_quantity: 8 },
+      claim: {
+        claim_count: 0,
+        completed_count: 0,
+        failed_count: 0,
+        confirmed_quantity: 0,
+        completed_quantity: 0,
+      },
+      planned: { planned_grower_count: 12, planned_quantity: 300 },
+    };
+    const client = fakeClient(rows);
+
+    await recomputeAndUpsert(
+      client,
+      { geoBoundaryKey: "9q8yy", cropId: null },
+      30,
+      computeBucketStart("2026-04-01T12:00:00Z")
+    );
+
+    const plannedQuery = client.calls.find((c) => c.text.includes("FROM crop_plans"));
+    assert.equal(plannedQuery.params[1], "9q8yy%");
+    const upsert = client.calls.find((c) => c.text.includes("upsert_derived_supply_signal"));
+    assert.equal(upsert.params[7], 5);
+    assert.equal(upsert.params[9], 8 / 6);
+    const payload = JSON.parse(upsert.params[11]);
+    assert.equal(payload.plannedGrowerCount, 12);
+    assert.equal(payload.plannedQuantity, 300);
+  });
+});
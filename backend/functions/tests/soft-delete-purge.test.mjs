@@ -0,0 +1,109 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// ── Inline the pure functions from the handler so we can test without pg ─────
+
+const DEFAULT_BATCH_SIZE = 500;
+const DEFAULT_MAX_BATCHES = 20;
+const DEFAULT_RETENTION_DAYS = 90;
+
+const PURGE_TARGETS = [
+  { table: "surplus_listings", retentionEnv: "LISTINGS_RETENTION_DAYS" },
+  { table: "requests", retentionEnv: "REQUESTS_RETENTION_DAYS" },
+  {
+    table: "users",
+    retentionEnv: "USERS_RETENTION_DAYS",
+    extraPredicate: "AND NOT EXISTS (SELECT 1 FROM organizations o WHERE o.service_user_id = users.id)",
+  },
+];
+
+function parsePositiveInt(value, fallback) {
+  if (value === undefined || value === null || value === "") return fallback;
+  const parsed = Number.parseInt(String(value), 10);
+  return Number.isInteger(parsed) && parsed > 0 ? parsed : fallback;
+}
+
+function resolvePurgeConfig(env, overrides = {}) {
+  const defaultRetentionDays = parsePositiveInt(
+    overrides.retentionDays ?? env.SOFT_DELETE_RETENTION_DAYS,
+    DEFAULT_RETENTION_DAYS
+  );
+
+  return {
+    batchSize: parsePositiveInt(overrides.batchSize ?? env.PURGE_BATCH_SIZE, DEFAULT_BATCH_SIZE),
+    maxBatches: parsePositiveInt(overrides.maxBatches ?? env.PURGE_MAX_BATCHES, DEFAULT_MAX_BATCHES),
+    targets: PURGE_TARGETS.map(({ table, retentionEnv, extraPredicate = "" }) => ({
+      table,
+      extraPredicate,
+      retentionDays: parsePositiveInt(
+        overrides.tableRetentionDays?.[table] ?? env[retentionEnv],
+        defaultRetentionDays
+      ),
+    })),
+  };
+}
+
+function retentionCutoff(now, retentionDays) {
+  return new Date(now.getTime() - retentionDays * 86_400_000);
+}
+
+// ── Tests ────────────────────────────────────────────────────────────────────
+
+describe("resolvePurgeConfig", () => {
+  it("uses defaults when nothing is configured", () => {
+    const config = resolvePurgeConfig({});
+    assert.equal(config.batchSize, DEFAULT_BATCH_SIZE);
+    assert.equal(config.maxBatches, DEFAULT_MAX_BATCHES);
+    assert.deepEqual(
+      config.targets.map((t) => t.retentionDays),
+      [90, 90, 90]
+    );
+  });
+
+  it("purges content before the users that own it", () => {
+    const config = resolvePurgeConfig({});
+    assert.deepEqual(
+      config.targets.map((t) => t.table),
+      ["surplus_listings", "requests", "users"]
+    );
+  });
+
+  it("applies the shared retention and per-table overrides from env", () => {
+    const config = resolvePurgeConfig({
+      SOFT_DELETE_RETENTION_DAYS: "30",
+      USERS_RETENTION_DAYS: "365",
+    });
+    assert.deepEqual(
+      config.targets.map((t) => t.retentionDays),
+      [30, 30, 365]
+    );
+  });
+
+  it("lets invocation overrides win over env", () => {
+    const config = resolvePurgeConfig(
+      { PURGE_BATCH_SIZE: "250", LISTINGS_RETENTION_DAYS: "60" },
+      { batchSize: 10, tableRetentionDays: { surplus_listings: 7 } }
+    );
+    assert.equal(config.batchSize, 10);
+    assert.equal(config.targets[0].retentionDays, 7);
+  });
+
+  it("rejects zero and invalid retention so nothing is purged immediately", () => {
+    const config = resolvePurgeConfig({ SOFT_DELETE_RETENTION_DAYS: "0", REQUESTS_RETENTION_DAYS: "abc" });
+    assert.equal(config.targets[0].retentionDays, DEFAULT_RETENTION_DAYS);
+    assert.equal(config.targets[1].retentionDays, DEFAULT_RETENTION_DAYS);
+  });
+
+  it("skips users still backing an organization", () => {
+    const config = resolvePurgeConfig({});
+    assert.match(config.targets[2].extraPredicate, /organizations o WHERE o\.service_user_id = users\.id/);
+    assert.equal(config.targets[0].extraPredicate, "");
+  });
+});
+
+describe("retentionCutoff", () => {
+  it("moves the cutoff back by the retention period", () => {
+    const now = new Date("2026-03-10T00:00:00Z");
+    assert.equal(retentionCutoff(now, 30).toISOString(), "2026-02-08T00:00:00.000Z");
+  });
+});
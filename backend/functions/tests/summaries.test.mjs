@@ -0,0 +1,112 @@
+import { describe, it } from "node:test";
+import assert from "node:assert/strict";
+
+// The summaries module has no pg dependency, so it is imported directly.
+import {
+  buildPrompt,
+  claimRate,
+  estimateUsd,
+  needsSummary,
+  templateSummary,
+} from "../lib/summaries.mjs";
+
+const NO_FULFILLMENT = {
+  resolvedClaimCount: 0,
+  fulfillmentRate: null,
+  noShowRate: null,
+  topUnfulfilledCrops: [],
+};
+
+function signal(cropId, scarcityScore) {
+  return {
+    geoBoundaryKey: "9q8y",
+    cropId,
+    windowDays: 7,
+    listingCount: 2,
+    requestCount: 5,
+    supplyQuantity: "3",
+    demandQuantity: "8",
+    scarcityScore,
+    abundanceScore: 0.1,
+  };
+}
+
+describe("buildPrompt", () => {
+  it("ranks by scarcity and caps rows like the API prompt", () => {
+    const signals = Array.from({ length: 12 }, (_, index) => signal(`crop-${index}`, index));
+    const lines = buildPrompt("9q8y", 7, signals, NO_FULFILLMENT).trimEnd().split("\n");
+
+    assert.ok(lines[0].startsWith("Area 9q8y, last 7 days."));
+    assert.equal(lines.length, 11);
+    assert.equal(
+      lines[1],
+      "- crop-11: 2 listings, 5 requests, supply 3, demand 8, scarcity 11.00, abundance 0.10"
+    );
+    assert.ok(!lines.some((line) => line.startsWith("- crop-0:")));
+  });
+
+  it("adds fulfillment context when present", () => {
+    const prompt = buildPrompt("9q8y", 7, [signal("crop-1", 0.8)], {
+      resolvedClaimCount: 20,
+      fulfillmentRate: 0.75,
+      noShowRate: 0.15,
+      topUnfulfilledCrops: [{ cropId: "crop-1", cropName: "Tomato", openRequestCount: 6 }],
+    });
+    assert.ok(prompt.includes("Claims: 20 resolved, fulfillment rate 75%, no-show rate 15%."));
+    assert.ok(prompt.includes("Top unfulfilled requests: Tomato (6 open)."));
+  });
+});
+
+describe("templateSummary", () => {
+  it("describes the scarcest signal", () => {
+    assert.equal(
+      templateSummary("9q8y", 7, [signal("a", 0.2), signal("b", 0.9)], NO_FULFILLMENT),
+      "Derived signal summary for 9q8y (7d): 2 listings, 5 requests, scarcity 0.90, abundance 0.10."
+    );
+  });
+});
+
+describe("claimRate", () => {
+  it("is null until a claim resolves", () => {
+    assert.equal(claimRate(0, 0), null);
+    assert.equal(claimRate(3, 4), 0.75);
+  });
+});
+
+describe("needsSummary", () => {
+  const now = new Date("2026-06-01T12:00:00Z");
+  const config = { leadMinutes: 75, minAgeMinutes: 60 };
+  const at = (minutesFromNow) => new Date(now.getTime() + minutesFromNow * 60_000);
+
+  it("generates when there is no live summary", () => {
+    assert.equal(needsSummary({ summaryExpiresAt: null }, now, config), true);
+  });
+
+  it("refreshes summaries that expire before the next run", () => {
+    const scope = { summaryGeneratedAt: at(-300), summaryExpiresAt: at(60), signalsComputedAt: null };
+    assert.equal(needsSummary(scope, now, config), true);
+  });
+
+  it("refreshes after a recompute only once the summary is old enough", () => {
+    const recent = { summaryGeneratedAt: at(-30), summaryExpiresAt: at(330), signalsComputedAt: at(-5) };
+    const older = { ...recent, summaryGeneratedAt: at(-90), summaryExpiresAt: at(270) };
+    assert.equal(needsSummary(recent, now, config), false);
+    assert.equal(needsSummary(older, now, config), true);
+  });
+
+  it("leaves summaries newer than the signals alone", () => {
+    const scope = { summaryGeneratedAt: at(-90), summaryExpiresAt: at(270), signalsComputedAt: at(-120) };
+    assert.equal(needsSummary(scope, now, config), false);
+  });
+});
+
+describe("estimateUsd", () => {
+  it("prices input and output tokens separately", () => {
+    const env = { AI_COST_PER_1K_INPUT_TOKENS_USD: "0.001", AI_COST_PER_1K_OUTPUT_TOKENS_USD: "0.004" };
+    assert.ok(Math.abs(estimateUsd("bedrock", 2000, 500, env) - 0.004) < 1e-12);
+  });
+
+  it("is free for the template provider", () => {
+    assert.equal(estimateUsd("template", 2000, 500), 0);
+  });
+});
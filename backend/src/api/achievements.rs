@@ -0,0 +1,60 @@
+use crate::db::TimedQuery;
+use crate::error::ApiError;
+use serde::Serialize;
+use tokio_postgres::Client;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AchievementEntry {
+    pub achievement_key: String,
+    pub season_year: Option<i32>,
+    pub earned_at: String,
+}
+
+/// Achievements written by the achievements worker, oldest first. Read-only;
+/// awards are only ever evaluated from claim events.
+pub async fn load_achievements(
+    client: &Client,
+    user_id: Uuid,
+) -> Result<Vec<AchievementEntry>, ApiError> {
+    let rows = client
+        .query_timed(
+            "achievements::load_achievements",
+            "select achievement_key, season_year, earned_at from user_achievements where user_id = $1 order by earned_at asc, achievement_key asc",
+            &[&user_id],
+        )
+        .await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|row| AchievementEntry {
+            achievement_key: row.get("achievement_key"),
+            season_year: row.get("season_year"),
+            earned_at: row
+                .get::<_, chrono::DateTime<chrono::Utc>>("earned_at")
+                .to_rfc3339(),
+        })
+        .collect())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn achievement_entry_serializes_camel_case() {
+        let entry = AchievementEntry {
+            achievement_key: "no_show_free_season".to_string(),
+            season_year: Some(2025),
+            earned_at: "2026-01-04T00:00:00+00:00".to_string(),
+        };
+
+        let json = serde_json::to_value(&entry).unwrap();
+        assert_eq!(json["achievementKey"], "no_show_free_season");
+        assert_eq!(json["seasonYear"], 2025);
+        assert_eq!(json["earnedAt"], "2026-01-04T00:00:00+00:00");
+    }
+}
@@ -0,0 +1,128 @@
+use super::{
+    build_prompt, FulfillmentContext, SummaryArtifact, SummaryFuture, SummaryProvider,
+    SYSTEM_PROMPT,
+};
+use crate::ai_model_config::AiModelConfig;
+use crate::config::AiConfig;
+use crate::models::feed::DerivedFeedSignal;
+use aws_config::{BehaviorVersion, Region};
+use aws_sdk_bedrockruntime::types::{
+    ContentBlock, ConversationRole, InferenceConfiguration, Message, SystemContentBlock,
+};
+use std::time::Duration;
+
+const MAX_OUTPUT_TOKENS: i32 = 300;
+
+/// Calls the Bedrock Converse API with the configured primary model.
+#[derive(Debug, Clone)]
+pub struct BedrockProvider {
+    enabled: bool,
+    timeout: Duration,
+    model: AiModelConfig,
+}
+
+impl BedrockProvider {
+    /// Requires `BEDROCK_SUMMARY_ENABLED=1`; otherwise every call fails fast
+    /// so the feed degrades without touching the network.
+    pub fn from_config(config: &AiConfig) -> Self {
+        Self {
+            enabled: config.bedrock_enabled,
+            timeout: config.bedrock_timeout,
+            model: config.model.clone(),
+        }
+    }
+
+    async fn converse(
+        &self,
+        geo_boundary_key: &str,
+        window_days: i32,
+        signals: &[DerivedFeedSignal],
+        fulfillment: &FulfillmentContext,
+    ) -> Result<SummaryArtifact, lambda_http::Error> {
+        if !self.enabled {
+            return Err(lambda_http::Error::from(
+                "Bedrock summarization disabled by configuration".to_string(),
+            ));
+        }
+
+        let config = aws_config::defaults(BehaviorVersion::latest())
+            .region(Region::new(self.model.region.clone()))
+            .load()
+            .await;
+        let client = aws_sdk_bedrockruntime::Client::new(&config);
+
+        let message = Message::builder()
+            .role(ConversationRole::User)
+            .content(ContentBlock::Text(build_prompt(
+                geo_boundary_key,
+                window_days,
+                signals,
+                fulfillment,
+            )))
+            .build()
+            .map_err(|e| lambda_http::Error::from(format!("Invalid Bedrock message: {e}")))?;
+
+        let output = client
+            .converse()
+            .model_id(&self.model.model_id)
+            .system(SystemContentBlock::Text(SYSTEM_PROMPT.to_string()))
+            .messages(message)
+            .inference_config(
+                InferenceConfiguration::builder()
+                    .max_tokens(MAX_OUTPUT_TOKENS)
+                    .temperature(0.2)
+                    .build(),
+            )
+            .send()
+            .await
+            .map_err(|e| lambda_http::Error::from(format!("Bedrock converse failed: {e}")))?;
+
+        let summary_text = output
+            .output()
+            .and_then(|output| output.as_message().ok())
+            .and_then(|message| {
+                message
+                    .content()
+                    .iter()
+                    .find_map(|block| block.as_text().ok())
+            })
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| lambda_http::Error::from("Bedrock returned no summary text"))?;
+
+        let (input_tokens, output_tokens) = output.usage().map_or((0, 0), |usage| {
+            (usage.input_tokens(), usage.output_tokens())
+        });
+
+        Ok(SummaryArtifact::new(
+            summary_text,
+            self.model.model_id.clone(),
+            format!("{}-{}", self.model.response_mode, self.model.schema_version),
+        )
+        .with_token_usage(input_tokens, output_tokens))
+    }
+}
+
+impl SummaryProvider for BedrockProvider {
+    fn name(&self) -> &'static str {
+        "bedrock"
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model.model_id
+    }
+
+    fn summarize<'a>(
+        &'a self,
+        geo_boundary_key: &'a str,
+        window_days: i32,
+        signals: &'a [DerivedFeedSignal],
+        fulfillment: &'a FulfillmentContext,
+    ) -> SummaryFuture<'a> {
+        Box::pin(self.converse(geo_boundary_key, window_days, signals, fulfillment))
+    }
+}
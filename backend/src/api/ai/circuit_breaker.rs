@@ -0,0 +1,109 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Consecutive-failure breaker keyed by provider name. State lives for the
+/// life of the execution environment, like the database pool.
+///
+/// After `failure_threshold` failures in a row the provider is skipped until
+/// `cooldown` elapses. The next call is a trial: success closes the circuit,
+/// failure reopens it for another cooldown.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    failure_threshold: u32,
+    cooldown: Duration,
+    state: Mutex<HashMap<&'static str, ProviderState>>,
+}
+
+#[derive(Debug, Default)]
+struct ProviderState {
+    consecutive_failures: u32,
+    open_until: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    pub fn new(failure_threshold: u32, cooldown: Duration) -> Self {
+        Self {
+            failure_threshold: failure_threshold.max(1),
+            cooldown,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Whether `provider` may be called at `now`.
+    pub fn allow(&self, provider: &'static str, now: Instant) -> bool {
+        let mut state = self.lock();
+        let entry = state.entry(provider).or_default();
+        let allowed = match entry.open_until {
+            Some(open_until) if now < open_until => false,
+            Some(_) => {
+                entry.open_until = None;
+                true
+            }
+            None => true,
+        };
+        drop(state);
+        allowed
+    }
+
+    pub fn record_success(&self, provider: &'static str) {
+        self.lock().remove(provider);
+    }
+
+    /// Returns `true` when this failure opened the circuit.
+    pub fn record_failure(&self, provider: &'static str, now: Instant) -> bool {
+        let mut state = self.lock();
+        let entry = state.entry(provider).or_default();
+        entry.consecutive_failures = entry.consecutive_failures.saturating_add(1);
+        let opened = entry.consecutive_failures >= self.failure_threshold;
+        if opened {
+            entry.open_until = Some(now + self.cooldown);
+        }
+        drop(state);
+        opened
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<&'static str, ProviderState>> {
+        self.state
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn opens_after_threshold_and_recovers_after_cooldown() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(30));
+        let start = Instant::now();
+
+        assert!(breaker.allow("bedrock", start));
+        assert!(!breaker.record_failure("bedrock", start));
+        assert!(breaker.allow("bedrock", start));
+        assert!(breaker.record_failure("bedrock", start));
+        assert!(!breaker.allow("bedrock", start + Duration::from_secs(29)));
+
+        let trial = start + Duration::from_secs(30);
+        assert!(breaker.allow("bedrock", trial));
+        assert!(breaker.record_failure("bedrock", trial));
+        assert!(!breaker.allow("bedrock", trial + Duration::from_secs(1)));
+
+        let next_trial = trial + Duration::from_secs(30);
+        assert!(breaker.allow("bedrock", next_trial));
+        breaker.record_success("bedrock");
+        assert!(!breaker.record_failure("bedrock", next_trial));
+        assert!(breaker.allow("bedrock", next_trial));
+    }
+
+    #[test]
+    fn providers_trip_independently() {
+        let breaker = CircuitBreaker::new(1, Duration::from_secs(30));
+        let now = Instant::now();
+
+        assert!(breaker.record_failure("bedrock", now));
+        assert!(!breaker.allow("bedrock", now));
+        assert!(breaker.allow("openai_compatible", now));
+    }
+}
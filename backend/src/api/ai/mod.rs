@@ -0,0 +1,485 @@
+//! AI summaries for the derived feed. A [`SummaryProvider`] is selected by
+//! `AI_SUMMARY_PROVIDER` in [`AiConfig`], and [`SummaryGenerator`] bounds every call with the
+//! provider's timeout and a per-provider circuit breaker so a slow or failing
+//! model costs the feed at most one timeout per cooldown window. Every
+//! attempt, including skipped and failed ones, is written to `ai_usage`.
+
+mod bedrock;
+mod circuit_breaker;
+mod openai_compatible;
+mod template;
+mod usage;
+
+pub use bedrock::BedrockProvider;
+pub use circuit_breaker::CircuitBreaker;
+pub use openai_compatible::OpenAiCompatibleProvider;
+pub use template::TemplateProvider;
+pub use usage::{SummaryUsage, UsageStatus};
+
+use crate::config::{self, AiConfig, SummaryProviderKind};
+use crate::models::feed::DerivedFeedSignal;
+use crate::telemetry;
+use chrono::Utc;
+use serde::Serialize;
+use std::fmt::Write as _;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use tokio_postgres::Client;
+use tracing::{warn, Instrument};
+
+/// Prompts include at most this many signals, strongest scarcity first.
+const MAX_PROMPT_SIGNALS: usize = 10;
+
+const SUMMARY_TTL_HOURS: i64 = 6;
+
+static BREAKER: OnceLock<CircuitBreaker> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct SummaryArtifact {
+    pub summary_text: String,
+    pub model_id: String,
+    pub model_version: String,
+    pub generated_at: chrono::DateTime<Utc>,
+    pub expires_at: chrono::DateTime<Utc>,
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+}
+
+impl SummaryArtifact {
+    /// Stamps `summary_text` with the standard freshness window.
+    pub fn new(summary_text: String, model_id: String, model_version: String) -> Self {
+        let generated_at = Utc::now();
+        Self {
+            summary_text,
+            model_id,
+            model_version,
+            generated_at,
+            expires_at: generated_at + chrono::Duration::hours(SUMMARY_TTL_HOURS),
+            input_tokens: 0,
+            output_tokens: 0,
+        }
+    }
+
+    /// Attaches the token counts the provider reported for the call.
+    #[must_use]
+    pub const fn with_token_usage(mut self, input_tokens: i32, output_tokens: i32) -> Self {
+        self.input_tokens = input_tokens;
+        self.output_tokens = output_tokens;
+        self
+    }
+}
+
+/// How claims in the area actually resolved over the window. Rates are
+/// `None` until at least one claim has resolved.
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FulfillmentContext {
+    pub resolved_claim_count: i64,
+    pub fulfillment_rate: Option<f64>,
+    pub no_show_rate: Option<f64>,
+    pub top_unfulfilled_crops: Vec<UnfulfilledCrop>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnfulfilledCrop {
+    pub crop_id: String,
+    pub crop_name: String,
+    pub open_request_count: i64,
+}
+
+impl FulfillmentContext {
+    pub fn is_empty(&self) -> bool {
+        self.resolved_claim_count == 0 && self.top_unfulfilled_crops.is_empty()
+    }
+}
+
+pub type SummaryFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<SummaryArtifact, lambda_http::Error>> + Send + 'a>>;
+
+pub trait SummaryProvider: Send + Sync {
+    /// Stable name used for configuration, breaker state, and logs.
+    fn name(&self) -> &'static str;
+
+    /// Upper bound on a single [`SummaryProvider::summarize`] call.
+    fn timeout(&self) -> Duration;
+
+    /// Model the provider is configured to call, recorded even when the call
+    /// fails.
+    fn model_id(&self) -> &str;
+
+    fn summarize<'a>(
+        &'a self,
+        geo_boundary_key: &'a str,
+        window_days: i32,
+        signals: &'a [DerivedFeedSignal],
+        fulfillment: &'a FulfillmentContext,
+    ) -> SummaryFuture<'a>;
+}
+
+pub struct SummaryGenerator {
+    provider: Box<dyn SummaryProvider>,
+}
+
+impl SummaryGenerator {
+    pub fn from_config(config: &AiConfig) -> Self {
+        Self::new(provider_for(config))
+    }
+
+    pub fn new(provider: Box<dyn SummaryProvider>) -> Self {
+        Self { provider }
+    }
+
+    /// Generates a summary and records the attempt under `feature_key`.
+    pub async fn generate(
+        &self,
+        client: &Client,
+        feature_key: &str,
+        geo_boundary_key: &str,
+        window_days: i32,
+        signals: &[DerivedFeedSignal],
+        fulfillment: &FulfillmentContext,
+    ) -> Result<SummaryArtifact, lambda_http::Error> {
+        let provider = self.provider.as_ref();
+        let started = Instant::now();
+        let outcome = generate_with(
+            breaker(),
+            provider,
+            geo_boundary_key,
+            window_days,
+            signals,
+            fulfillment,
+        )
+        .await;
+        let latency_ms = i32::try_from(started.elapsed().as_millis()).unwrap_or(i32::MAX);
+
+        let (status, input_tokens, output_tokens) = match &outcome {
+            Ok(artifact) => (
+                UsageStatus::Success,
+                artifact.input_tokens,
+                artifact.output_tokens,
+            ),
+            Err(failure) => (failure.status, 0, 0),
+        };
+        let model_id = outcome.as_ref().map_or_else(
+            |_| provider.model_id(),
+            |artifact| artifact.model_id.as_str(),
+        );
+        usage::record_best_effort(
+            client,
+            &SummaryUsage {
+                feature_key,
+                provider: provider.name(),
+                model_id,
+                geo_boundary_key,
+                status,
+                input_tokens,
+                output_tokens,
+                latency_ms,
+            },
+        )
+        .await;
+
+        outcome.map_err(|failure| failure.error)
+    }
+}
+
+/// A failed generation and how it failed, for usage accounting.
+#[derive(Debug)]
+struct GenerationFailure {
+    status: UsageStatus,
+    error: lambda_http::Error,
+}
+
+impl GenerationFailure {
+    const fn new(status: UsageStatus, error: lambda_http::Error) -> Self {
+        Self { status, error }
+    }
+}
+
+pub fn provider_for(config: &AiConfig) -> Box<dyn SummaryProvider> {
+    match config.summary_provider {
+        SummaryProviderKind::Template => Box::new(TemplateProvider),
+        SummaryProviderKind::OpenAiCompatible => {
+            Box::new(OpenAiCompatibleProvider::from_config(config))
+        }
+        SummaryProviderKind::Bedrock => Box::new(BedrockProvider::from_config(config)),
+    }
+}
+
+async fn generate_with(
+    breaker: &CircuitBreaker,
+    provider: &dyn SummaryProvider,
+    geo_boundary_key: &str,
+    window_days: i32,
+    signals: &[DerivedFeedSignal],
+    fulfillment: &FulfillmentContext,
+) -> Result<SummaryArtifact, GenerationFailure> {
+    let name = provider.name();
+    if !breaker.allow(name, Instant::now()) {
+        return Err(GenerationFailure::new(
+            UsageStatus::CircuitOpen,
+            lambda_http::Error::from(format!("Summary provider {name} skipped: circuit open")),
+        ));
+    }
+
+    let outcome = tokio::time::timeout(
+        provider.timeout(),
+        provider.summarize(geo_boundary_key, window_days, signals, fulfillment),
+    )
+    .instrument(telemetry::client_span(name, "summarize"))
+    .await;
+
+    match outcome {
+        Ok(Ok(artifact)) => {
+            breaker.record_success(name);
+            Ok(artifact)
+        }
+        Ok(Err(error)) => {
+            if breaker.record_failure(name, Instant::now()) {
+                warn!(provider = name, error = %error, "Summary provider circuit opened");
+            }
+            Err(GenerationFailure::new(UsageStatus::Error, error))
+        }
+        Err(_) => {
+            if breaker.record_failure(name, Instant::now()) {
+                warn!(
+                    provider = name,
+                    "Summary provider circuit opened after timeout"
+                );
+            }
+            Err(GenerationFailure::new(
+                UsageStatus::Timeout,
+                lambda_http::Error::from(format!(
+                    "Summary provider {name} timed out after {}ms",
+                    provider.timeout().as_millis()
+                )),
+            ))
+        }
+    }
+}
+
+fn breaker() -> &'static CircuitBreaker {
+    BREAKER.get_or_init(|| {
+        let ai = &config::get().ai;
+        CircuitBreaker::new(ai.breaker_failure_threshold, ai.breaker_cooldown)
+    })
+}
+
+pub const SYSTEM_PROMPT: &str = "You summarize local produce supply and demand for a community \
+garden app. Write two or three plain sentences for neighbors. Mention which crops are scarce or \
+abundant when crop ids are given, and never invent numbers that are not in the data. When \
+fulfillment data is given, suggest one concrete thing neighbors can do, such as growing an unmet \
+crop or confirming pickups to cut no-shows.";
+
+/// User prompt shared by the model-backed providers.
+pub fn build_prompt(
+    geo_boundary_key: &str,
+    window_days: i32,
+    signals: &[DerivedFeedSignal],
+    fulfillment: &FulfillmentContext,
+) -> String {
+    let mut ranked = signals.iter().collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.scarcity_score.total_cmp(&a.scarcity_score));
+
+    let mut prompt = format!(
+        "Area {geo_boundary_key}, last {window_days} days. Signals (crop, listings, requests, supply, demand, scarcity, abundance):\n"
+    );
+    for signal in ranked.into_iter().take(MAX_PROMPT_SIGNALS) {
+        let _ = writeln!(
+            prompt,
+            "- {}: {} listings, {} requests, supply {}, demand {}, scarcity {:.2}, abundance {:.2}",
+            signal.crop_id.as_deref().unwrap_or("all crops"),
+            signal.listing_count,
+            signal.request_count,
+            signal.supply_quantity,
+            signal.demand_quantity,
+            signal.scarcity_score,
+            signal.abundance_score
+        );
+    }
+
+    if !fulfillment.is_empty() {
+        let percent = |rate: Option<f64>| {
+            rate.map_or_else(|| "n/a".to_string(), |rate| format!("{:.0}%", rate * 100.0))
+        };
+        let _ = writeln!(
+            prompt,
+            "Claims: {} resolved, fulfillment rate {}, no-show rate {}.",
+            fulfillment.resolved_claim_count,
+            percent(fulfillment.fulfillment_rate),
+            percent(fulfillment.no_show_rate)
+        );
+        if !fulfillment.top_unfulfilled_crops.is_empty() {
+            let crops = fulfillment
+                .top_unfulfilled_crops
+                .iter()
+                .map(|crop| format!("{} ({} open)", crop.crop_name, crop.open_request_count))
+                .collect::<Vec<_>>();
+            let _ = writeln!(prompt, "Top unfulfilled requests: {}.", crops.join(", "));
+        }
+    }
+    prompt
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    struct FailingProvider;
+
+    impl SummaryProvider for FailingProvider {
+        fn name(&self) -> &'static str {
+            "failing"
+        }
+
+        fn timeout(&self) -> Duration {
+            Duration::from_millis(100)
+        }
+
+        fn model_id(&self) -> &'static str {
+            "failing-model"
+        }
+
+        fn summarize<'a>(
+            &'a self,
+            _geo_boundary_key: &'a str,
+            _window_days: i32,
+            _signals: &'a [DerivedFeedSignal],
+            _fulfillment: &'a FulfillmentContext,
+        ) -> SummaryFuture<'a> {
+            Box::pin(async { Err(lambda_http::Error::from("model unavailable")) })
+        }
+    }
+
+    fn signal(crop_id: &str, scarcity_score: f64) -> DerivedFeedSignal {
+        DerivedFeedSignal {
+            geo_boundary_key: "9q8y".to_string(),
+            geo_label: None,
+            crop_id: Some(crop_id.to_string()),
+            window_days: 7,
+            listing_count: 2,
+            request_count: 5,
+            supply_quantity: "3".to_string(),
+            demand_quantity: "8".to_string(),
+            scarcity_score,
+            abundance_score: 0.1,
+            computed_at: String::new(),
+            expires_at: String::new(),
+        }
+    }
+
+    fn provider_named(value: Option<&str>) -> &'static str {
+        let config = crate::config::Config::from_lookup(|name| match name {
+            "DATABASE_URL" => Some("postgres://localhost/test".to_string()),
+            "OPENAI_BASE_URL" => Some("http://localhost:11434".to_string()),
+            "AI_SUMMARY_PROVIDER" => value.map(str::to_string),
+            _ => None,
+        })
+        .unwrap();
+        provider_for(&config.ai).name()
+    }
+
+    #[test]
+    fn provider_for_selects_by_name() {
+        assert_eq!(provider_named(Some("template")), "template");
+        assert_eq!(provider_named(Some("mock")), "template");
+        assert_eq!(provider_named(Some("OpenAI")), "openai_compatible");
+        assert_eq!(provider_named(Some("bedrock")), "bedrock");
+        assert_eq!(provider_named(None), "bedrock");
+    }
+
+    #[tokio::test]
+    async fn template_generator_emits_traceable_metadata() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        let artifact = generate_with(
+            &breaker,
+            &TemplateProvider,
+            "9q8y",
+            7,
+            &[],
+            &FulfillmentContext::default(),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(artifact.model_id, "mock.derived-signal-summarizer");
+        assert_eq!(artifact.model_id, TemplateProvider.model_id());
+        assert_eq!(artifact.model_version, "v1");
+        assert_eq!((artifact.input_tokens, artifact.output_tokens), (0, 0));
+        assert!(artifact.expires_at > artifact.generated_at);
+    }
+
+    #[tokio::test]
+    async fn generate_with_skips_provider_once_circuit_opens() {
+        let breaker = CircuitBreaker::new(2, Duration::from_secs(60));
+        for _ in 0..2 {
+            let error = generate_with(
+                &breaker,
+                &FailingProvider,
+                "9q8y",
+                7,
+                &[],
+                &FulfillmentContext::default(),
+            )
+            .await
+            .unwrap_err();
+            assert_eq!(error.status, UsageStatus::Error);
+            assert_eq!(error.error.to_string(), "model unavailable");
+        }
+
+        let error = generate_with(
+            &breaker,
+            &FailingProvider,
+            "9q8y",
+            7,
+            &[],
+            &FulfillmentContext::default(),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(error.status, UsageStatus::CircuitOpen);
+        assert!(error.error.to_string().contains("circuit open"));
+    }
+
+    #[test]
+    fn build_prompt_ranks_by_scarcity_and_caps_rows() {
+        let signals = (0..12)
+            .map(|index| signal(&format!("crop-{index}"), f64::from(index)))
+            .collect::<Vec<_>>();
+        let prompt = build_prompt("9q8y", 7, &signals, &FulfillmentContext::default());
+
+        assert!(prompt.starts_with("Area 9q8y, last 7 days."));
+        assert_eq!(prompt.lines().count(), 1 + MAX_PROMPT_SIGNALS);
+        assert!(prompt.lines().nth(1).unwrap().starts_with("- crop-11:"));
+        assert!(!prompt.contains("crop-0:"));
+    }
+
+    #[test]
+    fn build_prompt_adds_fulfillment_context_when_present() {
+        let fulfillment = FulfillmentContext {
+            resolved_claim_count: 20,
+            fulfillment_rate: Some(0.75),
+            no_show_rate: Some(0.15),
+            top_unfulfilled_crops: vec![UnfulfilledCrop {
+                crop_id: "crop-1".to_string(),
+                crop_name: "Tomato".to_string(),
+                open_request_count: 6,
+            }],
+        };
+        let prompt = build_prompt("9q8y", 7, &[signal("crop-1", 0.8)], &fulfillment);
+
+        assert!(prompt.contains("Claims: 20 resolved, fulfillment rate 75%, no-show rate 15%."));
+        assert!(prompt.contains("Top unfulfilled requests: Tomato (6 open)."));
+    }
+
+    #[test]
+    fn build_prompt_omits_empty_fulfillment_context() {
+        let prompt = build_prompt("9q8y", 7, &[], &FulfillmentContext::default());
+        assert!(!prompt.contains("Claims:"));
+        assert!(!prompt.contains("unfulfilled"));
+    }
+}
@@ -0,0 +1,229 @@
+use super::{
+    build_prompt, FulfillmentContext, SummaryArtifact, SummaryFuture, SummaryProvider,
+    SYSTEM_PROMPT,
+};
+use crate::config::AiConfig;
+use crate::models::feed::DerivedFeedSignal;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+const MAX_OUTPUT_TOKENS: u32 = 300;
+
+/// Calls any server that implements the `OpenAI` chat completions API, such as
+/// `OpenAI` itself, a self-hosted gateway, or a local model server.
+#[derive(Debug, Clone)]
+pub struct OpenAiCompatibleProvider {
+    base_url: Option<String>,
+    api_key: Option<String>,
+    model: String,
+    timeout: Duration,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatCompletionRequest<'a> {
+    model: &'a str,
+    messages: [ChatMessage<'a>; 2],
+    max_tokens: u32,
+    temperature: f32,
+}
+
+#[derive(Debug, Serialize)]
+struct ChatMessage<'a> {
+    role: &'static str,
+    content: &'a str,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatCompletionResponse {
+    choices: Vec<ChatChoice>,
+    model: Option<String>,
+    usage: Option<ChatUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatUsage {
+    prompt_tokens: i32,
+    completion_tokens: i32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoice {
+    message: ChatChoiceMessage,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChatChoiceMessage {
+    content: Option<String>,
+}
+
+impl OpenAiCompatibleProvider {
+    pub fn from_config(config: &AiConfig) -> Self {
+        Self {
+            base_url: config.openai.base_url.clone(),
+            api_key: config
+                .openai
+                .api_key
+                .as_ref()
+                .map(|key| key.expose().to_string()),
+            model: config.openai.model.clone(),
+            timeout: config.openai.timeout,
+        }
+    }
+
+    async fn complete(
+        &self,
+        geo_boundary_key: &str,
+        window_days: i32,
+        signals: &[DerivedFeedSignal],
+        fulfillment: &FulfillmentContext,
+    ) -> Result<SummaryArtifact, lambda_http::Error> {
+        let base_url = self.base_url.as_deref().ok_or_else(|| {
+            lambda_http::Error::from("OPENAI_BASE_URL is not configured".to_string())
+        })?;
+
+        let client = reqwest::Client::builder()
+            .timeout(self.timeout)
+            .build()
+            .map_err(|e| lambda_http::Error::from(format!("Failed to build AI client: {e}")))?;
+
+        let prompt = build_prompt(geo_boundary_key, window_days, signals, fulfillment);
+        let body = ChatCompletionRequest {
+            model: &self.model,
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: SYSTEM_PROMPT,
+                },
+                ChatMessage {
+                    role: "user",
+                    content: &prompt,
+                },
+            ],
+            max_tokens: MAX_OUTPUT_TOKENS,
+            temperature: 0.2,
+        };
+
+        let mut request = client
+            .post(format!(
+                "{}/chat/completions",
+                base_url.trim_end_matches('/')
+            ))
+            .json(&body);
+        if let Some(api_key) = &self.api_key {
+            request = request.bearer_auth(api_key);
+        }
+
+        let response = request.send().await.map_err(|e| {
+            lambda_http::Error::from(format!("Chat completion request failed: {e}"))
+        })?;
+        if !response.status().is_success() {
+            return Err(lambda_http::Error::from(format!(
+                "Chat completion returned status {}",
+                response.status().as_u16()
+            )));
+        }
+
+        let completion = response
+            .json::<ChatCompletionResponse>()
+            .await
+            .map_err(|e| lambda_http::Error::from(format!("Invalid chat completion body: {e}")))?;
+        let summary_text = completion
+            .choices
+            .into_iter()
+            .find_map(|choice| choice.message.content)
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+            .ok_or_else(|| lambda_http::Error::from("Chat completion returned no summary text"))?;
+
+        let (input_tokens, output_tokens) = completion.usage.map_or((0, 0), |usage| {
+            (usage.prompt_tokens, usage.completion_tokens)
+        });
+
+        Ok(SummaryArtifact::new(
+            summary_text,
+            completion.model.unwrap_or_else(|| self.model.clone()),
+            "chat-completions-v1".to_string(),
+        )
+        .with_token_usage(input_tokens, output_tokens))
+    }
+}
+
+impl SummaryProvider for OpenAiCompatibleProvider {
+    fn name(&self) -> &'static str {
+        "openai_compatible"
+    }
+
+    fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    fn model_id(&self) -> &str {
+        &self.model
+    }
+
+    fn summarize<'a>(
+        &'a self,
+        geo_boundary_key: &'a str,
+        window_days: i32,
+        signals: &'a [DerivedFeedSignal],
+        fulfillment: &'a FulfillmentContext,
+    ) -> SummaryFuture<'a> {
+        Box::pin(self.complete(geo_boundary_key, window_days, signals, fulfillment))
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn request_body_matches_chat_completions_shape() {
+        let body = ChatCompletionRequest {
+            model: "m",
+            messages: [
+                ChatMessage {
+                    role: "system",
+                    content: "s",
+                },
+                ChatMessage {
+                    role: "user",
+                    content: "u",
+                },
+            ],
+            max_tokens: 300,
+            temperature: 0.2,
+        };
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["messages"][1]["role"], "user");
+        assert_eq!(json["max_tokens"], 300);
+    }
+
+    #[test]
+    fn response_usage_is_optional() {
+        let with_usage: ChatCompletionResponse = serde_json::from_str(
+            r#"{"choices":[],"usage":{"prompt_tokens":120,"completion_tokens":40,"total_tokens":160}}"#,
+        )
+        .unwrap();
+        let usage = with_usage.usage.unwrap();
+        assert_eq!((usage.prompt_tokens, usage.completion_tokens), (120, 40));
+
+        let without: ChatCompletionResponse = serde_json::from_str(r#"{"choices":[]}"#).unwrap();
+        assert!(without.usage.is_none());
+    }
+
+    #[tokio::test]
+    async fn missing_base_url_fails_without_network() {
+        let provider = OpenAiCompatibleProvider {
+            base_url: None,
+            api_key: None,
+            model: "gpt-4o-mini".to_string(),
+            timeout: Duration::from_millis(2500),
+        };
+        let error = provider
+            .summarize("9q8y", 7, &[], &FulfillmentContext::default())
+            .await
+            .unwrap_err();
+        assert!(error.to_string().contains("OPENAI_BASE_URL"));
+    }
+}
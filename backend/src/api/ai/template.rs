@@ -0,0 +1,83 @@
+use super::{FulfillmentContext, SummaryArtifact, SummaryFuture, SummaryProvider};
+use crate::models::feed::DerivedFeedSignal;
+use std::fmt::Write as _;
+use std::time::Duration;
+
+const MODEL_ID: &str = "mock.derived-signal-summarizer";
+
+/// Deterministic summary built from the strongest signal. Needs no network,
+/// so it suits local development, tests, and regions without model access.
+#[derive(Debug, Clone, Copy)]
+pub struct TemplateProvider;
+
+impl SummaryProvider for TemplateProvider {
+    fn name(&self) -> &'static str {
+        "template"
+    }
+
+    fn timeout(&self) -> Duration {
+        Duration::from_millis(100)
+    }
+
+    fn model_id(&self) -> &str {
+        MODEL_ID
+    }
+
+    fn summarize<'a>(
+        &'a self,
+        geo_boundary_key: &'a str,
+        window_days: i32,
+        signals: &'a [DerivedFeedSignal],
+        fulfillment: &'a FulfillmentContext,
+    ) -> SummaryFuture<'a> {
+        Box::pin(async move {
+            Ok(template_summary(
+                geo_boundary_key,
+                window_days,
+                signals,
+                fulfillment,
+            ))
+        })
+    }
+}
+
+fn template_summary(
+    geo_boundary_key: &str,
+    window_days: i32,
+    signals: &[DerivedFeedSignal],
+    fulfillment: &FulfillmentContext,
+) -> SummaryArtifact {
+    let strongest = signals
+        .iter()
+        .max_by(|a, b| a.scarcity_score.total_cmp(&b.scarcity_score));
+
+    let mut summary_text = strongest.map_or_else(
+        || {
+            format!(
+                "Derived signal summary for {geo_boundary_key} ({window_days}d): no signal rows available."
+            )
+        },
+        |top| {
+            format!(
+                "Derived signal summary for {geo_boundary_key} ({window_days}d): {} listings, {} requests, scarcity {:.2}, abundance {:.2}.",
+                top.listing_count, top.request_count, top.scarcity_score, top.abundance_score
+            )
+        },
+    );
+    if let Some(rate) = fulfillment.fulfillment_rate {
+        let _ = write!(
+            summary_text,
+            " {:.0}% of resolved claims were picked up.",
+            rate * 100.0
+        );
+    }
+    if let Some(crop) = fulfillment.top_unfulfilled_crops.first() {
+        let _ = write!(
+            summary_text,
+            " Most requested without a match: {} ({} open).",
+            crop.crop_name, crop.open_request_count
+        );
+    }
+
+    SummaryArtifact::new(summary_text, MODEL_ID.to_string(), "v1".to_string())
+}
@@ -0,0 +1,129 @@
+use crate::config::{self, AiConfig};
+use crate::db::TimedQuery;
+use tokio_postgres::Client;
+use tracing::warn;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsageStatus {
+    Success,
+    Error,
+    Timeout,
+    CircuitOpen,
+}
+
+impl UsageStatus {
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Error => "error",
+            Self::Timeout => "timeout",
+            Self::CircuitOpen => "circuit_open",
+        }
+    }
+}
+
+/// One summary generation attempt, successful or not.
+#[derive(Debug)]
+pub struct SummaryUsage<'a> {
+    pub feature_key: &'a str,
+    pub provider: &'static str,
+    pub model_id: &'a str,
+    pub geo_boundary_key: &'a str,
+    pub status: UsageStatus,
+    pub input_tokens: i32,
+    pub output_tokens: i32,
+    pub latency_ms: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TokenPricing {
+    pub input_per_1k: f64,
+    pub output_per_1k: f64,
+}
+
+impl TokenPricing {
+    pub const fn from_config(config: &AiConfig) -> Self {
+        Self {
+            input_per_1k: config.input_cost_per_1k,
+            output_per_1k: config.output_cost_per_1k,
+        }
+    }
+
+    /// The template provider never calls a model, so it is always free.
+    pub fn estimate_usd(&self, provider: &str, input_tokens: i32, output_tokens: i32) -> f64 {
+        if provider == "template" {
+            return 0.0;
+        }
+        (f64::from(input_tokens.max(0)) * self.input_per_1k
+            + f64::from(output_tokens.max(0)) * self.output_per_1k)
+            / 1000.0
+    }
+}
+
+/// Writes one `ai_usage` row. Accounting must never fail the feed, so errors
+/// are logged and dropped.
+pub async fn record_best_effort(client: &Client, usage: &SummaryUsage<'_>) {
+    let cost = TokenPricing::from_config(&config::get().ai).estimate_usd(
+        usage.provider,
+        usage.input_tokens,
+        usage.output_tokens,
+    );
+
+    let result = client
+        .execute_timed(
+            "ai::usage::record_best_effort",
+            "
+            insert into ai_usage (
+              feature_key, provider, model_id, geo_boundary_key, status,
+              input_tokens, output_tokens, latency_ms, estimated_cost_usd
+            )
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9::float8::numeric)
+            ",
+            &[
+                &usage.feature_key,
+                &usage.provider,
+                &usage.model_id,
+                &usage.geo_boundary_key,
+                &usage.status.as_str(),
+                &usage.input_tokens,
+                &usage.output_tokens,
+                &usage.latency_ms,
+                &cost,
+            ],
+        )
+        .await;
+
+    if let Err(error) = result {
+        warn!(
+            feature_key = usage.feature_key,
+            provider = usage.provider,
+            error = %error,
+            "Failed to record AI usage"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn estimate_usd_prices_input_and_output_separately() {
+        let pricing = TokenPricing {
+            input_per_1k: 0.001,
+            output_per_1k: 0.004,
+        };
+        let cost = pricing.estimate_usd("bedrock", 2000, 500);
+        assert!((cost - 0.004).abs() < 1e-12);
+    }
+
+    #[test]
+    fn estimate_usd_is_free_for_template_and_ignores_negative_counts() {
+        let pricing = TokenPricing {
+            input_per_1k: 0.001,
+            output_per_1k: 0.004,
+        };
+        assert!(pricing.estimate_usd("template", 2000, 500).abs() < f64::EPSILON);
+        assert!(pricing.estimate_usd("bedrock", -5, -5).abs() < f64::EPSILON);
+    }
+}
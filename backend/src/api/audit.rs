@@ -0,0 +1,171 @@
+use crate::auth::AuthContext;
+use crate::db::TimedQuery;
+use crate::error::ApiError;
+use serde_json::Value;
+use tokio_postgres::GenericClient;
+use uuid::Uuid;
+
+pub const ORGANIZATION_CREATED: &str = "admin.organization.created";
+pub const ORGANIZATION_SERVICE_AREAS_REPLACED: &str = "organization.service_areas.replaced";
+pub const ORGANIZATION_WEBHOOK_CREATED: &str = "organization.webhook.created";
+pub const ORGANIZATION_WEBHOOK_DELETED: &str = "organization.webhook.deleted";
+pub const API_KEY_CREATED: &str = "admin.api_key.created";
+pub const API_KEY_REVOKED: &str = "admin.api_key.revoked";
+pub const PROFILE_ADDRESS_CHANGED: &str = "profile.address.changed";
+pub const LISTING_DISCLOSURE_OVERRIDDEN: &str = "listing.disclosure_policy.overridden";
+pub const LISTING_HIDDEN: &str = "admin.listing.hidden";
+pub const LISTING_FORCE_EXPIRED: &str = "admin.listing.force_expired";
+pub const USER_SUSPENDED: &str = "admin.user.suspended";
+pub const USER_SUSPENSION_LIFTED: &str = "admin.user.suspension_lifted";
+pub const SIGNALS_RECOMPUTED: &str = "admin.signals.recomputed";
+pub const IMPERSONATION_STARTED: &str = "admin.impersonation.started";
+pub const IMPERSONATION_ENDED: &str = "admin.impersonation.ended";
+pub const IMPERSONATED_REQUEST: &str = "support.impersonated_request";
+pub const CO_MANAGER_INVITED: &str = "co_manager.invited";
+pub const CO_MANAGER_ACCEPTED: &str = "co_manager.accepted";
+pub const CO_MANAGER_REMOVED: &str = "co_manager.removed";
+pub const LISTING_UPDATED_BY_CO_MANAGER: &str = "listing.updated_by_co_manager";
+pub const LISTING_TRANSITIONED_BY_CO_MANAGER: &str = "listing.transitioned_by_co_manager";
+pub const CLAIM_TRANSITIONED_BY_CO_MANAGER: &str = "claim.transitioned_by_co_manager";
+
+/// Who performed an audited action. API-key callers record both the
+/// organization's service user and the key that was used; impersonated
+/// requests record the admin, not the user being viewed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Actor {
+    pub user_id: Option<Uuid>,
+    pub api_key_id: Option<Uuid>,
+}
+
+impl Actor {
+    #[must_use]
+    pub fn from_auth(auth: &AuthContext) -> Self {
+        let user_id = auth
+            .impersonation
+            .as_ref()
+            .map_or(auth.user_id.as_str(), |session| {
+                session.admin_user_id.as_str()
+            });
+        Self {
+            user_id: Uuid::parse_str(user_id).ok(),
+            api_key_id: auth
+                .api_key
+                .as_ref()
+                .and_then(|key| Uuid::parse_str(&key.key_id).ok()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AuditEntry<'a> {
+    pub actor: Actor,
+    pub action: &'static str,
+    pub target_type: &'static str,
+    pub target_id: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+    pub correlation_id: &'a str,
+}
+
+/// Appends an entry. Pass a transaction to make the audit row commit or roll
+/// back together with the change it describes.
+pub async fn record<C: GenericClient + Sync>(
+    client: &C,
+    entry: &AuditEntry<'_>,
+) -> Result<(), ApiError> {
+    client
+        .execute_timed(
+            "audit::record",
+            "
+            insert into audit_log
+                (actor_user_id, actor_api_key_id, action, target_type, target_id,
+                 before_snapshot, after_snapshot, correlation_id)
+            values ($1, $2, $3, $4, $5, $6, $7, $8)
+            ",
+            &[
+                &entry.actor.user_id,
+                &entry.actor.api_key_id,
+                &entry.action,
+                &entry.target_type,
+                &entry.target_id,
+                &entry.before,
+                &entry.after,
+                &entry.correlation_id,
+            ],
+        )
+        .await?;
+
+    Ok(())
+}
+
+/// Used where the audited change has already committed: a failed audit write
+/// is logged loudly instead of failing a request that otherwise succeeded.
+pub async fn record_best_effort<C: GenericClient + Sync>(client: &C, entry: &AuditEntry<'_>) {
+    if let Err(error) = record(client, entry).await {
+        tracing::error!(
+            correlation_id = entry.correlation_id,
+            action = entry.action,
+            target_type = entry.target_type,
+            target_id = entry.target_id.as_str(),
+            error = %error,
+            "Failed to write audit log entry"
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::auth::{ApiKeyPrincipal, ImpersonationPrincipal};
+
+    fn auth(user_id: &str, api_key: Option<ApiKeyPrincipal>) -> AuthContext {
+        AuthContext {
+            user_id: user_id.to_string(),
+            user_type: None,
+            tier: "free".to_string(),
+            email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key,
+            impersonation: None,
+        }
+    }
+
+    #[test]
+    fn actor_from_user_session_has_no_key() {
+        let actor = Actor::from_auth(&auth("5df666d4-f6b1-4e6f-97d6-321e531ad7ca", None));
+        assert!(actor.user_id.is_some());
+        assert!(actor.api_key_id.is_none());
+    }
+
+    #[test]
+    fn actor_from_api_key_records_key_id() {
+        let actor = Actor::from_auth(&auth(
+            "5df666d4-f6b1-4e6f-97d6-321e531ad7ca",
+            Some(ApiKeyPrincipal {
+                key_id: "b630af9b-6de5-44cd-9d83-d37df86ce2ef".to_string(),
+                organization_id: "6b7a6e9d-e31d-4ac2-b688-15f0490adf9b".to_string(),
+                scopes: vec!["feed:read".to_string()],
+            }),
+        ));
+        assert_eq!(
+            actor.api_key_id.map(|id| id.to_string()).as_deref(),
+            Some("b630af9b-6de5-44cd-9d83-d37df86ce2ef")
+        );
+    }
+
+    #[test]
+    fn actor_from_impersonated_request_is_the_admin() {
+        let mut context = auth("5df666d4-f6b1-4e6f-97d6-321e531ad7ca", None);
+        context.impersonation = Some(ImpersonationPrincipal {
+            session_id: "0f1e2d3c-4b5a-4978-8695-a4b3c2d1e0f9".to_string(),
+            admin_user_id: "b630af9b-6de5-44cd-9d83-d37df86ce2ef".to_string(),
+        });
+        let actor = Actor::from_auth(&context);
+        assert_eq!(
+            actor.user_id.map(|id| id.to_string()).as_deref(),
+            Some("b630af9b-6de5-44cd-9d83-d37df86ce2ef")
+        );
+    }
+}
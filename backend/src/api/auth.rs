@@ -1,5 +1,6 @@
-use crate::db;
-use lambda_http::{Error, Request, RequestExt};
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use lambda_http::{Request, RequestExt};
 use serde::{Deserialize, Serialize};
 use tracing::{error, warn};
 use uuid::Uuid;
@@ -19,11 +20,33 @@ pub struct AuthContext {
     pub tier: String,
     #[allow(dead_code)] // Will be used for user communication features
     pub email: Option<String>,
+    pub is_admin: bool,
+    /// Set by the authorizer while an admin suspension is in effect.
+    pub is_suspended: bool,
+    pub api_key: Option<ApiKeyPrincipal>,
+    pub impersonation: Option<ImpersonationPrincipal>,
 }
 
-pub fn extract_auth_context(request: &Request) -> Result<AuthContext, Error> {
+/// Present when the caller authenticated with a partner API key rather than a
+/// Cognito token; `user_id` is then the organization's service user.
+#[derive(Debug, Clone)]
+pub struct ApiKeyPrincipal {
+    pub key_id: String,
+    pub organization_id: String,
+    pub scopes: Vec<String>,
+}
+
+/// Present while an admin is viewing the API as another user through a
+/// support session; `user_id` is then the impersonated user.
+#[derive(Debug, Clone)]
+pub struct ImpersonationPrincipal {
+    pub session_id: String,
+    pub admin_user_id: String,
+}
+
+pub fn extract_auth_context(request: &Request) -> Result<AuthContext, ApiError> {
     let user_id = extract_authorizer_field(request, "userId")
-        .ok_or_else(|| Error::from("Missing userId in authorizer context"))?;
+        .ok_or_else(|| ApiError::unauthorized("Missing userId in authorizer context"))?;
 
     let tier = extract_authorizer_field(request, "tier").unwrap_or_else(|| "neighbor".to_string());
 
@@ -31,15 +54,50 @@ pub fn extract_auth_context(request: &Request) -> Result<AuthContext, Error> {
 
     let email = extract_authorizer_field(request, "email");
 
+    let is_admin = extract_authorizer_field(request, "isAdmin").is_some_and(|v| v == "true");
+
+    let is_suspended = extract_authorizer_field(request, "suspended").is_some_and(|v| v == "true");
+
+    let principal_type = extract_authorizer_field(request, "principalType");
+
+    let api_key = if principal_type.as_deref() == Some("api_key") {
+        Some(ApiKeyPrincipal {
+            key_id: extract_authorizer_field(request, "apiKeyId").unwrap_or_default(),
+            organization_id: extract_authorizer_field(request, "organizationId")
+                .unwrap_or_default(),
+            scopes: extract_authorizer_field(request, "scopes")
+                .map(|raw| parse_scopes(&raw))
+                .unwrap_or_default(),
+        })
+    } else {
+        None
+    };
+
+    let impersonation = if principal_type.as_deref() == Some("impersonation") {
+        Some(ImpersonationPrincipal {
+            session_id: extract_authorizer_field(request, "impersonationSessionId")
+                .unwrap_or_default(),
+            admin_user_id: extract_authorizer_field(request, "impersonatorId").unwrap_or_default(),
+        })
+    } else {
+        None
+    };
+
     Ok(AuthContext {
         user_id,
         user_type,
         tier,
         email,
+        is_admin,
+        is_suspended,
+        api_key,
+        impersonation,
     })
 }
 
-pub async fn extract_auth_context_with_fallback(request: &Request) -> Result<AuthContext, Error> {
+pub async fn extract_auth_context_with_fallback(
+    request: &Request,
+) -> Result<AuthContext, ApiError> {
     let mut context = extract_auth_context(request)?;
 
     if context.user_type.is_some() {
@@ -76,7 +134,8 @@ async fn load_user_type_from_db(user_id: &str) -> Option<UserType> {
     };
 
     match client
-        .query_opt(
+        .query_opt_timed(
+            "auth::load_user_type_from_db",
             "select user_type from users where id = $1 and deleted_at is null",
             &[&user_uuid],
         )
@@ -93,7 +152,7 @@ async fn load_user_type_from_db(user_id: &str) -> Option<UserType> {
     }
 }
 
-pub fn require_grower(ctx: &AuthContext) -> Result<(), Error> {
+pub fn require_grower(ctx: &AuthContext) -> Result<(), ApiError> {
     match &ctx.user_type {
         Some(UserType::Grower) => Ok(()),
         Some(UserType::Gatherer) => {
@@ -101,7 +160,8 @@ pub fn require_grower(ctx: &AuthContext) -> Result<(), Error> {
                 user_id = ctx.user_id.as_str(),
                 "Gatherers cannot access grower-only features"
             );
-            Err(Error::from(
+            Err(ApiError::forbidden(
+                "grower_only",
                 "Forbidden: This feature is only available to growers",
             ))
         }
@@ -110,24 +170,93 @@ pub fn require_grower(ctx: &AuthContext) -> Result<(), Error> {
                 user_id = ctx.user_id.as_str(),
                 "User type not set, onboarding may be incomplete"
             );
-            Err(Error::from(
-                "Forbidden: User type not set. Please complete onboarding.",
-            ))
+            Err(ApiError::onboarding_incomplete())
         }
     }
 }
 
-pub fn require_participant_user_type(user_type: Option<&UserType>) -> Result<(), Error> {
+pub fn require_participant_user_type(user_type: Option<&UserType>) -> Result<(), ApiError> {
     match user_type {
         Some(UserType::Grower | UserType::Gatherer) => Ok(()),
-        None => Err(Error::from(
-            "Forbidden: User type not set. Please complete onboarding.",
-        )),
+        None => Err(ApiError::onboarding_incomplete()),
+    }
+}
+
+pub fn require_admin(ctx: &AuthContext) -> Result<(), ApiError> {
+    if ctx.is_admin && ctx.api_key.is_none() {
+        return Ok(());
+    }
+
+    error!(
+        user_id = ctx.user_id.as_str(),
+        "Non-admin principal attempted admin operation"
+    );
+    Err(ApiError::forbidden(
+        "admin_required",
+        "Forbidden: This operation requires administrator access",
+    ))
+}
+
+/// Suspended principals keep read access; every other method is refused.
+pub fn require_not_suspended(ctx: &AuthContext, method: &str) -> Result<(), ApiError> {
+    if !ctx.is_suspended || matches!(method, "GET" | "HEAD") {
+        return Ok(());
+    }
+
+    warn!(
+        user_id = ctx.user_id.as_str(),
+        method = method,
+        "Suspended principal attempted a write"
+    );
+    Err(ApiError::forbidden(
+        "account_suspended",
+        "Forbidden: This account is suspended",
+    ))
+}
+
+/// Support sessions are read-only: an admin looking through a user's eyes
+/// can never change that user's data.
+pub fn require_read_only_impersonation(ctx: &AuthContext, method: &str) -> Result<(), ApiError> {
+    let Some(session) = &ctx.impersonation else {
+        return Ok(());
+    };
+    if matches!(method, "GET" | "HEAD") {
+        return Ok(());
+    }
+
+    warn!(
+        user_id = ctx.user_id.as_str(),
+        admin_id = session.admin_user_id.as_str(),
+        impersonation_session_id = session.session_id.as_str(),
+        method = method,
+        "Impersonated request attempted a write"
+    );
+    Err(ApiError::forbidden(
+        "impersonation_read_only",
+        "Forbidden: Impersonation sessions are read-only",
+    ))
+}
+
+/// Cognito users are unrestricted here; API key principals must hold `scope`.
+pub fn require_api_scope(ctx: &AuthContext, scope: &str) -> Result<(), ApiError> {
+    match &ctx.api_key {
+        None => Ok(()),
+        Some(principal) if principal.scopes.iter().any(|s| s == scope) => Ok(()),
+        Some(principal) => {
+            error!(
+                api_key_id = principal.key_id.as_str(),
+                required_scope = scope,
+                "API key is missing required scope"
+            );
+            Err(ApiError::forbidden(
+                "api_key_scope_missing",
+                format!("Forbidden: API key is missing required scope {scope}"),
+            ))
+        }
     }
 }
 
-#[allow(dead_code)] // Will be used when gatherer-specific endpoints are implemented
-pub fn require_user_type(ctx: &AuthContext, required: &UserType) -> Result<(), Error> {
+pub fn require_user_type(ctx: &AuthContext, required: &UserType) -> Result<(), ApiError> {
     match &ctx.user_type {
         Some(user_type) if user_type == required => Ok(()),
         Some(_) => {
@@ -137,9 +266,10 @@ pub fn require_user_type(ctx: &AuthContext, required: &UserType) -> Result<(), E
                 actual_type = ?ctx.user_type,
                 "User does not have required user type"
             );
-            Err(Error::from(format!(
-                "Forbidden: This feature requires user type {required:?}"
-            )))
+            Err(ApiError::forbidden(
+                "user_type_required",
+                format!("Forbidden: This feature requires user type {required:?}"),
+            ))
         }
         None => {
             error!(
@@ -147,13 +277,17 @@ pub fn require_user_type(ctx: &AuthContext, required: &UserType) -> Result<(), E
                 required_type = ?required,
                 "User type not set, onboarding may be incomplete"
             );
-            Err(Error::from(
-                "Forbidden: User type not set. Please complete onboarding.",
-            ))
+            Err(ApiError::onboarding_incomplete())
         }
     }
 }
 
+/// True when the authorizer let the request through without credentials,
+/// which it only does for public and guest routes.
+pub fn is_anonymous(request: &Request) -> bool {
+    extract_authorizer_field(request, "userId").is_none()
+}
+
 fn extract_authorizer_field(request: &Request, field_name: &str) -> Option<String> {
     request
         .request_context()
@@ -163,6 +297,14 @@ fn extract_authorizer_field(request: &Request, field_name: &str) -> Option<Strin
         .map(ToString::to_string)
 }
 
+fn parse_scopes(raw: &str) -> Vec<String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|scope| !scope.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
 fn parse_user_type(s: &str) -> Option<UserType> {
     match s.to_lowercase().as_str() {
         "grower" => Some(UserType::Grower),
@@ -204,6 +346,10 @@ mod tests {
             user_type: Some(UserType::Grower),
             tier: String::from("neighbor"),
             email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
         };
         assert!(require_grower(&ctx).is_ok());
     }
@@ -215,6 +361,10 @@ mod tests {
             user_type: Some(UserType::Gatherer),
             tier: String::from("neighbor"),
             email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
         };
         let result = require_grower(&ctx);
         assert!(result.is_err());
@@ -231,6 +381,10 @@ mod tests {
             user_type: None,
             tier: String::from("neighbor"),
             email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
         };
         let result = require_grower(&ctx);
         assert!(result.is_err());
@@ -263,6 +417,10 @@ mod tests {
             user_type: Some(UserType::Gatherer),
             tier: String::from("neighbor"),
             email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
         };
         assert!(require_user_type(&ctx, &UserType::Gatherer).is_ok());
     }
@@ -274,6 +432,10 @@ mod tests {
             user_type: Some(UserType::Grower),
             tier: String::from("neighbor"),
             email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
         };
         let result = require_user_type(&ctx, &UserType::Gatherer);
         assert!(result.is_err());
@@ -290,6 +452,10 @@ mod tests {
             user_type: None,
             tier: String::from("neighbor"),
             email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
         };
         let result = require_user_type(&ctx, &UserType::Grower);
         assert!(result.is_err());
@@ -299,6 +465,86 @@ mod tests {
             .contains("User type not set"));
     }
 
+    fn api_key_context(scopes: &[&str]) -> AuthContext {
+        AuthContext {
+            user_id: String::from("service-user"),
+            user_type: Some(UserType::Gatherer),
+            tier: String::from("neighbor"),
+            email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: Some(ApiKeyPrincipal {
+                key_id: String::from("key-1"),
+                organization_id: String::from("org-1"),
+                scopes: scopes.iter().map(ToString::to_string).collect(),
+            }),
+            impersonation: None,
+        }
+    }
+
+    #[test]
+    fn require_api_scope_allows_cognito_users_and_scoped_keys() {
+        let mut ctx = api_key_context(&[]);
+        ctx.api_key = None;
+        assert!(require_api_scope(&ctx, "feed:read").is_ok());
+        assert!(require_api_scope(&api_key_context(&["feed:read"]), "feed:read").is_ok());
+    }
+
+    #[test]
+    fn require_api_scope_rejects_key_without_scope() {
+        let result = require_api_scope(&api_key_context(&["listings:read"]), "claims:write");
+        assert!(result.unwrap_err().to_string().contains("Forbidden"));
+    }
+
+    #[test]
+    fn require_admin_rejects_api_key_principals() {
+        let mut ctx = api_key_context(&["feed:read"]);
+        ctx.is_admin = true;
+        assert!(require_admin(&ctx).is_err());
+        ctx.api_key = None;
+        assert!(require_admin(&ctx).is_ok());
+    }
+
+    #[test]
+    fn require_not_suspended_blocks_writes_only() {
+        let mut ctx = api_key_context(&[]);
+        ctx.api_key = None;
+        assert!(require_not_suspended(&ctx, "POST").is_ok());
+
+        ctx.is_suspended = true;
+        assert!(require_not_suspended(&ctx, "GET").is_ok());
+        for method in ["POST", "PUT", "PATCH", "DELETE"] {
+            let error = require_not_suspended(&ctx, method).unwrap_err();
+            assert_eq!(error.error_code(), "account_suspended", "{method}");
+        }
+    }
+
+    #[test]
+    fn require_read_only_impersonation_blocks_writes_only() {
+        let mut ctx = api_key_context(&[]);
+        ctx.api_key = None;
+        assert!(require_read_only_impersonation(&ctx, "DELETE").is_ok());
+
+        ctx.impersonation = Some(ImpersonationPrincipal {
+            session_id: String::from("session-1"),
+            admin_user_id: String::from("admin-1"),
+        });
+        assert!(require_read_only_impersonation(&ctx, "GET").is_ok());
+        assert!(require_read_only_impersonation(&ctx, "HEAD").is_ok());
+        for method in ["POST", "PUT", "PATCH", "DELETE"] {
+            let error = require_read_only_impersonation(&ctx, method).unwrap_err();
+            assert_eq!(error.error_code(), "impersonation_read_only", "{method}");
+        }
+    }
+
+    #[test]
+    fn parse_scopes_trims_and_skips_empty_entries() {
+        assert_eq!(
+            parse_scopes("feed:read, claims:write,,"),
+            vec!["feed:read".to_string(), "claims:write".to_string()]
+        );
+    }
+
     #[test]
     fn user_type_serialization() {
         let grower = UserType::Grower;
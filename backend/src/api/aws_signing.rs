@@ -0,0 +1,65 @@
+//! SigV4-signed requests to AWS APIs this binary has no SDK client for.
+//!
+//! The request is signed with the function's own credentials from the
+//! default provider chain, so IAM policies on the function apply as they
+//! would for an SDK call.
+
+use aws_config::BehaviorVersion;
+use aws_credential_types::provider::ProvideCredentials;
+use aws_sigv4::http_request::{sign, SignableBody, SignableRequest, SigningSettings};
+use aws_sigv4::sign::v4;
+use std::time::{Duration, SystemTime};
+
+/// Builds a signed JSON `POST` for `service`. `url_for` receives the
+/// configured region and returns the regional endpoint URL.
+pub async fn signed_json_post(
+    service: &str,
+    url_for: impl FnOnce(&str) -> String,
+    body: Vec<u8>,
+    timeout: Duration,
+) -> Result<reqwest::RequestBuilder, lambda_http::Error> {
+    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
+    let region = config
+        .region()
+        .ok_or_else(|| lambda_http::Error::from("AWS region is not configured"))?
+        .to_string();
+    let credentials = config
+        .credentials_provider()
+        .ok_or_else(|| lambda_http::Error::from("AWS credentials are not configured"))?
+        .provide_credentials()
+        .await
+        .map_err(|e| lambda_http::Error::from(format!("Failed to load credentials: {e}")))?;
+    let identity = credentials.into();
+
+    let url = url_for(&region);
+    let signing_params = v4::SigningParams::builder()
+        .identity(&identity)
+        .region(&region)
+        .name(service)
+        .time(SystemTime::now())
+        .settings(SigningSettings::default())
+        .build()
+        .map_err(|e| lambda_http::Error::from(format!("Failed to build signing params: {e}")))?
+        .into();
+    let signable = SignableRequest::new(
+        "POST",
+        url.as_str(),
+        std::iter::once(("content-type", "application/json")),
+        SignableBody::Bytes(&body),
+    )
+    .map_err(|e| lambda_http::Error::from(format!("Failed to sign {service} request: {e}")))?;
+    let (instructions, _signature) = sign(signable, &signing_params)
+        .map_err(|e| lambda_http::Error::from(format!("Failed to sign {service} request: {e}")))?
+        .into_parts();
+
+    let mut request = reqwest::Client::builder()
+        .timeout(timeout)
+        .build()?
+        .post(&url)
+        .header("content-type", "application/json");
+    for (name, value) in instructions.headers() {
+        request = request.header(name, value);
+    }
+
+    Ok(request.body(body))
+}
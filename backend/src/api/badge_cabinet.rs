@@ -1,8 +1,11 @@
+use crate::db::TimedQuery;
+use crate::error::ApiError;
 use serde::Serialize;
 use tokio_postgres::Client;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct BadgeCabinetEntry {
     pub badge_key: String,
@@ -16,12 +19,12 @@ pub async fn load_badges_read_only(
     user_id: Uuid,
 ) -> Result<Vec<BadgeCabinetEntry>, lambda_http::Error> {
     let rows = client
-        .query(
+        .query_timed("badge_cabinet::load_badges_read_only", 
             "select badge_key, awarded_at, coalesce((award_snapshot->>'proofCount')::int, 0) as proof_count from badge_award_audit where user_id = $1 order by awarded_at asc",
             &[&user_id],
         )
         .await
-        .map_err(|e| lambda_http::Error::from(format!("Database query error: {e}")))?;
+        .map_err(ApiError::from)?;
 
     Ok(rows
         .into_iter()
@@ -0,0 +1,680 @@
+//! Process configuration, read from the environment once at cold start.
+//!
+//! [`init`] validates every variable up front and fails the init phase with
+//! the full list of problems, so a bad deploy is caught before it serves a
+//! request instead of surfacing later as 500s. Handlers receive the loaded
+//! [`Config`] through the router; shared modules read it with [`get`].
+
+use crate::ai_model_config::AiModelConfig;
+use crate::handlers::listing_limits::ListingLimitsConfig;
+use crate::middleware::ai_guardrails::GuardrailsConfig;
+use crate::middleware::body_limits::BodyLimitsConfig;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::OnceLock;
+use std::time::Duration;
+
+static CONFIG: OnceLock<Config> = OnceLock::new();
+
+#[derive(Debug, Clone)]
+pub struct Config {
+    pub database: DatabaseConfig,
+    pub event_bus_name: String,
+    /// Allowed CORS origin for the frontend.
+    pub origin: String,
+    pub git_sha: String,
+    /// Default per-route deadline; routes may declare their own.
+    pub request_deadline: Duration,
+    pub body_limits: BodyLimitsConfig,
+    /// Rejects write payloads carrying keys no field accepts. Off until
+    /// clients stop sending extra keys; they are logged either way.
+    pub strict_request_fields: bool,
+    pub metrics_namespace: String,
+    pub feed_signing_secret: Option<Secret>,
+    pub stripe: StripeConfig,
+    pub signal_recompute_function_name: Option<String>,
+    /// How long a new pending claim reserves its quantity while the grower
+    /// reviews it. Zero turns holds off.
+    pub claim_hold: Duration,
+    pub listing_limits: ListingLimitsConfig,
+    pub geocoder: GeocoderConfig,
+    pub ai: AiConfig,
+    pub logging: LoggingConfig,
+}
+
+#[derive(Debug, Clone)]
+pub struct DatabaseConfig {
+    pub url: Secret,
+    pub statement_timeout_ms: u64,
+    pub pool_max_idle: usize,
+    pub pool_health_check_after: Duration,
+    pub retry_attempts: u32,
+    pub slow_query_threshold: Duration,
+}
+
+/// Billing is optional per environment, so each value is checked where it is
+/// used and a missing one answers `503 not_configured`.
+#[derive(Debug, Clone, Default)]
+pub struct StripeConfig {
+    pub secret_key: Option<Secret>,
+    pub premium_price_id: Option<String>,
+    pub webhook_secret: Option<Secret>,
+}
+
+#[derive(Debug, Clone)]
+pub struct GeocoderConfig {
+    pub provider: GeocoderProviderKind,
+    /// Nominatim endpoint.
+    pub base_url: String,
+    pub user_agent: String,
+    pub timeout: Duration,
+    /// Amazon Location place index; required for the `aws_location` provider.
+    pub place_index: Option<String>,
+    /// How long a `geocode_cache` row is trusted before the provider is asked
+    /// again.
+    pub cache_ttl: Duration,
+    /// The same for `geo_labels` rows. Area names change rarely, so this is
+    /// longer than the address cache.
+    pub label_ttl: Duration,
+    /// Listing pickup and grower addresses whose best match scores below this
+    /// (0 to 1) are rejected with suggestions instead of saved.
+    pub min_confidence: f64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeocoderProviderKind {
+    Nominatim,
+    AwsLocation,
+    Stub,
+}
+
+/// Log fields rewritten by `log_redaction` before a line is written. Names
+/// match field keys anywhere in the record, ignoring case.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggingConfig {
+    /// Replaced by a short hash, so equal values still correlate.
+    pub hashed_fields: Vec<String>,
+    /// Coordinates rounded to two decimal places, roughly 1 km.
+    pub coarse_fields: Vec<String>,
+    /// Logged in clear despite the lists above. Refused in `prod`.
+    pub unredacted_fields: Vec<String>,
+}
+
+const DEFAULT_HASHED_LOG_FIELDS: &[&str] = &[
+    "email",
+    "address",
+    "pickup_address",
+    "effective_pickup_address",
+    "phone",
+];
+const DEFAULT_COARSE_LOG_FIELDS: &[&str] = &["lat", "lng", "latitude", "longitude"];
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self {
+            hashed_fields: to_strings(DEFAULT_HASHED_LOG_FIELDS),
+            coarse_fields: to_strings(DEFAULT_COARSE_LOG_FIELDS),
+            unredacted_fields: Vec::new(),
+        }
+    }
+}
+
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| (*value).to_string()).collect()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SummaryProviderKind {
+    Bedrock,
+    Template,
+    OpenAiCompatible,
+}
+
+#[derive(Debug, Clone)]
+pub struct AiConfig {
+    pub summary_provider: SummaryProviderKind,
+    pub model: AiModelConfig,
+    pub bedrock_enabled: bool,
+    pub bedrock_timeout: Duration,
+    pub openai: OpenAiConfig,
+    pub breaker_failure_threshold: u32,
+    pub breaker_cooldown: Duration,
+    pub guardrails: GuardrailsConfig,
+    pub input_cost_per_1k: f64,
+    pub output_cost_per_1k: f64,
+}
+
+#[derive(Debug, Clone)]
+pub struct OpenAiConfig {
+    pub base_url: Option<String>,
+    pub api_key: Option<Secret>,
+    pub model: String,
+    pub timeout: Duration,
+}
+
+/// A value that must never reach the logs. `Debug` prints a placeholder.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Secret(String);
+
+impl Secret {
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    #[cfg(test)]
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+}
+
+impl fmt::Debug for Secret {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Secret([redacted])")
+    }
+}
+
+/// Every problem found while loading, reported together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigError {
+    pub problems: Vec<String>,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Invalid configuration: {}", self.problems.join("; "))
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+/// Loads and validates the environment. Called once from `main`.
+pub fn init() -> Result<&'static Config, ConfigError> {
+    if let Some(config) = CONFIG.get() {
+        return Ok(config);
+    }
+    let config = Config::from_env()?;
+    Ok(CONFIG.get_or_init(|| config))
+}
+
+/// The configuration loaded by [`init`].
+#[cfg(not(test))]
+pub fn get() -> &'static Config {
+    CONFIG
+        .get()
+        .unwrap_or_else(|| unreachable!("config::init runs before the first request"))
+}
+
+/// Unit tests never run `main`, so they share a config built from defaults.
+#[cfg(test)]
+pub fn get() -> &'static Config {
+    CONFIG.get_or_init(Config::for_tests)
+}
+
+impl Config {
+    pub fn from_env() -> Result<Self, ConfigError> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// Builds the configuration from `lookup`, which returns a variable's raw
+    /// value. Blank values count as unset.
+    pub fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, ConfigError> {
+        let mut env = Reader {
+            lookup: &lookup,
+            problems: Vec::new(),
+        };
+
+        let config = Self {
+            database: DatabaseConfig {
+                url: Secret(env.database_url()),
+                statement_timeout_ms: env.positive("DB_STATEMENT_TIMEOUT_MS", 3000),
+                pool_max_idle: env.parsed("DB_POOL_MAX_IDLE", 2),
+                pool_health_check_after: Duration::from_secs(
+                    env.parsed("DB_POOL_HEALTH_CHECK_AFTER_SECS", 30),
+                ),
+                retry_attempts: env.positive("DB_RETRY_ATTEMPTS", 3),
+                slow_query_threshold: Duration::from_millis(env.parsed("DB_SLOW_QUERY_MS", 250)),
+            },
+            event_bus_name: env.string("EVENT_BUS_NAME", "default"),
+            origin: env.string("ORIGIN", "http://localhost:5173"),
+            git_sha: env.string("GIT_SHA", "unknown"),
+            // Leaves headroom under the 5s Lambda timeout to log and return the 503.
+            request_deadline: Duration::from_millis(env.positive("REQUEST_DEADLINE_MS", 4000)),
+            body_limits: BodyLimitsConfig {
+                max_body_bytes: env.positive("MAX_REQUEST_BODY_BYTES", 128 * 1024),
+                max_json_depth: env.positive("MAX_JSON_DEPTH", 32),
+            },
+            strict_request_fields: env.parsed("STRICT_REQUEST_FIELDS", false),
+            metrics_namespace: env.string("METRICS_NAMESPACE", "CommunityGarden"),
+            feed_signing_secret: env.optional("FEED_SIGNING_SECRET").map(Secret),
+            stripe: StripeConfig {
+                secret_key: env.optional("STRIPE_SECRET_KEY").map(Secret),
+                premium_price_id: env.optional("STRIPE_PREMIUM_PRICE_ID"),
+                webhook_secret: env.optional("STRIPE_WEBHOOK_SECRET").map(Secret),
+            },
+            signal_recompute_function_name: env.optional("SIGNAL_RECOMPUTE_FUNCTION_NAME"),
+            claim_hold: Duration::from_secs(
+                env.parsed("CLAIM_HOLD_MINUTES", 30_u64).saturating_mul(60),
+            ),
+            listing_limits: ListingLimitsConfig {
+                max_live_per_user: env.non_negative("LISTING_MAX_LIVE_PER_USER", 20),
+                max_created_per_hour: env.non_negative("LISTING_MAX_CREATED_PER_HOUR", 5),
+            },
+            geocoder: env.geocoder(),
+            ai: env.ai(),
+            logging: env.logging(),
+        };
+
+        if env.problems.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError {
+                problems: env.problems,
+            })
+        }
+    }
+
+    /// Defaults plus a placeholder database and the stub geocoder, so no test
+    /// reaches a real provider.
+    #[cfg(test)]
+    pub fn for_tests() -> Self {
+        Self::from_lookup(|name| match name {
+            "DATABASE_URL" => Some("postgres://localhost/test".to_string()),
+            "GEOCODER_PROVIDER" => Some("stub".to_string()),
+            _ => None,
+        })
+        .unwrap_or_else(|error| unreachable!("defaults are valid: {error}"))
+    }
+}
+
+struct Reader<'a, F: Fn(&str) -> Option<String>> {
+    lookup: &'a F,
+    problems: Vec<String>,
+}
+
+impl<F: Fn(&str) -> Option<String>> Reader<'_, F> {
+    fn optional(&self, name: &str) -> Option<String> {
+        (self.lookup)(name)
+            .map(|value| value.trim().to_string())
+            .filter(|value| !value.is_empty())
+    }
+
+    fn string(&self, name: &str, default: &str) -> String {
+        self.optional(name).unwrap_or_else(|| default.to_string())
+    }
+
+    fn parsed<T: FromStr>(&mut self, name: &str, default: T) -> T {
+        let Some(raw) = self.optional(name) else {
+            return default;
+        };
+        raw.parse().unwrap_or_else(|_| {
+            self.problems
+                .push(format!("{name} has invalid value {raw:?}"));
+            default
+        })
+    }
+
+    fn positive<T: FromStr + PartialOrd + Default + Copy>(&mut self, name: &str, default: T) -> T {
+        let value = self.parsed(name, default);
+        if value > T::default() {
+            value
+        } else {
+            self.problems.push(format!("{name} must be greater than 0"));
+            default
+        }
+    }
+
+    fn non_negative(&mut self, name: &str, default: i64) -> i64 {
+        let value = self.parsed(name, default);
+        if value >= 0 {
+            value
+        } else {
+            self.problems.push(format!("{name} must not be negative"));
+            default
+        }
+    }
+
+    fn database_url(&mut self) -> String {
+        let Some(url) = self.optional("DATABASE_URL") else {
+            self.problems.push("DATABASE_URL is required".to_string());
+            return String::new();
+        };
+        if !(url.starts_with("postgres://") || url.starts_with("postgresql://")) {
+            self.problems
+                .push("DATABASE_URL must be a postgres:// URL".to_string());
+        }
+        url
+    }
+
+    /// Comma-separated names; unset keeps `default`.
+    fn list(&self, name: &str, default: &[&str]) -> Vec<String> {
+        self.optional(name).map_or_else(
+            || to_strings(default),
+            |raw| {
+                raw.split(',')
+                    .map(str::trim)
+                    .filter(|item| !item.is_empty())
+                    .map(str::to_string)
+                    .collect()
+            },
+        )
+    }
+
+    fn logging(&mut self) -> LoggingConfig {
+        let logging = LoggingConfig {
+            hashed_fields: self.list("LOG_HASHED_FIELDS", DEFAULT_HASHED_LOG_FIELDS),
+            coarse_fields: self.list("LOG_COARSE_FIELDS", DEFAULT_COARSE_LOG_FIELDS),
+            unredacted_fields: self.list("LOG_UNREDACTED_FIELDS", &[]),
+        };
+        if !logging.unredacted_fields.is_empty()
+            && self.optional("ENVIRONMENT_NAME").as_deref() == Some("prod")
+        {
+            self.problems
+                .push("LOG_UNREDACTED_FIELDS is not allowed in prod".to_string());
+        }
+        logging
+    }
+
+    fn geocoder(&mut self) -> GeocoderConfig {
+        let provider = match self
+            .optional("GEOCODER_PROVIDER")
+            .map(|value| value.to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("nominatim") => GeocoderProviderKind::Nominatim,
+            Some("aws_location" | "aws") => GeocoderProviderKind::AwsLocation,
+            Some("stub") => GeocoderProviderKind::Stub,
+            Some(other) => {
+                self.problems
+                    .push(format!("GEOCODER_PROVIDER has unknown value {other:?}"));
+                GeocoderProviderKind::Nominatim
+            }
+        };
+
+        let place_index = self.optional("GEOCODER_PLACE_INDEX");
+        if provider == GeocoderProviderKind::AwsLocation && place_index.is_none() {
+            self.problems.push(
+                "GEOCODER_PLACE_INDEX is required when GEOCODER_PROVIDER is aws_location"
+                    .to_string(),
+            );
+        }
+
+        GeocoderConfig {
+            provider,
+            base_url: self.string("GEOCODER_BASE_URL", "https://nominatim.openstreetmap.org"),
+            user_agent: self.string(
+                "GEOCODER_USER_AGENT",
+                "community-garden/0.1 (+https://github.com/allenheltondev/community-garden)",
+            ),
+            timeout: Duration::from_millis(self.positive("GEOCODER_TIMEOUT_MS", 3_000)),
+            place_index,
+            cache_ttl: Duration::from_secs(
+                self.positive("GEOCODE_CACHE_TTL_DAYS", 30_u64) * 86_400,
+            ),
+            label_ttl: Duration::from_secs(self.positive("GEO_LABEL_TTL_DAYS", 90_u64) * 86_400),
+            min_confidence: self.fraction("GEOCODE_MIN_CONFIDENCE", 0.6),
+        }
+    }
+
+    fn cost(&mut self, name: &str, default: f64) -> f64 {
+        let value = self.parsed(name, default);
+        if value.is_finite() && value >= 0.0 {
+            value
+        } else {
+            self.problems
+                .push(format!("{name} must be a non-negative number"));
+            default
+        }
+    }
+
+    fn fraction(&mut self, name: &str, default: f64) -> f64 {
+        let value = self.parsed(name, default);
+        if (0.0..=1.0).contains(&value) {
+            value
+        } else {
+            self.problems
+                .push(format!("{name} must be between 0 and 1"));
+            default
+        }
+    }
+
+    fn ai(&mut self) -> AiConfig {
+        let summary_provider = match self
+            .optional("AI_SUMMARY_PROVIDER")
+            .map(|value| value.to_ascii_lowercase())
+            .as_deref()
+        {
+            None | Some("bedrock") => SummaryProviderKind::Bedrock,
+            // `mock` is kept as an alias for `template`.
+            Some("template" | "mock") => SummaryProviderKind::Template,
+            Some("openai" | "openai_compatible") => SummaryProviderKind::OpenAiCompatible,
+            Some(other) => {
+                self.problems
+                    .push(format!("AI_SUMMARY_PROVIDER has unknown value {other:?}"));
+                SummaryProviderKind::Bedrock
+            }
+        };
+
+        let openai = OpenAiConfig {
+            base_url: self.optional("OPENAI_BASE_URL"),
+            api_key: self.optional("OPENAI_API_KEY").map(Secret),
+            model: self.string("OPENAI_SUMMARY_MODEL", "gpt-4o-mini"),
+            timeout: Duration::from_millis(self.positive("OPENAI_SUMMARY_TIMEOUT_MS", 2500)),
+        };
+        if summary_provider == SummaryProviderKind::OpenAiCompatible && openai.base_url.is_none() {
+            self.problems
+                .push("OPENAI_BASE_URL is required when AI_SUMMARY_PROVIDER is openai".to_string());
+        }
+
+        AiConfig {
+            summary_provider,
+            model: AiModelConfig {
+                provider: self.string("AI_PROVIDER", "bedrock"),
+                model_id: self
+                    .optional("BEDROCK_MODEL_PRIMARY")
+                    .or_else(|| self.optional("BEDROCK_MODEL_ID"))
+                    .unwrap_or_else(|| "amazon.nova-lite-v1:0".to_string()),
+                fallback_model_id: self.string("BEDROCK_MODEL_FALLBACK", "amazon.nova-micro-v1:0"),
+                region: self
+                    .optional("BEDROCK_REGION")
+                    .or_else(|| self.optional("AWS_REGION"))
+                    .unwrap_or_else(|| "us-east-1".to_string()),
+                response_mode: self.string("AI_RESPONSE_MODE", "tool_first_json"),
+                schema_version: self.string("AI_RESPONSE_SCHEMA_VERSION", "v1"),
+            },
+            bedrock_enabled: self.optional("BEDROCK_SUMMARY_ENABLED").as_deref() == Some("1"),
+            bedrock_timeout: Duration::from_millis(
+                self.positive("BEDROCK_SUMMARY_TIMEOUT_MS", 2500),
+            ),
+            openai,
+            breaker_failure_threshold: self.positive("AI_SUMMARY_BREAKER_FAILURES", 3),
+            breaker_cooldown: Duration::from_secs(
+                self.parsed("AI_SUMMARY_BREAKER_COOLDOWN_SECS", 60),
+            ),
+            guardrails: GuardrailsConfig {
+                max_daily_requests_per_user: self.positive("AI_MAX_DAILY_REQUESTS_PER_USER", 30),
+                max_daily_tokens_per_user: self.positive("AI_MAX_DAILY_TOKENS_PER_USER", 60_000),
+                default_estimated_tokens: self.positive("AI_DEFAULT_ESTIMATED_TOKENS", 1200),
+            },
+            // Defaults are Nova Lite prices.
+            input_cost_per_1k: self.cost("AI_COST_PER_1K_INPUT_TOKENS_USD", 0.000_06),
+            output_cost_per_1k: self.cost("AI_COST_PER_1K_OUTPUT_TOKENS_USD", 0.000_24),
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(vars: &[(&str, &str)]) -> Result<Config, ConfigError> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect();
+        Config::from_lookup(|name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn defaults_only_need_a_database_url() {
+        let config = load(&[("DATABASE_URL", "postgres://db/app")]).unwrap();
+        assert_eq!(config.database.url.expose(), "postgres://db/app");
+        assert_eq!(config.database.retry_attempts, 3);
+        assert_eq!(config.event_bus_name, "default");
+        assert_eq!(config.request_deadline, Duration::from_millis(4000));
+        assert_eq!(config.ai.summary_provider, SummaryProviderKind::Bedrock);
+        assert_eq!(config.geocoder.provider, GeocoderProviderKind::Nominatim);
+        assert!(config.stripe.secret_key.is_none());
+        assert!(!config.strict_request_fields);
+        assert_eq!(config.claim_hold, Duration::from_secs(30 * 60));
+        assert_eq!(config.listing_limits.max_live_per_user, 20);
+        assert_eq!(config.listing_limits.max_created_per_hour, 5);
+    }
+
+    #[test]
+    fn listing_limits_can_be_turned_off_but_not_negative() {
+        let config = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("LISTING_MAX_CREATED_PER_HOUR", "0"),
+        ])
+        .unwrap();
+        assert_eq!(config.listing_limits.max_created_per_hour, 0);
+
+        let error = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("LISTING_MAX_LIVE_PER_USER", "-1"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.problems,
+            vec!["LISTING_MAX_LIVE_PER_USER must not be negative"]
+        );
+    }
+
+    #[test]
+    fn blank_values_count_as_unset() {
+        let config = load(&[("DATABASE_URL", "postgres://db/app"), ("GIT_SHA", "  ")]).unwrap();
+        assert_eq!(config.git_sha, "unknown");
+    }
+
+    #[test]
+    fn reports_every_problem_at_once() {
+        let error = load(&[
+            ("DB_RETRY_ATTEMPTS", "0"),
+            ("REQUEST_DEADLINE_MS", "soon"),
+            ("AI_SUMMARY_PROVIDER", "gpt"),
+        ])
+        .unwrap_err();
+        assert_eq!(error.problems.len(), 4);
+        assert!(error.problems[0].contains("DATABASE_URL"));
+        assert!(error.to_string().contains("REQUEST_DEADLINE_MS"));
+        assert!(error.to_string().contains("AI_SUMMARY_PROVIDER"));
+    }
+
+    #[test]
+    fn rejects_non_postgres_database_url() {
+        let error = load(&[("DATABASE_URL", "mysql://db/app")]).unwrap_err();
+        assert_eq!(
+            error.problems,
+            vec!["DATABASE_URL must be a postgres:// URL"]
+        );
+    }
+
+    #[test]
+    fn openai_provider_requires_base_url() {
+        let error = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("AI_SUMMARY_PROVIDER", "OpenAI"),
+        ])
+        .unwrap_err();
+        assert!(error.problems[0].contains("OPENAI_BASE_URL"));
+    }
+
+    #[test]
+    fn aws_location_geocoder_requires_place_index() {
+        let error = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("GEOCODER_PROVIDER", "aws_location"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.problems,
+            vec!["GEOCODER_PLACE_INDEX is required when GEOCODER_PROVIDER is aws_location"]
+        );
+
+        let config = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("GEOCODER_PROVIDER", "AWS_LOCATION"),
+            ("GEOCODER_PLACE_INDEX", "garden-places"),
+            ("GEOCODE_CACHE_TTL_DAYS", "7"),
+        ])
+        .unwrap();
+        assert_eq!(config.geocoder.provider, GeocoderProviderKind::AwsLocation);
+        assert_eq!(config.geocoder.cache_ttl, Duration::from_secs(7 * 86_400));
+        assert_eq!(config.geocoder.label_ttl, Duration::from_secs(90 * 86_400));
+        assert!((config.geocoder.min_confidence - 0.6).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn geocode_min_confidence_must_be_a_fraction() {
+        let error = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("GEOCODE_MIN_CONFIDENCE", "60"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.problems,
+            vec!["GEOCODE_MIN_CONFIDENCE must be between 0 and 1"]
+        );
+    }
+
+    #[test]
+    fn bedrock_model_falls_back_to_legacy_variable() {
+        let config = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("BEDROCK_MODEL_ID", "legacy-model"),
+        ])
+        .unwrap();
+        assert_eq!(config.ai.model.model_id, "legacy-model");
+    }
+
+    #[test]
+    fn log_redaction_lists_are_configurable() {
+        let config = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("LOG_HASHED_FIELDS", "email, phone,"),
+            ("LOG_UNREDACTED_FIELDS", "lat"),
+        ])
+        .unwrap();
+        assert_eq!(config.logging.hashed_fields, vec!["email", "phone"]);
+        assert!(config.logging.coarse_fields.contains(&"lat".to_string()));
+        assert_eq!(config.logging.unredacted_fields, vec!["lat"]);
+    }
+
+    #[test]
+    fn unredacted_log_fields_are_refused_in_prod() {
+        let error = load(&[
+            ("DATABASE_URL", "postgres://db/app"),
+            ("ENVIRONMENT_NAME", "prod"),
+            ("LOG_UNREDACTED_FIELDS", "email"),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            error.problems,
+            vec!["LOG_UNREDACTED_FIELDS is not allowed in prod"]
+        );
+    }
+
+    #[test]
+    fn secrets_are_redacted_in_debug_output() {
+        let config = load(&[
+            ("DATABASE_URL", "postgres://user:hunter2@db/app"),
+            ("STRIPE_SECRET_KEY", "sk_live_123"),
+        ])
+        .unwrap();
+        let debug = format!("{config:?}");
+        assert!(!debug.contains("hunter2"));
+        assert!(!debug.contains("sk_live_123"));
+    }
+}
@@ -0,0 +1,82 @@
+//! How precisely coordinates are shown in responses. Stored lat/lng keep
+//! geocoding precision for distance checks; responses only carry the values
+//! rounded here, so a home or pickup address cannot be read back from them.
+//! Every response that includes coordinates maps them through this module.
+
+use crate::location;
+use tokio_postgres::Row;
+
+/// A kind of record whose coordinates appear in responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LocatedEntity {
+    Listing,
+    Request,
+    GrowerProfile,
+    GathererProfile,
+    CommunityEvent,
+}
+
+impl LocatedEntity {
+    /// Decimal places kept in responses. Two places is about a kilometre:
+    /// enough for a neighborhood, not a house. Community events are held at
+    /// public venues whose address is returned anyway, so their pins keep
+    /// street-level precision.
+    pub const fn response_precision(self) -> i32 {
+        match self {
+            Self::Listing | Self::Request | Self::GrowerProfile | Self::GathererProfile => 2,
+            Self::CommunityEvent => 4,
+        }
+    }
+
+    pub fn round(self, value: f64) -> f64 {
+        location::round_coordinate(value, self.response_precision())
+    }
+
+    /// Reads a required lat/lng pair from `row` and rounds it.
+    pub fn point(self, row: &Row, lat_column: &str, lng_column: &str) -> (f64, f64) {
+        (
+            self.round(row.get(lat_column)),
+            self.round(row.get(lng_column)),
+        )
+    }
+
+    /// Reads a nullable lat/lng pair from `row` and rounds whatever is set.
+    pub fn optional_point(
+        self,
+        row: &Row,
+        lat_column: &str,
+        lng_column: &str,
+    ) -> (Option<f64>, Option<f64>) {
+        (
+            row.get::<_, Option<f64>>(lat_column)
+                .map(|lat| self.round(lat)),
+            row.get::<_, Option<f64>>(lng_column)
+                .map(|lng| self.round(lng)),
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::float_cmp)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn private_locations_are_rounded_to_a_neighborhood() {
+        for entity in [
+            LocatedEntity::Listing,
+            LocatedEntity::Request,
+            LocatedEntity::GrowerProfile,
+            LocatedEntity::GathererProfile,
+        ] {
+            assert_eq!(entity.round(37.77493), 37.77);
+            assert_eq!(entity.round(-122.41942), -122.42);
+        }
+    }
+
+    #[test]
+    fn community_events_keep_venue_precision() {
+        assert_eq!(LocatedEntity::CommunityEvent.round(37.77493), 37.7749);
+        assert_eq!(LocatedEntity::CommunityEvent.round(-122.41942), -122.4194);
+    }
+}
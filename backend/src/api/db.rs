@@ -1,46 +1,522 @@
-use rustls::{ClientConfig, RootCertStore};
-use std::env;
-use std::str::FromStr;
-use tokio_postgres::config::{ChannelBinding, Config};
-use tokio_postgres::Client;
+use crate::config::{self as app_config, DatabaseConfig};
+use crate::error::ApiError;
+use crate::metrics;
+use crate::pg_tls::ConnectSettings;
+use crate::telemetry;
+use rand::Rng;
+use std::future::Future;
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+use tokio_postgres::config::Config;
+use tokio_postgres::types::ToSql;
+use tokio_postgres::{Client, GenericClient, Row, Transaction};
 use tokio_postgres_rustls::MakeRustlsConnect;
+use tracing::Instrument;
 
-pub async fn connect() -> Result<Client, lambda_http::Error> {
-    let database_url = env::var("DATABASE_URL")
-        .map_err(|_| lambda_http::Error::from("DATABASE_URL is required".to_string()))?;
+const RETRY_BASE_DELAY: Duration = Duration::from_millis(50);
+const RETRY_MAX_DELAY: Duration = Duration::from_secs(1);
 
-    let mut config = Config::from_str(&database_url)
-        .map_err(|e| lambda_http::Error::from(format!("Invalid DATABASE_URL: {e}")))?;
+static POOL: OnceLock<Pool> = OnceLock::new();
 
-    if matches!(config.get_channel_binding(), ChannelBinding::Require) {
-        config.channel_binding(ChannelBinding::Prefer);
+/// Connections kept warm for the life of the execution environment. Lambda
+/// serves one request at a time per environment, so a handful of idle clients
+/// covers handlers that hold more than one connection at once.
+struct Pool {
+    config: Config,
+    tls_connector: MakeRustlsConnect,
+    max_idle: usize,
+    health_check_after: Duration,
+    idle: Mutex<Vec<IdleClient>>,
+}
+
+struct IdleClient {
+    client: Client,
+    returned_at: Instant,
+}
+
+/// A checked-out connection. Dereferences to [`Client`] and goes back to the
+/// pool on drop unless the underlying connection has closed.
+pub struct PooledClient {
+    client: Option<Client>,
+}
+
+impl std::fmt::Debug for PooledClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PooledClient")
+            .field(
+                "open",
+                &self.client.as_ref().is_some_and(|c| !c.is_closed()),
+            )
+            .finish()
+    }
+}
+
+impl Deref for PooledClient {
+    type Target = Client;
+
+    fn deref(&self) -> &Client {
+        self.client
+            .as_ref()
+            .unwrap_or_else(|| unreachable!("client is only taken on drop"))
+    }
+}
+
+impl DerefMut for PooledClient {
+    fn deref_mut(&mut self) -> &mut Client {
+        self.client
+            .as_mut()
+            .unwrap_or_else(|| unreachable!("client is only taken on drop"))
+    }
+}
+
+impl Drop for PooledClient {
+    fn drop(&mut self) {
+        let (Some(client), Some(pool)) = (self.client.take(), POOL.get()) else {
+            return;
+        };
+        pool.release(client);
+    }
+}
+
+pub async fn connect() -> Result<PooledClient, lambda_http::Error> {
+    let pool = pool()?;
+
+    while let Some(idle) = pool.take_idle() {
+        if pool.is_healthy(&idle).await {
+            metrics::record_db_pool_checkout(true, pool.idle_count());
+            return Ok(PooledClient {
+                client: Some(idle.client),
+            });
+        }
+        tracing::warn!("Discarding unhealthy pooled Postgres connection");
+    }
+
+    let client = retry("connect", || pool.open()).await?;
+    metrics::record_db_pool_checkout(false, pool.idle_count());
+    Ok(PooledClient {
+        client: Some(client),
+    })
+}
+
+/// Points [`connect`] at `database_url` instead of the configured database
+/// for the rest of the process. Must run before the first connection.
+#[cfg(test)]
+pub fn use_database_url(database_url: &str) -> Result<(), lambda_http::Error> {
+    let database = DatabaseConfig {
+        url: app_config::Secret::new(database_url),
+        ..app_config::get().database.clone()
+    };
+    let pool = Pool::from_config(&database)?;
+    POOL.set(pool)
+        .map_err(|_| lambda_http::Error::from("database pool is already initialized"))
+}
+
+fn pool() -> Result<&'static Pool, lambda_http::Error> {
+    if let Some(pool) = POOL.get() {
+        return Ok(pool);
+    }
+
+    let pool = Pool::from_config(&app_config::get().database)?;
+    Ok(POOL.get_or_init(|| pool))
+}
+
+impl Pool {
+    fn from_config(database: &DatabaseConfig) -> Result<Self, lambda_http::Error> {
+        let settings = ConnectSettings::from_database_url(database.url.expose())
+            .map_err(lambda_http::Error::from)?;
+        let tls_connector = settings.tls_connector().map_err(lambda_http::Error::from)?;
+
+        let mut config = settings.config;
+        let options = with_statement_timeout(config.get_options(), database.statement_timeout_ms);
+        config.options(&options);
+
+        Ok(Self {
+            config,
+            tls_connector,
+            max_idle: database.pool_max_idle,
+            health_check_after: database.pool_health_check_after,
+            idle: Mutex::new(Vec::new()),
+        })
     }
 
-    let cert_result = rustls_native_certs::load_native_certs();
-    let mut root_store = RootCertStore::empty();
-    let (added, _) = root_store.add_parsable_certificates(cert_result.certs);
+    async fn open(&self) -> Result<Client, ApiError> {
+        let started_at = Instant::now();
+        let connected = self.config.connect(self.tls_connector.clone()).await;
+        metrics::record_db_connect(started_at.elapsed(), connected.is_ok());
+
+        let (client, connection) = connected.map_err(|e| {
+            let error = ApiError::from(e);
+            if error.is_retryable() {
+                error
+            } else {
+                ApiError::internal(format!("Database connection error: {error}"))
+            }
+        })?;
+
+        tokio::spawn(async move {
+            if let Err(e) = connection.await {
+                tracing::error!(error = %e, error_debug = ?e, "Postgres connection error");
+            }
+        });
+
+        Ok(client)
+    }
 
-    if added == 0 {
-        return Err(lambda_http::Error::from(
-            "No native root certificates available for TLS".to_string(),
-        ));
+    fn take_idle(&self) -> Option<IdleClient> {
+        self.idle.lock().ok()?.pop()
     }
 
-    let tls_config = ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
-    let tls_connector = MakeRustlsConnect::new(tls_config);
+    fn idle_count(&self) -> usize {
+        self.idle.lock().map_or(0, |idle| idle.len())
+    }
 
-    let (client, connection) = config
-        .connect(tls_connector)
-        .await
-        .map_err(|e| lambda_http::Error::from(format!("Database connection error: {e}")))?;
+    /// Closed connections are dropped outright; connections that sat idle
+    /// long enough for RDS or a NAT to have silently cut them get a ping.
+    async fn is_healthy(&self, idle: &IdleClient) -> bool {
+        if idle.client.is_closed() {
+            return false;
+        }
+        if idle.returned_at.elapsed() < self.health_check_after {
+            return true;
+        }
+        idle.client.simple_query("select 1").await.is_ok()
+    }
 
-    tokio::spawn(async move {
-        if let Err(e) = connection.await {
-            tracing::error!(error = %e, error_debug = ?e, "Postgres connection error");
+    fn release(&self, client: Client) {
+        if client.is_closed() {
+            return;
+        }
+        let Ok(mut idle) = self.idle.lock() else {
+            return;
+        };
+        if idle.len() < self.max_idle {
+            idle.push(IdleClient {
+                client,
+                returned_at: Instant::now(),
+            });
         }
-    });
+    }
+}
+
+/// Appends a server-side `statement_timeout` to any startup options already
+/// in `DATABASE_URL`. Postgres cancels the statement itself, so a query
+/// abandoned by a request deadline cannot keep running on a pooled connection.
+fn with_statement_timeout(existing: Option<&str>, timeout_ms: u64) -> String {
+    let timeout = format!("-c statement_timeout={timeout_ms}");
+    match existing
+        .map(str::trim)
+        .filter(|options| !options.is_empty())
+    {
+        Some(options) => format!("{options} {timeout}"),
+        None => timeout,
+    }
+}
+
+/// Runs `attempt` until it succeeds, fails with a non-transient error, or
+/// exhausts `DB_RETRY_ATTEMPTS`. Each attempt must open its own connection
+/// and transaction so a replay starts from a clean slate.
+pub async fn retry<T, F, Fut>(operation: &'static str, mut attempt: F) -> Result<T, ApiError>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T, ApiError>>,
+{
+    let max_attempts = app_config::get().database.retry_attempts;
 
-    Ok(client)
+    let mut attempt_number = 1;
+    loop {
+        match attempt().await {
+            Ok(value) => {
+                if attempt_number > 1 {
+                    tracing::info!(
+                        operation = operation,
+                        attempts = attempt_number,
+                        "Database operation succeeded after retry"
+                    );
+                }
+                return Ok(value);
+            }
+            Err(error) if error.is_retryable() && attempt_number < max_attempts => {
+                let delay = backoff_delay(attempt_number, rand::thread_rng().gen_range(0.5..=1.0));
+                tracing::warn!(
+                    operation = operation,
+                    attempt = attempt_number,
+                    max_attempts = max_attempts,
+                    delay_ms = u64::try_from(delay.as_millis()).unwrap_or(u64::MAX),
+                    error = %error,
+                    "Transient database error; retrying"
+                );
+                tokio::time::sleep(delay).await;
+                attempt_number += 1;
+            }
+            Err(error) => {
+                if error.is_retryable() {
+                    tracing::error!(
+                        operation = operation,
+                        attempts = attempt_number,
+                        error = %error,
+                        "Transient database error persisted after retries"
+                    );
+                }
+                return Err(error);
+            }
+        }
+    }
+}
+
+/// Commits `tx`. A connection lost mid-COMMIT leaves the outcome unknown, so
+/// it is reported as a non-retryable error instead of letting `retry` replay
+/// a write that may already have landed.
+pub async fn commit(tx: Transaction<'_>) -> Result<(), ApiError> {
+    tx.commit().await.map_err(|error| {
+        if error.code().is_some() {
+            ApiError::from(error)
+        } else {
+            ApiError::internal(format!("Commit outcome unknown: {error}"))
+        }
+    })
+}
+
+/// Timed versions of the [`GenericClient`] query methods, usable on pooled
+/// clients and transactions alike. `label` names the calling code path; the
+/// statement verb and table are derived from the SQL.
+pub trait TimedQuery: GenericClient + Sync {
+    fn query_timed<'a>(
+        &'a self,
+        label: &'static str,
+        sql: &'a str,
+        params: &'a [&'a (dyn ToSql + Sync)],
+    ) -> impl Future<Output = Result<Vec<Row>, tokio_postgres::Error>> + Send + 'a {
+        async move {
+            let started_at = Instant::now();
+            let result = self.query(sql, params).await;
+            log_query(
+                label,
+                sql,
+                started_at.elapsed(),
+                result.as_ref().map_or(0, Vec::len),
+            );
+            result
+        }
+        .instrument(telemetry::db_span(label, &statement_summary(sql)))
+    }
+
+    fn query_one_timed<'a>(
+        &'a self,
+        label: &'static str,
+        sql: &'a str,
+        params: &'a [&'a (dyn ToSql + Sync)],
+    ) -> impl Future<Output = Result<Row, tokio_postgres::Error>> + Send + 'a {
+        async move {
+            let started_at = Instant::now();
+            let result = self.query_one(sql, params).await;
+            log_query(
+                label,
+                sql,
+                started_at.elapsed(),
+                usize::from(result.is_ok()),
+            );
+            result
+        }
+        .instrument(telemetry::db_span(label, &statement_summary(sql)))
+    }
+
+    fn query_opt_timed<'a>(
+        &'a self,
+        label: &'static str,
+        sql: &'a str,
+        params: &'a [&'a (dyn ToSql + Sync)],
+    ) -> impl Future<Output = Result<Option<Row>, tokio_postgres::Error>> + Send + 'a {
+        async move {
+            let started_at = Instant::now();
+            let result = self.query_opt(sql, params).await;
+            let rows = result.as_ref().map_or(0, |row| usize::from(row.is_some()));
+            log_query(label, sql, started_at.elapsed(), rows);
+            result
+        }
+        .instrument(telemetry::db_span(label, &statement_summary(sql)))
+    }
+
+    fn execute_timed<'a>(
+        &'a self,
+        label: &'static str,
+        sql: &'a str,
+        params: &'a [&'a (dyn ToSql + Sync)],
+    ) -> impl Future<Output = Result<u64, tokio_postgres::Error>> + Send + 'a {
+        async move {
+            let started_at = Instant::now();
+            let result = self.execute(sql, params).await;
+            let rows = result
+                .as_ref()
+                .map_or(0, |count| usize::try_from(*count).unwrap_or(usize::MAX));
+            log_query(label, sql, started_at.elapsed(), rows);
+            result
+        }
+        .instrument(telemetry::db_span(label, &statement_summary(sql)))
+    }
+}
+
+impl<C: GenericClient + Sync> TimedQuery for C {}
+
+/// Logs every statement at debug level and anything slower than
+/// `DB_SLOW_QUERY_MS` as a warning.
+fn log_query(label: &'static str, sql: &str, elapsed: Duration, rows: usize) {
+    let threshold = app_config::get().database.slow_query_threshold;
+    let duration_ms = u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX);
+
+    if elapsed >= threshold {
+        tracing::warn!(
+            label = label,
+            statement = %statement_summary(sql),
+            duration_ms = duration_ms,
+            rows = rows,
+            threshold_ms = u64::try_from(threshold.as_millis()).unwrap_or(u64::MAX),
+            "Slow database query"
+        );
+    } else {
+        tracing::debug!(
+            label = label,
+            statement = %statement_summary(sql),
+            duration_ms = duration_ms,
+            rows = rows,
+            "Database query"
+        );
+    }
+}
+
+/// Reduces SQL to its verb and primary table, e.g. `select surplus_listings`,
+/// so log lines stay short and never carry literal values.
+fn statement_summary(sql: &str) -> String {
+    let mut tokens = sql
+        .split_whitespace()
+        .map(|token| token.trim_matches(|c: char| c == '(' || c == ')' || c == ',' || c == ';'));
+    let Some(verb) = tokens.next().map(str::to_ascii_lowercase) else {
+        return String::new();
+    };
+
+    let table_after = match verb.as_str() {
+        "select" | "delete" => Some("from"),
+        "insert" => Some("into"),
+        "update" => None,
+        _ => return verb,
+    };
+    let table = match table_after {
+        Some(keyword) => tokens
+            .skip_while(|token| !token.eq_ignore_ascii_case(keyword))
+            .nth(1),
+        None => tokens.next(),
+    };
+
+    match table.filter(|table| !table.is_empty()) {
+        Some(table) => format!("{verb} {}", table.to_ascii_lowercase()),
+        None => verb,
+    }
+}
+
+/// Exponential backoff scaled by `jitter` (0.5–1.0) so concurrent Lambdas
+/// retrying the same failover do not reconnect in lockstep.
+fn backoff_delay(attempt: u32, jitter: f64) -> Duration {
+    let exponential = RETRY_BASE_DELAY.saturating_mul(2u32.saturating_pow(attempt - 1));
+    exponential.min(RETRY_MAX_DELAY).mul_f64(jitter)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::error::DATABASE_UNAVAILABLE;
+    use std::cell::Cell;
+
+    #[test]
+    fn with_statement_timeout_preserves_existing_options() {
+        assert_eq!(
+            with_statement_timeout(None, 3000),
+            "-c statement_timeout=3000"
+        );
+        assert_eq!(
+            with_statement_timeout(Some("-c search_path=app"), 1500),
+            "-c search_path=app -c statement_timeout=1500"
+        );
+    }
+
+    #[test]
+    fn statement_summary_names_verb_and_table() {
+        assert_eq!(
+            statement_summary(
+                "\n  select id, title\n  from surplus_listings\n  where user_id = $1"
+            ),
+            "select surplus_listings"
+        );
+        assert_eq!(
+            statement_summary("insert into claims (listing_id) values ($1)"),
+            "insert claims"
+        );
+        assert_eq!(
+            statement_summary("UPDATE users SET tier = $1"),
+            "update users"
+        );
+        assert_eq!(
+            statement_summary("delete from api_keys where id = $1"),
+            "delete api_keys"
+        );
+        assert_eq!(
+            statement_summary("with recent as (select 1) select * from recent"),
+            "with"
+        );
+        assert_eq!(statement_summary("select 1"), "select");
+        assert_eq!(statement_summary("   "), "");
+    }
+
+    #[test]
+    fn backoff_delay_grows_and_caps() {
+        assert_eq!(backoff_delay(1, 1.0), Duration::from_millis(50));
+        assert_eq!(backoff_delay(2, 1.0), Duration::from_millis(100));
+        assert_eq!(backoff_delay(3, 0.5), Duration::from_millis(100));
+        assert_eq!(backoff_delay(20, 1.0), RETRY_MAX_DELAY);
+    }
+
+    #[tokio::test]
+    async fn retry_replays_transient_errors_until_success() {
+        let calls = Cell::new(0);
+        let result = retry("test", || {
+            calls.set(calls.get() + 1);
+            let call = calls.get();
+            async move {
+                if call < 3 {
+                    Err(ApiError::unavailable(DATABASE_UNAVAILABLE, "reset"))
+                } else {
+                    Ok(call)
+                }
+            }
+        })
+        .await;
+
+        assert_eq!(result.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn retry_gives_up_after_max_attempts() {
+        let calls = Cell::new(0);
+        let result: Result<(), ApiError> = retry("test", || {
+            calls.set(calls.get() + 1);
+            async { Err(ApiError::unavailable(DATABASE_UNAVAILABLE, "reset")) }
+        })
+        .await;
+
+        assert!(result.unwrap_err().is_retryable());
+        assert_eq!(calls.get(), app_config::get().database.retry_attempts);
+    }
+
+    #[tokio::test]
+    async fn retry_does_not_replay_permanent_errors() {
+        let calls = Cell::new(0);
+        let result: Result<(), ApiError> = retry("test", || {
+            calls.set(calls.get() + 1);
+            async { Err(ApiError::conflict("insufficient_quantity", "nope")) }
+        })
+        .await;
+
+        assert_eq!(result.unwrap_err().error_code(), "insufficient_quantity");
+        assert_eq!(calls.get(), 1);
+    }
 }
@@ -0,0 +1,286 @@
+use crate::config;
+use crate::db;
+use crate::middleware::correlation;
+use crate::router;
+use chrono::{Duration, Utc};
+use lambda_http::aws_lambda_events::apigw::{
+    ApiGatewayProxyRequestContext, ApiGatewayRequestAuthorizer,
+};
+use lambda_http::http::{HeaderValue, Method};
+use lambda_http::request::RequestContext;
+use lambda_http::{Body, Request, RequestExt};
+use serde_json::{json, Value};
+use std::process::Command;
+use testcontainers_modules::postgres::Postgres;
+use testcontainers_modules::testcontainers::runners::AsyncRunner;
+use testcontainers_modules::testcontainers::ImageExt;
+use tokio::sync::OnceCell;
+use tokio_postgres::NoTls;
+use uuid::Uuid;
+
+#[path = "../../migrations/runner.rs"]
+mod migrations;
+
+/// Stock Postgres plus the `vector` extension the embeddings migration needs.
+const POSTGRES_IMAGE: &str = "pgvector/pgvector";
+const POSTGRES_TAG: &str = "pg16";
+
+static DATABASE_URL: OnceCell<String> = OnceCell::const_new();
+
+/// Starts the shared database on first use, applies every migration, and
+/// points [`db::connect`] at it. Returns its URL.
+pub async fn start() -> &'static str {
+    DATABASE_URL
+        .get_or_init(|| async {
+            crate::install_rustls_crypto_provider();
+            // Event publishing is best effort; a closed local endpoint makes
+            // it fail fast instead of probing for AWS credentials.
+            std::env::set_var("AWS_REGION", "us-east-1");
+            std::env::set_var("AWS_ACCESS_KEY_ID", "e2e");
+            std::env::set_var("AWS_SECRET_ACCESS_KEY", "e2e");
+            std::env::set_var("AWS_ENDPOINT_URL", "http://127.0.0.1:9");
+
+            let container = Postgres::default()
+                .with_name(POSTGRES_IMAGE)
+                .with_tag(POSTGRES_TAG)
+                .start()
+                .await
+                .unwrap();
+            let host = container.get_host().await.unwrap();
+            let port = container.get_host_port_ipv4(5432).await.unwrap();
+            // Outlives the test that started it; the testcontainers reaper
+            // removes it when the process exits.
+            std::mem::forget(container);
+
+            let url =
+                format!("postgres://postgres:postgres@{host}:{port}/postgres?sslmode=disable");
+            let (mut client, connection) = tokio_postgres::connect(&url, NoTls).await.unwrap();
+            tokio::spawn(connection);
+            migrations::migrate(&mut client).await.unwrap();
+
+            db::use_database_url(&url).unwrap();
+            url
+        })
+        .await
+}
+
+/// A caller as the authorizer would describe them.
+#[derive(Debug, Clone)]
+pub struct TestUser {
+    pub id: Uuid,
+    pub user_type: Option<&'static str>,
+    pub is_admin: bool,
+}
+
+impl TestUser {
+    /// Signed up but not yet onboarded.
+    pub fn signed_up() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_type: None,
+            is_admin: false,
+        }
+    }
+
+    fn request_context(&self) -> RequestContext {
+        let mut authorizer = ApiGatewayRequestAuthorizer::default();
+        let fields = [
+            ("userId", Some(self.id.to_string())),
+            ("userType", self.user_type.map(str::to_string)),
+            ("email", Some(format!("{}@e2e.test", self.id))),
+            ("tier", Some("neighbor".to_string())),
+            ("principalType", Some("user".to_string())),
+            ("isAdmin", Some(self.is_admin.to_string())),
+            ("suspended", Some("false".to_string())),
+        ];
+        for (name, value) in fields {
+            if let Some(value) = value {
+                authorizer
+                    .fields
+                    .insert(name.to_string(), Value::String(value));
+            }
+        }
+        RequestContext::ApiGatewayV1(ApiGatewayProxyRequestContext {
+            authorizer,
+            ..ApiGatewayProxyRequestContext::default()
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct TestResponse {
+    pub status: u16,
+    /// `Null` for empty or non-JSON bodies.
+    pub body: Value,
+}
+
+impl TestResponse {
+    /// Fails with the response body so a wrong status explains itself.
+    pub fn assert_status(&self, expected: u16) -> &Value {
+        assert_eq!(self.status, expected, "{}", self.body);
+        &self.body
+    }
+
+    pub fn error_code(&self) -> &str {
+        self.body["errorCode"].as_str().unwrap_or_default()
+    }
+}
+
+/// Sends one request through the router. `path` may carry a query string.
+pub async fn call(
+    method: &str,
+    path: &str,
+    caller: Option<&TestUser>,
+    body: Option<Value>,
+) -> TestResponse {
+    let mut request = Request::new(body.map_or(Body::Empty, |body| Body::Text(body.to_string())));
+    *request.method_mut() = Method::from_bytes(method.as_bytes()).unwrap();
+    *request.uri_mut() = path.parse().unwrap();
+    request
+        .headers_mut()
+        .insert("content-type", HeaderValue::from_static("application/json"));
+    if let Some(caller) = caller {
+        request = request.with_request_context(caller.request_context());
+    }
+    let request = correlation::attach_correlation_id(request);
+
+    let response = router::route_request(&request, config::get())
+        .await
+        .unwrap();
+    let body = match response.body() {
+        Body::Empty => Value::Null,
+        Body::Text(text) => serde_json::from_str(text).unwrap_or(Value::Null),
+        Body::Binary(bytes) => serde_json::from_slice(bytes).unwrap_or(Value::Null),
+    };
+    TestResponse {
+        status: response.status().as_u16(),
+        body,
+    }
+}
+
+/// Onboards a new grower through `PUT /me`.
+pub async fn grower(address: &str) -> TestUser {
+    let mut user = TestUser::signed_up();
+    call(
+        "PUT",
+        "/me",
+        Some(&user),
+        Some(json!({
+            "displayName": "E2E Grower",
+            "userType": "grower",
+            "growerProfile": {
+                "address": address,
+                "shareRadiusMiles": 5.0,
+                "units": "imperial",
+                "locale": "en-US"
+            }
+        })),
+    )
+    .await
+    .assert_status(204);
+    user.user_type = Some("grower");
+    user
+}
+
+/// Onboards a new gatherer through `PUT /me`.
+pub async fn gatherer(address: &str) -> TestUser {
+    let mut user = TestUser::signed_up();
+    call(
+        "PUT",
+        "/me",
+        Some(&user),
+        Some(json!({
+            "displayName": "E2E Gatherer",
+            "userType": "gatherer",
+            "gathererProfile": {
+                "address": address,
+                "searchRadiusMiles": 10.0,
+                "units": "imperial",
+                "locale": "en-US"
+            }
+        })),
+    )
+    .await
+    .assert_status(204);
+    user.user_type = Some("gatherer");
+    user
+}
+
+/// Adds a catalog crop unique to the calling test. The catalog has no write
+/// route outside the seed job, so this goes straight to the database.
+pub async fn crop(common_name: &str) -> Uuid {
+    let client = db::connect().await.unwrap();
+    client
+        .query_one(
+            "insert into crops (slug, common_name) values ($1, $2) returning id",
+            &[&format!("e2e-{}", Uuid::new_v4()), &common_name],
+        )
+        .await
+        .unwrap()
+        .get("id")
+}
+
+/// A listing body for `POST /listings` available from now for a week.
+pub fn listing_payload(crop_id: Uuid, quantity: f64) -> Value {
+    let now = Utc::now();
+    json!({
+        "title": "E2E surplus",
+        "cropId": crop_id.to_string(),
+        "quantityTotal": quantity,
+        "unit": "lb",
+        "availableStart": now.to_rfc3339(),
+        "availableEnd": (now + Duration::days(7)).to_rfc3339(),
+        "pickupDisclosurePolicy": "immediate"
+    })
+}
+
+/// Decimal strings such as `quantityRemaining`, as a number.
+pub fn quantity(value: &Value) -> f64 {
+    value.as_str().unwrap().parse().unwrap()
+}
+
+/// Recomputes one signal scope with the same module the aggregation worker
+/// and `signal-recompute` use, and returns the computed values.
+pub fn recompute_signal(
+    database_url: &str,
+    geo_prefix: &str,
+    crop_id: Uuid,
+    window_days: i32,
+) -> Value {
+    let script = format!(
+        r#"
+        import pg from "pg";
+        import {{ computeBucketStart, normalizeRecomputeScope, recomputeAndUpsert }}
+          from "{dir}/functions/lib/signals.mjs";
+
+        const {{ scope }} = normalizeRecomputeScope({{
+          geoPrefix: "{geo_prefix}",
+          cropId: "{crop_id}",
+          windows: [{window_days}],
+        }});
+        const client = new pg.Client({{ connectionString: process.env.DATABASE_URL }});
+        await client.connect();
+        try {{
+          const bucketStart = computeBucketStart(new Date().toISOString());
+          const signal = await recomputeAndUpsert(client, scope, {window_days}, bucketStart);
+          console.log(JSON.stringify(signal));
+        }} finally {{
+          await client.end();
+        }}
+        "#,
+        dir = env!("CARGO_MANIFEST_DIR"),
+    );
+
+    let output = Command::new("node")
+        .args(["--input-type=module", "-e", &script])
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .env("DATABASE_URL", database_url.replace("?sslmode=disable", ""))
+        .output()
+        .unwrap();
+    assert!(
+        output.status.success(),
+        "{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+    serde_json::from_slice(&output.stdout).unwrap()
+}
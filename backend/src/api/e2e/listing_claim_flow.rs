@@ -0,0 +1,214 @@
+use super::harness::{self, call, quantity};
+use serde_json::json;
+use serial_test::serial;
+
+const GROWER_ADDRESS: &str = "12 Elm St, Buffalo, NY 14222";
+const GATHERER_ADDRESS: &str = "48 Lexington Ave, Buffalo, NY 14222";
+
+#[tokio::test]
+#[serial]
+#[ignore = "needs Docker; run with --ignored"]
+async fn listing_is_claimed_confirmed_and_completed() {
+    harness::start().await;
+    let grower = harness::grower(GROWER_ADDRESS).await;
+    let gatherer = harness::gatherer(GATHERER_ADDRESS).await;
+    let crop_id = harness::crop("Tomato").await;
+
+    let created = call(
+        "POST",
+        "/listings",
+        Some(&grower),
+        Some(harness::listing_payload(crop_id, 10.0)),
+    )
+    .await;
+    let listing = created.assert_status(201);
+    let listing_id = listing["id"].as_str().unwrap().to_string();
+    assert_eq!(listing["status"], "active");
+    assert!(listing["geoKey"]
+        .as_str()
+        .is_some_and(|key| !key.is_empty()));
+
+    let gatherer_post = call(
+        "POST",
+        "/listings",
+        Some(&gatherer),
+        Some(harness::listing_payload(crop_id, 1.0)),
+    )
+    .await;
+    assert_eq!(gatherer_post.status, 403, "{}", gatherer_post.body);
+
+    let claimed = call(
+        "POST",
+        "/claims",
+        Some(&gatherer),
+        Some(json!({ "listingId": listing_id, "quantityClaimed": 4 })),
+    )
+    .await;
+    let claim = claimed.assert_status(201);
+    let claim_path = format!("/claims/{}", claim["id"].as_str().unwrap());
+    assert_eq!(claim["status"], "pending");
+    assert_eq!(claim["listingOwnerId"], grower.id.to_string());
+
+    let self_confirm = call(
+        "PUT",
+        &claim_path,
+        Some(&gatherer),
+        Some(json!({ "status": "confirmed" })),
+    )
+    .await;
+    assert_eq!(self_confirm.status, 403);
+    assert_eq!(self_confirm.error_code(), "listing_owner_only");
+
+    let confirmed = call(
+        "PUT",
+        &claim_path,
+        Some(&grower),
+        Some(json!({ "status": "confirmed" })),
+    )
+    .await;
+    assert_eq!(confirmed.assert_status(200)["status"], "confirmed");
+
+    let listing = call(
+        "GET",
+        &format!("/my/listings/{listing_id}"),
+        Some(&grower),
+        None,
+    )
+    .await;
+    let remaining = quantity(&listing.assert_status(200)["quantityRemaining"]);
+    assert!((remaining - 6.0).abs() < f64::EPSILON);
+
+    let pickups = call("GET", "/me/pickups", Some(&gatherer), None).await;
+    let pickups = pickups.assert_status(200);
+    assert_eq!(pickups["items"][0]["claimId"], claim["id"]);
+    assert_eq!(pickups["items"][0]["cropName"], "Tomato");
+
+    let completed = call(
+        "PUT",
+        &claim_path,
+        Some(&gatherer),
+        Some(json!({ "status": "completed" })),
+    )
+    .await;
+    let claim = completed.assert_status(200);
+    assert_eq!(claim["status"], "completed");
+    assert!(claim["completedAt"].is_string());
+
+    let history = call("GET", "/me/pickups?view=history", Some(&gatherer), None).await;
+    assert_eq!(
+        history.assert_status(200)["items"][0]["status"],
+        "completed"
+    );
+
+    let reopened = call(
+        "PUT",
+        &claim_path,
+        Some(&grower),
+        Some(json!({ "status": "cancelled" })),
+    )
+    .await;
+    assert_eq!(reopened.status, 400);
+    assert_eq!(reopened.error_code(), "invalid_transition");
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "needs Docker; run with --ignored"]
+async fn claims_cannot_exceed_remaining_quantity() {
+    harness::start().await;
+    let grower = harness::grower(GROWER_ADDRESS).await;
+    let gatherer = harness::gatherer(GATHERER_ADDRESS).await;
+    let crop_id = harness::crop("Zucchini").await;
+
+    let created = call(
+        "POST",
+        "/listings",
+        Some(&grower),
+        Some(harness::listing_payload(crop_id, 2.0)),
+    )
+    .await;
+    let listing_id = created.assert_status(201)["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let too_many = call(
+        "POST",
+        "/claims",
+        Some(&gatherer),
+        Some(json!({ "listingId": listing_id, "quantityClaimed": 3 })),
+    )
+    .await;
+    assert_eq!(too_many.status, 409, "{}", too_many.body);
+    assert_eq!(too_many.error_code(), "insufficient_quantity");
+
+    let unknown = call(
+        "POST",
+        "/claims",
+        Some(&gatherer),
+        Some(json!({ "listingId": uuid::Uuid::new_v4(), "quantityClaimed": 1 })),
+    )
+    .await;
+    assert_eq!(unknown.status, 404);
+    assert_eq!(unknown.error_code(), "listing_not_found");
+}
+
+#[tokio::test]
+#[serial]
+#[ignore = "needs Docker; run with --ignored"]
+async fn pending_claims_hold_their_quantity_until_resolved() {
+    harness::start().await;
+    let grower = harness::grower(GROWER_ADDRESS).await;
+    let first = harness::gatherer(GATHERER_ADDRESS).await;
+    let second = harness::gatherer(GATHERER_ADDRESS).await;
+    let crop_id = harness::crop("Pepper").await;
+
+    let created = call(
+        "POST",
+        "/listings",
+        Some(&grower),
+        Some(harness::listing_payload(crop_id, 5.0)),
+    )
+    .await;
+    let listing_id = created.assert_status(201)["id"]
+        .as_str()
+        .unwrap()
+        .to_string();
+    let claim_body =
+        |quantity: u32| json!({ "listingId": listing_id, "quantityClaimed": quantity });
+
+    let held = call("POST", "/claims", Some(&first), Some(claim_body(4))).await;
+    let held = held.assert_status(201);
+    assert!(held["holdExpiresAt"].is_string());
+    let held_path = format!("/claims/{}", held["id"].as_str().unwrap());
+
+    let listing = call(
+        "GET",
+        &format!("/my/listings/{listing_id}"),
+        Some(&grower),
+        None,
+    )
+    .await;
+    let listing = listing.assert_status(200);
+    assert!((quantity(&listing["quantityRemaining"]) - 5.0).abs() < f64::EPSILON);
+    assert!((quantity(&listing["reservedQuantity"]) - 4.0).abs() < f64::EPSILON);
+    assert_eq!(listing["claimCounts"]["pending"], 1);
+    assert_eq!(listing["claimCounts"]["confirmed"], 0);
+
+    let blocked = call("POST", "/claims", Some(&second), Some(claim_body(2))).await;
+    assert_eq!(blocked.status, 409, "{}", blocked.body);
+    assert_eq!(blocked.error_code(), "insufficient_quantity");
+
+    let withdrawn = call(
+        "PUT",
+        &held_path,
+        Some(&first),
+        Some(json!({ "status": "cancelled" })),
+    )
+    .await;
+    assert!(withdrawn.assert_status(200)["holdExpiresAt"].is_null());
+
+    call("POST", "/claims", Some(&second), Some(claim_body(2)))
+        .await
+        .assert_status(201);
+}
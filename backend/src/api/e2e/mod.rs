@@ -0,0 +1,20 @@
+//! End-to-end tests against a real Postgres. Requests go through the router
+//! exactly as Lambda delivers them, with the authorizer context filled in
+//! the way the authorizer would, against a database started with
+//! testcontainers and migrated with the embedded migration list.
+//!
+//! They need Docker (and Node with `npm ci` run in `backend/` for the signal
+//! worker), so they are ignored by default:
+//!
+//! ```sh
+//! cargo test --bin api e2e -- --ignored
+//! ```
+//!
+//! Every test shares one database, so each creates its own users, crops and
+//! listings and asserts only on those.
+
+#![allow(clippy::unwrap_used)]
+
+mod harness;
+mod listing_claim_flow;
+mod signal_recompute;
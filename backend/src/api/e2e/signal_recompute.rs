@@ -0,0 +1,74 @@
+use super::harness::{self, call};
+use serde_json::{json, Value};
+use serial_test::serial;
+
+const GROWER_ADDRESS: &str = "301 Bird Ave, Buffalo, NY 14213";
+const GATHERER_ADDRESS: &str = "9 Ferry St, Buffalo, NY 14213";
+const WINDOW_DAYS: i32 = 7;
+
+#[tokio::test]
+#[serial]
+#[ignore = "needs Docker and Node; run with --ignored"]
+async fn recomputed_signal_counts_listings_and_reaches_the_feed() {
+    let database_url = harness::start().await;
+    let grower = harness::grower(GROWER_ADDRESS).await;
+    let gatherer = harness::gatherer(GATHERER_ADDRESS).await;
+    let crop_id = harness::crop("Kale").await;
+
+    let created = call(
+        "POST",
+        "/listings",
+        Some(&grower),
+        Some(harness::listing_payload(crop_id, 12.0)),
+    )
+    .await;
+    let listing = created.assert_status(201);
+    let listing_id = listing["id"].as_str().unwrap().to_string();
+    let geo_key = listing["geoKey"].as_str().unwrap().to_string();
+
+    let claimed = call(
+        "POST",
+        "/claims",
+        Some(&gatherer),
+        Some(json!({ "listingId": listing_id, "quantityClaimed": 5 })),
+    )
+    .await;
+    let claim_path = format!(
+        "/claims/{}",
+        claimed.assert_status(201)["id"].as_str().unwrap()
+    );
+    for (caller, status) in [(&grower, "confirmed"), (&gatherer, "completed")] {
+        call(
+            "PUT",
+            &claim_path,
+            Some(caller),
+            Some(json!({ "status": status })),
+        )
+        .await
+        .assert_status(200);
+    }
+
+    let signal = harness::recompute_signal(database_url, &geo_key[..6], crop_id, WINDOW_DAYS);
+    assert_eq!(signal["listingCount"], 1);
+    assert_eq!(signal["requestCount"], 0);
+    assert!(signal["supplyQuantity"].as_f64().unwrap() > 0.0);
+
+    let feed = call(
+        "GET",
+        &format!("/feed/derived?geoKey={geo_key}&windowDays={WINDOW_DAYS}"),
+        Some(&gatherer),
+        None,
+    )
+    .await;
+    let feed = feed.assert_status(200);
+    assert_eq!(feed["freshness"]["isStale"], false);
+    let crop_id = crop_id.to_string();
+    let scoped = feed["signals"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|signal| signal["cropId"] == Value::String(crop_id.clone()))
+        .unwrap();
+    assert_eq!(scoped["geoBoundaryKey"], &geo_key[..6]);
+    assert_eq!(scoped["listingCount"], 1);
+}
@@ -0,0 +1,681 @@
+use lambda_http::http::header::CONTENT_TYPE;
+use lambda_http::http::{HeaderValue, StatusCode};
+use lambda_http::{Body, Response};
+use serde::Serialize;
+use std::fmt;
+use tokio_postgres::error::SqlState;
+
+pub const ONBOARDING_INCOMPLETE: &str = "onboarding_incomplete";
+pub const DATABASE_UNAVAILABLE: &str = "database_unavailable";
+pub const DATABASE_TIMEOUT: &str = "database_timeout";
+pub const REQUEST_TIMEOUT: &str = "request_timeout";
+
+const ONBOARDING_INCOMPLETE_MESSAGE: &str =
+    "User type is not configured. Set userType via PUT /me before calling this endpoint.";
+
+/// Error returned by API handlers. Each variant maps to one HTTP status and
+/// carries a stable `errorCode` clients can branch on (also sent upper-cased
+/// as `code`); the human-readable message is for logs and display only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ApiError {
+    BadRequest {
+        field: Option<String>,
+        code: &'static str,
+        message: String,
+    },
+    Unauthorized {
+        message: String,
+    },
+    Forbidden {
+        code: &'static str,
+        message: String,
+    },
+    NotFound {
+        code: &'static str,
+        message: String,
+    },
+    Conflict {
+        code: &'static str,
+        message: String,
+    },
+    PayloadTooLarge {
+        message: String,
+    },
+    TooManyRequests {
+        code: &'static str,
+        message: String,
+    },
+    Unavailable {
+        code: &'static str,
+        message: String,
+    },
+    Validation {
+        issues: Vec<ValidationIssue>,
+    },
+    /// Well-formed input the server could not act on with confidence;
+    /// `suggestions` are corrected values the client can offer instead.
+    Unprocessable {
+        field: String,
+        code: &'static str,
+        message: String,
+        suggestions: Vec<String>,
+    },
+    Internal {
+        message: String,
+    },
+}
+
+/// One failed check on a request field. Validators collect these so a single
+/// response can flag every offending field at once.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationIssue {
+    pub field: String,
+    pub code: &'static str,
+    pub message: String,
+}
+
+/// Accumulates validation issues across a payload. Non-validation errors
+/// captured along the way win over collected issues.
+#[derive(Debug, Default)]
+pub struct ValidationErrors {
+    issues: Vec<ValidationIssue>,
+    fatal: Option<ApiError>,
+}
+
+impl ValidationErrors {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add(&mut self, field: &str, code: &'static str, message: impl Into<String>) {
+        self.issues.push(ValidationIssue {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+        });
+    }
+
+    /// Records the error from a field parser and returns the parsed value when
+    /// it succeeded.
+    pub fn capture<T>(&mut self, result: Result<T, ApiError>) -> Option<T> {
+        match result {
+            Ok(value) => Some(value),
+            Err(ApiError::BadRequest {
+                field,
+                code,
+                message,
+            }) => {
+                self.issues.push(ValidationIssue {
+                    field: field.unwrap_or_default(),
+                    code,
+                    message,
+                });
+                None
+            }
+            Err(ApiError::Validation { issues }) => {
+                self.issues.extend(issues);
+                None
+            }
+            Err(other) => {
+                self.fatal.get_or_insert(other);
+                None
+            }
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), ApiError> {
+        if let Some(fatal) = self.fatal {
+            return Err(fatal);
+        }
+        if self.issues.is_empty() {
+            return Ok(());
+        }
+        Err(ApiError::Validation {
+            issues: self.issues,
+        })
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ErrorBody<'a> {
+    error: &'a str,
+    error_code: &'a str,
+    code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    field: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    details: Option<&'a [ValidationIssue]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    suggestions: Option<&'a [String]>,
+}
+
+impl ApiError {
+    pub fn bad_request(code: &'static str, message: impl Into<String>) -> Self {
+        Self::BadRequest {
+            field: None,
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn invalid_field(field: &str, code: &'static str, message: impl Into<String>) -> Self {
+        Self::BadRequest {
+            field: Some(field.to_string()),
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unauthorized(message: impl Into<String>) -> Self {
+        Self::Unauthorized {
+            message: message.into(),
+        }
+    }
+
+    pub fn forbidden(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Forbidden {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn onboarding_incomplete() -> Self {
+        Self::forbidden(
+            ONBOARDING_INCOMPLETE,
+            "Forbidden: User type not set. Please complete onboarding.",
+        )
+    }
+
+    pub fn not_found(code: &'static str, message: impl Into<String>) -> Self {
+        Self::NotFound {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn conflict(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Conflict {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn payload_too_large(message: impl Into<String>) -> Self {
+        Self::PayloadTooLarge {
+            message: message.into(),
+        }
+    }
+
+    pub fn too_many_requests(code: &'static str, message: impl Into<String>) -> Self {
+        Self::TooManyRequests {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unavailable(code: &'static str, message: impl Into<String>) -> Self {
+        Self::Unavailable {
+            code,
+            message: message.into(),
+        }
+    }
+
+    pub fn unprocessable(
+        field: &str,
+        code: &'static str,
+        message: impl Into<String>,
+        suggestions: Vec<String>,
+    ) -> Self {
+        Self::Unprocessable {
+            field: field.to_string(),
+            code,
+            message: message.into(),
+            suggestions,
+        }
+    }
+
+    pub fn internal(message: impl Into<String>) -> Self {
+        Self::Internal {
+            message: message.into(),
+        }
+    }
+
+    /// Set only for transient database failures; `db::retry` replays the
+    /// operation when it sees one.
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self,
+            Self::Unavailable {
+                code: DATABASE_UNAVAILABLE,
+                ..
+            }
+        )
+    }
+
+    #[must_use]
+    pub const fn status(&self) -> StatusCode {
+        match self {
+            Self::BadRequest { .. } | Self::Validation { .. } => StatusCode::BAD_REQUEST,
+            Self::Unauthorized { .. } => StatusCode::UNAUTHORIZED,
+            Self::Forbidden { .. } => StatusCode::FORBIDDEN,
+            Self::NotFound { .. } => StatusCode::NOT_FOUND,
+            Self::Conflict { .. } => StatusCode::CONFLICT,
+            Self::PayloadTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
+            Self::TooManyRequests { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Unprocessable { .. } => StatusCode::UNPROCESSABLE_ENTITY,
+            Self::Unavailable { .. } => StatusCode::SERVICE_UNAVAILABLE,
+            Self::Internal { .. } => StatusCode::INTERNAL_SERVER_ERROR,
+        }
+    }
+
+    /// A single validation issue reports its own code so clients that branch
+    /// on one-field errors keep working; several issues report
+    /// `validation_failed` and list the rest under `details`.
+    #[must_use]
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::Validation { issues } => match issues.as_slice() {
+                [only] => only.code,
+                _ => "validation_failed",
+            },
+            Self::BadRequest { code, .. }
+            | Self::Forbidden { code, .. }
+            | Self::NotFound { code, .. }
+            | Self::Conflict { code, .. }
+            | Self::TooManyRequests { code, .. }
+            | Self::Unprocessable { code, .. }
+            | Self::Unavailable { code, .. } => code,
+            Self::Unauthorized { .. } => "unauthorized",
+            Self::PayloadTooLarge { .. } => "payload_too_large",
+            Self::Internal { .. } => "internal_error",
+        }
+    }
+
+    /// `error_code` in `SCREAMING_SNAKE_CASE`, e.g. `LISTING_NOT_FOUND`. Derived
+    /// rather than stored so the two can never disagree.
+    #[must_use]
+    pub fn code(&self) -> String {
+        self.error_code().to_ascii_uppercase()
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        match self {
+            Self::Validation { issues } => issues
+                .first()
+                .map_or("Request validation failed", |issue| issue.message.as_str()),
+            Self::BadRequest { message, .. }
+            | Self::Unauthorized { message }
+            | Self::PayloadTooLarge { message }
+            | Self::Forbidden { message, .. }
+            | Self::NotFound { message, .. }
+            | Self::Conflict { message, .. }
+            | Self::TooManyRequests { message, .. }
+            | Self::Unprocessable { message, .. }
+            | Self::Unavailable { message, .. }
+            | Self::Internal { message } => message,
+        }
+    }
+
+    /// Internal and unavailable details stay in logs; clients get a generic
+    /// message so connection strings and env var names never leak.
+    fn public_message(&self) -> &str {
+        match self {
+            Self::Internal { .. } => "Internal server error",
+            Self::Unavailable {
+                code: DATABASE_UNAVAILABLE,
+                ..
+            } => "Database temporarily unavailable, please retry",
+            Self::Unavailable {
+                code: DATABASE_TIMEOUT | REQUEST_TIMEOUT,
+                ..
+            } => "Request timed out, please retry",
+            Self::Unavailable {
+                code: "not_configured",
+                ..
+            } => "Service not configured in this environment",
+            _ => self.message(),
+        }
+    }
+
+    #[must_use]
+    pub fn into_response(self) -> Response<Body> {
+        let field = match &self {
+            Self::BadRequest { field, .. } => field.as_deref(),
+            Self::Validation { issues } => issues.first().map(|issue| issue.field.as_str()),
+            Self::Unprocessable { field, .. } => Some(field.as_str()),
+            _ => None,
+        };
+        let details = match &self {
+            Self::Validation { issues } => Some(issues.as_slice()),
+            _ => None,
+        };
+        let suggestions = match &self {
+            Self::Unprocessable { suggestions, .. } => Some(suggestions.as_slice()),
+            _ => None,
+        };
+
+        // Onboarding keeps its original `{error: code, message}` shape because
+        // the frontend branches on `error === "onboarding_incomplete"`.
+        let body = if self.error_code() == ONBOARDING_INCOMPLETE {
+            ErrorBody {
+                error: ONBOARDING_INCOMPLETE,
+                error_code: ONBOARDING_INCOMPLETE,
+                code: self.code(),
+                field,
+                message: Some(ONBOARDING_INCOMPLETE_MESSAGE),
+                details: None,
+                suggestions: None,
+            }
+        } else {
+            ErrorBody {
+                error: self.public_message(),
+                error_code: self.error_code(),
+                code: self.code(),
+                field,
+                message: None,
+                details,
+                suggestions,
+            }
+        };
+
+        let payload = serde_json::to_string(&body)
+            .unwrap_or_else(|_| r#"{"error":"Internal server error"}"#.to_string());
+
+        let mut response = Response::new(Body::from(payload));
+        *response.status_mut() = self.status();
+        response
+            .headers_mut()
+            .insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        response
+    }
+
+    /// Classifies an untyped error raised outside the handlers (db, location,
+    /// AI clients) so it still gets a sensible status and code.
+    fn from_message(message: String) -> Self {
+        if message.contains("Invalid JSON body") || message.contains("Request body is required") {
+            return Self::bad_request("invalid_body", message);
+        }
+
+        if message.contains("address is required") {
+            return Self::invalid_field("address", "required", message);
+        }
+
+        if message.contains("Address could not be geocoded") {
+            return Self::invalid_field("address", "address_not_geocodable", message);
+        }
+
+        if message.contains("Geocoding service unavailable") {
+            return Self::unavailable("geocoding_unavailable", message);
+        }
+
+        if message.contains("is not configured") {
+            return Self::unavailable("not_configured", message);
+        }
+
+        if message.contains("Missing userId in authorizer context") {
+            return Self::unauthorized(message);
+        }
+
+        if message.contains("user type not set")
+            || message.contains("onboarding may be incomplete")
+            || message.contains("Please complete onboarding")
+        {
+            return Self::onboarding_incomplete();
+        }
+
+        if message.contains("Forbidden:") {
+            return Self::forbidden("forbidden", message);
+        }
+
+        Self::internal(message)
+    }
+}
+
+impl fmt::Display for ApiError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation { issues } if issues.len() > 1 => {
+                let messages: Vec<&str> = issues.iter().map(|i| i.message.as_str()).collect();
+                f.write_str(&messages.join("; "))
+            }
+            _ => f.write_str(self.message()),
+        }
+    }
+}
+
+impl std::error::Error for ApiError {}
+
+impl From<lambda_http::Error> for ApiError {
+    fn from(error: lambda_http::Error) -> Self {
+        match error.downcast::<Self>() {
+            Ok(api_error) => *api_error,
+            Err(other) => Self::from_message(other.to_string()),
+        }
+    }
+}
+
+impl From<tokio_postgres::Error> for ApiError {
+    fn from(error: tokio_postgres::Error) -> Self {
+        if error.code() == Some(&SqlState::QUERY_CANCELED) {
+            return Self::unavailable(
+                DATABASE_TIMEOUT,
+                format!("Database statement timed out: {error}"),
+            );
+        }
+
+        if is_transient_db_error(&error) {
+            return Self::unavailable(
+                DATABASE_UNAVAILABLE,
+                format!("Transient database error: {error}"),
+            );
+        }
+
+        if let Some(db_error) = error.as_db_error() {
+            return Self::internal(format!(
+                "Database query error: {} (detail: {})",
+                db_error.message(),
+                db_error.detail().unwrap_or("none")
+            ));
+        }
+
+        Self::internal(format!("Database query error: {error}"))
+    }
+}
+
+/// Errors a retry can plausibly fix: serialization conflicts, deadlocks, a
+/// primary that is mid-failover, and connections dropped underneath us.
+fn is_transient_db_error(error: &tokio_postgres::Error) -> bool {
+    const TRANSIENT_STATES: &[SqlState] = &[
+        SqlState::T_R_SERIALIZATION_FAILURE,
+        SqlState::T_R_DEADLOCK_DETECTED,
+        SqlState::READ_ONLY_SQL_TRANSACTION,
+        SqlState::ADMIN_SHUTDOWN,
+        SqlState::CRASH_SHUTDOWN,
+        SqlState::CANNOT_CONNECT_NOW,
+    ];
+
+    if let Some(code) = error.code() {
+        return TRANSIENT_STATES.contains(code);
+    }
+
+    error.is_closed()
+        || std::error::Error::source(error)
+            .is_some_and(<dyn std::error::Error>::is::<std::io::Error>)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn body_json(response: &Response<Body>) -> serde_json::Value {
+        let text = match response.body() {
+            Body::Text(text) => text.as_str(),
+            Body::Binary(bytes) => std::str::from_utf8(bytes).unwrap(),
+            Body::Empty => "",
+        };
+        serde_json::from_str(text).unwrap()
+    }
+
+    #[test]
+    fn bad_request_includes_field_and_code() {
+        let response =
+            ApiError::invalid_field("quantity", "must_be_positive", "quantity must be > 0")
+                .into_response();
+        assert_eq!(response.status().as_u16(), 400);
+
+        let json = body_json(&response);
+        assert_eq!(json["error"], "quantity must be > 0");
+        assert_eq!(json["errorCode"], "must_be_positive");
+        assert_eq!(json["code"], "MUST_BE_POSITIVE");
+        assert_eq!(json["field"], "quantity");
+    }
+
+    #[test]
+    fn unprocessable_lists_suggestions() {
+        let response = ApiError::unprocessable(
+            "pickupAddress",
+            "address_low_confidence",
+            "pickupAddress could not be matched precisely",
+            vec!["12 Elm St, Buffalo, NY 14222".to_string()],
+        )
+        .into_response();
+        assert_eq!(response.status().as_u16(), 422);
+
+        let json = body_json(&response);
+        assert_eq!(json["errorCode"], "address_low_confidence");
+        assert_eq!(json["field"], "pickupAddress");
+        assert_eq!(json["suggestions"][0], "12 Elm St, Buffalo, NY 14222");
+        assert!(json.get("details").is_none());
+    }
+
+    #[test]
+    fn code_is_upper_cased_error_code() {
+        let cases = [
+            (
+                ApiError::not_found("listing_not_found", "Listing not found"),
+                "LISTING_NOT_FOUND",
+            ),
+            (
+                ApiError::conflict("invalid_transition", "Cannot move claim"),
+                "INVALID_TRANSITION",
+            ),
+            (ApiError::unauthorized("Missing token"), "UNAUTHORIZED"),
+            (ApiError::internal("boom"), "INTERNAL_ERROR"),
+            (ApiError::onboarding_incomplete(), "ONBOARDING_INCOMPLETE"),
+        ];
+        for (error, code) in cases {
+            assert_eq!(error.code(), code);
+            assert_eq!(body_json(&error.into_response())["code"], code);
+        }
+    }
+
+    #[test]
+    fn internal_error_hides_details() {
+        let response = ApiError::internal("Database query error: password=secret").into_response();
+        assert_eq!(response.status().as_u16(), 500);
+
+        let json = body_json(&response);
+        assert_eq!(json["error"], "Internal server error");
+        assert_eq!(json["errorCode"], "internal_error");
+    }
+
+    #[test]
+    fn boxed_api_error_round_trips_through_lambda_error() {
+        let boxed: lambda_http::Error =
+            ApiError::not_found("claim_not_found", "Claim not found").into();
+        let recovered = ApiError::from(boxed);
+        assert_eq!(recovered.status().as_u16(), 404);
+        assert_eq!(recovered.error_code(), "claim_not_found");
+    }
+
+    #[test]
+    fn untyped_errors_default_to_internal() {
+        let error = ApiError::from(lambda_http::Error::from("something broke"));
+        assert_eq!(error.status().as_u16(), 500);
+    }
+
+    #[test]
+    fn validation_errors_report_every_field() {
+        let mut errors = ValidationErrors::new();
+        errors.add("title", "required", "title is required");
+        errors.capture::<()>(Err(ApiError::invalid_field(
+            "unit",
+            "required",
+            "unit is required",
+        )));
+
+        let response = errors.into_result().unwrap_err().into_response();
+        assert_eq!(response.status().as_u16(), 400);
+
+        let json = body_json(&response);
+        assert_eq!(json["errorCode"], "validation_failed");
+        assert_eq!(json["field"], "title");
+        assert_eq!(json["details"][0]["field"], "title");
+        assert_eq!(json["details"][1]["field"], "unit");
+        assert_eq!(json["details"][1]["code"], "required");
+    }
+
+    #[test]
+    fn single_validation_issue_keeps_its_code() {
+        let mut errors = ValidationErrors::new();
+        errors.add("quantity", "must_be_positive", "quantity must be > 0");
+
+        let error = errors.into_result().unwrap_err();
+        assert_eq!(error.error_code(), "must_be_positive");
+        assert_eq!(error.to_string(), "quantity must be > 0");
+    }
+
+    #[test]
+    fn captured_non_validation_error_wins() {
+        let mut errors = ValidationErrors::new();
+        errors.add("title", "required", "title is required");
+        errors.capture::<()>(Err(ApiError::internal("db down")));
+
+        assert_eq!(errors.into_result().unwrap_err().status().as_u16(), 500);
+    }
+
+    #[test]
+    fn onboarding_keeps_legacy_shape_with_error_code() {
+        let json = body_json(&ApiError::onboarding_incomplete().into_response());
+        assert_eq!(json["error"], ONBOARDING_INCOMPLETE);
+        assert_eq!(json["errorCode"], ONBOARDING_INCOMPLETE);
+        assert_eq!(json["message"], ONBOARDING_INCOMPLETE_MESSAGE);
+    }
+
+    #[test]
+    fn database_unavailable_is_retryable_and_hides_details() {
+        let error = ApiError::unavailable(
+            DATABASE_UNAVAILABLE,
+            "Transient database error: connection reset by 10.0.0.12",
+        );
+        assert!(error.is_retryable());
+        assert!(!ApiError::unavailable("not_configured", "x").is_retryable());
+
+        let response = error.into_response();
+        assert_eq!(response.status().as_u16(), 503);
+        let json = body_json(&response);
+        assert_eq!(json["errorCode"], DATABASE_UNAVAILABLE);
+        assert!(!json["error"].as_str().unwrap().contains("10.0.0.12"));
+    }
+
+    #[test]
+    fn timeouts_are_503_with_specific_codes() {
+        for code in [DATABASE_TIMEOUT, REQUEST_TIMEOUT] {
+            let error = ApiError::unavailable(code, "canceling statement due to statement timeout");
+            assert!(!error.is_retryable());
+
+            let response = error.into_response();
+            assert_eq!(response.status().as_u16(), 503);
+            let json = body_json(&response);
+            assert_eq!(json["errorCode"], code);
+            assert_eq!(json["error"], "Request timed out, please retry");
+        }
+    }
+}
@@ -0,0 +1,495 @@
+use crate::config;
+use crate::metrics;
+use crate::telemetry;
+use aws_config::BehaviorVersion;
+use aws_sdk_eventbridge::types::PutEventsRequestEntry;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tracing::Instrument;
+
+/// Version stamped on every event detail emitted by the API. Bump when a
+/// field is removed or changes meaning; additive fields keep the version.
+pub const EVENT_SCHEMA_VERSION: u32 = 1;
+
+pub const EVENT_SOURCE: &str = "community-garden.api";
+
+pub const LISTING_CREATED: &str = "listing.created";
+pub const LISTING_UPDATED: &str = "listing.updated";
+pub const LISTING_STATUS_CHANGED: &str = "listing.status_changed";
+pub const REQUEST_CREATED: &str = "request.created";
+pub const REQUEST_UPDATED: &str = "request.updated";
+pub const CLAIM_CREATED: &str = "claim.created";
+pub const CLAIM_UPDATED: &str = "claim.updated";
+pub const USER_PROFILE_UPDATED: &str = "user.profile.updated";
+pub const MESSAGE_CREATED: &str = "message.created";
+pub const LISTING_QUESTION_CREATED: &str = "listing_question.created";
+pub const LISTING_QUESTION_ANSWERED: &str = "listing_question.answered";
+pub const COMMUNITY_EVENT_CREATED: &str = "community_event.created";
+pub const COMMUNITY_EVENT_UPDATED: &str = "community_event.updated";
+pub const DELIVERY_UPDATED: &str = "delivery.updated";
+pub const MODERATION_ACTION_TAKEN: &str = "moderation.action_taken";
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingEventDetail {
+    pub schema_version: u32,
+    pub listing_id: String,
+    pub user_id: String,
+    /// Null for listing kinds without a crop, such as tool loans.
+    pub crop_id: Option<String>,
+    pub status: String,
+    /// Set on `listing.status_changed`: the status the listing moved from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_status: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_key: Option<String>,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestEventDetail {
+    pub schema_version: u32,
+    pub request_id: String,
+    pub user_id: String,
+    pub crop_id: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub geo_key: Option<String>,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimEventDetail {
+    pub schema_version: u32,
+    pub claim_id: String,
+    pub listing_id: String,
+    pub request_id: Option<String>,
+    pub claimer_id: String,
+    pub listing_owner_id: String,
+    pub status: String,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+/// Carries routing ids only; message text stays in Postgres so notification
+/// consumers never see it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageEventDetail {
+    pub schema_version: u32,
+    pub message_id: String,
+    pub conversation_id: String,
+    pub sender_id: String,
+    pub recipient_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+/// A question asked or answered on a listing. Like messages, the text stays
+/// in Postgres; consumers load it by id.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingQuestionEventDetail {
+    pub schema_version: u32,
+    pub question_id: String,
+    pub listing_id: String,
+    pub asker_id: String,
+    pub listing_owner_id: String,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+/// A harvest day, gleaning party, or similar gathering. Carries the geo key
+/// and time window so feed consumers can place it without a lookup.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityEventDetail {
+    pub schema_version: u32,
+    pub event_id: String,
+    pub organizer_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    pub event_type: String,
+    pub status: String,
+    pub geo_key: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+/// A volunteer delivery changed state. Addresses stay in Postgres; consumers
+/// only learn who to notify.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryEventDetail {
+    pub schema_version: u32,
+    pub claim_id: String,
+    pub driver_id: String,
+    pub claimer_id: String,
+    pub status: String,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+/// An admin acted on a user's account or listing. Addressed to the affected
+/// user so notification consumers can tell them what happened and why.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationActionEventDetail {
+    pub schema_version: u32,
+    pub user_id: String,
+    pub action: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub listing_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileUpdatedEventDetail {
+    pub schema_version: u32,
+    pub user_id: String,
+    pub correlation_id: String,
+    pub occurred_at: String,
+}
+
+impl ListingEventDetail {
+    #[must_use]
+    pub fn new(
+        listing_id: String,
+        user_id: String,
+        crop_id: Option<String>,
+        status: String,
+        geo_key: Option<String>,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            listing_id,
+            user_id,
+            crop_id,
+            status,
+            previous_status: None,
+            geo_key,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+
+    #[must_use]
+    pub fn with_previous_status(mut self, previous_status: String) -> Self {
+        self.previous_status = Some(previous_status);
+        self
+    }
+}
+
+impl RequestEventDetail {
+    #[must_use]
+    pub fn new(
+        request_id: String,
+        user_id: String,
+        crop_id: String,
+        status: String,
+        geo_key: Option<String>,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            request_id,
+            user_id,
+            crop_id,
+            status,
+            geo_key,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl MessageEventDetail {
+    #[must_use]
+    pub fn new(
+        message_id: String,
+        conversation_id: String,
+        sender_id: String,
+        recipient_id: String,
+        listing_id: Option<String>,
+        request_id: Option<String>,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            message_id,
+            conversation_id,
+            sender_id,
+            recipient_id,
+            listing_id,
+            request_id,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl ListingQuestionEventDetail {
+    #[must_use]
+    pub fn new(
+        question_id: String,
+        listing_id: String,
+        asker_id: String,
+        listing_owner_id: String,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            question_id,
+            listing_id,
+            asker_id,
+            listing_owner_id,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl CommunityEventDetail {
+    #[must_use]
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        event_id: String,
+        organizer_id: String,
+        group_id: Option<String>,
+        event_type: String,
+        status: String,
+        geo_key: String,
+        starts_at: String,
+        ends_at: String,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            event_id,
+            organizer_id,
+            group_id,
+            event_type,
+            status,
+            geo_key,
+            starts_at,
+            ends_at,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl DeliveryEventDetail {
+    #[must_use]
+    pub fn new(
+        claim_id: String,
+        driver_id: String,
+        claimer_id: String,
+        status: String,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            claim_id,
+            driver_id,
+            claimer_id,
+            status,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl ModerationActionEventDetail {
+    #[must_use]
+    pub fn new(
+        user_id: String,
+        action: &str,
+        listing_id: Option<String>,
+        reason: Option<String>,
+        correlation_id: &str,
+    ) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            user_id,
+            action: action.to_string(),
+            listing_id,
+            reason,
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+impl ProfileUpdatedEventDetail {
+    #[must_use]
+    pub fn new(user_id: &str, correlation_id: &str) -> Self {
+        Self {
+            schema_version: EVENT_SCHEMA_VERSION,
+            user_id: user_id.to_string(),
+            correlation_id: correlation_id.to_string(),
+            occurred_at: Utc::now().to_rfc3339(),
+        }
+    }
+}
+
+pub async fn publish<T: Serialize + Sync>(
+    detail_type: &str,
+    detail: &T,
+) -> Result<(), lambda_http::Error> {
+    let result = put_event(detail_type, detail).await;
+    if result.is_err() {
+        metrics::record_event_emit_failure(detail_type);
+    }
+    result
+}
+
+/// Confirms the configured bus exists and is reachable with the function's
+/// credentials. `PutEvents` has no dry-run mode, so this stands in for one
+/// without emitting anything consumers would see.
+pub async fn check_event_bus(event_bus_name: &str) -> Result<(), lambda_http::Error> {
+    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
+    let client = aws_sdk_eventbridge::Client::new(&config);
+
+    client
+        .describe_event_bus()
+        .name(event_bus_name)
+        .send()
+        .await
+        .map_err(|e| lambda_http::Error::from(format!("Event bus check failed: {e}")))?;
+    Ok(())
+}
+
+async fn put_event<T: Serialize + Sync>(
+    detail_type: &str,
+    detail: &T,
+) -> Result<(), lambda_http::Error> {
+    let event_bus_name = &config::get().event_bus_name;
+    let detail = serde_json::to_string(detail)
+        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize {detail_type}: {e}")))?;
+
+    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
+    let client = aws_sdk_eventbridge::Client::new(&config);
+
+    let entry = PutEventsRequestEntry::builder()
+        .event_bus_name(event_bus_name)
+        .source(EVENT_SOURCE)
+        .detail_type(detail_type)
+        .detail(detail)
+        .set_trace_header(telemetry::xray_trace_header())
+        .build();
+
+    let response = client
+        .put_events()
+        .entries(entry)
+        .send()
+        .instrument(telemetry::client_span("eventbridge", "PutEvents"))
+        .await
+        .map_err(|e| {
+            lambda_http::Error::from(format!("Failed to emit {detail_type} event: {e}"))
+        })?;
+
+    if response.failed_entry_count() > 0 {
+        return Err(lambda_http::Error::from(format!(
+            "Failed to emit {detail_type} event: one or more entries were rejected"
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn listing_detail_serializes_versioned_camel_case_payload() {
+        let detail = ListingEventDetail::new(
+            "listing-1".to_string(),
+            "user-1".to_string(),
+            Some("crop-1".to_string()),
+            "active".to_string(),
+            Some("9q8yyk8".to_string()),
+            "corr-1",
+        );
+
+        let value = serde_json::to_value(&detail).unwrap();
+        assert_eq!(value["schemaVersion"], EVENT_SCHEMA_VERSION);
+        assert_eq!(value["listingId"], "listing-1");
+        assert_eq!(value["cropId"], "crop-1");
+        assert_eq!(value["geoKey"], "9q8yyk8");
+        assert_eq!(value["correlationId"], "corr-1");
+        assert!(value.get("previousStatus").is_none());
+
+        let changed = detail.with_previous_status("pending".to_string());
+        let value = serde_json::to_value(&changed).unwrap();
+        assert_eq!(value["previousStatus"], "pending");
+    }
+
+    #[test]
+    fn request_detail_omits_missing_geo_key() {
+        let detail = RequestEventDetail::new(
+            "request-1".to_string(),
+            "user-1".to_string(),
+            "crop-1".to_string(),
+            "open".to_string(),
+            None,
+            "corr-1",
+        );
+
+        let value = serde_json::to_value(&detail).unwrap();
+        assert!(value.get("geoKey").is_none());
+    }
+
+    #[test]
+    fn listing_detail_round_trips_through_consumer_shape() {
+        let payload = serde_json::json!({
+            "schemaVersion": 1,
+            "listingId": "listing-1",
+            "userId": "user-1",
+            "cropId": "crop-1",
+            "status": "active",
+            "correlationId": "corr-1",
+            "occurredAt": "2026-01-01T00:00:00+00:00"
+        });
+
+        let detail: ListingEventDetail = serde_json::from_value(payload).unwrap();
+        assert_eq!(detail.schema_version, 1);
+        assert!(detail.geo_key.is_none());
+    }
+
+    #[test]
+    fn message_detail_omits_absent_subject_and_body() {
+        let detail = MessageEventDetail::new(
+            "message-1".to_string(),
+            "conversation-1".to_string(),
+            "user-1".to_string(),
+            "user-2".to_string(),
+            Some("listing-1".to_string()),
+            None,
+            "corr-1",
+        );
+
+        let value = serde_json::to_value(&detail).unwrap();
+        assert_eq!(value["recipientId"], "user-2");
+        assert_eq!(value["listingId"], "listing-1");
+        assert!(value.get("requestId").is_none());
+        assert!(value.get("body").is_none());
+    }
+}
@@ -1,8 +1,11 @@
+use crate::db::TimedQuery;
+use crate::error::ApiError;
 use serde::{Deserialize, Serialize};
 use tokio_postgres::Client;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
-#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Serialize, PartialEq, Eq, PartialOrd, Ord, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum GardenerTier {
     Novice,
@@ -11,7 +14,7 @@ pub enum GardenerTier {
     Master,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[allow(clippy::struct_field_names)]
 pub struct GardenerTierScoreBreakdown {
@@ -23,7 +26,7 @@ pub struct GardenerTierScoreBreakdown {
     pub total_points: i32,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GardenerTierDecision {
     pub tier: GardenerTier,
@@ -32,7 +35,7 @@ pub struct GardenerTierDecision {
     pub breakdown: GardenerTierScoreBreakdown,
 }
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GardenerTierProfile {
     pub current_tier: GardenerTier,
@@ -46,12 +49,12 @@ pub async fn load_tier_read_only(
     user_id: Uuid,
 ) -> Result<GardenerTierProfile, lambda_http::Error> {
     let row = client
-        .query_opt(
+        .query_opt_timed("gardener_tier::load_tier_read_only", 
             "select tier::text as tier, promoted_at, explanation, score_breakdown from gardener_tier_promotions where user_id = $1 order by promoted_at desc limit 1",
             &[&user_id],
         )
         .await
-        .map_err(|e| lambda_http::Error::from(format!("Database query error: {e}")))?;
+        .map_err(ApiError::from)?;
 
     #[allow(clippy::option_if_let_else)]
     match row {
@@ -0,0 +1,216 @@
+use super::{
+    Coordinates, GeocodeCandidate, GeocodeFuture, GeocodingProvider, PlaceName,
+    ReverseGeocodeFuture, MAX_CANDIDATES,
+};
+use crate::aws_signing;
+use crate::config::GeocoderConfig;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// Amazon Location Service `SearchPlaceIndexForText` and
+/// `SearchPlaceIndexForPosition` against the configured place index. The index must be created with `IntendedUse: Storage`,
+/// since results are kept in `geocode_cache`.
+#[derive(Debug, Clone)]
+pub struct AwsLocationProvider {
+    place_index: String,
+    timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SearchResponse {
+    #[serde(default)]
+    results: Vec<SearchResult>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct SearchResult {
+    place: Place,
+    /// 0 to 1; only text searches return it.
+    relevance: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Place {
+    geometry: Geometry,
+    label: Option<String>,
+    neighborhood: Option<String>,
+    municipality: Option<String>,
+    region: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+struct Geometry {
+    /// `[longitude, latitude]`.
+    point: Vec<f64>,
+}
+
+impl AwsLocationProvider {
+    pub fn from_config(config: &GeocoderConfig) -> Self {
+        Self {
+            place_index: config.place_index.clone().unwrap_or_default(),
+            timeout: config.timeout,
+        }
+    }
+}
+
+impl GeocodingProvider for AwsLocationProvider {
+    fn name(&self) -> &'static str {
+        "aws_location"
+    }
+
+    fn search<'a>(&'a self, address: &'a str) -> GeocodeFuture<'a> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(&serde_json::json!({
+                "Text": address,
+                "MaxResults": MAX_CANDIDATES,
+            }))?;
+            let request = aws_signing::signed_json_post(
+                "geo",
+                |region| search_url(region, &self.place_index, "text"),
+                body,
+                self.timeout,
+            )
+            .await?;
+
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(lambda_http::Error::from(format!(
+                    "Amazon Location returned status {}",
+                    response.status().as_u16()
+                )));
+            }
+
+            let parsed = response.json::<SearchResponse>().await?;
+            Ok(parse_candidates(parsed))
+        })
+    }
+
+    fn reverse(&self, point: Coordinates) -> ReverseGeocodeFuture<'_> {
+        Box::pin(async move {
+            let body = serde_json::to_vec(&serde_json::json!({
+                "Position": [point.lng, point.lat],
+                "MaxResults": 1,
+            }))?;
+            let request = aws_signing::signed_json_post(
+                "geo",
+                |region| search_url(region, &self.place_index, "position"),
+                body,
+                self.timeout,
+            )
+            .await?;
+
+            let response = request.send().await?;
+            if !response.status().is_success() {
+                return Err(lambda_http::Error::from(format!(
+                    "Amazon Location returned status {}",
+                    response.status().as_u16()
+                )));
+            }
+
+            let parsed = response.json::<SearchResponse>().await?;
+            Ok(parse_top_place_name(parsed))
+        })
+    }
+}
+
+fn search_url(region: &str, place_index: &str, kind: &str) -> String {
+    format!(
+        "https://places.geo.{region}.amazonaws.com/places/v0/indexes/{place_index}/search/{kind}"
+    )
+}
+
+fn parse_candidates(response: SearchResponse) -> Vec<GeocodeCandidate> {
+    response
+        .results
+        .into_iter()
+        .filter_map(|result| {
+            let point = match result.place.geometry.point.as_slice() {
+                [lng, lat] => Coordinates::checked(*lat, *lng)?,
+                _ => return None,
+            };
+            Some(GeocodeCandidate {
+                point,
+                label: result.place.label,
+                confidence: result.relevance.map_or(1.0, |score| score.clamp(0.0, 1.0)),
+            })
+        })
+        .collect()
+}
+
+fn parse_top_place_name(response: SearchResponse) -> Option<PlaceName> {
+    let place = response.results.into_iter().next()?.place;
+    let name = PlaceName {
+        neighborhood: place.neighborhood,
+        locality: place.municipality,
+        region: place.region,
+    };
+    (name != PlaceName::default()).then_some(name)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn search_url_targets_regional_index() {
+        assert_eq!(
+            search_url("us-east-1", "garden-places", "text"),
+            "https://places.geo.us-east-1.amazonaws.com/places/v0/indexes/garden-places/search/text"
+        );
+        assert_eq!(
+            search_url("us-east-1", "garden-places", "position"),
+            "https://places.geo.us-east-1.amazonaws.com/places/v0/indexes/garden-places/search/position"
+        );
+    }
+
+    #[test]
+    fn parses_point_as_longitude_then_latitude() {
+        let response: SearchResponse = serde_json::from_str(
+            r#"{"Summary":{},"Results":[{"Place":{"Geometry":{"Point":[-122.67648,45.52306]},"Label":"1 Main St, Portland, OR 97201, USA"},"Relevance":0.92}]}"#,
+        )
+        .unwrap();
+        let candidates = parse_candidates(response);
+        assert_eq!(
+            candidates[0].point,
+            Coordinates {
+                lat: 45.523_06,
+                lng: -122.676_48
+            }
+        );
+        assert_eq!(
+            candidates[0].label.as_deref(),
+            Some("1 Main St, Portland, OR 97201, USA")
+        );
+        assert!((candidates[0].confidence - 0.92).abs() < f64::EPSILON);
+        assert!(parse_candidates(SearchResponse {
+            results: Vec::new()
+        })
+        .is_empty());
+    }
+
+    #[test]
+    fn parses_place_name_from_position_search() {
+        let response: SearchResponse = serde_json::from_str(
+            r#"{"Summary":{},"Results":[{"Place":{"Geometry":{"Point":[-78.87,42.92]},"Neighborhood":"Elmwood Village","Municipality":"Buffalo","Region":"New York","Country":"USA"},"Distance":12.5}]}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_top_place_name(response),
+            Some(PlaceName {
+                neighborhood: Some("Elmwood Village".to_string()),
+                locality: Some("Buffalo".to_string()),
+                region: Some("New York".to_string()),
+            })
+        );
+
+        let bare: SearchResponse =
+            serde_json::from_str(r#"{"Results":[{"Place":{"Geometry":{"Point":[0.0,0.0]}}}]}"#)
+                .unwrap();
+        assert_eq!(parse_top_place_name(bare), None);
+    }
+}
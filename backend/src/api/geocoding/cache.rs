@@ -0,0 +1,93 @@
+//! `geocode_cache` reads and writes. Both run on their own pooled
+//! connection rather than the caller's, so a cache problem can never abort
+//! the transaction that is saving the listing or profile; failures are
+//! logged and the request carries on as a cache miss.
+
+use super::Coordinates;
+use crate::db::{self, TimedQuery};
+use std::time::Duration;
+use tracing::warn;
+
+/// A cached match and the confidence it was stored with, so a caller that
+/// needs a precise match can still reject one cached by a lenient caller.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CachedPoint {
+    pub point: Coordinates,
+    pub confidence: f64,
+}
+
+/// Returns the cached match for `key` unless the entry has expired.
+pub async fn lookup_cached(key: &str) -> Option<CachedPoint> {
+    let client = match db::connect().await {
+        Ok(client) => client,
+        Err(error) => {
+            warn!(error = %error, "Geocode cache unavailable; calling provider");
+            return None;
+        }
+    };
+
+    match client
+        .query_opt_timed(
+            "geocoding::lookup_cached",
+            "
+            select lat, lng, confidence
+              from geocode_cache
+             where address_hash = $1
+               and expires_at > now()
+            ",
+            &[&key],
+        )
+        .await
+    {
+        Ok(row) => row.and_then(|row| {
+            Some(CachedPoint {
+                point: Coordinates::checked(row.get("lat"), row.get("lng"))?,
+                confidence: row.get("confidence"),
+            })
+        }),
+        Err(error) => {
+            warn!(error = %error, "Geocode cache lookup failed; calling provider");
+            None
+        }
+    }
+}
+
+/// Stores a provider result, replacing any expired entry for the same key.
+pub async fn store_cached(key: &str, provider: &str, cached: CachedPoint, ttl: Duration) {
+    let client = match db::connect().await {
+        Ok(client) => client,
+        Err(error) => {
+            warn!(error = %error, "Geocode cache unavailable; result not stored");
+            return;
+        }
+    };
+
+    let ttl_seconds = f64::from(u32::try_from(ttl.as_secs()).unwrap_or(u32::MAX));
+    if let Err(error) = client
+        .execute_timed(
+            "geocoding::store_cached",
+            "
+            insert into geocode_cache (address_hash, provider, lat, lng, confidence, expires_at)
+            values ($1, $2, $3, $4, $5, now() + make_interval(secs => $6))
+            on conflict (address_hash) do update
+               set provider = excluded.provider,
+                   lat = excluded.lat,
+                   lng = excluded.lng,
+                   confidence = excluded.confidence,
+                   created_at = now(),
+                   expires_at = excluded.expires_at
+            ",
+            &[
+                &key,
+                &provider,
+                &cached.point.lat,
+                &cached.point.lng,
+                &cached.confidence,
+                &ttl_seconds,
+            ],
+        )
+        .await
+    {
+        warn!(error = %error, "Failed to store geocode cache entry");
+    }
+}
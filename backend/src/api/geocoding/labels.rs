@@ -0,0 +1,224 @@
+//! Human-readable names for geohash prefixes, so responses can show
+//! "Elmwood, Buffalo" next to a raw `dr8q` key. Names come from a reverse
+//! lookup at the cell's center and are kept in `geo_labels`, including
+//! cells the provider could not name, so each prefix costs one provider
+//! call per TTL. Like the geocode cache, everything here runs on its own
+//! connection and degrades to "no label" rather than failing the request.
+
+use super::{Coordinates, GeocodingProvider, PlaceName};
+use crate::config;
+use crate::db::{self, TimedQuery};
+use crate::location;
+use crate::telemetry;
+use std::collections::HashMap;
+use tracing::{warn, Instrument};
+
+/// Cells at least this long (about 5 km across) are labelled by
+/// neighborhood; shorter ones by city.
+pub const NEIGHBORHOOD_PREFIX_LEN: usize = 5;
+
+/// Uncached prefixes resolved per call. Anything beyond this is left
+/// unlabelled and picked up by a later request, which keeps a feed with many
+/// signal areas from fanning out into a burst of provider calls.
+const MAX_LOOKUPS_PER_CALL: usize = 2;
+
+/// Builds the display label for a cell of `prefix_len` characters, or
+/// `None` when the place has nothing suitable at that scale.
+pub fn format_label(prefix_len: usize, place: &PlaceName) -> Option<String> {
+    let (specific, general) = match place.neighborhood.as_deref() {
+        Some(neighborhood) if prefix_len >= NEIGHBORHOOD_PREFIX_LEN => (
+            Some(neighborhood),
+            place.locality.as_deref().or(place.region.as_deref()),
+        ),
+        _ => (place.locality.as_deref(), place.region.as_deref()),
+    };
+
+    match (specific, general) {
+        (Some(specific), Some(general)) if specific != general => {
+            Some(format!("{specific}, {general}"))
+        }
+        (Some(name), _) | (None, Some(name)) => Some(name.to_string()),
+        (None, None) => None,
+    }
+}
+
+/// Labels for a single prefix; see [`labels_for`].
+pub async fn label_for(geo_prefix: &str) -> Option<String> {
+    labels_for(&[geo_prefix]).await.remove(geo_prefix)
+}
+
+/// Labels for each distinct, valid prefix in `geo_prefixes`. Prefixes with
+/// no known name are absent from the map.
+pub async fn labels_for(geo_prefixes: &[&str]) -> HashMap<String, String> {
+    let mut wanted = geo_prefixes
+        .iter()
+        .copied()
+        .filter(|prefix| location::is_valid_geo_key(prefix))
+        .map(str::to_string)
+        .collect::<Vec<_>>();
+    wanted.sort_unstable();
+    wanted.dedup();
+
+    let mut labels = HashMap::new();
+    if wanted.is_empty() {
+        return labels;
+    }
+
+    let client = match db::connect().await {
+        Ok(client) => client,
+        Err(error) => {
+            warn!(error = %error, "Geo label cache unavailable; returning unlabelled areas");
+            return labels;
+        }
+    };
+
+    let cached = match client
+        .query_timed(
+            "geocoding::labels_for",
+            "
+            select geo_prefix, label
+              from geo_labels
+             where geo_prefix = any($1)
+               and expires_at > now()
+            ",
+            &[&wanted],
+        )
+        .await
+    {
+        Ok(rows) => rows,
+        Err(error) => {
+            warn!(error = %error, "Geo label lookup failed; returning unlabelled areas");
+            return labels;
+        }
+    };
+
+    let mut known = Vec::with_capacity(cached.len());
+    for row in cached {
+        let prefix: String = row.get("geo_prefix");
+        if let Some(label) = row.get::<_, Option<String>>("label") {
+            labels.insert(prefix.clone(), label);
+        }
+        known.push(prefix);
+    }
+
+    let provider = super::provider();
+    let misses = wanted.iter().filter(|prefix| !known.contains(prefix));
+    for prefix in misses.take(MAX_LOOKUPS_PER_CALL) {
+        let Some(center) = cell_center(prefix) else {
+            continue;
+        };
+        let outcome = provider
+            .reverse(center)
+            .instrument(telemetry::client_span("geocoder", "reverse"))
+            .await;
+        let label = match outcome {
+            Ok(place) => place.and_then(|place| format_label(prefix.len(), &place)),
+            Err(error) => {
+                // Not cached, so the next request tries again.
+                warn!(
+                    geo_prefix = prefix.as_str(),
+                    provider = provider.name(),
+                    error = %error,
+                    "Reverse geocoding failed"
+                );
+                continue;
+            }
+        };
+
+        store_label(&client, prefix, label.as_deref(), provider).await;
+        if let Some(label) = label {
+            labels.insert(prefix.clone(), label);
+        }
+    }
+
+    labels
+}
+
+fn cell_center(geo_prefix: &str) -> Option<Coordinates> {
+    let (center, _, _) = geohash::decode(geo_prefix).ok()?;
+    Coordinates::checked(center.y, center.x)
+}
+
+async fn store_label(
+    client: &tokio_postgres::Client,
+    geo_prefix: &str,
+    label: Option<&str>,
+    provider: &dyn GeocodingProvider,
+) {
+    let ttl = config::get().geocoder.label_ttl;
+    let ttl_seconds = f64::from(u32::try_from(ttl.as_secs()).unwrap_or(u32::MAX));
+    if let Err(error) = client
+        .execute_timed(
+            "geocoding::store_label",
+            "
+            insert into geo_labels (geo_prefix, label, provider, expires_at)
+            values ($1, $2, $3, now() + make_interval(secs => $4))
+            on conflict (geo_prefix) do update
+               set label = excluded.label,
+                   provider = excluded.provider,
+                   created_at = now(),
+                   expires_at = excluded.expires_at
+            ",
+            &[&geo_prefix, &label, &provider.name(), &ttl_seconds],
+        )
+        .await
+    {
+        warn!(geo_prefix = geo_prefix, error = %error, "Failed to store geo label");
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn place(
+        neighborhood: Option<&str>,
+        locality: Option<&str>,
+        region: Option<&str>,
+    ) -> PlaceName {
+        PlaceName {
+            neighborhood: neighborhood.map(str::to_string),
+            locality: locality.map(str::to_string),
+            region: region.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn long_prefixes_are_labelled_by_neighborhood() {
+        let elmwood = place(Some("Elmwood"), Some("Buffalo"), Some("New York"));
+        assert_eq!(
+            format_label(5, &elmwood).as_deref(),
+            Some("Elmwood, Buffalo")
+        );
+        assert_eq!(
+            format_label(4, &elmwood).as_deref(),
+            Some("Buffalo, New York")
+        );
+    }
+
+    #[test]
+    fn missing_parts_fall_back_without_repeating_names() {
+        assert_eq!(
+            format_label(6, &place(None, Some("Buffalo"), Some("New York"))).as_deref(),
+            Some("Buffalo, New York")
+        );
+        assert_eq!(
+            format_label(4, &place(None, Some("Monaco"), Some("Monaco"))).as_deref(),
+            Some("Monaco")
+        );
+        assert_eq!(
+            format_label(3, &place(Some("Elmwood"), None, Some("New York"))).as_deref(),
+            Some("New York")
+        );
+        assert_eq!(format_label(5, &PlaceName::default()), None);
+    }
+
+    #[test]
+    fn cell_center_decodes_valid_prefixes_only() {
+        let center = cell_center("dr8q").unwrap();
+        assert!((center.lat - 43.330_078).abs() < 1e-6);
+        assert!((center.lng + 78.222_656).abs() < 1e-6);
+        assert_eq!(cell_center("ai!"), None);
+    }
+}
@@ -0,0 +1,124 @@
+//! Forward and reverse geocoding. A [`GeocodingProvider`] is selected by
+//! `GEOCODER_PROVIDER` in [`GeocoderConfig`], and every result is kept in
+//! `geocode_cache` under a hash of the normalized address, so saving a
+//! listing or profile with an unchanged address does not call the provider
+//! again until the entry's TTL runs out. Reverse lookups name geohash
+//! prefixes for display and are cached in `geo_labels` (see [`labels`]).
+
+mod aws_location;
+mod cache;
+pub mod labels;
+mod nominatim;
+mod stub;
+
+pub use aws_location::AwsLocationProvider;
+pub use cache::{lookup_cached, store_cached, CachedPoint};
+pub use nominatim::NominatimProvider;
+pub use stub::StubProvider;
+
+use crate::config::{GeocoderConfig, GeocoderProviderKind};
+use sha2::{Digest, Sha256};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::OnceLock;
+
+static PROVIDER: OnceLock<Box<dyn GeocodingProvider>> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Coordinates {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+impl Coordinates {
+    /// Rejects values outside the valid latitude and longitude ranges.
+    pub fn checked(lat: f64, lng: f64) -> Option<Self> {
+        ((-90.0..=90.0).contains(&lat) && (-180.0..=180.0).contains(&lng))
+            .then_some(Self { lat, lng })
+    }
+}
+
+/// One forward geocoding match.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GeocodeCandidate {
+    pub point: Coordinates,
+    /// The provider's formatted address for the match, offered back to the
+    /// caller as a correction.
+    pub label: Option<String>,
+    /// How precisely the match pins the address, from 0 (only a broad area
+    /// matched) to 1 (a specific building). Providers map their own scores
+    /// onto this scale.
+    pub confidence: f64,
+}
+
+/// Matches best first, at most [`MAX_CANDIDATES`]; empty means the provider
+/// answered but found no match.
+pub type GeocodeFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Vec<GeocodeCandidate>, lambda_http::Error>> + Send + 'a>>;
+
+/// Candidates requested per search, so a low-confidence match comes with
+/// alternatives to suggest.
+pub const MAX_CANDIDATES: usize = 3;
+
+/// The parts of a reverse geocoding result used to build area labels. Any
+/// part the provider did not return is `None`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PlaceName {
+    pub neighborhood: Option<String>,
+    pub locality: Option<String>,
+    pub region: Option<String>,
+}
+
+/// `Ok(None)` means the provider answered but has no place at that point.
+pub type ReverseGeocodeFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Option<PlaceName>, lambda_http::Error>> + Send + 'a>>;
+
+pub trait GeocodingProvider: Send + Sync {
+    /// Stable name used for the cache, metrics, and logs.
+    fn name(&self) -> &'static str;
+
+    fn search<'a>(&'a self, address: &'a str) -> GeocodeFuture<'a>;
+
+    fn reverse(&self, point: Coordinates) -> ReverseGeocodeFuture<'_>;
+}
+
+pub fn provider_for(config: &GeocoderConfig) -> Box<dyn GeocodingProvider> {
+    match config.provider {
+        GeocoderProviderKind::Nominatim => Box::new(NominatimProvider::from_config(config)),
+        GeocoderProviderKind::AwsLocation => Box::new(AwsLocationProvider::from_config(config)),
+        GeocoderProviderKind::Stub => Box::new(StubProvider),
+    }
+}
+
+/// The provider for this process, built on first use.
+pub fn provider() -> &'static dyn GeocodingProvider {
+    PROVIDER
+        .get_or_init(|| provider_for(&crate::config::get().geocoder))
+        .as_ref()
+}
+
+/// Cache key for an already whitespace-normalized address. Case is folded so
+/// "1 Main St" and "1 MAIN ST" share an entry, and only the hash is stored,
+/// so the cache never holds an address in clear.
+pub fn cache_key(normalized_address: &str) -> String {
+    hex::encode(Sha256::digest(normalized_address.to_lowercase().as_bytes()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_key_ignores_case_only() {
+        assert_eq!(cache_key("1 Main St"), cache_key("1 MAIN ST"));
+        assert_ne!(cache_key("1 Main St"), cache_key("2 Main St"));
+        assert_eq!(cache_key("1 Main St").len(), 64);
+    }
+
+    #[test]
+    fn checked_coordinates_reject_out_of_range_values() {
+        assert!(Coordinates::checked(45.5, -122.6).is_some());
+        assert!(Coordinates::checked(91.0, 0.0).is_none());
+        assert!(Coordinates::checked(0.0, -180.5).is_none());
+    }
+}
@@ -0,0 +1,223 @@
+use super::{
+    Coordinates, GeocodeCandidate, GeocodeFuture, GeocodingProvider, PlaceName,
+    ReverseGeocodeFuture, MAX_CANDIDATES,
+};
+use crate::config::GeocoderConfig;
+use serde::Deserialize;
+use std::time::Duration;
+
+/// OpenStreetMap Nominatim, or any server speaking its `/search` and
+/// `/reverse` APIs.
+#[derive(Debug, Clone)]
+pub struct NominatimProvider {
+    base_url: String,
+    user_agent: String,
+    timeout: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+struct NominatimSearchResult {
+    lat: String,
+    lon: String,
+    #[serde(default)]
+    display_name: Option<String>,
+    /// 30 is a building, 26-27 a street, 16 a city; see the Nominatim docs
+    /// on place ranking.
+    #[serde(default)]
+    place_rank: Option<u8>,
+}
+
+/// A point with no place (e.g. open ocean) comes back as `{"error": ...}`
+/// with no `address`.
+#[derive(Debug, Deserialize)]
+struct NominatimReverseResult {
+    #[serde(default)]
+    address: Option<NominatimAddress>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NominatimAddress {
+    neighbourhood: Option<String>,
+    suburb: Option<String>,
+    quarter: Option<String>,
+    city: Option<String>,
+    town: Option<String>,
+    village: Option<String>,
+    state: Option<String>,
+}
+
+impl NominatimProvider {
+    pub fn from_config(config: &GeocoderConfig) -> Self {
+        Self {
+            base_url: config.base_url.clone(),
+            user_agent: config.user_agent.clone(),
+            timeout: config.timeout,
+        }
+    }
+
+    fn client(&self) -> Result<reqwest::Client, lambda_http::Error> {
+        reqwest::Client::builder()
+            .timeout(self.timeout)
+            .user_agent(self.user_agent.as_str())
+            .build()
+            .map_err(|error| {
+                lambda_http::Error::from(format!("Failed to build geocoder client: {error}"))
+            })
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/{path}", self.base_url.trim_end_matches('/'))
+    }
+}
+
+fn ensure_success(response: &reqwest::Response) -> Result<(), lambda_http::Error> {
+    if response.status().is_success() {
+        Ok(())
+    } else {
+        Err(lambda_http::Error::from(format!(
+            "Nominatim returned status {}",
+            response.status().as_u16()
+        )))
+    }
+}
+
+impl GeocodingProvider for NominatimProvider {
+    fn name(&self) -> &'static str {
+        "nominatim"
+    }
+
+    fn search<'a>(&'a self, address: &'a str) -> GeocodeFuture<'a> {
+        Box::pin(async move {
+            let limit = MAX_CANDIDATES.to_string();
+            let response = self
+                .client()?
+                .get(self.url("search"))
+                .query(&[
+                    ("format", "jsonv2"),
+                    ("limit", limit.as_str()),
+                    ("addressdetails", "0"),
+                    ("q", address),
+                ])
+                .send()
+                .await?;
+            ensure_success(&response)?;
+
+            let results = response.json::<Vec<NominatimSearchResult>>().await?;
+            Ok(parse_candidates(results))
+        })
+    }
+
+    fn reverse(&self, point: Coordinates) -> ReverseGeocodeFuture<'_> {
+        Box::pin(async move {
+            let (lat, lon) = (point.lat.to_string(), point.lng.to_string());
+            let response = self
+                .client()?
+                .get(self.url("reverse"))
+                .query(&[
+                    ("format", "jsonv2"),
+                    ("addressdetails", "1"),
+                    // Suburb-level detail; street names are never needed.
+                    ("zoom", "14"),
+                    ("lat", lat.as_str()),
+                    ("lon", lon.as_str()),
+                ])
+                .send()
+                .await?;
+            ensure_success(&response)?;
+
+            let result = response.json::<NominatimReverseResult>().await?;
+            Ok(parse_reverse_result(result))
+        })
+    }
+}
+
+fn parse_candidates(results: Vec<NominatimSearchResult>) -> Vec<GeocodeCandidate> {
+    results
+        .into_iter()
+        .filter_map(|result| {
+            let lat = result.lat.parse::<f64>().ok()?;
+            let lng = result.lon.parse::<f64>().ok()?;
+            Some(GeocodeCandidate {
+                point: Coordinates::checked(lat, lng)?,
+                label: result.display_name,
+                confidence: confidence_for_rank(result.place_rank),
+            })
+        })
+        .collect()
+}
+
+/// Nominatim has no match score, so confidence follows how specific the
+/// matched place is. A result without a rank is trusted, as before ranks
+/// were read.
+fn confidence_for_rank(place_rank: Option<u8>) -> f64 {
+    match place_rank {
+        None | Some(28..) => 1.0,
+        Some(26..=27) => 0.7,
+        Some(21..=25) => 0.4,
+        Some(_) => 0.2,
+    }
+}
+
+fn parse_reverse_result(result: NominatimReverseResult) -> Option<PlaceName> {
+    let address = result.address?;
+    let place = PlaceName {
+        neighborhood: address.neighbourhood.or(address.suburb).or(address.quarter),
+        locality: address.city.or(address.town).or(address.village),
+        region: address.state,
+    };
+    (place != PlaceName::default()).then_some(place)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_candidates_and_skips_unusable_ones() {
+        let results: Vec<NominatimSearchResult> = serde_json::from_str(
+            r#"[{"lat":"45.52306","lon":"-122.67648","display_name":"1 Main St, Portland","place_rank":30},{"lat":"north","lon":"0"},{"lat":"45.5","lon":"-122.6","place_rank":16}]"#,
+        )
+        .unwrap();
+        let candidates = parse_candidates(results);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(
+            candidates[0].point,
+            Coordinates {
+                lat: 45.523_06,
+                lng: -122.676_48
+            }
+        );
+        assert_eq!(candidates[0].label.as_deref(), Some("1 Main St, Portland"));
+        assert!((candidates[0].confidence - 1.0).abs() < f64::EPSILON);
+        assert!(candidates[1].confidence < 0.5);
+        assert!(parse_candidates(Vec::new()).is_empty());
+    }
+
+    #[test]
+    fn street_matches_rank_between_buildings_and_areas() {
+        assert!(confidence_for_rank(Some(26)) > confidence_for_rank(Some(22)));
+        assert!(confidence_for_rank(Some(30)) > confidence_for_rank(Some(26)));
+        assert!((confidence_for_rank(None) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn parses_reverse_address_with_fallbacks() {
+        let result: NominatimReverseResult = serde_json::from_str(
+            r#"{"place_id":1,"address":{"suburb":"Elmwood","city":"Buffalo","state":"New York","country":"United States"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            parse_reverse_result(result),
+            Some(PlaceName {
+                neighborhood: Some("Elmwood".to_string()),
+                locality: Some("Buffalo".to_string()),
+                region: Some("New York".to_string()),
+            })
+        );
+
+        let no_place: NominatimReverseResult =
+            serde_json::from_str(r#"{"error":"Unable to geocode"}"#).unwrap();
+        assert_eq!(parse_reverse_result(no_place), None);
+    }
+}
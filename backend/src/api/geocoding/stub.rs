@@ -0,0 +1,109 @@
+use super::{
+    Coordinates, GeocodeCandidate, GeocodeFuture, GeocodingProvider, PlaceName,
+    ReverseGeocodeFuture,
+};
+use sha2::{Digest, Sha256};
+
+/// Deterministic coordinates derived from the address, with no network
+/// call. For local development and tests; the same address always lands on
+/// the same point, an address containing "unknown" has no match, and one
+/// containing "approximate" matches only loosely, with two suggestions.
+/// Reverse lookups name the point after its coordinates.
+#[derive(Debug, Clone, Copy)]
+pub struct StubProvider;
+
+impl GeocodingProvider for StubProvider {
+    fn name(&self) -> &'static str {
+        "stub"
+    }
+
+    fn search<'a>(&'a self, address: &'a str) -> GeocodeFuture<'a> {
+        Box::pin(async move { Ok(stub_candidates(address)) })
+    }
+
+    fn reverse(&self, point: Coordinates) -> ReverseGeocodeFuture<'_> {
+        Box::pin(async move { Ok(Some(stub_place_name(point))) })
+    }
+}
+
+fn stub_candidates(address: &str) -> Vec<GeocodeCandidate> {
+    let Some(point) = stub_coordinates(address) else {
+        return Vec::new();
+    };
+    if !address.to_lowercase().contains("approximate") {
+        return vec![GeocodeCandidate {
+            point,
+            label: Some(address.to_string()),
+            confidence: 1.0,
+        }];
+    }
+
+    ["North", "South"]
+        .iter()
+        .map(|side| GeocodeCandidate {
+            point,
+            label: Some(format!("{address} {side}")),
+            confidence: 0.3,
+        })
+        .collect()
+}
+
+fn stub_place_name(point: Coordinates) -> PlaceName {
+    PlaceName {
+        neighborhood: Some(format!("Plot {:.2} {:.2}", point.lat, point.lng)),
+        locality: Some(format!("Stubville {:.0}", point.lat)),
+        region: Some("Stub County".to_string()),
+    }
+}
+
+fn stub_coordinates(address: &str) -> Option<Coordinates> {
+    if address.to_lowercase().contains("unknown") {
+        return None;
+    }
+    let digest = Sha256::digest(address.to_lowercase().as_bytes());
+    let fraction = |bytes: [u8; 4]| f64::from(u32::from_be_bytes(bytes)) / f64::from(u32::MAX);
+    // Stays within populated latitudes so geohash cells look realistic.
+    let lat = fraction([digest[0], digest[1], digest[2], digest[3]]).mul_add(120.0, -60.0);
+    let lng = fraction([digest[4], digest[5], digest[6], digest[7]]).mul_add(360.0, -180.0);
+    Coordinates::checked(lat, lng)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stub_is_deterministic_and_case_insensitive() {
+        let first = stub_coordinates("1 Main St").unwrap();
+        assert_eq!(stub_coordinates("1 MAIN ST"), Some(first));
+        assert_ne!(stub_coordinates("2 Main St"), Some(first));
+        assert!((-60.0..=60.0).contains(&first.lat));
+    }
+
+    #[test]
+    fn stub_has_no_match_for_unknown_addresses() {
+        assert_eq!(stub_coordinates("Unknown Road"), None);
+        assert!(stub_candidates("Unknown Road").is_empty());
+    }
+
+    #[test]
+    fn stub_approximate_addresses_match_loosely() {
+        let candidates = stub_candidates("9 Approximate Way, Springfield, 12345");
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates
+            .iter()
+            .all(|candidate| candidate.confidence < 0.5));
+        assert!((stub_candidates("1 Main St")[0].confidence - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn stub_reverse_names_every_point() {
+        let place = stub_place_name(Coordinates {
+            lat: 42.92,
+            lng: -78.87,
+        });
+        assert_eq!(place.neighborhood.as_deref(), Some("Plot 42.92 -78.87"));
+        assert_eq!(place.locality.as_deref(), Some("Stubville 43"));
+    }
+}
@@ -0,0 +1,536 @@
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::{extract_auth_context, AuthContext};
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::events::{self, ListingEventDetail, ModerationActionEventDetail};
+use crate::http_util::{json_response, parse_json_body};
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use tracing::error;
+use uuid::Uuid;
+
+const MAX_REASON_LENGTH: usize = 500;
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModerationActionRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModeratedListingResponse {
+    pub id: String,
+    pub user_id: String,
+    pub status: String,
+    pub hidden_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSuspensionResponse {
+    pub user_id: String,
+    pub suspended_at: Option<String>,
+    pub suspension_reason: Option<String>,
+}
+
+/// Hides a listing from every discovery and read path. Hiding an already
+/// hidden listing returns it unchanged without a second audit entry.
+pub async fn hide_listing(
+    request: &Request,
+    correlation_id: &str,
+    listing_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let listing_id = parse_path_id(listing_id, "listingId")?;
+    let reason = parse_reason(request, true)?;
+
+    let mut client = db::connect().await?;
+    let transaction = client.transaction().await?;
+
+    let current = lock_listing(&transaction, listing_id).await?;
+    if current
+        .get::<_, Option<DateTime<Utc>>>("admin_hidden_at")
+        .is_some()
+    {
+        transaction.commit().await?;
+        return json_response(200, &row_to_listing_response(&current));
+    }
+
+    let row = transaction
+        .query_one_timed(
+            "admin_moderation::hide_listing",
+            "
+            update surplus_listings
+               set admin_hidden_at = now(),
+                   moderation_held_at = coalesce(moderation_held_at, now())
+             where id = $1
+            returning id, user_id, crop_id, status::text as status, geo_key, admin_hidden_at
+            ",
+            &[&listing_id],
+        )
+        .await?;
+    let response = row_to_listing_response(&row);
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::LISTING_HIDDEN,
+            target_type: "listing",
+            target_id: response.id.clone(),
+            before: serde_json::to_value(row_to_listing_response(&current)).ok(),
+            after: Some(serde_json::json!({
+                "hiddenAt": response.hidden_at,
+                "reason": reason,
+            })),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        listing_id = response.id.as_str(),
+        "Admin hid listing"
+    );
+
+    emit_listing_events_best_effort(&row, "listing_hidden", reason, correlation_id).await;
+
+    json_response(200, &response)
+}
+
+/// Moves a listing straight to `expired`. Completed listings keep their
+/// outcome; already-expired listings are returned unchanged.
+pub async fn expire_listing(
+    request: &Request,
+    correlation_id: &str,
+    listing_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let listing_id = parse_path_id(listing_id, "listingId")?;
+    let reason = parse_reason(request, false)?;
+
+    let mut client = db::connect().await?;
+    let transaction = client.transaction().await?;
+
+    let current = lock_listing(&transaction, listing_id).await?;
+    match current.get::<_, String>("status").as_str() {
+        "expired" => {
+            transaction.commit().await?;
+            return json_response(200, &row_to_listing_response(&current));
+        }
+        "completed" => {
+            return Err(ApiError::conflict(
+                "listing_not_expirable",
+                "Completed listings cannot be expired",
+            ));
+        }
+        _ => {}
+    }
+
+    let row = transaction
+        .query_one_timed(
+            "admin_moderation::expire_listing",
+            "
+            update surplus_listings
+               set status = 'expired'
+             where id = $1
+            returning id, user_id, crop_id, status::text as status, geo_key, admin_hidden_at
+            ",
+            &[&listing_id],
+        )
+        .await?;
+    let response = row_to_listing_response(&row);
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::LISTING_FORCE_EXPIRED,
+            target_type: "listing",
+            target_id: response.id.clone(),
+            before: Some(serde_json::json!({ "status": current.get::<_, String>("status") })),
+            after: Some(serde_json::json!({
+                "status": response.status,
+                "reason": reason,
+            })),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        listing_id = response.id.as_str(),
+        "Admin force-expired listing"
+    );
+
+    emit_listing_events_best_effort(&row, "listing_expired", reason, correlation_id).await;
+
+    json_response(200, &response)
+}
+
+/// Suspends a user. Suspended users keep read access, but the router refuses
+/// every write they attempt until the suspension is lifted.
+pub async fn suspend_user(
+    request: &Request,
+    correlation_id: &str,
+    user_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_path_id(user_id, "userId")?;
+    ensure_not_self(&auth, user_id)?;
+    let reason = parse_reason(request, true)?;
+
+    let mut client = db::connect().await?;
+    let transaction = client.transaction().await?;
+
+    let current = lock_user(&transaction, user_id).await?;
+    if current
+        .get::<_, Option<DateTime<Utc>>>("suspended_at")
+        .is_some()
+    {
+        transaction.commit().await?;
+        return json_response(200, &row_to_suspension_response(&current));
+    }
+
+    let row = transaction
+        .query_one_timed(
+            "admin_moderation::suspend_user",
+            "
+            update users
+               set suspended_at = now(),
+                   suspension_reason = $2,
+                   updated_at = now()
+             where id = $1
+            returning id, suspended_at, suspension_reason
+            ",
+            &[&user_id, &reason],
+        )
+        .await?;
+    let response = row_to_suspension_response(&row);
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::USER_SUSPENDED,
+            target_type: "user",
+            target_id: response.user_id.clone(),
+            before: Some(serde_json::json!({ "suspendedAt": null })),
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        user_id = response.user_id.as_str(),
+        "Admin suspended user"
+    );
+
+    emit_moderation_event_best_effort(
+        ModerationActionEventDetail::new(
+            response.user_id.clone(),
+            "user_suspended",
+            None,
+            reason,
+            correlation_id,
+        ),
+        correlation_id,
+    )
+    .await;
+
+    json_response(200, &response)
+}
+
+/// Lifts a suspension. Lifting one that is not in effect is a no-op.
+pub async fn lift_user_suspension(
+    request: &Request,
+    correlation_id: &str,
+    user_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_path_id(user_id, "userId")?;
+
+    let mut client = db::connect().await?;
+    let transaction = client.transaction().await?;
+
+    let current = lock_user(&transaction, user_id).await?;
+    if current
+        .get::<_, Option<DateTime<Utc>>>("suspended_at")
+        .is_none()
+    {
+        transaction.commit().await?;
+        return json_response(200, &row_to_suspension_response(&current));
+    }
+
+    let row = transaction
+        .query_one_timed(
+            "admin_moderation::lift_user_suspension",
+            "
+            update users
+               set suspended_at = null,
+                   suspension_reason = null,
+                   updated_at = now()
+             where id = $1
+            returning id, suspended_at, suspension_reason
+            ",
+            &[&user_id],
+        )
+        .await?;
+    let response = row_to_suspension_response(&row);
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::USER_SUSPENSION_LIFTED,
+            target_type: "user",
+            target_id: response.user_id.clone(),
+            before: serde_json::to_value(row_to_suspension_response(&current)).ok(),
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        user_id = response.user_id.as_str(),
+        "Admin lifted user suspension"
+    );
+
+    emit_moderation_event_best_effort(
+        ModerationActionEventDetail::new(
+            response.user_id.clone(),
+            "user_reinstated",
+            None,
+            None,
+            correlation_id,
+        ),
+        correlation_id,
+    )
+    .await;
+
+    json_response(200, &response)
+}
+
+async fn lock_listing(
+    transaction: &tokio_postgres::Transaction<'_>,
+    listing_id: Uuid,
+) -> Result<Row, ApiError> {
+    transaction
+        .query_opt_timed(
+            "admin_moderation::lock_listing",
+            "
+            select id, user_id, crop_id, status::text as status, geo_key, admin_hidden_at
+            from surplus_listings
+            where id = $1
+              and deleted_at is null
+            for update
+            ",
+            &[&listing_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))
+}
+
+async fn lock_user(
+    transaction: &tokio_postgres::Transaction<'_>,
+    user_id: Uuid,
+) -> Result<Row, ApiError> {
+    transaction
+        .query_opt_timed(
+            "admin_moderation::lock_user",
+            "
+            select id, suspended_at, suspension_reason
+            from users
+            where id = $1
+              and deleted_at is null
+            for update
+            ",
+            &[&user_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("user_not_found", "User not found"))
+}
+
+fn parse_path_id(value: &str, field_name: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        ApiError::invalid_field(
+            field_name,
+            "invalid_uuid",
+            format!("{field_name} must be a valid UUID"),
+        )
+    })
+}
+
+/// Reads the optional `reason` body. Hides and suspensions must say why,
+/// since the reason is shown to the affected user.
+fn parse_reason(request: &Request, required: bool) -> Result<Option<String>, ApiError> {
+    let payload: ModerationActionRequest = match request.body() {
+        Body::Empty => ModerationActionRequest::default(),
+        _ => parse_json_body(request)?,
+    };
+    normalize_reason(payload.reason, required)
+}
+
+fn normalize_reason(reason: Option<String>, required: bool) -> Result<Option<String>, ApiError> {
+    let reason = reason
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty());
+
+    match reason {
+        None if required => Err(ApiError::invalid_field(
+            "reason",
+            "required",
+            "reason is required",
+        )),
+        Some(value) if value.chars().count() > MAX_REASON_LENGTH => Err(ApiError::invalid_field(
+            "reason",
+            "too_long",
+            format!("reason must be at most {MAX_REASON_LENGTH} characters"),
+        )),
+        other => Ok(other),
+    }
+}
+
+fn ensure_not_self(auth: &AuthContext, user_id: Uuid) -> Result<(), ApiError> {
+    if auth.user_id == user_id.to_string() {
+        return Err(ApiError::invalid_field(
+            "userId",
+            "self_suspension",
+            "Admins cannot suspend themselves",
+        ));
+    }
+    Ok(())
+}
+
+fn row_to_listing_response(row: &Row) -> ModeratedListingResponse {
+    ModeratedListingResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        user_id: row.get::<_, Uuid>("user_id").to_string(),
+        status: row.get("status"),
+        hidden_at: row
+            .get::<_, Option<DateTime<Utc>>>("admin_hidden_at")
+            .map(|value| value.to_rfc3339()),
+    }
+}
+
+fn row_to_suspension_response(row: &Row) -> UserSuspensionResponse {
+    UserSuspensionResponse {
+        user_id: row.get::<_, Uuid>("id").to_string(),
+        suspended_at: row
+            .get::<_, Option<DateTime<Utc>>>("suspended_at")
+            .map(|value| value.to_rfc3339()),
+        suspension_reason: row.get("suspension_reason"),
+    }
+}
+
+/// Listing actions notify the owner and also publish `listing.updated` so
+/// feed and search projections drop the listing.
+async fn emit_listing_events_best_effort(
+    row: &Row,
+    action: &str,
+    reason: Option<String>,
+    correlation_id: &str,
+) {
+    let listing_id = row.get::<_, Uuid>("id").to_string();
+    let owner_id = row.get::<_, Uuid>("user_id").to_string();
+
+    let listing_detail = ListingEventDetail::new(
+        listing_id.clone(),
+        owner_id.clone(),
+        row.get::<_, Option<Uuid>>("crop_id")
+            .map(|id| id.to_string()),
+        row.get::<_, String>("status"),
+        row.get::<_, Option<String>>("geo_key"),
+        correlation_id,
+    );
+    if let Err(error) = events::publish(events::LISTING_UPDATED, &listing_detail).await {
+        error!(
+            correlation_id = correlation_id,
+            listing_id = listing_id.as_str(),
+            error = %error,
+            "Failed to emit listing event after admin moderation"
+        );
+    }
+
+    emit_moderation_event_best_effort(
+        ModerationActionEventDetail::new(
+            owner_id,
+            action,
+            Some(listing_id),
+            reason,
+            correlation_id,
+        ),
+        correlation_id,
+    )
+    .await;
+}
+
+async fn emit_moderation_event_best_effort(
+    detail: ModerationActionEventDetail,
+    correlation_id: &str,
+) {
+    if let Err(error) = events::publish(events::MODERATION_ACTION_TAKEN, &detail).await {
+        error!(
+            correlation_id = correlation_id,
+            user_id = detail.user_id.as_str(),
+            action = detail.action.as_str(),
+            error = %error,
+            "Failed to emit moderation action event"
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn required_reason_rejects_missing_and_blank_values() {
+        assert!(normalize_reason(None, true).is_err());
+        assert!(normalize_reason(Some("   ".to_string()), true).is_err());
+    }
+
+    #[test]
+    fn optional_reason_allows_missing_value() {
+        assert_eq!(normalize_reason(None, false).unwrap(), None);
+        assert_eq!(normalize_reason(Some(String::new()), false).unwrap(), None);
+    }
+
+    #[test]
+    fn reason_is_trimmed() {
+        assert_eq!(
+            normalize_reason(Some("  spam  ".to_string()), true).unwrap(),
+            Some("spam".to_string())
+        );
+    }
+
+    #[test]
+    fn reason_over_limit_is_rejected() {
+        let long = "x".repeat(MAX_REASON_LENGTH + 1);
+        assert!(normalize_reason(Some(long), false).is_err());
+        let exact = "x".repeat(MAX_REASON_LENGTH);
+        assert!(normalize_reason(Some(exact), true).is_ok());
+    }
+}
@@ -0,0 +1,424 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::Row;
+
+const DEFAULT_FUNNEL_DAYS: i32 = 30;
+const MAX_FUNNEL_DAYS: i32 = 90;
+
+const DEFAULT_ERROR_LIMIT: i64 = 50;
+const MAX_ERROR_LIMIT: i64 = 200;
+
+/// Matches the baseline sweep's default, so `staleScopeCount` counts the
+/// scopes the next sweep would pick up.
+const SIGNAL_STALE_AFTER_MINUTES: i32 = 6 * 60;
+
+const ERROR_SOURCES: [&str; 4] = ["stripe_webhook", "agent_task", "reminder", "ai_summary"];
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatusCount {
+    pub status: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaimsFunnel {
+    pub days: i32,
+    pub claimed: i64,
+    pub confirmed: i64,
+    pub completed: i64,
+    pub cancelled: i64,
+    pub no_show: i64,
+    /// Completed claims over claims that reached a final state.
+    pub completion_rate: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalFreshness {
+    pub scope_count: i64,
+    pub stale_scope_count: i64,
+    pub stale_after_minutes: i32,
+    pub newest_computed_at: Option<String>,
+    pub oldest_computed_at: Option<String>,
+    /// Age of the least recently recomputed scope, in seconds.
+    pub max_lag_seconds: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminStatsResponse {
+    pub generated_at: String,
+    /// Distinct users who listed, requested, claimed, or messaged in the last 24 hours.
+    pub daily_active_users: i64,
+    pub listings_by_status: Vec<StatusCount>,
+    pub claims_funnel: ClaimsFunnel,
+    pub signal_freshness: SignalFreshness,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentErrorResponse {
+    pub source: String,
+    pub occurred_at: String,
+    pub message: String,
+    pub reference_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecentErrorListResponse {
+    pub items: Vec<RecentErrorResponse>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct RecentErrorsQuery {
+    source: Option<String>,
+    limit: i64,
+}
+
+/// Operational snapshot aggregated from live tables, so routine health checks
+/// do not need ad hoc SQL against production.
+pub async fn get_admin_stats(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let days = parse_days(request.uri().query())?;
+
+    let client = db::connect().await?;
+
+    let active_row = client
+        .query_one_timed(
+            "admin_ops::daily_active_users",
+            "
+            select count(distinct user_id)::bigint as daily_active_users
+            from (
+              select user_id from surplus_listings where created_at >= now() - interval '1 day'
+              union all
+              select user_id from requests where created_at >= now() - interval '1 day'
+              union all
+              select claimer_id from claims where claimed_at >= now() - interval '1 day'
+              union all
+              select sender_id from messages where created_at >= now() - interval '1 day'
+            ) active
+            ",
+            &[],
+        )
+        .await?;
+
+    let listing_rows = client
+        .query_timed(
+            "admin_ops::listings_by_status",
+            "
+            select status::text as status, count(*)::bigint as count
+            from surplus_listings
+            where deleted_at is null
+            group by status
+            order by status
+            ",
+            &[],
+        )
+        .await?;
+
+    let funnel_row = client
+        .query_one_timed(
+            "admin_ops::claims_funnel",
+            "
+            select
+              count(*)::bigint as claimed,
+              count(*) filter (where confirmed_at is not null or status in ('confirmed', 'completed'))::bigint as confirmed,
+              count(*) filter (where status = 'completed')::bigint as completed,
+              count(*) filter (where status = 'cancelled')::bigint as cancelled,
+              count(*) filter (where status = 'no_show')::bigint as no_show
+            from claims
+            where claimed_at >= now() - make_interval(days => $1)
+            ",
+            &[&days],
+        )
+        .await?;
+
+    let freshness_row = client
+        .query_one_timed(
+            "admin_ops::signal_freshness",
+            "
+            with latest as (
+              select distinct on (geo_boundary_key, crop_scope_id) computed_at
+              from derived_supply_signals
+              where schema_version = 1
+              order by geo_boundary_key, crop_scope_id, computed_at desc, id desc
+            )
+            select
+              count(*)::bigint as scope_count,
+              count(*) filter (
+                where computed_at < now() - make_interval(mins => $1)
+              )::bigint as stale_scope_count,
+              max(computed_at) as newest_computed_at,
+              min(computed_at) as oldest_computed_at
+            from latest
+            ",
+            &[&SIGNAL_STALE_AFTER_MINUTES],
+        )
+        .await?;
+
+    let now = Utc::now();
+    let response = AdminStatsResponse {
+        generated_at: now.to_rfc3339(),
+        daily_active_users: active_row.get("daily_active_users"),
+        listings_by_status: listing_rows
+            .iter()
+            .map(|row| StatusCount {
+                status: row.get("status"),
+                count: row.get("count"),
+            })
+            .collect(),
+        claims_funnel: row_to_funnel(&funnel_row, days),
+        signal_freshness: row_to_freshness(&freshness_row, now),
+    };
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        days,
+        daily_active_users = response.daily_active_users,
+        stale_scope_count = response.signal_freshness.stale_scope_count,
+        "Returned admin stats"
+    );
+
+    json_response(200, &response)
+}
+
+/// Newest failures across webhook processing, agent task runs, reminder
+/// dispatches, and AI summary generation, merged into one timeline.
+pub async fn list_recent_errors(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let query = parse_recent_errors_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "admin_ops::list_recent_errors",
+            "
+            select source, occurred_at, message, reference_id
+            from (
+              select 'stripe_webhook' as source,
+                     created_at as occurred_at,
+                     coalesce(event_type || ': ', '') || reason as message,
+                     event_id as reference_id
+              from stripe_webhook_failures
+              union all
+              select 'agent_task',
+                     coalesce(finished_at, created_at),
+                     coalesce(error_message, 'Agent task run failed'),
+                     agent_task_id::text
+              from agent_task_runs
+              where run_status = 'failed'
+              union all
+              select 'reminder',
+                     created_at,
+                     'Reminder dispatch failed',
+                     reminder_rule_id::text
+              from reminder_dispatches
+              where delivery_status = 'failed'
+              union all
+              select 'ai_summary',
+                     created_at,
+                     feature_key || ' ' || status || ' (' || provider || '/' || model_id || ')',
+                     geo_boundary_key
+              from ai_usage
+              where status <> 'success'
+            ) errors
+            where ($1::text is null or source = $1)
+            order by occurred_at desc
+            limit $2
+            ",
+            &[&query.source, &query.limit],
+        )
+        .await?;
+
+    let items: Vec<RecentErrorResponse> = rows.iter().map(row_to_error).collect();
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        error_count = items.len(),
+        "Listed recent errors"
+    );
+
+    json_response(200, &RecentErrorListResponse { items })
+}
+
+fn parse_days(query: Option<&str>) -> Result<i32, ApiError> {
+    let Some(value) = query.and_then(|raw| {
+        raw.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find_map(|(key, value)| (key == "days" && !value.is_empty()).then_some(value))
+    }) else {
+        return Ok(DEFAULT_FUNNEL_DAYS);
+    };
+
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|days| (1..=MAX_FUNNEL_DAYS).contains(days))
+        .ok_or_else(|| {
+            ApiError::invalid_field(
+                "days",
+                "invalid_days",
+                format!("Invalid days. Must be between 1 and {MAX_FUNNEL_DAYS}"),
+            )
+        })
+}
+
+fn parse_recent_errors_query(query: Option<&str>) -> Result<RecentErrorsQuery, ApiError> {
+    let mut parsed = RecentErrorsQuery {
+        source: None,
+        limit: DEFAULT_ERROR_LIMIT,
+    };
+
+    let Some(raw_query) = query else {
+        return Ok(parsed);
+    };
+
+    for pair in raw_query.split('&') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+
+        match key {
+            "source" => {
+                if !ERROR_SOURCES.contains(&value) {
+                    return Err(ApiError::invalid_field(
+                        "source",
+                        "invalid_source",
+                        format!(
+                            "Invalid source. Must be one of: {}",
+                            ERROR_SOURCES.join(", ")
+                        ),
+                    ));
+                }
+                parsed.source = Some(value.to_string());
+            }
+            "limit" => {
+                parsed.limit = value
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|limit| (1..=MAX_ERROR_LIMIT).contains(limit))
+                    .ok_or_else(|| {
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            format!("Invalid limit. Must be between 1 and {MAX_ERROR_LIMIT}"),
+                        )
+                    })?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn row_to_funnel(row: &Row, days: i32) -> ClaimsFunnel {
+    let completed: i64 = row.get("completed");
+    let cancelled: i64 = row.get("cancelled");
+    let no_show: i64 = row.get("no_show");
+
+    ClaimsFunnel {
+        days,
+        claimed: row.get("claimed"),
+        confirmed: row.get("confirmed"),
+        completed,
+        cancelled,
+        no_show,
+        completion_rate: completion_rate(completed, cancelled, no_show),
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn completion_rate(completed: i64, cancelled: i64, no_show: i64) -> Option<f64> {
+    let resolved = completed + cancelled + no_show;
+    (resolved > 0).then(|| completed as f64 / resolved as f64)
+}
+
+fn row_to_freshness(row: &Row, now: DateTime<Utc>) -> SignalFreshness {
+    let newest = row.get::<_, Option<DateTime<Utc>>>("newest_computed_at");
+    let oldest = row.get::<_, Option<DateTime<Utc>>>("oldest_computed_at");
+
+    SignalFreshness {
+        scope_count: row.get("scope_count"),
+        stale_scope_count: row.get("stale_scope_count"),
+        stale_after_minutes: SIGNAL_STALE_AFTER_MINUTES,
+        newest_computed_at: newest.map(|at| at.to_rfc3339()),
+        oldest_computed_at: oldest.map(|at| at.to_rfc3339()),
+        max_lag_seconds: oldest.map(|at| (now - at).num_seconds().max(0)),
+    }
+}
+
+fn row_to_error(row: &Row) -> RecentErrorResponse {
+    RecentErrorResponse {
+        source: row.get("source"),
+        occurred_at: row.get::<_, DateTime<Utc>>("occurred_at").to_rfc3339(),
+        message: row.get("message"),
+        reference_id: row.get("reference_id"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_days_defaults_and_bounds() {
+        assert_eq!(parse_days(None).unwrap(), DEFAULT_FUNNEL_DAYS);
+        assert_eq!(parse_days(Some("days=7")).unwrap(), 7);
+
+        for raw in ["days=0", "days=91", "days=month"] {
+            let error = parse_days(Some(raw)).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_days", "{raw}");
+        }
+    }
+
+    #[test]
+    fn parse_recent_errors_query_reads_filters() {
+        assert_eq!(
+            parse_recent_errors_query(None).unwrap(),
+            RecentErrorsQuery {
+                source: None,
+                limit: DEFAULT_ERROR_LIMIT,
+            }
+        );
+
+        let parsed = parse_recent_errors_query(Some("source=ai_summary&limit=10")).unwrap();
+        assert_eq!(parsed.source.as_deref(), Some("ai_summary"));
+        assert_eq!(parsed.limit, 10);
+    }
+
+    #[test]
+    fn parse_recent_errors_query_rejects_unknown_source_and_bad_limit() {
+        let error = parse_recent_errors_query(Some("source=cron")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_source");
+
+        let error = parse_recent_errors_query(Some("limit=500")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_limit");
+    }
+
+    #[test]
+    fn completion_rate_ignores_open_claims() {
+        assert_eq!(completion_rate(0, 0, 0), None);
+        assert_eq!(completion_rate(3, 0, 1), Some(0.75));
+    }
+}
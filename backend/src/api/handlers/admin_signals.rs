@@ -0,0 +1,242 @@
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::extract_auth_context;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::lambda_invoke;
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Prefix lengths the rolling aggregation writes; other lengths are never read.
+const GEO_PRECISIONS: [usize; 3] = [4, 5, 6];
+const SUPPORTED_WINDOWS_DAYS: [i32; 3] = [7, 14, 30];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputeSignalsRequest {
+    pub geo_prefix: String,
+    pub crop_id: Option<String>,
+    pub windows: Option<Vec<i32>>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct RecomputeScope {
+    geo_prefix: String,
+    crop_id: Option<Uuid>,
+    windows: Vec<i32>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RecomputeInvocation<'a> {
+    geo_prefix: &'a str,
+    crop_id: Option<String>,
+    windows: &'a [i32],
+    correlation_id: &'a str,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecomputedSignal {
+    pub window_days: i32,
+    pub bucket_start: String,
+    pub computed_at: String,
+    pub listing_count: i64,
+    pub request_count: i64,
+    pub supply_quantity: f64,
+    pub demand_quantity: f64,
+    pub scarcity_score: f64,
+    pub abundance_score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SignalRecomputeResponse {
+    pub geo_prefix: String,
+    pub crop_id: Option<String>,
+    pub signals: Vec<RecomputedSignal>,
+}
+
+/// Recomputes one signal scope through the shared worker path and returns
+/// the refreshed values, so ops can fix a suspect signal without running the
+/// replay pipeline.
+pub async fn recompute_signals(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let payload: RecomputeSignalsRequest = parse_json_body(request)?;
+    let scope = normalize_scope(payload)?;
+
+    let function_name = config
+        .signal_recompute_function_name
+        .as_deref()
+        .ok_or_else(|| {
+            ApiError::unavailable(
+                "not_configured",
+                "SIGNAL_RECOMPUTE_FUNCTION_NAME is not configured",
+            )
+        })?;
+
+    let client = db::connect().await?;
+    if let Some(crop_id) = scope.crop_id {
+        let exists = client
+            .query_opt_timed(
+                "admin_signals::recompute_signals",
+                "select 1 from crops where id = $1",
+                &[&crop_id],
+            )
+            .await?
+            .is_some();
+        if !exists {
+            return Err(ApiError::not_found("crop_not_found", "Crop not found"));
+        }
+    }
+
+    let invocation = RecomputeInvocation {
+        geo_prefix: &scope.geo_prefix,
+        crop_id: scope.crop_id.map(|id| id.to_string()),
+        windows: &scope.windows,
+        correlation_id,
+    };
+    let response: SignalRecomputeResponse = lambda_invoke::invoke_json(function_name, &invocation)
+        .await
+        .map_err(|error| {
+            tracing::error!(
+                correlation_id = correlation_id,
+                geo_prefix = scope.geo_prefix.as_str(),
+                error = %error,
+                "Signal recompute invocation failed"
+            );
+            ApiError::unavailable("signal_recompute_failed", "Signal recompute failed")
+        })?;
+
+    audit::record_best_effort(
+        &*client,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::SIGNALS_RECOMPUTED,
+            target_type: "signal_scope",
+            target_id: format!(
+                "{}|{}",
+                response.geo_prefix,
+                response.crop_id.as_deref().unwrap_or_default()
+            ),
+            before: None,
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        geo_prefix = response.geo_prefix.as_str(),
+        windows = response.signals.len(),
+        "Admin recomputed signal scope"
+    );
+
+    json_response(200, &response)
+}
+
+fn normalize_scope(payload: RecomputeSignalsRequest) -> Result<RecomputeScope, ApiError> {
+    let geo_prefix = payload.geo_prefix.trim().to_lowercase();
+    if !GEO_PRECISIONS.contains(&geo_prefix.len()) || !geo_prefix.chars().all(is_geohash_char) {
+        return Err(ApiError::invalid_field(
+            "geoPrefix",
+            "invalid_geo_prefix",
+            "geoPrefix must be a geohash of 4, 5, or 6 characters",
+        ));
+    }
+
+    let crop_id = payload
+        .crop_id
+        .as_deref()
+        .map(|value| parse_uuid(value, "cropId"))
+        .transpose()?;
+
+    let mut windows = payload
+        .windows
+        .unwrap_or_else(|| SUPPORTED_WINDOWS_DAYS.to_vec());
+    windows.sort_unstable();
+    windows.dedup();
+    if windows.is_empty() {
+        return Err(ApiError::invalid_field(
+            "windows",
+            "required",
+            "windows must not be empty",
+        ));
+    }
+    if windows
+        .iter()
+        .any(|window| !SUPPORTED_WINDOWS_DAYS.contains(window))
+    {
+        return Err(ApiError::invalid_field(
+            "windows",
+            "invalid_window",
+            "windows must only contain 7, 14, or 30",
+        ));
+    }
+
+    Ok(RecomputeScope {
+        geo_prefix,
+        crop_id,
+        windows,
+    })
+}
+
+const fn is_geohash_char(c: char) -> bool {
+    matches!(c, '0'..='9' | 'b'..='h' | 'j' | 'k' | 'm' | 'n' | 'p'..='z')
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn payload(geo_prefix: &str, windows: Option<Vec<i32>>) -> RecomputeSignalsRequest {
+        RecomputeSignalsRequest {
+            geo_prefix: geo_prefix.to_string(),
+            crop_id: None,
+            windows,
+        }
+    }
+
+    #[test]
+    fn defaults_to_every_window() {
+        let scope = normalize_scope(payload(" 9Q8Y ", None)).unwrap();
+        assert_eq!(scope.geo_prefix, "9q8y");
+        assert_eq!(scope.crop_id, None);
+        assert_eq!(scope.windows, vec![7, 14, 30]);
+    }
+
+    #[test]
+    fn dedupes_and_sorts_windows() {
+        let scope = normalize_scope(payload("9q8yy", Some(vec![30, 7, 30]))).unwrap();
+        assert_eq!(scope.windows, vec![7, 30]);
+    }
+
+    #[test]
+    fn rejects_unwritten_prefix_lengths_and_bad_characters() {
+        assert!(normalize_scope(payload("9q8", None)).is_err());
+        assert!(normalize_scope(payload("9q8yyk8", None)).is_err());
+        assert!(normalize_scope(payload("9q8a", None)).is_err());
+    }
+
+    #[test]
+    fn rejects_empty_or_unsupported_windows() {
+        assert!(normalize_scope(payload("9q8y", Some(vec![]))).is_err());
+        assert!(normalize_scope(payload("9q8y", Some(vec![3]))).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_crop_id() {
+        let mut request = payload("9q8y", None);
+        request.crop_id = Some("nope".to_string());
+        assert!(normalize_scope(request).is_err());
+    }
+}
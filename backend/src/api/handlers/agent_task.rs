@@ -1,5 +1,7 @@
 use crate::auth::extract_auth_context;
-use crate::db;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body};
 use crate::middleware::entitlements;
 use lambda_http::{Body, Request, Response};
 use serde::{Deserialize, Serialize};
@@ -42,7 +44,7 @@ pub struct AgentTaskListResponse {
 pub async fn list_agent_tasks(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request)?;
     let client = db::connect().await?;
     if let Err(feature_locked) = require_premium_automation(&client, user_id).await {
@@ -50,7 +52,7 @@ pub async fn list_agent_tasks(
     }
 
     let rows = client
-        .query(
+        .query_timed("agent_task::list_agent_tasks", 
             "
             select id, name, schedule_cron, instruction, status, last_run_at, next_run_at, created_at
               from agent_tasks
@@ -59,8 +61,7 @@ pub async fn list_agent_tasks(
             ",
             &[&user_id],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     tracing::info!(correlation_id = correlation_id, user_id = %user_id, count = rows.len(), "Listed agent tasks");
 
@@ -75,7 +76,7 @@ pub async fn list_agent_tasks(
 pub async fn create_agent_task(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request)?;
     let payload: CreateAgentTaskRequest = parse_json_body(request)?;
     let client = db::connect().await?;
@@ -84,15 +85,22 @@ pub async fn create_agent_task(
     }
 
     if payload.name.trim().is_empty() || payload.instruction.trim().is_empty() {
-        return error_response(400, "name and instruction are required");
+        return Err(ApiError::bad_request(
+            "required",
+            "name and instruction are required",
+        ));
     }
 
     if payload.schedule_cron.split_whitespace().count() < 5 {
-        return error_response(400, "scheduleCron must look like a cron expression");
+        return Err(ApiError::invalid_field(
+            "scheduleCron",
+            "invalid_cron",
+            "scheduleCron must look like a cron expression",
+        ));
     }
 
     let row = client
-        .query_one(
+        .query_one_timed("agent_task::create_agent_task", 
             "
             insert into agent_tasks (user_id, name, schedule_cron, instruction, status)
             values ($1, $2, $3, $4, 'active')
@@ -105,8 +113,7 @@ pub async fn create_agent_task(
                 &payload.instruction.trim(),
             ],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     tracing::info!(
         correlation_id = correlation_id,
@@ -122,10 +129,11 @@ pub async fn update_agent_task_status(
     request: &Request,
     correlation_id: &str,
     task_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request)?;
-    let task_uuid = Uuid::parse_str(task_id)
-        .map_err(|_| lambda_http::Error::from("taskId must be a valid UUID"))?;
+    let task_uuid = Uuid::parse_str(task_id).map_err(|_| {
+        ApiError::invalid_field("taskId", "invalid_uuid", "taskId must be a valid UUID")
+    })?;
     let payload: UpdateAgentTaskStatusRequest = parse_json_body(request)?;
     let client = db::connect().await?;
     if let Err(feature_locked) = require_premium_automation(&client, user_id).await {
@@ -133,11 +141,15 @@ pub async fn update_agent_task_status(
     }
 
     if !matches!(payload.status.as_str(), "active" | "paused") {
-        return error_response(400, "status must be active or paused");
+        return Err(ApiError::invalid_field(
+            "status",
+            "invalid_enum",
+            "status must be active or paused",
+        ));
     }
 
     let row = client
-        .query_opt(
+        .query_opt_timed("agent_task::update_agent_task_status", 
             "
             update agent_tasks
                set status = $3,
@@ -147,11 +159,13 @@ pub async fn update_agent_task_status(
             ",
             &[&task_uuid, &user_id, &payload.status],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     let Some(row) = row else {
-        return error_response(404, "Agent task not found");
+        return Err(ApiError::not_found(
+            "agent_task_not_found",
+            "Agent task not found",
+        ));
     };
 
     tracing::info!(correlation_id = correlation_id, user_id = %user_id, task_id = task_id, status = payload.status, "Updated agent task status");
@@ -185,43 +199,7 @@ async fn require_premium_automation(
     entitlements::require_entitlement(client, user_id, "agent.tasks.automation").await
 }
 
-fn extract_user_id(request: &Request) -> Result<Uuid, lambda_http::Error> {
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
     let auth = extract_auth_context(request)?;
-    Uuid::parse_str(&auth.user_id).map_err(|_| lambda_http::Error::from("Invalid user ID format"))
-}
-
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(status, &serde_json::json!({ "error": message }))
-}
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
 }
@@ -1,6 +1,8 @@
-use crate::ai_model_config;
 use crate::auth::extract_auth_context;
-use crate::db;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body};
 use crate::middleware::{ai_guardrails, entitlements};
 use crate::structured_json;
 use lambda_http::{Body, Request, Response};
@@ -40,20 +42,29 @@ pub struct WeeklyPlanResponse {
 pub async fn generate_weekly_plan(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
     let auth = extract_auth_context(request)?;
     let user_id = Uuid::parse_str(&auth.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
 
     let payload: WeeklyPlanRequest = parse_json_body(request)?;
     let window_days = payload.window_days.unwrap_or(DEFAULT_WINDOW_DAYS);
     if ![7, 14, 30].contains(&window_days) {
-        return error_response(400, "windowDays must be one of: 7, 14, 30");
+        return Err(ApiError::invalid_field(
+            "windowDays",
+            "invalid_window_days",
+            "windowDays must be one of: 7, 14, 30",
+        ));
     }
 
     let geo_key = payload.geo_key.trim().to_ascii_lowercase();
     if geo_key.len() < 4 {
-        return error_response(400, "geoKey must be at least 4 characters");
+        return Err(ApiError::invalid_field(
+            "geoKey",
+            "invalid_geo_key",
+            "geoKey must be at least 4 characters",
+        ));
     }
     let geo_prefix = geo_key[..4].to_string();
 
@@ -66,7 +77,8 @@ pub async fn generate_weekly_plan(
     }
 
     let rows = client
-        .query(
+        .query_timed(
+            "ai_copilot::generate_weekly_plan",
             "
             select
               geo_boundary_key,
@@ -80,12 +92,11 @@ pub async fn generate_weekly_plan(
             ",
             &[&geo_prefix, &window_days],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     let recommendations = build_recommendations(&rows);
 
-    let model_cfg = ai_model_config::load_model_config();
+    let model_cfg = &config.ai.model;
     let model_id = model_cfg.model_id.clone();
     let model_version = format!("{}-{}", model_cfg.response_mode, model_cfg.schema_version);
 
@@ -98,13 +109,13 @@ pub async fn generate_weekly_plan(
     .await?;
 
     if !guardrails.allowed {
-        return error_response(
-            429,
+        return Err(ApiError::too_many_requests(
+            "ai_guardrail_blocked",
             guardrails
                 .reason
                 .as_deref()
                 .unwrap_or("ai_guardrail_blocked"),
-        );
+        ));
     }
 
     tracing::info!(
@@ -216,42 +227,6 @@ fn build_recommendations(rows: &[Row]) -> Vec<WeeklyPlanRecommendation> {
     ]
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(status, &serde_json::json!({ "error": message }))
-}
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
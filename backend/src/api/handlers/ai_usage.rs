@@ -0,0 +1,152 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::Row;
+
+const DEFAULT_DAYS: i32 = 7;
+const MAX_DAYS: i32 = 90;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiUsageRollupRow {
+    pub day: String,
+    pub feature_key: String,
+    pub provider: String,
+    pub model_id: String,
+    pub generation_count: i64,
+    pub failure_count: i64,
+    pub input_tokens: i64,
+    pub output_tokens: i64,
+    pub avg_latency_ms: f64,
+    pub p95_latency_ms: f64,
+    pub estimated_cost_usd: f64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AiUsageRollupResponse {
+    pub days: i32,
+    pub generation_count: i64,
+    pub failure_count: i64,
+    pub estimated_cost_usd: f64,
+    /// One row per UTC day, feature, provider, and model; newest day first.
+    pub items: Vec<AiUsageRollupRow>,
+}
+
+/// Daily AI generation totals for spotting runaway generation, e.g. when the
+/// summary cache stops hitting.
+pub async fn get_ai_usage_rollup(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let days = parse_days(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "ai_usage::get_ai_usage_rollup",
+            "
+            select
+              to_char(date_trunc('day', created_at at time zone 'utc'), 'YYYY-MM-DD') as day,
+              feature_key,
+              provider,
+              model_id,
+              count(*)::bigint as generation_count,
+              count(*) filter (where status <> 'success')::bigint as failure_count,
+              coalesce(sum(input_tokens), 0)::bigint as input_tokens,
+              coalesce(sum(output_tokens), 0)::bigint as output_tokens,
+              avg(latency_ms)::float8 as avg_latency_ms,
+              percentile_cont(0.95) within group (order by latency_ms)::float8 as p95_latency_ms,
+              coalesce(sum(estimated_cost_usd), 0)::float8 as estimated_cost_usd
+            from ai_usage
+            where created_at >= now() - make_interval(days => $1)
+            group by 1, feature_key, provider, model_id
+            order by day desc, estimated_cost_usd desc, generation_count desc
+            ",
+            &[&days],
+        )
+        .await?;
+
+    let items = rows.iter().map(row_to_rollup).collect::<Vec<_>>();
+    let response = AiUsageRollupResponse {
+        days,
+        generation_count: items.iter().map(|item| item.generation_count).sum(),
+        failure_count: items.iter().map(|item| item.failure_count).sum(),
+        estimated_cost_usd: items.iter().map(|item| item.estimated_cost_usd).sum(),
+        items,
+    };
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        days,
+        generation_count = response.generation_count,
+        "Returned AI usage rollup"
+    );
+
+    json_response(200, &response)
+}
+
+fn parse_days(query: Option<&str>) -> Result<i32, ApiError> {
+    let Some(value) = query.and_then(|raw| {
+        raw.split('&')
+            .filter_map(|pair| pair.split_once('='))
+            .find_map(|(key, value)| (key == "days" && !value.is_empty()).then_some(value))
+    }) else {
+        return Ok(DEFAULT_DAYS);
+    };
+
+    value
+        .parse::<i32>()
+        .ok()
+        .filter(|days| (1..=MAX_DAYS).contains(days))
+        .ok_or_else(|| {
+            ApiError::invalid_field(
+                "days",
+                "invalid_days",
+                format!("Invalid days. Must be between 1 and {MAX_DAYS}"),
+            )
+        })
+}
+
+fn row_to_rollup(row: &Row) -> AiUsageRollupRow {
+    AiUsageRollupRow {
+        day: row.get("day"),
+        feature_key: row.get("feature_key"),
+        provider: row.get("provider"),
+        model_id: row.get("model_id"),
+        generation_count: row.get("generation_count"),
+        failure_count: row.get("failure_count"),
+        input_tokens: row.get("input_tokens"),
+        output_tokens: row.get("output_tokens"),
+        avg_latency_ms: row
+            .get::<_, Option<f64>>("avg_latency_ms")
+            .unwrap_or_default(),
+        p95_latency_ms: row
+            .get::<_, Option<f64>>("p95_latency_ms")
+            .unwrap_or_default(),
+        estimated_cost_usd: row.get("estimated_cost_usd"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_days_defaults_and_bounds() {
+        assert_eq!(parse_days(None).unwrap(), DEFAULT_DAYS);
+        assert_eq!(parse_days(Some("days=")).unwrap(), DEFAULT_DAYS);
+        assert_eq!(parse_days(Some("foo=1&days=30")).unwrap(), 30);
+
+        for raw in ["days=0", "days=91", "days=week"] {
+            let error = parse_days(Some(raw)).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_days", "{raw}");
+        }
+    }
+}
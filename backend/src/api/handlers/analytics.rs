@@ -1,5 +1,7 @@
 use crate::auth::extract_auth_context;
-use crate::db;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body};
 use crate::middleware::entitlements;
 use lambda_http::{Body, Request, Response};
 use serde::{Deserialize, Serialize};
@@ -24,10 +26,10 @@ pub struct PremiumKpiResponse {
 pub async fn track_premium_event(
     request: &Request,
     _correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let auth = extract_auth_context(request)?;
     let user_id = Uuid::parse_str(&auth.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
 
     let client = db::connect().await?;
     if let Err(feature_locked) =
@@ -42,19 +44,23 @@ pub async fn track_premium_event(
         payload.event_name.as_str(),
         "paywall_view" | "checkout_start" | "subscribe" | "cancel"
     ) {
-        return error_response(400, "Unsupported analytics event");
+        return Err(ApiError::invalid_field(
+            "eventName",
+            "invalid_enum",
+            "Unsupported analytics event",
+        ));
     }
 
     client
-        .execute(
+        .execute_timed(
+            "analytics::track_premium_event",
             "
             insert into premium_analytics_events (user_id, event_name, event_source, metadata)
             values ($1, $2, 'frontend', $3)
             ",
             &[&user_id, &payload.event_name, &payload.metadata],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     json_response(202, &serde_json::json!({"accepted": true}))
 }
@@ -62,10 +68,10 @@ pub async fn track_premium_event(
 pub async fn get_premium_kpis(
     request: &Request,
     _correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let auth = extract_auth_context(request)?;
     let user_id = Uuid::parse_str(&auth.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
 
     let window_days = parse_window_days(request).unwrap_or(7);
     let client = db::connect().await?;
@@ -77,7 +83,8 @@ pub async fn get_premium_kpis(
     }
 
     let rows = client
-        .query(
+        .query_timed(
+            "analytics::get_premium_kpis",
             "
             select event_name, count(*)::bigint as total
               from premium_analytics_events
@@ -86,8 +93,7 @@ pub async fn get_premium_kpis(
             ",
             &[&window_days],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     let mut funnel: HashMap<String, i64> = HashMap::new();
     for row in rows {
@@ -119,17 +125,17 @@ pub async fn log_backend_event(
     user_id: Option<Uuid>,
     event_name: &str,
     metadata: Option<serde_json::Value>,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     client
-        .execute(
+        .execute_timed(
+            "analytics::log_backend_event",
             "
             insert into premium_analytics_events (user_id, event_name, event_source, metadata)
             values ($1, $2, 'backend', $3)
             ",
             &[&user_id, &event_name, &metadata],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     Ok(())
 }
@@ -149,42 +155,6 @@ fn parse_window_days(request: &Request) -> Option<i32> {
     })
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(status, &serde_json::json!({ "error": message }))
-}
-
 fn count_to_f64(value: i64) -> f64 {
     i32::try_from(value).map_or_else(|_| f64::from(i32::MAX), f64::from)
 }
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
@@ -0,0 +1,513 @@
+use crate::auth::{extract_auth_context, AuthContext, UserType};
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::location;
+use crate::models::feed::FeedAnnouncement;
+use chrono::{DateTime, Duration, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Deserialize;
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const ALLOWED_CATEGORIES: [&str; 4] = ["weather", "swap", "safety", "general"];
+const MAX_TITLE_CHARS: usize = 120;
+const MAX_BODY_CHARS: usize = 1000;
+const MAX_EXPIRY_DAYS: i64 = 30;
+const FEED_ANNOUNCEMENT_LIMIT: i64 = 20;
+
+/// Growers post to their own neighborhood: a geohash of at least this
+/// precision inside the area of their grower profile. Admins may post to
+/// any area, however wide.
+const GROWER_SCOPE_PRECISION: usize = 4;
+
+/// Columns read by [`row_to_announcement`].
+macro_rules! announcement_columns {
+    () => {
+        "a.id, a.author_id, u.display_name as author_display_name, a.category, a.title,
+         a.body, a.geo_key, a.expires_at, a.created_at"
+    };
+}
+
+const FIND_ANNOUNCEMENT: &str = concat!(
+    "select ",
+    announcement_columns!(),
+    "
+    from announcements a
+    left join users u on u.id = a.author_id
+    where a.id = $1
+      and a.deleted_at is null"
+);
+
+/// `$1` lists every area enclosing the viewer; `$2` matches areas inside the
+/// feed's geo prefix.
+const FEED_ANNOUNCEMENTS: &str = concat!(
+    "select ",
+    announcement_columns!(),
+    "
+    from announcements a
+    left join users u on u.id = a.author_id
+    where a.deleted_at is null
+      and a.expires_at > $3
+      and (a.geo_key = any($1) or a.geo_key like $2)
+    order by a.created_at desc, a.id desc
+    limit $4"
+);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAnnouncementRequest {
+    pub title: String,
+    pub body: String,
+    pub category: Option<String>,
+    pub geo_key: Option<String>,
+    pub expires_at: String,
+}
+
+#[derive(Debug)]
+struct NormalizedAnnouncement {
+    title: String,
+    body: String,
+    category: String,
+    geo_key: Option<String>,
+    expires_at: DateTime<Utc>,
+}
+
+/// Posts an announcement. Verified growers post to their own neighborhood
+/// (defaulting to it when `geoKey` is omitted); admins must name the area.
+pub async fn create_announcement(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let payload: CreateAnnouncementRequest = parse_json_body(request)?;
+    let normalized = normalize_payload(&payload, Utc::now())?;
+
+    let client = db::connect().await?;
+    let author = client
+        .query_opt_timed(
+            "announcement::create_announcement",
+            "
+            select u.is_verified, gp.geo_key as home_geo_key
+            from users u
+            left join grower_profiles gp on gp.user_id = u.id
+            where u.id = $1
+              and u.deleted_at is null
+            ",
+            &[&user_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("user_not_found", "User not found"))?;
+
+    let geo_key = resolve_scope(
+        &auth,
+        author.get("is_verified"),
+        author.get::<_, Option<String>>("home_geo_key").as_deref(),
+        normalized.geo_key.as_deref(),
+    )?;
+
+    let row = client
+        .query_one_timed(
+            "announcement::create_announcement",
+            "
+            insert into announcements (author_id, geo_key, category, title, body, expires_at)
+            values ($1, $2, $3, $4, $5, $6)
+            returning id
+            ",
+            &[
+                &user_id,
+                &geo_key,
+                &normalized.category,
+                &normalized.title,
+                &normalized.body,
+                &normalized.expires_at,
+            ],
+        )
+        .await?;
+    let announcement_id: Uuid = row.get("id");
+
+    let response = load_announcement(&client, announcement_id)
+        .await?
+        .ok_or_else(announcement_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        announcement_id = %announcement_id,
+        geo_key = response.geo_key.as_str(),
+        category = response.category.as_str(),
+        "Posted announcement"
+    );
+
+    json_response(201, &response)
+}
+
+/// Takes an announcement down before it expires. Authors may remove their
+/// own; admins may remove any.
+pub async fn delete_announcement(
+    request: &Request,
+    correlation_id: &str,
+    announcement_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let announcement_id = parse_uuid(announcement_id, "announcementId")?;
+
+    let client = db::connect().await?;
+    let author_id: Uuid = client
+        .query_opt_timed(
+            "announcement::delete_announcement",
+            "select author_id from announcements where id = $1 and deleted_at is null",
+            &[&announcement_id],
+        )
+        .await?
+        .ok_or_else(announcement_not_found)?
+        .get("author_id");
+
+    if author_id != user_id && !is_admin(&auth) {
+        return Err(ApiError::forbidden(
+            "announcement_author_required",
+            "Forbidden: Only the author or an admin can remove this announcement",
+        ));
+    }
+
+    client
+        .execute_timed(
+            "announcement::delete_announcement",
+            "update announcements set deleted_at = now() where id = $1 and deleted_at is null",
+            &[&announcement_id],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        announcement_id = %announcement_id,
+        removed_by_admin = author_id != user_id,
+        "Removed announcement"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// Unexpired announcements that cover `geo_key`: those posted for any
+/// enclosing area, plus those posted anywhere inside `geo_prefix`. Newest
+/// first, capped for the feed.
+pub async fn load_for_feed(
+    client: &Client,
+    geo_key: &str,
+    geo_prefix: &str,
+    as_of: DateTime<Utc>,
+) -> Result<Vec<FeedAnnouncement>, ApiError> {
+    let enclosing = enclosing_scopes(geo_key);
+    let inside_pattern = format!("{geo_prefix}%");
+    let rows = client
+        .query_timed(
+            "announcement::load_for_feed",
+            FEED_ANNOUNCEMENTS,
+            &[
+                &enclosing,
+                &inside_pattern,
+                &as_of,
+                &FEED_ANNOUNCEMENT_LIMIT,
+            ],
+        )
+        .await?;
+
+    Ok(rows.iter().map(row_to_announcement).collect())
+}
+
+async fn load_announcement(
+    client: &Client,
+    announcement_id: Uuid,
+) -> Result<Option<FeedAnnouncement>, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "announcement::load_announcement",
+            FIND_ANNOUNCEMENT,
+            &[&announcement_id],
+        )
+        .await?;
+    Ok(row.as_ref().map(row_to_announcement))
+}
+
+/// Decides which area an announcement is posted to, or why the caller may
+/// not post.
+fn resolve_scope(
+    auth: &AuthContext,
+    is_verified: bool,
+    home_geo_key: Option<&str>,
+    requested: Option<&str>,
+) -> Result<String, ApiError> {
+    if is_admin(auth) {
+        return requested.map(str::to_string).ok_or_else(|| {
+            ApiError::invalid_field(
+                "geoKey",
+                "invalid_geo_key",
+                "geoKey is required for admin announcements",
+            )
+        });
+    }
+
+    if auth.user_type != Some(UserType::Grower) || !is_verified {
+        return Err(ApiError::forbidden(
+            "verified_grower_required",
+            "Forbidden: Only verified growers and admins can post announcements",
+        ));
+    }
+
+    let neighborhood: String = home_geo_key
+        .ok_or_else(|| {
+            ApiError::forbidden(
+                "grower_profile_required",
+                "Forbidden: Set up a grower profile before posting announcements",
+            )
+        })?
+        .chars()
+        .take(GROWER_SCOPE_PRECISION)
+        .collect();
+
+    match requested {
+        None => Ok(neighborhood),
+        Some(geo_key)
+            if geo_key.len() >= GROWER_SCOPE_PRECISION && geo_key.starts_with(&neighborhood) =>
+        {
+            Ok(geo_key.to_string())
+        }
+        Some(_) => Err(ApiError::forbidden(
+            "announcement_outside_neighborhood",
+            "Forbidden: Growers can only post announcements within their own neighborhood",
+        )),
+    }
+}
+
+fn normalize_payload(
+    payload: &CreateAnnouncementRequest,
+    now: DateTime<Utc>,
+) -> Result<NormalizedAnnouncement, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let title = payload.title.trim();
+    if title.is_empty() {
+        errors.add("title", "required", "title is required");
+    } else if title.chars().count() > MAX_TITLE_CHARS {
+        errors.add(
+            "title",
+            "too_long",
+            format!("title must be at most {MAX_TITLE_CHARS} characters"),
+        );
+    }
+
+    let body = payload.body.trim();
+    if body.is_empty() {
+        errors.add("body", "required", "body is required");
+    } else if body.chars().count() > MAX_BODY_CHARS {
+        errors.add(
+            "body",
+            "too_long",
+            format!("body must be at most {MAX_BODY_CHARS} characters"),
+        );
+    }
+
+    let category = payload.category.as_deref().unwrap_or("general");
+    if !ALLOWED_CATEGORIES.contains(&category) {
+        errors.add(
+            "category",
+            "invalid_enum",
+            format!(
+                "Invalid category '{category}'. Allowed values: {}",
+                ALLOWED_CATEGORIES.join(", ")
+            ),
+        );
+    }
+
+    let geo_key = payload
+        .geo_key
+        .as_deref()
+        .map(|value| value.trim().to_ascii_lowercase());
+    if geo_key
+        .as_deref()
+        .is_some_and(|value| !location::is_valid_geo_key(value))
+    {
+        errors.add(
+            "geoKey",
+            "invalid_geo_key",
+            "geoKey must be a valid geohash (1-12 chars, base32)",
+        );
+    }
+
+    let expires_at = if let Ok(parsed) = DateTime::parse_from_rfc3339(&payload.expires_at) {
+        let expires_at = parsed.with_timezone(&Utc);
+        if expires_at <= now || expires_at > now + Duration::days(MAX_EXPIRY_DAYS) {
+            errors.add(
+                "expiresAt",
+                "invalid_expiry",
+                format!("expiresAt must be in the future and at most {MAX_EXPIRY_DAYS} days away"),
+            );
+        }
+        Some(expires_at)
+    } else {
+        errors.add(
+            "expiresAt",
+            "invalid_timestamp",
+            "expiresAt must be a valid RFC3339 timestamp",
+        );
+        None
+    };
+
+    errors.into_result()?;
+    let Some(expires_at) = expires_at else {
+        return Err(ApiError::internal(
+            "announcement validation passed without expiresAt",
+        ));
+    };
+
+    Ok(NormalizedAnnouncement {
+        title: title.to_string(),
+        body: body.to_string(),
+        category: category.to_string(),
+        geo_key,
+        expires_at,
+    })
+}
+
+/// Every geohash that contains `geo_key`, including itself.
+fn enclosing_scopes(geo_key: &str) -> Vec<String> {
+    (1..=geo_key.len())
+        .map(|len| geo_key[..len].to_string())
+        .collect()
+}
+
+const fn is_admin(auth: &AuthContext) -> bool {
+    auth.is_admin && auth.api_key.is_none()
+}
+
+fn row_to_announcement(row: &Row) -> FeedAnnouncement {
+    FeedAnnouncement {
+        id: row.get::<_, Uuid>("id").to_string(),
+        author_id: row.get::<_, Uuid>("author_id").to_string(),
+        author_display_name: row.get("author_display_name"),
+        category: row.get("category"),
+        title: row.get("title"),
+        body: row.get("body"),
+        geo_key: row.get("geo_key"),
+        expires_at: row.get::<_, DateTime<Utc>>("expires_at").to_rfc3339(),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    }
+}
+
+fn announcement_not_found() -> ApiError {
+    ApiError::not_found("announcement_not_found", "Announcement not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn auth(user_type: Option<UserType>, is_admin: bool) -> AuthContext {
+        AuthContext {
+            user_id: Uuid::nil().to_string(),
+            user_type,
+            tier: "free".to_string(),
+            email: None,
+            is_admin,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
+        }
+    }
+
+    fn payload(expires_at: &str) -> CreateAnnouncementRequest {
+        CreateAnnouncementRequest {
+            title: " Frost tonight ".to_string(),
+            body: "Cover tomatoes before sundown.".to_string(),
+            category: Some("weather".to_string()),
+            geo_key: Some("9Q8Y".to_string()),
+            expires_at: expires_at.to_string(),
+        }
+    }
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-10-16T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn normalize_payload_trims_and_lowercases() {
+        let normalized = normalize_payload(&payload("2026-10-17T12:00:00Z"), now()).unwrap();
+        assert_eq!(normalized.title, "Frost tonight");
+        assert_eq!(normalized.category, "weather");
+        assert_eq!(normalized.geo_key.as_deref(), Some("9q8y"));
+    }
+
+    #[test]
+    fn normalize_payload_bounds_expiry() {
+        for expires_at in ["2026-10-16T11:00:00Z", "2026-11-30T00:00:00Z"] {
+            let error = normalize_payload(&payload(expires_at), now()).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_expiry");
+        }
+        let error = normalize_payload(&payload("tomorrow"), now()).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_timestamp");
+    }
+
+    #[test]
+    fn normalize_payload_rejects_unknown_category() {
+        let mut request = payload("2026-10-17T12:00:00Z");
+        request.category = Some("sale".to_string());
+        let error = normalize_payload(&request, now()).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+    }
+
+    #[test]
+    fn resolve_scope_requires_verified_grower_or_admin() {
+        let grower = auth(Some(UserType::Grower), false);
+        let gatherer = auth(Some(UserType::Gatherer), false);
+
+        let error = resolve_scope(&grower, false, Some("9q8yyk"), None).unwrap_err();
+        assert_eq!(error.error_code(), "verified_grower_required");
+        let error = resolve_scope(&gatherer, true, Some("9q8yyk"), None).unwrap_err();
+        assert_eq!(error.error_code(), "verified_grower_required");
+
+        let admin = auth(None, true);
+        assert_eq!(
+            resolve_scope(&admin, false, None, Some("9q")).unwrap(),
+            "9q"
+        );
+        assert!(resolve_scope(&admin, false, None, None).is_err());
+    }
+
+    #[test]
+    fn resolve_scope_keeps_growers_in_their_neighborhood() {
+        let grower = auth(Some(UserType::Grower), false);
+
+        assert_eq!(
+            resolve_scope(&grower, true, Some("9q8yyk"), None).unwrap(),
+            "9q8y"
+        );
+        assert_eq!(
+            resolve_scope(&grower, true, Some("9q8yyk"), Some("9q8yy")).unwrap(),
+            "9q8yy"
+        );
+        for requested in ["9q8", "9q8z"] {
+            let error = resolve_scope(&grower, true, Some("9q8yyk"), Some(requested)).unwrap_err();
+            assert_eq!(error.error_code(), "announcement_outside_neighborhood");
+        }
+
+        let error = resolve_scope(&grower, true, None, None).unwrap_err();
+        assert_eq!(error.error_code(), "grower_profile_required");
+    }
+
+    #[test]
+    fn enclosing_scopes_lists_every_prefix() {
+        assert_eq!(enclosing_scopes("9q8y"), vec!["9", "9q", "9q8", "9q8y"]);
+    }
+}
@@ -0,0 +1,404 @@
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body};
+use crate::router::ALLOWED_API_KEY_SCOPES;
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+const KEY_PREFIX: &str = "cgk_";
+const DISPLAY_PREFIX_LEN: usize = 12;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateApiKeyRequest {
+    pub organization_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub expires_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub organization_id: String,
+    pub name: String,
+    pub key_prefix: String,
+    pub scopes: Vec<String>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub expires_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatedApiKeyResponse {
+    #[serde(flatten)]
+    pub key: ApiKeyResponse,
+    /// Plaintext key. Only returned once; the database stores its hash.
+    pub api_key: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyListResponse {
+    pub items: Vec<ApiKeyResponse>,
+}
+
+pub async fn create_api_key(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let admin_id = extract_admin_id(request)?;
+    let payload: CreateApiKeyRequest = parse_json_body(request)?;
+
+    let organization_id =
+        Uuid::parse_str(&payload.organization_id).map_err(|_| invalid_organization_id())?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::invalid_field(
+            "name",
+            "required",
+            "name is required",
+        ));
+    }
+    let scopes = validate_scopes(&payload.scopes)?;
+    let expires_at = payload
+        .expires_at
+        .as_deref()
+        .map(|raw| {
+            chrono::DateTime::parse_from_rfc3339(raw)
+                .map(|value| value.with_timezone(&chrono::Utc))
+                .map_err(|_| {
+                    ApiError::invalid_field(
+                        "expiresAt",
+                        "invalid_timestamp",
+                        "expiresAt must be an RFC 3339 timestamp",
+                    )
+                })
+        })
+        .transpose()?;
+
+    let api_key = generate_api_key();
+    let client = db::connect().await?;
+
+    let row = client
+        .query_opt_timed("api_key::create_api_key", 
+            "
+            insert into api_keys (organization_id, name, key_prefix, key_hash, scopes, created_by, expires_at)
+            select o.id, $2, $3, $4, $5, $6, $7
+              from organizations o
+             where o.id = $1
+               and o.deleted_at is null
+            returning id, organization_id, name, key_prefix, scopes, created_at,
+                      last_used_at, expires_at, revoked_at
+            ",
+            &[
+                &organization_id,
+                &name,
+                &display_prefix(&api_key),
+                &hash_api_key(&api_key),
+                &scopes,
+                &admin_id,
+                &expires_at,
+            ],
+        )
+        .await?;
+
+    let Some(row) = row else {
+        return Err(ApiError::not_found(
+            "organization_not_found",
+            "Organization not found",
+        ));
+    };
+
+    let key = row_to_response(&row);
+    audit::record_best_effort(
+        &*client,
+        &AuditEntry {
+            actor: admin_actor(admin_id),
+            action: audit::API_KEY_CREATED,
+            target_type: "api_key",
+            target_id: key.id.clone(),
+            before: None,
+            after: serde_json::to_value(&key).ok(),
+            correlation_id,
+        },
+    )
+    .await;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = %admin_id,
+        organization_id = key.organization_id.as_str(),
+        api_key_id = key.id.as_str(),
+        "Issued partner API key"
+    );
+
+    json_response(201, &CreatedApiKeyResponse { key, api_key })
+}
+
+pub async fn list_api_keys(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let admin_id = extract_admin_id(request)?;
+    let organization_id = parse_organization_filter(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "api_key::list_api_keys",
+            "
+            select id, organization_id, name, key_prefix, scopes, created_at,
+                   last_used_at, expires_at, revoked_at
+              from api_keys
+             where ($1::uuid is null or organization_id = $1)
+             order by created_at desc
+            ",
+            &[&organization_id],
+        )
+        .await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = %admin_id,
+        api_key_count = rows.len(),
+        "Listed partner API keys"
+    );
+
+    json_response(
+        200,
+        &ApiKeyListResponse {
+            items: rows.iter().map(row_to_response).collect(),
+        },
+    )
+}
+
+pub async fn revoke_api_key(
+    request: &Request,
+    correlation_id: &str,
+    key_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let admin_id = extract_admin_id(request)?;
+    let key_uuid = Uuid::parse_str(key_id).map_err(|_| {
+        ApiError::invalid_field("apiKeyId", "invalid_uuid", "apiKeyId must be a valid UUID")
+    })?;
+
+    let client = db::connect().await?;
+    let row = client
+        .query_opt_timed(
+            "api_key::revoke_api_key",
+            "
+            with previous as (
+                select revoked_at from api_keys where id = $1
+            )
+            update api_keys
+               set revoked_at = coalesce(revoked_at, now())
+             where id = $1
+            returning id, organization_id, name, key_prefix, scopes, created_at,
+                      last_used_at, expires_at, revoked_at,
+                      (select revoked_at from previous) as previous_revoked_at
+            ",
+            &[&key_uuid],
+        )
+        .await?;
+
+    let Some(row) = row else {
+        return Err(ApiError::not_found(
+            "api_key_not_found",
+            "API key not found",
+        ));
+    };
+
+    let key = row_to_response(&row);
+    let previous_revoked_at = row
+        .get::<_, Option<chrono::DateTime<chrono::Utc>>>("previous_revoked_at")
+        .map(|value| value.to_rfc3339());
+    if previous_revoked_at.is_none() {
+        audit::record_best_effort(
+            &*client,
+            &AuditEntry {
+                actor: admin_actor(admin_id),
+                action: audit::API_KEY_REVOKED,
+                target_type: "api_key",
+                target_id: key.id.clone(),
+                before: Some(serde_json::json!({ "revokedAt": null })),
+                after: Some(serde_json::json!({ "revokedAt": key.revoked_at })),
+                correlation_id,
+            },
+        )
+        .await;
+    }
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = %admin_id,
+        api_key_id = key_id,
+        "Revoked partner API key"
+    );
+
+    json_response(200, &key)
+}
+
+fn parse_organization_filter(query: Option<&str>) -> Result<Option<Uuid>, ApiError> {
+    let Some(raw_query) = query else {
+        return Ok(None);
+    };
+
+    for pair in raw_query.split('&') {
+        if let Some(("organizationId", value)) = pair.split_once('=') {
+            if !value.is_empty() {
+                return Uuid::parse_str(value)
+                    .map(Some)
+                    .map_err(|_| invalid_organization_id());
+            }
+        }
+    }
+
+    Ok(None)
+}
+
+fn validate_scopes(scopes: &[String]) -> Result<Vec<String>, ApiError> {
+    if scopes.is_empty() {
+        return Err(ApiError::invalid_field(
+            "scopes",
+            "required",
+            "scopes must not be empty",
+        ));
+    }
+
+    let mut normalized = Vec::with_capacity(scopes.len());
+    for scope in scopes {
+        let scope = scope.trim();
+        if !ALLOWED_API_KEY_SCOPES.contains(&scope) {
+            return Err(ApiError::invalid_field(
+                "scopes",
+                "invalid_scope",
+                format!(
+                    "scopes must be drawn from {}",
+                    ALLOWED_API_KEY_SCOPES.join("|")
+                ),
+            ));
+        }
+        if !normalized.iter().any(|existing| existing == scope) {
+            normalized.push(scope.to_string());
+        }
+    }
+
+    Ok(normalized)
+}
+
+fn invalid_organization_id() -> ApiError {
+    ApiError::invalid_field(
+        "organizationId",
+        "invalid_uuid",
+        "organizationId must be a valid UUID",
+    )
+}
+
+fn generate_api_key() -> String {
+    format!(
+        "{KEY_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+fn display_prefix(api_key: &str) -> String {
+    api_key.chars().take(DISPLAY_PREFIX_LEN).collect()
+}
+
+fn hash_api_key(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+fn row_to_response(row: &Row) -> ApiKeyResponse {
+    let optional_timestamp = |column: &str| {
+        row.get::<_, Option<chrono::DateTime<chrono::Utc>>>(column)
+            .map(|value| value.to_rfc3339())
+    };
+
+    ApiKeyResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        organization_id: row.get::<_, Uuid>("organization_id").to_string(),
+        name: row.get("name"),
+        key_prefix: row.get("key_prefix"),
+        scopes: row.get("scopes"),
+        created_at: row
+            .get::<_, chrono::DateTime<chrono::Utc>>("created_at")
+            .to_rfc3339(),
+        last_used_at: optional_timestamp("last_used_at"),
+        expires_at: optional_timestamp("expires_at"),
+        revoked_at: optional_timestamp("revoked_at"),
+    }
+}
+
+const fn admin_actor(admin_id: Uuid) -> Actor {
+    Actor {
+        user_id: Some(admin_id),
+        api_key_id: None,
+    }
+}
+
+fn extract_admin_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_keys_are_prefixed_and_unique() {
+        let first = generate_api_key();
+        let second = generate_api_key();
+        assert!(first.starts_with(KEY_PREFIX));
+        assert_eq!(first.len(), KEY_PREFIX.len() + 64);
+        assert_ne!(first, second);
+        assert_eq!(display_prefix(&first).len(), DISPLAY_PREFIX_LEN);
+    }
+
+    #[test]
+    fn validate_scopes_dedupes_known_scopes() {
+        let scopes = validate_scopes(&[
+            "feed:read".to_string(),
+            " feed:read ".to_string(),
+            "claims:write".to_string(),
+        ])
+        .unwrap();
+        assert_eq!(scopes, vec!["feed:read", "claims:write"]);
+    }
+
+    #[test]
+    fn validate_scopes_rejects_unknown_or_empty() {
+        assert!(validate_scopes(&[]).is_err());
+        assert!(validate_scopes(&["admin:write".to_string()]).is_err());
+    }
+
+    #[test]
+    fn parse_organization_filter_reads_optional_uuid() {
+        assert_eq!(parse_organization_filter(None).unwrap(), None);
+        assert!(parse_organization_filter(Some(
+            "organizationId=5df666d4-f6b1-4e6f-97d6-321e531ad7ca"
+        ))
+        .unwrap()
+        .is_some());
+        assert!(parse_organization_filter(Some("organizationId=nope")).is_err());
+    }
+
+    #[test]
+    fn hash_matches_authorizer_format() {
+        let hash = hash_api_key("cgk_example");
+        assert_eq!(hash.len(), 64);
+        assert!(hash.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -0,0 +1,219 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: i64 = 50;
+const MAX_LIMIT: i64 = 200;
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct AuditLogQuery {
+    actor_id: Option<Uuid>,
+    action: Option<String>,
+    target_type: Option<String>,
+    target_id: Option<String>,
+    before_id: Option<i64>,
+    limit: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogEntryResponse {
+    pub id: i64,
+    pub occurred_at: String,
+    pub actor_user_id: Option<String>,
+    pub actor_api_key_id: Option<String>,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub before: Option<serde_json::Value>,
+    pub after: Option<serde_json::Value>,
+    pub correlation_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditLogListResponse {
+    pub items: Vec<AuditLogEntryResponse>,
+    /// Pass as `beforeId` to fetch the next (older) page; absent on the last page.
+    pub next_before_id: Option<i64>,
+}
+
+pub async fn list_audit_log(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let query = parse_audit_log_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "audit_log::list_audit_log",
+            "
+            select id, occurred_at, actor_user_id, actor_api_key_id, action,
+                   target_type, target_id, before_snapshot, after_snapshot, correlation_id
+              from audit_log
+             where ($1::uuid is null or actor_user_id = $1)
+               and ($2::text is null or action = $2)
+               and ($3::text is null or target_type = $3)
+               and ($4::text is null or target_id = $4)
+               and ($5::bigint is null or id < $5)
+             order by id desc
+             limit $6
+            ",
+            &[
+                &query.actor_id,
+                &query.action,
+                &query.target_type,
+                &query.target_id,
+                &query.before_id,
+                &query.limit,
+            ],
+        )
+        .await?;
+
+    let items: Vec<AuditLogEntryResponse> = rows.iter().map(row_to_response).collect();
+    let next_before_id = if i64::try_from(items.len()).unwrap_or(i64::MAX) == query.limit {
+        items.last().map(|item| item.id)
+    } else {
+        None
+    };
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        entry_count = items.len(),
+        "Listed audit log entries"
+    );
+
+    json_response(
+        200,
+        &AuditLogListResponse {
+            items,
+            next_before_id,
+        },
+    )
+}
+
+fn parse_audit_log_query(query: Option<&str>) -> Result<AuditLogQuery, ApiError> {
+    let mut parsed = AuditLogQuery {
+        limit: DEFAULT_LIMIT,
+        ..AuditLogQuery::default()
+    };
+
+    let Some(raw_query) = query else {
+        return Ok(parsed);
+    };
+
+    for pair in raw_query.split('&') {
+        let Some((key, value)) = pair.split_once('=') else {
+            continue;
+        };
+        if value.is_empty() {
+            continue;
+        }
+
+        match key {
+            "actorId" => {
+                parsed.actor_id = Some(Uuid::parse_str(value).map_err(|_| {
+                    ApiError::invalid_field(
+                        "actorId",
+                        "invalid_uuid",
+                        "actorId must be a valid UUID",
+                    )
+                })?);
+            }
+            "action" => parsed.action = Some(value.to_string()),
+            "targetType" => parsed.target_type = Some(value.to_string()),
+            "targetId" => parsed.target_id = Some(value.to_string()),
+            "beforeId" => {
+                parsed.before_id = Some(value.parse::<i64>().map_err(|_| {
+                    ApiError::invalid_field(
+                        "beforeId",
+                        "invalid_cursor",
+                        "beforeId must be an integer",
+                    )
+                })?);
+            }
+            "limit" => {
+                parsed.limit = value
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|limit| (1..=MAX_LIMIT).contains(limit))
+                    .ok_or_else(|| {
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            format!("Invalid limit. Must be between 1 and {MAX_LIMIT}"),
+                        )
+                    })?;
+            }
+            _ => {}
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn row_to_response(row: &Row) -> AuditLogEntryResponse {
+    AuditLogEntryResponse {
+        id: row.get("id"),
+        occurred_at: row
+            .get::<_, chrono::DateTime<chrono::Utc>>("occurred_at")
+            .to_rfc3339(),
+        actor_user_id: row
+            .get::<_, Option<Uuid>>("actor_user_id")
+            .map(|id| id.to_string()),
+        actor_api_key_id: row
+            .get::<_, Option<Uuid>>("actor_api_key_id")
+            .map(|id| id.to_string()),
+        action: row.get("action"),
+        target_type: row.get("target_type"),
+        target_id: row.get("target_id"),
+        before: row.get("before_snapshot"),
+        after: row.get("after_snapshot"),
+        correlation_id: row.get("correlation_id"),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_audit_log_query_defaults() {
+        let parsed = parse_audit_log_query(None).unwrap();
+        assert_eq!(parsed.limit, DEFAULT_LIMIT);
+        assert!(parsed.actor_id.is_none());
+    }
+
+    #[test]
+    fn parse_audit_log_query_reads_filters() {
+        let parsed = parse_audit_log_query(Some(
+            "actorId=5df666d4-f6b1-4e6f-97d6-321e531ad7ca&targetType=listing&beforeId=42&limit=10",
+        ))
+        .unwrap();
+        assert!(parsed.actor_id.is_some());
+        assert_eq!(parsed.target_type.as_deref(), Some("listing"));
+        assert_eq!(parsed.before_id, Some(42));
+        assert_eq!(parsed.limit, 10);
+    }
+
+    #[test]
+    fn parse_audit_log_query_rejects_out_of_range_limit() {
+        let error = parse_audit_log_query(Some("limit=500")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_limit");
+    }
+
+    #[test]
+    fn parse_audit_log_query_rejects_bad_actor() {
+        let error = parse_audit_log_query(Some("actorId=nope")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_uuid");
+    }
+}
@@ -0,0 +1,394 @@
+//! Vacation mode under `/me/away`. While a grower is away their active and
+//! pending listings are paused and claims on their listings are refused;
+//! when the period ends each paused listing goes back to the status it had.
+//! Periods starting later are picked up by the away-mode worker, which also
+//! ends periods as they run out.
+
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body};
+use crate::models::away::{AwayPeriod, CreateAwayPeriodRequest};
+use chrono::{DateTime, Duration, Utc};
+use lambda_http::{Body, Request, Response};
+use tokio_postgres::{GenericClient, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_AWAY_DAYS: i64 = 90;
+const MAX_DAYS_AHEAD: i64 = 365;
+const MAX_NOTE_CHARS: usize = 500;
+
+/// Pauses the grower's claimable listings for `$1`, remembering each one's
+/// status so it can be restored. The away-mode worker runs the same update.
+const PAUSE_LISTINGS_SQL: &str = "
+    update surplus_listings
+       set status_before_away = status,
+           status = 'paused'::listing_status,
+           away_period_id = $1
+     where user_id = $2
+       and deleted_at is null
+       and status in ('active'::listing_status, 'pending'::listing_status)
+    ";
+
+/// Puts back listings still paused for `$1`. A listing whose last pending
+/// claim was confirmed while paused is left as the claim flow set it.
+const RESUME_LISTINGS_SQL: &str = "
+    update surplus_listings
+       set status = coalesce(status_before_away, 'active'::listing_status),
+           status_before_away = null,
+           away_period_id = null
+     where away_period_id = $1
+       and status = 'paused'::listing_status
+    ";
+
+#[derive(Debug, PartialEq, Eq)]
+struct NormalizedAwayPeriod {
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    note: Option<String>,
+}
+
+pub async fn get_away_period(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let client = db::connect().await?;
+
+    let row = client
+        .query_opt_timed(
+            "away::get_away_period",
+            "
+            select a.id, a.starts_at, a.ends_at, a.note, a.created_at,
+                   a.started_at is not null or a.starts_at <= now() as is_active,
+                   (select count(*) from surplus_listings l
+                     where l.away_period_id = a.id
+                       and l.status = 'paused'::listing_status) as paused_listing_count
+              from grower_away_periods a
+             where a.user_id = $1
+               and a.ended_at is null
+            ",
+            &[&user_id],
+        )
+        .await?
+        .ok_or_else(away_period_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        "Loaded away period"
+    );
+
+    json_response(200, &row_to_period(&row))
+}
+
+/// Schedules an away period. One that has already started pauses the
+/// grower's listings straight away.
+pub async fn create_away_period(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: CreateAwayPeriodRequest = parse_json_body(request)?;
+    let now = Utc::now();
+    let period = normalize_away_period(&payload, now)?;
+
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let existing = tx
+        .query_opt_timed(
+            "away::create_away_period",
+            "
+            select id from grower_away_periods
+             where user_id = $1 and ended_at is null
+             for update
+            ",
+            &[&user_id],
+        )
+        .await?;
+    if existing.is_some() {
+        return Err(ApiError::conflict(
+            "away_period_exists",
+            "An away period is already scheduled; end it before adding another",
+        ));
+    }
+
+    let starts_now = period.starts_at <= now;
+    let row = tx
+        .query_one_timed(
+            "away::create_away_period",
+            "
+            insert into grower_away_periods (user_id, starts_at, ends_at, note, started_at)
+            values ($1, $2, $3, $4, case when $5 then now() end)
+            returning id, starts_at, ends_at, note, created_at
+            ",
+            &[
+                &user_id,
+                &period.starts_at,
+                &period.ends_at,
+                &period.note,
+                &starts_now,
+            ],
+        )
+        .await?;
+    let period_id: Uuid = row.get("id");
+
+    let paused = if starts_now {
+        pause_listings(&tx, period_id, user_id).await?
+    } else {
+        0
+    };
+
+    tx.commit().await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        away_period_id = %period_id,
+        paused_listing_count = paused,
+        "Created away period"
+    );
+
+    json_response(
+        201,
+        &AwayPeriod {
+            id: period_id.to_string(),
+            starts_at: row.get::<_, DateTime<Utc>>("starts_at").to_rfc3339(),
+            ends_at: row.get::<_, DateTime<Utc>>("ends_at").to_rfc3339(),
+            note: row.get("note"),
+            status: period_status(starts_now).to_string(),
+            paused_listing_count: i64::try_from(paused).unwrap_or(i64::MAX),
+            created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        },
+    )
+}
+
+/// Ends the grower's away period now, or cancels it if it has not started,
+/// reactivating anything it paused.
+pub async fn end_away_period(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let period_id: Uuid = tx
+        .query_opt_timed(
+            "away::end_away_period",
+            "
+            select id from grower_away_periods
+             where user_id = $1 and ended_at is null
+             for update
+            ",
+            &[&user_id],
+        )
+        .await?
+        .ok_or_else(away_period_not_found)?
+        .get("id");
+
+    let resumed = tx
+        .execute_timed("away::end_away_period", RESUME_LISTINGS_SQL, &[&period_id])
+        .await?;
+    tx.execute_timed(
+        "away::end_away_period",
+        "update grower_away_periods set ended_at = now() where id = $1",
+        &[&period_id],
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        away_period_id = %period_id,
+        resumed_listing_count = resumed,
+        "Ended away period"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+async fn pause_listings<C: GenericClient + Sync>(
+    client: &C,
+    period_id: Uuid,
+    user_id: Uuid,
+) -> Result<u64, ApiError> {
+    Ok(client
+        .execute_timed(
+            "away::pause_listings",
+            PAUSE_LISTINGS_SQL,
+            &[&period_id, &user_id],
+        )
+        .await?)
+}
+
+fn normalize_away_period(
+    payload: &CreateAwayPeriodRequest,
+    now: DateTime<Utc>,
+) -> Result<NormalizedAwayPeriod, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let starts_at = match payload.starts_at.as_deref().map(str::trim) {
+        None | Some("") => Some(now),
+        Some(value) => errors
+            .capture(parse_datetime(value, "startsAt"))
+            .map(|starts_at| starts_at.max(now)),
+    };
+    let ends_at = errors.capture(parse_datetime(payload.ends_at.trim(), "endsAt"));
+
+    if let Some(starts_at) = starts_at {
+        if starts_at > now + Duration::days(MAX_DAYS_AHEAD) {
+            errors.add(
+                "startsAt",
+                "too_far_ahead",
+                format!("startsAt must be within {MAX_DAYS_AHEAD} days"),
+            );
+        }
+    }
+    if let (Some(starts_at), Some(ends_at)) = (starts_at, ends_at) {
+        if ends_at <= starts_at {
+            errors.add("endsAt", "invalid_range", "endsAt must be after startsAt");
+        } else if ends_at - starts_at > Duration::days(MAX_AWAY_DAYS) {
+            errors.add(
+                "endsAt",
+                "too_long",
+                format!("An away period can last at most {MAX_AWAY_DAYS} days"),
+            );
+        }
+    }
+
+    let note = payload
+        .note
+        .as_deref()
+        .map(str::trim)
+        .filter(|note| !note.is_empty());
+    if note.is_some_and(|note| note.chars().count() > MAX_NOTE_CHARS) {
+        errors.add(
+            "note",
+            "too_long",
+            format!("note must be at most {MAX_NOTE_CHARS} characters"),
+        );
+    }
+
+    errors.into_result()?;
+    match (starts_at, ends_at) {
+        (Some(starts_at), Some(ends_at)) => Ok(NormalizedAwayPeriod {
+            starts_at,
+            ends_at,
+            note: note.map(ToString::to_string),
+        }),
+        _ => Err(ApiError::internal("Away period range missing")),
+    }
+}
+
+fn parse_datetime(value: &str, field_name: &str) -> Result<DateTime<Utc>, ApiError> {
+    let parsed = DateTime::parse_from_rfc3339(value).map_err(|_| {
+        ApiError::invalid_field(
+            field_name,
+            "invalid_timestamp",
+            format!("{field_name} must be a valid RFC3339 timestamp"),
+        )
+    })?;
+    Ok(parsed.with_timezone(&Utc))
+}
+
+const fn period_status(is_active: bool) -> &'static str {
+    if is_active {
+        "active"
+    } else {
+        "scheduled"
+    }
+}
+
+fn row_to_period(row: &Row) -> AwayPeriod {
+    AwayPeriod {
+        id: row.get::<_, Uuid>("id").to_string(),
+        starts_at: row.get::<_, DateTime<Utc>>("starts_at").to_rfc3339(),
+        ends_at: row.get::<_, DateTime<Utc>>("ends_at").to_rfc3339(),
+        note: row.get("note"),
+        status: period_status(row.get("is_active")).to_string(),
+        paused_listing_count: row.get("paused_listing_count"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn away_period_not_found() -> ApiError {
+    ApiError::not_found("away_period_not_found", "No away period is scheduled")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn now() -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339("2026-07-01T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    fn payload(starts_at: Option<&str>, ends_at: &str) -> CreateAwayPeriodRequest {
+        CreateAwayPeriodRequest {
+            starts_at: starts_at.map(ToString::to_string),
+            ends_at: ends_at.to_string(),
+            note: None,
+        }
+    }
+
+    #[test]
+    fn defaults_start_to_now_and_clamps_past_starts() {
+        let period = normalize_away_period(&payload(None, "2026-07-10T00:00:00Z"), now()).unwrap();
+        assert_eq!(period.starts_at, now());
+
+        let period = normalize_away_period(
+            &payload(Some("2026-06-01T00:00:00Z"), "2026-07-10T00:00:00Z"),
+            now(),
+        )
+        .unwrap();
+        assert_eq!(period.starts_at, now());
+    }
+
+    #[test]
+    fn keeps_future_starts_and_trims_notes() {
+        let mut input = payload(Some("2026-08-01T00:00:00-04:00"), "2026-08-15T00:00:00Z");
+        input.note = Some("  Camping  ".to_string());
+        let period = normalize_away_period(&input, now()).unwrap();
+
+        assert_eq!(period.starts_at.to_rfc3339(), "2026-08-01T04:00:00+00:00");
+        assert_eq!(period.note.as_deref(), Some("Camping"));
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        for (starts_at, ends_at, code) in [
+            (None, "not a date", "invalid_timestamp"),
+            (None, "2026-06-30T00:00:00Z", "invalid_range"),
+            (
+                Some("2026-08-10T00:00:00Z"),
+                "2026-08-01T00:00:00Z",
+                "invalid_range",
+            ),
+            (None, "2026-12-01T00:00:00Z", "too_long"),
+            (
+                Some("2027-08-01T00:00:00Z"),
+                "2027-08-10T00:00:00Z",
+                "too_far_ahead",
+            ),
+        ] {
+            let error = normalize_away_period(&payload(starts_at, ends_at), now()).unwrap_err();
+            assert_eq!(error.error_code(), code, "{ends_at}");
+        }
+    }
+}
@@ -1,12 +1,14 @@
 use crate::auth::extract_auth_context;
-use crate::db;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
 use crate::handlers::analytics;
+use crate::http_util::{json_response, parse_json_body};
 use lambda_http::{Body, Request, Response};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use sha2::Sha256;
 use std::collections::HashMap;
-use std::env;
 use uuid::Uuid;
 
 #[derive(Debug, Deserialize)]
@@ -26,16 +28,22 @@ pub struct CreateCheckoutSessionResponse {
 pub async fn create_checkout_session(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
     let auth = extract_auth_context(request)?;
     let user_id = Uuid::parse_str(&auth.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let payload: CreateCheckoutSessionRequest = parse_json_body(request)?;
 
-    let stripe_secret = env::var("STRIPE_SECRET_KEY")
-        .map_err(|_| lambda_http::Error::from("STRIPE_SECRET_KEY is not configured"))?;
-    let stripe_price_id = env::var("STRIPE_PREMIUM_PRICE_ID")
-        .map_err(|_| lambda_http::Error::from("STRIPE_PREMIUM_PRICE_ID is not configured"))?;
+    let stripe_secret = config.stripe.secret_key.as_ref().ok_or_else(|| {
+        ApiError::unavailable("not_configured", "STRIPE_SECRET_KEY is not configured")
+    })?;
+    let stripe_price_id = config.stripe.premium_price_id.clone().ok_or_else(|| {
+        ApiError::unavailable(
+            "not_configured",
+            "STRIPE_PREMIUM_PRICE_ID is not configured",
+        )
+    })?;
 
     let mut form = HashMap::new();
     form.insert("mode", "subscription".to_string());
@@ -49,16 +57,18 @@ pub async fn create_checkout_session(
     let client = reqwest::Client::new();
     let stripe_resp = client
         .post("https://api.stripe.com/v1/checkout/sessions")
-        .basic_auth(stripe_secret, Some(""))
+        .basic_auth(stripe_secret.expose(), Some(""))
         .form(&form)
         .send()
         .await
-        .map_err(|e| lambda_http::Error::from(format!("Stripe request failed: {e}")))?;
+        .map_err(|e| {
+            ApiError::unavailable("stripe_unavailable", format!("Stripe request failed: {e}"))
+        })?;
 
     if !stripe_resp.status().is_success() {
         let status = stripe_resp.status();
         let body = stripe_resp.text().await.unwrap_or_default();
-        return Err(lambda_http::Error::from(format!(
+        return Err(ApiError::internal(format!(
             "Stripe checkout creation failed ({status}): {body}"
         )));
     }
@@ -66,19 +76,20 @@ pub async fn create_checkout_session(
     let payload: Value = stripe_resp
         .json()
         .await
-        .map_err(|e| lambda_http::Error::from(format!("Invalid Stripe response JSON: {e}")))?;
+        .map_err(|e| ApiError::internal(format!("Invalid Stripe response JSON: {e}")))?;
 
     let checkout_url = payload
         .get("url")
         .and_then(Value::as_str)
-        .ok_or_else(|| lambda_http::Error::from("Stripe checkout URL missing"))?;
+        .ok_or_else(|| ApiError::internal("Stripe checkout URL missing"))?;
     let checkout_session_id = payload
         .get("id")
         .and_then(Value::as_str)
-        .ok_or_else(|| lambda_http::Error::from("Stripe checkout id missing"))?;
+        .ok_or_else(|| ApiError::internal("Stripe checkout id missing"))?;
 
+    let client = db::connect().await?;
     let _ = analytics::log_backend_event(
-        &db::connect().await?,
+        &client,
         Some(user_id),
         "checkout_start",
         Some(serde_json::json!({ "checkoutSessionId": checkout_session_id })),
@@ -104,16 +115,17 @@ pub async fn create_checkout_session(
 pub async fn handle_webhook(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
     let raw_body = extract_raw_body(request)?;
-    verify_stripe_signature(request, &raw_body)?;
+    verify_stripe_signature(request, &raw_body, config)?;
 
     let event: Value = serde_json::from_str(&raw_body)
-        .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}")))?;
+        .map_err(|e| ApiError::bad_request("invalid_body", format!("Invalid JSON body: {e}")))?;
     let event_id = event
         .get("id")
         .and_then(Value::as_str)
-        .ok_or_else(|| lambda_http::Error::from("Stripe event missing id"))?;
+        .ok_or_else(|| ApiError::bad_request("invalid_event", "Stripe event missing id"))?;
     let event_type = event
         .get("type")
         .and_then(Value::as_str)
@@ -126,12 +138,15 @@ pub async fn handle_webhook(
     let object = event
         .get("data")
         .and_then(|d| d.get("object"))
-        .ok_or_else(|| lambda_http::Error::from("Stripe event missing data.object"))?;
+        .ok_or_else(|| {
+            ApiError::bad_request("invalid_event", "Stripe event missing data.object")
+        })?;
 
     let client = db::connect().await?;
 
     let inserted = client
-        .execute(
+        .execute_timed(
+            "billing::handle_webhook",
             "
             insert into stripe_webhook_events (id, event_type, created_unix)
             values ($1, $2, $3)
@@ -139,8 +154,7 @@ pub async fn handle_webhook(
             ",
             &[&event_id, &event_type, &event_created],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     if inserted == 0 {
         tracing::info!(
@@ -176,7 +190,8 @@ pub async fn handle_webhook(
     if let Err(err) = result {
         let payload_json: serde_json::Value = serde_json::to_value(&event).unwrap_or_default();
         let _ = client
-            .execute(
+            .execute_timed(
+                "billing::handle_webhook",
                 "
                 insert into stripe_webhook_failures (event_id, event_type, reason, payload)
                 values ($1, $2, $3, $4)
@@ -185,7 +200,7 @@ pub async fn handle_webhook(
             )
             .await;
 
-        return Err(lambda_http::Error::from(format!(
+        return Err(ApiError::internal(format!(
             "Stripe webhook processing failed: {err}"
         )));
     }
@@ -203,7 +218,7 @@ async fn apply_checkout_session_completed(
         let stripe_subscription_id = object.get("subscription").and_then(Value::as_str);
 
         client
-            .execute(
+            .execute_timed("billing::apply_checkout_session_completed", 
                 "
                 update users
                    set tier = 'premium',
@@ -251,7 +266,7 @@ async fn apply_subscription_update(
         let (tier, sub_status) = map_subscription_status(status);
         let current_period_end_unix = extract_current_period_end_unix(object);
         let updated = client
-            .execute(
+            .execute_timed("billing::apply_subscription_update", 
                 "
                 update users
                    set tier = $2,
@@ -324,34 +339,38 @@ fn map_subscription_status(status: &str) -> (&'static str, &'static str) {
     }
 }
 
-fn extract_raw_body(request: &Request) -> Result<String, lambda_http::Error> {
+fn extract_raw_body(request: &Request) -> Result<String, ApiError> {
     match request.body() {
         Body::Text(text) => Ok(text.clone()),
         Body::Binary(bytes) => String::from_utf8(bytes.clone())
-            .map_err(|e| lambda_http::Error::from(format!("Invalid UTF-8 body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
+            .map_err(|e| ApiError::bad_request("invalid_body", format!("Invalid UTF-8 body: {e}"))),
+        Body::Empty => Err(ApiError::bad_request(
+            "invalid_body",
+            "Request body is required",
         )),
     }
 }
 
-fn verify_stripe_signature(request: &Request, body: &str) -> Result<(), lambda_http::Error> {
-    let secret = env::var("STRIPE_WEBHOOK_SECRET")
-        .map_err(|_| lambda_http::Error::from("STRIPE_WEBHOOK_SECRET is not configured"))?;
+fn verify_stripe_signature(request: &Request, body: &str, config: &Config) -> Result<(), ApiError> {
+    let secret = config.stripe.webhook_secret.as_ref().ok_or_else(|| {
+        ApiError::unavailable("not_configured", "STRIPE_WEBHOOK_SECRET is not configured")
+    })?;
     let signature_header = request
         .headers()
         .get("Stripe-Signature")
         .and_then(|v| v.to_str().ok())
-        .ok_or_else(|| lambda_http::Error::from("Missing Stripe-Signature header"))?;
+        .ok_or_else(|| {
+            ApiError::bad_request("invalid_signature", "Missing Stripe-Signature header")
+        })?;
 
-    verify_signature_with_secret(&secret, signature_header, body)
+    verify_signature_with_secret(secret.expose(), signature_header, body)
 }
 
 fn verify_signature_with_secret(
     secret: &str,
     signature_header: &str,
     body: &str,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     type HmacSha256 = hmac::Hmac<Sha256>;
     use hmac::Mac;
 
@@ -368,61 +387,32 @@ fn verify_signature_with_secret(
         }
     }
 
-    let ts =
-        timestamp.ok_or_else(|| lambda_http::Error::from("Stripe signature missing timestamp"))?;
+    let ts = timestamp.ok_or_else(|| {
+        ApiError::bad_request("invalid_signature", "Stripe signature missing timestamp")
+    })?;
     if candidate_signatures.is_empty() {
-        return Err(lambda_http::Error::from(
-            "Stripe signature missing v1 digest".to_string(),
+        return Err(ApiError::bad_request(
+            "invalid_signature",
+            "Stripe signature missing v1 digest",
         ));
     }
 
     let signed_payload = format!("{ts}.{body}");
     let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
-        .map_err(|_| lambda_http::Error::from("Invalid webhook secret"))?;
+        .map_err(|_| ApiError::internal("Invalid webhook secret"))?;
     mac.update(signed_payload.as_bytes());
     let expected = hex::encode(mac.finalize().into_bytes());
 
     if candidate_signatures.iter().any(|sig| sig == &expected) {
         Ok(())
     } else {
-        Err(lambda_http::Error::from(
-            "Invalid Stripe signature".to_string(),
+        Err(ApiError::bad_request(
+            "invalid_signature",
+            "Invalid Stripe signature",
         ))
     }
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
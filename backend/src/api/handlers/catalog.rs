@@ -1,19 +1,18 @@
-use crate::db;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
 use crate::models::catalog::{CatalogCrop, CatalogVariety, SourceAttribution};
-use crate::models::crop::ErrorResponse;
 use lambda_http::{Body, Response};
-use serde::Serialize;
 use uuid::Uuid;
 
-pub async fn list_catalog_crops() -> Result<Response<Body>, lambda_http::Error> {
+pub async fn list_catalog_crops() -> Result<Response<Body>, ApiError> {
     let client = db::connect().await?;
     let rows = client
-        .query(
-            "select id, slug, common_name, scientific_name, category, description, source_provider, source_record_id, source_url, source_license, attribution_text, import_batch_id, imported_at::text as imported_at, last_verified_at::text as last_verified_at from crops order by common_name asc",
+        .query_timed("catalog::list_catalog_crops", 
+            "select id, slug, common_name, scientific_name, category, nutrition_group, description, source_provider, source_record_id, source_url, source_license, attribution_text, import_batch_id, imported_at::text as imported_at, last_verified_at::text as last_verified_at from crops order by common_name asc",
             &[],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let crops = rows
         .into_iter()
@@ -23,6 +22,7 @@ pub async fn list_catalog_crops() -> Result<Response<Body>, lambda_http::Error>
             common_name: row.get("common_name"),
             scientific_name: row.get("scientific_name"),
             category: row.get("category"),
+            nutrition_group: row.get("nutrition_group"),
             description: row.get("description"),
             source_attribution: SourceAttribution {
                 source: row.get("source_provider"),
@@ -40,37 +40,35 @@ pub async fn list_catalog_crops() -> Result<Response<Body>, lambda_http::Error>
     json_response(200, &crops)
 }
 
-pub async fn list_catalog_varieties(crop_id: &str) -> Result<Response<Body>, lambda_http::Error> {
-    let crop_uuid = Uuid::parse_str(crop_id)
-        .map_err(|_| lambda_http::Error::from("crop id must be a valid UUID".to_string()))?;
+pub async fn list_catalog_varieties(crop_id: &str) -> Result<Response<Body>, ApiError> {
+    let crop_uuid = Uuid::parse_str(crop_id).map_err(|_| {
+        ApiError::invalid_field("cropId", "invalid_uuid", "crop id must be a valid UUID")
+    })?;
 
     let client = db::connect().await?;
 
     let exists = client
-        .query_one(
+        .query_one_timed(
+            "catalog::list_catalog_varieties",
             "select exists(select 1 from crops where id = $1)",
             &[&crop_uuid],
         )
-        .await
-        .map_err(|error| db_error(&error))?
+        .await?
         .get::<_, bool>(0);
 
     if !exists {
-        return json_response(
-            404,
-            &ErrorResponse {
-                error: "Catalog crop not found".to_string(),
-            },
-        );
+        return Err(ApiError::not_found(
+            "catalog_crop_not_found",
+            "Catalog crop not found",
+        ));
     }
 
     let rows = client
-        .query(
+        .query_timed("catalog::list_catalog_varieties", 
             "select id, crop_id, slug, name, description, source_provider, source_record_id, source_url, source_license, attribution_text, import_batch_id, imported_at::text as imported_at, last_verified_at::text as last_verified_at from crop_varieties where crop_id = $1 order by name asc",
             &[&crop_uuid],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let varieties = rows
         .into_iter()
@@ -95,21 +93,3 @@ pub async fn list_catalog_varieties(crop_id: &str) -> Result<Response<Body>, lam
 
     json_response(200, &varieties)
 }
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
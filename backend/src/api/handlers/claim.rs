@@ -1,12 +1,17 @@
-use crate::auth::{
-    extract_auth_context_with_fallback, require_participant_user_type, require_user_type, UserType,
-};
-use crate::db;
-use crate::models::crop::ErrorResponse;
-use aws_config::BehaviorVersion;
-use aws_sdk_eventbridge::types::PutEventsRequestEntry;
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::extract_auth_context;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::events::{self, ClaimEventDetail};
+use crate::handlers::donation_receipt;
+use crate::http_util::{json_response, parse_json_body, parse_uuid, request_locale};
+use crate::location;
+use crate::quantity;
+use crate::units::{self, UnitMatch};
 use chrono::{DateTime, Utc};
 use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use tokio_postgres::{Row, Transaction};
 use tracing::{error, info};
@@ -21,8 +26,17 @@ const CLAIMABLE_LISTING_STATUSES: [&str; 2] = ["active", "pending"];
 pub struct CreateClaimRequest {
     pub listing_id: String,
     pub request_id: Option<String>,
-    pub quantity_claimed: f64,
+    pub quantity_claimed: Decimal,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub delivery_requested: bool,
+    /// What `quantityClaimed` amounts to in the linked request's unit, for
+    /// units the catalog cannot convert.
+    pub request_quantity: Option<Decimal>,
+    /// Links a request in a different, unconvertible unit without saying
+    /// how much of it the claim covers.
+    #[serde(default)]
+    pub allow_unit_mismatch: bool,
 }
 
 #[derive(Debug, Deserialize)]
@@ -40,21 +54,39 @@ pub struct ClaimResponse {
     pub request_id: Option<String>,
     pub claimer_id: String,
     pub listing_owner_id: String,
+    /// Names of the claimed listing's crop and variety in the caller's
+    /// `Accept-Language`.
+    pub crop_name: Option<String>,
+    pub variety_name: Option<String>,
     pub quantity_claimed: String,
+    /// How much of the linked request the claim covers, in the request's
+    /// unit; null when the units could not be related.
+    pub request_quantity: Option<String>,
     pub status: String,
     pub notes: Option<String>,
+    pub delivery_requested: bool,
+    pub delivery_status: Option<String>,
     pub claimed_at: String,
     pub confirmed_at: Option<String>,
     pub completed_at: Option<String>,
     pub cancelled_at: Option<String>,
+    /// Until when a pending claim reserves its quantity; null once the grower
+    /// acts on it or the hold lapses.
+    pub hold_expires_at: Option<String>,
+    /// Last change to the claim or its delivery; see `updatedSince` on
+    /// `GET /claims`.
+    pub updated_at: String,
 }
 
 #[derive(Debug)]
 struct NormalizedCreateClaimInput {
     listing_id: Uuid,
     request_id: Option<Uuid>,
-    quantity_claimed: f64,
+    quantity_claimed: Decimal,
     notes: Option<String>,
+    delivery_requested: bool,
+    request_quantity: Option<Decimal>,
+    allow_unit_mismatch: bool,
 }
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -87,77 +119,165 @@ struct TransitionDecision {
     stamp_cancelled_at: bool,
 }
 
+/// What a claim transition does to its volunteer delivery, if one exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeliverySync {
+    None,
+    Settle,
+    Cancel,
+}
+
 pub async fn create_claim(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_user_type(&auth_context, &UserType::Gatherer)?;
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
 
     let claimer_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let payload: CreateClaimRequest = parse_json_body(request)?;
     let normalized = normalize_create_payload(&payload)?;
 
+    let normalized = &normalized;
+    let hold = config.claim_hold;
+    let locale = request_locale(request);
+    let locale = locale.as_deref();
+    let (claim_row, listing_owner_id) = db::retry("create_claim", move || {
+        insert_pending_claim(normalized, claimer_id, hold, locale)
+    })
+    .await?;
+
+    let response = row_to_claim_response(&claim_row, listing_owner_id);
+    emit_claim_event_best_effort(events::CLAIM_CREATED, &response, correlation_id).await;
+
+    info!(
+        correlation_id = correlation_id,
+        claim_id = response.id.as_str(),
+        listing_id = response.listing_id.as_str(),
+        claimer_id = response.claimer_id.as_str(),
+        hold_expires_at = response.hold_expires_at.as_deref(),
+        "Created claim in pending state"
+    );
+
+    json_response(201, &response)
+}
+
+/// One attempt at checking quantity and inserting the claim; `db::retry`
+/// replays it from scratch on serialization failures. Quantity reserved by
+/// other pending claims counts as taken. With holds on, the new claim
+/// reserves its own quantity until `hold` elapses.
+async fn insert_pending_claim(
+    normalized: &NormalizedCreateClaimInput,
+    claimer_id: Uuid,
+    hold: std::time::Duration,
+    locale: Option<&str>,
+) -> Result<(Row, Uuid), ApiError> {
     let mut client = db::connect().await?;
-    let tx = client
-        .transaction()
-        .await
-        .map_err(|error| db_error(&error))?;
+    let tx = client.transaction().await?;
 
     let listing_row = tx
-        .query_opt(
+        .query_opt_timed(
+            "claim::insert_pending_claim",
             "
-            select id, user_id, crop_id, variety_id, status::text as status,
-                   quantity_remaining::double precision as quantity_remaining
-            from surplus_listings
-            where id = $1
-              and deleted_at is null
-            for update
+            select l.id, l.user_id, l.crop_id, l.variety_id, l.unit, l.status::text as status,
+                   l.quantity_remaining, l.moderation_held_at is not null as moderation_held,
+                   exists (
+                       select 1 from grower_away_periods a
+                        where a.user_id = l.user_id
+                          and a.ended_at is null
+                          and a.starts_at <= now()
+                   ) as owner_away
+            from surplus_listings l
+            where l.id = $1
+              and l.deleted_at is null
+            for update of l
             ",
             &[&normalized.listing_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let Some(listing) = listing_row else {
-        return error_response(404, "Listing not found");
+        return Err(ApiError::not_found(
+            "listing_not_found",
+            "Listing not found",
+        ));
     };
 
     let listing_owner_id = listing.get::<_, Uuid>("user_id");
     let listing_status: String = listing.get("status");
-    let listing_crop_id: Uuid = listing.get("crop_id");
+    let listing_crop_id: Option<Uuid> = listing.get("crop_id");
+
+    if listing.get::<_, bool>("moderation_held") {
+        return Err(ApiError::conflict(
+            "listing_not_claimable",
+            "Listing is under moderation review",
+        ));
+    }
+
+    // Covers listings the away-mode worker has not paused yet as well as
+    // paused ones.
+    if listing.get::<_, bool>("owner_away") {
+        return Err(ApiError::conflict(
+            "grower_away",
+            "The grower is away and not taking claims right now",
+        ));
+    }
 
     if !is_claimable_listing_status(&listing_status) {
         if listing_status == "claimed" {
-            return error_response(409, "Insufficient quantity remaining");
+            return Err(insufficient_quantity());
         }
-        return Err(lambda_http::Error::from(
+        return Err(ApiError::conflict(
+            "listing_not_claimable",
             "Listing is not claimable in its current status",
         ));
     }
 
-    if let Some(quantity_remaining) = listing.get::<_, Option<f64>>("quantity_remaining") {
-        if quantity_remaining < normalized.quantity_claimed {
-            return error_response(409, "Insufficient quantity remaining");
+    check_share_radius(&tx, normalized.listing_id, claimer_id).await?;
+
+    let reserved_quantity = release_expired_holds(&tx, normalized.listing_id).await?;
+    if let Some(quantity_remaining) = listing.get::<_, Option<Decimal>>("quantity_remaining") {
+        if quantity_remaining - reserved_quantity < normalized.quantity_claimed {
+            return Err(insufficient_quantity());
         }
     }
 
-    if let Some(request_id) = normalized.request_id {
-        validate_request_linkage(&tx, request_id, claimer_id, listing_crop_id).await?;
-    }
+    let request_quantity = match normalized.request_id {
+        Some(request_id) => {
+            validate_request_linkage(
+                &tx,
+                normalized,
+                request_id,
+                claimer_id,
+                listing_crop_id,
+                listing.get("unit"),
+            )
+            .await?
+        }
+        None => None,
+    };
 
     let claim_row = tx
-        .query_one(
+        .query_one_timed(
+            "claim::insert_pending_claim",
             "
             insert into claims
-                (listing_id, request_id, claimer_id, quantity_claimed, status, notes)
+                (listing_id, request_id, claimer_id, quantity_claimed, status, notes,
+                 delivery_requested, hold_expires_at, request_quantity)
             values
-                ($1, $2, $3, $4::double precision, 'pending'::claim_status, $5)
+                ($1, $2, $3, $4::numeric, 'pending'::claim_status, $5, $6, $7, $9::numeric)
             returning id, listing_id, request_id, claimer_id,
                       quantity_claimed::text as quantity_claimed,
+                      request_quantity::text as request_quantity,
                       status::text as status, notes,
-                      claimed_at, confirmed_at, completed_at, cancelled_at
+                      delivery_requested, null::text as delivery_status,
+                      claimed_at, confirmed_at, completed_at, cancelled_at,
+                      hold_expires_at, updated_at,
+                      (select crop_display_name(l.crop_id, $8)
+                         from surplus_listings l where l.id = claims.listing_id) as crop_name,
+                      (select variety_display_name(l.variety_id, $8)
+                         from surplus_listings l where l.id = claims.listing_id) as variety_name
             ",
             &[
                 &normalized.listing_id,
@@ -165,45 +285,36 @@ pub async fn create_claim(
                 &claimer_id,
                 &normalized.quantity_claimed,
                 &normalized.notes,
+                &normalized.delivery_requested,
+                &hold_expiry(Utc::now(), hold),
+                &locale,
+                &request_quantity,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
-
-    adjust_listing_quantity_if_needed(
-        &tx,
-        normalized.listing_id,
-        normalized.quantity_claimed,
-        ListingQuantityAdjustment::Decrement,
-    )
-    .await?;
+        .await?;
 
-    tx.commit().await.map_err(|error| db_error(&error))?;
+    if claim_row
+        .get::<_, Option<DateTime<Utc>>>("hold_expires_at")
+        .is_some()
+    {
+        adjust_reserved_quantity(&tx, normalized.listing_id, normalized.quantity_claimed).await?;
+    }
 
-    let response = row_to_claim_response(&claim_row, listing_owner_id);
-    emit_claim_event_best_effort("claim.created", &response, correlation_id).await;
+    db::commit(tx).await?;
 
-    info!(
-        correlation_id = correlation_id,
-        claim_id = response.id.as_str(),
-        listing_id = response.listing_id.as_str(),
-        claimer_id = response.claimer_id.as_str(),
-        "Created claim in pending state"
-    );
-
-    json_response(201, &response)
+    Ok((claim_row, listing_owner_id))
 }
 
+#[allow(clippy::too_many_lines)]
 pub async fn transition_claim(
     request: &Request,
     correlation_id: &str,
     claim_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_claim_transition_user_type(auth_context.user_type.as_ref())?;
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
 
     let actor_user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let id = parse_uuid(claim_id, "claimId")?;
 
     let payload: TransitionClaimRequest = parse_json_body(request)?;
@@ -211,43 +322,59 @@ pub async fn transition_claim(
     let notes = normalize_optional_text(payload.notes.as_deref());
 
     let mut client = db::connect().await?;
-    let tx = client
-        .transaction()
-        .await
-        .map_err(|error| db_error(&error))?;
+    let tx = client.transaction().await?;
 
     let claim_context_row = tx
-        .query_opt(
+        .query_opt_timed(
+            "claim::transition_claim",
             "
             select c.id, c.listing_id, c.request_id, c.claimer_id,
-                   c.quantity_claimed::double precision as quantity_claimed_value,
+                   c.quantity_claimed as quantity_claimed_value,
                    c.quantity_claimed::text as quantity_claimed,
                    c.status::text as status, c.notes,
                    c.claimed_at, c.confirmed_at, c.completed_at, c.cancelled_at,
-                   l.user_id as listing_owner_id
+                   c.hold_expires_at is not null as holds_quantity,
+                   l.user_id as listing_owner_id,
+                   exists (
+                     select 1 from grower_co_managers m
+                     where m.owner_id = l.user_id
+                       and m.co_manager_id = $2
+                       and m.status = 'accepted'
+                   ) as actor_co_manages
             from claims c
             inner join surplus_listings l on l.id = c.listing_id
             where c.id = $1
               and l.deleted_at is null
             for update of c, l
             ",
-            &[&id],
+            &[&id, &actor_user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let Some(claim_context) = claim_context_row else {
-        return error_response(404, "Claim not found");
+        return Err(ApiError::not_found("claim_not_found", "Claim not found"));
     };
 
     let current_status = parse_claim_status(&claim_context.get::<_, String>("status"))?;
     let claimer_id: Uuid = claim_context.get("claimer_id");
     let listing_owner_id: Uuid = claim_context.get("listing_owner_id");
     let listing_id: Uuid = claim_context.get("listing_id");
-    let quantity_claimed: f64 = claim_context.get("quantity_claimed_value");
-
-    let actor_role = determine_actor_role(actor_user_id, claimer_id, listing_owner_id)?;
+    let quantity_claimed: Decimal = claim_context.get("quantity_claimed_value");
+
+    let actor_co_manages: bool = claim_context.get("actor_co_manages");
+    let actor_role = determine_actor_role(
+        actor_user_id,
+        claimer_id,
+        listing_owner_id,
+        actor_co_manages,
+    )?;
     let decision = evaluate_transition(current_status, target_status, actor_role)?;
+    let release_hold = claim_context.get::<_, bool>("holds_quantity")
+        && releases_hold(current_status, target_status);
+
+    if release_hold {
+        adjust_reserved_quantity(&tx, listing_id, -quantity_claimed).await?;
+    }
 
     adjust_listing_quantity_if_needed(
         &tx,
@@ -257,8 +384,11 @@ pub async fn transition_claim(
     )
     .await?;
 
+    sync_delivery_if_needed(&tx, id, delivery_sync_for(target_status)).await?;
+
     let updated_claim = tx
-        .query_one(
+        .query_one_timed(
+            "claim::transition_claim",
             "
             update claims
             set status = $1::claim_status,
@@ -274,12 +404,24 @@ pub async fn transition_claim(
                 cancelled_at = case
                     when $5 then coalesce(cancelled_at, now())
                     else cancelled_at
+                end,
+                hold_expires_at = case
+                    when $7 then null
+                    else hold_expires_at
                 end
             where id = $6
             returning id, listing_id, request_id, claimer_id,
                       quantity_claimed::text as quantity_claimed,
-                      status::text as status, notes,
-                      claimed_at, confirmed_at, completed_at, cancelled_at
+                      request_quantity::text as request_quantity,
+                      status::text as status, notes, delivery_requested,
+                      (select d.status from claim_deliveries d where d.claim_id = claims.id)
+                        as delivery_status,
+                      claimed_at, confirmed_at, completed_at, cancelled_at,
+                      hold_expires_at, updated_at,
+                      (select crop_display_name(l.crop_id, $8)
+                         from surplus_listings l where l.id = claims.listing_id) as crop_name,
+                      (select variety_display_name(l.variety_id, $8)
+                         from surplus_listings l where l.id = claims.listing_id) as variety_name
             ",
             &[
                 &target_status.as_db_value(),
@@ -288,15 +430,40 @@ pub async fn transition_claim(
                 &decision.stamp_completed_at,
                 &decision.stamp_cancelled_at,
                 &id,
+                &release_hold,
+                &request_locale(request),
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
-    tx.commit().await.map_err(|error| db_error(&error))?;
+    let receipt_recorded = if decision.stamp_completed_at {
+        donation_receipt::record_for_completed_claim(&tx, id).await?
+    } else {
+        false
+    };
 
     let response = row_to_claim_response(&updated_claim, listing_owner_id);
-    emit_claim_event_best_effort("claim.updated", &response, correlation_id).await;
+    let acted_for_owner =
+        actor_role == ClaimActorRole::ListingOwner && actor_user_id != listing_owner_id;
+    if acted_for_owner {
+        audit::record(
+            &tx,
+            &AuditEntry {
+                actor: Actor::from_auth(&auth_context),
+                action: audit::CLAIM_TRANSITIONED_BY_CO_MANAGER,
+                target_type: "claim",
+                target_id: response.id.clone(),
+                before: Some(serde_json::json!({ "status": current_status.as_db_value() })),
+                after: serde_json::to_value(&response).ok(),
+                correlation_id,
+            },
+        )
+        .await?;
+    }
+
+    tx.commit().await?;
+
+    emit_claim_event_best_effort(events::CLAIM_UPDATED, &response, correlation_id).await;
 
     info!(
         correlation_id = correlation_id,
@@ -304,6 +471,7 @@ pub async fn transition_claim(
         actor_user_id = auth_context.user_id.as_str(),
         previous_status = current_status.as_db_value(),
         new_status = response.status.as_str(),
+        receipt_recorded = receipt_recorded,
         "Updated claim state"
     );
 
@@ -312,42 +480,102 @@ pub async fn transition_claim(
 
 fn normalize_create_payload(
     payload: &CreateClaimRequest,
-) -> Result<NormalizedCreateClaimInput, lambda_http::Error> {
-    if payload.quantity_claimed <= 0.0 {
-        return Err(lambda_http::Error::from(
+) -> Result<NormalizedCreateClaimInput, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let quantity_claimed = errors.capture(quantity::validate(
+        payload.quantity_claimed,
+        "quantityClaimed",
+    ));
+    if quantity_claimed.is_some_and(|value| value <= Decimal::ZERO) {
+        errors.add(
+            "quantityClaimed",
+            "must_be_positive",
             "quantityClaimed must be greater than 0",
-        ));
+        );
+    }
+
+    let listing_id = errors.capture(parse_uuid(&payload.listing_id, "listingId"));
+    let request_id = errors.capture(parse_optional_uuid(
+        payload.request_id.as_deref(),
+        "requestId",
+    ));
+
+    let request_quantity = payload
+        .request_quantity
+        .and_then(|value| errors.capture(quantity::validate(value, "requestQuantity")));
+    if request_quantity.is_some_and(|value| value <= Decimal::ZERO) {
+        errors.add(
+            "requestQuantity",
+            "must_be_positive",
+            "requestQuantity must be greater than 0",
+        );
+    }
+    if payload.request_id.is_none() {
+        if payload.request_quantity.is_some() {
+            errors.add(
+                "requestQuantity",
+                "request_required",
+                "requestQuantity needs a requestId",
+            );
+        }
+        if payload.allow_unit_mismatch {
+            errors.add(
+                "allowUnitMismatch",
+                "request_required",
+                "allowUnitMismatch needs a requestId",
+            );
+        }
     }
 
+    errors.into_result()?;
+    let (Some(listing_id), Some(request_id), Some(quantity_claimed)) =
+        (listing_id, request_id, quantity_claimed)
+    else {
+        return Err(ApiError::internal(
+            "claim validation passed with missing fields",
+        ));
+    };
+
     Ok(NormalizedCreateClaimInput {
-        listing_id: parse_uuid(&payload.listing_id, "listingId")?,
-        request_id: parse_optional_uuid(payload.request_id.as_deref(), "requestId")?,
-        quantity_claimed: payload.quantity_claimed,
+        listing_id,
+        request_id,
+        quantity_claimed,
         notes: normalize_optional_text(payload.notes.as_deref()),
+        delivery_requested: payload.delivery_requested,
+        request_quantity,
+        allow_unit_mismatch: payload.allow_unit_mismatch,
     })
 }
 
+/// Checks the request a claim links to and returns how much of it the claim
+/// covers, in the request's unit.
 async fn validate_request_linkage(
     tx: &Transaction<'_>,
+    normalized: &NormalizedCreateClaimInput,
     request_id: Uuid,
     claimer_id: Uuid,
-    listing_crop_id: Uuid,
-) -> Result<(), lambda_http::Error> {
+    listing_crop_id: Option<Uuid>,
+    listing_unit: Option<&str>,
+) -> Result<Option<Decimal>, ApiError> {
     let request_row = tx
-        .query_opt(
+        .query_opt_timed(
+            "claim::validate_request_linkage",
             "
-            select user_id, crop_id, status::text as status
+            select user_id, crop_id, unit, status::text as status
             from requests
             where id = $1
               and deleted_at is null
             ",
             &[&request_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let Some(request) = request_row else {
-        return Err(lambda_http::Error::from("Request not found"));
+        return Err(ApiError::not_found(
+            "request_not_found",
+            "Request not found",
+        ));
     };
 
     let request_owner_id: Uuid = request.get("user_id");
@@ -355,40 +583,154 @@ async fn validate_request_linkage(
     let request_status: String = request.get("status");
 
     if request_owner_id != claimer_id {
-        return Err(lambda_http::Error::from(
+        return Err(ApiError::forbidden(
+            "request_not_owned",
             "Forbidden: requestId must belong to the claimer",
         ));
     }
 
     if !is_linkable_request_status(&request_status) {
-        return Err(lambda_http::Error::from(
+        return Err(ApiError::invalid_field(
+            "requestId",
+            "request_not_open",
             "requestId must reference an open request",
         ));
     }
 
-    if request_crop_id != listing_crop_id {
-        return Err(lambda_http::Error::from(
+    if Some(request_crop_id) != listing_crop_id {
+        return Err(ApiError::invalid_field(
+            "requestId",
+            "request_crop_mismatch",
             "requestId crop must match listing crop",
         ));
     }
 
+    resolve_request_quantity(normalized, listing_unit, request.get("unit"))
+}
+
+/// Puts the claimed quantity in the request's unit. Units the catalog cannot
+/// relate need the claimer to give `requestQuantity` themselves or to link
+/// anyway with `allowUnitMismatch`.
+fn resolve_request_quantity(
+    normalized: &NormalizedCreateClaimInput,
+    listing_unit: Option<&str>,
+    request_unit: Option<&str>,
+) -> Result<Option<Decimal>, ApiError> {
+    let explicit = normalized.request_quantity;
+    match units::compare(listing_unit, request_unit) {
+        UnitMatch::Unspecified => Ok(explicit),
+        UnitMatch::Same => Ok(Some(explicit.unwrap_or(normalized.quantity_claimed))),
+        UnitMatch::Convertible(factor) => match explicit {
+            Some(value) => Ok(Some(value)),
+            None => quantity::validate(
+                units::convert(normalized.quantity_claimed, factor),
+                "requestQuantity",
+            )
+            .map(Some),
+        },
+        UnitMatch::Incompatible | UnitMatch::Unknown => {
+            if explicit.is_none() && !normalized.allow_unit_mismatch {
+                return Err(ApiError::invalid_field(
+                    "requestQuantity",
+                    "request_unit_mismatch",
+                    format!(
+                        "Listing is measured in {} but the request in {}; give requestQuantity in the request's unit or set allowUnitMismatch",
+                        listing_unit.unwrap_or_default(),
+                        request_unit.unwrap_or_default(),
+                    ),
+                ));
+            }
+            Ok(explicit)
+        }
+    }
+}
+
+/// Applies the grower's share radius when they have opted into enforcing it.
+/// Distance runs from the listing's location, or the grower's address when
+/// the listing has none, to the claimer's gatherer profile location.
+async fn check_share_radius(
+    tx: &Transaction<'_>,
+    listing_id: Uuid,
+    claimer_id: Uuid,
+) -> Result<(), ApiError> {
+    let row = tx
+        .query_opt_timed(
+            "claim::check_share_radius",
+            "
+            select gp.share_radius_km,
+                   coalesce(l.lat, gp.lat) as listing_lat,
+                   coalesce(l.lng, gp.lng) as listing_lng,
+                   ga.lat as claimer_lat, ga.lng as claimer_lng
+            from surplus_listings l
+            join grower_profiles gp on gp.user_id = l.user_id
+            left join gatherer_profiles ga on ga.user_id = $2
+            where l.id = $1
+              and gp.enforce_share_radius
+            ",
+            &[&listing_id, &claimer_id],
+        )
+        .await?;
+
+    let Some(row) = row else {
+        return Ok(());
+    };
+
+    let point = |lat: &str, lng: &str| {
+        row.get::<_, Option<f64>>(lat)
+            .zip(row.get::<_, Option<f64>>(lng))
+    };
+    evaluate_share_radius(
+        row.get("share_radius_km"),
+        point("listing_lat", "listing_lng"),
+        point("claimer_lat", "claimer_lng"),
+    )
+}
+
+/// A listing with no location anywhere cannot be measured from, so it stays
+/// claimable; a claimer without a location cannot show they are in range.
+fn evaluate_share_radius(
+    share_radius_km: f64,
+    listing: Option<(f64, f64)>,
+    claimer: Option<(f64, f64)>,
+) -> Result<(), ApiError> {
+    let Some((listing_lat, listing_lng)) = listing else {
+        return Ok(());
+    };
+    let Some((claimer_lat, claimer_lng)) = claimer else {
+        return Err(ApiError::forbidden(
+            "share_radius_location_required",
+            "Forbidden: Set a gatherer profile location to claim from this grower",
+        ));
+    };
+
+    if location::haversine_km(listing_lat, listing_lng, claimer_lat, claimer_lng) > share_radius_km
+    {
+        return Err(ApiError::forbidden(
+            "outside_share_radius",
+            "Forbidden: Your location is outside this grower's share radius",
+        ));
+    }
+
     Ok(())
 }
 
+/// A co-manager of the owner's garden acts on claims as the owner would.
 fn determine_actor_role(
     actor_user_id: Uuid,
     claimer_id: Uuid,
     listing_owner_id: Uuid,
-) -> Result<ClaimActorRole, lambda_http::Error> {
+    actor_co_manages: bool,
+) -> Result<ClaimActorRole, ApiError> {
     if actor_user_id == claimer_id {
         return Ok(ClaimActorRole::Claimer);
     }
 
-    if actor_user_id == listing_owner_id {
+    if actor_user_id == listing_owner_id || actor_co_manages {
         return Ok(ClaimActorRole::ListingOwner);
     }
 
-    Err(lambda_http::Error::from(
+    Err(ApiError::forbidden(
+        "not_claim_participant",
         "Forbidden: You are not a participant in this claim",
     ))
 }
@@ -397,7 +739,7 @@ fn evaluate_transition(
     current: ClaimStatus,
     target: ClaimStatus,
     actor_role: ClaimActorRole,
-) -> Result<TransitionDecision, lambda_http::Error> {
+) -> Result<TransitionDecision, ApiError> {
     if current == target {
         return Ok(TransitionDecision {
             quantity_adjustment: ListingQuantityAdjustment::None,
@@ -410,7 +752,8 @@ fn evaluate_transition(
     match (current, target) {
         (ClaimStatus::Pending, ClaimStatus::Confirmed) => {
             if actor_role != ClaimActorRole::ListingOwner {
-                return Err(lambda_http::Error::from(
+                return Err(ApiError::forbidden(
+                    "listing_owner_only",
                     "Forbidden: Only listing owner can confirm a pending claim",
                 ));
             }
@@ -441,7 +784,8 @@ fn evaluate_transition(
         }),
         (ClaimStatus::Confirmed, ClaimStatus::NoShow) => {
             if actor_role != ClaimActorRole::ListingOwner {
-                return Err(lambda_http::Error::from(
+                return Err(ApiError::forbidden(
+                    "listing_owner_only",
                     "Forbidden: Only listing owner can mark no_show",
                 ));
             }
@@ -453,58 +797,106 @@ fn evaluate_transition(
                 stamp_cancelled_at: true,
             })
         }
-        _ => Err(lambda_http::Error::from(format!(
-            "Invalid claim transition from '{}' to '{}'",
-            current.as_db_value(),
-            target.as_db_value()
-        ))),
+        _ => Err(ApiError::invalid_field(
+            "status",
+            "invalid_transition",
+            format!(
+                "Invalid claim transition from '{}' to '{}'",
+                current.as_db_value(),
+                target.as_db_value()
+            ),
+        )),
     }
 }
 
+/// Completing a claim settles its delivery: an accepted run is marked
+/// delivered and an offer nobody accepted is cancelled. Cancelling or a
+/// no-show cancels any delivery still in progress.
+const fn delivery_sync_for(target: ClaimStatus) -> DeliverySync {
+    match target {
+        ClaimStatus::Completed => DeliverySync::Settle,
+        ClaimStatus::Cancelled | ClaimStatus::NoShow => DeliverySync::Cancel,
+        ClaimStatus::Pending | ClaimStatus::Confirmed => DeliverySync::None,
+    }
+}
+
+async fn sync_delivery_if_needed(
+    tx: &Transaction<'_>,
+    claim_id: Uuid,
+    sync: DeliverySync,
+) -> Result<(), ApiError> {
+    let sql = match sync {
+        DeliverySync::None => return Ok(()),
+        DeliverySync::Settle => {
+            "
+            update claim_deliveries
+            set status = case when status = 'offered' then 'cancelled' else 'delivered' end,
+                delivered_at = case when status = 'offered' then null else now() end,
+                cancelled_at = case when status = 'offered' then now() else null end
+            where claim_id = $1
+              and status in ('offered', 'accepted', 'picked_up')
+            "
+        }
+        DeliverySync::Cancel => {
+            "
+            update claim_deliveries
+            set status = 'cancelled',
+                cancelled_at = now()
+            where claim_id = $1
+              and status in ('offered', 'accepted', 'picked_up')
+            "
+        }
+    };
+
+    tx.execute_timed("claim::sync_delivery_if_needed", sql, &[&claim_id])
+        .await?;
+    Ok(())
+}
+
 async fn adjust_listing_quantity_if_needed(
     tx: &Transaction<'_>,
     listing_id: Uuid,
-    quantity_claimed: f64,
+    quantity_claimed: Decimal,
     adjustment: ListingQuantityAdjustment,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     match adjustment {
         ListingQuantityAdjustment::None => Ok(()),
         ListingQuantityAdjustment::Decrement => {
             let updated_rows = tx
-                .execute(
+                .execute_timed("claim::adjust_listing_quantity_if_needed", 
                     "
                     update surplus_listings
                     set quantity_remaining = case
                             when quantity_remaining is null then null
-                            else quantity_remaining - $1::double precision
+                            else quantity_remaining - $1::numeric
                         end,
                         status = case
-                            when quantity_remaining is not null and quantity_remaining - $1::double precision <= 0
+                            when quantity_remaining is not null and quantity_remaining - $1::numeric <= 0
                                 then 'claimed'::listing_status
                             else status
                         end
                     where id = $2
                       and deleted_at is null
-                      and (quantity_remaining is null or quantity_remaining >= $1::double precision)
+                      and (quantity_remaining is null or quantity_remaining >= $1::numeric)
                     ",
                     &[&quantity_claimed, &listing_id],
                 )
-                .await
-                .map_err(|error| db_error(&error))?;
+                .await?;
 
             if updated_rows == 0 {
-                return Err(lambda_http::Error::from("Insufficient quantity remaining"));
+                return Err(insufficient_quantity());
             }
 
             Ok(())
         }
         ListingQuantityAdjustment::Increment => {
-            tx.execute(
+            tx.execute_timed(
+                "claim::adjust_listing_quantity_if_needed",
                 "
                 update surplus_listings
                 set quantity_remaining = case
                         when quantity_remaining is null then null
-                        else quantity_remaining + $1::double precision
+                        else quantity_remaining + $1::numeric
                     end,
                     status = case
                         when status = 'claimed'::listing_status then 'active'::listing_status
@@ -515,18 +907,79 @@ async fn adjust_listing_quantity_if_needed(
                 ",
                 &[&quantity_claimed, &listing_id],
             )
-            .await
-            .map_err(|error| db_error(&error))?;
+            .await?;
 
             Ok(())
         }
     }
 }
 
-fn require_claim_transition_user_type(
-    user_type: Option<&UserType>,
-) -> Result<(), lambda_http::Error> {
-    require_participant_user_type(user_type)
+/// Releases this listing's holds that have outlived their expiry, which the
+/// auto-cancel worker would otherwise release on its next run, and returns
+/// the quantity still reserved. The caller holds the listing row lock.
+async fn release_expired_holds(
+    tx: &Transaction<'_>,
+    listing_id: Uuid,
+) -> Result<Decimal, ApiError> {
+    let row = tx
+        .query_one_timed(
+            "claim::release_expired_holds",
+            "
+            with expired as (
+                update claims
+                set hold_expires_at = null
+                where listing_id = $1
+                  and status = 'pending'::claim_status
+                  and hold_expires_at <= now()
+                returning quantity_claimed
+            )
+            update surplus_listings
+            set reserved_quantity = greatest(
+                    reserved_quantity - coalesce((select sum(quantity_claimed) from expired), 0),
+                    0
+                )
+            where id = $1
+            returning reserved_quantity
+            ",
+            &[&listing_id],
+        )
+        .await?;
+    Ok(row.get("reserved_quantity"))
+}
+
+/// Adds `delta` (negative to release) to the listing's reserved quantity.
+async fn adjust_reserved_quantity(
+    tx: &Transaction<'_>,
+    listing_id: Uuid,
+    delta: Decimal,
+) -> Result<(), ApiError> {
+    tx.execute_timed(
+        "claim::adjust_reserved_quantity",
+        "
+        update surplus_listings
+        set reserved_quantity = greatest(reserved_quantity + $1::numeric, 0)
+        where id = $2
+        ",
+        &[&delta, &listing_id],
+    )
+    .await?;
+    Ok(())
+}
+
+/// `None` when holds are turned off.
+fn hold_expiry(now: DateTime<Utc>, hold: std::time::Duration) -> Option<DateTime<Utc>> {
+    if hold.is_zero() {
+        return None;
+    }
+    chrono::Duration::from_std(hold)
+        .ok()
+        .and_then(|hold| now.checked_add_signed(hold))
+}
+
+/// A pending claim's hold ends with the grower's first decision on it, or
+/// with the claimer withdrawing it.
+fn releases_hold(current: ClaimStatus, target: ClaimStatus) -> bool {
+    current == ClaimStatus::Pending && target != ClaimStatus::Pending
 }
 
 fn is_claimable_listing_status(status: &str) -> bool {
@@ -537,18 +990,22 @@ fn is_linkable_request_status(status: &str) -> bool {
     status == "open"
 }
 
-fn parse_claim_status(value: &str) -> Result<ClaimStatus, lambda_http::Error> {
+fn parse_claim_status(value: &str) -> Result<ClaimStatus, ApiError> {
     match value {
         "pending" => Ok(ClaimStatus::Pending),
         "confirmed" => Ok(ClaimStatus::Confirmed),
         "completed" => Ok(ClaimStatus::Completed),
         "cancelled" => Ok(ClaimStatus::Cancelled),
         "no_show" => Ok(ClaimStatus::NoShow),
-        _ => Err(lambda_http::Error::from(format!(
-            "Invalid claim status '{}'. Allowed values: {}",
-            value,
-            ALLOWED_CLAIM_STATUSES.join(", ")
-        ))),
+        _ => Err(ApiError::invalid_field(
+            "status",
+            "invalid_enum",
+            format!(
+                "Invalid claim status '{}'. Allowed values: {}",
+                value,
+                ALLOWED_CLAIM_STATUSES.join(", ")
+            ),
+        )),
     }
 }
 
@@ -564,32 +1021,10 @@ impl ClaimStatus {
     }
 }
 
-fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, lambda_http::Error> {
-    Uuid::parse_str(value)
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid UUID")))
-}
-
-fn parse_optional_uuid(
-    value: Option<&str>,
-    field_name: &str,
-) -> Result<Option<Uuid>, lambda_http::Error> {
+fn parse_optional_uuid(value: Option<&str>, field_name: &str) -> Result<Option<Uuid>, ApiError> {
     value.map_or(Ok(None), |v| parse_uuid(v, field_name).map(Some))
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
 fn normalize_optional_text(value: Option<&str>) -> Option<String> {
     value.and_then(|text| {
         let trimmed = text.trim();
@@ -610,9 +1045,14 @@ fn row_to_claim_response(row: &Row, listing_owner_id: Uuid) -> ClaimResponse {
             .map(|id| id.to_string()),
         claimer_id: row.get::<_, Uuid>("claimer_id").to_string(),
         listing_owner_id: listing_owner_id.to_string(),
+        crop_name: row.get("crop_name"),
+        variety_name: row.get("variety_name"),
         quantity_claimed: row.get("quantity_claimed"),
+        request_quantity: row.get("request_quantity"),
         status: row.get("status"),
         notes: row.get("notes"),
+        delivery_requested: row.get("delivery_requested"),
+        delivery_status: row.get("delivery_status"),
         claimed_at: row.get::<_, DateTime<Utc>>("claimed_at").to_rfc3339(),
         confirmed_at: row
             .get::<_, Option<DateTime<Utc>>>("confirmed_at")
@@ -623,6 +1063,10 @@ fn row_to_claim_response(row: &Row, listing_owner_id: Uuid) -> ClaimResponse {
         cancelled_at: row
             .get::<_, Option<DateTime<Utc>>>("cancelled_at")
             .map(|value| value.to_rfc3339()),
+        hold_expires_at: row
+            .get::<_, Option<DateTime<Utc>>>("hold_expires_at")
+            .map(|value| value.to_rfc3339()),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
     }
 }
 
@@ -631,43 +1075,19 @@ async fn emit_claim_event(
     claim: &ClaimResponse,
     correlation_id: &str,
 ) -> Result<(), lambda_http::Error> {
-    let event_bus_name = std::env::var("EVENT_BUS_NAME").unwrap_or_else(|_| "default".to_string());
-
-    let detail = serde_json::json!({
-        "claimId": claim.id,
-        "listingId": claim.listing_id,
-        "requestId": claim.request_id,
-        "claimerId": claim.claimer_id,
-        "listingOwnerId": claim.listing_owner_id,
-        "status": claim.status,
-        "correlationId": correlation_id,
-        "occurredAt": Utc::now().to_rfc3339(),
-    });
-
-    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
-    let client = aws_sdk_eventbridge::Client::new(&config);
-
-    let entry = PutEventsRequestEntry::builder()
-        .event_bus_name(event_bus_name)
-        .source("community-garden.api")
-        .detail_type(detail_type)
-        .detail(detail.to_string())
-        .build();
-
-    let response = client
-        .put_events()
-        .entries(entry)
-        .send()
-        .await
-        .map_err(|e| lambda_http::Error::from(format!("Failed to emit claim event: {e}")))?;
-
-    if response.failed_entry_count() > 0 {
-        return Err(lambda_http::Error::from(
-            "Failed to emit claim event: one or more entries were rejected",
-        ));
-    }
+    let detail = ClaimEventDetail {
+        schema_version: events::EVENT_SCHEMA_VERSION,
+        claim_id: claim.id.clone(),
+        listing_id: claim.listing_id.clone(),
+        request_id: claim.request_id.clone(),
+        claimer_id: claim.claimer_id.clone(),
+        listing_owner_id: claim.listing_owner_id.clone(),
+        status: claim.status.clone(),
+        correlation_id: correlation_id.to_string(),
+        occurred_at: Utc::now().to_rfc3339(),
+    };
 
-    Ok(())
+    events::publish(detail_type, &detail).await
 }
 
 async fn emit_claim_event_best_effort(
@@ -686,31 +1106,8 @@ async fn emit_claim_event_best_effort(
     }
 }
 
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-pub fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(
-        status,
-        &ErrorResponse {
-            error: message.to_string(),
-        },
-    )
+fn insufficient_quantity() -> ApiError {
+    ApiError::conflict("insufficient_quantity", "Insufficient quantity remaining")
 }
 
 #[cfg(test)]
@@ -722,15 +1119,65 @@ mod tests {
         CreateClaimRequest {
             listing_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
             request_id: Some("3c861fd9-69eb-42f3-ab57-9ef8f85eb6da".to_string()),
-            quantity_claimed: 3.5,
+            quantity_claimed: Decimal::new(35, 1),
             notes: Some("Can pick up tomorrow".to_string()),
+            delivery_requested: false,
+            request_quantity: None,
+            allow_unit_mismatch: false,
         }
     }
 
+    #[test]
+    fn resolve_request_quantity_converts_or_requires_an_override() {
+        let mut normalized = normalize_create_payload(&valid_create_payload()).unwrap();
+        assert_eq!(
+            resolve_request_quantity(&normalized, Some("lb"), Some("lbs")).unwrap(),
+            Some(Decimal::new(35, 1))
+        );
+        assert_eq!(
+            resolve_request_quantity(&normalized, Some("kg"), Some("lb")).unwrap(),
+            Some(Decimal::new(7716, 3))
+        );
+        assert_eq!(
+            resolve_request_quantity(&normalized, None, Some("lb")).unwrap(),
+            None
+        );
+
+        let error = resolve_request_quantity(&normalized, Some("lb"), Some("dozen")).unwrap_err();
+        assert_eq!(error.error_code(), "request_unit_mismatch");
+
+        normalized.request_quantity = Some(Decimal::new(2, 0));
+        assert_eq!(
+            resolve_request_quantity(&normalized, Some("lb"), Some("dozen")).unwrap(),
+            Some(Decimal::new(2, 0))
+        );
+
+        normalized.request_quantity = None;
+        normalized.allow_unit_mismatch = true;
+        assert_eq!(
+            resolve_request_quantity(&normalized, Some("bag"), Some("dozen")).unwrap(),
+            None
+        );
+    }
+
+    #[test]
+    fn normalize_create_payload_requires_request_for_unit_fields() {
+        let mut payload = valid_create_payload();
+        payload.request_id = None;
+        payload.request_quantity = Some(Decimal::ONE);
+        payload.allow_unit_mismatch = true;
+        let error = normalize_create_payload(&payload).unwrap_err();
+        assert_eq!(error.error_code(), "validation_failed");
+
+        let mut payload = valid_create_payload();
+        payload.request_quantity = Some(Decimal::ZERO);
+        assert!(normalize_create_payload(&payload).is_err());
+    }
+
     #[test]
     fn normalize_create_payload_accepts_valid_input() {
         let normalized = normalize_create_payload(&valid_create_payload()).unwrap();
-        assert!((normalized.quantity_claimed - 3.5).abs() < f64::EPSILON);
+        assert_eq!(normalized.quantity_claimed, Decimal::new(35, 1));
         assert!(normalized.request_id.is_some());
         assert_eq!(normalized.notes.as_deref(), Some("Can pick up tomorrow"));
     }
@@ -738,34 +1185,40 @@ mod tests {
     #[test]
     fn normalize_create_payload_rejects_non_positive_quantity() {
         let mut payload = valid_create_payload();
-        payload.quantity_claimed = 0.0;
+        payload.quantity_claimed = Decimal::ZERO;
         let result = normalize_create_payload(&payload);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("quantityClaimed"));
     }
 
     #[test]
-    fn normalize_create_payload_trims_blank_notes() {
+    fn normalize_create_payload_rejects_excess_precision() {
         let mut payload = valid_create_payload();
-        payload.notes = Some("   ".to_string());
-        let normalized = normalize_create_payload(&payload).unwrap();
-        assert_eq!(normalized.notes, None);
+        payload.quantity_claimed = Decimal::new(12_345, 4);
+        let error = normalize_create_payload(&payload).unwrap_err();
+        assert_eq!(error.error_code(), "too_many_decimal_places");
     }
 
     #[test]
-    fn require_claim_transition_user_type_accepts_grower_and_gatherer() {
-        assert!(require_claim_transition_user_type(Some(&UserType::Grower)).is_ok());
-        assert!(require_claim_transition_user_type(Some(&UserType::Gatherer)).is_ok());
+    fn claim_payload_accepts_numeric_and_string_quantities() {
+        let from_number: CreateClaimRequest = serde_json::from_str(
+            r#"{"listingId":"5df666d4-f6b1-4e6f-97d6-321e531ad7ca","quantityClaimed":0.1}"#,
+        )
+        .unwrap();
+        let from_string: CreateClaimRequest = serde_json::from_str(
+            r#"{"listingId":"5df666d4-f6b1-4e6f-97d6-321e531ad7ca","quantityClaimed":"0.1"}"#,
+        )
+        .unwrap();
+        assert_eq!(from_number.quantity_claimed, Decimal::new(1, 1));
+        assert_eq!(from_string.quantity_claimed, Decimal::new(1, 1));
     }
 
     #[test]
-    fn require_claim_transition_user_type_rejects_missing_type() {
-        let result = require_claim_transition_user_type(None);
-        assert!(result.is_err());
-        assert!(result
-            .unwrap_err()
-            .to_string()
-            .contains("User type not set"));
+    fn normalize_create_payload_trims_blank_notes() {
+        let mut payload = valid_create_payload();
+        payload.notes = Some("   ".to_string());
+        let normalized = normalize_create_payload(&payload).unwrap();
+        assert_eq!(normalized.notes, None);
     }
 
     #[test]
@@ -814,7 +1267,7 @@ mod tests {
     fn determine_actor_role_identifies_claimer() {
         let actor = Uuid::parse_str("6b7a6e9d-e31d-4ac2-b688-15f0490adf9b").unwrap();
         let owner = Uuid::parse_str("b630af9b-6de5-44cd-9d83-d37df86ce2ef").unwrap();
-        let role = determine_actor_role(actor, actor, owner).unwrap();
+        let role = determine_actor_role(actor, actor, owner, false).unwrap();
         assert_eq!(role, ClaimActorRole::Claimer);
     }
 
@@ -822,7 +1275,16 @@ mod tests {
     fn determine_actor_role_identifies_listing_owner() {
         let claimer = Uuid::parse_str("6b7a6e9d-e31d-4ac2-b688-15f0490adf9b").unwrap();
         let owner = Uuid::parse_str("b630af9b-6de5-44cd-9d83-d37df86ce2ef").unwrap();
-        let role = determine_actor_role(owner, claimer, owner).unwrap();
+        let role = determine_actor_role(owner, claimer, owner, false).unwrap();
+        assert_eq!(role, ClaimActorRole::ListingOwner);
+    }
+
+    #[test]
+    fn determine_actor_role_lets_co_managers_act_as_owner() {
+        let co_manager = Uuid::parse_str("d6d8958f-bfd8-4a9a-a18f-793fbe6746d5").unwrap();
+        let claimer = Uuid::parse_str("6b7a6e9d-e31d-4ac2-b688-15f0490adf9b").unwrap();
+        let owner = Uuid::parse_str("b630af9b-6de5-44cd-9d83-d37df86ce2ef").unwrap();
+        let role = determine_actor_role(co_manager, claimer, owner, true).unwrap();
         assert_eq!(role, ClaimActorRole::ListingOwner);
     }
 
@@ -831,7 +1293,7 @@ mod tests {
         let actor = Uuid::parse_str("d6d8958f-bfd8-4a9a-a18f-793fbe6746d5").unwrap();
         let claimer = Uuid::parse_str("6b7a6e9d-e31d-4ac2-b688-15f0490adf9b").unwrap();
         let owner = Uuid::parse_str("b630af9b-6de5-44cd-9d83-d37df86ce2ef").unwrap();
-        let result = determine_actor_role(actor, claimer, owner);
+        let result = determine_actor_role(actor, claimer, owner, false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Forbidden"));
     }
@@ -997,4 +1459,73 @@ mod tests {
         assert!(!result.stamp_completed_at);
         assert!(!result.stamp_cancelled_at);
     }
+
+    #[test]
+    fn delivery_sync_follows_claim_outcome() {
+        assert_eq!(
+            delivery_sync_for(ClaimStatus::Completed),
+            DeliverySync::Settle
+        );
+        assert_eq!(
+            delivery_sync_for(ClaimStatus::Cancelled),
+            DeliverySync::Cancel
+        );
+        assert_eq!(delivery_sync_for(ClaimStatus::NoShow), DeliverySync::Cancel);
+        assert_eq!(
+            delivery_sync_for(ClaimStatus::Confirmed),
+            DeliverySync::None
+        );
+    }
+
+    #[test]
+    fn hold_expiry_is_off_for_a_zero_hold() {
+        let now = Utc::now();
+        assert_eq!(hold_expiry(now, std::time::Duration::ZERO), None);
+        assert_eq!(
+            hold_expiry(now, std::time::Duration::from_secs(30 * 60)),
+            Some(now + chrono::Duration::minutes(30))
+        );
+    }
+
+    #[test]
+    fn holds_end_when_a_pending_claim_moves_on() {
+        assert!(releases_hold(ClaimStatus::Pending, ClaimStatus::Confirmed));
+        assert!(releases_hold(ClaimStatus::Pending, ClaimStatus::Cancelled));
+        assert!(!releases_hold(ClaimStatus::Pending, ClaimStatus::Pending));
+        assert!(!releases_hold(
+            ClaimStatus::Confirmed,
+            ClaimStatus::Cancelled
+        ));
+    }
+
+    #[test]
+    fn claim_payload_defaults_delivery_requested_to_false() {
+        let payload: CreateClaimRequest = serde_json::from_str(
+            r#"{"listingId":"5df666d4-f6b1-4e6f-97d6-321e531ad7ca","quantityClaimed":1}"#,
+        )
+        .unwrap();
+        assert!(!payload.delivery_requested);
+    }
+
+    #[test]
+    fn share_radius_rejects_distant_or_unlocated_claimers() {
+        let listing = Some((37.7749, -122.4194));
+        // Oakland is roughly 13 km from San Francisco.
+        let oakland = Some((37.8044, -122.2712));
+
+        assert!(evaluate_share_radius(20.0, listing, oakland).is_ok());
+        assert_eq!(
+            evaluate_share_radius(5.0, listing, oakland)
+                .unwrap_err()
+                .error_code(),
+            "outside_share_radius"
+        );
+        assert_eq!(
+            evaluate_share_radius(5.0, listing, None)
+                .unwrap_err()
+                .error_code(),
+            "share_radius_location_required"
+        );
+        assert!(evaluate_share_radius(5.0, None, None).is_ok());
+    }
 }
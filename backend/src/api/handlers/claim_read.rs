@@ -1,7 +1,8 @@
-use crate::auth::{extract_auth_context_with_fallback, require_participant_user_type};
-use crate::db;
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
 use crate::handlers::claim::ClaimResponse;
-use crate::models::crop::ErrorResponse;
+use crate::http_util::{json_response, parse_uuid, percent_decode, request_locale};
 use chrono::{DateTime, Utc};
 use lambda_http::{Body, Request, Response};
 use serde::Serialize;
@@ -12,15 +13,110 @@ use uuid::Uuid;
 const ALLOWED_CLAIM_STATUSES: [&str; 5] =
     ["pending", "confirmed", "completed", "cancelled", "no_show"];
 
+/// Confirmed claims the caller still has to collect, soonest first, or
+/// finished ones, most recent first.
+const MY_PICKUPS: &str = "
+    select c.id, c.listing_id, l.user_id as listing_owner_id,
+           coalesce(l.title, cr.common_name, initcap(replace(l.listing_kind::text, '_', ' '))) as title,
+           l.crop_id, cr.common_name as crop_name,
+           c.quantity_claimed::text as quantity_claimed, l.unit,
+           c.status::text as status,
+           l.available_start, l.available_end,
+           l.pickup_location_text, l.effective_pickup_address,
+           l.pickup_disclosure_policy::text as pickup_disclosure_policy,
+           l.pickup_notes,
+           c.delivery_requested, d.status as delivery_status,
+           c.confirmed_at, c.completed_at
+    from claims c
+    join surplus_listings l on l.id = c.listing_id
+    left join crops cr on cr.id = l.crop_id
+    left join claim_deliveries d on d.claim_id = c.id
+    where c.claimer_id = $1
+      and l.deleted_at is null
+      and (
+        ($2 = 'upcoming' and c.status = 'confirmed')
+        or ($2 = 'history' and c.status in ('completed', 'no_show'))
+      )
+    order by
+      case when $2 = 'upcoming'
+        then coalesce(l.available_start, c.confirmed_at) end asc nulls last,
+      case when $2 = 'history'
+        then coalesce(c.completed_at, c.confirmed_at, c.claimed_at) end desc,
+      c.id desc
+    limit $3 offset $4
+";
+
 #[derive(Debug)]
 struct ListClaimsQuery {
     listing_id: Option<Uuid>,
     request_id: Option<Uuid>,
     status: Option<String>,
+    /// Switches to the change feed: claims changed at or after this time,
+    /// oldest change first, paged with `after_id` instead of an offset.
+    updated_since: Option<DateTime<Utc>>,
+    after_id: Option<Uuid>,
+    limit: i64,
+    offset: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PickupView {
+    Upcoming,
+    History,
+}
+
+impl PickupView {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Upcoming => "upcoming",
+            Self::History => "history",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct ListPickupsQuery {
+    view: PickupView,
     limit: i64,
     offset: i64,
 }
 
+/// A claim joined with what the gatherer needs to go and collect it.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PickupItem {
+    pub claim_id: String,
+    pub listing_id: String,
+    pub listing_owner_id: String,
+    pub title: String,
+    pub crop_id: Option<String>,
+    pub crop_name: Option<String>,
+    pub quantity_claimed: String,
+    pub unit: Option<String>,
+    pub status: String,
+    pub available_start: Option<String>,
+    pub available_end: Option<String>,
+    pub pickup_location_text: Option<String>,
+    /// Null until the listing's disclosure policy lets the claimer see it.
+    pub pickup_address: Option<String>,
+    pub pickup_notes: Option<String>,
+    pub delivery_requested: bool,
+    pub delivery_status: Option<String>,
+    pub confirmed_at: Option<String>,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListPickupsResponse {
+    pub view: &'static str,
+    pub items: Vec<PickupItem>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ListClaimsResponse {
@@ -29,17 +125,22 @@ pub struct ListClaimsResponse {
     pub offset: i64,
     pub has_more: bool,
     pub next_offset: Option<i64>,
+    /// Change feed only: pass back as `updatedSince` and `afterId` for the
+    /// next page, or for the next poll once `hasMore` is false.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_updated_since: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_after_id: Option<String>,
 }
 
 pub async fn list_claims(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_participant_user_type(auth_context.user_type.as_ref())?;
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let query = parse_list_claims_query(request.uri().query())?;
 
     let client = db::connect().await?;
@@ -48,21 +149,44 @@ pub async fn list_claims(
     let fetch_limit = query.limit + 1;
 
     let rows = client
-        .query(
+        .query_timed(
+            "claim_read::list_claims",
             "
             select c.id, c.listing_id, c.request_id, c.claimer_id,
                    l.user_id as listing_owner_id,
+                   crop_display_name(l.crop_id, $7) as crop_name,
+                   variety_display_name(l.variety_id, $7) as variety_name,
                    c.quantity_claimed::text as quantity_claimed,
-                   c.status::text as status, c.notes,
-                   c.claimed_at, c.confirmed_at, c.completed_at, c.cancelled_at
+                   c.request_quantity::text as request_quantity,
+                   c.status::text as status, c.notes, c.delivery_requested,
+                   d.status as delivery_status,
+                   c.claimed_at, c.confirmed_at, c.completed_at, c.cancelled_at,
+                   c.hold_expires_at, c.updated_at
             from claims c
             inner join surplus_listings l on l.id = c.listing_id
+            left join claim_deliveries d on d.claim_id = c.id
             where l.deleted_at is null
-              and (c.claimer_id = $1 or l.user_id = $1)
+              and (
+                c.claimer_id = $1
+                or l.user_id = $1
+                or exists (
+                  select 1 from grower_co_managers m
+                  where m.owner_id = l.user_id
+                    and m.co_manager_id = $1
+                    and m.status = 'accepted'
+                )
+              )
               and ($2::uuid is null or c.listing_id = $2)
               and ($3::uuid is null or c.request_id = $3)
               and ($4::text is null or c.status::text = $4)
-            order by c.claimed_at desc, c.id desc
+              and (
+                $8::timestamptz is null
+                or (c.updated_at, c.id) > ($8, coalesce($9::uuid, '00000000-0000-0000-0000-000000000000'::uuid))
+              )
+            order by
+              case when $8::timestamptz is not null then c.updated_at end asc,
+              case when $8::timestamptz is not null then c.id end asc,
+              c.claimed_at desc, c.id desc
             limit $5 offset $6
             ",
             &[
@@ -72,13 +196,15 @@ pub async fn list_claims(
                 &query.status,
                 &fetch_limit,
                 &query.offset,
+                &request_locale(request),
+                &query.updated_since,
+                &query.after_id,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let limit = usize::try_from(query.limit)
-        .map_err(|_| lambda_http::Error::from("Invalid limit. Must be between 1 and 100"))?;
+        .map_err(|_| invalid_limit("Invalid limit. Must be between 1 and 100"))?;
     let has_more = rows.len() > limit;
     let items = rows
         .into_iter()
@@ -86,12 +212,19 @@ pub async fn list_claims(
         .map(|row| row_to_claim_response(&row))
         .collect::<Vec<_>>();
 
+    let (next_updated_since, next_after_id) = next_sync_cursor(&query, &items);
     let response = ListClaimsResponse {
         items,
         limit: query.limit,
         offset: query.offset,
         has_more,
-        next_offset: compute_next_offset(query.offset, query.limit, has_more),
+        next_offset: if query.updated_since.is_some() {
+            None
+        } else {
+            compute_next_offset(query.offset, query.limit, has_more)
+        },
+        next_updated_since,
+        next_after_id,
     };
 
     info!(
@@ -100,6 +233,7 @@ pub async fn list_claims(
         listing_id_filter = ?query.listing_id,
         request_id_filter = ?query.request_id,
         status_filter = ?query.status,
+        updated_since = ?query.updated_since,
         limit = query.limit,
         offset = query.offset,
         returned_count = response.items.len(),
@@ -110,10 +244,130 @@ pub async fn list_claims(
     json_response(200, &response)
 }
 
-fn parse_list_claims_query(query: Option<&str>) -> Result<ListClaimsQuery, lambda_http::Error> {
+/// The caller's pickups as a gatherer: confirmed claims still to collect
+/// (`view=upcoming`, the default) or completed and missed ones
+/// (`view=history`), each with its listing's title, crop and pickup details.
+pub async fn list_my_pickups(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let query = parse_list_pickups_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let fetch_limit = query.limit + 1;
+    let rows = client
+        .query_timed(
+            "claim_read::list_my_pickups",
+            MY_PICKUPS,
+            &[&user_id, &query.view.as_str(), &fetch_limit, &query.offset],
+        )
+        .await?;
+
+    let limit = usize::try_from(query.limit)
+        .map_err(|_| invalid_limit("Invalid limit. Must be between 1 and 100"))?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_pickup_item)
+        .collect::<Vec<_>>();
+
+    let response = ListPickupsResponse {
+        view: query.view.as_str(),
+        items,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: compute_next_offset(query.offset, query.limit, has_more),
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        view = response.view,
+        limit = query.limit,
+        offset = query.offset,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        "Listed gatherer pickups"
+    );
+
+    json_response(200, &response)
+}
+
+fn parse_list_pickups_query(query: Option<&str>) -> Result<ListPickupsQuery, ApiError> {
+    let mut view = PickupView::Upcoming;
+    let mut limit: i64 = 20;
+    let mut offset: i64 = 0;
+
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        match key {
+            "view" if !value.is_empty() => {
+                view = match value {
+                    "upcoming" => PickupView::Upcoming,
+                    "history" => PickupView::History,
+                    _ => {
+                        return Err(ApiError::invalid_field(
+                            "view",
+                            "invalid_enum",
+                            format!(
+                                "Invalid pickup view '{value}'. Allowed values: upcoming, history"
+                            ),
+                        ))
+                    }
+                };
+            }
+            "limit" => {
+                limit = value
+                    .parse::<i64>()
+                    .map_err(|_| invalid_limit("Invalid limit. Must be an integer"))?;
+                if !(1..=100).contains(&limit) {
+                    return Err(invalid_limit("Invalid limit. Must be between 1 and 100"));
+                }
+            }
+            "offset" => {
+                offset = value.parse::<i64>().map_err(|_| {
+                    ApiError::invalid_field(
+                        "offset",
+                        "invalid_offset",
+                        "Invalid offset. Must be an integer",
+                    )
+                })?;
+                if offset < 0 {
+                    return Err(ApiError::invalid_field(
+                        "offset",
+                        "invalid_offset",
+                        "Invalid offset. Must be greater than or equal to 0",
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    Ok(ListPickupsQuery {
+        view,
+        limit,
+        offset,
+    })
+}
+
+fn parse_list_claims_query(query: Option<&str>) -> Result<ListClaimsQuery, ApiError> {
     let mut listing_id: Option<Uuid> = None;
     let mut request_id: Option<Uuid> = None;
     let mut status: Option<String> = None;
+    let mut updated_since: Option<DateTime<Utc>> = None;
+    let mut after_id: Option<Uuid> = None;
     let mut limit: i64 = 20;
     let mut offset: i64 = 0;
 
@@ -126,44 +380,52 @@ fn parse_list_claims_query(query: Option<&str>) -> Result<ListClaimsQuery, lambd
             let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
 
             match key {
-                "listingId" => {
-                    if !value.is_empty() {
-                        listing_id = Some(parse_uuid(value, "listingId")?);
-                    }
+                "listingId" if !value.is_empty() => {
+                    listing_id = Some(parse_uuid(value, "listingId")?);
                 }
-                "requestId" => {
-                    if !value.is_empty() {
-                        request_id = Some(parse_uuid(value, "requestId")?);
-                    }
+                "requestId" if !value.is_empty() => {
+                    request_id = Some(parse_uuid(value, "requestId")?);
                 }
-                "status" => {
-                    if !value.is_empty() {
-                        if !ALLOWED_CLAIM_STATUSES.contains(&value) {
-                            return Err(lambda_http::Error::from(format!(
+                "status" if !value.is_empty() => {
+                    if !ALLOWED_CLAIM_STATUSES.contains(&value) {
+                        return Err(ApiError::invalid_field(
+                            "status",
+                            "invalid_enum",
+                            format!(
                                 "Invalid claim status filter '{}'. Allowed values: {}",
                                 value,
                                 ALLOWED_CLAIM_STATUSES.join(", ")
-                            )));
-                        }
-                        status = Some(value.to_string());
+                            ),
+                        ));
                     }
+                    status = Some(value.to_string());
+                }
+                "updatedSince" if !value.is_empty() => {
+                    updated_since = Some(parse_updated_since(value)?);
+                }
+                "afterId" if !value.is_empty() => {
+                    after_id = Some(parse_uuid(value, "afterId")?);
                 }
                 "limit" => {
-                    limit = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid limit. Must be an integer")
-                    })?;
+                    limit = value
+                        .parse::<i64>()
+                        .map_err(|_| invalid_limit("Invalid limit. Must be an integer"))?;
                     if !(1..=100).contains(&limit) {
-                        return Err(lambda_http::Error::from(
-                            "Invalid limit. Must be between 1 and 100",
-                        ));
+                        return Err(invalid_limit("Invalid limit. Must be between 1 and 100"));
                     }
                 }
                 "offset" => {
                     offset = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid offset. Must be an integer")
+                        ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be an integer",
+                        )
                     })?;
                     if offset < 0 {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
                             "Invalid offset. Must be greater than or equal to 0",
                         ));
                     }
@@ -173,20 +435,70 @@ fn parse_list_claims_query(query: Option<&str>) -> Result<ListClaimsQuery, lambd
         }
     }
 
+    if after_id.is_some() && updated_since.is_none() {
+        return Err(ApiError::invalid_field(
+            "afterId",
+            "updated_since_required",
+            "afterId pages the change feed and needs updatedSince",
+        ));
+    }
+    if updated_since.is_some() && offset != 0 {
+        return Err(ApiError::invalid_field(
+            "offset",
+            "invalid_offset",
+            "offset cannot be combined with updatedSince; page with afterId",
+        ));
+    }
+
     Ok(ListClaimsQuery {
         listing_id,
         request_id,
         status,
+        updated_since,
+        after_id,
         limit,
         offset,
     })
 }
 
+fn parse_updated_since(value: &str) -> Result<DateTime<Utc>, ApiError> {
+    let decoded = percent_decode(value, "updatedSince")?;
+    DateTime::parse_from_rfc3339(&decoded)
+        .map(|value| value.with_timezone(&Utc))
+        .map_err(|_| {
+            ApiError::invalid_field(
+                "updatedSince",
+                "invalid_timestamp",
+                "updatedSince must be a valid RFC3339 timestamp",
+            )
+        })
+}
+
+/// Where the change feed resumes: after the last claim returned, or where
+/// the caller already was when nothing changed.
+fn next_sync_cursor(
+    query: &ListClaimsQuery,
+    items: &[ClaimResponse],
+) -> (Option<String>, Option<String>) {
+    let Some(updated_since) = query.updated_since else {
+        return (None, None);
+    };
+    items.last().map_or_else(
+        || {
+            (
+                Some(updated_since.to_rfc3339()),
+                query.after_id.map(|id| id.to_string()),
+            )
+        },
+        |last| (Some(last.updated_at.clone()), Some(last.id.clone())),
+    )
+}
+
 async fn validate_claim_filter_access(
     client: &Client,
     user_id: Uuid,
     query: &ListClaimsQuery,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     if let Some(listing_id) = query.listing_id {
         ensure_listing_filter_access(client, listing_id, user_id).await?;
     }
@@ -202,9 +514,10 @@ async fn ensure_listing_filter_access(
     client: &Client,
     listing_id: Uuid,
     user_id: Uuid,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let listing_owner = client
-        .query_opt(
+        .query_opt_timed(
+            "claim_read::ensure_listing_filter_access",
             "
             select user_id
             from surplus_listings
@@ -213,40 +526,54 @@ async fn ensure_listing_filter_access(
             ",
             &[&listing_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let Some(owner_row) = listing_owner else {
-        return Err(lambda_http::Error::from("Listing not found"));
+        return Err(ApiError::not_found(
+            "listing_not_found",
+            "Listing not found",
+        ));
     };
 
     let listing_owner_id = owner_row.get::<_, Uuid>("user_id");
-    let is_claimer = client
-        .query_one(
+    let access = client
+        .query_one_timed(
+            "claim_read::ensure_listing_filter_access",
             "
             select exists(
                 select 1
                 from claims
                 where listing_id = $1
                   and claimer_id = $2
-            )
+            ) as is_claimer,
+            exists(
+                select 1
+                from grower_co_managers
+                where owner_id = $3
+                  and co_manager_id = $2
+                  and status = 'accepted'
+            ) as co_manages
             ",
-            &[&listing_id, &user_id],
+            &[&listing_id, &user_id, &listing_owner_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?
-        .get::<_, bool>(0);
+        .await?;
 
-    ensure_listing_scope(listing_owner_id, user_id, is_claimer)
+    ensure_listing_scope(
+        listing_owner_id,
+        user_id,
+        access.get("is_claimer"),
+        access.get("co_manages"),
+    )
 }
 
 async fn ensure_request_filter_access(
     client: &Client,
     request_id: Uuid,
     user_id: Uuid,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let request_owner = client
-        .query_opt(
+        .query_opt_timed(
+            "claim_read::ensure_request_filter_access",
             "
             select user_id
             from requests
@@ -255,16 +582,19 @@ async fn ensure_request_filter_access(
             ",
             &[&request_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let Some(owner_row) = request_owner else {
-        return Err(lambda_http::Error::from("Request not found"));
+        return Err(ApiError::not_found(
+            "request_not_found",
+            "Request not found",
+        ));
     };
 
     let request_owner_id = owner_row.get::<_, Uuid>("user_id");
     let is_listing_owner_for_request = client
-        .query_one(
+        .query_one_timed(
+            "claim_read::ensure_request_filter_access",
             "
             select exists(
                 select 1
@@ -277,8 +607,7 @@ async fn ensure_request_filter_access(
             ",
             &[&request_id, &user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?
+        .await?
         .get::<_, bool>(0);
 
     ensure_request_scope(request_owner_id, user_id, is_listing_owner_for_request)
@@ -288,11 +617,13 @@ fn ensure_listing_scope(
     listing_owner_id: Uuid,
     user_id: Uuid,
     is_claimer: bool,
-) -> Result<(), lambda_http::Error> {
-    if listing_owner_id == user_id || is_claimer {
+    co_manages: bool,
+) -> Result<(), ApiError> {
+    if listing_owner_id == user_id || is_claimer || co_manages {
         Ok(())
     } else {
-        Err(lambda_http::Error::from(
+        Err(ApiError::forbidden(
+            "not_claim_participant",
             "Forbidden: You are not permitted to access claims for this listing",
         ))
     }
@@ -302,11 +633,12 @@ fn ensure_request_scope(
     request_owner_id: Uuid,
     user_id: Uuid,
     is_listing_owner_for_request: bool,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     if request_owner_id == user_id || is_listing_owner_for_request {
         Ok(())
     } else {
-        Err(lambda_http::Error::from(
+        Err(ApiError::forbidden(
+            "not_claim_participant",
             "Forbidden: You are not permitted to access claims for this request",
         ))
     }
@@ -320,11 +652,6 @@ const fn compute_next_offset(offset: i64, limit: i64, has_more: bool) -> Option<
     }
 }
 
-fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, lambda_http::Error> {
-    Uuid::parse_str(value)
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid UUID")))
-}
-
 fn row_to_claim_response(row: &Row) -> ClaimResponse {
     ClaimResponse {
         id: row.get::<_, Uuid>("id").to_string(),
@@ -334,9 +661,14 @@ fn row_to_claim_response(row: &Row) -> ClaimResponse {
             .map(|id| id.to_string()),
         claimer_id: row.get::<_, Uuid>("claimer_id").to_string(),
         listing_owner_id: row.get::<_, Uuid>("listing_owner_id").to_string(),
+        crop_name: row.get("crop_name"),
+        variety_name: row.get("variety_name"),
         quantity_claimed: row.get("quantity_claimed"),
+        request_quantity: row.get("request_quantity"),
         status: row.get("status"),
         notes: row.get("notes"),
+        delivery_requested: row.get("delivery_requested"),
+        delivery_status: row.get("delivery_status"),
         claimed_at: row.get::<_, DateTime<Utc>>("claimed_at").to_rfc3339(),
         confirmed_at: row
             .get::<_, Option<DateTime<Utc>>>("confirmed_at")
@@ -347,36 +679,55 @@ fn row_to_claim_response(row: &Row) -> ClaimResponse {
         cancelled_at: row
             .get::<_, Option<DateTime<Utc>>>("cancelled_at")
             .map(|value| value.to_rfc3339()),
+        hold_expires_at: row
+            .get::<_, Option<DateTime<Utc>>>("hold_expires_at")
+            .map(|value| value.to_rfc3339()),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
     }
 }
 
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
+fn row_to_pickup_item(row: &Row) -> PickupItem {
+    let policy: String = row.get("pickup_disclosure_policy");
+    PickupItem {
+        claim_id: row.get::<_, Uuid>("id").to_string(),
+        listing_id: row.get::<_, Uuid>("listing_id").to_string(),
+        listing_owner_id: row.get::<_, Uuid>("listing_owner_id").to_string(),
+        title: row.get("title"),
+        crop_id: row
+            .get::<_, Option<Uuid>>("crop_id")
+            .map(|id| id.to_string()),
+        crop_name: row.get("crop_name"),
+        quantity_claimed: row.get("quantity_claimed"),
+        unit: row.get("unit"),
+        status: row.get("status"),
+        available_start: row
+            .get::<_, Option<DateTime<Utc>>>("available_start")
+            .map(|value| value.to_rfc3339()),
+        available_end: row
+            .get::<_, Option<DateTime<Utc>>>("available_end")
+            .map(|value| value.to_rfc3339()),
+        pickup_location_text: row.get("pickup_location_text"),
+        pickup_address: disclosed_address(&policy, row.get("effective_pickup_address")),
+        pickup_notes: row.get("pickup_notes"),
+        delivery_requested: row.get("delivery_requested"),
+        delivery_status: row.get("delivery_status"),
+        confirmed_at: row
+            .get::<_, Option<DateTime<Utc>>>("confirmed_at")
+            .map(|value| value.to_rfc3339()),
+        completed_at: row
+            .get::<_, Option<DateTime<Utc>>>("completed_at")
+            .map(|value| value.to_rfc3339()),
+    }
 }
 
-fn json_response<T: serde::Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload).map_err(|error| {
-        lambda_http::Error::from(format!("Failed to serialize response: {error}"))
-    })?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|error| lambda_http::Error::from(error.to_string()))
+/// Every pickup is of a confirmed claim, so the address shows unless the
+/// grower holds it back until `after_accepted`, as the schedule feed does.
+fn disclosed_address(policy: &str, address: Option<String>) -> Option<String> {
+    address.filter(|_| policy != "after_accepted")
 }
 
-#[allow(dead_code)]
-fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(
-        status,
-        &ErrorResponse {
-            error: message.to_string(),
-        },
-    )
+fn invalid_limit(message: &str) -> ApiError {
+    ApiError::invalid_field("limit", "invalid_limit", message)
 }
 
 #[cfg(test)]
@@ -384,6 +735,43 @@ fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_h
 mod tests {
     use super::*;
 
+    #[test]
+    fn parse_list_pickups_query_defaults_to_upcoming() {
+        let parsed = parse_list_pickups_query(None).unwrap();
+        assert_eq!(parsed.view, PickupView::Upcoming);
+        assert_eq!(parsed.limit, 20);
+        assert_eq!(parsed.offset, 0);
+
+        let parsed = parse_list_pickups_query(Some("view=history&limit=5&offset=10")).unwrap();
+        assert_eq!(parsed.view, PickupView::History);
+        assert_eq!(parsed.limit, 5);
+        assert_eq!(parsed.offset, 10);
+    }
+
+    #[test]
+    fn parse_list_pickups_query_rejects_unknown_view_and_bad_paging() {
+        let error = parse_list_pickups_query(Some("view=pending")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+        let error = parse_list_pickups_query(Some("limit=101")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_limit");
+        let error = parse_list_pickups_query(Some("offset=-1")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_offset");
+    }
+
+    #[test]
+    fn disclosed_address_follows_the_listing_policy() {
+        let address = Some("12 Elm St".to_string());
+        assert_eq!(
+            disclosed_address("after_confirmed", address.clone()).as_deref(),
+            Some("12 Elm St")
+        );
+        assert_eq!(
+            disclosed_address("immediate", address.clone()).as_deref(),
+            Some("12 Elm St")
+        );
+        assert_eq!(disclosed_address("after_accepted", address), None);
+    }
+
     #[test]
     fn parse_list_claims_query_defaults() {
         let parsed = parse_list_claims_query(None).unwrap();
@@ -414,6 +802,28 @@ mod tests {
         assert_eq!(parsed.offset, 5);
     }
 
+    #[test]
+    fn parse_list_claims_query_reads_the_change_feed_cursor() {
+        let parsed = parse_list_claims_query(Some(
+            "updatedSince=2026-10-01T12%3A00%3A00.250%2B02%3A00&afterId=5df666d4-f6b1-4e6f-97d6-321e531ad7ca",
+        ))
+        .unwrap();
+        assert_eq!(
+            parsed.updated_since.unwrap().to_rfc3339(),
+            "2026-10-01T10:00:00.250+00:00"
+        );
+        assert!(parsed.after_id.is_some());
+
+        let error = parse_list_claims_query(Some("updatedSince=yesterday")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_timestamp");
+        let error = parse_list_claims_query(Some("afterId=5df666d4-f6b1-4e6f-97d6-321e531ad7ca"))
+            .unwrap_err();
+        assert_eq!(error.error_code(), "updated_since_required");
+        let error = parse_list_claims_query(Some("updatedSince=2026-10-01T12:00:00Z&offset=20"))
+            .unwrap_err();
+        assert_eq!(error.error_code(), "invalid_offset");
+    }
+
     #[test]
     fn parse_list_claims_query_rejects_invalid_status() {
         let result = parse_list_claims_query(Some("status=closed"));
@@ -457,7 +867,7 @@ mod tests {
     #[test]
     fn ensure_listing_scope_allows_listing_owner() {
         let user_id = Uuid::parse_str("5df666d4-f6b1-4e6f-97d6-321e531ad7ca").unwrap();
-        let result = ensure_listing_scope(user_id, user_id, false);
+        let result = ensure_listing_scope(user_id, user_id, false, false);
         assert!(result.is_ok());
     }
 
@@ -465,7 +875,15 @@ mod tests {
     fn ensure_listing_scope_allows_claimer() {
         let user_id = Uuid::parse_str("5df666d4-f6b1-4e6f-97d6-321e531ad7ca").unwrap();
         let owner_id = Uuid::parse_str("3c861fd9-69eb-42f3-ab57-9ef8f85eb6da").unwrap();
-        let result = ensure_listing_scope(owner_id, user_id, true);
+        let result = ensure_listing_scope(owner_id, user_id, true, false);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn ensure_listing_scope_allows_co_manager() {
+        let user_id = Uuid::parse_str("5df666d4-f6b1-4e6f-97d6-321e531ad7ca").unwrap();
+        let owner_id = Uuid::parse_str("3c861fd9-69eb-42f3-ab57-9ef8f85eb6da").unwrap();
+        let result = ensure_listing_scope(owner_id, user_id, false, true);
         assert!(result.is_ok());
     }
 
@@ -473,7 +891,7 @@ mod tests {
     fn ensure_listing_scope_rejects_non_participant() {
         let user_id = Uuid::parse_str("5df666d4-f6b1-4e6f-97d6-321e531ad7ca").unwrap();
         let owner_id = Uuid::parse_str("3c861fd9-69eb-42f3-ab57-9ef8f85eb6da").unwrap();
-        let result = ensure_listing_scope(owner_id, user_id, false);
+        let result = ensure_listing_scope(owner_id, user_id, false, false);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("Forbidden"));
     }
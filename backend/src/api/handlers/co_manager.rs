@@ -0,0 +1,540 @@
+//! Household accounts. A grower invites another grower by email to co-manage
+//! their garden; once the invitation is accepted the co-manager can read and
+//! edit the owner's listings and act on claims against them as the owner
+//! would. Listings stay owned by the inviting grower. Either side can end the
+//! arrangement by deleting it.
+
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::{extract_auth_context, AuthContext};
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{GenericClient, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_CO_MANAGERS_PER_OWNER: i64 = 3;
+const MAX_EMAIL_CHARS: usize = 320;
+
+const CO_MANAGER_COLUMNS: &str = "
+    m.id, m.owner_id, m.co_manager_id, m.status, m.created_at, m.accepted_at,
+    owner.display_name as owner_display_name,
+    co_manager.display_name as co_manager_display_name";
+
+const CO_MANAGER_JOINS: &str = "
+    from grower_co_managers m
+    join users owner on owner.id = m.owner_id
+    join users co_manager on co_manager.id = m.co_manager_id";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCoManagerRequest {
+    pub email: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoManagerResponse {
+    pub id: String,
+    pub owner_id: String,
+    pub owner_display_name: Option<String>,
+    pub co_manager_id: String,
+    pub co_manager_display_name: Option<String>,
+    pub status: String,
+    pub created_at: String,
+    pub accepted_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCoManagersResponse {
+    pub items: Vec<CoManagerResponse>,
+}
+
+/// Whose listing `listing_id` is, if `user_id` owns it or co-manages the
+/// owner's garden. `delegated` is set when the caller is acting for someone
+/// else, so the change can be audited under the caller.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ManagedListing {
+    pub owner_id: Uuid,
+    pub delegated: bool,
+}
+
+/// Looks up the owner of a listing the caller may manage. Listings the caller
+/// cannot manage resolve to `None` so callers return the same 404 as for a
+/// missing listing.
+pub async fn resolve_managed_listing<C: GenericClient + Sync>(
+    client: &C,
+    listing_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<ManagedListing>, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "co_manager::resolve_managed_listing",
+            "
+            select l.user_id
+            from surplus_listings l
+            where l.id = $1
+              and l.deleted_at is null
+              and (
+                l.user_id = $2
+                or exists (
+                  select 1 from grower_co_managers m
+                  where m.owner_id = l.user_id
+                    and m.co_manager_id = $2
+                    and m.status = 'accepted'
+                )
+              )
+            ",
+            &[&listing_id, &user_id],
+        )
+        .await?;
+
+    Ok(row.map(|row| {
+        let owner_id: Uuid = row.get("user_id");
+        ManagedListing {
+            owner_id,
+            delegated: owner_id != user_id,
+        }
+    }))
+}
+
+/// Fails unless `user_id` has an accepted invitation from `owner_id`.
+pub async fn ensure_co_manages<C: GenericClient + Sync>(
+    client: &C,
+    owner_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), ApiError> {
+    let row = client
+        .query_opt_timed(
+            "co_manager::ensure_co_manages",
+            "
+            select 1 from grower_co_managers
+            where owner_id = $1 and co_manager_id = $2 and status = 'accepted'
+            ",
+            &[&owner_id, &user_id],
+        )
+        .await?;
+    if row.is_none() {
+        return Err(ApiError::forbidden(
+            "not_co_manager",
+            "You do not co-manage this grower's listings",
+        ));
+    }
+    Ok(())
+}
+
+/// The caller's co-managers and outstanding invitations, oldest first.
+pub async fn list_co_managers(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let client = db::connect().await?;
+
+    let sql = format!(
+        "select {CO_MANAGER_COLUMNS} {CO_MANAGER_JOINS}
+         where m.owner_id = $1
+         order by m.created_at asc, m.id asc"
+    );
+    let rows = client
+        .query_timed("co_manager::list_co_managers", &sql, &[&user_id])
+        .await?;
+    let items: Vec<CoManagerResponse> = rows.iter().map(row_to_response).collect();
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        co_manager_count = items.len(),
+        "Listed co-managers"
+    );
+
+    json_response(200, &ListCoManagersResponse { items })
+}
+
+/// Invites the grower with the given email to co-manage the caller's garden.
+pub async fn invite_co_manager(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_user_id(&auth.user_id)?;
+    let payload: InviteCoManagerRequest = parse_json_body(request)?;
+    let email = normalize_email(&payload.email)?;
+
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let invitee = tx
+        .query_opt_timed(
+            "co_manager::invite_co_manager",
+            "
+            select id, user_type
+            from users
+            where lower(email) = $1 and deleted_at is null
+            ",
+            &[&email],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found("user_not_found", "No account uses that email address")
+        })?;
+    let invitee_id: Uuid = invitee.get("id");
+    if invitee_id == user_id {
+        return Err(ApiError::invalid_field(
+            "email",
+            "self_invite",
+            "You cannot invite yourself",
+        ));
+    }
+    if invitee.get::<_, Option<String>>("user_type").as_deref() != Some("grower") {
+        return Err(ApiError::conflict(
+            "invitee_not_grower",
+            "Only growers can co-manage listings",
+        ));
+    }
+
+    let existing: i64 = tx
+        .query_one_timed(
+            "co_manager::invite_co_manager",
+            "select count(*) as count from grower_co_managers where owner_id = $1",
+            &[&user_id],
+        )
+        .await?
+        .get("count");
+    if existing >= MAX_CO_MANAGERS_PER_OWNER {
+        return Err(ApiError::conflict(
+            "co_manager_limit_reached",
+            format!("A garden can have at most {MAX_CO_MANAGERS_PER_OWNER} co-managers"),
+        ));
+    }
+
+    let inserted = tx
+        .query_opt_timed(
+            "co_manager::invite_co_manager",
+            "
+            insert into grower_co_managers (owner_id, co_manager_id)
+            values ($1, $2)
+            on conflict (owner_id, co_manager_id) do nothing
+            returning id
+            ",
+            &[&user_id, &invitee_id],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::conflict(
+                "co_manager_exists",
+                "That grower is already invited or co-managing",
+            )
+        })?;
+    let id: Uuid = inserted.get("id");
+    let response = load_by_id(&tx, id).await?;
+
+    audit::record(
+        &tx,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::CO_MANAGER_INVITED,
+            target_type: "grower_co_manager",
+            target_id: response.id.clone(),
+            before: None,
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        co_manager_id = %invitee_id,
+        "Invited co-manager"
+    );
+
+    json_response(201, &response)
+}
+
+/// Removes a co-manager or withdraws an invitation from the caller's garden.
+pub async fn remove_co_manager(
+    request: &Request,
+    correlation_id: &str,
+    co_manager_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_user_id(&auth.user_id)?;
+    let id = parse_uuid(co_manager_id, "coManagerId")?;
+
+    delete_membership(&auth, user_id, id, Side::Owner, correlation_id).await
+}
+
+/// Gardens the caller co-manages, and invitations waiting for an answer.
+pub async fn list_invitations(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let client = db::connect().await?;
+
+    let sql = format!(
+        "select {CO_MANAGER_COLUMNS} {CO_MANAGER_JOINS}
+         where m.co_manager_id = $1
+         order by m.created_at asc, m.id asc"
+    );
+    let rows = client
+        .query_timed("co_manager::list_invitations", &sql, &[&user_id])
+        .await?;
+    let items: Vec<CoManagerResponse> = rows.iter().map(row_to_response).collect();
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        invitation_count = items.len(),
+        "Listed co-manager invitations"
+    );
+
+    json_response(200, &ListCoManagersResponse { items })
+}
+
+pub async fn accept_invitation(
+    request: &Request,
+    correlation_id: &str,
+    invitation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_user_id(&auth.user_id)?;
+    let id = parse_uuid(invitation_id, "invitationId")?;
+
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let updated = tx
+        .query_opt_timed(
+            "co_manager::accept_invitation",
+            "
+            update grower_co_managers
+               set status = 'accepted',
+                   accepted_at = coalesce(accepted_at, now())
+             where id = $1 and co_manager_id = $2
+            returning id
+            ",
+            &[&id, &user_id],
+        )
+        .await?;
+    if updated.is_none() {
+        return Err(invitation_not_found());
+    }
+    let response = load_by_id(&tx, id).await?;
+
+    audit::record(
+        &tx,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::CO_MANAGER_ACCEPTED,
+            target_type: "grower_co_manager",
+            target_id: response.id.clone(),
+            before: None,
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        invitation_id = %id,
+        "Accepted co-manager invitation"
+    );
+
+    json_response(200, &response)
+}
+
+/// Declines an invitation, or stops co-managing a garden already joined.
+pub async fn leave_invitation(
+    request: &Request,
+    correlation_id: &str,
+    invitation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_user_id(&auth.user_id)?;
+    let id = parse_uuid(invitation_id, "invitationId")?;
+
+    delete_membership(&auth, user_id, id, Side::CoManager, correlation_id).await
+}
+
+/// Which side of the arrangement is ending it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    Owner,
+    CoManager,
+}
+
+impl Side {
+    const fn column(self) -> &'static str {
+        match self {
+            Self::Owner => "owner_id",
+            Self::CoManager => "co_manager_id",
+        }
+    }
+}
+
+async fn delete_membership(
+    auth: &AuthContext,
+    user_id: Uuid,
+    id: Uuid,
+    side: Side,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let sql = format!(
+        "select {CO_MANAGER_COLUMNS} {CO_MANAGER_JOINS}
+         where m.id = $1 and m.{} = $2
+         for update of m",
+        side.column()
+    );
+    let before = tx
+        .query_opt_timed("co_manager::delete_membership", &sql, &[&id, &user_id])
+        .await?
+        .map(|row| row_to_response(&row))
+        .ok_or_else(|| match side {
+            Side::Owner => ApiError::not_found("co_manager_not_found", "Co-manager not found"),
+            Side::CoManager => invitation_not_found(),
+        })?;
+    tx.execute_timed(
+        "co_manager::delete_membership",
+        "delete from grower_co_managers where id = $1",
+        &[&id],
+    )
+    .await?;
+
+    audit::record(
+        &tx,
+        &AuditEntry {
+            actor: Actor::from_auth(auth),
+            action: audit::CO_MANAGER_REMOVED,
+            target_type: "grower_co_manager",
+            target_id: id.to_string(),
+            before: serde_json::to_value(&before).ok(),
+            after: None,
+            correlation_id,
+        },
+    )
+    .await?;
+
+    tx.commit().await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        membership_id = %id,
+        removed_by = side.column(),
+        "Removed co-manager"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+async fn load_by_id<C: GenericClient + Sync>(
+    client: &C,
+    id: Uuid,
+) -> Result<CoManagerResponse, ApiError> {
+    let sql = format!("select {CO_MANAGER_COLUMNS} {CO_MANAGER_JOINS} where m.id = $1");
+    client
+        .query_opt_timed("co_manager::load_by_id", &sql, &[&id])
+        .await?
+        .map(|row| row_to_response(&row))
+        .ok_or_else(invitation_not_found)
+}
+
+fn normalize_email(email: &str) -> Result<String, ApiError> {
+    let email = email.trim().to_lowercase();
+    if email.is_empty() {
+        return Err(ApiError::invalid_field(
+            "email",
+            "required",
+            "email is required",
+        ));
+    }
+    let well_formed = email.chars().count() <= MAX_EMAIL_CHARS
+        && email
+            .split_once('@')
+            .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+    if !well_formed {
+        return Err(ApiError::invalid_field(
+            "email",
+            "invalid_email",
+            "email must be a valid email address",
+        ));
+    }
+    Ok(email)
+}
+
+fn row_to_response(row: &Row) -> CoManagerResponse {
+    CoManagerResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        owner_id: row.get::<_, Uuid>("owner_id").to_string(),
+        owner_display_name: row.get("owner_display_name"),
+        co_manager_id: row.get::<_, Uuid>("co_manager_id").to_string(),
+        co_manager_display_name: row.get("co_manager_display_name"),
+        status: row.get("status"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        accepted_at: row
+            .get::<_, Option<DateTime<Utc>>>("accepted_at")
+            .map(|accepted_at| accepted_at.to_rfc3339()),
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    parse_user_id(&auth.user_id)
+}
+
+fn parse_user_id(user_id: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn invitation_not_found() -> ApiError {
+    ApiError::not_found("invitation_not_found", "Invitation not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_email_trims_and_lowercases() {
+        assert_eq!(
+            normalize_email("  Partner@Example.ORG ").unwrap(),
+            "partner@example.org"
+        );
+    }
+
+    #[test]
+    fn normalize_email_rejects_missing_and_malformed_addresses() {
+        for (email, code) in [
+            ("   ", "required"),
+            ("partner", "invalid_email"),
+            ("@example.org", "invalid_email"),
+            ("partner@localhost", "invalid_email"),
+        ] {
+            assert_eq!(
+                normalize_email(email).unwrap_err().error_code(),
+                code,
+                "{email}"
+            );
+        }
+    }
+}
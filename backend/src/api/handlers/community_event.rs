@@ -0,0 +1,889 @@
+use crate::auth::extract_auth_context;
+use crate::coordinate_privacy::LocatedEntity;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::events::{self, CommunityEventDetail};
+use crate::handlers::listing_discovery::parse_positive_radius;
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::location;
+use crate::repo;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{Client, Row};
+use tracing::{error, info};
+use uuid::Uuid;
+
+const ALLOWED_EVENT_TYPES: [&str; 5] =
+    ["harvest_day", "gleaning", "seed_swap", "workshop", "other"];
+const ALLOWED_EVENT_STATUS: [&str; 2] = ["scheduled", "cancelled"];
+const MAX_TITLE_CHARS: usize = 120;
+const MAX_DESCRIPTION_CHARS: usize = 2000;
+const MAX_CAPACITY: i32 = 10_000;
+
+/// Columns read by [`row_to_response`]. `$1` is the viewer, used to report
+/// whether they have already RSVP'd.
+macro_rules! event_columns {
+    () => {
+        "e.id, e.organizer_id, e.group_id, e.title, e.description, e.event_type,
+         e.location_text, e.address, e.geo_key, e.lat, e.lng, e.starts_at, e.ends_at,
+         e.capacity, e.status, e.created_at,
+         (select count(*) from community_event_rsvps r where r.event_id = e.id) as rsvp_count,
+         exists(
+             select 1 from community_event_rsvps r where r.event_id = e.id and r.user_id = $1
+         ) as viewer_rsvped"
+    };
+}
+
+const FIND_EVENT: &str = concat!(
+    "select ",
+    event_columns!(),
+    "
+    from community_events e
+    where e.id = $2
+      and e.deleted_at is null"
+);
+
+const DISCOVER_EVENTS: &str = concat!(
+    "select ",
+    event_columns!(),
+    "
+    from community_events e
+    where e.deleted_at is null
+      and e.status = 'scheduled'
+      and e.geo_key like $2
+      and e.ends_at > $3
+    order by e.starts_at asc, e.id asc
+    limit $4 offset $5"
+);
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertEventRequest {
+    pub title: String,
+    pub description: Option<String>,
+    pub event_type: String,
+    pub location_text: Option<String>,
+    pub address: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub capacity: Option<i32>,
+    pub group_id: Option<String>,
+    pub status: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommunityEventResponse {
+    pub id: String,
+    pub organizer_id: String,
+    pub group_id: Option<String>,
+    pub title: String,
+    pub description: Option<String>,
+    pub event_type: String,
+    pub location_text: Option<String>,
+    pub address: String,
+    pub geo_key: String,
+    pub lat: f64,
+    pub lng: f64,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub capacity: Option<i32>,
+    pub rsvp_count: i64,
+    pub spots_remaining: Option<i64>,
+    pub viewer_rsvped: bool,
+    pub status: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DiscoverEventsResponse {
+    pub items: Vec<CommunityEventResponse>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug)]
+struct NormalizedEventInput {
+    title: String,
+    description: Option<String>,
+    event_type: String,
+    location_text: Option<String>,
+    address: String,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    capacity: Option<i32>,
+    group_id: Option<Uuid>,
+    status: String,
+}
+
+#[derive(Debug)]
+struct DiscoverEventsQuery {
+    geo_key: String,
+    radius_km: Option<f64>,
+    limit: i64,
+    offset: i64,
+}
+
+pub async fn create_event(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: UpsertEventRequest = parse_json_body(request)?;
+    let normalized = normalize_payload(&payload)?;
+    if normalized.status != "scheduled" {
+        return Err(ApiError::invalid_field(
+            "status",
+            "invalid_enum",
+            "New events must be scheduled",
+        ));
+    }
+
+    let client = db::connect().await?;
+    if let Some(group_id) = normalized.group_id {
+        validate_group_attribution(&client, group_id, user_id).await?;
+    }
+    let geocoded = location::geocode_address(&normalized.address, correlation_id).await?;
+
+    let row = client
+        .query_one_timed(
+            "community_event::create_event",
+            "
+            insert into community_events
+                (organizer_id, group_id, title, description, event_type, location_text,
+                 address, geo_key, lat, lng, starts_at, ends_at, capacity)
+            values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13)
+            returning id
+            ",
+            &[
+                &user_id,
+                &normalized.group_id,
+                &normalized.title,
+                &normalized.description,
+                &normalized.event_type,
+                &normalized.location_text,
+                &normalized.address,
+                &geocoded.geo_key,
+                &geocoded.lat,
+                &geocoded.lng,
+                &normalized.starts_at,
+                &normalized.ends_at,
+                &normalized.capacity,
+            ],
+        )
+        .await?;
+    let event_id: Uuid = row.get("id");
+
+    let response = load_event(&client, event_id, user_id)
+        .await?
+        .ok_or_else(event_not_found)?;
+    emit_event_best_effort(events::COMMUNITY_EVENT_CREATED, &response, correlation_id).await;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        event_id = %event_id,
+        geo_key = response.geo_key.as_str(),
+        "Created community event"
+    );
+
+    json_response(201, &response)
+}
+
+/// Upcoming scheduled events near `geoKey`, soonest first. Uses the same
+/// geohash-prefix scan as listing discovery.
+pub async fn discover_events(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let query = parse_discover_events_query(request.uri().query())?;
+
+    let geo_prefix = location::geo_prefix_for_radius(&query.geo_key, query.radius_km);
+    let geo_pattern = format!("{geo_prefix}%");
+    let now = Utc::now();
+    let fetch_limit = query.limit + 1;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "community_event::discover_events",
+            DISCOVER_EVENTS,
+            &[&user_id, &geo_pattern, &now, &fetch_limit, &query.offset],
+        )
+        .await?;
+
+    let limit = usize::try_from(query.limit).map_err(|_| invalid_limit())?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_response)
+        .collect::<Vec<_>>();
+
+    let response = DiscoverEventsResponse {
+        items,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: if has_more {
+            Some(query.offset + query.limit)
+        } else {
+            None
+        },
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        geo_key = query.geo_key,
+        geo_prefix = geo_prefix,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        "Listed discoverable community events"
+    );
+
+    json_response(200, &response)
+}
+
+pub async fn get_event(
+    request: &Request,
+    correlation_id: &str,
+    event_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let event_id = parse_uuid(event_id, "eventId")?;
+
+    let client = db::connect().await?;
+    let response = load_event(&client, event_id, user_id)
+        .await?
+        .ok_or_else(event_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        event_id = %event_id,
+        "Fetched community event"
+    );
+
+    json_response(200, &response)
+}
+
+/// Organizer-only full update. Setting `status` to `cancelled` cancels the
+/// event; capacity cannot drop below the RSVPs already taken.
+pub async fn update_event(
+    request: &Request,
+    correlation_id: &str,
+    event_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let event_id = parse_uuid(event_id, "eventId")?;
+    let payload: UpsertEventRequest = parse_json_body(request)?;
+    let normalized = normalize_payload(&payload)?;
+
+    let mut client = db::connect().await?;
+    if let Some(group_id) = normalized.group_id {
+        validate_group_attribution(&client, group_id, user_id).await?;
+    }
+    let geocoded = location::geocode_address(&normalized.address, correlation_id).await?;
+
+    let tx = client.transaction().await?;
+    let current = tx
+        .query_opt_timed(
+            "community_event::update_event",
+            "
+            select e.organizer_id,
+                   (select count(*) from community_event_rsvps r where r.event_id = e.id)
+                     as rsvp_count
+            from community_events e
+            where e.id = $1
+              and e.deleted_at is null
+            for update of e
+            ",
+            &[&event_id],
+        )
+        .await?
+        .ok_or_else(event_not_found)?;
+
+    if current.get::<_, Uuid>("organizer_id") != user_id {
+        return Err(ApiError::forbidden(
+            "event_organizer_required",
+            "Forbidden: Only the organizer can update this event",
+        ));
+    }
+    let rsvp_count: i64 = current.get("rsvp_count");
+    if normalized
+        .capacity
+        .is_some_and(|capacity| i64::from(capacity) < rsvp_count)
+    {
+        return Err(ApiError::conflict(
+            "capacity_below_rsvps",
+            format!("capacity cannot be lower than the {rsvp_count} RSVPs already taken"),
+        ));
+    }
+
+    tx.execute_timed(
+        "community_event::update_event",
+        "
+        update community_events
+        set group_id = $2,
+            title = $3,
+            description = $4,
+            event_type = $5,
+            location_text = $6,
+            address = $7,
+            geo_key = $8,
+            lat = $9,
+            lng = $10,
+            starts_at = $11,
+            ends_at = $12,
+            capacity = $13,
+            status = $14,
+            updated_at = now()
+        where id = $1
+        ",
+        &[
+            &event_id,
+            &normalized.group_id,
+            &normalized.title,
+            &normalized.description,
+            &normalized.event_type,
+            &normalized.location_text,
+            &normalized.address,
+            &geocoded.geo_key,
+            &geocoded.lat,
+            &geocoded.lng,
+            &normalized.starts_at,
+            &normalized.ends_at,
+            &normalized.capacity,
+            &normalized.status,
+        ],
+    )
+    .await?;
+    db::commit(tx).await?;
+
+    let response = load_event(&client, event_id, user_id)
+        .await?
+        .ok_or_else(event_not_found)?;
+    emit_event_best_effort(events::COMMUNITY_EVENT_UPDATED, &response, correlation_id).await;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        event_id = %event_id,
+        status = response.status.as_str(),
+        "Updated community event"
+    );
+
+    json_response(200, &response)
+}
+
+/// RSVPs the caller. The event row is locked so concurrent RSVPs cannot
+/// overfill capacity. Repeating an RSVP returns 200 with the current state.
+pub async fn rsvp_event(
+    request: &Request,
+    correlation_id: &str,
+    event_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let event_id = parse_uuid(event_id, "eventId")?;
+
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+    let event_row = tx
+        .query_opt_timed(
+            "community_event::rsvp_event",
+            "
+            select e.capacity, e.status, e.ends_at,
+                   (select count(*) from community_event_rsvps r where r.event_id = e.id)
+                     as rsvp_count,
+                   exists(
+                       select 1 from community_event_rsvps r
+                       where r.event_id = e.id and r.user_id = $2
+                   ) as already_rsvped
+            from community_events e
+            where e.id = $1
+              and e.deleted_at is null
+            for update of e
+            ",
+            &[&event_id, &user_id],
+        )
+        .await?
+        .ok_or_else(event_not_found)?;
+
+    let already_rsvped: bool = event_row.get("already_rsvped");
+    if !already_rsvped {
+        check_rsvp_open(
+            event_row.get("status"),
+            event_row.get("ends_at"),
+            event_row.get("capacity"),
+            event_row.get("rsvp_count"),
+            Utc::now(),
+        )?;
+        tx.execute_timed(
+            "community_event::rsvp_event",
+            "insert into community_event_rsvps (event_id, user_id) values ($1, $2)",
+            &[&event_id, &user_id],
+        )
+        .await?;
+    }
+    db::commit(tx).await?;
+
+    let response = load_event(&client, event_id, user_id)
+        .await?
+        .ok_or_else(event_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        event_id = %event_id,
+        repeated = already_rsvped,
+        rsvp_count = response.rsvp_count,
+        "RSVP'd to community event"
+    );
+
+    json_response(if already_rsvped { 200 } else { 201 }, &response)
+}
+
+pub async fn cancel_rsvp(
+    request: &Request,
+    correlation_id: &str,
+    event_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let event_id = parse_uuid(event_id, "eventId")?;
+
+    let client = db::connect().await?;
+    let removed = client
+        .execute_timed(
+            "community_event::cancel_rsvp",
+            "delete from community_event_rsvps where event_id = $1 and user_id = $2",
+            &[&event_id, &user_id],
+        )
+        .await?;
+    if removed == 0 {
+        return Err(ApiError::not_found("rsvp_not_found", "RSVP not found"));
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        event_id = %event_id,
+        "Cancelled community event RSVP"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+async fn load_event(
+    client: &Client,
+    event_id: Uuid,
+    viewer_id: Uuid,
+) -> Result<Option<CommunityEventResponse>, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "community_event::load_event",
+            FIND_EVENT,
+            &[&viewer_id, &event_id],
+        )
+        .await?;
+    Ok(row.as_ref().map(row_to_response))
+}
+
+async fn validate_group_attribution(
+    client: &Client,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), ApiError> {
+    if repo::group::member_role(client, group_id, user_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::forbidden(
+            "not_group_member",
+            "Forbidden: You must be a member of the group to host events for it",
+        ));
+    }
+    Ok(())
+}
+
+fn check_rsvp_open(
+    status: &str,
+    ends_at: DateTime<Utc>,
+    capacity: Option<i32>,
+    rsvp_count: i64,
+    now: DateTime<Utc>,
+) -> Result<(), ApiError> {
+    if status != "scheduled" || ends_at <= now {
+        return Err(ApiError::conflict(
+            "event_closed",
+            "This event is no longer accepting RSVPs",
+        ));
+    }
+    if capacity.is_some_and(|capacity| rsvp_count >= i64::from(capacity)) {
+        return Err(ApiError::conflict("event_full", "This event is full"));
+    }
+    Ok(())
+}
+
+fn normalize_payload(payload: &UpsertEventRequest) -> Result<NormalizedEventInput, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let title = payload.title.trim();
+    if title.is_empty() {
+        errors.add("title", "required", "title is required");
+    } else if title.chars().count() > MAX_TITLE_CHARS {
+        errors.add(
+            "title",
+            "too_long",
+            format!("title must be at most {MAX_TITLE_CHARS} characters"),
+        );
+    }
+
+    let description = non_blank(payload.description.as_deref());
+    if description.is_some_and(|value| value.chars().count() > MAX_DESCRIPTION_CHARS) {
+        errors.add(
+            "description",
+            "too_long",
+            format!("description must be at most {MAX_DESCRIPTION_CHARS} characters"),
+        );
+    }
+
+    if !ALLOWED_EVENT_TYPES.contains(&payload.event_type.as_str()) {
+        errors.add(
+            "eventType",
+            "invalid_enum",
+            format!(
+                "Invalid eventType '{}'. Allowed values: {}",
+                payload.event_type,
+                ALLOWED_EVENT_TYPES.join(", ")
+            ),
+        );
+    }
+
+    let status = payload.status.as_deref().unwrap_or("scheduled");
+    if !ALLOWED_EVENT_STATUS.contains(&status) {
+        errors.add(
+            "status",
+            "invalid_enum",
+            format!(
+                "Invalid status '{status}'. Allowed values: {}",
+                ALLOWED_EVENT_STATUS.join(", ")
+            ),
+        );
+    }
+
+    let address = location::normalize_address(&payload.address);
+    if address.is_empty() {
+        errors.add("address", "required", "address is required");
+    }
+
+    let starts_at = errors.capture(parse_datetime(&payload.starts_at, "startsAt"));
+    let ends_at = errors.capture(parse_datetime(&payload.ends_at, "endsAt"));
+    if let (Some(start), Some(end)) = (starts_at, ends_at) {
+        if start >= end {
+            errors.add(
+                "startsAt",
+                "invalid_window",
+                "startsAt must be earlier than endsAt",
+            );
+        }
+    }
+
+    if payload
+        .capacity
+        .is_some_and(|capacity| !(1..=MAX_CAPACITY).contains(&capacity))
+    {
+        errors.add(
+            "capacity",
+            "invalid_capacity",
+            format!("capacity must be between 1 and {MAX_CAPACITY}"),
+        );
+    }
+
+    let group_id = errors.capture(
+        non_blank(payload.group_id.as_deref())
+            .map_or(Ok(None), |value| parse_uuid(value, "groupId").map(Some)),
+    );
+
+    errors.into_result()?;
+    let (Some(starts_at), Some(ends_at), Some(group_id)) = (starts_at, ends_at, group_id) else {
+        return Err(ApiError::internal(
+            "event validation passed with missing fields",
+        ));
+    };
+
+    Ok(NormalizedEventInput {
+        title: title.to_string(),
+        description: description.map(str::to_string),
+        event_type: payload.event_type.clone(),
+        location_text: non_blank(payload.location_text.as_deref()).map(str::to_string),
+        address,
+        starts_at,
+        ends_at,
+        capacity: payload.capacity,
+        group_id,
+        status: status.to_string(),
+    })
+}
+
+fn parse_discover_events_query(query: Option<&str>) -> Result<DiscoverEventsQuery, ApiError> {
+    let mut geo_key: Option<String> = None;
+    let mut radius_km: Option<f64> = None;
+    let mut limit: i64 = 20;
+    let mut offset: i64 = 0;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            match key {
+                "geoKey" => {
+                    let normalized = value.trim().to_ascii_lowercase();
+                    if !location::is_valid_geo_key(&normalized) {
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
+                            "geoKey must be a valid geohash (1-12 chars, base32)",
+                        ));
+                    }
+                    geo_key = Some(normalized);
+                }
+                "radiusMiles" => {
+                    let miles = parse_positive_radius(value, "radiusMiles")?;
+                    radius_km = Some(miles * location::KM_PER_MILE);
+                }
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| invalid_limit())?;
+                    if !(1..=100).contains(&limit) {
+                        return Err(invalid_limit());
+                    }
+                }
+                "offset" => {
+                    offset = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|offset| *offset >= 0)
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "offset",
+                                "invalid_offset",
+                                "Invalid offset. Must be an integer greater than or equal to 0",
+                            )
+                        })?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let geo_key = geo_key.ok_or_else(|| {
+        ApiError::invalid_field("geoKey", "invalid_geo_key", "geoKey is required")
+    })?;
+
+    Ok(DiscoverEventsQuery {
+        geo_key,
+        radius_km,
+        limit,
+        offset,
+    })
+}
+
+fn parse_datetime(value: &str, field_name: &str) -> Result<DateTime<Utc>, ApiError> {
+    let parsed = DateTime::parse_from_rfc3339(value).map_err(|_| {
+        ApiError::invalid_field(
+            field_name,
+            "invalid_timestamp",
+            format!("{field_name} must be a valid RFC3339 timestamp"),
+        )
+    })?;
+    Ok(parsed.with_timezone(&Utc))
+}
+
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|text| !text.is_empty())
+}
+
+fn row_to_response(row: &Row) -> CommunityEventResponse {
+    let (lat, lng) = LocatedEntity::CommunityEvent.point(row, "lat", "lng");
+    let capacity: Option<i32> = row.get("capacity");
+    let rsvp_count: i64 = row.get("rsvp_count");
+
+    CommunityEventResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        organizer_id: row.get::<_, Uuid>("organizer_id").to_string(),
+        group_id: row
+            .get::<_, Option<Uuid>>("group_id")
+            .map(|id| id.to_string()),
+        title: row.get("title"),
+        description: row.get("description"),
+        event_type: row.get("event_type"),
+        location_text: row.get("location_text"),
+        address: row.get("address"),
+        geo_key: row.get("geo_key"),
+        lat,
+        lng,
+        starts_at: row.get::<_, DateTime<Utc>>("starts_at").to_rfc3339(),
+        ends_at: row.get::<_, DateTime<Utc>>("ends_at").to_rfc3339(),
+        capacity,
+        rsvp_count,
+        spots_remaining: capacity.map(|capacity| (i64::from(capacity) - rsvp_count).max(0)),
+        viewer_rsvped: row.get("viewer_rsvped"),
+        status: row.get("status"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    }
+}
+
+async fn emit_event_best_effort(
+    detail_type: &str,
+    event: &CommunityEventResponse,
+    correlation_id: &str,
+) {
+    let detail = CommunityEventDetail::new(
+        event.id.clone(),
+        event.organizer_id.clone(),
+        event.group_id.clone(),
+        event.event_type.clone(),
+        event.status.clone(),
+        event.geo_key.clone(),
+        event.starts_at.clone(),
+        event.ends_at.clone(),
+        correlation_id,
+    );
+
+    if let Err(event_error) = events::publish(detail_type, &detail).await {
+        error!(
+            correlation_id = correlation_id,
+            event_id = event.id.as_str(),
+            detail_type = detail_type,
+            error = %event_error,
+            "Failed to emit community event after successful write"
+        );
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn event_not_found() -> ApiError {
+    ApiError::not_found("event_not_found", "Event not found")
+}
+
+fn invalid_limit() -> ApiError {
+    ApiError::invalid_field(
+        "limit",
+        "invalid_limit",
+        "Invalid limit. Must be an integer between 1 and 100",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn valid_payload() -> UpsertEventRequest {
+        UpsertEventRequest {
+            title: " Orchard gleaning ".to_string(),
+            description: Some("Bring bags".to_string()),
+            event_type: "gleaning".to_string(),
+            location_text: Some("  ".to_string()),
+            address: " 12  Orchard Ln ".to_string(),
+            starts_at: "2026-09-12T09:00:00Z".to_string(),
+            ends_at: "2026-09-12T12:00:00Z".to_string(),
+            capacity: Some(15),
+            group_id: None,
+            status: None,
+        }
+    }
+
+    #[test]
+    fn normalize_payload_trims_and_defaults_status() {
+        let normalized = normalize_payload(&valid_payload()).unwrap();
+        assert_eq!(normalized.title, "Orchard gleaning");
+        assert_eq!(normalized.address, "12 Orchard Ln");
+        assert_eq!(normalized.location_text, None);
+        assert_eq!(normalized.status, "scheduled");
+        assert_eq!(normalized.capacity, Some(15));
+    }
+
+    #[test]
+    fn normalize_payload_reports_every_invalid_field() {
+        let mut payload = valid_payload();
+        payload.event_type = "rave".to_string();
+        payload.ends_at = payload.starts_at.clone();
+        payload.capacity = Some(0);
+
+        let error = normalize_payload(&payload).unwrap_err();
+        let fields: Vec<&str> = match &error {
+            ApiError::Validation { issues } => {
+                issues.iter().map(|issue| issue.field.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        assert_eq!(fields, vec!["eventType", "startsAt", "capacity"]);
+    }
+
+    #[test]
+    fn check_rsvp_open_enforces_status_window_and_capacity() {
+        let now = DateTime::parse_from_rfc3339("2026-09-12T08:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let ends_at = now + chrono::Duration::hours(4);
+
+        assert!(check_rsvp_open("scheduled", ends_at, None, 500, now).is_ok());
+        assert!(check_rsvp_open("scheduled", ends_at, Some(10), 9, now).is_ok());
+        assert_eq!(
+            check_rsvp_open("scheduled", ends_at, Some(10), 10, now)
+                .unwrap_err()
+                .error_code(),
+            "event_full"
+        );
+        assert_eq!(
+            check_rsvp_open("cancelled", ends_at, None, 0, now)
+                .unwrap_err()
+                .error_code(),
+            "event_closed"
+        );
+        assert_eq!(
+            check_rsvp_open("scheduled", now, None, 0, now)
+                .unwrap_err()
+                .error_code(),
+            "event_closed"
+        );
+    }
+
+    #[test]
+    fn parse_discover_events_query_reuses_listing_geo_rules() {
+        let parsed =
+            parse_discover_events_query(Some("geoKey=9Q8YYK8&radiusMiles=10&limit=5")).unwrap();
+        assert_eq!(parsed.geo_key, "9q8yyk8");
+        assert_eq!(parsed.radius_km, Some(10.0 * location::KM_PER_MILE));
+        assert_eq!(parsed.limit, 5);
+
+        assert!(parse_discover_events_query(Some("radiusMiles=10")).is_err());
+        assert!(parse_discover_events_query(Some("geoKey=abc!")).is_err());
+    }
+
+    #[test]
+    fn queries_bind_viewer_as_first_parameter() {
+        for sql in [FIND_EVENT, DISCOVER_EVENTS] {
+            assert!(sql.contains("r.user_id = $1"));
+            assert!(sql.contains("e.deleted_at is null"));
+        }
+        assert!(DISCOVER_EVENTS.contains("e.geo_key like $2"));
+    }
+}
@@ -0,0 +1,733 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::events::{self, MessageEventDetail};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{Client, Row};
+use tracing::{error, info};
+use uuid::Uuid;
+
+const MAX_MESSAGE_CHARS: usize = 2000;
+
+const CONVERSATION_COLUMNS: &str =
+    "id, listing_id, request_id, owner_id, participant_id, created_at, last_message_at";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartConversationRequest {
+    pub listing_id: Option<String>,
+    pub request_id: Option<String>,
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SendMessageRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationResponse {
+    pub id: String,
+    pub listing_id: Option<String>,
+    pub request_id: Option<String>,
+    pub owner_id: String,
+    pub participant_id: String,
+    pub unread_count: i64,
+    pub created_at: String,
+    pub last_message_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageResponse {
+    pub id: String,
+    pub conversation_id: String,
+    pub sender_id: String,
+    pub body: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartConversationResponse {
+    pub conversation: ConversationResponse,
+    pub message: MessageResponse,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListConversationsResponse {
+    pub items: Vec<ConversationResponse>,
+    pub unread_total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListMessagesResponse {
+    pub items: Vec<MessageResponse>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ConversationSubject {
+    Listing(Uuid),
+    Request(Uuid),
+}
+
+#[derive(Debug)]
+struct PageQuery {
+    limit: i64,
+    offset: i64,
+}
+
+/// The two members of a conversation, loaded before any read or write so
+/// non-members get the same 404 as a missing conversation.
+#[derive(Debug)]
+#[allow(clippy::struct_field_names)]
+struct ConversationMembers {
+    owner_id: Uuid,
+    participant_id: Uuid,
+    listing_id: Option<Uuid>,
+    request_id: Option<Uuid>,
+}
+
+impl ConversationMembers {
+    fn other_member(&self, user_id: Uuid) -> Option<Uuid> {
+        if user_id == self.owner_id {
+            Some(self.participant_id)
+        } else if user_id == self.participant_id {
+            Some(self.owner_id)
+        } else {
+            None
+        }
+    }
+}
+
+pub async fn list_conversations(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let query = parse_page_query(request.uri().query())?;
+    let fetch_limit = query.limit + 1;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "conversation::list_conversations",
+            "
+            with mine as (
+                select c.id, c.listing_id, c.request_id, c.owner_id, c.participant_id,
+                       c.created_at, c.last_message_at,
+                       (
+                           select count(*)
+                           from messages m
+                           where m.conversation_id = c.id
+                             and m.sender_id <> $1
+                             and m.created_at > coalesce(r.last_read_at, '-infinity'::timestamptz)
+                       ) as unread_count
+                from conversations c
+                left join conversation_reads r
+                  on r.conversation_id = c.id and r.user_id = $1
+                where c.owner_id = $1 or c.participant_id = $1
+            )
+            select mine.*, (sum(unread_count) over ())::bigint as unread_total
+            from mine
+            order by last_message_at desc, id desc
+            limit $2 offset $3
+            ",
+            &[&user_id, &fetch_limit, &query.offset],
+        )
+        .await?;
+
+    let unread_total = rows
+        .first()
+        .map_or(0, |row| row.get::<_, i64>("unread_total"));
+    let limit = usize::try_from(query.limit).map_err(|_| invalid_limit())?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_conversation_response)
+        .collect::<Vec<_>>();
+
+    let response = ListConversationsResponse {
+        items,
+        unread_total,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: compute_next_offset(query.offset, query.limit, has_more),
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        returned_count = response.items.len(),
+        unread_total = response.unread_total,
+        has_more = response.has_more,
+        "Listed conversations"
+    );
+
+    json_response(200, &response)
+}
+
+/// Opens (or reuses) the caller's conversation with the owner of a listing or
+/// request and posts the first message in it.
+pub async fn start_conversation(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: StartConversationRequest = parse_json_body(request)?;
+    let subject = parse_subject(&payload)?;
+    let body = normalize_message_body(&payload.body)?;
+
+    let mut client = db::connect().await?;
+    let owner_id = load_subject_owner(&client, subject).await?;
+    if owner_id == user_id {
+        return Err(ApiError::bad_request(
+            "cannot_message_self",
+            "You cannot start a conversation about your own listing or request",
+        ));
+    }
+
+    let tx = client.transaction().await?;
+    let upsert_sql = match subject {
+        ConversationSubject::Listing(_) => format!(
+            "
+            insert into conversations (listing_id, owner_id, participant_id)
+            values ($1, $2, $3)
+            on conflict (listing_id, participant_id) where listing_id is not null
+            do update set last_message_at = now()
+            returning {CONVERSATION_COLUMNS}
+            "
+        ),
+        ConversationSubject::Request(_) => format!(
+            "
+            insert into conversations (request_id, owner_id, participant_id)
+            values ($1, $2, $3)
+            on conflict (request_id, participant_id) where request_id is not null
+            do update set last_message_at = now()
+            returning {CONVERSATION_COLUMNS}
+            "
+        ),
+    };
+    let subject_id = match subject {
+        ConversationSubject::Listing(id) | ConversationSubject::Request(id) => id,
+    };
+    let conversation_row = tx
+        .query_one_timed(
+            "conversation::start_conversation",
+            &upsert_sql,
+            &[&subject_id, &owner_id, &user_id],
+        )
+        .await?;
+    let conversation_id: Uuid = conversation_row.get("id");
+
+    let message_row = insert_message(&tx, conversation_id, user_id, &body).await?;
+    db::commit(tx).await?;
+
+    let message = row_to_message_response(&message_row);
+    let mut conversation = row_to_conversation_response(&conversation_row);
+    conversation.last_message_at.clone_from(&message.created_at);
+
+    emit_message_event_best_effort(
+        &message,
+        owner_id,
+        conversation.listing_id.clone(),
+        conversation.request_id.clone(),
+        correlation_id,
+    )
+    .await;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        conversation_id = %conversation_id,
+        "Started conversation"
+    );
+
+    json_response(
+        201,
+        &StartConversationResponse {
+            conversation,
+            message,
+        },
+    )
+}
+
+pub async fn list_messages(
+    request: &Request,
+    correlation_id: &str,
+    conversation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let conversation_id = parse_uuid(conversation_id, "conversationId")?;
+    let query = parse_page_query(request.uri().query())?;
+    let fetch_limit = query.limit + 1;
+
+    let client = db::connect().await?;
+    load_members_for(&client, conversation_id, user_id).await?;
+
+    let rows = client
+        .query_timed(
+            "conversation::list_messages",
+            "
+            select id, conversation_id, sender_id, body, created_at
+            from messages
+            where conversation_id = $1
+            order by created_at desc, id desc
+            limit $2 offset $3
+            ",
+            &[&conversation_id, &fetch_limit, &query.offset],
+        )
+        .await?;
+
+    let limit = usize::try_from(query.limit).map_err(|_| invalid_limit())?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_message_response)
+        .collect::<Vec<_>>();
+
+    let response = ListMessagesResponse {
+        items,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: compute_next_offset(query.offset, query.limit, has_more),
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        conversation_id = %conversation_id,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        "Listed conversation messages"
+    );
+
+    json_response(200, &response)
+}
+
+pub async fn send_message(
+    request: &Request,
+    correlation_id: &str,
+    conversation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let conversation_id = parse_uuid(conversation_id, "conversationId")?;
+    let payload: SendMessageRequest = parse_json_body(request)?;
+    let body = normalize_message_body(&payload.body)?;
+
+    let mut client = db::connect().await?;
+    let members = load_members_for(&client, conversation_id, user_id).await?;
+    let recipient_id = members
+        .other_member(user_id)
+        .ok_or_else(conversation_not_found)?;
+
+    let tx = client.transaction().await?;
+    let message_row = insert_message(&tx, conversation_id, user_id, &body).await?;
+    db::commit(tx).await?;
+
+    let message = row_to_message_response(&message_row);
+    emit_message_event_best_effort(
+        &message,
+        recipient_id,
+        members.listing_id.map(|id| id.to_string()),
+        members.request_id.map(|id| id.to_string()),
+        correlation_id,
+    )
+    .await;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        conversation_id = %conversation_id,
+        message_id = message.id.as_str(),
+        "Sent conversation message"
+    );
+
+    json_response(201, &message)
+}
+
+pub async fn mark_conversation_read(
+    request: &Request,
+    correlation_id: &str,
+    conversation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let conversation_id = parse_uuid(conversation_id, "conversationId")?;
+
+    let client = db::connect().await?;
+    load_members_for(&client, conversation_id, user_id).await?;
+    client
+        .execute_timed(
+            "conversation::mark_conversation_read",
+            "
+            insert into conversation_reads (conversation_id, user_id, last_read_at)
+            values ($1, $2, now())
+            on conflict (conversation_id, user_id)
+            do update set last_read_at = greatest(conversation_reads.last_read_at, excluded.last_read_at)
+            ",
+            &[&conversation_id, &user_id],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        conversation_id = %conversation_id,
+        "Marked conversation read"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+async fn load_subject_owner(
+    client: &Client,
+    subject: ConversationSubject,
+) -> Result<Uuid, ApiError> {
+    let (sql, id, not_found) = match subject {
+        ConversationSubject::Listing(id) => (
+            "select user_id from surplus_listings where id = $1 and deleted_at is null",
+            id,
+            ApiError::not_found("listing_not_found", "Listing not found"),
+        ),
+        ConversationSubject::Request(id) => (
+            "select user_id from requests where id = $1 and deleted_at is null",
+            id,
+            ApiError::not_found("request_not_found", "Request not found"),
+        ),
+    };
+
+    client
+        .query_opt_timed("conversation::load_subject_owner", sql, &[&id])
+        .await?
+        .map(|row| row.get::<_, Uuid>("user_id"))
+        .ok_or(not_found)
+}
+
+async fn load_members_for(
+    client: &Client,
+    conversation_id: Uuid,
+    user_id: Uuid,
+) -> Result<ConversationMembers, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "conversation::load_members_for",
+            "
+            select owner_id, participant_id, listing_id, request_id
+            from conversations
+            where id = $1
+            ",
+            &[&conversation_id],
+        )
+        .await?
+        .ok_or_else(conversation_not_found)?;
+
+    let members = ConversationMembers {
+        owner_id: row.get("owner_id"),
+        participant_id: row.get("participant_id"),
+        listing_id: row.get("listing_id"),
+        request_id: row.get("request_id"),
+    };
+
+    if members.other_member(user_id).is_none() {
+        return Err(conversation_not_found());
+    }
+    Ok(members)
+}
+
+/// Inserts the message, bumps the conversation's `last_message_at`, and moves
+/// the sender's read cursor so their own message never counts as unread.
+async fn insert_message(
+    tx: &tokio_postgres::Transaction<'_>,
+    conversation_id: Uuid,
+    sender_id: Uuid,
+    body: &str,
+) -> Result<Row, ApiError> {
+    let message_row = tx
+        .query_one_timed(
+            "conversation::insert_message",
+            "
+            insert into messages (conversation_id, sender_id, body)
+            values ($1, $2, $3)
+            returning id, conversation_id, sender_id, body, created_at
+            ",
+            &[&conversation_id, &sender_id, &body],
+        )
+        .await?;
+    let created_at: DateTime<Utc> = message_row.get("created_at");
+
+    tx.execute_timed(
+        "conversation::insert_message",
+        "update conversations set last_message_at = $2 where id = $1",
+        &[&conversation_id, &created_at],
+    )
+    .await?;
+    tx.execute_timed(
+        "conversation::insert_message",
+        "
+        insert into conversation_reads (conversation_id, user_id, last_read_at)
+        values ($1, $2, $3)
+        on conflict (conversation_id, user_id)
+        do update set last_read_at = greatest(conversation_reads.last_read_at, excluded.last_read_at)
+        ",
+        &[&conversation_id, &sender_id, &created_at],
+    )
+    .await?;
+
+    Ok(message_row)
+}
+
+fn parse_subject(payload: &StartConversationRequest) -> Result<ConversationSubject, ApiError> {
+    let listing_id = non_blank(payload.listing_id.as_deref());
+    let request_id = non_blank(payload.request_id.as_deref());
+
+    match (listing_id, request_id) {
+        (Some(listing_id), None) => Ok(ConversationSubject::Listing(parse_uuid(
+            listing_id,
+            "listingId",
+        )?)),
+        (None, Some(request_id)) => Ok(ConversationSubject::Request(parse_uuid(
+            request_id,
+            "requestId",
+        )?)),
+        _ => Err(ApiError::bad_request(
+            "invalid_subject",
+            "Exactly one of listingId or requestId is required",
+        )),
+    }
+}
+
+fn non_blank(value: Option<&str>) -> Option<&str> {
+    value.map(str::trim).filter(|text| !text.is_empty())
+}
+
+fn normalize_message_body(body: &str) -> Result<String, ApiError> {
+    let trimmed = body.trim();
+    if trimmed.is_empty() {
+        return Err(ApiError::invalid_field(
+            "body",
+            "required",
+            "body is required",
+        ));
+    }
+    if trimmed.chars().count() > MAX_MESSAGE_CHARS {
+        return Err(ApiError::invalid_field(
+            "body",
+            "too_long",
+            format!("body must be at most {MAX_MESSAGE_CHARS} characters"),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn parse_page_query(query: Option<&str>) -> Result<PageQuery, ApiError> {
+    let mut limit: i64 = 20;
+    let mut offset: i64 = 0;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| invalid_limit())?;
+                    if !(1..=100).contains(&limit) {
+                        return Err(invalid_limit());
+                    }
+                }
+                "offset" => {
+                    offset = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|offset| *offset >= 0)
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "offset",
+                                "invalid_offset",
+                                "Invalid offset. Must be an integer greater than or equal to 0",
+                            )
+                        })?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(PageQuery { limit, offset })
+}
+
+const fn compute_next_offset(offset: i64, limit: i64, has_more: bool) -> Option<i64> {
+    if has_more {
+        offset.checked_add(limit)
+    } else {
+        None
+    }
+}
+
+fn row_to_conversation_response(row: &Row) -> ConversationResponse {
+    ConversationResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        listing_id: row
+            .get::<_, Option<Uuid>>("listing_id")
+            .map(|id| id.to_string()),
+        request_id: row
+            .get::<_, Option<Uuid>>("request_id")
+            .map(|id| id.to_string()),
+        owner_id: row.get::<_, Uuid>("owner_id").to_string(),
+        participant_id: row.get::<_, Uuid>("participant_id").to_string(),
+        unread_count: row.try_get::<_, i64>("unread_count").unwrap_or_default(),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        last_message_at: row.get::<_, DateTime<Utc>>("last_message_at").to_rfc3339(),
+    }
+}
+
+fn row_to_message_response(row: &Row) -> MessageResponse {
+    MessageResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        conversation_id: row.get::<_, Uuid>("conversation_id").to_string(),
+        sender_id: row.get::<_, Uuid>("sender_id").to_string(),
+        body: row.get("body"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    }
+}
+
+async fn emit_message_event_best_effort(
+    message: &MessageResponse,
+    recipient_id: Uuid,
+    listing_id: Option<String>,
+    request_id: Option<String>,
+    correlation_id: &str,
+) {
+    let detail = MessageEventDetail::new(
+        message.id.clone(),
+        message.conversation_id.clone(),
+        message.sender_id.clone(),
+        recipient_id.to_string(),
+        listing_id,
+        request_id,
+        correlation_id,
+    );
+
+    if let Err(event_error) = events::publish(events::MESSAGE_CREATED, &detail).await {
+        error!(
+            correlation_id = correlation_id,
+            message_id = message.id.as_str(),
+            error = %event_error,
+            "Failed to emit message event after successful write"
+        );
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn conversation_not_found() -> ApiError {
+    ApiError::not_found("conversation_not_found", "Conversation not found")
+}
+
+fn invalid_limit() -> ApiError {
+    ApiError::invalid_field(
+        "limit",
+        "invalid_limit",
+        "Invalid limit. Must be an integer between 1 and 100",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const LISTING_ID: &str = "5df666d4-f6b1-4e6f-97d6-321e531ad7ca";
+
+    fn payload(listing_id: Option<&str>, request_id: Option<&str>) -> StartConversationRequest {
+        StartConversationRequest {
+            listing_id: listing_id.map(str::to_string),
+            request_id: request_id.map(str::to_string),
+            body: "Are these sprayed?".to_string(),
+        }
+    }
+
+    #[test]
+    fn parse_subject_requires_exactly_one_target() {
+        assert_eq!(
+            parse_subject(&payload(Some(LISTING_ID), None)).unwrap(),
+            ConversationSubject::Listing(Uuid::parse_str(LISTING_ID).unwrap())
+        );
+        assert!(matches!(
+            parse_subject(&payload(None, Some(LISTING_ID))).unwrap(),
+            ConversationSubject::Request(_)
+        ));
+
+        let both = parse_subject(&payload(Some(LISTING_ID), Some(LISTING_ID))).unwrap_err();
+        assert_eq!(both.error_code(), "invalid_subject");
+        let neither = parse_subject(&payload(Some("  "), None)).unwrap_err();
+        assert_eq!(neither.error_code(), "invalid_subject");
+    }
+
+    #[test]
+    fn normalize_message_body_trims_and_bounds_length() {
+        assert_eq!(normalize_message_body("  hi  ").unwrap(), "hi");
+        assert_eq!(
+            normalize_message_body("   ").unwrap_err().error_code(),
+            "required"
+        );
+        assert!(normalize_message_body(&"é".repeat(MAX_MESSAGE_CHARS)).is_ok());
+        assert_eq!(
+            normalize_message_body(&"a".repeat(MAX_MESSAGE_CHARS + 1))
+                .unwrap_err()
+                .error_code(),
+            "too_long"
+        );
+    }
+
+    #[test]
+    fn other_member_rejects_outsiders() {
+        let owner_id = Uuid::new_v4();
+        let participant_id = Uuid::new_v4();
+        let members = ConversationMembers {
+            owner_id,
+            participant_id,
+            listing_id: None,
+            request_id: None,
+        };
+
+        assert_eq!(members.other_member(owner_id), Some(participant_id));
+        assert_eq!(members.other_member(participant_id), Some(owner_id));
+        assert_eq!(members.other_member(Uuid::new_v4()), None);
+    }
+
+    #[test]
+    fn parse_page_query_defaults_and_validates() {
+        let defaults = parse_page_query(None).unwrap();
+        assert_eq!((defaults.limit, defaults.offset), (20, 0));
+
+        let parsed = parse_page_query(Some("limit=5&offset=10")).unwrap();
+        assert_eq!((parsed.limit, parsed.offset), (5, 10));
+
+        assert!(parse_page_query(Some("limit=0")).is_err());
+        assert!(parse_page_query(Some("offset=-1")).is_err());
+    }
+}
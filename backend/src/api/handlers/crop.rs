@@ -1,8 +1,10 @@
-use crate::auth::{extract_auth_context_with_fallback, require_grower};
-use crate::db;
-use crate::models::crop::{ErrorResponse, GrowerCropItem, UpsertGrowerCropRequest};
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::handlers::garden;
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::models::crop::{GrowerCropItem, UpsertGrowerCropRequest};
 use lambda_http::{Body, Request, Response};
-use serde::Serialize;
 use tokio_postgres::{Client, Row};
 use tracing::info;
 use uuid::Uuid;
@@ -13,28 +15,27 @@ const ALLOWED_VISIBILITY: [&str; 3] = ["private", "local", "public"];
 pub async fn list_my_crops(
     request: &Request,
     _correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     // Require grower user type - gatherers will receive 403 Forbidden
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let client = db::connect().await?;
 
     let rows = client
-        .query(
+        .query_timed(
+            "crop::list_my_crops",
             "
             select id, user_id, crop_id, variety_id, status::text, visibility::text,
-                   surplus_enabled, nickname, default_unit, notes, created_at, updated_at
+                   surplus_enabled, nickname, default_unit, notes, bed_id, created_at, updated_at
             from grower_crop_library
             where user_id = $1
             order by created_at desc
             ",
             &[&user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let items = rows
         .into_iter()
@@ -47,51 +48,44 @@ pub async fn get_my_crop(
     request: &Request,
     _correlation_id: &str,
     crop_library_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     // Require grower user type - gatherers will receive 403 Forbidden
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let id = parse_uuid(crop_library_id, "crop library id")?;
     let client = db::connect().await?;
 
     let maybe_row = client
-        .query_opt(
+        .query_opt_timed(
+            "crop::get_my_crop",
             "
             select id, user_id, crop_id, variety_id, status::text, visibility::text,
-                   surplus_enabled, nickname, default_unit, notes, created_at, updated_at
+                   surplus_enabled, nickname, default_unit, notes, bed_id, created_at, updated_at
             from grower_crop_library
             where id = $1 and user_id = $2
             ",
             &[&id, &user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if let Some(row) = maybe_row {
         return json_response(200, &row_to_item(&row));
     }
 
-    json_response(
-        404,
-        &ErrorResponse {
-            error: "Grower crop record not found".to_string(),
-        },
-    )
+    Err(crop_not_found())
 }
 
 pub async fn create_my_crop(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     // Require grower user type - gatherers will receive 403 Forbidden
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let payload: UpsertGrowerCropRequest = parse_json_body(request)?;
     validate_upsert_payload(&payload)?;
 
@@ -99,18 +93,23 @@ pub async fn create_my_crop(
     let variety_id = parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?;
     let variety_id_text = variety_id.map(|v| v.to_string());
 
+    let bed_id = parse_optional_uuid(payload.bed_id.as_deref(), "bed_id")?;
+
     let client = db::connect().await?;
     validate_catalog_links(&client, crop_id, variety_id).await?;
+    if let Some(bed_id) = bed_id {
+        garden::ensure_bed_owned(&client, bed_id, user_id, "bed_id").await?;
+    }
 
     let row = client
-        .query_one(
+        .query_one_timed("crop::create_my_crop", 
             "
             insert into grower_crop_library
-                (user_id, crop_id, variety_id, status, visibility, surplus_enabled, nickname, default_unit, notes)
+                (user_id, crop_id, variety_id, status, visibility, surplus_enabled, nickname, default_unit, notes, bed_id)
             values
-                ($1, $2, $3::text::uuid, $4::text::grower_crop_status, $5::text::visibility_scope, $6, $7, $8, $9)
+                ($1, $2, $3::text::uuid, $4::text::grower_crop_status, $5::text::visibility_scope, $6, $7, $8, $9, $10)
             returning id, user_id, crop_id, variety_id, status::text, visibility::text,
-                      surplus_enabled, nickname, default_unit, notes, created_at, updated_at
+                      surplus_enabled, nickname, default_unit, notes, bed_id, created_at, updated_at
             ",
             &[
                 &user_id,
@@ -122,10 +121,10 @@ pub async fn create_my_crop(
                 &payload.nickname,
                 &payload.default_unit,
                 &payload.notes,
+                &bed_id,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     info!(
         correlation_id = correlation_id,
@@ -141,13 +140,12 @@ pub async fn update_my_crop(
     request: &Request,
     correlation_id: &str,
     crop_library_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     // Require grower user type - gatherers will receive 403 Forbidden
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let payload: UpsertGrowerCropRequest = parse_json_body(request)?;
     validate_upsert_payload(&payload)?;
 
@@ -156,11 +154,17 @@ pub async fn update_my_crop(
     let variety_id = parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?;
     let variety_id_text = variety_id.map(|v| v.to_string());
 
+    let bed_id = parse_optional_uuid(payload.bed_id.as_deref(), "bed_id")?;
+
     let client = db::connect().await?;
     validate_catalog_links(&client, crop_id, variety_id).await?;
+    if let Some(bed_id) = bed_id {
+        garden::ensure_bed_owned(&client, bed_id, user_id, "bed_id").await?;
+    }
 
     let maybe_row = client
-        .query_opt(
+        .query_opt_timed(
+            "crop::update_my_crop",
             "
             update grower_crop_library
             set crop_id = $1,
@@ -171,10 +175,11 @@ pub async fn update_my_crop(
                 nickname = $6,
                 default_unit = $7,
                 notes = $8,
+                bed_id = $11,
                 updated_at = now()
             where id = $9 and user_id = $10
             returning id, user_id, crop_id, variety_id, status::text, visibility::text,
-                      surplus_enabled, nickname, default_unit, notes, created_at, updated_at
+                      surplus_enabled, nickname, default_unit, notes, bed_id, created_at, updated_at
             ",
             &[
                 &crop_id,
@@ -187,10 +192,10 @@ pub async fn update_my_crop(
                 &payload.notes,
                 &id,
                 &user_id,
+                &bed_id,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if let Some(row) = maybe_row {
         info!(
@@ -202,43 +207,32 @@ pub async fn update_my_crop(
         return json_response(200, &row_to_item(&row));
     }
 
-    json_response(
-        404,
-        &ErrorResponse {
-            error: "Grower crop record not found".to_string(),
-        },
-    )
+    Err(crop_not_found())
 }
 
 pub async fn delete_my_crop(
     request: &Request,
     correlation_id: &str,
     crop_library_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     // Require grower user type - gatherers will receive 403 Forbidden
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let id = parse_uuid(crop_library_id, "crop library id")?;
     let client = db::connect().await?;
 
     let deleted = client
-        .execute(
+        .execute_timed(
+            "crop::delete_my_crop",
             "delete from grower_crop_library where id = $1 and user_id = $2",
             &[&id, &user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if deleted == 0 {
-        return json_response(
-            404,
-            &ErrorResponse {
-                error: "Grower crop record not found".to_string(),
-            },
-        );
+        return Err(crop_not_found());
     }
 
     info!(
@@ -251,24 +245,32 @@ pub async fn delete_my_crop(
     Response::builder()
         .status(204)
         .body(Body::Empty)
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
+        .map_err(|e| ApiError::internal(e.to_string()))
 }
 
-fn validate_upsert_payload(payload: &UpsertGrowerCropRequest) -> Result<(), lambda_http::Error> {
+fn validate_upsert_payload(payload: &UpsertGrowerCropRequest) -> Result<(), ApiError> {
     if !ALLOWED_STATUS.contains(&payload.status.as_str()) {
-        return Err(lambda_http::Error::from(format!(
-            "Invalid status '{}'. Allowed values: {}",
-            payload.status,
-            ALLOWED_STATUS.join(", ")
-        )));
+        return Err(ApiError::invalid_field(
+            "status",
+            "invalid_enum",
+            format!(
+                "Invalid status '{}'. Allowed values: {}",
+                payload.status,
+                ALLOWED_STATUS.join(", ")
+            ),
+        ));
     }
 
     if !ALLOWED_VISIBILITY.contains(&payload.visibility.as_str()) {
-        return Err(lambda_http::Error::from(format!(
-            "Invalid visibility '{}'. Allowed values: {}",
-            payload.visibility,
-            ALLOWED_VISIBILITY.join(", ")
-        )));
+        return Err(ApiError::invalid_field(
+            "visibility",
+            "invalid_enum",
+            format!(
+                "Invalid visibility '{}'. Allowed values: {}",
+                payload.visibility,
+                ALLOWED_VISIBILITY.join(", ")
+            ),
+        ));
     }
 
     Ok(())
@@ -278,35 +280,39 @@ async fn validate_catalog_links(
     client: &Client,
     crop_id: Uuid,
     variety_id: Option<Uuid>,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let crop_exists = client
-        .query_one(
+        .query_one_timed(
+            "crop::validate_catalog_links",
             "select exists(select 1 from crops where id = $1)",
             &[&crop_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?
+        .await?
         .get::<_, bool>(0);
 
     if !crop_exists {
-        return Err(lambda_http::Error::from(
-            "crop_id does not reference an existing catalog crop".to_string(),
+        return Err(ApiError::invalid_field(
+            "crop_id",
+            "unknown_crop",
+            "crop_id does not reference an existing catalog crop",
         ));
     }
 
     if let Some(variety) = variety_id {
         let matches = client
-            .query_one(
+            .query_one_timed(
+                "crop::validate_catalog_links",
                 "select exists(select 1 from crop_varieties where id = $1 and crop_id = $2)",
                 &[&variety, &crop_id],
             )
-            .await
-            .map_err(|error| db_error(&error))?
+            .await?
             .get::<_, bool>(0);
 
         if !matches {
-            return Err(lambda_http::Error::from(
-                "variety_id must belong to the specified crop_id".to_string(),
+            return Err(ApiError::invalid_field(
+                "variety_id",
+                "variety_crop_mismatch",
+                "variety_id must belong to the specified crop_id",
             ));
         }
     }
@@ -314,33 +320,10 @@ async fn validate_catalog_links(
     Ok(())
 }
 
-fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, lambda_http::Error> {
-    let normalized = value.trim();
-    Uuid::parse_str(normalized)
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid UUID")))
-}
-
-fn parse_optional_uuid(
-    value: Option<&str>,
-    field_name: &str,
-) -> Result<Option<Uuid>, lambda_http::Error> {
+fn parse_optional_uuid(value: Option<&str>, field_name: &str) -> Result<Option<Uuid>, ApiError> {
     value.map_or(Ok(None), |v| parse_uuid(v, field_name).map(Some))
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
 fn row_to_item(row: &Row) -> GrowerCropItem {
     GrowerCropItem {
         id: row.get::<_, Uuid>("id").to_string(),
@@ -355,6 +338,7 @@ fn row_to_item(row: &Row) -> GrowerCropItem {
         nickname: row.get("nickname"),
         default_unit: row.get("default_unit"),
         notes: row.get("notes"),
+        bed_id: row.get::<_, Option<Uuid>>("bed_id").map(|v| v.to_string()),
         created_at: row
             .get::<_, chrono::DateTime<chrono::Utc>>("created_at")
             .to_rfc3339(),
@@ -364,31 +348,8 @@ fn row_to_item(row: &Row) -> GrowerCropItem {
     }
 }
 
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-pub fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(
-        status,
-        &ErrorResponse {
-            error: message.to_string(),
-        },
-    )
+fn crop_not_found() -> ApiError {
+    ApiError::not_found("crop_not_found", "Grower crop record not found")
 }
 
 #[cfg(test)]
@@ -405,6 +366,7 @@ mod tests {
             nickname: None,
             default_unit: None,
             notes: None,
+            bed_id: None,
         }
     }
 
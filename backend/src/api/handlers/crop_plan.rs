@@ -0,0 +1,609 @@
+//! Seasonal crop plans under `/me/crop-plans`: what a grower intends to
+//! plant in a coming season and how much they expect to harvest. The
+//! grower's geo key is copied onto each plan so the signal aggregation can
+//! count planned quantities as anticipated supply, and the feed can warn
+//! when many neighbors plan the same crop.
+
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::models::crop_plan::{CropPlanItem, ListCropPlansResponse, UpsertCropPlanRequest};
+use crate::quantity;
+use chrono::{DateTime, Datelike, Months, NaiveDate, Utc};
+use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const SEASONS: [&str; 4] = ["spring", "summer", "fall", "winter"];
+const MAX_UNIT_CHARS: usize = 32;
+const MAX_NOTES_CHARS: usize = 2000;
+/// Plans further out than this are guesses, not supply worth signalling.
+const MAX_YEARS_AHEAD: i32 = 2;
+
+#[derive(Debug)]
+struct NormalizedPlan {
+    crop_id: Uuid,
+    variety_id: Option<Uuid>,
+    season: &'static str,
+    season_year: i16,
+    expected_quantity: Decimal,
+    unit: String,
+    notes: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ListCropPlansQuery {
+    season_year: Option<i16>,
+    season: Option<&'static str>,
+}
+
+pub async fn list_crop_plans(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let query = parse_list_crop_plans_query(request.uri().query())?;
+    let client = db::connect().await?;
+
+    let rows = client
+        .query_timed(
+            "crop_plan::list_crop_plans",
+            "
+            select id, crop_id, variety_id, season, season_year::int as season_year,
+                   season_starts_on::text as season_starts_on,
+                   expected_quantity::text as expected_quantity, unit, notes,
+                   created_at, updated_at
+              from crop_plans
+             where user_id = $1
+               and ($2::smallint is null or season_year = $2)
+               and ($3::text is null or season = $3)
+             order by season_starts_on desc, created_at asc, id asc
+            ",
+            &[&user_id, &query.season_year, &query.season],
+        )
+        .await?;
+
+    let items = rows.iter().map(row_to_item).collect::<Vec<_>>();
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        returned_count = items.len(),
+        "Listed crop plans"
+    );
+
+    json_response(200, &ListCropPlansResponse { items })
+}
+
+pub async fn create_crop_plan(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: UpsertCropPlanRequest = parse_json_body(request)?;
+    let plan = normalize_plan(&payload, Utc::now().date_naive())?;
+
+    let client = db::connect().await?;
+    validate_catalog_links(&client, plan.crop_id, plan.variety_id).await?;
+    ensure_plan_available(&client, user_id, &plan, None).await?;
+
+    let row = client
+        .query_one_timed(
+            "crop_plan::create_crop_plan",
+            "
+            insert into crop_plans
+                (user_id, crop_id, variety_id, season, season_year, expected_quantity, unit,
+                 notes, geo_key)
+            values
+                ($1, $2, $3, $4, $5, $6::numeric, $7, $8,
+                 (select geo_key from grower_profiles where user_id = $1))
+            returning id, crop_id, variety_id, season, season_year::int as season_year,
+                      season_starts_on::text as season_starts_on,
+                      expected_quantity::text as expected_quantity, unit, notes,
+                      created_at, updated_at
+            ",
+            &[
+                &user_id,
+                &plan.crop_id,
+                &plan.variety_id,
+                &plan.season,
+                &plan.season_year,
+                &plan.expected_quantity,
+                &plan.unit,
+                &plan.notes,
+            ],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        crop_plan_id = %row.get::<_, Uuid>("id"),
+        season = plan.season,
+        season_year = plan.season_year,
+        "Created crop plan"
+    );
+
+    json_response(201, &row_to_item(&row))
+}
+
+/// Replaces the plan's fields and refreshes its geo key from the grower's
+/// current profile, since the plan is about a season that has not happened.
+pub async fn update_crop_plan(
+    request: &Request,
+    correlation_id: &str,
+    plan_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(plan_id, "planId")?;
+    let payload: UpsertCropPlanRequest = parse_json_body(request)?;
+    let plan = normalize_plan(&payload, Utc::now().date_naive())?;
+
+    let client = db::connect().await?;
+    validate_catalog_links(&client, plan.crop_id, plan.variety_id).await?;
+    ensure_plan_available(&client, user_id, &plan, Some(id)).await?;
+
+    let row = client
+        .query_opt_timed(
+            "crop_plan::update_crop_plan",
+            "
+            update crop_plans
+               set crop_id = $1,
+                   variety_id = $2,
+                   season = $3,
+                   season_year = $4,
+                   expected_quantity = $5::numeric,
+                   unit = $6,
+                   notes = $7,
+                   geo_key = (select geo_key from grower_profiles where user_id = $9),
+                   updated_at = now()
+             where id = $8 and user_id = $9
+            returning id, crop_id, variety_id, season, season_year::int as season_year,
+                      season_starts_on::text as season_starts_on,
+                      expected_quantity::text as expected_quantity, unit, notes,
+                      created_at, updated_at
+            ",
+            &[
+                &plan.crop_id,
+                &plan.variety_id,
+                &plan.season,
+                &plan.season_year,
+                &plan.expected_quantity,
+                &plan.unit,
+                &plan.notes,
+                &id,
+                &user_id,
+            ],
+        )
+        .await?
+        .ok_or_else(crop_plan_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        crop_plan_id = %id,
+        "Updated crop plan"
+    );
+
+    json_response(200, &row_to_item(&row))
+}
+
+pub async fn delete_crop_plan(
+    request: &Request,
+    correlation_id: &str,
+    plan_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(plan_id, "planId")?;
+    let client = db::connect().await?;
+
+    let deleted = client
+        .execute_timed(
+            "crop_plan::delete_crop_plan",
+            "delete from crop_plans where id = $1 and user_id = $2",
+            &[&id, &user_id],
+        )
+        .await?;
+    if deleted == 0 {
+        return Err(crop_plan_not_found());
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        crop_plan_id = %id,
+        "Deleted crop plan"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// First day of `season` in `season_year`, matching the generated
+/// `season_starts_on` column.
+fn season_starts_on(season: &str, season_year: i32) -> Option<NaiveDate> {
+    let month = match season {
+        "spring" => 3,
+        "summer" => 6,
+        "fall" => 9,
+        "winter" => 12,
+        _ => return None,
+    };
+    NaiveDate::from_ymd_opt(season_year, month, 1)
+}
+
+fn normalize_plan(
+    payload: &UpsertCropPlanRequest,
+    today: NaiveDate,
+) -> Result<NormalizedPlan, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let crop_id = errors.capture(parse_uuid(&payload.crop_id, "cropId"));
+    let variety_id = errors
+        .capture(
+            payload
+                .variety_id
+                .as_deref()
+                .map_or(Ok(None), |v| parse_uuid(v, "varietyId").map(Some)),
+        )
+        .flatten();
+
+    let season = SEASONS
+        .iter()
+        .copied()
+        .find(|season| season.eq_ignore_ascii_case(payload.season.trim()));
+    if season.is_none() {
+        errors.add(
+            "season",
+            "invalid_season",
+            "season must be one of spring, summer, fall, winter",
+        );
+    }
+
+    let season_year = i16::try_from(payload.season_year).ok();
+    let starts_on = season.and_then(|season| season_starts_on(season, payload.season_year));
+    match (season_year, starts_on) {
+        (None, _) => errors.add(
+            "seasonYear",
+            "invalid_season_year",
+            "seasonYear must be a calendar year",
+        ),
+        (Some(_), Some(start)) if start + Months::new(3) <= today => errors.add(
+            "seasonYear",
+            "past_season",
+            "Plans can only be made for the current or a coming season",
+        ),
+        (Some(_), _) if payload.season_year > today.year() + MAX_YEARS_AHEAD => errors.add(
+            "seasonYear",
+            "too_far_ahead",
+            format!("seasonYear must be at most {MAX_YEARS_AHEAD} years ahead"),
+        ),
+        _ => {}
+    }
+
+    let expected_quantity = errors.capture(quantity::validate(
+        payload.expected_quantity,
+        "expectedQuantity",
+    ));
+    if expected_quantity.is_some_and(|value| value <= Decimal::ZERO) {
+        errors.add(
+            "expectedQuantity",
+            "must_be_positive",
+            "expectedQuantity must be greater than 0",
+        );
+    }
+
+    let unit = trimmed(Some(&payload.unit));
+    match &unit {
+        None => errors.add("unit", "required", "unit is required"),
+        Some(unit) if unit.chars().count() > MAX_UNIT_CHARS => errors.add(
+            "unit",
+            "too_long",
+            format!("unit must be at most {MAX_UNIT_CHARS} characters"),
+        ),
+        Some(_) => {}
+    }
+
+    let notes = trimmed(payload.notes.as_deref());
+    if notes
+        .as_ref()
+        .is_some_and(|notes| notes.chars().count() > MAX_NOTES_CHARS)
+    {
+        errors.add(
+            "notes",
+            "too_long",
+            format!("notes must be at most {MAX_NOTES_CHARS} characters"),
+        );
+    }
+
+    errors.into_result()?;
+    let (Some(crop_id), Some(season), Some(season_year), Some(expected_quantity), Some(unit)) =
+        (crop_id, season, season_year, expected_quantity, unit)
+    else {
+        return Err(ApiError::internal(
+            "crop plan validation passed with missing fields",
+        ));
+    };
+
+    Ok(NormalizedPlan {
+        crop_id,
+        variety_id,
+        season,
+        season_year,
+        expected_quantity,
+        unit,
+        notes,
+    })
+}
+
+async fn validate_catalog_links(
+    client: &Client,
+    crop_id: Uuid,
+    variety_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let crop_exists = client
+        .query_one_timed(
+            "crop_plan::validate_catalog_links",
+            "select exists(select 1 from crops where id = $1)",
+            &[&crop_id],
+        )
+        .await?
+        .get::<_, bool>(0);
+
+    if !crop_exists {
+        return Err(ApiError::invalid_field(
+            "cropId",
+            "unknown_crop",
+            "cropId does not reference an existing catalog crop",
+        ));
+    }
+
+    if let Some(variety) = variety_id {
+        let matches = client
+            .query_one_timed(
+                "crop_plan::validate_catalog_links",
+                "select exists(select 1 from crop_varieties where id = $1 and crop_id = $2)",
+                &[&variety, &crop_id],
+            )
+            .await?
+            .get::<_, bool>(0);
+
+        if !matches {
+            return Err(ApiError::invalid_field(
+                "varietyId",
+                "variety_crop_mismatch",
+                "varietyId must belong to the specified cropId",
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// One plan per crop and variety per season; a second would double-count
+/// the grower's anticipated supply.
+async fn ensure_plan_available(
+    client: &Client,
+    user_id: Uuid,
+    plan: &NormalizedPlan,
+    except: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let taken = client
+        .query_one_timed(
+            "crop_plan::ensure_plan_available",
+            "
+            select exists(
+              select 1 from crop_plans
+               where user_id = $1 and season_year = $2 and season = $3 and crop_id = $4
+                 and variety_id is not distinct from $5
+                 and ($6::uuid is null or id <> $6)
+            )
+            ",
+            &[
+                &user_id,
+                &plan.season_year,
+                &plan.season,
+                &plan.crop_id,
+                &plan.variety_id,
+                &except,
+            ],
+        )
+        .await?
+        .get::<_, bool>(0);
+
+    if taken {
+        return Err(ApiError::conflict(
+            "crop_plan_exists",
+            "You already have a plan for this crop in this season",
+        ));
+    }
+    Ok(())
+}
+
+fn parse_list_crop_plans_query(query: Option<&str>) -> Result<ListCropPlansQuery, ApiError> {
+    let mut parsed = ListCropPlansQuery {
+        season_year: None,
+        season: None,
+    };
+
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "seasonYear" => {
+                parsed.season_year = Some(value.parse::<i16>().map_err(|_| {
+                    ApiError::invalid_field(
+                        "seasonYear",
+                        "invalid_season_year",
+                        "seasonYear must be a calendar year",
+                    )
+                })?);
+            }
+            "season" => {
+                parsed.season = Some(
+                    SEASONS
+                        .iter()
+                        .copied()
+                        .find(|season| season.eq_ignore_ascii_case(value))
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "season",
+                                "invalid_season",
+                                "season must be one of spring, summer, fall, winter",
+                            )
+                        })?,
+                );
+            }
+            _ => {}
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn trimmed(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn row_to_item(row: &Row) -> CropPlanItem {
+    CropPlanItem {
+        id: row.get::<_, Uuid>("id").to_string(),
+        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+        variety_id: row
+            .get::<_, Option<Uuid>>("variety_id")
+            .map(|v| v.to_string()),
+        season: row.get("season"),
+        season_year: row.get("season_year"),
+        season_starts_on: row.get("season_starts_on"),
+        expected_quantity: row.get("expected_quantity"),
+        unit: row.get("unit"),
+        notes: row.get("notes"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn crop_plan_not_found() -> ApiError {
+    ApiError::not_found("crop_plan_not_found", "Crop plan not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn payload() -> UpsertCropPlanRequest {
+        UpsertCropPlanRequest {
+            crop_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
+            variety_id: None,
+            season: " Summer ".to_string(),
+            season_year: 2026,
+            expected_quantity: Decimal::from_str("40").unwrap(),
+            unit: " lb ".to_string(),
+            notes: Some("   ".to_string()),
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 7, 15).unwrap()
+    }
+
+    fn issue_codes(error: ApiError) -> Vec<(String, &'static str)> {
+        match error {
+            ApiError::Validation { issues } => issues
+                .iter()
+                .map(|issue| (issue.field.clone(), issue.code))
+                .collect(),
+            other => panic!("unexpected error: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn normalize_accepts_the_current_season() {
+        let plan = normalize_plan(&payload(), today()).unwrap();
+        assert_eq!(plan.season, "summer");
+        assert_eq!(plan.season_year, 2026);
+        assert_eq!(plan.unit, "lb");
+        assert_eq!(plan.notes, None);
+    }
+
+    #[test]
+    fn normalize_rejects_past_and_distant_seasons() {
+        let mut spring = payload();
+        spring.season = "spring".to_string();
+        assert_eq!(
+            issue_codes(normalize_plan(&spring, today()).unwrap_err()),
+            vec![("seasonYear".to_string(), "past_season")]
+        );
+
+        let mut distant = payload();
+        distant.season_year = 2029;
+        assert_eq!(
+            issue_codes(normalize_plan(&distant, today()).unwrap_err()),
+            vec![("seasonYear".to_string(), "too_far_ahead")]
+        );
+    }
+
+    #[test]
+    fn normalize_reports_every_invalid_field() {
+        let mut invalid = payload();
+        invalid.crop_id = "nope".to_string();
+        invalid.season = "monsoon".to_string();
+        invalid.expected_quantity = Decimal::ZERO;
+        invalid.unit = "  ".to_string();
+
+        assert_eq!(
+            issue_codes(normalize_plan(&invalid, today()).unwrap_err()),
+            vec![
+                ("cropId".to_string(), "invalid_uuid"),
+                ("season".to_string(), "invalid_season"),
+                ("expectedQuantity".to_string(), "must_be_positive"),
+                ("unit".to_string(), "required"),
+            ]
+        );
+    }
+
+    #[test]
+    fn winter_starts_in_december_of_its_year() {
+        assert_eq!(
+            season_starts_on("winter", 2026),
+            NaiveDate::from_ymd_opt(2026, 12, 1)
+        );
+        assert_eq!(season_starts_on("monsoon", 2026), None);
+
+        let mut winter = payload();
+        winter.season = "winter".to_string();
+        winter.season_year = 2025;
+        let january = NaiveDate::from_ymd_opt(2026, 1, 20).unwrap();
+        assert!(normalize_plan(&winter, january).is_ok());
+    }
+
+    #[test]
+    fn list_query_parses_filters() {
+        assert_eq!(
+            parse_list_crop_plans_query(Some("seasonYear=2027&season=fall")).unwrap(),
+            ListCropPlansQuery {
+                season_year: Some(2027),
+                season: Some("fall"),
+            }
+        );
+        let bad = parse_list_crop_plans_query(Some("season=monsoon"));
+        assert_eq!(bad.unwrap_err().error_code(), "invalid_season");
+    }
+}
@@ -0,0 +1,354 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const DEFAULT_PREVIEW_LIMIT: i64 = 5;
+const MAX_PREVIEW_LIMIT: i64 = 20;
+
+/// Every count on the grower home screen in one round trip. Claims count
+/// against the grower's undeleted listings; unread messages are those from
+/// the other member newer than the grower's read cursor, as in
+/// `GET /conversations`.
+const DASHBOARD_COUNTS: &str = "
+    with unread as (
+        select m.conversation_id
+        from conversations c
+        join messages m on m.conversation_id = c.id
+        left join conversation_reads r
+          on r.conversation_id = c.id and r.user_id = $1
+        where (c.owner_id = $1 or c.participant_id = $1)
+          and m.sender_id <> $1
+          and m.created_at > coalesce(r.last_read_at, '-infinity'::timestamptz)
+    ),
+    open_claims as (
+        select c.status
+        from claims c
+        join surplus_listings l on l.id = c.listing_id
+        where l.user_id = $1
+          and l.deleted_at is null
+          and c.status in ('pending', 'confirmed')
+    )
+    select (select count(*)
+            from surplus_listings
+            where user_id = $1
+              and deleted_at is null
+              and status = 'active'::listing_status) as active_listings,
+           (select count(*) from open_claims where status = 'pending') as pending_claims,
+           (select count(*) from open_claims where status = 'confirmed') as upcoming_pickups,
+           (select count(*) from unread) as unread_messages,
+           (select count(distinct conversation_id) from unread) as unread_conversations
+";
+
+/// The grower's active listings, the ones closing soonest first.
+const ACTIVE_LISTINGS: &str = "
+    select l.id,
+           coalesce(l.title, cr.common_name, initcap(replace(l.listing_kind::text, '_', ' '))) as title,
+           cr.common_name as crop_name,
+           l.quantity_remaining::text as quantity_remaining, l.unit,
+           l.available_end, l.expire_at
+    from surplus_listings l
+    left join crops cr on cr.id = l.crop_id
+    where l.user_id = $1
+      and l.deleted_at is null
+      and l.status = 'active'::listing_status
+    order by least(l.available_end, l.expire_at) asc nulls last, l.created_at desc, l.id desc
+    limit $2
+";
+
+/// Claims on the grower's listings in one status. Pending claims come oldest
+/// first, since their holds lapse in that order; confirmed ones by when the
+/// pickup window opens, as `GET /me/pickups` orders them for the gatherer.
+const CLAIMS_BY_STATUS: &str = "
+    select c.id, c.listing_id,
+           coalesce(l.title, cr.common_name, initcap(replace(l.listing_kind::text, '_', ' '))) as title,
+           c.claimer_id, u.display_name as claimer_name,
+           c.quantity_claimed::text as quantity_claimed, l.unit,
+           c.status::text as status,
+           c.claimed_at, c.hold_expires_at,
+           l.available_start, l.available_end,
+           c.delivery_requested
+    from claims c
+    join surplus_listings l on l.id = c.listing_id
+    left join crops cr on cr.id = l.crop_id
+    left join users u on u.id = c.claimer_id
+    where l.user_id = $1
+      and l.deleted_at is null
+      and c.status::text = $2
+    order by
+      case when $2 = 'pending' then c.claimed_at end asc,
+      case when $2 = 'confirmed'
+        then coalesce(l.available_start, c.confirmed_at) end asc nulls last,
+      c.id asc
+    limit $3
+";
+
+#[derive(Debug)]
+struct DashboardQuery {
+    limit: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct DashboardCounts {
+    active_listings: i64,
+    pending_claims: i64,
+    upcoming_pickups: i64,
+    unread_messages: i64,
+    unread_conversations: i64,
+}
+
+/// A count with the first few items behind it. `count` is the full total;
+/// `items` holds at most the requested `limit`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardSection<T> {
+    pub count: i64,
+    pub items: Vec<T>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardListing {
+    pub id: String,
+    pub title: String,
+    pub crop_name: Option<String>,
+    pub quantity_remaining: Option<String>,
+    pub unit: Option<String>,
+    pub available_end: Option<String>,
+    pub expire_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardClaim {
+    pub claim_id: String,
+    pub listing_id: String,
+    pub title: String,
+    pub claimer_id: String,
+    pub claimer_name: Option<String>,
+    pub quantity_claimed: String,
+    pub unit: Option<String>,
+    pub status: String,
+    pub claimed_at: String,
+    /// When a pending claim's reservation lapses; null once confirmed.
+    pub hold_expires_at: Option<String>,
+    pub available_start: Option<String>,
+    pub available_end: Option<String>,
+    pub delivery_requested: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UnreadMessagesSummary {
+    pub count: i64,
+    pub conversation_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DashboardResponse {
+    pub active_listings: DashboardSection<DashboardListing>,
+    pub pending_claims: DashboardSection<DashboardClaim>,
+    pub unread_messages: UnreadMessagesSummary,
+    pub next_pickups: DashboardSection<DashboardClaim>,
+    pub generated_at: String,
+}
+
+/// The grower home screen in one call: active listings, claims waiting on
+/// the grower, unread messages and confirmed pickups still to hand over.
+/// Counts are totals; each list is a preview of at most `limit` items.
+pub async fn get_my_dashboard(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let query = parse_dashboard_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let (counts, active_listings, pending_claims, next_pickups) = tokio::try_join!(
+        load_counts(&client, user_id),
+        load_active_listings(&client, user_id, query.limit),
+        load_claims(&client, user_id, "pending", query.limit),
+        load_claims(&client, user_id, "confirmed", query.limit),
+    )?;
+
+    let response = DashboardResponse {
+        active_listings: DashboardSection {
+            count: counts.active_listings,
+            items: active_listings,
+        },
+        pending_claims: DashboardSection {
+            count: counts.pending_claims,
+            items: pending_claims,
+        },
+        unread_messages: UnreadMessagesSummary {
+            count: counts.unread_messages,
+            conversation_count: counts.unread_conversations,
+        },
+        next_pickups: DashboardSection {
+            count: counts.upcoming_pickups,
+            items: next_pickups,
+        },
+        generated_at: Utc::now().to_rfc3339(),
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        limit = query.limit,
+        active_listing_count = response.active_listings.count,
+        pending_claim_count = response.pending_claims.count,
+        unread_message_count = response.unread_messages.count,
+        upcoming_pickup_count = response.next_pickups.count,
+        "Loaded grower dashboard"
+    );
+
+    json_response(200, &response)
+}
+
+async fn load_counts(client: &Client, user_id: Uuid) -> Result<DashboardCounts, ApiError> {
+    let row = client
+        .query_one_timed("dashboard::counts", DASHBOARD_COUNTS, &[&user_id])
+        .await?;
+
+    Ok(DashboardCounts {
+        active_listings: row.get("active_listings"),
+        pending_claims: row.get("pending_claims"),
+        upcoming_pickups: row.get("upcoming_pickups"),
+        unread_messages: row.get("unread_messages"),
+        unread_conversations: row.get("unread_conversations"),
+    })
+}
+
+async fn load_active_listings(
+    client: &Client,
+    user_id: Uuid,
+    limit: i64,
+) -> Result<Vec<DashboardListing>, ApiError> {
+    let rows = client
+        .query_timed(
+            "dashboard::active_listings",
+            ACTIVE_LISTINGS,
+            &[&user_id, &limit],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| DashboardListing {
+            id: row.get::<_, Uuid>("id").to_string(),
+            title: row.get("title"),
+            crop_name: row.get("crop_name"),
+            quantity_remaining: row.get("quantity_remaining"),
+            unit: row.get("unit"),
+            available_end: optional_timestamp(row, "available_end"),
+            expire_at: optional_timestamp(row, "expire_at"),
+        })
+        .collect())
+}
+
+async fn load_claims(
+    client: &Client,
+    user_id: Uuid,
+    status: &str,
+    limit: i64,
+) -> Result<Vec<DashboardClaim>, ApiError> {
+    let rows = client
+        .query_timed(
+            "dashboard::claims",
+            CLAIMS_BY_STATUS,
+            &[&user_id, &status, &limit],
+        )
+        .await?;
+
+    Ok(rows.iter().map(row_to_dashboard_claim).collect())
+}
+
+fn row_to_dashboard_claim(row: &Row) -> DashboardClaim {
+    DashboardClaim {
+        claim_id: row.get::<_, Uuid>("id").to_string(),
+        listing_id: row.get::<_, Uuid>("listing_id").to_string(),
+        title: row.get("title"),
+        claimer_id: row.get::<_, Uuid>("claimer_id").to_string(),
+        claimer_name: row.get("claimer_name"),
+        quantity_claimed: row.get("quantity_claimed"),
+        unit: row.get("unit"),
+        status: row.get("status"),
+        claimed_at: row.get::<_, DateTime<Utc>>("claimed_at").to_rfc3339(),
+        hold_expires_at: optional_timestamp(row, "hold_expires_at"),
+        available_start: optional_timestamp(row, "available_start"),
+        available_end: optional_timestamp(row, "available_end"),
+        delivery_requested: row.get("delivery_requested"),
+    }
+}
+
+fn optional_timestamp(row: &Row, column: &str) -> Option<String> {
+    row.get::<_, Option<DateTime<Utc>>>(column)
+        .map(|value| value.to_rfc3339())
+}
+
+fn parse_dashboard_query(query: Option<&str>) -> Result<DashboardQuery, ApiError> {
+    let mut limit = DEFAULT_PREVIEW_LIMIT;
+
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        if key == "limit" {
+            limit = value.parse::<i64>().map_err(|_| {
+                ApiError::invalid_field(
+                    "limit",
+                    "invalid_limit",
+                    "Invalid limit. Must be an integer",
+                )
+            })?;
+            if !(1..=MAX_PREVIEW_LIMIT).contains(&limit) {
+                return Err(ApiError::invalid_field(
+                    "limit",
+                    "invalid_limit",
+                    format!("Invalid limit. Must be between 1 and {MAX_PREVIEW_LIMIT}"),
+                ));
+            }
+        }
+    }
+
+    Ok(DashboardQuery { limit })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_dashboard_query_defaults_the_limit() {
+        assert_eq!(
+            parse_dashboard_query(None).unwrap().limit,
+            DEFAULT_PREVIEW_LIMIT
+        );
+        assert_eq!(
+            parse_dashboard_query(Some("other=1&")).unwrap().limit,
+            DEFAULT_PREVIEW_LIMIT
+        );
+    }
+
+    #[test]
+    fn parse_dashboard_query_validates_the_limit() {
+        for raw in ["limit=0", "limit=21", "limit=five", "limit="] {
+            let error = parse_dashboard_query(Some(raw)).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_limit", "{raw}");
+        }
+        assert_eq!(parse_dashboard_query(Some("limit=20")).unwrap().limit, 20);
+    }
+}
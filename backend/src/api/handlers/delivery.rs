@@ -0,0 +1,864 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::events::{self, DeliveryEventDetail};
+use crate::handlers::listing_discovery::parse_positive_radius;
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::location;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{Client, Row};
+use tracing::{error, info};
+use uuid::Uuid;
+
+const ALLOWED_UPDATE_STATUSES: [&str; 3] = ["picked_up", "delivered", "cancelled"];
+
+/// Joins a delivery to its claim and both ends of the run. `$1` is the claim.
+macro_rules! find_delivery_sql {
+    () => {
+        "select d.claim_id, d.driver_id, d.status, d.offered_at, d.accepted_at,
+                d.picked_up_at, d.delivered_at, d.cancelled_at,
+                c.claimer_id, l.user_id as listing_owner_id,
+                l.effective_pickup_address as pickup_address,
+                ga.address as dropoff_address
+         from claim_deliveries d
+         inner join claims c on c.id = d.claim_id
+         inner join surplus_listings l on l.id = c.listing_id
+         left join gatherer_profiles ga on ga.user_id = c.claimer_id
+         where d.claim_id = $1"
+    };
+}
+
+const FIND_DELIVERY: &str = find_delivery_sql!();
+const LOCK_DELIVERY: &str = concat!(find_delivery_sql!(), "\n         for update of d");
+
+/// Confirmed claims that asked for delivery and have no live offer. Only the
+/// listing's geohash and a coarse drop-off area are exposed; full addresses
+/// are revealed to the driver once the claimer accepts.
+const LIST_OPEN_DELIVERIES: &str = "
+    select c.id as claim_id, c.listing_id, l.title, l.unit,
+           c.quantity_claimed::text as quantity_claimed,
+           l.geo_key as pickup_geo_key, left(ga.geo_key, 5) as dropoff_area,
+           c.confirmed_at
+    from claims c
+    inner join surplus_listings l on l.id = c.listing_id
+    left join gatherer_profiles ga on ga.user_id = c.claimer_id
+    left join claim_deliveries d on d.claim_id = c.id
+    where c.delivery_requested = true
+      and c.status = 'confirmed'
+      and c.claimer_id <> $1
+      and l.deleted_at is null
+      and l.geo_key like $2
+      and (d.claim_id is null or d.status = 'cancelled')
+    order by c.confirmed_at asc, c.id asc
+    limit $3 offset $4
+";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateDeliveryRequest {
+    pub status: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeliveryResponse {
+    pub claim_id: String,
+    pub driver_id: String,
+    pub claimer_id: String,
+    pub status: String,
+    pub pickup_address: Option<String>,
+    pub dropoff_address: Option<String>,
+    pub offered_at: String,
+    pub accepted_at: Option<String>,
+    pub picked_up_at: Option<String>,
+    pub delivered_at: Option<String>,
+    pub cancelled_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenDeliveryItem {
+    pub claim_id: String,
+    pub listing_id: String,
+    pub title: Option<String>,
+    pub unit: Option<String>,
+    pub quantity_claimed: String,
+    pub pickup_geo_key: Option<String>,
+    pub dropoff_area: Option<String>,
+    pub confirmed_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListOpenDeliveriesResponse {
+    pub items: Vec<OpenDeliveryItem>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug)]
+struct OpenDeliveriesQuery {
+    geo_key: String,
+    radius_km: Option<f64>,
+    limit: i64,
+    offset: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeliveryStatus {
+    Offered,
+    Accepted,
+    PickedUp,
+    Delivered,
+    Cancelled,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum DeliveryActor {
+    Driver,
+    Claimer,
+    ListingOwner,
+}
+
+/// Claim state checked before a driver may offer a delivery.
+#[derive(Debug)]
+struct OfferContext<'a> {
+    driver_is_volunteer: bool,
+    driver_is_claimer: bool,
+    delivery_requested: bool,
+    claim_status: &'a str,
+    existing_delivery_status: Option<&'a str>,
+}
+
+pub async fn list_open_deliveries(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let query = parse_open_deliveries_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    if !is_volunteer_driver(&client, user_id).await? {
+        return Err(volunteer_driver_required());
+    }
+
+    let geo_prefix = location::geo_prefix_for_radius(&query.geo_key, query.radius_km);
+    let geo_pattern = format!("{geo_prefix}%");
+    let fetch_limit = query.limit + 1;
+
+    let rows = client
+        .query_timed(
+            "delivery::list_open_deliveries",
+            LIST_OPEN_DELIVERIES,
+            &[&user_id, &geo_pattern, &fetch_limit, &query.offset],
+        )
+        .await?;
+
+    let limit = usize::try_from(query.limit).map_err(|_| invalid_limit())?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_open_delivery)
+        .collect::<Vec<_>>();
+
+    let response = ListOpenDeliveriesResponse {
+        items,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: if has_more {
+            Some(query.offset + query.limit)
+        } else {
+            None
+        },
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        geo_prefix = geo_prefix,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        "Listed open delivery requests"
+    );
+
+    json_response(200, &response)
+}
+
+/// A volunteer driver offers to carry a confirmed claim. A cancelled offer
+/// is replaced; any live offer blocks new ones.
+pub async fn offer_delivery(
+    request: &Request,
+    correlation_id: &str,
+    claim_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let driver_id = extract_user_id(request)?;
+    let claim_id = parse_uuid(claim_id, "claimId")?;
+
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let claim_row = tx
+        .query_opt_timed(
+            "delivery::offer_delivery",
+            "
+            select c.claimer_id, c.status::text as claim_status, c.delivery_requested,
+                   d.status as delivery_status,
+                   (select u.volunteer_driver from users u where u.id = $2) as driver_is_volunteer
+            from claims c
+            inner join surplus_listings l on l.id = c.listing_id
+            left join claim_deliveries d on d.claim_id = c.id
+            where c.id = $1
+              and l.deleted_at is null
+            for update of c
+            ",
+            &[&claim_id, &driver_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("claim_not_found", "Claim not found"))?;
+
+    let claimer_id: Uuid = claim_row.get("claimer_id");
+    check_offer(&OfferContext {
+        driver_is_volunteer: claim_row
+            .get::<_, Option<bool>>("driver_is_volunteer")
+            .unwrap_or(false),
+        driver_is_claimer: claimer_id == driver_id,
+        delivery_requested: claim_row.get("delivery_requested"),
+        claim_status: claim_row.get("claim_status"),
+        existing_delivery_status: claim_row.get("delivery_status"),
+    })?;
+
+    let offered = tx
+        .execute_timed(
+            "delivery::offer_delivery",
+            "
+            insert into claim_deliveries (claim_id, driver_id)
+            values ($1, $2)
+            on conflict (claim_id) do update
+            set driver_id = excluded.driver_id,
+                status = 'offered',
+                offered_at = now(),
+                accepted_at = null,
+                picked_up_at = null,
+                delivered_at = null,
+                cancelled_at = null
+            where claim_deliveries.status = 'cancelled'
+            ",
+            &[&claim_id, &driver_id],
+        )
+        .await?;
+    if offered == 0 {
+        return Err(delivery_already_offered());
+    }
+
+    db::commit(tx).await?;
+
+    let response = load_delivery(&client, claim_id, driver_id).await?;
+    emit_delivery_event_best_effort(&response, correlation_id).await;
+
+    info!(
+        correlation_id = correlation_id,
+        claim_id = %claim_id,
+        driver_id = %driver_id,
+        "Offered volunteer delivery"
+    );
+
+    json_response(201, &response)
+}
+
+pub async fn accept_delivery(
+    request: &Request,
+    correlation_id: &str,
+    claim_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let claim_id = parse_uuid(claim_id, "claimId")?;
+    let response = apply_transition(user_id, claim_id, DeliveryStatus::Accepted).await?;
+    emit_delivery_event_best_effort(&response, correlation_id).await;
+
+    info!(
+        correlation_id = correlation_id,
+        claim_id = %claim_id,
+        user_id = %user_id,
+        "Accepted volunteer delivery"
+    );
+
+    json_response(200, &response)
+}
+
+/// Drivers advance a delivery to `picked_up` then `delivered`; either the
+/// driver or the claimer may cancel before pickup.
+pub async fn update_delivery(
+    request: &Request,
+    correlation_id: &str,
+    claim_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let claim_id = parse_uuid(claim_id, "claimId")?;
+    let payload: UpdateDeliveryRequest = parse_json_body(request)?;
+    let target = parse_update_status(&payload.status)?;
+
+    let response = apply_transition(user_id, claim_id, target).await?;
+    emit_delivery_event_best_effort(&response, correlation_id).await;
+
+    info!(
+        correlation_id = correlation_id,
+        claim_id = %claim_id,
+        user_id = %user_id,
+        new_status = response.status.as_str(),
+        "Updated volunteer delivery"
+    );
+
+    json_response(200, &response)
+}
+
+pub async fn get_delivery(
+    request: &Request,
+    correlation_id: &str,
+    claim_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let claim_id = parse_uuid(claim_id, "claimId")?;
+
+    let client = db::connect().await?;
+    let response = load_delivery(&client, claim_id, user_id).await?;
+
+    info!(
+        correlation_id = correlation_id,
+        claim_id = %claim_id,
+        user_id = %user_id,
+        "Fetched volunteer delivery"
+    );
+
+    json_response(200, &response)
+}
+
+async fn apply_transition(
+    user_id: Uuid,
+    claim_id: Uuid,
+    target: DeliveryStatus,
+) -> Result<DeliveryResponse, ApiError> {
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let row = tx
+        .query_opt_timed("delivery::apply_transition", LOCK_DELIVERY, &[&claim_id])
+        .await?
+        .ok_or_else(delivery_not_found)?;
+
+    let actor = determine_actor(user_id, &row)?;
+    let current = parse_stored_status(row.get("status"))?;
+    if current != target {
+        evaluate_transition(current, target, actor)?;
+        tx.execute_timed(
+            "delivery::apply_transition",
+            "
+            update claim_deliveries
+            set status = $2,
+                accepted_at = case when $2 = 'accepted' then now() else accepted_at end,
+                picked_up_at = case when $2 = 'picked_up' then now() else picked_up_at end,
+                delivered_at = case when $2 = 'delivered' then now() else delivered_at end,
+                cancelled_at = case when $2 = 'cancelled' then now() else cancelled_at end
+            where claim_id = $1
+            ",
+            &[&claim_id, &target.as_str()],
+        )
+        .await?;
+    }
+
+    db::commit(tx).await?;
+
+    load_delivery(&client, claim_id, user_id).await
+}
+
+/// Loads the delivery as seen by `viewer_id`, who must be the driver, the
+/// claimer, or the listing owner.
+async fn load_delivery(
+    client: &Client,
+    claim_id: Uuid,
+    viewer_id: Uuid,
+) -> Result<DeliveryResponse, ApiError> {
+    let row = client
+        .query_opt_timed("delivery::load_delivery", FIND_DELIVERY, &[&claim_id])
+        .await?
+        .ok_or_else(delivery_not_found)?;
+    let actor = determine_actor(viewer_id, &row)?;
+    let status = parse_stored_status(row.get("status"))?;
+    Ok(row_to_delivery_response(
+        &row,
+        reveals_addresses(status, actor),
+    ))
+}
+
+async fn is_volunteer_driver(client: &Client, user_id: Uuid) -> Result<bool, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "delivery::is_volunteer_driver",
+            "select volunteer_driver from users where id = $1 and deleted_at is null",
+            &[&user_id],
+        )
+        .await?;
+    Ok(row.is_some_and(|row| row.get::<_, bool>("volunteer_driver")))
+}
+
+fn check_offer(context: &OfferContext<'_>) -> Result<(), ApiError> {
+    if !context.driver_is_volunteer {
+        return Err(volunteer_driver_required());
+    }
+    if context.driver_is_claimer {
+        return Err(ApiError::forbidden(
+            "cannot_deliver_own_claim",
+            "Forbidden: You cannot offer to deliver your own claim",
+        ));
+    }
+    if !context.delivery_requested {
+        return Err(ApiError::conflict(
+            "delivery_not_requested",
+            "This claim did not request delivery",
+        ));
+    }
+    if context.claim_status != "confirmed" {
+        return Err(ApiError::conflict(
+            "claim_not_confirmed",
+            "Deliveries can only be offered for confirmed claims",
+        ));
+    }
+    if context
+        .existing_delivery_status
+        .is_some_and(|status| status != "cancelled")
+    {
+        return Err(delivery_already_offered());
+    }
+    Ok(())
+}
+
+fn determine_actor(user_id: Uuid, row: &Row) -> Result<DeliveryActor, ApiError> {
+    if user_id == row.get::<_, Uuid>("driver_id") {
+        return Ok(DeliveryActor::Driver);
+    }
+    if user_id == row.get::<_, Uuid>("claimer_id") {
+        return Ok(DeliveryActor::Claimer);
+    }
+    if user_id == row.get::<_, Uuid>("listing_owner_id") {
+        return Ok(DeliveryActor::ListingOwner);
+    }
+    Err(ApiError::forbidden(
+        "not_delivery_participant",
+        "Forbidden: You are not a participant in this delivery",
+    ))
+}
+
+fn evaluate_transition(
+    current: DeliveryStatus,
+    target: DeliveryStatus,
+    actor: DeliveryActor,
+) -> Result<(), ApiError> {
+    match (current, target) {
+        (DeliveryStatus::Offered, DeliveryStatus::Accepted) => {
+            require_actor(actor, DeliveryActor::Claimer, "Only the claimer can accept")
+        }
+        (DeliveryStatus::Accepted, DeliveryStatus::PickedUp)
+        | (DeliveryStatus::PickedUp, DeliveryStatus::Delivered) => require_actor(
+            actor,
+            DeliveryActor::Driver,
+            "Only the driver can update pickup and drop-off",
+        ),
+        (DeliveryStatus::Offered | DeliveryStatus::Accepted, DeliveryStatus::Cancelled) => {
+            if actor == DeliveryActor::ListingOwner {
+                return Err(ApiError::forbidden(
+                    "driver_or_claimer_only",
+                    "Forbidden: Only the driver or claimer can cancel a delivery",
+                ));
+            }
+            Ok(())
+        }
+        _ => Err(ApiError::invalid_field(
+            "status",
+            "invalid_transition",
+            format!(
+                "Invalid delivery transition from '{}' to '{}'",
+                current.as_str(),
+                target.as_str()
+            ),
+        )),
+    }
+}
+
+fn require_actor(
+    actor: DeliveryActor,
+    required: DeliveryActor,
+    message: &str,
+) -> Result<(), ApiError> {
+    if actor == required {
+        Ok(())
+    } else {
+        Err(ApiError::forbidden(
+            "delivery_role_required",
+            format!("Forbidden: {message}"),
+        ))
+    }
+}
+
+/// Addresses go to the driver only, and only while the run is in progress.
+fn reveals_addresses(status: DeliveryStatus, actor: DeliveryActor) -> bool {
+    actor == DeliveryActor::Driver
+        && matches!(status, DeliveryStatus::Accepted | DeliveryStatus::PickedUp)
+}
+
+fn parse_update_status(value: &str) -> Result<DeliveryStatus, ApiError> {
+    match value {
+        "picked_up" => Ok(DeliveryStatus::PickedUp),
+        "delivered" => Ok(DeliveryStatus::Delivered),
+        "cancelled" => Ok(DeliveryStatus::Cancelled),
+        _ => Err(ApiError::invalid_field(
+            "status",
+            "invalid_enum",
+            format!(
+                "Invalid delivery status '{}'. Allowed values: {}",
+                value,
+                ALLOWED_UPDATE_STATUSES.join(", ")
+            ),
+        )),
+    }
+}
+
+fn parse_stored_status(value: &str) -> Result<DeliveryStatus, ApiError> {
+    match value {
+        "offered" => Ok(DeliveryStatus::Offered),
+        "accepted" => Ok(DeliveryStatus::Accepted),
+        "picked_up" => Ok(DeliveryStatus::PickedUp),
+        "delivered" => Ok(DeliveryStatus::Delivered),
+        "cancelled" => Ok(DeliveryStatus::Cancelled),
+        _ => Err(ApiError::internal(format!(
+            "unexpected stored delivery status '{value}'"
+        ))),
+    }
+}
+
+impl DeliveryStatus {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Offered => "offered",
+            Self::Accepted => "accepted",
+            Self::PickedUp => "picked_up",
+            Self::Delivered => "delivered",
+            Self::Cancelled => "cancelled",
+        }
+    }
+}
+
+fn parse_open_deliveries_query(query: Option<&str>) -> Result<OpenDeliveriesQuery, ApiError> {
+    let mut geo_key: Option<String> = None;
+    let mut radius_km: Option<f64> = None;
+    let mut limit: i64 = 20;
+    let mut offset: i64 = 0;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            match key {
+                "geoKey" => {
+                    let normalized = value.trim().to_ascii_lowercase();
+                    if !location::is_valid_geo_key(&normalized) {
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
+                            "geoKey must be a valid geohash (1-12 chars, base32)",
+                        ));
+                    }
+                    geo_key = Some(normalized);
+                }
+                "radiusMiles" => {
+                    let miles = parse_positive_radius(value, "radiusMiles")?;
+                    radius_km = Some(miles * location::KM_PER_MILE);
+                }
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| invalid_limit())?;
+                    if !(1..=100).contains(&limit) {
+                        return Err(invalid_limit());
+                    }
+                }
+                "offset" => {
+                    offset = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|offset| *offset >= 0)
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "offset",
+                                "invalid_offset",
+                                "Invalid offset. Must be an integer greater than or equal to 0",
+                            )
+                        })?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let geo_key = geo_key.ok_or_else(|| {
+        ApiError::invalid_field("geoKey", "invalid_geo_key", "geoKey is required")
+    })?;
+
+    Ok(OpenDeliveriesQuery {
+        geo_key,
+        radius_km,
+        limit,
+        offset,
+    })
+}
+
+fn row_to_delivery_response(row: &Row, include_addresses: bool) -> DeliveryResponse {
+    let optional_timestamp = |column: &str| {
+        row.get::<_, Option<DateTime<Utc>>>(column)
+            .map(|value| value.to_rfc3339())
+    };
+
+    DeliveryResponse {
+        claim_id: row.get::<_, Uuid>("claim_id").to_string(),
+        driver_id: row.get::<_, Uuid>("driver_id").to_string(),
+        claimer_id: row.get::<_, Uuid>("claimer_id").to_string(),
+        status: row.get("status"),
+        pickup_address: if include_addresses {
+            row.get("pickup_address")
+        } else {
+            None
+        },
+        dropoff_address: if include_addresses {
+            row.get("dropoff_address")
+        } else {
+            None
+        },
+        offered_at: row.get::<_, DateTime<Utc>>("offered_at").to_rfc3339(),
+        accepted_at: optional_timestamp("accepted_at"),
+        picked_up_at: optional_timestamp("picked_up_at"),
+        delivered_at: optional_timestamp("delivered_at"),
+        cancelled_at: optional_timestamp("cancelled_at"),
+    }
+}
+
+fn row_to_open_delivery(row: &Row) -> OpenDeliveryItem {
+    OpenDeliveryItem {
+        claim_id: row.get::<_, Uuid>("claim_id").to_string(),
+        listing_id: row.get::<_, Uuid>("listing_id").to_string(),
+        title: row.get("title"),
+        unit: row.get("unit"),
+        quantity_claimed: row.get("quantity_claimed"),
+        pickup_geo_key: row.get("pickup_geo_key"),
+        dropoff_area: row.get("dropoff_area"),
+        confirmed_at: row
+            .get::<_, Option<DateTime<Utc>>>("confirmed_at")
+            .map(|value| value.to_rfc3339()),
+    }
+}
+
+async fn emit_delivery_event_best_effort(delivery: &DeliveryResponse, correlation_id: &str) {
+    let detail = DeliveryEventDetail::new(
+        delivery.claim_id.clone(),
+        delivery.driver_id.clone(),
+        delivery.claimer_id.clone(),
+        delivery.status.clone(),
+        correlation_id,
+    );
+
+    if let Err(event_error) = events::publish(events::DELIVERY_UPDATED, &detail).await {
+        error!(
+            correlation_id = correlation_id,
+            claim_id = delivery.claim_id.as_str(),
+            error = %event_error,
+            "Failed to emit delivery event after successful write"
+        );
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn volunteer_driver_required() -> ApiError {
+    ApiError::forbidden(
+        "volunteer_driver_required",
+        "Forbidden: Opt in as a volunteer driver on your profile first",
+    )
+}
+
+fn delivery_already_offered() -> ApiError {
+    ApiError::conflict(
+        "delivery_already_offered",
+        "Another driver has already offered to deliver this claim",
+    )
+}
+
+fn delivery_not_found() -> ApiError {
+    ApiError::not_found("delivery_not_found", "Delivery not found")
+}
+
+fn invalid_limit() -> ApiError {
+    ApiError::invalid_field(
+        "limit",
+        "invalid_limit",
+        "Invalid limit. Must be an integer between 1 and 100",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn open_offer() -> OfferContext<'static> {
+        OfferContext {
+            driver_is_volunteer: true,
+            driver_is_claimer: false,
+            delivery_requested: true,
+            claim_status: "confirmed",
+            existing_delivery_status: None,
+        }
+    }
+
+    #[test]
+    fn check_offer_accepts_confirmed_claim_without_live_offer() {
+        assert!(check_offer(&open_offer()).is_ok());
+        let replaced = OfferContext {
+            existing_delivery_status: Some("cancelled"),
+            ..open_offer()
+        };
+        assert!(check_offer(&replaced).is_ok());
+    }
+
+    #[test]
+    fn check_offer_reports_each_blocking_condition() {
+        let cases = [
+            (
+                OfferContext {
+                    driver_is_volunteer: false,
+                    ..open_offer()
+                },
+                "volunteer_driver_required",
+            ),
+            (
+                OfferContext {
+                    driver_is_claimer: true,
+                    ..open_offer()
+                },
+                "cannot_deliver_own_claim",
+            ),
+            (
+                OfferContext {
+                    delivery_requested: false,
+                    ..open_offer()
+                },
+                "delivery_not_requested",
+            ),
+            (
+                OfferContext {
+                    claim_status: "pending",
+                    ..open_offer()
+                },
+                "claim_not_confirmed",
+            ),
+            (
+                OfferContext {
+                    existing_delivery_status: Some("accepted"),
+                    ..open_offer()
+                },
+                "delivery_already_offered",
+            ),
+        ];
+
+        for (context, code) in cases {
+            assert_eq!(check_offer(&context).unwrap_err().error_code(), code);
+        }
+    }
+
+    #[test]
+    fn evaluate_transition_follows_delivery_lifecycle() {
+        use DeliveryActor::{Claimer, Driver, ListingOwner};
+        use DeliveryStatus::{Accepted, Cancelled, Delivered, Offered, PickedUp};
+
+        assert!(evaluate_transition(Offered, Accepted, Claimer).is_ok());
+        assert!(evaluate_transition(Offered, Accepted, Driver).is_err());
+        assert!(evaluate_transition(Accepted, PickedUp, Driver).is_ok());
+        assert!(evaluate_transition(Accepted, PickedUp, Claimer).is_err());
+        assert!(evaluate_transition(PickedUp, Delivered, Driver).is_ok());
+        assert!(evaluate_transition(Offered, Cancelled, Claimer).is_ok());
+        assert!(evaluate_transition(Accepted, Cancelled, Driver).is_ok());
+        assert!(evaluate_transition(Accepted, Cancelled, ListingOwner).is_err());
+    }
+
+    #[test]
+    fn evaluate_transition_rejects_skipped_and_terminal_states() {
+        use DeliveryStatus::{Accepted, Cancelled, Delivered, Offered, PickedUp};
+
+        for (current, target) in [
+            (Offered, PickedUp),
+            (Accepted, Delivered),
+            (PickedUp, Cancelled),
+            (Delivered, Cancelled),
+            (Cancelled, Accepted),
+        ] {
+            let error = evaluate_transition(current, target, DeliveryActor::Driver).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_transition");
+        }
+    }
+
+    #[test]
+    fn addresses_are_revealed_only_to_active_driver() {
+        assert!(reveals_addresses(
+            DeliveryStatus::Accepted,
+            DeliveryActor::Driver
+        ));
+        assert!(reveals_addresses(
+            DeliveryStatus::PickedUp,
+            DeliveryActor::Driver
+        ));
+        assert!(!reveals_addresses(
+            DeliveryStatus::Offered,
+            DeliveryActor::Driver
+        ));
+        assert!(!reveals_addresses(
+            DeliveryStatus::Delivered,
+            DeliveryActor::Driver
+        ));
+        assert!(!reveals_addresses(
+            DeliveryStatus::Accepted,
+            DeliveryActor::ListingOwner
+        ));
+    }
+
+    #[test]
+    fn parse_update_status_rejects_offered_and_accepted() {
+        assert_eq!(
+            parse_update_status("picked_up").unwrap(),
+            DeliveryStatus::PickedUp
+        );
+        assert!(parse_update_status("accepted").is_err());
+        assert!(parse_update_status("offered").is_err());
+    }
+
+    #[test]
+    fn open_deliveries_query_requires_geo_key() {
+        assert!(parse_open_deliveries_query(None).is_err());
+        let parsed = parse_open_deliveries_query(Some("geoKey=9Q8YY&radiusMiles=5")).unwrap();
+        assert_eq!(parsed.geo_key, "9q8yy");
+        assert!(parsed.radius_km.is_some());
+        assert_eq!(parsed.limit, 20);
+    }
+
+    #[test]
+    fn open_deliveries_hide_full_addresses() {
+        assert!(!LIST_OPEN_DELIVERIES.contains("address"));
+        assert!(LOCK_DELIVERY.ends_with("for update of d"));
+    }
+}
@@ -0,0 +1,376 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use chrono::{DateTime, Datelike, NaiveDate, TimeZone, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::{Row, Transaction};
+use tracing::info;
+use uuid::Uuid;
+
+const MIN_RECEIPT_YEAR: i32 = 2000;
+
+/// Snapshots a completed claim into a receipt when the claimer is a partner
+/// organization's service user or a gatherer with an organization
+/// affiliation. Does nothing for unaffiliated claimers, for listings without
+/// a crop (tool loans, compost), or when a receipt already exists.
+const RECORD_RECEIPT: &str = "
+    insert into donation_receipts
+        (claim_id, organization_id, organization_name, claimer_id, grower_id, grower_name,
+         crop_id, crop_name, quantity, unit, estimated_value_cents, value_source, donated_at)
+    select c.id, o.id, coalesce(o.name, nullif(btrim(ga.organization_affiliation), '')),
+           c.claimer_id, l.user_id, gu.display_name,
+           l.crop_id, cr.common_name, c.quantity_claimed, l.unit,
+           round(c.quantity_claimed * mv.cents_per_unit)::bigint, mv.source,
+           coalesce(c.completed_at, now())
+    from claims c
+    inner join surplus_listings l on l.id = c.listing_id
+    inner join crops cr on cr.id = l.crop_id
+    left join users gu on gu.id = l.user_id
+    left join organizations o on o.service_user_id = c.claimer_id and o.deleted_at is null
+    left join gatherer_profiles ga on ga.user_id = c.claimer_id
+    left join crop_market_values mv
+      on mv.crop_id = l.crop_id and mv.unit = lower(btrim(l.unit))
+    where c.id = $1
+      and c.status = 'completed'
+      and (o.id is not null or nullif(btrim(ga.organization_affiliation), '') is not null)
+    on conflict (claim_id) do nothing
+";
+
+/// Receipts in `[$2, $3)` for one scope: `$1` is an organization id when
+/// `$4` is true, otherwise the claimer's user id. Totals cover the whole
+/// year, not just the page.
+const LIST_RECEIPTS: &str = "
+    select r.id, r.claim_id, r.organization_name, r.grower_name, r.crop_name,
+           r.quantity::text as quantity, r.unit, r.estimated_value_cents, r.value_source,
+           r.donated_at,
+           count(*) over () as total_count,
+           (sum(r.estimated_value_cents) over ())::bigint as total_value_cents,
+           count(*) filter (where r.estimated_value_cents is null) over () as unvalued_count
+    from donation_receipts r
+    where (case when $4 then r.organization_id = $1 else r.claimer_id = $1 end)
+      and r.donated_at >= $2
+      and r.donated_at < $3
+    order by r.donated_at asc, r.id asc
+    limit $5 offset $6
+";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DonationReceiptItem {
+    pub id: String,
+    pub claim_id: Option<String>,
+    pub donation_date: String,
+    pub donated_at: String,
+    pub organization_name: String,
+    pub grower_name: Option<String>,
+    pub crop_name: String,
+    pub quantity: String,
+    pub unit: Option<String>,
+    pub estimated_value_cents: Option<i64>,
+    pub estimated_value: Option<String>,
+    pub value_source: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DonationReceiptTotals {
+    pub receipt_count: i64,
+    pub estimated_value_cents: i64,
+    pub estimated_value: String,
+    pub unvalued_count: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListDonationReceiptsResponse {
+    pub year: i32,
+    pub items: Vec<DonationReceiptItem>,
+    pub totals: DonationReceiptTotals,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug)]
+struct ListReceiptsQuery {
+    year: i32,
+    limit: i64,
+    offset: i64,
+}
+
+/// Called inside the claim transition transaction once a claim completes.
+pub async fn record_for_completed_claim(
+    tx: &Transaction<'_>,
+    claim_id: Uuid,
+) -> Result<bool, ApiError> {
+    let inserted = tx
+        .execute_timed(
+            "donation_receipt::record_for_completed_claim",
+            RECORD_RECEIPT,
+            &[&claim_id],
+        )
+        .await?;
+    Ok(inserted > 0)
+}
+
+/// Lists a year's donation receipts, flat and ordered by date so clients can
+/// render them straight to CSV or PDF. Partner API keys see their
+/// organization's receipts; signed-in gatherers see receipts for their own
+/// claims.
+pub async fn list_receipts(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let query = parse_list_receipts_query(request.uri().query(), Utc::now().year())?;
+
+    let (scope_id, by_organization) = match &auth.api_key {
+        Some(principal) => (
+            Uuid::parse_str(&principal.organization_id)
+                .map_err(|_| ApiError::unauthorized("Invalid organization ID format"))?,
+            true,
+        ),
+        None => (
+            Uuid::parse_str(&auth.user_id)
+                .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?,
+            false,
+        ),
+    };
+
+    let (starts_at, ends_at) = year_bounds(query.year)?;
+    let fetch_limit = query.limit + 1;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "donation_receipt::list_receipts",
+            LIST_RECEIPTS,
+            &[
+                &scope_id,
+                &starts_at,
+                &ends_at,
+                &by_organization,
+                &fetch_limit,
+                &query.offset,
+            ],
+        )
+        .await?;
+
+    let totals = rows.first().map_or_else(
+        || totals_from_parts(0, 0, 0),
+        |row| {
+            totals_from_parts(
+                row.get("total_count"),
+                row.get::<_, Option<i64>>("total_value_cents").unwrap_or(0),
+                row.get("unvalued_count"),
+            )
+        },
+    );
+
+    let limit = usize::try_from(query.limit).map_err(|_| invalid_limit())?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_receipt)
+        .collect::<Vec<_>>();
+
+    let response = ListDonationReceiptsResponse {
+        year: query.year,
+        items,
+        totals,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: if has_more {
+            Some(query.offset + query.limit)
+        } else {
+            None
+        },
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        scope_id = %scope_id,
+        by_organization = by_organization,
+        year = query.year,
+        returned_count = response.items.len(),
+        "Listed donation receipts"
+    );
+
+    json_response(200, &response)
+}
+
+fn parse_list_receipts_query(
+    query: Option<&str>,
+    current_year: i32,
+) -> Result<ListReceiptsQuery, ApiError> {
+    let mut year = current_year;
+    let mut limit: i64 = 100;
+    let mut offset: i64 = 0;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            match key {
+                "year" => {
+                    year = value
+                        .parse::<i32>()
+                        .ok()
+                        .filter(|year| (MIN_RECEIPT_YEAR..=current_year).contains(year))
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "year",
+                                "invalid_year",
+                                format!(
+                                    "year must be between {MIN_RECEIPT_YEAR} and {current_year}"
+                                ),
+                            )
+                        })?;
+                }
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| invalid_limit())?;
+                    if !(1..=500).contains(&limit) {
+                        return Err(invalid_limit());
+                    }
+                }
+                "offset" => {
+                    offset = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|offset| *offset >= 0)
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "offset",
+                                "invalid_offset",
+                                "Invalid offset. Must be an integer greater than or equal to 0",
+                            )
+                        })?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(ListReceiptsQuery {
+        year,
+        limit,
+        offset,
+    })
+}
+
+/// Calendar-year bounds in UTC, end-exclusive.
+fn year_bounds(year: i32) -> Result<(DateTime<Utc>, DateTime<Utc>), ApiError> {
+    let start_of = |year: i32| {
+        NaiveDate::from_ymd_opt(year, 1, 1)
+            .and_then(|date| date.and_hms_opt(0, 0, 0))
+            .map(|naive| Utc.from_utc_datetime(&naive))
+            .ok_or_else(|| ApiError::internal(format!("invalid receipt year {year}")))
+    };
+    Ok((start_of(year)?, start_of(year + 1)?))
+}
+
+/// Renders cents as a plain decimal dollar amount, e.g. `1234` -> `"12.34"`.
+fn format_cents(cents: i64) -> String {
+    format!("{}.{:02}", cents / 100, cents % 100)
+}
+
+fn totals_from_parts(
+    receipt_count: i64,
+    estimated_value_cents: i64,
+    unvalued_count: i64,
+) -> DonationReceiptTotals {
+    DonationReceiptTotals {
+        receipt_count,
+        estimated_value_cents,
+        estimated_value: format_cents(estimated_value_cents),
+        unvalued_count,
+    }
+}
+
+fn row_to_receipt(row: &Row) -> DonationReceiptItem {
+    let donated_at: DateTime<Utc> = row.get("donated_at");
+    let estimated_value_cents: Option<i64> = row.get("estimated_value_cents");
+
+    DonationReceiptItem {
+        id: row.get::<_, Uuid>("id").to_string(),
+        claim_id: row
+            .get::<_, Option<Uuid>>("claim_id")
+            .map(|id| id.to_string()),
+        donation_date: donated_at.format("%Y-%m-%d").to_string(),
+        donated_at: donated_at.to_rfc3339(),
+        organization_name: row.get("organization_name"),
+        grower_name: row.get("grower_name"),
+        crop_name: row.get("crop_name"),
+        quantity: row.get("quantity"),
+        unit: row.get("unit"),
+        estimated_value_cents,
+        estimated_value: estimated_value_cents.map(format_cents),
+        value_source: row.get("value_source"),
+    }
+}
+
+fn invalid_limit() -> ApiError {
+    ApiError::invalid_field(
+        "limit",
+        "invalid_limit",
+        "Invalid limit. Must be an integer between 1 and 500",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_query_defaults_to_current_year() {
+        let parsed = parse_list_receipts_query(None, 2026).unwrap();
+        assert_eq!(parsed.year, 2026);
+        assert_eq!(parsed.limit, 100);
+        assert_eq!(parsed.offset, 0);
+    }
+
+    #[test]
+    fn parse_query_rejects_future_and_ancient_years() {
+        assert_eq!(
+            parse_list_receipts_query(Some("year=2025"), 2026)
+                .unwrap()
+                .year,
+            2025
+        );
+        for raw in ["year=2027", "year=1999", "year=abc"] {
+            let error = parse_list_receipts_query(Some(raw), 2026).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_year");
+        }
+    }
+
+    #[test]
+    fn year_bounds_cover_the_calendar_year() {
+        let (start, end) = year_bounds(2025).unwrap();
+        assert_eq!(start.to_rfc3339(), "2025-01-01T00:00:00+00:00");
+        assert_eq!(end.to_rfc3339(), "2026-01-01T00:00:00+00:00");
+    }
+
+    #[test]
+    fn format_cents_pads_to_two_places() {
+        assert_eq!(format_cents(0), "0.00");
+        assert_eq!(format_cents(5), "0.05");
+        assert_eq!(format_cents(123_456), "1234.56");
+    }
+
+    #[test]
+    fn record_receipt_requires_affiliation_and_is_idempotent() {
+        assert!(RECORD_RECEIPT.contains("c.status = 'completed'"));
+        assert!(RECORD_RECEIPT.contains("o.id is not null or"));
+        assert!(RECORD_RECEIPT.contains("on conflict (claim_id) do nothing"));
+    }
+}
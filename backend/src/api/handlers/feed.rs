@@ -1,23 +1,43 @@
-use crate::ai::{SummaryArtifact, SummaryGenerator};
-use crate::ai_model_config;
+use crate::ai::{FulfillmentContext, SummaryArtifact, SummaryGenerator, UnfulfilledCrop};
 use crate::auth::extract_auth_context;
-use crate::db;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::geocoding;
+use crate::handlers::announcement;
+use crate::http_util::{json_response, request_locale};
+use crate::listing_projection::ListingProjection;
 use crate::location;
 use crate::middleware::{ai_guardrails, entitlements};
 use crate::models::feed::{
-    DerivedFeedAiSummary, DerivedFeedFreshness, DerivedFeedResponse, DerivedFeedSignal,
-    GrowerGuidance, GrowerGuidanceExplanation, GrowerGuidanceSignalRef,
+    DerivedFeedAiSummary, DerivedFeedForecast, DerivedFeedFreshness, DerivedFeedResponse,
+    DerivedFeedSignal, GrowerGuidance, GrowerGuidanceExplanation, GrowerGuidanceSignalRef,
+    PlantingConcentrationWarning,
 };
-use crate::models::listing::ListingItem;
+use crate::repo;
 use chrono::{DateTime, Datelike, Utc};
 use lambda_http::{Body, Request, Response};
-use serde::Serialize;
 use tokio_postgres::Row;
 use tracing::info;
 use uuid::Uuid;
 
 const DEFAULT_WINDOW_DAYS: i32 = 7;
 const SUPPORTED_WINDOWS_DAYS: [i32; 3] = [7, 14, 30];
+/// Below this many growers a shared plan is neither a trend nor anonymous.
+const PLANTING_WARNING_MIN_GROWERS: i32 = 5;
+/// Share of the season's planners growing one crop before it is flagged.
+const PLANTING_WARNING_MIN_SHARE: f64 = 0.4;
+const MAX_PLANTING_WARNINGS: usize = 3;
+
+#[derive(Debug, Clone)]
+struct PlannedCrop {
+    crop_id: Uuid,
+    crop_name: String,
+    season: String,
+    season_year: i32,
+    grower_count: i32,
+    planner_count: i32,
+}
 
 #[derive(Debug)]
 struct DerivedFeedQuery {
@@ -25,16 +45,18 @@ struct DerivedFeedQuery {
     window_days: i32,
     limit: i64,
     offset: i64,
+    projection: ListingProjection,
 }
 
 #[allow(clippy::too_many_lines)]
 pub async fn get_derived_feed(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
     let auth_context = extract_auth_context(request)?;
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let query = parse_derived_feed_query(request.uri().query())?;
     let geo_prefix = derive_geo_prefix(&query.geo_key);
     let geo_pattern = format!("{geo_prefix}%");
@@ -43,41 +65,31 @@ pub async fn get_derived_feed(
 
     let client = db::connect().await?;
 
-    let listing_rows = client
-        .query(
-            "
-            select id, user_id, grower_crop_id, crop_id, variety_id, title, unit,
-                   quantity_total::text as quantity_total,
-                   quantity_remaining::text as quantity_remaining,
-                   available_start, available_end, status::text,
-                   pickup_location_text, pickup_address, effective_pickup_address,
-                   pickup_disclosure_policy::text as pickup_disclosure_policy,
-                   pickup_notes, contact_pref::text as contact_pref,
-                   geo_key, lat, lng, created_at
-            from surplus_listings
-            where deleted_at is null
-              and status = 'active'
-              and geo_key is not null
-              and geo_key like $1
-            order by created_at desc, id desc
-            limit $2 offset $3
-            ",
-            &[&geo_pattern, &fetch_limit, &query.offset],
+    let locale = request_locale(request);
+    let listing_rows = repo::listing::list_by_geo_prefix(
+        &client,
+        "active",
+        &geo_prefix,
+        None,
+        fetch_limit,
+        query.offset,
+        locale.as_deref(),
+    )
+    .await?;
+
+    let limit = usize::try_from(query.limit).map_err(|_| {
+        ApiError::invalid_field(
+            "limit",
+            "invalid_limit",
+            "Invalid limit. Must be between 1 and 100",
         )
-        .await
-        .map_err(db_error)?;
-
-    let limit = usize::try_from(query.limit)
-        .map_err(|_| lambda_http::Error::from("Invalid limit. Must be between 1 and 100"))?;
+    })?;
     let has_more = listing_rows.len() > limit;
-    let items = listing_rows
-        .into_iter()
-        .take(limit)
-        .map(|row| row_to_listing_item(&row))
-        .collect::<Vec<_>>();
+    let items = listing_rows.into_iter().take(limit).collect::<Vec<_>>();
 
     let fresh_rows = client
-        .query(
+        .query_timed(
+            "feed::get_derived_feed",
             "
             select
               geo_boundary_key,
@@ -96,12 +108,12 @@ pub async fn get_derived_feed(
             ",
             &[&geo_prefix, &query.window_days, &as_of],
         )
-        .await
-        .map_err(db_error)?;
+        .await?;
 
     let (signal_rows, freshness) = if fresh_rows.is_empty() {
         let fallback_rows = client
-            .query(
+            .query_timed(
+                "feed::get_derived_feed",
                 "
                 select distinct on (geo_boundary_key, crop_scope_id)
                   geo_boundary_key,
@@ -124,8 +136,7 @@ pub async fn get_derived_feed(
                 ",
                 &[&geo_pattern, &query.window_days],
             )
-            .await
-            .map_err(db_error)?;
+            .await?;
 
         (
             fallback_rows,
@@ -150,28 +161,79 @@ pub async fn get_derived_feed(
         )
     };
 
-    let signals = signal_rows
+    let mut signals = signal_rows
         .into_iter()
         .map(|row| row_to_signal(&row))
         .collect::<Vec<_>>();
 
-    let grower_guidance = build_deterministic_grower_guidance(&signals, query.window_days, as_of);
+    record_feed_access_best_effort(&client, &geo_prefix, query.window_days, correlation_id).await;
+
+    let mut forecast = client
+        .query_timed(
+            "feed::get_derived_feed",
+            "
+            select geo_boundary_key,
+                   crop_id,
+                   window_days::int as window_days,
+                   horizon_days::int as horizon_days,
+                   target_date::text as target_date,
+                   projected_scarcity_score::float8 as projected_scarcity_score,
+                   projected_abundance_score::float8 as projected_abundance_score,
+                   model,
+                   generated_at
+            from derived_signal_forecasts
+            where schema_version = 1
+              and geo_boundary_key = $1
+              and window_days = $2
+              and expires_at > $3
+            order by horizon_days asc, projected_scarcity_score desc
+            limit 50
+            ",
+            &[&geo_prefix, &window_days_i16(query.window_days), &as_of],
+        )
+        .await?
+        .iter()
+        .map(row_to_forecast)
+        .collect::<Vec<_>>();
+
+    let planting_warnings = load_planting_warnings(&client, &geo_pattern, as_of).await?;
+
+    let mut geo_keys = vec![geo_prefix.as_str()];
+    geo_keys.extend(
+        signals
+            .iter()
+            .map(|signal| signal.geo_boundary_key.as_str()),
+    );
+    geo_keys.extend(forecast.iter().map(|row| row.geo_boundary_key.as_str()));
+    let geo_labels = geocoding::labels::labels_for(&geo_keys).await;
+    for signal in &mut signals {
+        signal.geo_label = geo_labels.get(&signal.geo_boundary_key).cloned();
+    }
+    for row in &mut forecast {
+        row.geo_label = geo_labels.get(&row.geo_boundary_key).cloned();
+    }
+
+    let announcements =
+        announcement::load_for_feed(&client, &query.geo_key, &geo_prefix, as_of).await?;
+
+    let grower_guidance =
+        build_deterministic_grower_guidance(&signals, &geo_prefix, query.window_days, as_of);
 
     let ai_summary = if entitlements::require_entitlement(&client, user_id, "ai.feed_insights.read")
         .await
         .is_ok()
     {
-        let model_id = ai_model_config::load_model_config().model_id;
+        let model_id = &config.ai.model.model_id;
 
         let guardrails =
-            ai_guardrails::enforce_and_record(&client, user_id, "ai.feed_insights.read", &model_id)
+            ai_guardrails::enforce_and_record(&client, user_id, "ai.feed_insights.read", model_id)
                 .await
                 .ok();
 
         if matches!(guardrails.as_ref().map(|g| g.allowed), Some(false)) {
             None
         } else {
-            load_or_generate_ai_summary(&client, &geo_prefix, query.window_days, &signals)
+            load_or_generate_ai_summary(&client, config, &geo_prefix, query.window_days, &signals)
                 .await
                 .unwrap_or_else(|error| {
                     tracing::warn!(error = %error, "AI summary generation failed; degrading gracefully");
@@ -183,8 +245,13 @@ pub async fn get_derived_feed(
     };
 
     let response = DerivedFeedResponse {
+        geo_label: geo_labels.get(&geo_prefix).cloned(),
+        geo_boundary_key: geo_prefix.clone(),
         items,
+        announcements,
         signals,
+        forecast,
+        planting_warnings,
         freshness,
         ai_summary,
         grower_guidance,
@@ -205,19 +272,174 @@ pub async fn get_derived_feed(
         geo_prefix = geo_prefix,
         window_days = query.window_days,
         listing_count = response.items.len(),
+        announcement_count = response.announcements.len(),
         signal_count = response.signals.len(),
+        forecast_count = response.forecast.len(),
+        planting_warning_count = response.planting_warnings.len(),
         feed_stale = response.freshness.is_stale,
+        sparse_fields = !query.projection.is_full(),
         "Returned derived feed response"
     );
 
-    json_response(200, &response)
+    json_response(200, &query.projection.apply(&response)?)
 }
 
-fn parse_derived_feed_query(query: Option<&str>) -> Result<DerivedFeedQuery, lambda_http::Error> {
+async fn record_feed_access_best_effort(
+    client: &tokio_postgres::Client,
+    geo_prefix: &str,
+    window_days: i32,
+    correlation_id: &str,
+) {
+    let window_days = window_days_i16(window_days);
+
+    if let Err(error) = client
+        .execute_timed("feed::record_feed_access_best_effort", 
+            "
+            insert into feed_geo_access (geo_boundary_key, window_days, access_count, last_accessed_at)
+            values ($1, $2, 1, now())
+            on conflict (geo_boundary_key, window_days) do update
+              set access_count = feed_geo_access.access_count + 1,
+                  last_accessed_at = now()
+            ",
+            &[&geo_prefix, &window_days],
+        )
+        .await
+    {
+        tracing::warn!(
+            correlation_id = correlation_id,
+            geo_prefix = geo_prefix,
+            error = %error,
+            "Failed to record derived feed access"
+        );
+    }
+}
+
+/// Crop plans in the area for seasons not yet over and starting within a
+/// year, counted per crop and season against everyone planning that season.
+async fn load_planting_warnings(
+    client: &tokio_postgres::Client,
+    geo_pattern: &str,
+    as_of: DateTime<Utc>,
+) -> Result<Vec<PlantingConcentrationWarning>, ApiError> {
+    let rows = client
+        .query_timed(
+            "feed::load_planting_warnings",
+            "
+            with upcoming as (
+              select user_id, crop_id, season, season_year, season_starts_on
+                from crop_plans
+               where geo_key like $1
+                 and season_starts_on + interval '3 months' > $2::timestamptz
+                 and season_starts_on <= $2::timestamptz + interval '1 year'
+            ),
+            planners as (
+              select season, season_year, count(distinct user_id)::int as planner_count
+                from upcoming
+               group by season, season_year
+            )
+            select u.crop_id,
+                   c.common_name,
+                   u.season,
+                   u.season_year::int as season_year,
+                   count(distinct u.user_id)::int as grower_count,
+                   p.planner_count
+              from upcoming u
+              join planners p on p.season = u.season and p.season_year = u.season_year
+              join crops c on c.id = u.crop_id
+             group by u.crop_id, c.common_name, u.season, u.season_year, u.season_starts_on,
+                      p.planner_count
+            having count(distinct u.user_id) >= $3::int
+             order by u.season_starts_on asc
+            ",
+            &[&geo_pattern, &as_of, &PLANTING_WARNING_MIN_GROWERS],
+        )
+        .await?;
+
+    Ok(planting_warnings(
+        rows.iter()
+            .map(|row| PlannedCrop {
+                crop_id: row.get("crop_id"),
+                crop_name: row.get("common_name"),
+                season: row.get("season"),
+                season_year: row.get("season_year"),
+                grower_count: row.get("grower_count"),
+                planner_count: row.get("planner_count"),
+            })
+            .collect(),
+    ))
+}
+
+/// Keeps crops planned by enough growers that also make up a large share of
+/// their season's planners, most-planned first.
+fn planting_warnings(planned: Vec<PlannedCrop>) -> Vec<PlantingConcentrationWarning> {
+    let mut flagged = planned
+        .into_iter()
+        .filter(|crop| {
+            crop.grower_count >= PLANTING_WARNING_MIN_GROWERS
+                && crop.planner_count > 0
+                && f64::from(crop.grower_count) / f64::from(crop.planner_count)
+                    >= PLANTING_WARNING_MIN_SHARE
+        })
+        .collect::<Vec<_>>();
+    flagged.sort_by(|a, b| {
+        b.grower_count
+            .cmp(&a.grower_count)
+            .then_with(|| a.season_year.cmp(&b.season_year))
+            .then_with(|| a.crop_name.cmp(&b.crop_name))
+    });
+
+    flagged
+        .into_iter()
+        .take(MAX_PLANTING_WARNINGS)
+        .map(|crop| PlantingConcentrationWarning {
+            message: format!(
+                "{} neighbors are planning {} for {} {}",
+                crop.grower_count,
+                crop.crop_name.to_lowercase(),
+                crop.season,
+                crop.season_year
+            ),
+            crop_id: crop.crop_id.to_string(),
+            crop_name: crop.crop_name,
+            season: crop.season,
+            season_year: crop.season_year,
+            grower_count: crop.grower_count,
+            planner_count: crop.planner_count,
+        })
+        .collect()
+}
+
+#[allow(clippy::cast_possible_truncation)]
+const fn window_days_i16(window_days: i32) -> i16 {
+    // parse_derived_feed_query only admits 7, 14, or 30.
+    window_days as i16
+}
+
+/// `geo_label` is filled in by the caller, which labels all rows at once.
+fn row_to_forecast(row: &Row) -> DerivedFeedForecast {
+    DerivedFeedForecast {
+        geo_boundary_key: row.get("geo_boundary_key"),
+        geo_label: None,
+        crop_id: row
+            .get::<_, Option<Uuid>>("crop_id")
+            .map(|id| id.to_string()),
+        window_days: row.get("window_days"),
+        horizon_days: row.get("horizon_days"),
+        target_date: row.get("target_date"),
+        projected_scarcity_score: row.get("projected_scarcity_score"),
+        projected_abundance_score: row.get("projected_abundance_score"),
+        model: row.get("model"),
+        generated_at: row.get::<_, DateTime<Utc>>("generated_at").to_rfc3339(),
+    }
+}
+
+fn parse_derived_feed_query(query: Option<&str>) -> Result<DerivedFeedQuery, ApiError> {
     let mut geo_key: Option<String> = None;
     let mut window_days = DEFAULT_WINDOW_DAYS;
     let mut limit: i64 = 20;
     let mut offset: i64 = 0;
+    let mut fields: Option<&str> = None;
+    let mut view: Option<&str> = None;
 
     if let Some(raw_query) = query {
         for pair in raw_query.split('&') {
@@ -230,10 +452,16 @@ fn parse_derived_feed_query(query: Option<&str>) -> Result<DerivedFeedQuery, lam
                 "geoKey" => {
                     let normalized = value.trim().to_ascii_lowercase();
                     if normalized.is_empty() {
-                        return Err(lambda_http::Error::from("geoKey is required"));
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
+                            "geoKey is required",
+                        ));
                     }
                     if !is_valid_geo_key(&normalized) {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
                             "geoKey must be a valid geohash (1-12 chars, base32)",
                         ));
                     }
@@ -241,10 +469,16 @@ fn parse_derived_feed_query(query: Option<&str>) -> Result<DerivedFeedQuery, lam
                 }
                 "windowDays" => {
                     let parsed = value.parse::<i32>().map_err(|_| {
-                        lambda_http::Error::from("windowDays must be one of: 7, 14, 30")
+                        ApiError::invalid_field(
+                            "windowDays",
+                            "invalid_window_days",
+                            "windowDays must be one of: 7, 14, 30",
+                        )
                     })?;
                     if !SUPPORTED_WINDOWS_DAYS.contains(&parsed) {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "windowDays",
+                            "invalid_window_days",
                             "windowDays must be one of: 7, 14, 30",
                         ));
                     }
@@ -252,36 +486,53 @@ fn parse_derived_feed_query(query: Option<&str>) -> Result<DerivedFeedQuery, lam
                 }
                 "limit" => {
                     limit = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid limit. Must be an integer")
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be an integer",
+                        )
                     })?;
                     if !(1..=100).contains(&limit) {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
                             "Invalid limit. Must be between 1 and 100",
                         ));
                     }
                 }
                 "offset" => {
                     offset = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid offset. Must be an integer")
+                        ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be an integer",
+                        )
                     })?;
                     if offset < 0 {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
                             "Invalid offset. Must be greater than or equal to 0",
                         ));
                     }
                 }
+                "fields" => fields = Some(value),
+                "view" => view = Some(value),
                 _ => {}
             }
         }
     }
 
-    let geo_key = geo_key.ok_or_else(|| lambda_http::Error::from("geoKey is required"))?;
+    let geo_key = geo_key.ok_or_else(|| {
+        ApiError::invalid_field("geoKey", "invalid_geo_key", "geoKey is required")
+    })?;
 
     Ok(DerivedFeedQuery {
         geo_key,
         window_days,
         limit,
         offset,
+        projection: ListingProjection::parse(fields, view)?,
     })
 }
 
@@ -300,48 +551,11 @@ fn is_valid_geo_key(value: &str) -> bool {
         .all(|ch| matches!(ch, '0'..='9' | 'b'..='h' | 'j'..='k' | 'm'..='n' | 'p'..='z'))
 }
 
-fn row_to_listing_item(row: &Row) -> ListingItem {
-    ListingItem {
-        id: row.get::<_, Uuid>("id").to_string(),
-        user_id: row.get::<_, Uuid>("user_id").to_string(),
-        grower_crop_id: row
-            .get::<_, Option<Uuid>>("grower_crop_id")
-            .map(|id| id.to_string()),
-        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
-        variety_id: row
-            .get::<_, Option<Uuid>>("variety_id")
-            .map(|id| id.to_string()),
-        title: row.get("title"),
-        unit: row.get("unit"),
-        quantity_total: row.get("quantity_total"),
-        quantity_remaining: row.get("quantity_remaining"),
-        available_start: row
-            .get::<_, Option<DateTime<Utc>>>("available_start")
-            .map(|value| value.to_rfc3339()),
-        available_end: row
-            .get::<_, Option<DateTime<Utc>>>("available_end")
-            .map(|value| value.to_rfc3339()),
-        status: row.get("status"),
-        pickup_location_text: row.get("pickup_location_text"),
-        pickup_address: row.get("pickup_address"),
-        effective_pickup_address: row.get("effective_pickup_address"),
-        pickup_disclosure_policy: row.get("pickup_disclosure_policy"),
-        pickup_notes: row.get("pickup_notes"),
-        contact_pref: row.get("contact_pref"),
-        geo_key: row.get("geo_key"),
-        lat: row
-            .get::<_, Option<f64>>("lat")
-            .map(location::round_for_response),
-        lng: row
-            .get::<_, Option<f64>>("lng")
-            .map(location::round_for_response),
-        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
-    }
-}
-
-fn row_to_signal(row: &Row) -> DerivedFeedSignal {
+/// `geo_label` is left empty; see [`geocoding::labels::labels_for`].
+pub fn row_to_signal(row: &Row) -> DerivedFeedSignal {
     DerivedFeedSignal {
         geo_boundary_key: row.get("geo_boundary_key"),
+        geo_label: None,
         crop_id: row
             .get::<_, Option<Uuid>>("crop_id")
             .map(|id| id.to_string()),
@@ -359,6 +573,7 @@ fn row_to_signal(row: &Row) -> DerivedFeedSignal {
 
 fn build_deterministic_grower_guidance(
     signals: &[DerivedFeedSignal],
+    geo_prefix: &str,
     window_days: i32,
     as_of: DateTime<Utc>,
 ) -> Option<GrowerGuidance> {
@@ -411,6 +626,7 @@ fn build_deterministic_grower_guidance(
     };
 
     Some(GrowerGuidance {
+        id: grower_guidance_id(geo_prefix, window_days, season, strategy),
         guidance_text,
         explanation: GrowerGuidanceExplanation {
             season: season.to_string(),
@@ -423,6 +639,34 @@ fn build_deterministic_grower_guidance(
     })
 }
 
+/// Guidance is recomputed on every read, so its id is the inputs that shaped
+/// it; feedback handlers parse it back with [`parse_grower_guidance_id`].
+fn grower_guidance_id(geo_prefix: &str, window_days: i32, season: &str, strategy: &str) -> String {
+    format!("{geo_prefix}:{window_days}:{season}:{strategy}")
+}
+
+/// Splits a guidance id into `(geo_prefix, window_days, season, strategy)`.
+pub fn parse_grower_guidance_id(id: &str) -> Option<(String, i32, String, String)> {
+    let mut parts = id.split(':');
+    let geo_prefix = parts.next()?;
+    let window_days = parts.next()?.parse::<i32>().ok()?;
+    let season = parts.next()?;
+    let strategy = parts.next()?;
+    let valid = parts.next().is_none()
+        && location::is_valid_geo_key(geo_prefix)
+        && SUPPORTED_WINDOWS_DAYS.contains(&window_days)
+        && ["spring", "summer", "fall", "winter"].contains(&season)
+        && ["increase-resilience", "share-surplus"].contains(&strategy);
+    valid.then(|| {
+        (
+            geo_prefix.to_string(),
+            window_days,
+            season.to_string(),
+            strategy.to_string(),
+        )
+    })
+}
+
 fn count_as_f64(count: usize) -> f64 {
     u32::try_from(count).map_or_else(|_| f64::from(u32::MAX), f64::from)
 }
@@ -475,19 +719,21 @@ fn capitalize_first(value: &str) -> String {
 
 async fn load_or_generate_ai_summary(
     client: &tokio_postgres::Client,
+    config: &Config,
     geo_prefix: &str,
     window_days: i32,
     signals: &[DerivedFeedSignal],
-) -> Result<Option<DerivedFeedAiSummary>, lambda_http::Error> {
+) -> Result<Option<DerivedFeedAiSummary>, ApiError> {
     if signals.is_empty() {
         return Ok(None);
     }
 
     let now = Utc::now();
     let cached_row = client
-        .query_opt(
+        .query_opt_timed(
+            "feed::load_or_generate_ai_summary",
             "
-            select summary_text, model_id, model_version, generated_at, expires_at
+            select id, summary_text, model_id, model_version, generated_at, expires_at
             from derived_signal_summaries
             where schema_version = 1
               and geo_boundary_key = $1
@@ -498,11 +744,11 @@ async fn load_or_generate_ai_summary(
             ",
             &[&geo_prefix, &window_days, &now],
         )
-        .await
-        .map_err(db_error)?;
+        .await?;
 
     if let Some(row) = cached_row {
         return Ok(Some(DerivedFeedAiSummary {
+            id: row.get::<_, i64>("id").to_string(),
             summary_text: row.get("summary_text"),
             model_id: row.get("model_id"),
             model_version: row.get("model_version"),
@@ -512,11 +758,30 @@ async fn load_or_generate_ai_summary(
         }));
     }
 
-    let generator = SummaryGenerator::from_env();
-    let artifact = generator.generate(geo_prefix, window_days, signals)?;
-    persist_ai_summary(client, geo_prefix, window_days, signals, &artifact).await?;
+    let fulfillment = load_fulfillment_context(client, geo_prefix, window_days).await?;
+    let generator = SummaryGenerator::from_config(&config.ai);
+    let artifact = generator
+        .generate(
+            client,
+            "ai.feed_insights.read",
+            geo_prefix,
+            window_days,
+            signals,
+            &fulfillment,
+        )
+        .await?;
+    let summary_id = persist_ai_summary(
+        client,
+        geo_prefix,
+        window_days,
+        signals,
+        &fulfillment,
+        &artifact,
+    )
+    .await?;
 
     Ok(Some(DerivedFeedAiSummary {
+        id: summary_id.to_string(),
         summary_text: artifact.summary_text,
         model_id: artifact.model_id,
         model_version: artifact.model_version,
@@ -531,14 +796,17 @@ async fn persist_ai_summary(
     geo_prefix: &str,
     window_days: i32,
     signals: &[DerivedFeedSignal],
+    fulfillment: &FulfillmentContext,
     artifact: &SummaryArtifact,
-) -> Result<(), lambda_http::Error> {
-    let snapshot: serde_json::Value = serde_json::to_value(signals).map_err(|error| {
-        lambda_http::Error::from(format!("Failed to serialize signal snapshot: {error}"))
-    })?;
-
-    client
-        .execute(
+) -> Result<i64, ApiError> {
+    let snapshot = serde_json::json!({
+        "signals": signals,
+        "fulfillment": fulfillment,
+    });
+
+    let row = client
+        .query_one_timed(
+            "feed::persist_ai_summary",
             "
             insert into derived_signal_summaries (
               schema_version,
@@ -563,6 +831,7 @@ async fn persist_ai_summary(
                   generated_at = excluded.generated_at,
                   expires_at = excluded.expires_at,
                   updated_at = now()
+            returning id
             ",
             &[
                 &1,
@@ -576,32 +845,76 @@ async fn persist_ai_summary(
                 &artifact.expires_at,
             ],
         )
-        .await
-        .map_err(db_error)?;
+        .await?;
 
-    Ok(())
+    Ok(row.get("id"))
 }
 
-#[allow(clippy::needless_pass_by_value)]
-fn db_error(error: tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
+/// Claim outcomes and unmet requests for the area, so the summary can point
+/// at what is not getting picked up or matched rather than restating counts.
+async fn load_fulfillment_context(
+    client: &tokio_postgres::Client,
+    geo_prefix: &str,
+    window_days: i32,
+) -> Result<FulfillmentContext, ApiError> {
+    let claim_row = client
+        .query_one_timed(
+            "feed::load_fulfillment_context",
+            "
+            select
+              count(*) filter (where c.status in ('completed', 'cancelled', 'no_show'))::bigint as resolved_count,
+              count(*) filter (where c.status = 'completed')::bigint as completed_count,
+              count(*) filter (where c.status = 'no_show')::bigint as no_show_count
+            from claims c
+            join surplus_listings l on l.id = c.listing_id
+            where l.geo_key like $1 || '%'
+              and c.claimed_at >= now() - make_interval(days => $2)
+            ",
+            &[&geo_prefix, &window_days],
+        )
+        .await?;
 
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload).map_err(|error| {
-        lambda_http::Error::from(format!("Failed to serialize response: {error}"))
-    })?;
+    let top_unfulfilled_crops = client
+        .query_timed(
+            "feed::load_fulfillment_context",
+            "
+            select r.crop_id, c.common_name, count(*)::bigint as open_request_count
+            from requests r
+            join crops c on c.id = r.crop_id
+            where r.status = 'open'
+              and r.deleted_at is null
+              and r.geo_key like $1 || '%'
+              and r.created_at >= now() - make_interval(days => $2)
+            group by r.crop_id, c.common_name
+            order by open_request_count desc, c.common_name asc
+            limit 3
+            ",
+            &[&geo_prefix, &window_days],
+        )
+        .await?
+        .iter()
+        .map(|row| UnfulfilledCrop {
+            crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+            crop_name: row.get("common_name"),
+            open_request_count: row.get("open_request_count"),
+        })
+        .collect();
+
+    let resolved: i64 = claim_row.get("resolved_count");
+    Ok(FulfillmentContext {
+        resolved_claim_count: resolved,
+        fulfillment_rate: claim_rate(claim_row.get("completed_count"), resolved),
+        no_show_rate: claim_rate(claim_row.get("no_show_count"), resolved),
+        top_unfulfilled_crops,
+    })
+}
 
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|error| lambda_http::Error::from(error.to_string()))
+#[allow(clippy::cast_precision_loss)]
+fn claim_rate(count: i64, resolved: i64) -> Option<f64> {
+    (resolved > 0).then(|| count as f64 / resolved as f64)
 }
 
+#[allow(clippy::needless_pass_by_value)]
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -614,6 +927,18 @@ mod tests {
         assert_eq!(parsed.window_days, 7);
         assert_eq!(parsed.limit, 20);
         assert_eq!(parsed.offset, 0);
+        assert!(parsed.projection.is_full());
+    }
+
+    #[test]
+    fn parse_derived_feed_query_accepts_fields() {
+        let parsed =
+            parse_derived_feed_query(Some("geoKey=9q8yyk8&fields=title,quantityRemaining"))
+                .unwrap();
+        assert_eq!(
+            parsed.projection,
+            ListingProjection::Fields(vec!["id", "title", "quantityRemaining"])
+        );
     }
 
     #[test]
@@ -653,6 +978,7 @@ mod tests {
         let signals = vec![
             DerivedFeedSignal {
                 geo_boundary_key: "9q8y".to_string(),
+                geo_label: None,
                 crop_id: None,
                 window_days: 7,
                 listing_count: 4,
@@ -666,6 +992,7 @@ mod tests {
             },
             DerivedFeedSignal {
                 geo_boundary_key: "9q8y".to_string(),
+                geo_label: None,
                 crop_id: Some("11111111-1111-1111-1111-111111111111".to_string()),
                 window_days: 7,
                 listing_count: 5,
@@ -681,6 +1008,7 @@ mod tests {
 
         let guidance = build_deterministic_grower_guidance(
             &signals,
+            "9q8y",
             7,
             DateTime::parse_from_rfc3339("2026-02-21T12:00:00Z")
                 .unwrap()
@@ -703,6 +1031,7 @@ mod tests {
     fn deterministic_grower_guidance_prefers_abundance_strategy() {
         let signals = vec![DerivedFeedSignal {
             geo_boundary_key: "9q8y".to_string(),
+            geo_label: None,
             crop_id: None,
             window_days: 14,
             listing_count: 12,
@@ -717,6 +1046,7 @@ mod tests {
 
         let guidance = build_deterministic_grower_guidance(
             &signals,
+            "9q8y",
             14,
             DateTime::parse_from_rfc3339("2026-07-01T12:00:00Z")
                 .unwrap()
@@ -726,6 +1056,70 @@ mod tests {
 
         assert_eq!(guidance.explanation.strategy, "share-surplus");
         assert_eq!(guidance.explanation.season, "summer");
+        assert_eq!(guidance.id, "9q8y:14:summer:share-surplus");
         assert!(guidance.guidance_text.contains("Summer guidance"));
     }
+
+    #[test]
+    fn claim_rate_is_none_until_claims_resolve() {
+        assert_eq!(claim_rate(0, 0), None);
+        assert_eq!(claim_rate(3, 4), Some(0.75));
+        assert_eq!(claim_rate(0, 5), Some(0.0));
+    }
+
+    fn planned(crop_name: &str, grower_count: i32, planner_count: i32) -> PlannedCrop {
+        PlannedCrop {
+            crop_id: Uuid::nil(),
+            crop_name: crop_name.to_string(),
+            season: "summer".to_string(),
+            season_year: 2027,
+            grower_count,
+            planner_count,
+        }
+    }
+
+    #[test]
+    fn planting_warnings_flag_crops_most_planners_share() {
+        let warnings = planting_warnings(vec![
+            planned("Tomato", 6, 30),
+            planned("Zucchini", 12, 20),
+            planned("Kale", 5, 10),
+            planned("Beans", 4, 5),
+        ]);
+
+        assert_eq!(
+            warnings
+                .iter()
+                .map(|warning| warning.crop_name.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Zucchini", "Kale"]
+        );
+        assert_eq!(
+            warnings[0].message,
+            "12 neighbors are planning zucchini for summer 2027"
+        );
+    }
+
+    #[test]
+    fn grower_guidance_id_round_trips() {
+        let id = grower_guidance_id("9q8y", 7, "winter", "increase-resilience");
+        assert_eq!(
+            parse_grower_guidance_id(&id),
+            Some((
+                "9q8y".to_string(),
+                7,
+                "winter".to_string(),
+                "increase-resilience".to_string()
+            ))
+        );
+        assert_eq!(
+            parse_grower_guidance_id("9q8y:9:winter:share-surplus"),
+            None
+        );
+        assert_eq!(parse_grower_guidance_id("9q8y:7:winter"), None);
+        assert_eq!(
+            parse_grower_guidance_id("9q8y:7:winter:share-surplus:x"),
+            None
+        );
+    }
 }
@@ -0,0 +1,284 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::handlers::feed::parse_grower_guidance_id;
+use crate::http_util::{json_response, parse_json_body};
+use crate::models::feed::AiFeedbackResponse;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Deserialize;
+use tokio_postgres::Client;
+use tracing::info;
+use uuid::Uuid;
+
+const TARGET_TYPES: [&str; 2] = ["summary", "guidance"];
+const RATINGS: [&str; 2] = ["up", "down"];
+const MAX_REASON_CHARS: usize = 500;
+const GUIDANCE_MODEL_ID: &str = "deterministic.grower-guidance";
+const GUIDANCE_MODEL_VERSION: &str = "v1";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedFeedbackRequest {
+    pub target_type: String,
+    pub target_id: String,
+    pub rating: String,
+    pub reason: Option<String>,
+}
+
+#[derive(Debug)]
+struct NormalizedFeedback {
+    target_type: &'static str,
+    target_id: String,
+    rating: &'static str,
+    reason: Option<String>,
+}
+
+/// What the rating was about, captured when the feedback is recorded.
+#[derive(Debug)]
+struct FeedbackTarget {
+    model_id: String,
+    model_version: String,
+    snapshot: serde_json::Value,
+}
+
+/// Records a thumbs up or down on an AI summary or grower guidance from the
+/// derived feed. Rating the same target again replaces the earlier rating.
+pub async fn submit_feed_feedback(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let payload: FeedFeedbackRequest = parse_json_body(request)?;
+    let feedback = normalize_payload(&payload)?;
+
+    let client = db::connect().await?;
+    let target = match feedback.target_type {
+        "summary" => load_summary_target(&client, &feedback.target_id).await?,
+        _ => guidance_target(&feedback.target_id)?,
+    };
+
+    let row = client
+        .query_one_timed(
+            "feed_feedback::submit_feed_feedback",
+            "
+            insert into ai_feedback
+              (user_id, target_type, target_id, rating, reason, model_id, model_version, snapshot)
+            values ($1, $2, $3, $4, $5, $6, $7, $8)
+            on conflict (user_id, target_type, target_id)
+            do update
+              set rating = excluded.rating,
+                  reason = excluded.reason,
+                  model_id = excluded.model_id,
+                  model_version = excluded.model_version,
+                  snapshot = excluded.snapshot,
+                  updated_at = now()
+            returning id, created_at, updated_at
+            ",
+            &[
+                &user_id,
+                &feedback.target_type,
+                &feedback.target_id,
+                &feedback.rating,
+                &feedback.reason,
+                &target.model_id,
+                &target.model_version,
+                &target.snapshot,
+            ],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        target_type = feedback.target_type,
+        target_id = feedback.target_id.as_str(),
+        rating = feedback.rating,
+        model_id = target.model_id.as_str(),
+        has_reason = feedback.reason.is_some(),
+        "Recorded AI feed feedback"
+    );
+
+    let response = AiFeedbackResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        target_type: feedback.target_type.to_string(),
+        target_id: feedback.target_id,
+        rating: feedback.rating.to_string(),
+        reason: feedback.reason,
+        model_id: target.model_id,
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
+    };
+    json_response(200, &response)
+}
+
+fn normalize_payload(payload: &FeedFeedbackRequest) -> Result<NormalizedFeedback, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let target_type = TARGET_TYPES
+        .into_iter()
+        .find(|value| *value == payload.target_type);
+    if target_type.is_none() {
+        errors.add(
+            "targetType",
+            "invalid_enum",
+            format!(
+                "Invalid targetType '{}'. Allowed values: {}",
+                payload.target_type,
+                TARGET_TYPES.join(", ")
+            ),
+        );
+    }
+
+    let target_id = payload.target_id.trim();
+    if target_id.is_empty() {
+        errors.add("targetId", "required", "targetId is required");
+    }
+
+    let rating = RATINGS.into_iter().find(|value| *value == payload.rating);
+    if rating.is_none() {
+        errors.add(
+            "rating",
+            "invalid_enum",
+            format!(
+                "Invalid rating '{}'. Allowed values: {}",
+                payload.rating,
+                RATINGS.join(", ")
+            ),
+        );
+    }
+
+    let reason = payload
+        .reason
+        .as_deref()
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty());
+    if reason.is_some_and(|reason| reason.chars().count() > MAX_REASON_CHARS) {
+        errors.add(
+            "reason",
+            "too_long",
+            format!("reason must be at most {MAX_REASON_CHARS} characters"),
+        );
+    }
+
+    errors.into_result()?;
+    match (target_type, rating) {
+        (Some(target_type), Some(rating)) => Ok(NormalizedFeedback {
+            target_type,
+            target_id: target_id.to_string(),
+            rating,
+            reason: reason.map(str::to_string),
+        }),
+        _ => Err(ApiError::bad_request(
+            "invalid_feedback",
+            "Invalid feedback payload",
+        )),
+    }
+}
+
+async fn load_summary_target(client: &Client, target_id: &str) -> Result<FeedbackTarget, ApiError> {
+    let summary_id = target_id.parse::<i64>().map_err(|_| {
+        ApiError::invalid_field(
+            "targetId",
+            "invalid_summary_id",
+            "targetId must be the id of an AI summary",
+        )
+    })?;
+
+    let row = client
+        .query_opt_timed(
+            "feed_feedback::load_summary_target",
+            "
+            select geo_boundary_key, window_days::int as window_days, summary_text,
+                   model_id, model_version, signal_snapshot, generated_at
+            from derived_signal_summaries
+            where id = $1
+            ",
+            &[&summary_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("summary_not_found", "Summary not found"))?;
+
+    Ok(FeedbackTarget {
+        model_id: row.get("model_id"),
+        model_version: row.get("model_version"),
+        snapshot: serde_json::json!({
+            "geoBoundaryKey": row.get::<_, String>("geo_boundary_key"),
+            "windowDays": row.get::<_, i32>("window_days"),
+            "summaryText": row.get::<_, String>("summary_text"),
+            "generatedAt": row.get::<_, DateTime<Utc>>("generated_at").to_rfc3339(),
+            "signalSnapshot": row.get::<_, serde_json::Value>("signal_snapshot"),
+        }),
+    })
+}
+
+fn guidance_target(target_id: &str) -> Result<FeedbackTarget, ApiError> {
+    let (geo_prefix, window_days, season, strategy) = parse_grower_guidance_id(target_id)
+        .ok_or_else(|| {
+            ApiError::invalid_field(
+                "targetId",
+                "invalid_guidance_id",
+                "targetId must be the id of a grower guidance",
+            )
+        })?;
+
+    Ok(FeedbackTarget {
+        model_id: GUIDANCE_MODEL_ID.to_string(),
+        model_version: GUIDANCE_MODEL_VERSION.to_string(),
+        snapshot: serde_json::json!({
+            "geoPrefix": geo_prefix,
+            "windowDays": window_days,
+            "season": season,
+            "strategy": strategy,
+        }),
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn payload(target_type: &str, rating: &str, reason: Option<&str>) -> FeedFeedbackRequest {
+        FeedFeedbackRequest {
+            target_type: target_type.to_string(),
+            target_id: " 42 ".to_string(),
+            rating: rating.to_string(),
+            reason: reason.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn normalize_payload_trims_and_drops_blank_reason() {
+        let normalized = normalize_payload(&payload("summary", "down", Some("  "))).unwrap();
+        assert_eq!(normalized.target_type, "summary");
+        assert_eq!(normalized.target_id, "42");
+        assert_eq!(normalized.rating, "down");
+        assert_eq!(normalized.reason, None);
+    }
+
+    #[test]
+    fn normalize_payload_rejects_unknown_values() {
+        let error = normalize_payload(&payload("forecast", "meh", None)).unwrap_err();
+        assert_eq!(error.error_code(), "validation_failed");
+    }
+
+    #[test]
+    fn normalize_payload_caps_reason_length() {
+        let long = "x".repeat(MAX_REASON_CHARS + 1);
+        assert!(normalize_payload(&payload("summary", "up", Some(&long))).is_err());
+    }
+
+    #[test]
+    fn guidance_target_snapshots_id_parts() {
+        let target = guidance_target("9q8y:7:winter:increase-resilience").unwrap();
+        assert_eq!(target.model_id, GUIDANCE_MODEL_ID);
+        assert_eq!(target.snapshot["strategy"], "increase-resilience");
+        assert_eq!(target.snapshot["windowDays"], 7);
+
+        let error = guidance_target("not-a-guidance-id").unwrap_err();
+        assert_eq!(error.error_code(), "invalid_guidance_id");
+    }
+}
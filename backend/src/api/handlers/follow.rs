@@ -0,0 +1,170 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_uuid};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::Client;
+use tracing::info;
+use uuid::Uuid;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FollowResponse {
+    pub grower_id: String,
+    pub following: bool,
+    pub follower_count: i64,
+}
+
+/// Follows a grower so the caller is alerted when they post a listing.
+/// Following again returns 200 with the current state.
+pub async fn follow_grower(
+    request: &Request,
+    correlation_id: &str,
+    grower_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let follower_id = extract_user_id(request)?;
+    let grower_id = parse_uuid(grower_id, "userId")?;
+    check_follow_target(follower_id, grower_id)?;
+
+    let client = db::connect().await?;
+    let is_grower = client
+        .query_opt_timed(
+            "follow::follow_grower",
+            "
+            select 1
+            from users
+            where id = $1
+              and deleted_at is null
+              and user_type = 'grower'
+            ",
+            &[&grower_id],
+        )
+        .await?
+        .is_some();
+    if !is_grower {
+        return Err(grower_not_found());
+    }
+
+    let inserted = client
+        .execute_timed(
+            "follow::follow_grower",
+            "
+            insert into user_follows (follower_id, grower_id)
+            values ($1, $2)
+            on conflict (follower_id, grower_id) do nothing
+            ",
+            &[&follower_id, &grower_id],
+        )
+        .await?;
+
+    let response = FollowResponse {
+        grower_id: grower_id.to_string(),
+        following: true,
+        follower_count: follower_count(&client, grower_id).await?,
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        follower_id = %follower_id,
+        grower_id = %grower_id,
+        repeated = inserted == 0,
+        follower_count = response.follower_count,
+        "Followed grower"
+    );
+
+    json_response(if inserted == 0 { 200 } else { 201 }, &response)
+}
+
+pub async fn unfollow_grower(
+    request: &Request,
+    correlation_id: &str,
+    grower_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let follower_id = extract_user_id(request)?;
+    let grower_id = parse_uuid(grower_id, "userId")?;
+
+    let client = db::connect().await?;
+    let removed = client
+        .execute_timed(
+            "follow::unfollow_grower",
+            "delete from user_follows where follower_id = $1 and grower_id = $2",
+            &[&follower_id, &grower_id],
+        )
+        .await?;
+    if removed == 0 {
+        return Err(ApiError::not_found(
+            "follow_not_found",
+            "You are not following this grower",
+        ));
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        follower_id = %follower_id,
+        grower_id = %grower_id,
+        "Unfollowed grower"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+async fn follower_count(client: &Client, grower_id: Uuid) -> Result<i64, ApiError> {
+    let row = client
+        .query_one_timed(
+            "follow::follower_count",
+            "select count(*) as follower_count from user_follows where grower_id = $1",
+            &[&grower_id],
+        )
+        .await?;
+    Ok(row.get("follower_count"))
+}
+
+fn check_follow_target(follower_id: Uuid, grower_id: Uuid) -> Result<(), ApiError> {
+    if follower_id == grower_id {
+        return Err(ApiError::invalid_field(
+            "userId",
+            "cannot_follow_self",
+            "You cannot follow yourself",
+        ));
+    }
+    Ok(())
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn grower_not_found() -> ApiError {
+    ApiError::not_found("grower_not_found", "Grower not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn check_follow_target_rejects_self_follow() {
+        let user = Uuid::new_v4();
+        let error = check_follow_target(user, user).unwrap_err();
+        assert_eq!(error.error_code(), "cannot_follow_self");
+        assert!(check_follow_target(user, Uuid::new_v4()).is_ok());
+    }
+
+    #[test]
+    fn follow_response_serializes_camel_case() {
+        let response = FollowResponse {
+            grower_id: Uuid::nil().to_string(),
+            following: true,
+            follower_count: 3,
+        };
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["growerId"], Uuid::nil().to_string());
+        assert_eq!(json["followerCount"], 3);
+    }
+}
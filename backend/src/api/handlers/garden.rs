@@ -0,0 +1,738 @@
+//! Grower garden layout under `/me/plots`: plots split into beds, which
+//! crop library entries point at through `bed_id`. Rotation history is
+//! written by a trigger on `grower_crop_library`, so this module only reads
+//! it.
+
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::models::garden::{
+    BedPlanting, GardenBed, GardenPlot, UpsertGardenBedRequest, UpsertGardenPlotRequest,
+};
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_NAME_CHARS: usize = 80;
+const MAX_NOTES_CHARS: usize = 2000;
+const MAX_AREA_SCALE: u32 = 2;
+/// Well past any home or allotment garden; mostly guards against unit
+/// mix-ups such as square inches.
+const MAX_AREA_SQ_FT: i64 = 1_000_000;
+const MAX_HISTORY_ROWS: i64 = 200;
+
+#[derive(Debug, PartialEq)]
+struct NormalizedPlot {
+    name: String,
+    notes: Option<String>,
+}
+
+#[derive(Debug, PartialEq)]
+struct NormalizedBed {
+    name: String,
+    area_sq_ft: Option<Decimal>,
+    notes: Option<String>,
+}
+
+pub async fn list_plots(
+    request: &Request,
+    _correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let client = db::connect().await?;
+
+    let plot_rows = client
+        .query_timed(
+            "garden::list_plots",
+            "
+            select id, name, notes, created_at, updated_at
+              from garden_plots
+             where user_id = $1
+             order by lower(name), id
+            ",
+            &[&user_id],
+        )
+        .await?;
+    let bed_rows = client
+        .query_timed(
+            "garden::list_plots",
+            "
+            select b.id, b.plot_id, b.name, b.area_sq_ft::text as area_sq_ft, b.notes,
+                   b.created_at, b.updated_at,
+                   array(
+                     select g.id from grower_crop_library g
+                      where g.bed_id = b.id
+                      order by g.created_at, g.id
+                   ) as grower_crop_ids
+              from garden_beds b
+             where b.user_id = $1
+             order by lower(b.name), b.id
+            ",
+            &[&user_id],
+        )
+        .await?;
+
+    let mut beds_by_plot: HashMap<String, Vec<GardenBed>> = HashMap::new();
+    for bed in bed_rows.iter().map(row_to_bed) {
+        beds_by_plot
+            .entry(bed.plot_id.clone())
+            .or_default()
+            .push(bed);
+    }
+    let plots = plot_rows
+        .iter()
+        .map(|row| {
+            let plot_id = row.get::<_, Uuid>("id").to_string();
+            row_to_plot(row, beds_by_plot.remove(&plot_id).unwrap_or_default())
+        })
+        .collect::<Vec<_>>();
+
+    json_response(200, &plots)
+}
+
+pub async fn get_plot(
+    request: &Request,
+    _correlation_id: &str,
+    plot_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(plot_id, "plotId")?;
+    let client = db::connect().await?;
+
+    let row = client
+        .query_opt_timed(
+            "garden::get_plot",
+            "
+            select id, name, notes, created_at, updated_at
+              from garden_plots
+             where id = $1 and user_id = $2
+            ",
+            &[&id, &user_id],
+        )
+        .await?
+        .ok_or_else(plot_not_found)?;
+
+    let beds = load_plot_beds(&client, id).await?;
+    json_response(200, &row_to_plot(&row, beds))
+}
+
+pub async fn create_plot(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: UpsertGardenPlotRequest = parse_json_body(request)?;
+    let plot = normalize_plot(&payload)?;
+
+    let client = db::connect().await?;
+    ensure_plot_name_available(&client, user_id, &plot.name, None).await?;
+
+    let row = client
+        .query_one_timed(
+            "garden::create_plot",
+            "
+            insert into garden_plots (user_id, name, notes)
+            values ($1, $2, $3)
+            returning id, name, notes, created_at, updated_at
+            ",
+            &[&user_id, &plot.name, &plot.notes],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        plot_id = %row.get::<_, Uuid>("id"),
+        "Created garden plot"
+    );
+
+    json_response(201, &row_to_plot(&row, Vec::new()))
+}
+
+pub async fn update_plot(
+    request: &Request,
+    correlation_id: &str,
+    plot_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(plot_id, "plotId")?;
+    let payload: UpsertGardenPlotRequest = parse_json_body(request)?;
+    let plot = normalize_plot(&payload)?;
+
+    let client = db::connect().await?;
+    ensure_plot_name_available(&client, user_id, &plot.name, Some(id)).await?;
+
+    let row = client
+        .query_opt_timed(
+            "garden::update_plot",
+            "
+            update garden_plots
+               set name = $1,
+                   notes = $2,
+                   updated_at = now()
+             where id = $3 and user_id = $4
+            returning id, name, notes, created_at, updated_at
+            ",
+            &[&plot.name, &plot.notes, &id, &user_id],
+        )
+        .await?
+        .ok_or_else(plot_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        plot_id = %id,
+        "Updated garden plot"
+    );
+
+    let beds = load_plot_beds(&client, id).await?;
+    json_response(200, &row_to_plot(&row, beds))
+}
+
+/// Deletes the plot and its beds. Crops planted there stay in the library
+/// with no bed.
+pub async fn delete_plot(
+    request: &Request,
+    correlation_id: &str,
+    plot_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(plot_id, "plotId")?;
+    let client = db::connect().await?;
+
+    let deleted = client
+        .execute_timed(
+            "garden::delete_plot",
+            "delete from garden_plots where id = $1 and user_id = $2",
+            &[&id, &user_id],
+        )
+        .await?;
+    if deleted == 0 {
+        return Err(plot_not_found());
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        plot_id = %id,
+        "Deleted garden plot"
+    );
+
+    no_content()
+}
+
+pub async fn create_bed(
+    request: &Request,
+    correlation_id: &str,
+    plot_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let plot_id = parse_uuid(plot_id, "plotId")?;
+    let payload: UpsertGardenBedRequest = parse_json_body(request)?;
+    let bed = normalize_bed(&payload)?;
+
+    let client = db::connect().await?;
+    ensure_plot_owned(&client, plot_id, user_id).await?;
+    ensure_bed_name_available(&client, plot_id, &bed.name, None).await?;
+
+    let row = client
+        .query_one_timed(
+            "garden::create_bed",
+            "
+            insert into garden_beds (plot_id, user_id, name, area_sq_ft, notes)
+            values ($1, $2, $3, $4::numeric, $5)
+            returning id, plot_id, name, area_sq_ft::text as area_sq_ft, notes,
+                      created_at, updated_at, array[]::uuid[] as grower_crop_ids
+            ",
+            &[&plot_id, &user_id, &bed.name, &bed.area_sq_ft, &bed.notes],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        plot_id = %plot_id,
+        bed_id = %row.get::<_, Uuid>("id"),
+        "Created garden bed"
+    );
+
+    json_response(201, &row_to_bed(&row))
+}
+
+pub async fn update_bed(
+    request: &Request,
+    correlation_id: &str,
+    plot_id: &str,
+    bed_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let plot_id = parse_uuid(plot_id, "plotId")?;
+    let id = parse_uuid(bed_id, "bedId")?;
+    let payload: UpsertGardenBedRequest = parse_json_body(request)?;
+    let bed = normalize_bed(&payload)?;
+
+    let client = db::connect().await?;
+    ensure_bed_name_available(&client, plot_id, &bed.name, Some(id)).await?;
+
+    let row = client
+        .query_opt_timed(
+            "garden::update_bed",
+            "
+            update garden_beds b
+               set name = $1,
+                   area_sq_ft = $2::numeric,
+                   notes = $3,
+                   updated_at = now()
+             where b.id = $4 and b.plot_id = $5 and b.user_id = $6
+            returning b.id, b.plot_id, b.name, b.area_sq_ft::text as area_sq_ft, b.notes,
+                      b.created_at, b.updated_at,
+                      array(
+                        select g.id from grower_crop_library g
+                         where g.bed_id = b.id
+                         order by g.created_at, g.id
+                      ) as grower_crop_ids
+            ",
+            &[
+                &bed.name,
+                &bed.area_sq_ft,
+                &bed.notes,
+                &id,
+                &plot_id,
+                &user_id,
+            ],
+        )
+        .await?
+        .ok_or_else(bed_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        bed_id = %id,
+        "Updated garden bed"
+    );
+
+    json_response(200, &row_to_bed(&row))
+}
+
+pub async fn delete_bed(
+    request: &Request,
+    correlation_id: &str,
+    plot_id: &str,
+    bed_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let plot_id = parse_uuid(plot_id, "plotId")?;
+    let id = parse_uuid(bed_id, "bedId")?;
+    let client = db::connect().await?;
+
+    let deleted = client
+        .execute_timed(
+            "garden::delete_bed",
+            "delete from garden_beds where id = $1 and plot_id = $2 and user_id = $3",
+            &[&id, &plot_id, &user_id],
+        )
+        .await?;
+    if deleted == 0 {
+        return Err(bed_not_found());
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        bed_id = %id,
+        "Deleted garden bed"
+    );
+
+    no_content()
+}
+
+/// What has been planted in a bed, most recent first.
+pub async fn list_bed_history(
+    request: &Request,
+    _correlation_id: &str,
+    plot_id: &str,
+    bed_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let plot_id = parse_uuid(plot_id, "plotId")?;
+    let id = parse_uuid(bed_id, "bedId")?;
+    let client = db::connect().await?;
+
+    let owned = client
+        .query_one_timed(
+            "garden::list_bed_history",
+            "select exists(select 1 from garden_beds where id = $1 and plot_id = $2 and user_id = $3)",
+            &[&id, &plot_id, &user_id],
+        )
+        .await?
+        .get::<_, bool>(0);
+    if !owned {
+        return Err(bed_not_found());
+    }
+
+    let rows = client
+        .query_timed(
+            "garden::list_bed_history",
+            "
+            select p.grower_crop_id, p.crop_id, c.common_name as crop_name, p.variety_id,
+                   p.started_at, p.ended_at
+              from garden_bed_plantings p
+              join crops c on c.id = p.crop_id
+             where p.bed_id = $1
+             order by p.started_at desc, p.id desc
+             limit $2
+            ",
+            &[&id, &MAX_HISTORY_ROWS],
+        )
+        .await?;
+
+    let history = rows.iter().map(row_to_planting).collect::<Vec<_>>();
+    json_response(200, &history)
+}
+
+/// Checks that `bed_id` is one of the caller's beds, for crop library
+/// writes.
+pub async fn ensure_bed_owned(
+    client: &Client,
+    bed_id: Uuid,
+    user_id: Uuid,
+    field: &str,
+) -> Result<(), ApiError> {
+    let owned = client
+        .query_one_timed(
+            "garden::ensure_bed_owned",
+            "select exists(select 1 from garden_beds where id = $1 and user_id = $2)",
+            &[&bed_id, &user_id],
+        )
+        .await?
+        .get::<_, bool>(0);
+
+    if owned {
+        Ok(())
+    } else {
+        Err(ApiError::invalid_field(
+            field,
+            "unknown_bed",
+            format!("{field} must reference one of your garden beds"),
+        ))
+    }
+}
+
+async fn load_plot_beds(client: &Client, plot_id: Uuid) -> Result<Vec<GardenBed>, ApiError> {
+    let rows = client
+        .query_timed(
+            "garden::load_plot_beds",
+            "
+            select b.id, b.plot_id, b.name, b.area_sq_ft::text as area_sq_ft, b.notes,
+                   b.created_at, b.updated_at,
+                   array(
+                     select g.id from grower_crop_library g
+                      where g.bed_id = b.id
+                      order by g.created_at, g.id
+                   ) as grower_crop_ids
+              from garden_beds b
+             where b.plot_id = $1
+             order by lower(b.name), b.id
+            ",
+            &[&plot_id],
+        )
+        .await?;
+    Ok(rows.iter().map(row_to_bed).collect())
+}
+
+async fn ensure_plot_owned(client: &Client, plot_id: Uuid, user_id: Uuid) -> Result<(), ApiError> {
+    let owned = client
+        .query_one_timed(
+            "garden::ensure_plot_owned",
+            "select exists(select 1 from garden_plots where id = $1 and user_id = $2)",
+            &[&plot_id, &user_id],
+        )
+        .await?
+        .get::<_, bool>(0);
+
+    if owned {
+        Ok(())
+    } else {
+        Err(plot_not_found())
+    }
+}
+
+async fn ensure_plot_name_available(
+    client: &Client,
+    user_id: Uuid,
+    name: &str,
+    except: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let taken = client
+        .query_one_timed(
+            "garden::ensure_plot_name_available",
+            "
+            select exists(
+              select 1 from garden_plots
+               where user_id = $1 and lower(name) = lower($2)
+                 and ($3::uuid is null or id <> $3)
+            )
+            ",
+            &[&user_id, &name, &except],
+        )
+        .await?
+        .get::<_, bool>(0);
+
+    if taken {
+        return Err(ApiError::conflict(
+            "plot_name_taken",
+            "You already have a plot with this name",
+        ));
+    }
+    Ok(())
+}
+
+async fn ensure_bed_name_available(
+    client: &Client,
+    plot_id: Uuid,
+    name: &str,
+    except: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let taken = client
+        .query_one_timed(
+            "garden::ensure_bed_name_available",
+            "
+            select exists(
+              select 1 from garden_beds
+               where plot_id = $1 and lower(name) = lower($2)
+                 and ($3::uuid is null or id <> $3)
+            )
+            ",
+            &[&plot_id, &name, &except],
+        )
+        .await?
+        .get::<_, bool>(0);
+
+    if taken {
+        return Err(ApiError::conflict(
+            "bed_name_taken",
+            "This plot already has a bed with this name",
+        ));
+    }
+    Ok(())
+}
+
+fn normalize_plot(payload: &UpsertGardenPlotRequest) -> Result<NormalizedPlot, ApiError> {
+    let mut errors = ValidationErrors::new();
+    let name = errors.capture(normalize_name(&payload.name));
+    let notes = errors.capture(normalize_notes(payload.notes.as_deref()));
+    errors.into_result()?;
+
+    match (name, notes) {
+        (Some(name), Some(notes)) => Ok(NormalizedPlot { name, notes }),
+        _ => Err(ApiError::internal(
+            "plot validation passed with missing fields",
+        )),
+    }
+}
+
+fn normalize_bed(payload: &UpsertGardenBedRequest) -> Result<NormalizedBed, ApiError> {
+    let mut errors = ValidationErrors::new();
+    let name = errors.capture(normalize_name(&payload.name));
+    let notes = errors.capture(normalize_notes(payload.notes.as_deref()));
+
+    let area_sq_ft = payload.area_sq_ft.map(Decimal::normalize);
+    if let Some(area) = area_sq_ft {
+        if area <= Decimal::ZERO {
+            errors.add(
+                "areaSqFt",
+                "must_be_positive",
+                "areaSqFt must be greater than 0",
+            );
+        } else if area.scale() > MAX_AREA_SCALE {
+            errors.add(
+                "areaSqFt",
+                "too_many_decimal_places",
+                format!("areaSqFt must have at most {MAX_AREA_SCALE} decimal places"),
+            );
+        } else if area > Decimal::from(MAX_AREA_SQ_FT) {
+            errors.add(
+                "areaSqFt",
+                "area_too_large",
+                format!("areaSqFt must be at most {MAX_AREA_SQ_FT}"),
+            );
+        }
+    }
+    errors.into_result()?;
+
+    match (name, notes) {
+        (Some(name), Some(notes)) => Ok(NormalizedBed {
+            name,
+            area_sq_ft,
+            notes,
+        }),
+        _ => Err(ApiError::internal(
+            "bed validation passed with missing fields",
+        )),
+    }
+}
+
+fn normalize_name(value: &str) -> Result<String, ApiError> {
+    let name = value.trim();
+    if name.is_empty() {
+        return Err(ApiError::invalid_field(
+            "name",
+            "required",
+            "name is required",
+        ));
+    }
+    if name.chars().count() > MAX_NAME_CHARS {
+        return Err(ApiError::invalid_field(
+            "name",
+            "too_long",
+            format!("name must be at most {MAX_NAME_CHARS} characters"),
+        ));
+    }
+    Ok(name.to_string())
+}
+
+fn normalize_notes(value: Option<&str>) -> Result<Option<String>, ApiError> {
+    let notes = value.map(str::trim).filter(|notes| !notes.is_empty());
+    if notes.is_some_and(|notes| notes.chars().count() > MAX_NOTES_CHARS) {
+        return Err(ApiError::invalid_field(
+            "notes",
+            "too_long",
+            format!("notes must be at most {MAX_NOTES_CHARS} characters"),
+        ));
+    }
+    Ok(notes.map(str::to_string))
+}
+
+fn row_to_plot(row: &Row, beds: Vec<GardenBed>) -> GardenPlot {
+    GardenPlot {
+        id: row.get::<_, Uuid>("id").to_string(),
+        name: row.get("name"),
+        notes: row.get("notes"),
+        beds,
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
+    }
+}
+
+fn row_to_bed(row: &Row) -> GardenBed {
+    GardenBed {
+        id: row.get::<_, Uuid>("id").to_string(),
+        plot_id: row.get::<_, Uuid>("plot_id").to_string(),
+        name: row.get("name"),
+        area_sq_ft: row.get("area_sq_ft"),
+        notes: row.get("notes"),
+        grower_crop_ids: row
+            .get::<_, Vec<Uuid>>("grower_crop_ids")
+            .iter()
+            .map(Uuid::to_string)
+            .collect(),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
+    }
+}
+
+fn row_to_planting(row: &Row) -> BedPlanting {
+    BedPlanting {
+        grower_crop_id: row
+            .get::<_, Option<Uuid>>("grower_crop_id")
+            .map(|v| v.to_string()),
+        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+        crop_name: row.get("crop_name"),
+        variety_id: row
+            .get::<_, Option<Uuid>>("variety_id")
+            .map(|v| v.to_string()),
+        started_at: row.get::<_, DateTime<Utc>>("started_at").to_rfc3339(),
+        ended_at: row
+            .get::<_, Option<DateTime<Utc>>>("ended_at")
+            .map(|t| t.to_rfc3339()),
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn no_content() -> Result<Response<Body>, ApiError> {
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+fn plot_not_found() -> ApiError {
+    ApiError::not_found("plot_not_found", "Garden plot not found")
+}
+
+fn bed_not_found() -> ApiError {
+    ApiError::not_found("bed_not_found", "Garden bed not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn bed(area: Option<&str>) -> UpsertGardenBedRequest {
+        UpsertGardenBedRequest {
+            name: " North raised bed ".to_string(),
+            area_sq_ft: area.map(|value| Decimal::from_str(value).unwrap()),
+            notes: Some(String::new()),
+        }
+    }
+
+    #[test]
+    fn plot_names_are_trimmed_and_required() {
+        let plot = normalize_plot(&UpsertGardenPlotRequest {
+            name: "  Backyard ".to_string(),
+            notes: Some(" South fence ".to_string()),
+        })
+        .unwrap();
+        assert_eq!(
+            plot,
+            NormalizedPlot {
+                name: "Backyard".to_string(),
+                notes: Some("South fence".to_string()),
+            }
+        );
+
+        let blank = normalize_plot(&UpsertGardenPlotRequest {
+            name: "   ".to_string(),
+            notes: None,
+        });
+        assert_eq!(blank.unwrap_err().error_code(), "required");
+    }
+
+    #[test]
+    fn bed_area_is_normalized_and_bounded() {
+        let normalized = normalize_bed(&bed(Some("32.50"))).unwrap();
+        assert_eq!(normalized.name, "North raised bed");
+        assert_eq!(
+            normalized.area_sq_ft,
+            Some(Decimal::from_str("32.5").unwrap())
+        );
+        assert_eq!(normalized.notes, None);
+
+        assert!(normalize_bed(&bed(None)).unwrap().area_sq_ft.is_none());
+        assert_eq!(
+            normalize_bed(&bed(Some("0"))).unwrap_err().error_code(),
+            "must_be_positive"
+        );
+        assert_eq!(
+            normalize_bed(&bed(Some("1.005"))).unwrap_err().error_code(),
+            "too_many_decimal_places"
+        );
+        assert_eq!(
+            normalize_bed(&bed(Some("1000001")))
+                .unwrap_err()
+                .error_code(),
+            "area_too_large"
+        );
+    }
+}
@@ -0,0 +1,720 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body, parse_uuid, request_locale};
+use crate::models::listing::ListingItem;
+use crate::repo;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_NAME_CHARS: usize = 100;
+const MAX_DESCRIPTION_CHARS: usize = 1000;
+const INVITE_CODE_LEN: usize = 8;
+/// Uppercase letters and digits without the look-alikes 0/O and 1/I/L, so
+/// codes survive being read aloud or copied from a flyer.
+const INVITE_CODE_ALPHABET: &[u8] = b"ABCDEFGHJKMNPQRSTUVWXYZ23456789";
+const INVITE_CODE_ATTEMPTS: usize = 3;
+const PUBLIC_PAGE_LISTING_LIMIT: i64 = 20;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateGroupRequest {
+    pub name: String,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct JoinGroupRequest {
+    pub invite_code: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateMemberRoleRequest {
+    pub role: String,
+}
+
+/// A group as seen by one of its members. `invite_code` is only returned to
+/// owners and admins.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub role: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invite_code: Option<String>,
+    pub member_count: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupListResponse {
+    pub items: Vec<GroupResponse>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPublicResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub member_count: i64,
+    pub created_at: String,
+    pub listings: Vec<ListingItem>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupMemberResponse {
+    pub group_id: String,
+    pub user_id: String,
+    pub role: String,
+    pub joined_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InviteCodeResponse {
+    pub group_id: String,
+    pub invite_code: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum GroupRole {
+    Owner,
+    Admin,
+    Member,
+}
+
+impl GroupRole {
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "owner" => Some(Self::Owner),
+            "admin" => Some(Self::Admin),
+            "member" => Some(Self::Member),
+            _ => None,
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Owner => "owner",
+            Self::Admin => "admin",
+            Self::Member => "member",
+        }
+    }
+
+    /// Owners and admins see the invite code, rotate it, and remove members.
+    const fn can_manage(self) -> bool {
+        matches!(self, Self::Owner | Self::Admin)
+    }
+
+    /// Owners can remove anyone but themselves; admins can remove members.
+    const fn can_remove(self, target: Self) -> bool {
+        match (self, target) {
+            (_, Self::Owner) | (Self::Member, _) => false,
+            (Self::Owner, _) => true,
+            (Self::Admin, target) => matches!(target, Self::Member),
+        }
+    }
+}
+
+pub async fn create_group(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: CreateGroupRequest = parse_json_body(request)?;
+    let (name, description) = normalize_create_payload(&payload)?;
+
+    let mut client = db::connect().await?;
+    let tx = client.transaction().await?;
+
+    let mut group_row = None;
+    for _ in 0..INVITE_CODE_ATTEMPTS {
+        group_row = tx
+            .query_opt_timed(
+                "group::create_group",
+                "
+                insert into groups (name, description, invite_code, created_by)
+                values ($1, $2, $3, $4)
+                on conflict (invite_code) do nothing
+                returning id, name, description, invite_code, created_at
+                ",
+                &[&name, &description, &generate_invite_code(), &user_id],
+            )
+            .await?;
+        if group_row.is_some() {
+            break;
+        }
+    }
+    let group_row = group_row
+        .ok_or_else(|| ApiError::internal("Could not allocate a unique group invite code"))?;
+    let group_id: Uuid = group_row.get("id");
+
+    tx.execute_timed(
+        "group::create_group",
+        "
+        insert into group_members (group_id, user_id, role)
+        values ($1, $2, 'owner')
+        ",
+        &[&group_id, &user_id],
+    )
+    .await?;
+    db::commit(tx).await?;
+
+    let response = row_to_group_response(&group_row, GroupRole::Owner, 1);
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        group_id = %group_id,
+        "Created group"
+    );
+
+    json_response(201, &response)
+}
+
+pub async fn list_my_groups(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let client = db::connect().await?;
+
+    let rows = client
+        .query_timed(
+            "group::list_my_groups",
+            "
+            select g.id, g.name, g.description, g.invite_code, g.created_at,
+                   gm.role::text as role,
+                   (select count(*) from group_members c where c.group_id = g.id) as member_count
+            from group_members gm
+            join groups g on g.id = gm.group_id
+            where gm.user_id = $1
+              and g.deleted_at is null
+            order by gm.joined_at desc, g.id
+            ",
+            &[&user_id],
+        )
+        .await?;
+
+    let items = rows
+        .iter()
+        .map(|row| {
+            let role = role_from_row(row)?;
+            Ok(row_to_group_response(row, role, row.get("member_count")))
+        })
+        .collect::<Result<Vec<_>, ApiError>>()?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        returned_count = items.len(),
+        "Listed groups for member"
+    );
+
+    json_response(200, &GroupListResponse { items })
+}
+
+/// Joins the group matching an invite code. Joining a group the caller
+/// already belongs to is a no-op that returns their existing membership.
+pub async fn join_group(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: JoinGroupRequest = parse_json_body(request)?;
+    let invite_code = normalize_invite_code(&payload.invite_code)?;
+
+    let client = db::connect().await?;
+    let row = client
+        .query_opt_timed(
+            "group::join_group",
+            "
+            with target as (
+                select id from groups where invite_code = $1 and deleted_at is null
+            ),
+            joined as (
+                insert into group_members (group_id, user_id, role)
+                select id, $2, 'member' from target
+                on conflict (group_id, user_id) do update set role = group_members.role
+                returning group_id, role::text as role, (xmax = 0) as inserted
+            )
+            select g.id, g.name, g.description, g.invite_code, g.created_at,
+                   joined.role, joined.inserted,
+                   (select count(*) from group_members c where c.group_id = g.id)
+                     + case when joined.inserted then 1 else 0 end as member_count
+            from joined
+            join groups g on g.id = joined.group_id
+            ",
+            &[&invite_code, &user_id],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found("invalid_invite_code", "No group matches that invite code")
+        })?;
+
+    let role = role_from_row(&row)?;
+    let response = row_to_group_response(&row, role, row.get("member_count"));
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        group_id = response.id.as_str(),
+        newly_joined = row.get::<_, bool>("inserted"),
+        "Joined group"
+    );
+
+    json_response(200, &response)
+}
+
+/// Public page for a group: its profile and the active listings members have
+/// posted under it. Membership is not required.
+pub async fn get_group_page(
+    request: &Request,
+    correlation_id: &str,
+    group_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let group_id = parse_uuid(group_id, "groupId")?;
+    let client = db::connect().await?;
+
+    let row = client
+        .query_opt_timed(
+            "group::get_group_page",
+            "
+            select g.id, g.name, g.description, g.created_at,
+                   (select count(*) from group_members c where c.group_id = g.id) as member_count
+            from groups g
+            where g.id = $1
+              and g.deleted_at is null
+            ",
+            &[&group_id],
+        )
+        .await?
+        .ok_or_else(group_not_found)?;
+
+    let locale = request_locale(request);
+    let listings = repo::listing::list_by_group(
+        &client,
+        group_id,
+        "active",
+        PUBLIC_PAGE_LISTING_LIMIT,
+        0,
+        locale.as_deref(),
+    )
+    .await?;
+
+    let response = GroupPublicResponse {
+        id: group_id.to_string(),
+        name: row.get("name"),
+        description: row.get("description"),
+        member_count: row.get("member_count"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        listings,
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        group_id = %group_id,
+        listing_count = response.listings.len(),
+        "Fetched group public page"
+    );
+
+    json_response(200, &response)
+}
+
+/// Owner-only: promotes a member to admin or demotes an admin to member.
+/// Ownership itself cannot be reassigned here.
+pub async fn update_member_role(
+    request: &Request,
+    correlation_id: &str,
+    group_id: &str,
+    member_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let group_id = parse_uuid(group_id, "groupId")?;
+    let member_id = parse_uuid(member_id, "userId")?;
+    let payload: UpdateMemberRoleRequest = parse_json_body(request)?;
+    let new_role = parse_assignable_role(&payload.role)?;
+
+    let client = db::connect().await?;
+    let caller_role = require_member(&client, group_id, user_id).await?;
+    if caller_role != GroupRole::Owner {
+        return Err(ApiError::forbidden(
+            "group_owner_required",
+            "Forbidden: Only the group owner can change member roles",
+        ));
+    }
+    if member_id == user_id {
+        return Err(ApiError::bad_request(
+            "cannot_change_own_role",
+            "The group owner cannot change their own role",
+        ));
+    }
+
+    let row = client
+        .query_opt_timed(
+            "group::update_member_role",
+            "
+            update group_members
+            set role = $3::text::group_member_role
+            where group_id = $1
+              and user_id = $2
+              and role <> 'owner'
+            returning group_id, user_id, role::text as role, joined_at
+            ",
+            &[&group_id, &member_id, &new_role.as_str()],
+        )
+        .await?
+        .ok_or_else(member_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        group_id = %group_id,
+        member_id = %member_id,
+        role = new_role.as_str(),
+        "Updated group member role"
+    );
+
+    json_response(200, &row_to_member_response(&row))
+}
+
+/// Removes a member. Owners and admins remove others (subject to
+/// [`GroupRole::can_remove`]); any non-owner may remove themselves to leave.
+pub async fn remove_member(
+    request: &Request,
+    correlation_id: &str,
+    group_id: &str,
+    member_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let group_id = parse_uuid(group_id, "groupId")?;
+    let member_id = parse_uuid(member_id, "userId")?;
+
+    let client = db::connect().await?;
+    let caller_role = require_member(&client, group_id, user_id).await?;
+    let target_role = if member_id == user_id {
+        caller_role
+    } else {
+        let role = repo::group::member_role(&client, group_id, member_id)
+            .await?
+            .ok_or_else(member_not_found)?;
+        parse_stored_role(&role)?
+    };
+
+    check_removal(caller_role, target_role, member_id == user_id)?;
+
+    client
+        .execute_timed(
+            "group::remove_member",
+            "delete from group_members where group_id = $1 and user_id = $2",
+            &[&group_id, &member_id],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        group_id = %group_id,
+        member_id = %member_id,
+        "Removed group member"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// Replaces the invite code so previously shared codes stop working.
+/// Existing members are unaffected.
+pub async fn rotate_invite_code(
+    request: &Request,
+    correlation_id: &str,
+    group_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let group_id = parse_uuid(group_id, "groupId")?;
+
+    let client = db::connect().await?;
+    let caller_role = require_member(&client, group_id, user_id).await?;
+    if !caller_role.can_manage() {
+        return Err(group_manager_required());
+    }
+
+    let mut invite_code = None;
+    for _ in 0..INVITE_CODE_ATTEMPTS {
+        let candidate = generate_invite_code();
+        let updated = client
+            .execute_timed(
+                "group::rotate_invite_code",
+                "
+                update groups
+                set invite_code = $2, updated_at = now()
+                where id = $1
+                  and not exists (select 1 from groups where invite_code = $2)
+                ",
+                &[&group_id, &candidate],
+            )
+            .await?;
+        if updated == 1 {
+            invite_code = Some(candidate);
+            break;
+        }
+    }
+    let invite_code = invite_code
+        .ok_or_else(|| ApiError::internal("Could not allocate a unique group invite code"))?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        group_id = %group_id,
+        "Rotated group invite code"
+    );
+
+    json_response(
+        200,
+        &InviteCodeResponse {
+            group_id: group_id.to_string(),
+            invite_code,
+        },
+    )
+}
+
+/// Loads the caller's role, answering 404 for non-members so private
+/// membership details are not revealed.
+async fn require_member(
+    client: &Client,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<GroupRole, ApiError> {
+    let role = repo::group::member_role(client, group_id, user_id)
+        .await?
+        .ok_or_else(group_not_found)?;
+    parse_stored_role(&role)
+}
+
+fn check_removal(caller: GroupRole, target: GroupRole, is_self: bool) -> Result<(), ApiError> {
+    if is_self {
+        if caller == GroupRole::Owner {
+            return Err(ApiError::bad_request(
+                "owner_cannot_leave",
+                "The group owner cannot leave the group",
+            ));
+        }
+        return Ok(());
+    }
+    if caller.can_remove(target) {
+        Ok(())
+    } else {
+        Err(group_manager_required())
+    }
+}
+
+fn normalize_create_payload(
+    payload: &CreateGroupRequest,
+) -> Result<(String, Option<String>), ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        errors.add("name", "required", "name is required");
+    } else if name.chars().count() > MAX_NAME_CHARS {
+        errors.add(
+            "name",
+            "too_long",
+            format!("name must be at most {MAX_NAME_CHARS} characters"),
+        );
+    }
+
+    let description = payload
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|value| !value.is_empty());
+    if description.is_some_and(|value| value.chars().count() > MAX_DESCRIPTION_CHARS) {
+        errors.add(
+            "description",
+            "too_long",
+            format!("description must be at most {MAX_DESCRIPTION_CHARS} characters"),
+        );
+    }
+
+    errors.into_result()?;
+    Ok((name.to_string(), description.map(str::to_string)))
+}
+
+fn normalize_invite_code(value: &str) -> Result<String, ApiError> {
+    let code: String = value
+        .chars()
+        .filter(|c| !c.is_whitespace() && *c != '-')
+        .collect::<String>()
+        .to_ascii_uppercase();
+
+    if code.len() != INVITE_CODE_LEN || !code.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return Err(ApiError::invalid_field(
+            "inviteCode",
+            "invalid_invite_code",
+            format!("inviteCode must be {INVITE_CODE_LEN} letters or digits"),
+        ));
+    }
+    Ok(code)
+}
+
+fn generate_invite_code() -> String {
+    let mut rng = rand::thread_rng();
+    (0..INVITE_CODE_LEN)
+        .map(|_| char::from(INVITE_CODE_ALPHABET[rng.gen_range(0..INVITE_CODE_ALPHABET.len())]))
+        .collect()
+}
+
+fn parse_assignable_role(value: &str) -> Result<GroupRole, ApiError> {
+    match GroupRole::parse(value.trim()) {
+        Some(role @ (GroupRole::Admin | GroupRole::Member)) => Ok(role),
+        _ => Err(ApiError::invalid_field(
+            "role",
+            "invalid_enum",
+            format!("Invalid role '{value}'. Allowed values: admin, member"),
+        )),
+    }
+}
+
+fn role_from_row(row: &Row) -> Result<GroupRole, ApiError> {
+    parse_stored_role(row.get("role"))
+}
+
+fn parse_stored_role(role: &str) -> Result<GroupRole, ApiError> {
+    GroupRole::parse(role).ok_or_else(|| ApiError::internal(format!("Unknown group role '{role}'")))
+}
+
+fn row_to_group_response(row: &Row, role: GroupRole, member_count: i64) -> GroupResponse {
+    GroupResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        name: row.get("name"),
+        description: row.get("description"),
+        role: role.as_str().to_string(),
+        invite_code: role.can_manage().then(|| row.get("invite_code")),
+        member_count,
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    }
+}
+
+fn row_to_member_response(row: &Row) -> GroupMemberResponse {
+    GroupMemberResponse {
+        group_id: row.get::<_, Uuid>("group_id").to_string(),
+        user_id: row.get::<_, Uuid>("user_id").to_string(),
+        role: row.get("role"),
+        joined_at: row.get::<_, DateTime<Utc>>("joined_at").to_rfc3339(),
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn group_not_found() -> ApiError {
+    ApiError::not_found("group_not_found", "Group not found")
+}
+
+fn member_not_found() -> ApiError {
+    ApiError::not_found("group_member_not_found", "Group member not found")
+}
+
+fn group_manager_required() -> ApiError {
+    ApiError::forbidden(
+        "group_admin_required",
+        "Forbidden: Only group owners and admins can do this",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_invite_codes_use_the_unambiguous_alphabet() {
+        for _ in 0..50 {
+            let code = generate_invite_code();
+            assert_eq!(code.len(), INVITE_CODE_LEN);
+            assert!(code.bytes().all(|b| INVITE_CODE_ALPHABET.contains(&b)));
+            assert_eq!(normalize_invite_code(&code).unwrap(), code);
+        }
+    }
+
+    #[test]
+    fn normalize_invite_code_accepts_typed_variants() {
+        assert_eq!(normalize_invite_code(" abcd-2345 ").unwrap(), "ABCD2345");
+        assert_eq!(
+            normalize_invite_code("ABC").unwrap_err().error_code(),
+            "invalid_invite_code"
+        );
+        assert!(normalize_invite_code("ABCD234!").is_err());
+    }
+
+    #[test]
+    fn normalize_create_payload_trims_and_validates() {
+        let (name, description) = normalize_create_payload(&CreateGroupRequest {
+            name: "  Elm Street Allotment ".to_string(),
+            description: Some("   ".to_string()),
+        })
+        .unwrap();
+        assert_eq!(name, "Elm Street Allotment");
+        assert_eq!(description, None);
+
+        let error = normalize_create_payload(&CreateGroupRequest {
+            name: " ".to_string(),
+            description: Some("x".repeat(MAX_DESCRIPTION_CHARS + 1)),
+        })
+        .unwrap_err();
+        assert_eq!(error.error_code(), "validation_failed");
+    }
+
+    #[test]
+    fn only_admin_and_member_roles_are_assignable() {
+        assert_eq!(parse_assignable_role("admin").unwrap(), GroupRole::Admin);
+        assert_eq!(parse_assignable_role("member").unwrap(), GroupRole::Member);
+        assert_eq!(
+            parse_assignable_role("owner").unwrap_err().error_code(),
+            "invalid_enum"
+        );
+    }
+
+    #[test]
+    fn removal_rules_protect_the_owner_and_respect_rank() {
+        use GroupRole::{Admin, Member, Owner};
+
+        assert!(check_removal(Owner, Admin, false).is_ok());
+        assert!(check_removal(Owner, Member, false).is_ok());
+        assert!(check_removal(Admin, Member, false).is_ok());
+        assert!(check_removal(Admin, Admin, false).is_err());
+        assert!(check_removal(Admin, Owner, false).is_err());
+        assert!(check_removal(Member, Member, false).is_err());
+
+        assert!(check_removal(Member, Member, true).is_ok());
+        assert!(check_removal(Admin, Admin, true).is_ok());
+        assert_eq!(
+            check_removal(Owner, Owner, true).unwrap_err().error_code(),
+            "owner_cannot_leave"
+        );
+    }
+
+    #[test]
+    fn invite_code_is_only_exposed_to_managers() {
+        assert!(GroupRole::Owner.can_manage());
+        assert!(GroupRole::Admin.can_manage());
+        assert!(!GroupRole::Member.can_manage());
+    }
+}
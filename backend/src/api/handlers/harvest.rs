@@ -0,0 +1,714 @@
+//! Grower harvest log under `/me/harvests`. Each harvest belongs to a crop in
+//! the grower's library; the crop and the grower's geo key are copied onto
+//! the row so the signal aggregation can count harvests by area as ground
+//! truth for local supply. A harvest can be turned into a prefilled listing
+//! draft, and `POST /listings` with its `harvestId` links the two.
+
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::models::harvest::{
+    HarvestItem, HarvestListingDraft, ListHarvestsResponse, UpsertHarvestRequest,
+};
+use crate::quantity;
+use chrono::{DateTime, Duration, NaiveDate, SecondsFormat, Utc};
+use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_UNIT_CHARS: usize = 32;
+const MAX_NOTES_CHARS: usize = 2000;
+/// Harvest dates are the grower's local date, so a day of slack keeps an
+/// evening harvest west of UTC from reading as the future.
+const FUTURE_SLACK_DAYS: i64 = 1;
+/// Drafts are open from now for this long; picked produce is rarely worth
+/// offering for longer without a fresh listing.
+const DRAFT_AVAILABILITY_DAYS: i64 = 3;
+
+#[derive(Debug)]
+struct NormalizedHarvest {
+    grower_crop_id: Uuid,
+    harvested_on: NaiveDate,
+    quantity: Decimal,
+    unit: Option<String>,
+    notes: Option<String>,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+struct ListHarvestsQuery {
+    from: Option<NaiveDate>,
+    to: Option<NaiveDate>,
+    limit: i64,
+    offset: i64,
+}
+
+pub async fn list_harvests(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let query = parse_list_harvests_query(request.uri().query())?;
+    let client = db::connect().await?;
+
+    let rows = client
+        .query_timed(
+            "harvest::list_harvests",
+            "
+            select id, grower_crop_id, crop_id, harvested_on::text as harvested_on,
+                   quantity::text as quantity, unit, notes, listing_id, created_at, updated_at
+              from harvests
+             where user_id = $1
+               and ($2::date is null or harvested_on >= $2)
+               and ($3::date is null or harvested_on <= $3)
+             order by harvested_on desc, created_at desc, id desc
+             limit $4 offset $5
+            ",
+            &[
+                &user_id,
+                &query.from,
+                &query.to,
+                &(query.limit + 1),
+                &query.offset,
+            ],
+        )
+        .await?;
+
+    let limit = usize::try_from(query.limit).unwrap_or(usize::MAX);
+    let has_more = rows.len() > limit;
+    let items = rows.iter().take(limit).map(row_to_item).collect::<Vec<_>>();
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        returned_count = items.len(),
+        has_more = has_more,
+        "Listed harvests"
+    );
+
+    json_response(
+        200,
+        &ListHarvestsResponse {
+            items,
+            limit: query.limit,
+            offset: query.offset,
+            has_more,
+            next_offset: has_more.then_some(query.offset + query.limit),
+        },
+    )
+}
+
+pub async fn get_harvest(
+    request: &Request,
+    _correlation_id: &str,
+    harvest_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(harvest_id, "harvestId")?;
+    let client = db::connect().await?;
+
+    let row = client
+        .query_opt_timed(
+            "harvest::get_harvest",
+            "
+            select id, grower_crop_id, crop_id, harvested_on::text as harvested_on,
+                   quantity::text as quantity, unit, notes, listing_id, created_at, updated_at
+              from harvests
+             where id = $1 and user_id = $2
+            ",
+            &[&id, &user_id],
+        )
+        .await?
+        .ok_or_else(harvest_not_found)?;
+
+    json_response(200, &row_to_item(&row))
+}
+
+pub async fn create_harvest(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let payload: UpsertHarvestRequest = parse_json_body(request)?;
+    let harvest = normalize_harvest(&payload, Utc::now().date_naive())?;
+
+    let client = db::connect().await?;
+    let (crop_id, unit) = resolve_library_crop(&client, user_id, &harvest).await?;
+
+    let row = client
+        .query_one_timed(
+            "harvest::create_harvest",
+            "
+            insert into harvests
+                (user_id, grower_crop_id, crop_id, harvested_on, quantity, unit, notes, geo_key)
+            values
+                ($1, $2, $3, $4, $5::numeric, $6, $7,
+                 (select geo_key from grower_profiles where user_id = $1))
+            returning id, grower_crop_id, crop_id, harvested_on::text as harvested_on,
+                      quantity::text as quantity, unit, notes, listing_id, created_at, updated_at
+            ",
+            &[
+                &user_id,
+                &harvest.grower_crop_id,
+                &crop_id,
+                &harvest.harvested_on,
+                &harvest.quantity,
+                &unit,
+                &harvest.notes,
+            ],
+        )
+        .await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        harvest_id = %row.get::<_, Uuid>("id"),
+        "Logged harvest"
+    );
+
+    json_response(201, &row_to_item(&row))
+}
+
+/// Replaces the harvest's fields. The geo key stays as captured when the
+/// harvest was logged.
+pub async fn update_harvest(
+    request: &Request,
+    correlation_id: &str,
+    harvest_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(harvest_id, "harvestId")?;
+    let payload: UpsertHarvestRequest = parse_json_body(request)?;
+    let harvest = normalize_harvest(&payload, Utc::now().date_naive())?;
+
+    let client = db::connect().await?;
+    let (crop_id, unit) = resolve_library_crop(&client, user_id, &harvest).await?;
+
+    let row = client
+        .query_opt_timed(
+            "harvest::update_harvest",
+            "
+            update harvests
+               set grower_crop_id = $1,
+                   crop_id = $2,
+                   harvested_on = $3,
+                   quantity = $4::numeric,
+                   unit = $5,
+                   notes = $6,
+                   updated_at = now()
+             where id = $7 and user_id = $8
+            returning id, grower_crop_id, crop_id, harvested_on::text as harvested_on,
+                      quantity::text as quantity, unit, notes, listing_id, created_at, updated_at
+            ",
+            &[
+                &harvest.grower_crop_id,
+                &crop_id,
+                &harvest.harvested_on,
+                &harvest.quantity,
+                &unit,
+                &harvest.notes,
+                &id,
+                &user_id,
+            ],
+        )
+        .await?
+        .ok_or_else(harvest_not_found)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        harvest_id = %id,
+        "Updated harvest"
+    );
+
+    json_response(200, &row_to_item(&row))
+}
+
+/// Deleting a harvest leaves any listing created from it in place.
+pub async fn delete_harvest(
+    request: &Request,
+    correlation_id: &str,
+    harvest_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(harvest_id, "harvestId")?;
+    let client = db::connect().await?;
+
+    let deleted = client
+        .execute_timed(
+            "harvest::delete_harvest",
+            "delete from harvests where id = $1 and user_id = $2",
+            &[&id, &user_id],
+        )
+        .await?;
+    if deleted == 0 {
+        return Err(harvest_not_found());
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        harvest_id = %id,
+        "Deleted harvest"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// A `POST /listings` body for the whole harvest, available from now. The
+/// pickup address is left out so the listing falls back to the grower's
+/// profile address.
+pub async fn get_listing_draft(
+    request: &Request,
+    correlation_id: &str,
+    harvest_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let id = parse_uuid(harvest_id, "harvestId")?;
+    let client = db::connect().await?;
+
+    let row = client
+        .query_opt_timed(
+            "harvest::get_listing_draft",
+            "
+            select h.id, h.crop_id, h.quantity::text as quantity, h.unit,
+                   coalesce(nullif(btrim(l.nickname), ''), c.common_name) as title,
+                   l.variety_id,
+                   exists (
+                     select 1 from surplus_listings sl
+                      where sl.id = h.listing_id and sl.deleted_at is null
+                   ) as listed
+              from harvests h
+              join crops c on c.id = h.crop_id
+              left join grower_crop_library l on l.id = h.grower_crop_id
+             where h.id = $1 and h.user_id = $2
+            ",
+            &[&id, &user_id],
+        )
+        .await?
+        .ok_or_else(harvest_not_found)?;
+
+    if row.get::<_, bool>("listed") {
+        return Err(harvest_already_listed());
+    }
+
+    let (available_start, available_end) = draft_window(Utc::now());
+    let draft = HarvestListingDraft {
+        harvest_id: id.to_string(),
+        title: row.get("title"),
+        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+        variety_id: row
+            .get::<_, Option<Uuid>>("variety_id")
+            .map(|v| v.to_string()),
+        quantity_total: row.get("quantity"),
+        unit: row.get("unit"),
+        available_start,
+        available_end,
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        harvest_id = %id,
+        "Prepared listing draft from harvest"
+    );
+
+    json_response(200, &draft)
+}
+
+/// Checks that a listing being created for `crop_id` may be linked to the
+/// harvest: it must be the caller's, of the same crop, and not already
+/// behind another live listing. `listing_id` is the listing being created,
+/// so an idempotent replay passes.
+pub async fn ensure_listable(
+    client: &Client,
+    harvest_id: Uuid,
+    user_id: Uuid,
+    crop_id: Uuid,
+    listing_id: Uuid,
+) -> Result<(), ApiError> {
+    let row = client
+        .query_opt_timed(
+            "harvest::ensure_listable",
+            "
+            select h.crop_id,
+                   exists (
+                     select 1 from surplus_listings sl
+                      where sl.id = h.listing_id and sl.id <> $3 and sl.deleted_at is null
+                   ) as listed
+              from harvests h
+             where h.id = $1 and h.user_id = $2
+            ",
+            &[&harvest_id, &user_id, &listing_id],
+        )
+        .await?
+        .ok_or_else(harvest_not_found)?;
+
+    if row.get::<_, Uuid>("crop_id") != crop_id {
+        return Err(ApiError::invalid_field(
+            "harvestId",
+            "harvest_crop_mismatch",
+            "harvestId must be a harvest of the listing's crop",
+        ));
+    }
+    if row.get::<_, bool>("listed") {
+        return Err(harvest_already_listed());
+    }
+    Ok(())
+}
+
+pub async fn link_listing(
+    client: &Client,
+    harvest_id: Uuid,
+    user_id: Uuid,
+    listing_id: Uuid,
+) -> Result<(), ApiError> {
+    client
+        .execute_timed(
+            "harvest::link_listing",
+            "
+            update harvests
+               set listing_id = $3,
+                   updated_at = now()
+             where id = $1 and user_id = $2
+               and listing_id is distinct from $3
+            ",
+            &[&harvest_id, &user_id, &listing_id],
+        )
+        .await?;
+    Ok(())
+}
+
+fn normalize_harvest(
+    payload: &UpsertHarvestRequest,
+    today: NaiveDate,
+) -> Result<NormalizedHarvest, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let grower_crop_id = errors.capture(parse_uuid(&payload.grower_crop_id, "growerCropId"));
+
+    let harvested_on = NaiveDate::parse_from_str(payload.harvested_on.trim(), "%Y-%m-%d").ok();
+    match harvested_on {
+        None => errors.add(
+            "harvestedOn",
+            "invalid_date",
+            "harvestedOn must use YYYY-MM-DD",
+        ),
+        Some(date) if date > today + Duration::days(FUTURE_SLACK_DAYS) => errors.add(
+            "harvestedOn",
+            "future_date",
+            "harvestedOn cannot be in the future",
+        ),
+        Some(_) => {}
+    }
+
+    let quantity = errors.capture(quantity::validate(payload.quantity, "quantity"));
+    if quantity.is_some_and(|value| value <= Decimal::ZERO) {
+        errors.add(
+            "quantity",
+            "must_be_positive",
+            "quantity must be greater than 0",
+        );
+    }
+
+    let unit = trimmed(payload.unit.as_deref());
+    if unit
+        .as_ref()
+        .is_some_and(|unit| unit.chars().count() > MAX_UNIT_CHARS)
+    {
+        errors.add(
+            "unit",
+            "too_long",
+            format!("unit must be at most {MAX_UNIT_CHARS} characters"),
+        );
+    }
+
+    let notes = trimmed(payload.notes.as_deref());
+    if notes
+        .as_ref()
+        .is_some_and(|notes| notes.chars().count() > MAX_NOTES_CHARS)
+    {
+        errors.add(
+            "notes",
+            "too_long",
+            format!("notes must be at most {MAX_NOTES_CHARS} characters"),
+        );
+    }
+
+    errors.into_result()?;
+    let (Some(grower_crop_id), Some(harvested_on), Some(quantity)) =
+        (grower_crop_id, harvested_on, quantity)
+    else {
+        return Err(ApiError::internal(
+            "harvest validation passed with missing fields",
+        ));
+    };
+
+    Ok(NormalizedHarvest {
+        grower_crop_id,
+        harvested_on,
+        quantity,
+        unit,
+        notes,
+    })
+}
+
+/// The library entry's crop, and the unit to store: the one sent, or the
+/// entry's default.
+async fn resolve_library_crop(
+    client: &Client,
+    user_id: Uuid,
+    harvest: &NormalizedHarvest,
+) -> Result<(Uuid, String), ApiError> {
+    let row = client
+        .query_opt_timed(
+            "harvest::resolve_library_crop",
+            "select crop_id, default_unit from grower_crop_library where id = $1 and user_id = $2",
+            &[&harvest.grower_crop_id, &user_id],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::invalid_field(
+                "growerCropId",
+                "unknown_grower_crop",
+                "growerCropId must reference a crop in your library",
+            )
+        })?;
+
+    let unit = harvest
+        .unit
+        .clone()
+        .or_else(|| trimmed(row.get::<_, Option<String>>("default_unit").as_deref()))
+        .ok_or_else(|| {
+            ApiError::invalid_field(
+                "unit",
+                "required",
+                "unit is required when the crop has no default unit",
+            )
+        })?;
+
+    Ok((row.get("crop_id"), unit))
+}
+
+fn parse_list_harvests_query(query: Option<&str>) -> Result<ListHarvestsQuery, ApiError> {
+    let mut parsed = ListHarvestsQuery {
+        from: None,
+        to: None,
+        limit: 20,
+        offset: 0,
+    };
+
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+        match key {
+            "from" => parsed.from = Some(parse_query_date(value, "from")?),
+            "to" => parsed.to = Some(parse_query_date(value, "to")?),
+            "limit" => {
+                parsed.limit = value
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|limit| (1..=100).contains(limit))
+                    .ok_or_else(|| {
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be between 1 and 100",
+                        )
+                    })?;
+            }
+            "offset" => {
+                parsed.offset = value
+                    .parse::<i64>()
+                    .ok()
+                    .filter(|offset| *offset >= 0)
+                    .ok_or_else(|| {
+                        ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be greater than or equal to 0",
+                        )
+                    })?;
+            }
+            _ => {}
+        }
+    }
+
+    if let (Some(from), Some(to)) = (parsed.from, parsed.to) {
+        if from > to {
+            return Err(ApiError::invalid_field(
+                "from",
+                "invalid_range",
+                "from must be on or before to",
+            ));
+        }
+    }
+
+    Ok(parsed)
+}
+
+fn parse_query_date(value: &str, field: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| {
+        ApiError::invalid_field(
+            field,
+            "invalid_date",
+            format!("{field} must use YYYY-MM-DD"),
+        )
+    })
+}
+
+fn draft_window(now: DateTime<Utc>) -> (String, String) {
+    let end = now + Duration::days(DRAFT_AVAILABILITY_DAYS);
+    (
+        now.to_rfc3339_opts(SecondsFormat::Secs, true),
+        end.to_rfc3339_opts(SecondsFormat::Secs, true),
+    )
+}
+
+fn trimmed(value: Option<&str>) -> Option<String> {
+    value
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(str::to_string)
+}
+
+fn row_to_item(row: &Row) -> HarvestItem {
+    HarvestItem {
+        id: row.get::<_, Uuid>("id").to_string(),
+        grower_crop_id: row
+            .get::<_, Option<Uuid>>("grower_crop_id")
+            .map(|v| v.to_string()),
+        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+        harvested_on: row.get("harvested_on"),
+        quantity: row.get("quantity"),
+        unit: row.get("unit"),
+        notes: row.get("notes"),
+        listing_id: row
+            .get::<_, Option<Uuid>>("listing_id")
+            .map(|v| v.to_string()),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        updated_at: row.get::<_, DateTime<Utc>>("updated_at").to_rfc3339(),
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn harvest_not_found() -> ApiError {
+    ApiError::not_found("harvest_not_found", "Harvest not found")
+}
+
+fn harvest_already_listed() -> ApiError {
+    ApiError::conflict(
+        "harvest_already_listed",
+        "A listing has already been created from this harvest",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn payload() -> UpsertHarvestRequest {
+        UpsertHarvestRequest {
+            grower_crop_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
+            harvested_on: "2026-07-14".to_string(),
+            quantity: Decimal::from_str("4.5").unwrap(),
+            unit: Some(" lb ".to_string()),
+            notes: Some("   ".to_string()),
+        }
+    }
+
+    fn today() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 7, 15).unwrap()
+    }
+
+    #[test]
+    fn normalize_trims_text_and_keeps_valid_fields() {
+        let harvest = normalize_harvest(&payload(), today()).unwrap();
+        assert_eq!(harvest.harvested_on.to_string(), "2026-07-14");
+        assert_eq!(harvest.quantity, Decimal::from_str("4.5").unwrap());
+        assert_eq!(harvest.unit.as_deref(), Some("lb"));
+        assert_eq!(harvest.notes, None);
+    }
+
+    #[test]
+    fn normalize_reports_every_invalid_field() {
+        let mut invalid = payload();
+        invalid.grower_crop_id = "nope".to_string();
+        invalid.harvested_on = "2026-07-17".to_string();
+        invalid.quantity = Decimal::ZERO;
+        invalid.unit = Some("x".repeat(MAX_UNIT_CHARS + 1));
+
+        let fields = match normalize_harvest(&invalid, today()).unwrap_err() {
+            ApiError::Validation { issues } => issues
+                .iter()
+                .map(|issue| (issue.field.clone(), issue.code))
+                .collect::<Vec<_>>(),
+            other => panic!("unexpected error: {other:?}"),
+        };
+        assert_eq!(
+            fields,
+            vec![
+                ("growerCropId".to_string(), "invalid_uuid"),
+                ("harvestedOn".to_string(), "future_date"),
+                ("quantity".to_string(), "must_be_positive"),
+                ("unit".to_string(), "too_long"),
+            ]
+        );
+    }
+
+    #[test]
+    fn normalize_allows_a_day_of_timezone_slack() {
+        let mut tomorrow = payload();
+        tomorrow.harvested_on = "2026-07-16".to_string();
+        assert!(normalize_harvest(&tomorrow, today()).is_ok());
+    }
+
+    #[test]
+    fn list_query_parses_date_range_and_paging() {
+        let query =
+            parse_list_harvests_query(Some("from=2026-06-01&to=2026-06-30&limit=5")).unwrap();
+        assert_eq!(
+            query,
+            ListHarvestsQuery {
+                from: NaiveDate::from_ymd_opt(2026, 6, 1),
+                to: NaiveDate::from_ymd_opt(2026, 6, 30),
+                limit: 5,
+                offset: 0,
+            }
+        );
+
+        let reversed = parse_list_harvests_query(Some("from=2026-07-01&to=2026-06-01"));
+        assert_eq!(reversed.unwrap_err().error_code(), "invalid_range");
+        let bad_date = parse_list_harvests_query(Some("from=June"));
+        assert_eq!(bad_date.unwrap_err().error_code(), "invalid_date");
+        let bad_limit = parse_list_harvests_query(Some("limit=0"));
+        assert_eq!(bad_limit.unwrap_err().error_code(), "invalid_limit");
+    }
+
+    #[test]
+    fn draft_window_opens_now_for_three_days() {
+        let now = DateTime::parse_from_rfc3339("2026-07-15T16:30:12.5Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            draft_window(now),
+            (
+                "2026-07-15T16:30:12Z".to_string(),
+                "2026-07-18T16:30:12Z".to_string()
+            )
+        );
+    }
+}
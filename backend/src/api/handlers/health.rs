@@ -0,0 +1,253 @@
+use crate::auth::extract_auth_context;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::events;
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use std::future::Future;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Each deep check gives up after this long so one slow dependency cannot
+/// hold the probe past the uptime monitor's own timeout.
+const CHECK_TIMEOUT: Duration = Duration::from_millis(1500);
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthResponse {
+    pub status: &'static str,
+    pub version: &'static str,
+    pub git_sha: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepHealthResponse {
+    pub status: &'static str,
+    pub version: &'static str,
+    pub git_sha: String,
+    pub migration_version: Option<String>,
+    pub checks: DeepHealthChecks,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepHealthChecks {
+    pub database: CheckResult,
+    pub migrations: CheckResult,
+    pub event_bus: CheckResult,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CheckResult {
+    pub ok: bool,
+    pub latency_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Shallow liveness probe: touches no dependencies, so it stays green while
+/// the database is down and only says the function can serve requests.
+pub fn get_health(config: &Config) -> Result<Response<Body>, ApiError> {
+    let response = HealthResponse {
+        status: "ok",
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: config.git_sha.clone(),
+    };
+    health_response(200, &response)
+}
+
+/// Readiness probe for deploy pipelines. Returns 503 with the same body when
+/// any dependency check fails.
+pub async fn get_deep_health(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+
+    let (database, migration_version, migrations) = match db::connect().await {
+        Ok(client) => {
+            let (database, _) = run_check(async {
+                client
+                    .query_one_timed("health::ping", "select 1", &[])
+                    .await
+                    .map(|_| ())
+                    .map_err(ApiError::from)
+            })
+            .await;
+            let (migrations, migration_version) = run_check(async {
+                client
+                    .query_one_timed(
+                        "health::migration_version",
+                        "select max(version) as version from schema_migrations",
+                        &[],
+                    )
+                    .await
+                    .map(|row| row.get::<_, Option<String>>("version"))
+                    .map_err(ApiError::from)
+            })
+            .await;
+            (database, migration_version.flatten(), migrations)
+        }
+        Err(error) => {
+            let failed = CheckResult {
+                ok: false,
+                latency_ms: 0,
+                error: Some(public_error(&ApiError::from(error))),
+            };
+            let migrations = CheckResult {
+                ok: false,
+                latency_ms: 0,
+                error: Some("database unavailable".to_string()),
+            };
+            (failed, None, migrations)
+        }
+    };
+
+    let (event_bus, _) = run_check(Box::pin(async {
+        events::check_event_bus(&config.event_bus_name)
+            .await
+            .map_err(|e| ApiError::unavailable("event_bus_unavailable", e.to_string()))
+    }))
+    .await;
+
+    let checks = DeepHealthChecks {
+        database,
+        migrations,
+        event_bus,
+    };
+    let healthy = checks.database.ok && checks.migrations.ok && checks.event_bus.ok;
+    let response = DeepHealthResponse {
+        status: if healthy { "ok" } else { "degraded" },
+        version: env!("CARGO_PKG_VERSION"),
+        git_sha: config.git_sha.clone(),
+        migration_version,
+        checks,
+    };
+
+    if healthy {
+        info!(
+            correlation_id = correlation_id,
+            user_id = auth_context.user_id.as_str(),
+            migration_version = ?response.migration_version,
+            "Deep health check passed"
+        );
+    } else {
+        warn!(
+            correlation_id = correlation_id,
+            user_id = auth_context.user_id.as_str(),
+            database_ok = response.checks.database.ok,
+            migrations_ok = response.checks.migrations.ok,
+            event_bus_ok = response.checks.event_bus.ok,
+            "Deep health check degraded"
+        );
+    }
+
+    health_response(if healthy { 200 } else { 503 }, &response)
+}
+
+async fn run_check<T, F>(check: F) -> (CheckResult, Option<T>)
+where
+    F: Future<Output = Result<T, ApiError>>,
+{
+    let started = Instant::now();
+    let outcome = tokio::time::timeout(CHECK_TIMEOUT, check).await;
+    let latency_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    match outcome {
+        Ok(Ok(value)) => (
+            CheckResult {
+                ok: true,
+                latency_ms,
+                error: None,
+            },
+            Some(value),
+        ),
+        Ok(Err(error)) => (
+            CheckResult {
+                ok: false,
+                latency_ms,
+                error: Some(public_error(&error)),
+            },
+            None,
+        ),
+        Err(_) => (
+            CheckResult {
+                ok: false,
+                latency_ms,
+                error: Some("timed out".to_string()),
+            },
+            None,
+        ),
+    }
+}
+
+/// Reports the error code only; messages can carry hostnames or ARNs.
+fn public_error(error: &ApiError) -> String {
+    warn!(error = %error, "Health check dependency failed");
+    error.error_code().to_string()
+}
+
+fn health_response<T: Serialize>(status: u16, payload: &T) -> Result<Response<Body>, ApiError> {
+    let body = serde_json::to_string(payload)
+        .map_err(|e| ApiError::internal(format!("Failed to serialize response: {e}")))?;
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .header("cache-control", "no-store")
+        .body(Body::from(body))
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shallow_health_reports_version_without_dependencies() {
+        let response = get_health(&Config::for_tests()).unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(response.headers()["cache-control"], "no-store");
+
+        let Body::Text(text) = response.body() else {
+            unreachable!("health body is text");
+        };
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["status"], "ok");
+        assert_eq!(json["version"], env!("CARGO_PKG_VERSION"));
+        assert!(json["gitSha"].is_string());
+    }
+
+    #[tokio::test]
+    async fn run_check_reports_success_and_failure() {
+        let (ok, value) = run_check(async { Ok::<_, ApiError>(7) }).await;
+        assert!(ok.ok);
+        assert_eq!(value, Some(7));
+
+        let (failed, value) = run_check(async {
+            Err::<(), _>(ApiError::unavailable(
+                "database_unavailable",
+                "connection refused to 10.0.0.1",
+            ))
+        })
+        .await;
+        assert!(!failed.ok);
+        assert_eq!(failed.error.as_deref(), Some("database_unavailable"));
+        assert_eq!(value, None);
+    }
+
+    #[test]
+    fn check_result_omits_missing_error() {
+        let json = serde_json::to_value(CheckResult {
+            ok: true,
+            latency_ms: 3,
+            error: None,
+        })
+        .unwrap();
+        assert_eq!(json, serde_json::json!({ "ok": true, "latencyMs": 3 }));
+    }
+}
@@ -0,0 +1,453 @@
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::{extract_auth_context, AuthContext};
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body};
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tokio_postgres::Row;
+use uuid::Uuid;
+
+const TOKEN_PREFIX: &str = "cgi_";
+const DEFAULT_DURATION_MINUTES: i64 = 15;
+const MAX_DURATION_MINUTES: i64 = 60;
+const MAX_REASON_LENGTH: usize = 500;
+/// Sessions shown to the affected user, newest first.
+const HISTORY_LIMIT: i64 = 50;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartImpersonationRequest {
+    pub reason: String,
+    pub duration_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonationSessionResponse {
+    pub session_id: String,
+    pub admin_user_id: String,
+    pub target_user_id: String,
+    pub reason: String,
+    pub created_at: String,
+    pub expires_at: String,
+    pub ended_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartedImpersonationResponse {
+    #[serde(flatten)]
+    pub session: ImpersonationSessionResponse,
+    /// Sent as `X-Impersonation-Token` alongside the admin's own bearer
+    /// token. Only returned once; the database stores its hash.
+    pub token: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonatedRequestResponse {
+    pub occurred_at: String,
+    pub method: String,
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonationHistoryItem {
+    #[serde(flatten)]
+    pub session: ImpersonationSessionResponse,
+    pub requests: Vec<ImpersonatedRequestResponse>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpersonationHistoryResponse {
+    pub items: Vec<ImpersonationHistoryItem>,
+}
+
+/// Opens a read-only support session for `user_id`. The returned token only
+/// works together with the issuing admin's credentials.
+pub async fn start_impersonation(
+    request: &Request,
+    correlation_id: &str,
+    user_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let admin_id = parse_user_id(&auth.user_id)?;
+    let target_id = parse_path_id(user_id, "userId")?;
+    if admin_id == target_id {
+        return Err(ApiError::invalid_field(
+            "userId",
+            "self_impersonation",
+            "Admins cannot impersonate themselves",
+        ));
+    }
+
+    let payload: StartImpersonationRequest = parse_json_body(request)?;
+    let reason = normalize_reason(&payload.reason)?;
+    let duration_minutes = validate_duration(payload.duration_minutes)?;
+    let token = generate_token();
+
+    let mut client = db::connect().await?;
+    let transaction = client.transaction().await?;
+
+    let row = transaction
+        .query_opt_timed(
+            "impersonation::start_impersonation",
+            "
+            insert into impersonation_sessions
+                (admin_user_id, target_user_id, reason, token_hash, expires_at)
+            select $1, u.id, $3, $4, now() + make_interval(mins => $5::int)
+              from users u
+             where u.id = $2
+               and u.deleted_at is null
+            returning id, admin_user_id, target_user_id, reason, created_at, expires_at, ended_at
+            ",
+            &[
+                &admin_id,
+                &target_id,
+                &reason,
+                &hash_token(&token),
+                &i32::try_from(duration_minutes).unwrap_or(i32::MAX),
+            ],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("user_not_found", "User not found"))?;
+    let session = row_to_session(&row);
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::IMPERSONATION_STARTED,
+            target_type: "user",
+            target_id: session.target_user_id.clone(),
+            before: None,
+            after: serde_json::to_value(&session).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = %admin_id,
+        user_id = session.target_user_id.as_str(),
+        impersonation_session_id = session.session_id.as_str(),
+        expires_at = session.expires_at.as_str(),
+        "Admin started impersonation session"
+    );
+
+    json_response(201, &StartedImpersonationResponse { session, token })
+}
+
+/// Ends a session before it expires. Ending one that is already over
+/// returns it unchanged without a second audit entry.
+pub async fn end_impersonation(
+    request: &Request,
+    correlation_id: &str,
+    session_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let session_id = parse_path_id(session_id, "sessionId")?;
+
+    let client = db::connect().await?;
+    let row = client
+        .query_opt_timed(
+            "impersonation::end_impersonation",
+            "
+            with previous as (
+                select ended_at from impersonation_sessions where id = $1
+            )
+            update impersonation_sessions
+               set ended_at = coalesce(ended_at, least(now(), expires_at))
+             where id = $1
+            returning id, admin_user_id, target_user_id, reason, created_at, expires_at, ended_at,
+                      (select ended_at from previous) as previous_ended_at
+            ",
+            &[&session_id],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                "impersonation_session_not_found",
+                "Impersonation session not found",
+            )
+        })?;
+    let session = row_to_session(&row);
+
+    if row
+        .get::<_, Option<DateTime<Utc>>>("previous_ended_at")
+        .is_none()
+    {
+        audit::record_best_effort(
+            &*client,
+            &AuditEntry {
+                actor: Actor::from_auth(&auth),
+                action: audit::IMPERSONATION_ENDED,
+                target_type: "user",
+                target_id: session.target_user_id.clone(),
+                before: Some(
+                    serde_json::json!({ "sessionId": session.session_id, "endedAt": null }),
+                ),
+                after: serde_json::to_value(&session).ok(),
+                correlation_id,
+            },
+        )
+        .await;
+    }
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = auth.user_id.as_str(),
+        impersonation_session_id = session.session_id.as_str(),
+        "Admin ended impersonation session"
+    );
+
+    json_response(200, &session)
+}
+
+/// Lists the support sessions opened on the caller's account and every
+/// request made through them.
+pub async fn list_my_impersonations(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let user_id = parse_user_id(&auth.user_id)?;
+
+    let client = db::connect().await?;
+    let session_rows = client
+        .query_timed(
+            "impersonation::list_sessions",
+            "
+            select id, admin_user_id, target_user_id, reason, created_at, expires_at, ended_at
+              from impersonation_sessions
+             where target_user_id = $1
+             order by created_at desc
+             limit $2
+            ",
+            &[&user_id, &HISTORY_LIMIT],
+        )
+        .await?;
+
+    let session_ids: Vec<String> = session_rows
+        .iter()
+        .map(|row| row.get::<_, Uuid>("id").to_string())
+        .collect();
+    let request_rows = if session_ids.is_empty() {
+        Vec::new()
+    } else {
+        client
+            .query_timed(
+                "impersonation::list_requests",
+                "
+                select occurred_at,
+                       after_snapshot ->> 'sessionId' as session_id,
+                       after_snapshot ->> 'method' as method,
+                       after_snapshot ->> 'path' as path
+                  from audit_log
+                 where action = $1
+                   and target_type = 'user'
+                   and target_id = $2
+                   and after_snapshot ->> 'sessionId' = any($3)
+                 order by id
+                ",
+                &[
+                    &audit::IMPERSONATED_REQUEST,
+                    &user_id.to_string(),
+                    &session_ids,
+                ],
+            )
+            .await?
+    };
+
+    let mut requests_by_session: HashMap<String, Vec<ImpersonatedRequestResponse>> = HashMap::new();
+    for row in &request_rows {
+        let Some(session_id) = row.get::<_, Option<String>>("session_id") else {
+            continue;
+        };
+        requests_by_session
+            .entry(session_id)
+            .or_default()
+            .push(ImpersonatedRequestResponse {
+                occurred_at: row.get::<_, DateTime<Utc>>("occurred_at").to_rfc3339(),
+                method: row.get::<_, Option<String>>("method").unwrap_or_default(),
+                path: row.get::<_, Option<String>>("path").unwrap_or_default(),
+            });
+    }
+
+    let items: Vec<ImpersonationHistoryItem> = session_rows
+        .iter()
+        .map(|row| {
+            let session = row_to_session(row);
+            let requests = requests_by_session
+                .remove(&session.session_id)
+                .unwrap_or_default();
+            ImpersonationHistoryItem { session, requests }
+        })
+        .collect();
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        user_id = auth.user_id.as_str(),
+        session_count = items.len(),
+        "Listed impersonation sessions for user"
+    );
+
+    json_response(200, &ImpersonationHistoryResponse { items })
+}
+
+/// Stamps one impersonated request in the audit log, including requests
+/// that are then refused. Called by the router before any handler runs.
+pub async fn record_impersonated_request(
+    auth: &AuthContext,
+    method: &str,
+    path: &str,
+    correlation_id: &str,
+) {
+    let Some(session) = &auth.impersonation else {
+        return;
+    };
+
+    let entry = AuditEntry {
+        actor: Actor::from_auth(auth),
+        action: audit::IMPERSONATED_REQUEST,
+        target_type: "user",
+        target_id: auth.user_id.clone(),
+        before: None,
+        after: Some(serde_json::json!({
+            "sessionId": session.session_id,
+            "method": method,
+            "path": path,
+        })),
+        correlation_id,
+    };
+
+    match db::connect().await {
+        Ok(client) => audit::record_best_effort(&*client, &entry).await,
+        Err(error) => tracing::error!(
+            correlation_id = correlation_id,
+            impersonation_session_id = session.session_id.as_str(),
+            error = %error,
+            "Failed to connect to database to audit impersonated request"
+        ),
+    }
+}
+
+fn validate_duration(duration_minutes: Option<i64>) -> Result<i64, ApiError> {
+    match duration_minutes {
+        None => Ok(DEFAULT_DURATION_MINUTES),
+        Some(minutes) if (1..=MAX_DURATION_MINUTES).contains(&minutes) => Ok(minutes),
+        Some(_) => Err(ApiError::invalid_field(
+            "durationMinutes",
+            "out_of_range",
+            format!("durationMinutes must be between 1 and {MAX_DURATION_MINUTES}"),
+        )),
+    }
+}
+
+fn normalize_reason(reason: &str) -> Result<String, ApiError> {
+    let reason = reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::invalid_field(
+            "reason",
+            "required",
+            "reason is required",
+        ));
+    }
+    if reason.chars().count() > MAX_REASON_LENGTH {
+        return Err(ApiError::invalid_field(
+            "reason",
+            "too_long",
+            format!("reason must be at most {MAX_REASON_LENGTH} characters"),
+        ));
+    }
+    Ok(reason.to_string())
+}
+
+fn generate_token() -> String {
+    format!(
+        "{TOKEN_PREFIX}{}{}",
+        Uuid::new_v4().simple(),
+        Uuid::new_v4().simple()
+    )
+}
+
+fn hash_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+fn parse_path_id(value: &str, field_name: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value).map_err(|_| {
+        ApiError::invalid_field(
+            field_name,
+            "invalid_uuid",
+            format!("{field_name} must be a valid UUID"),
+        )
+    })
+}
+
+fn parse_user_id(user_id: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn row_to_session(row: &Row) -> ImpersonationSessionResponse {
+    ImpersonationSessionResponse {
+        session_id: row.get::<_, Uuid>("id").to_string(),
+        admin_user_id: row.get::<_, Uuid>("admin_user_id").to_string(),
+        target_user_id: row.get::<_, Uuid>("target_user_id").to_string(),
+        reason: row.get("reason"),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        expires_at: row.get::<_, DateTime<Utc>>("expires_at").to_rfc3339(),
+        ended_at: row
+            .get::<_, Option<DateTime<Utc>>>("ended_at")
+            .map(|value| value.to_rfc3339()),
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_tokens_are_prefixed_and_unique() {
+        let first = generate_token();
+        assert!(first.starts_with(TOKEN_PREFIX));
+        assert_ne!(first, generate_token());
+    }
+
+    #[test]
+    fn token_hash_matches_the_authorizer_format() {
+        let hash = hash_token("cgi_example");
+        assert_eq!(hash.len(), 64);
+        assert!(hash
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+    }
+
+    #[test]
+    fn duration_defaults_and_is_capped() {
+        assert_eq!(validate_duration(None).unwrap(), DEFAULT_DURATION_MINUTES);
+        assert_eq!(validate_duration(Some(60)).unwrap(), 60);
+        assert!(validate_duration(Some(0)).is_err());
+        assert!(validate_duration(Some(61)).is_err());
+    }
+
+    #[test]
+    fn reason_is_required_and_bounded() {
+        assert_eq!(
+            normalize_reason("  feed is empty  ").unwrap(),
+            "feed is empty"
+        );
+        assert!(normalize_reason("   ").is_err());
+        assert!(normalize_reason(&"x".repeat(MAX_REASON_LENGTH + 1)).is_err());
+    }
+}
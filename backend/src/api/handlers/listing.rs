@@ -1,31 +1,44 @@
-use crate::auth::{extract_auth_context_with_fallback, require_grower};
-use crate::db;
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::{extract_auth_context, AuthContext};
+use crate::config::Config;
+use crate::coordinate_privacy::LocatedEntity;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::events::{self, ListingEventDetail};
+use crate::handlers::listing_limits::{self, ListingLimitsConfig};
+use crate::handlers::listing_transition::{ListingActorRole, ListingStatus};
+use crate::handlers::{co_manager, harvest, listing_transition};
+use crate::http_util::{json_response, parse_json_body, parse_uuid, request_locale};
+use crate::listing_kind::{invalid_kind, ListingKind};
+use crate::listing_projection::ListingProjection;
 use crate::location;
-use crate::models::crop::ErrorResponse;
-use crate::models::listing::{ListMyListingsResponse, ListingItem};
-use aws_config::BehaviorVersion;
-use aws_sdk_eventbridge::types::PutEventsRequestEntry;
+use crate::models::listing::ListMyListingsResponse;
+use crate::quantity;
+use crate::repo;
 use chrono::{DateTime, Utc};
 use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tokio_postgres::{Client, Row};
 use tracing::{error, info};
 use uuid::Uuid;
 
+const DEFAULT_PICKUP_DISCLOSURE_POLICY: &str = "after_confirmed";
 const ALLOWED_PICKUP_DISCLOSURE_POLICY: [&str; 3] =
     ["immediate", "after_confirmed", "after_accepted"];
 const ALLOWED_CONTACT_PREF: [&str; 3] = ["app_message", "phone", "knock"];
 const ALLOWED_LISTING_STATUS: [&str; 5] = ["active", "pending", "claimed", "expired", "completed"];
-const ALLOWED_LISTING_READ_STATUS: [&str; 3] = ["active", "expired", "completed"];
+const ALLOWED_LISTING_READ_STATUS: [&str; 5] =
+    ["scheduled", "active", "paused", "expired", "completed"];
 const UPDATE_LISTING_SQL: &str = "
             update surplus_listings
             set crop_id = $1,
                 variety_id = $2,
                 title = $3,
                 unit = $4,
-                quantity_total = $5::double precision,
-                quantity_remaining = least(coalesce(quantity_remaining, $5::double precision), $5::double precision),
+                quantity_total = $5::numeric,
+                quantity_remaining = least(coalesce(quantity_remaining, $5::numeric), $5::numeric),
                 available_start = $6,
                 available_end = $7,
                 status = $8::text::listing_status,
@@ -37,7 +50,12 @@ const UPDATE_LISTING_SQL: &str = "
                 contact_pref = $14::text::contact_preference,
                 geo_key = $15,
                 lat = $16,
-                lng = $17
+                lng = $17,
+                group_id = $20,
+                listing_kind = $21::text::listing_kind,
+                return_by = $22,
+                publish_at = $23,
+                expire_at = $24
             where id = $18
               and user_id = $19
               and deleted_at is null
@@ -48,16 +66,21 @@ const UPDATE_LISTING_SQL: &str = "
                       pickup_location_text, pickup_address, effective_pickup_address,
                       pickup_disclosure_policy::text as pickup_disclosure_policy,
                       pickup_notes, contact_pref::text as contact_pref,
-                      geo_key, lat, lng, created_at
+                      geo_key, lat, lng, group_id, created_at,
+                      listing_kind::text as listing_kind, return_by,
+                      publish_at, expire_at
             ";
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct UpsertListingRequest {
     pub title: String,
-    pub crop_id: String,
+    /// Defaults to `produce`.
+    pub listing_kind: Option<String>,
+    /// Required for crop kinds; must be absent for tool loans and compost.
+    pub crop_id: Option<String>,
     pub variety_id: Option<String>,
-    pub quantity_total: f64,
+    pub quantity_total: Decimal,
     pub unit: String,
     pub available_start: String,
     pub available_end: String,
@@ -66,7 +89,22 @@ pub struct UpsertListingRequest {
     pub pickup_disclosure_policy: Option<String>,
     pub pickup_notes: Option<String>,
     pub contact_pref: Option<String>,
+    /// `active` or `pending` on create, which is the default. On update an
+    /// omitted status keeps the current one. Must be omitted with
+    /// `publish_at`.
     pub status: Option<String>,
+    pub group_id: Option<String>,
+    /// When a loaned tool is due back; tool loans only.
+    pub return_by: Option<String>,
+    /// A future time holds the listing as `scheduled` until the listing
+    /// schedule worker makes it active. Only listings not yet published can
+    /// be rescheduled.
+    pub publish_at: Option<String>,
+    /// When the listing schedule worker expires the listing if it is still
+    /// active or pending, independent of the availability window.
+    pub expire_at: Option<String>,
+    /// Links a new listing to the grower's harvest; ignored on update.
+    pub harvest_id: Option<String>,
 }
 
 #[derive(Debug)]
@@ -79,8 +117,10 @@ struct ResolvedLocationInput {
 
 #[derive(Debug)]
 struct NormalizedListingInput {
-    crop_id: Uuid,
+    listing_kind: ListingKind,
+    crop_id: Option<Uuid>,
     variety_id: Option<Uuid>,
+    quantity_total: Decimal,
     available_start: DateTime<Utc>,
     available_end: DateTime<Utc>,
     pickup_address: Option<String>,
@@ -91,13 +131,20 @@ struct NormalizedListingInput {
     geo_key: String,
     lat: f64,
     lng: f64,
+    group_id: Option<Uuid>,
+    return_by: Option<DateTime<Utc>>,
+    publish_at: Option<DateTime<Utc>>,
+    expire_at: Option<DateTime<Utc>>,
 }
 
 #[derive(Debug)]
 struct ListMyListingsQuery {
     status: Option<String>,
+    /// Lists another grower's listings for a co-manager of their garden.
+    owner_id: Option<Uuid>,
     limit: i64,
     offset: i64,
+    projection: ListingProjection,
 }
 
 #[derive(Debug, Serialize)]
@@ -105,7 +152,8 @@ struct ListMyListingsQuery {
 pub struct ListingWriteResponse {
     pub id: String,
     pub user_id: String,
-    pub crop_id: String,
+    pub listing_kind: String,
+    pub crop_id: Option<String>,
     pub variety_id: Option<String>,
     pub title: String,
     pub quantity_total: String,
@@ -123,76 +171,52 @@ pub struct ListingWriteResponse {
     pub geo_key: String,
     pub lat: f64,
     pub lng: f64,
+    pub group_id: Option<String>,
+    pub return_by: Option<String>,
+    pub publish_at: Option<String>,
+    pub expire_at: Option<String>,
     pub created_at: String,
 }
 
 pub async fn list_my_listings(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let query = parse_list_my_listings_query(request.uri().query())?;
 
     let client = db::connect().await?;
+    let owner_id = match query.owner_id {
+        Some(owner_id) if owner_id != user_id => {
+            co_manager::ensure_co_manages(&client, owner_id, user_id).await?;
+            owner_id
+        }
+        _ => user_id,
+    };
     let fetch_limit = query.limit + 1;
 
-    let rows = if let Some(status) = &query.status {
-        client
-            .query(
-                "
-                select id, user_id, grower_crop_id, crop_id, variety_id, title, unit,
-                       quantity_total::text as quantity_total,
-                       quantity_remaining::text as quantity_remaining,
-                       available_start, available_end, status::text,
-                       pickup_location_text, pickup_address, effective_pickup_address,
-                       pickup_disclosure_policy::text, pickup_notes, contact_pref::text,
-                       geo_key, lat, lng, created_at
-                from surplus_listings
-                where user_id = $1
-                  and deleted_at is null
-                  and status = $2::text::listing_status
-                order by created_at desc, id desc
-                limit $3 offset $4
-                ",
-                &[&user_id, status, &fetch_limit, &query.offset],
-            )
-            .await
-            .map_err(|error| db_error(&error))?
-    } else {
-        client
-            .query(
-                "
-                select id, user_id, grower_crop_id, crop_id, variety_id, title, unit,
-                       quantity_total::text as quantity_total,
-                       quantity_remaining::text as quantity_remaining,
-                       available_start, available_end, status::text,
-                       pickup_location_text, pickup_address, effective_pickup_address,
-                       pickup_disclosure_policy::text, pickup_notes, contact_pref::text,
-                       geo_key, lat, lng, created_at
-                from surplus_listings
-                where user_id = $1
-                  and deleted_at is null
-                order by created_at desc, id desc
-                limit $2 offset $3
-                ",
-                &[&user_id, &fetch_limit, &query.offset],
-            )
-            .await
-            .map_err(|error| db_error(&error))?
-    };
+    let rows = repo::listing::list_by_owner(
+        &client,
+        owner_id,
+        query.status.as_deref(),
+        fetch_limit,
+        query.offset,
+        request_locale(request).as_deref(),
+    )
+    .await?;
 
-    let limit = usize::try_from(query.limit)
-        .map_err(|_| lambda_http::Error::from("Invalid limit. Must be between 1 and 100"))?;
+    let limit = usize::try_from(query.limit).map_err(|_| {
+        ApiError::invalid_field(
+            "limit",
+            "invalid_limit",
+            "Invalid limit. Must be between 1 and 100",
+        )
+    })?;
     let has_more = rows.len() > limit;
-    let items = rows
-        .into_iter()
-        .take(limit)
-        .map(|row| row_to_listing_item(&row))
-        .collect::<Vec<_>>();
+    let items = rows.into_iter().take(limit).collect::<Vec<_>>();
 
     let response = ListMyListingsResponse {
         items,
@@ -209,94 +233,117 @@ pub async fn list_my_listings(
     info!(
         correlation_id = correlation_id,
         user_id = %user_id,
+        owner_id = %owner_id,
         status_filter = ?query.status,
         limit = query.limit,
         offset = query.offset,
         returned_count = response.items.len(),
         has_more = response.has_more,
+        sparse_fields = !query.projection.is_full(),
         "Listed grower-owned surplus listings"
     );
 
-    json_response(200, &response)
+    json_response(200, &query.projection.apply(&response)?)
 }
 
 pub async fn get_listing(
     request: &Request,
     correlation_id: &str,
     listing_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let id = parse_uuid(listing_id, "listingId")?;
 
     let client = db::connect().await?;
-    let maybe_row = client
-        .query_opt(
-            "
-            select id, user_id, grower_crop_id, crop_id, variety_id, title, unit,
-                   quantity_total::text as quantity_total,
-                   quantity_remaining::text as quantity_remaining,
-                   available_start, available_end, status::text,
-                   pickup_location_text, pickup_address, effective_pickup_address,
-                   pickup_disclosure_policy::text, pickup_notes, contact_pref::text,
-                   geo_key, lat, lng, created_at
-            from surplus_listings
-            where id = $1
-              and user_id = $2
-              and deleted_at is null
-            ",
-            &[&id, &user_id],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
+    let maybe_listing = match co_manager::resolve_managed_listing(&client, id, user_id).await? {
+        Some(managed) => {
+            repo::listing::find_by_owner(
+                &client,
+                id,
+                managed.owner_id,
+                request_locale(request).as_deref(),
+            )
+            .await?
+        }
+        None => None,
+    };
 
-    if let Some(row) = maybe_row {
+    if let Some(listing) = maybe_listing {
         info!(
             correlation_id = correlation_id,
             user_id = %user_id,
             listing_id = %id,
             "Fetched grower-owned listing"
         );
-        return json_response(200, &row_to_listing_item(&row));
+        return json_response(200, &listing);
     }
 
-    error_response(404, "Listing not found")
+    Err(ApiError::not_found(
+        "listing_not_found",
+        "Listing not found",
+    ))
 }
 
-#[allow(clippy::too_many_lines)]
 pub async fn create_listing(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let payload: UpsertListingRequest = parse_json_body(request)?;
+    create_listing_from_payload(
+        request,
+        &auth_context,
+        &payload,
+        &config.listing_limits,
+        correlation_id,
+    )
+    .await
+}
 
+/// Everything after the body is parsed, so listings built from a template
+/// go through the same checks, geocoding and idempotency as `POST /listings`.
+#[allow(clippy::too_many_lines)]
+pub async fn create_listing_from_payload(
+    request: &Request,
+    auth_context: &AuthContext,
+    payload: &UpsertListingRequest,
+    limits: &ListingLimitsConfig,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
-    let payload: UpsertListingRequest = parse_json_body(request)?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let idempotency_key = extract_idempotency_key(request);
     let listing_id = idempotency_key.as_deref().map_or_else(Uuid::new_v4, |key| {
         derive_deterministic_listing_id(user_id, key)
     });
 
     let client = db::connect().await?;
-    validate_catalog_links(
-        &client,
-        parse_uuid(&payload.crop_id, "crop_id")?,
-        parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?,
-    )
-    .await?;
+    listing_limits::enforce(&client, limits, user_id, listing_id).await?;
+    if let Some(crop_id) = parse_optional_uuid(payload.crop_id.as_deref(), "crop_id")? {
+        validate_catalog_links(
+            &client,
+            crop_id,
+            parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?,
+        )
+        .await?;
+    }
 
     let effective_pickup_address =
         resolve_effective_pickup_address(&client, user_id, payload.pickup_address.as_deref())
             .await?;
-    let geocoded = location::geocode_address(&effective_pickup_address, correlation_id).await?;
+    let geocoded = geocode_pickup_address(
+        &effective_pickup_address,
+        payload.pickup_address.as_deref(),
+        correlation_id,
+    )
+    .await?;
 
     let normalized = normalize_payload(
-        &payload,
+        payload,
         ResolvedLocationInput {
             effective_pickup_address,
             geo_key: geocoded.geo_key,
@@ -304,9 +351,28 @@ pub async fn create_listing(
             lng: geocoded.lng,
         },
     )?;
+    let status = initial_status(&normalized, Utc::now())?;
+    if let Some(group_id) = normalized.group_id {
+        validate_group_attribution(&client, group_id, user_id).await?;
+    }
+    let harvest_id = parse_optional_uuid(payload.harvest_id.as_deref(), "harvestId")?;
+    if let Some(harvest_id) = harvest_id {
+        let Some(crop_id) = normalized
+            .crop_id
+            .filter(|_| normalized.listing_kind == ListingKind::Produce)
+        else {
+            return Err(ApiError::invalid_field(
+                "harvestId",
+                "harvest_kind_mismatch",
+                "Only produce listings can be created from a harvest",
+            ));
+        };
+        harvest::ensure_listable(&client, harvest_id, user_id, crop_id, listing_id).await?;
+    }
 
     let inserted_row = client
-        .query_opt(
+        .query_opt_timed(
+            "listing::create_listing",
             "
             insert into surplus_listings
                 (id, user_id, crop_id, variety_id, title, unit,
@@ -314,14 +380,16 @@ pub async fn create_listing(
                  available_start, available_end, status,
                  pickup_location_text, pickup_address, effective_pickup_address,
                  pickup_disclosure_policy, pickup_notes,
-                 contact_pref, geo_key, lat, lng)
+                 contact_pref, geo_key, lat, lng, group_id,
+                 listing_kind, return_by, publish_at, expire_at)
             values
                 ($1, $2, $3, $4, $5, $6,
-                 $7::double precision, $7::double precision,
+                 $7::numeric, $7::numeric,
                  $8, $9, $10::text::listing_status,
                  $11, $12, $13,
                  $14::text::pickup_disclosure_policy, $15,
-                 $16::text::contact_preference, $17, $18, $19)
+                 $16::text::contact_preference, $17, $18, $19, $20,
+                 $21::text::listing_kind, $22, $23, $24)
             on conflict (id) do nothing
             returning id, user_id, crop_id, variety_id, title,
                       quantity_total::text as quantity_total,
@@ -330,7 +398,9 @@ pub async fn create_listing(
                       pickup_location_text, pickup_address, effective_pickup_address,
                       pickup_disclosure_policy::text as pickup_disclosure_policy,
                       pickup_notes, contact_pref::text as contact_pref,
-                      geo_key, lat, lng, created_at
+                      geo_key, lat, lng, group_id, created_at,
+                      listing_kind::text as listing_kind, return_by,
+                      publish_at, expire_at
             ",
             &[
                 &listing_id,
@@ -339,10 +409,10 @@ pub async fn create_listing(
                 &normalized.variety_id,
                 &payload.title,
                 &payload.unit,
-                &payload.quantity_total,
+                &normalized.quantity_total,
                 &normalized.available_start,
                 &normalized.available_end,
-                &normalized.status,
+                &status.as_str(),
                 &payload.pickup_location_text,
                 &normalized.pickup_address,
                 &normalized.effective_pickup_address,
@@ -352,16 +422,21 @@ pub async fn create_listing(
                 &normalized.geo_key,
                 &normalized.lat,
                 &normalized.lng,
+                &normalized.group_id,
+                &normalized.listing_kind.as_str(),
+                &normalized.return_by,
+                &normalized.publish_at,
+                &normalized.expire_at,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let (row, is_new_row) = if let Some(row) = inserted_row {
         (row, true)
     } else {
         let existing_row = client
-            .query_opt(
+            .query_opt_timed(
+                "listing::create_listing",
                 "
                 select id, user_id, crop_id, variety_id, title,
                        quantity_total::text as quantity_total,
@@ -370,7 +445,9 @@ pub async fn create_listing(
                        pickup_location_text, pickup_address, effective_pickup_address,
                        pickup_disclosure_policy::text as pickup_disclosure_policy,
                        pickup_notes, contact_pref::text as contact_pref,
-                       geo_key, lat, lng, created_at
+                       geo_key, lat, lng, group_id, created_at,
+                       listing_kind::text as listing_kind, return_by,
+                       publish_at, expire_at
                 from surplus_listings
                 where id = $1
                   and user_id = $2
@@ -378,18 +455,33 @@ pub async fn create_listing(
                 ",
                 &[&listing_id, &user_id],
             )
-            .await
-            .map_err(|error| db_error(&error))?;
+            .await?;
 
         let Some(existing_row) = existing_row else {
-            return error_response(409, "Idempotency key collision with an existing listing");
+            return Err(ApiError::conflict(
+                "idempotency_key_conflict",
+                "Idempotency key collision with an existing listing",
+            ));
         };
 
         (existing_row, false)
     };
 
+    if let Some(harvest_id) = harvest_id {
+        harvest::link_listing(&client, harvest_id, user_id, listing_id).await?;
+    }
+
     if is_new_row {
-        emit_listing_event_best_effort("listing.created", &row, correlation_id).await;
+        emit_listing_event_best_effort(events::LISTING_CREATED, &row, correlation_id).await;
+        record_disclosure_override(
+            &client,
+            auth_context,
+            listing_id,
+            None,
+            &normalized.pickup_disclosure_policy,
+            correlation_id,
+        )
+        .await;
     }
 
     info!(
@@ -403,32 +495,46 @@ pub async fn create_listing(
     json_response(201, &row_to_write_response(&row))
 }
 
+#[allow(clippy::too_many_lines)]
 pub async fn update_listing(
     request: &Request,
     correlation_id: &str,
     listing_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let auth_context = extract_auth_context_with_fallback(request).await?;
-    require_grower(&auth_context)?;
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let id = parse_uuid(listing_id, "listingId")?;
 
     let payload: UpsertListingRequest = parse_json_body(request)?;
 
     let client = db::connect().await?;
-    validate_catalog_links(
-        &client,
-        parse_uuid(&payload.crop_id, "crop_id")?,
-        parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?,
-    )
-    .await?;
+    // Co-managers edit on the owner's behalf: the owner's default address,
+    // groups and ownership apply as if the owner made the change.
+    let managed = co_manager::resolve_managed_listing(&client, id, user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))?;
+    let owner_id = managed.owner_id;
+
+    if let Some(crop_id) = parse_optional_uuid(payload.crop_id.as_deref(), "crop_id")? {
+        validate_catalog_links(
+            &client,
+            crop_id,
+            parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?,
+        )
+        .await?;
+    }
 
     let effective_pickup_address =
-        resolve_effective_pickup_address(&client, user_id, payload.pickup_address.as_deref())
+        resolve_effective_pickup_address(&client, owner_id, payload.pickup_address.as_deref())
             .await?;
-    let geocoded = location::geocode_address(&effective_pickup_address, correlation_id).await?;
+    let geocoded = geocode_pickup_address(
+        &effective_pickup_address,
+        payload.pickup_address.as_deref(),
+        correlation_id,
+    )
+    .await?;
 
     let normalized = normalize_payload(
         &payload,
@@ -439,19 +545,62 @@ pub async fn update_listing(
             lng: geocoded.lng,
         },
     )?;
+    if let Some(group_id) = normalized.group_id {
+        validate_group_attribution(&client, group_id, owner_id).await?;
+    }
+
+    let previous = client
+        .query_opt_timed(
+            "listing::update_listing",
+            "
+            select pickup_disclosure_policy::text as pickup_disclosure_policy,
+                   status::text as status
+            from surplus_listings
+            where id = $1
+              and user_id = $2
+              and deleted_at is null
+            ",
+            &[&id, &owner_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))?;
+    let previous_disclosure_policy: Option<String> = previous.get("pickup_disclosure_policy");
+
+    // A changed status follows the same rules as
+    // `POST /listings/{listingId}/transition`.
+    let now = Utc::now();
+    let previous_status = ListingStatus::parse(&previous.get::<_, String>("status"))?;
+    let requested_status = payload
+        .status
+        .as_ref()
+        .map(|_| ListingStatus::parse(&normalized.status))
+        .transpose()?;
+    let status = updated_status(
+        previous_status,
+        requested_status,
+        normalized.publish_at,
+        now,
+    )?;
+    listing_transition::evaluate_transition(
+        previous_status,
+        status,
+        ListingActorRole::from_delegated(managed.delegated),
+        listing_transition::window_open(Some(normalized.available_end), normalized.expire_at, now),
+    )?;
 
     let maybe_row = client
-        .query_opt(
+        .query_opt_timed(
+            "listing::update_listing",
             UPDATE_LISTING_SQL,
             &[
                 &normalized.crop_id,
                 &normalized.variety_id,
                 &payload.title,
                 &payload.unit,
-                &payload.quantity_total,
+                &normalized.quantity_total,
                 &normalized.available_start,
                 &normalized.available_end,
-                &normalized.status,
+                &status.as_str(),
                 &payload.pickup_location_text,
                 &normalized.pickup_address,
                 &normalized.effective_pickup_address,
@@ -462,18 +611,43 @@ pub async fn update_listing(
                 &normalized.lat,
                 &normalized.lng,
                 &id,
-                &user_id,
+                &owner_id,
+                &normalized.group_id,
+                &normalized.listing_kind.as_str(),
+                &normalized.return_by,
+                &normalized.publish_at,
+                &normalized.expire_at,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if let Some(row) = maybe_row {
-        emit_listing_event_best_effort("listing.updated", &row, correlation_id).await;
+        emit_listing_event_best_effort(events::LISTING_UPDATED, &row, correlation_id).await;
+        if status != previous_status {
+            listing_transition::emit_status_changed_best_effort(
+                &row,
+                previous_status,
+                correlation_id,
+            )
+            .await;
+        }
+        record_disclosure_override(
+            &client,
+            &auth_context,
+            id,
+            previous_disclosure_policy.as_deref(),
+            &normalized.pickup_disclosure_policy,
+            correlation_id,
+        )
+        .await;
+        if managed.delegated {
+            record_co_manager_update(&client, &auth_context, &row, correlation_id).await;
+        }
 
         info!(
             correlation_id = correlation_id,
             user_id = %user_id,
+            owner_id = %owner_id,
             listing_id = %id,
             "Updated surplus listing"
         );
@@ -481,46 +655,184 @@ pub async fn update_listing(
         return json_response(200, &row_to_write_response(&row));
     }
 
-    error_response(404, "Listing not found")
+    Err(ApiError::not_found(
+        "listing_not_found",
+        "Listing not found",
+    ))
+}
+
+/// Records an edit a co-manager made to someone else's listing, so the owner
+/// can see who changed what.
+async fn record_co_manager_update(
+    client: &Client,
+    auth_context: &AuthContext,
+    row: &Row,
+    correlation_id: &str,
+) {
+    let response = row_to_write_response(row);
+    audit::record_best_effort(
+        client,
+        &AuditEntry {
+            actor: Actor::from_auth(auth_context),
+            action: audit::LISTING_UPDATED_BY_CO_MANAGER,
+            target_type: "listing",
+            target_id: response.id.clone(),
+            before: None,
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await;
+}
+
+/// Audits listings that move off the default pickup disclosure policy, or
+/// change policy once off it. Listings on the default are not recorded.
+async fn record_disclosure_override(
+    client: &Client,
+    auth_context: &AuthContext,
+    listing_id: Uuid,
+    previous_policy: Option<&str>,
+    policy: &str,
+    correlation_id: &str,
+) {
+    let previous = previous_policy.unwrap_or(DEFAULT_PICKUP_DISCLOSURE_POLICY);
+    if previous == policy {
+        return;
+    }
+
+    audit::record_best_effort(
+        client,
+        &AuditEntry {
+            actor: Actor::from_auth(auth_context),
+            action: audit::LISTING_DISCLOSURE_OVERRIDDEN,
+            target_type: "listing",
+            target_id: listing_id.to_string(),
+            before: previous_policy
+                .map(|value| serde_json::json!({ "pickupDisclosurePolicy": value })),
+            after: Some(serde_json::json!({ "pickupDisclosurePolicy": policy })),
+            correlation_id,
+        },
+    )
+    .await;
+}
+
+/// New listings with a future `publishAt` start scheduled; the rest start
+/// active or pending. An `expireAt` that has already passed is refused.
+fn initial_status(
+    normalized: &NormalizedListingInput,
+    now: DateTime<Utc>,
+) -> Result<ListingStatus, ApiError> {
+    if normalized
+        .expire_at
+        .is_some_and(|expire_at| expire_at <= now)
+    {
+        return Err(ApiError::invalid_field(
+            "expireAt",
+            "expire_at_past",
+            "expireAt must be in the future",
+        ));
+    }
+    if normalized
+        .publish_at
+        .is_some_and(|publish_at| publish_at > now)
+    {
+        return Ok(ListingStatus::Scheduled);
+    }
+    ensure_initial_status(&normalized.status)?;
+    ListingStatus::parse(&normalized.status)
+}
+
+/// The status an update leaves a listing in. An omitted status keeps the
+/// current one, except that a scheduled listing whose `publishAt` is cleared
+/// or no longer in the future is published now. Only a scheduled listing can
+/// be given a future `publishAt`.
+fn updated_status(
+    previous: ListingStatus,
+    requested: Option<ListingStatus>,
+    publish_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> Result<ListingStatus, ApiError> {
+    let publish_later = publish_at.is_some_and(|publish_at| publish_at > now);
+    if publish_later && previous != ListingStatus::Scheduled {
+        return Err(ApiError::invalid_field(
+            "publishAt",
+            "already_published",
+            "Only a scheduled listing can be given a future publishAt",
+        ));
+    }
+    Ok(match (requested, previous) {
+        (Some(requested), _) => requested,
+        (None, ListingStatus::Scheduled) if !publish_later => ListingStatus::Active,
+        (None, previous) => previous,
+    })
+}
+
+/// New listings start active or pending; the other statuses are only reached
+/// through transitions.
+fn ensure_initial_status(status: &str) -> Result<(), ApiError> {
+    if matches!(status, "active" | "pending") {
+        return Ok(());
+    }
+    Err(ApiError::invalid_field(
+        "status",
+        "invalid_initial_status",
+        format!("New listings must be active or pending, not '{status}'"),
+    ))
 }
 
+#[allow(clippy::too_many_lines)]
 fn normalize_payload(
     payload: &UpsertListingRequest,
     resolved_location: ResolvedLocationInput,
-) -> Result<NormalizedListingInput, lambda_http::Error> {
+) -> Result<NormalizedListingInput, ApiError> {
+    let mut errors = ValidationErrors::new();
+
     if payload.title.trim().is_empty() {
-        return Err(lambda_http::Error::from("title is required"));
+        errors.add("title", "required", "title is required");
     }
 
     if payload.unit.trim().is_empty() {
-        return Err(lambda_http::Error::from("unit is required"));
+        errors.add("unit", "required", "unit is required");
     }
 
-    if payload.quantity_total <= 0.0 {
-        return Err(lambda_http::Error::from(
+    let quantity_total =
+        errors.capture(quantity::validate(payload.quantity_total, "quantityTotal"));
+    if quantity_total.is_some_and(|value| value <= Decimal::ZERO) {
+        errors.add(
+            "quantityTotal",
+            "invalid_quantity",
             "quantityTotal must be greater than 0",
-        ));
+        );
     }
 
-    let available_start = parse_datetime(&payload.available_start, "availableStart")?;
-    let available_end = parse_datetime(&payload.available_end, "availableEnd")?;
-
-    if available_start > available_end {
-        return Err(lambda_http::Error::from(
-            "availableStart must be earlier than or equal to availableEnd",
-        ));
+    let available_start =
+        errors.capture(parse_datetime(&payload.available_start, "availableStart"));
+    let available_end = errors.capture(parse_datetime(&payload.available_end, "availableEnd"));
+
+    if let (Some(start), Some(end)) = (available_start, available_end) {
+        if start > end {
+            errors.add(
+                "availableStart",
+                "invalid_window",
+                "availableStart must be earlier than or equal to availableEnd",
+            );
+        }
     }
 
     let pickup_disclosure_policy = payload
         .pickup_disclosure_policy
         .clone()
-        .unwrap_or_else(|| "after_confirmed".to_string());
+        .unwrap_or_else(|| DEFAULT_PICKUP_DISCLOSURE_POLICY.to_string());
     if !ALLOWED_PICKUP_DISCLOSURE_POLICY.contains(&pickup_disclosure_policy.as_str()) {
-        return Err(lambda_http::Error::from(format!(
-            "Invalid pickupDisclosurePolicy '{}'. Allowed values: {}",
-            pickup_disclosure_policy,
-            ALLOWED_PICKUP_DISCLOSURE_POLICY.join(", ")
-        )));
+        errors.add(
+            "pickupDisclosurePolicy",
+            "invalid_enum",
+            format!(
+                "Invalid pickupDisclosurePolicy '{}'. Allowed values: {}",
+                pickup_disclosure_policy,
+                ALLOWED_PICKUP_DISCLOSURE_POLICY.join(", ")
+            ),
+        );
     }
 
     let contact_pref = payload
@@ -528,11 +840,15 @@ fn normalize_payload(
         .clone()
         .unwrap_or_else(|| "app_message".to_string());
     if !ALLOWED_CONTACT_PREF.contains(&contact_pref.as_str()) {
-        return Err(lambda_http::Error::from(format!(
-            "Invalid contactPref '{}'. Allowed values: {}",
-            contact_pref,
-            ALLOWED_CONTACT_PREF.join(", ")
-        )));
+        errors.add(
+            "contactPref",
+            "invalid_enum",
+            format!(
+                "Invalid contactPref '{}'. Allowed values: {}",
+                contact_pref,
+                ALLOWED_CONTACT_PREF.join(", ")
+            ),
+        );
     }
 
     let status = payload
@@ -540,19 +856,116 @@ fn normalize_payload(
         .clone()
         .unwrap_or_else(|| "active".to_string());
     if !ALLOWED_LISTING_STATUS.contains(&status.as_str()) {
-        return Err(lambda_http::Error::from(format!(
-            "Invalid status '{}'. Allowed values: {}",
-            status,
-            ALLOWED_LISTING_STATUS.join(", ")
-        )));
+        errors.add(
+            "status",
+            "invalid_enum",
+            format!(
+                "Invalid status '{}'. Allowed values: {}",
+                status,
+                ALLOWED_LISTING_STATUS.join(", ")
+            ),
+        );
     }
 
-    let crop_id = parse_uuid(&payload.crop_id, "crop_id")?;
-    let variety_id = parse_optional_uuid(payload.variety_id.as_deref(), "variety_id")?;
+    let listing_kind = errors.capture(
+        payload
+            .listing_kind
+            .as_deref()
+            .map_or(Ok(ListingKind::Produce), |value| {
+                ListingKind::parse(value).ok_or_else(|| invalid_kind("listingKind", value))
+            }),
+    );
+    let crop_id = errors.capture(parse_optional_uuid(payload.crop_id.as_deref(), "crop_id"));
+    let variety_id = errors.capture(parse_optional_uuid(
+        payload.variety_id.as_deref(),
+        "variety_id",
+    ));
+    let group_id = errors.capture(parse_optional_uuid(payload.group_id.as_deref(), "groupId"));
+    let return_by = errors.capture(
+        payload
+            .return_by
+            .as_deref()
+            .map(|value| parse_datetime(value, "returnBy"))
+            .transpose(),
+    );
+    if let Some(kind) = listing_kind {
+        validate_kind_fields(&mut errors, kind, payload);
+    }
+    let publish_at = errors.capture(
+        payload
+            .publish_at
+            .as_deref()
+            .map(|value| parse_datetime(value, "publishAt"))
+            .transpose(),
+    );
+    let expire_at = errors.capture(
+        payload
+            .expire_at
+            .as_deref()
+            .map(|value| parse_datetime(value, "expireAt"))
+            .transpose(),
+    );
+    if payload.publish_at.is_some() && payload.status.is_some() {
+        errors.add(
+            "status",
+            "status_with_publish_at",
+            "Omit status when setting publishAt; the listing goes active when it publishes",
+        );
+    }
+    if let (Some(Some(publish_at)), Some(Some(expire_at))) = (publish_at, expire_at) {
+        if expire_at <= publish_at {
+            errors.add(
+                "expireAt",
+                "invalid_schedule",
+                "expireAt must be later than publishAt",
+            );
+        }
+    }
+    if let (Some(Some(return_by)), Some(end)) = (return_by, available_end) {
+        if return_by <= end {
+            errors.add(
+                "returnBy",
+                "invalid_window",
+                "returnBy must be later than availableEnd",
+            );
+        }
+    }
+
+    errors.into_result()?;
+    let (
+        Some(listing_kind),
+        Some(crop_id),
+        Some(variety_id),
+        Some(quantity_total),
+        Some(available_start),
+        Some(available_end),
+        Some(group_id),
+        Some(return_by),
+        Some(publish_at),
+        Some(expire_at),
+    ) = (
+        listing_kind,
+        crop_id,
+        variety_id,
+        quantity_total,
+        available_start,
+        available_end,
+        group_id,
+        return_by,
+        publish_at,
+        expire_at,
+    )
+    else {
+        return Err(ApiError::internal(
+            "listing validation passed with missing fields",
+        ));
+    };
 
     Ok(NormalizedListingInput {
+        listing_kind,
         crop_id,
         variety_id,
+        quantity_total,
         available_start,
         available_end,
         pickup_address: location::normalize_optional_address(payload.pickup_address.as_deref()),
@@ -563,40 +976,118 @@ fn normalize_payload(
         geo_key: resolved_location.geo_key,
         lat: resolved_location.lat,
         lng: resolved_location.lng,
+        group_id,
+        return_by,
+        publish_at,
+        expire_at,
     })
 }
 
+/// Runs the field checks of a create without resolving a location, for
+/// payloads that are stored now and turned into listings later.
+pub fn validate_payload(payload: &UpsertListingRequest) -> Result<(), ApiError> {
+    normalize_payload(
+        payload,
+        ResolvedLocationInput {
+            effective_pickup_address: String::new(),
+            geo_key: String::new(),
+            lat: 0.0,
+            lng: 0.0,
+        },
+    )
+    .map(|_| ())
+}
+
+/// Crop kinds need a catalog crop and other kinds must not send one; only
+/// tool loans carry a return date.
+fn validate_kind_fields(
+    errors: &mut ValidationErrors,
+    kind: ListingKind,
+    payload: &UpsertListingRequest,
+) {
+    if kind.has_crop() && payload.crop_id.is_none() {
+        errors.add(
+            "crop_id",
+            "required",
+            format!("crop_id is required for {} listings", kind.as_str()),
+        );
+    }
+    if !kind.has_crop() && (payload.crop_id.is_some() || payload.variety_id.is_some()) {
+        errors.add(
+            "crop_id",
+            "not_applicable",
+            format!("{} listings do not take a crop or variety", kind.as_str()),
+        );
+    }
+    match (kind.has_return_by(), payload.return_by.is_some()) {
+        (true, false) => errors.add(
+            "returnBy",
+            "required",
+            format!("returnBy is required for {} listings", kind.as_str()),
+        ),
+        (false, true) => errors.add(
+            "returnBy",
+            "not_applicable",
+            format!(
+                "returnBy only applies to {} listings",
+                ListingKind::ToolsLoan.as_str()
+            ),
+        ),
+        _ => {}
+    }
+}
+
+/// An address sent with the listing must pin a street location. The grower
+/// profile fallback is geocoded as stored; it was held to the same standard
+/// when the profile was saved.
+async fn geocode_pickup_address(
+    effective_pickup_address: &str,
+    pickup_address: Option<&str>,
+    correlation_id: &str,
+) -> Result<location::GeocodedPoint, ApiError> {
+    let geocoded = if location::normalize_optional_address(pickup_address).is_some() {
+        location::geocode_street_address(effective_pickup_address, "pickupAddress", correlation_id)
+            .await?
+    } else {
+        location::geocode_address(effective_pickup_address, correlation_id).await?
+    };
+    Ok(geocoded)
+}
+
 async fn resolve_effective_pickup_address(
     client: &Client,
     user_id: Uuid,
     pickup_address: Option<&str>,
-) -> Result<String, lambda_http::Error> {
+) -> Result<String, ApiError> {
     if let Some(override_address) = location::normalize_optional_address(pickup_address) {
         return Ok(override_address);
     }
 
     let grower_address = client
-        .query_opt(
+        .query_opt_timed(
+            "listing::resolve_effective_pickup_address",
             "select address from grower_profiles where user_id = $1",
             &[&user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?
+        .await?
         .and_then(|row| row.get::<_, Option<String>>("address"));
 
     location::normalize_optional_address(grower_address.as_deref()).ok_or_else(|| {
-        lambda_http::Error::from(
-            "pickupAddress is required because grower profile address is missing".to_string(),
+        ApiError::invalid_field(
+            "pickupAddress",
+            "required",
+            "pickupAddress is required because grower profile address is missing",
         )
     })
 }
 
-fn parse_list_my_listings_query(
-    query: Option<&str>,
-) -> Result<ListMyListingsQuery, lambda_http::Error> {
+fn parse_list_my_listings_query(query: Option<&str>) -> Result<ListMyListingsQuery, ApiError> {
     let mut status: Option<String> = None;
+    let mut owner_id: Option<Uuid> = None;
     let mut limit: i64 = 20;
     let mut offset: i64 = 0;
+    let mut fields: Option<&str> = None;
+    let mut view: Option<&str> = None;
 
     if let Some(raw_query) = query {
         for pair in raw_query.split('&') {
@@ -607,38 +1098,55 @@ fn parse_list_my_listings_query(
             let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
 
             match key {
-                "status" => {
-                    if !value.is_empty() {
-                        if !ALLOWED_LISTING_READ_STATUS.contains(&value) {
-                            return Err(lambda_http::Error::from(format!(
+                "status" if !value.is_empty() => {
+                    if !ALLOWED_LISTING_READ_STATUS.contains(&value) {
+                        return Err(ApiError::invalid_field(
+                            "status",
+                            "invalid_enum",
+                            format!(
                                 "Invalid listing status '{}'. Allowed values: {}",
                                 value,
                                 ALLOWED_LISTING_READ_STATUS.join(", ")
-                            )));
-                        }
-                        status = Some(value.to_string());
+                            ),
+                        ));
                     }
+                    status = Some(value.to_string());
                 }
                 "limit" => {
                     limit = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid limit. Must be an integer")
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be an integer",
+                        )
                     })?;
                     if !(1..=100).contains(&limit) {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
                             "Invalid limit. Must be between 1 and 100",
                         ));
                     }
                 }
                 "offset" => {
                     offset = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid offset. Must be an integer")
+                        ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be an integer",
+                        )
                     })?;
                     if offset < 0 {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
                             "Invalid offset. Must be greater than or equal to 0",
                         ));
                     }
                 }
+                "ownerId" if !value.is_empty() => owner_id = Some(parse_uuid(value, "ownerId")?),
+                "fields" => fields = Some(value),
+                "view" => view = Some(value),
                 _ => {}
             }
         }
@@ -646,8 +1154,10 @@ fn parse_list_my_listings_query(
 
     Ok(ListMyListingsQuery {
         status,
+        owner_id,
         limit,
         offset,
+        projection: ListingProjection::parse(fields, view)?,
     })
 }
 
@@ -655,35 +1165,39 @@ async fn validate_catalog_links(
     client: &Client,
     crop_id: Uuid,
     variety_id: Option<Uuid>,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let crop_exists = client
-        .query_one(
+        .query_one_timed(
+            "listing::validate_catalog_links",
             "select exists(select 1 from crops where id = $1)",
             &[&crop_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?
+        .await?
         .get::<_, bool>(0);
 
     if !crop_exists {
-        return Err(lambda_http::Error::from(
-            "crop_id does not reference an existing catalog crop".to_string(),
+        return Err(ApiError::invalid_field(
+            "crop_id",
+            "unknown_crop",
+            "crop_id does not reference an existing catalog crop",
         ));
     }
 
     if let Some(variety) = variety_id {
         let matches = client
-            .query_one(
+            .query_one_timed(
+                "listing::validate_catalog_links",
                 "select exists(select 1 from crop_varieties where id = $1 and crop_id = $2)",
                 &[&variety, &crop_id],
             )
-            .await
-            .map_err(|error| db_error(&error))?
+            .await?
             .get::<_, bool>(0);
 
         if !matches {
-            return Err(lambda_http::Error::from(
-                "variety_id must belong to the specified crop_id".to_string(),
+            return Err(ApiError::invalid_field(
+                "variety_id",
+                "variety_crop_mismatch",
+                "variety_id must belong to the specified crop_id",
             ));
         }
     }
@@ -691,45 +1205,42 @@ async fn validate_catalog_links(
     Ok(())
 }
 
+/// Members of any role may post under their group; the listing stays owned by
+/// the member who posted it.
+async fn validate_group_attribution(
+    client: &Client,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<(), ApiError> {
+    if repo::group::member_role(client, group_id, user_id)
+        .await?
+        .is_none()
+    {
+        return Err(ApiError::forbidden(
+            "not_group_member",
+            "Forbidden: You must be a member of the group to post listings under it",
+        ));
+    }
+    Ok(())
+}
+
 async fn emit_listing_event(
     detail_type: &str,
     listing_row: &Row,
     correlation_id: &str,
 ) -> Result<(), lambda_http::Error> {
-    let event_bus_name = std::env::var("EVENT_BUS_NAME").unwrap_or_else(|_| "default".to_string());
-
-    let detail = serde_json::json!({
-        "listingId": listing_row.get::<_, Uuid>("id").to_string(),
-        "userId": listing_row.get::<_, Uuid>("user_id").to_string(),
-        "status": listing_row.get::<_, String>("status"),
-        "correlationId": correlation_id,
-        "occurredAt": Utc::now().to_rfc3339(),
-    });
-
-    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
-    let client = aws_sdk_eventbridge::Client::new(&config);
-
-    let entry = PutEventsRequestEntry::builder()
-        .event_bus_name(event_bus_name)
-        .source("community-garden.api")
-        .detail_type(detail_type)
-        .detail(detail.to_string())
-        .build();
-
-    let response = client
-        .put_events()
-        .entries(entry)
-        .send()
-        .await
-        .map_err(|e| lambda_http::Error::from(format!("Failed to emit listing event: {e}")))?;
-
-    if response.failed_entry_count() > 0 {
-        return Err(lambda_http::Error::from(
-            "Failed to emit listing event: one or more entries were rejected",
-        ));
-    }
+    let detail = ListingEventDetail::new(
+        listing_row.get::<_, Uuid>("id").to_string(),
+        listing_row.get::<_, Uuid>("user_id").to_string(),
+        listing_row
+            .get::<_, Option<Uuid>>("crop_id")
+            .map(|id| id.to_string()),
+        listing_row.get::<_, String>("status"),
+        listing_row.get::<_, Option<String>>("geo_key"),
+        correlation_id,
+    );
 
-    Ok(())
+    events::publish(detail_type, &detail).await
 }
 
 async fn emit_listing_event_best_effort(
@@ -748,45 +1259,28 @@ async fn emit_listing_event_best_effort(
     }
 }
 
-fn parse_datetime(value: &str, field_name: &str) -> Result<DateTime<Utc>, lambda_http::Error> {
+fn parse_datetime(value: &str, field_name: &str) -> Result<DateTime<Utc>, ApiError> {
     let parsed = DateTime::parse_from_rfc3339(value).map_err(|_| {
-        lambda_http::Error::from(format!("{field_name} must be a valid RFC3339 timestamp"))
+        ApiError::invalid_field(
+            field_name,
+            "invalid_timestamp",
+            format!("{field_name} must be a valid RFC3339 timestamp"),
+        )
     })?;
     Ok(parsed.with_timezone(&Utc))
 }
 
-fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, lambda_http::Error> {
-    let normalized = value.trim();
-    Uuid::parse_str(normalized)
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid UUID")))
-}
-
-fn parse_optional_uuid(
-    value: Option<&str>,
-    field_name: &str,
-) -> Result<Option<Uuid>, lambda_http::Error> {
+fn parse_optional_uuid(value: Option<&str>, field_name: &str) -> Result<Option<Uuid>, ApiError> {
     value.map_or(Ok(None), |v| parse_uuid(v, field_name).map(Some))
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
 fn row_to_write_response(row: &Row) -> ListingWriteResponse {
+    let (lat, lng) = LocatedEntity::Listing.point(row, "lat", "lng");
     ListingWriteResponse {
         id: row.get::<_, Uuid>("id").to_string(),
         user_id: row.get::<_, Uuid>("user_id").to_string(),
-        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+        listing_kind: row.get("listing_kind"),
+        crop_id: row.get::<_, Option<Uuid>>("crop_id").map(|v| v.to_string()),
         variety_id: row
             .get::<_, Option<Uuid>>("variety_id")
             .map(|v| v.to_string()),
@@ -804,78 +1298,24 @@ fn row_to_write_response(row: &Row) -> ListingWriteResponse {
         pickup_notes: row.get("pickup_notes"),
         contact_pref: row.get("contact_pref"),
         geo_key: row.get("geo_key"),
-        lat: location::round_for_response(row.get("lat")),
-        lng: location::round_for_response(row.get("lng")),
-        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
-    }
-}
-
-fn row_to_listing_item(row: &Row) -> ListingItem {
-    ListingItem {
-        id: row.get::<_, Uuid>("id").to_string(),
-        user_id: row.get::<_, Uuid>("user_id").to_string(),
-        grower_crop_id: row
-            .get::<_, Option<Uuid>>("grower_crop_id")
-            .map(|id| id.to_string()),
-        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
-        variety_id: row
-            .get::<_, Option<Uuid>>("variety_id")
+        lat,
+        lng,
+        group_id: row
+            .get::<_, Option<Uuid>>("group_id")
             .map(|id| id.to_string()),
-        title: row.get("title"),
-        unit: row.get("unit"),
-        quantity_total: row.get("quantity_total"),
-        quantity_remaining: row.get("quantity_remaining"),
-        available_start: row
-            .get::<_, Option<DateTime<Utc>>>("available_start")
-            .map(|v| v.to_rfc3339()),
-        available_end: row
-            .get::<_, Option<DateTime<Utc>>>("available_end")
-            .map(|v| v.to_rfc3339()),
-        status: row.get("status"),
-        pickup_location_text: row.get("pickup_location_text"),
-        pickup_address: row.get("pickup_address"),
-        effective_pickup_address: row.get("effective_pickup_address"),
-        pickup_disclosure_policy: row.get("pickup_disclosure_policy"),
-        pickup_notes: row.get("pickup_notes"),
-        contact_pref: row.get("contact_pref"),
-        geo_key: row.get("geo_key"),
-        lat: row
-            .get::<_, Option<f64>>("lat")
-            .map(location::round_for_response),
-        lng: row
-            .get::<_, Option<f64>>("lng")
-            .map(location::round_for_response),
+        return_by: row
+            .get::<_, Option<DateTime<Utc>>>("return_by")
+            .map(|value| value.to_rfc3339()),
+        publish_at: row
+            .get::<_, Option<DateTime<Utc>>>("publish_at")
+            .map(|value| value.to_rfc3339()),
+        expire_at: row
+            .get::<_, Option<DateTime<Utc>>>("expire_at")
+            .map(|value| value.to_rfc3339()),
         created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
     }
 }
 
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-pub fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(
-        status,
-        &ErrorResponse {
-            error: message.to_string(),
-        },
-    )
-}
-
 fn extract_idempotency_key(request: &Request) -> Option<String> {
     request
         .headers()
@@ -908,9 +1348,10 @@ mod tests {
     fn valid_payload() -> UpsertListingRequest {
         UpsertListingRequest {
             title: "Fresh Tomatoes".to_string(),
-            crop_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
+            listing_kind: None,
+            crop_id: Some("5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string()),
             variety_id: None,
-            quantity_total: 12.5,
+            quantity_total: Decimal::new(125, 1),
             unit: "lb".to_string(),
             available_start: "2026-02-20T10:00:00Z".to_string(),
             available_end: "2026-02-20T18:00:00Z".to_string(),
@@ -920,6 +1361,11 @@ mod tests {
             pickup_notes: None,
             contact_pref: Some("app_message".to_string()),
             status: Some("active".to_string()),
+            group_id: None,
+            return_by: None,
+            publish_at: None,
+            expire_at: None,
+            harvest_id: None,
         }
     }
 
@@ -942,6 +1388,89 @@ mod tests {
         assert_eq!(normalized.geo_key, "9q8yyk8");
     }
 
+    #[test]
+    fn ensure_initial_status_allows_only_active_or_pending() {
+        assert!(ensure_initial_status("active").is_ok());
+        assert!(ensure_initial_status("pending").is_ok());
+        let error = ensure_initial_status("completed").unwrap_err();
+        assert_eq!(error.error_code(), "invalid_initial_status");
+    }
+
+    #[test]
+    fn normalize_payload_checks_the_schedule() {
+        let mut payload = valid_payload();
+        payload.publish_at = Some("2026-02-20T06:00:00Z".to_string());
+        payload.expire_at = Some("2026-02-20T05:00:00Z".to_string());
+
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        let codes: Vec<&str> = match &error {
+            ApiError::Validation { issues } => issues.iter().map(|issue| issue.code).collect(),
+            _ => Vec::new(),
+        };
+        assert_eq!(codes, vec!["status_with_publish_at", "invalid_schedule"]);
+
+        payload.status = None;
+        payload.expire_at = Some("2026-02-22T18:00:00Z".to_string());
+        let normalized = normalize_payload(&payload, resolved_location()).unwrap();
+        assert!(normalized.publish_at.is_some());
+        assert!(normalized.expire_at.is_some());
+    }
+
+    #[test]
+    fn initial_status_schedules_a_future_publish() {
+        let mut payload = valid_payload();
+        payload.status = None;
+        payload.publish_at = Some("2026-02-20T06:00:00Z".to_string());
+        let normalized = normalize_payload(&payload, resolved_location()).unwrap();
+
+        let before = DateTime::parse_from_rfc3339("2026-02-19T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let after = DateTime::parse_from_rfc3339("2026-02-20T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        assert_eq!(
+            initial_status(&normalized, before).unwrap(),
+            ListingStatus::Scheduled
+        );
+        assert_eq!(
+            initial_status(&normalized, after).unwrap(),
+            ListingStatus::Active
+        );
+
+        payload.expire_at = Some("2026-02-20T09:00:00Z".to_string());
+        let normalized = normalize_payload(&payload, resolved_location()).unwrap();
+        let error = initial_status(&normalized, after).unwrap_err();
+        assert_eq!(error.error_code(), "expire_at_past");
+    }
+
+    #[test]
+    fn updated_status_publishes_or_keeps_a_schedule() {
+        let now = Utc::now();
+        let later = Some(now + chrono::Duration::hours(6));
+        let earlier = Some(now - chrono::Duration::hours(6));
+        let scheduled = ListingStatus::Scheduled;
+
+        assert_eq!(
+            updated_status(scheduled, None, later, now).unwrap(),
+            scheduled
+        );
+        assert_eq!(
+            updated_status(scheduled, None, earlier, now).unwrap(),
+            ListingStatus::Active
+        );
+        assert_eq!(
+            updated_status(scheduled, None, None, now).unwrap(),
+            ListingStatus::Active
+        );
+        assert_eq!(
+            updated_status(ListingStatus::Pending, None, earlier, now).unwrap(),
+            ListingStatus::Pending
+        );
+        let error = updated_status(ListingStatus::Active, None, later, now).unwrap_err();
+        assert_eq!(error.error_code(), "already_published");
+    }
+
     #[test]
     fn normalize_payload_rejects_invalid_window() {
         let mut payload = valid_payload();
@@ -976,6 +1505,47 @@ mod tests {
             .contains("Invalid contactPref"));
     }
 
+    #[test]
+    fn normalize_payload_reports_every_invalid_field() {
+        let mut payload = valid_payload();
+        payload.title = " ".to_string();
+        payload.quantity_total = Decimal::ZERO;
+        payload.contact_pref = Some("carrier_pigeon".to_string());
+
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert_eq!(error.error_code(), "validation_failed");
+
+        let fields: Vec<&str> = match &error {
+            ApiError::Validation { issues } => {
+                issues.iter().map(|issue| issue.field.as_str()).collect()
+            }
+            _ => Vec::new(),
+        };
+        assert_eq!(fields, vec!["title", "quantityTotal", "contactPref"]);
+    }
+
+    #[test]
+    fn normalize_payload_parses_optional_group_id() {
+        let mut payload = valid_payload();
+        assert_eq!(
+            normalize_payload(&payload, resolved_location())
+                .unwrap()
+                .group_id,
+            None
+        );
+
+        payload.group_id = Some("0e7ab2f8-9d1b-46b0-9c53-b6053bc90011".to_string());
+        let normalized = normalize_payload(&payload, resolved_location()).unwrap();
+        assert_eq!(
+            normalized.group_id,
+            Some(Uuid::parse_str("0e7ab2f8-9d1b-46b0-9c53-b6053bc90011").unwrap())
+        );
+
+        payload.group_id = Some("not-a-uuid".to_string());
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_uuid");
+    }
+
     #[test]
     fn normalize_payload_normalizes_pickup_address() {
         let payload = valid_payload();
@@ -983,11 +1553,81 @@ mod tests {
         assert_eq!(normalized.pickup_address.as_deref(), Some("123 Main St"));
     }
 
+    #[test]
+    fn normalize_payload_rejects_excess_precision() {
+        let mut payload = valid_payload();
+        payload.quantity_total = Decimal::new(12_345, 4);
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert_eq!(error.error_code(), "too_many_decimal_places");
+    }
+
+    #[test]
+    fn normalize_payload_defaults_to_produce_and_requires_a_crop() {
+        let normalized = normalize_payload(&valid_payload(), resolved_location()).unwrap();
+        assert_eq!(normalized.listing_kind, ListingKind::Produce);
+
+        let mut payload = valid_payload();
+        payload.listing_kind = Some("seedlings".to_string());
+        payload.crop_id = None;
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+        assert!(error.to_string().contains("crop_id"));
+    }
+
+    #[test]
+    fn normalize_payload_applies_tool_loan_rules() {
+        let mut payload = valid_payload();
+        payload.listing_kind = Some("tools_loan".to_string());
+        payload.crop_id = None;
+        payload.unit = "each".to_string();
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+        assert!(error.to_string().contains("returnBy"));
+
+        payload.return_by = Some("2026-02-20T12:00:00Z".to_string());
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_window");
+
+        payload.return_by = Some("2026-03-01T18:00:00Z".to_string());
+        let normalized = normalize_payload(&payload, resolved_location()).unwrap();
+        assert_eq!(normalized.listing_kind, ListingKind::ToolsLoan);
+        assert_eq!(normalized.crop_id, None);
+        assert!(normalized.return_by.is_some());
+    }
+
+    #[test]
+    fn normalize_payload_rejects_fields_that_do_not_fit_the_kind() {
+        let mut payload = valid_payload();
+        payload.listing_kind = Some("compost".to_string());
+        payload.return_by = Some("2026-03-01T18:00:00Z".to_string());
+
+        let fields: Vec<(String, &str)> =
+            match normalize_payload(&payload, resolved_location()).unwrap_err() {
+                ApiError::Validation { issues } => issues
+                    .into_iter()
+                    .map(|issue| (issue.field, issue.code))
+                    .collect(),
+                _ => Vec::new(),
+            };
+        assert_eq!(
+            fields,
+            vec![
+                ("crop_id".to_string(), "not_applicable"),
+                ("returnBy".to_string(), "not_applicable"),
+            ]
+        );
+
+        payload.listing_kind = Some("firewood".to_string());
+        let error = normalize_payload(&payload, resolved_location()).unwrap_err();
+        assert!(error.to_string().contains("listingKind"));
+    }
+
     #[test]
     fn update_listing_sql_preserves_existing_remaining_inventory() {
         assert!(UPDATE_LISTING_SQL.contains("quantity_remaining = least("));
-        assert!(UPDATE_LISTING_SQL.contains("coalesce(quantity_remaining, $5::double precision)"));
+        assert!(UPDATE_LISTING_SQL.contains("coalesce(quantity_remaining, $5::numeric)"));
         assert!(!UPDATE_LISTING_SQL.contains("quantity_remaining = $5,"));
+        assert!(UPDATE_LISTING_SQL.contains("group_id = $20"));
     }
 
     #[test]
@@ -1007,6 +1647,31 @@ mod tests {
         assert_eq!(parsed.offset, 20);
     }
 
+    #[test]
+    fn parse_list_my_listings_query_parses_owner_id() {
+        let parsed = parse_list_my_listings_query(Some(
+            "ownerId=b630af9b-6de5-44cd-9d83-d37df86ce2ef&status=active",
+        ))
+        .unwrap();
+        assert_eq!(
+            parsed.owner_id,
+            Some(Uuid::parse_str("b630af9b-6de5-44cd-9d83-d37df86ce2ef").unwrap())
+        );
+
+        let error = parse_list_my_listings_query(Some("ownerId=not-a-uuid")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_uuid");
+    }
+
+    #[test]
+    fn parse_list_my_listings_query_rejects_fields_with_view() {
+        let error = parse_list_my_listings_query(Some("fields=title&view=compact")).unwrap_err();
+        assert_eq!(error.error_code(), "conflicting_projection");
+        assert!(!parse_list_my_listings_query(Some("view=compact"))
+            .unwrap()
+            .projection
+            .is_full());
+    }
+
     #[test]
     fn deterministic_listing_id_is_stable_for_same_key() {
         let user_id = Uuid::parse_str("0e7ab2f8-9d1b-46b0-9c53-b6053bc90011").unwrap();
@@ -1,75 +1,269 @@
-use crate::auth::extract_auth_context;
+use crate::auth::{self, extract_auth_context};
 use crate::db;
+use crate::error::ApiError;
+use crate::geocoding;
+use crate::http_util::{json_response, parse_uuid, percent_decode, request_locale};
+use crate::listing_kind::ListingKind;
+use crate::listing_projection::ListingProjection;
 use crate::location;
-use crate::models::crop::ErrorResponse;
-use crate::models::listing::{DiscoverListingsResponse, ListingItem};
-use chrono::{DateTime, Utc};
+use crate::models::listing::{
+    AlongRouteListingsResponse, BatchListingsResponse, DiscoverListingsResponse,
+    GuestDiscoverListingsResponse, GuestListingItem, ListingCalendarDay, ListingCalendarResponse,
+    ListingItem, RouteListing,
+};
+use crate::repo;
+use crate::route_corridor::{self, PolylineError, RoutePoint};
+use chrono::{Datelike, Months, NaiveDate, Utc};
 use lambda_http::{Body, Request, Response};
-use serde::Serialize;
-use tokio_postgres::Row;
 use tracing::info;
 use uuid::Uuid;
 
 const ALLOWED_DISCOVER_STATUS: [&str; 1] = ["active"];
-const KM_PER_MILE: f64 = 1.609_344;
+const MAX_BATCH_IDS: usize = 50;
+const DEFAULT_BUFFER_MILES: f64 = 1.0;
+const MAX_BUFFER_MILES: f64 = 10.0;
+/// Listings read from the route's covering cells before the exact distance
+/// check. Newest win if a dense corridor has more.
+const MAX_ROUTE_CANDIDATES: i64 = 500;
+/// Geo keys shown to anonymous guests are cut to cells about 5 km across.
+const GUEST_GEO_KEY_PRECISION: usize = 5;
+
+#[derive(Debug)]
+struct BatchListingsQuery {
+    ids: Vec<Uuid>,
+    projection: ListingProjection,
+}
 
 #[derive(Debug)]
 struct DiscoverListingsQuery {
     geo_key: String,
     status: String,
+    kinds: Option<Vec<ListingKind>>,
     radius_km: Option<f64>,
     radius_miles: Option<f64>,
     limit: i64,
     offset: i64,
+    projection: ListingProjection,
+}
+
+#[derive(Debug)]
+struct ListingCalendarQuery {
+    geo_key: String,
+    kinds: Option<Vec<ListingKind>>,
+    /// First day of the requested month.
+    month_start: NaiveDate,
+}
+
+#[derive(Debug)]
+struct AlongRouteQuery {
+    route: Vec<RoutePoint>,
+    buffer_miles: f64,
+    kinds: Option<Vec<ListingKind>>,
+    limit: i64,
+    offset: i64,
 }
 
 pub async fn discover_listings(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
+    if auth::is_anonymous(request) {
+        return discover_listings_as_guest(request, correlation_id).await;
+    }
+
     let auth_context = extract_auth_context(request)?;
     let query = parse_discover_listings_query(request.uri().query())?;
+    let page = load_discover_page(&query, request_locale(request).as_deref()).await?;
+
+    let response = DiscoverListingsResponse {
+        geo_boundary_key: page.geo_prefix,
+        geo_label: page.geo_label,
+        items: page.items,
+        limit: query.limit,
+        offset: query.offset,
+        has_more: page.has_more,
+        next_offset: page.next_offset,
+    };
 
-    let geo_prefix = derive_geo_prefix(&query.geo_key, query.radius_km);
-    let geo_pattern = format!("{geo_prefix}%");
+    info!(
+        correlation_id = correlation_id,
+        user_id = auth_context.user_id.as_str(),
+        geo_key = query.geo_key,
+        geo_prefix = response.geo_boundary_key,
+        status_filter = query.status,
+        kind_filter = ?kind_names(query.kinds.as_deref()),
+        requested_radius_km = ?query.radius_km,
+        requested_radius_miles = ?query.radius_miles,
+        limit = query.limit,
+        offset = query.offset,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        sparse_fields = !query.projection.is_full(),
+        "Listed discoverable surplus listings"
+    );
+
+    json_response(200, &query.projection.apply(&response)?)
+}
+
+/// Discovery for community sites showing activity to visitors who have not
+/// signed up. Same query parameters, but `fields` is ignored and each item is
+/// reduced to what a listing says, never who posted it or where to pick up.
+async fn discover_listings_as_guest(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let query = parse_discover_listings_query(request.uri().query())?;
+    let page = load_discover_page(&query, request_locale(request).as_deref()).await?;
+
+    let response = GuestDiscoverListingsResponse {
+        geo_boundary_key: page.geo_prefix,
+        geo_label: page.geo_label,
+        items: page.items.into_iter().map(guest_listing_item).collect(),
+        limit: query.limit,
+        offset: query.offset,
+        has_more: page.has_more,
+        next_offset: page.next_offset,
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        geo_key = query.geo_key,
+        geo_prefix = response.geo_boundary_key,
+        kind_filter = ?kind_names(query.kinds.as_deref()),
+        limit = query.limit,
+        offset = query.offset,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        "Listed discoverable surplus listings for a guest"
+    );
+
+    json_response(200, &response)
+}
+
+struct DiscoverPage {
+    geo_prefix: String,
+    geo_label: Option<String>,
+    items: Vec<ListingItem>,
+    has_more: bool,
+    next_offset: Option<i64>,
+}
+
+async fn load_discover_page(
+    query: &DiscoverListingsQuery,
+    locale: Option<&str>,
+) -> Result<DiscoverPage, ApiError> {
+    let geo_prefix = location::geo_prefix_for_radius(&query.geo_key, query.radius_km);
     let fetch_limit = query.limit + 1;
 
     let client = db::connect().await?;
-    let rows = client
-        .query(
-            "
-            select id, user_id, grower_crop_id, crop_id, variety_id, title, unit,
-                   quantity_total::text as quantity_total,
-                   quantity_remaining::text as quantity_remaining,
-                   available_start, available_end, status::text,
-                   pickup_location_text, pickup_address, effective_pickup_address,
-                   pickup_disclosure_policy::text as pickup_disclosure_policy,
-                   pickup_notes, contact_pref::text as contact_pref,
-                   geo_key, lat, lng, created_at
-            from surplus_listings
-            where deleted_at is null
-              and status = $1::text::listing_status
-              and geo_key is not null
-              and geo_key like $2
-            order by created_at desc, id desc
-            limit $3 offset $4
-            ",
-            &[&query.status, &geo_pattern, &fetch_limit, &query.offset],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
+    let rows = repo::listing::list_by_geo_prefix(
+        &client,
+        &query.status,
+        &geo_prefix,
+        query.kinds.as_deref(),
+        fetch_limit,
+        query.offset,
+        locale,
+    )
+    .await?;
 
-    let limit = usize::try_from(query.limit)
-        .map_err(|_| lambda_http::Error::from("Invalid limit. Must be between 1 and 100"))?;
+    let limit = usize::try_from(query.limit).map_err(|_| {
+        ApiError::invalid_field(
+            "limit",
+            "invalid_limit",
+            "Invalid limit. Must be between 1 and 100",
+        )
+    })?;
     let has_more = rows.len() > limit;
-    let items = rows
+    let items = rows.into_iter().take(limit).collect::<Vec<_>>();
+
+    let geo_label = geocoding::labels::label_for(&geo_prefix).await;
+
+    Ok(DiscoverPage {
+        geo_prefix,
+        geo_label,
+        items,
+        has_more,
+        next_offset: if has_more {
+            Some(query.offset + query.limit)
+        } else {
+            None
+        },
+    })
+}
+
+fn guest_listing_item(item: ListingItem) -> GuestListingItem {
+    GuestListingItem {
+        id: item.id,
+        listing_kind: item.listing_kind,
+        crop_id: item.crop_id,
+        variety_id: item.variety_id,
+        crop_name: item.crop_name,
+        variety_name: item.variety_name,
+        title: item.title,
+        unit: item.unit,
+        quantity_remaining: item.quantity_remaining,
+        available_start: item.available_start,
+        available_end: item.available_end,
+        return_by: item.return_by,
+        geo_key: item
+            .geo_key
+            .map(|key| key.chars().take(GUEST_GEO_KEY_PRECISION).collect()),
+        created_at: item.created_at,
+    }
+}
+
+/// Active listings within `bufferMiles` of an encoded polyline, ordered by
+/// where they fall along it. The buffer is covered with geohash prefixes for
+/// the query, then each candidate's distance to the line is checked exactly.
+pub async fn list_listings_along_route(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let query = parse_along_route_query(request.uri().query())?;
+    let buffer_km = query.buffer_miles * location::KM_PER_MILE;
+    let geo_prefixes = route_corridor::covering_prefixes(&query.route, buffer_km);
+
+    let client = db::connect().await?;
+    let candidates = repo::listing::list_by_geo_prefixes(
+        &client,
+        "active",
+        &geo_prefixes,
+        query.kinds.as_deref(),
+        MAX_ROUTE_CANDIDATES,
+        request_locale(request).as_deref(),
+    )
+    .await?;
+    let candidate_count = candidates.len();
+
+    let mut matches = candidates
         .into_iter()
+        .filter_map(|(listing, point)| {
+            let (lat, lng) = point?;
+            let position = route_corridor::position_on_route(&query.route, lat, lng)?;
+            (position.distance_km <= buffer_km).then_some(RouteListing {
+                listing,
+                distance_km: round_km(position.distance_km),
+                along_route_km: round_km(position.along_km),
+            })
+        })
+        .collect::<Vec<_>>();
+    matches.sort_by(|a, b| a.along_route_km.total_cmp(&b.along_route_km));
+
+    let limit = usize::try_from(query.limit).unwrap_or(usize::MAX);
+    let offset = usize::try_from(query.offset).unwrap_or(usize::MAX);
+    let has_more = matches.len() > offset.saturating_add(limit);
+    let items = matches
+        .into_iter()
+        .skip(offset)
         .take(limit)
-        .map(|row| row_to_listing_item(&row))
         .collect::<Vec<_>>();
 
-    let response = DiscoverListingsResponse {
+    let response = AlongRouteListingsResponse {
         items,
+        route_km: round_km(route_corridor::route_length_km(&query.route)),
+        buffer_miles: query.buffer_miles,
         limit: query.limit,
         offset: query.offset,
         has_more,
@@ -83,30 +277,200 @@ pub async fn discover_listings(
     info!(
         correlation_id = correlation_id,
         user_id = auth_context.user_id.as_str(),
-        geo_key = query.geo_key,
-        geo_prefix = geo_prefix,
-        status_filter = query.status,
-        requested_radius_km = ?query.radius_km,
-        requested_radius_miles = ?query.radius_miles,
-        limit = query.limit,
-        offset = query.offset,
+        route_points = query.route.len(),
+        route_km = response.route_km,
+        buffer_miles = query.buffer_miles,
+        kind_filter = ?kind_names(query.kinds.as_deref()),
+        geo_prefix_count = geo_prefixes.len(),
+        candidate_count = candidate_count,
+        candidates_truncated =
+            i64::try_from(candidate_count).unwrap_or(i64::MAX) >= MAX_ROUTE_CANDIDATES,
         returned_count = response.items.len(),
         has_more = response.has_more,
-        "Listed discoverable surplus listings"
+        "Listed listings along route"
     );
 
     json_response(200, &response)
 }
 
-fn parse_discover_listings_query(
-    query: Option<&str>,
-) -> Result<DiscoverListingsQuery, lambda_http::Error> {
+/// How many listings under `geoKey` are available on each day of `month`,
+/// so gatherers can see which days are worth a trip. Days are UTC and the
+/// month defaults to the current one.
+pub async fn get_listing_calendar(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let query = parse_listing_calendar_query(request.uri().query(), Utc::now().date_naive())?;
+    let month_end = query
+        .month_start
+        .checked_add_months(Months::new(1))
+        .and_then(|next| next.pred_opt())
+        .ok_or_else(|| {
+            ApiError::invalid_field("month", "invalid_month", "month is out of range")
+        })?;
+
+    let client = db::connect().await?;
+    let counts = repo::listing::count_available_by_day(
+        &client,
+        &query.geo_key,
+        query.kinds.as_deref(),
+        query.month_start,
+        month_end,
+    )
+    .await?;
+
+    let geo_label = geocoding::labels::label_for(&query.geo_key).await;
+    let response = ListingCalendarResponse {
+        geo_boundary_key: query.geo_key.clone(),
+        geo_label,
+        month: query.month_start.format("%Y-%m").to_string(),
+        days: counts
+            .into_iter()
+            .map(|(day, listing_count)| ListingCalendarDay {
+                date: day.to_string(),
+                listing_count,
+            })
+            .collect(),
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = auth_context.user_id.as_str(),
+        geo_key = query.geo_key,
+        month = response.month,
+        kind_filter = ?kind_names(query.kinds.as_deref()),
+        busiest_day_count = response
+            .days
+            .iter()
+            .map(|day| day.listing_count)
+            .max()
+            .unwrap_or(0),
+        "Built listing availability calendar"
+    );
+
+    json_response(200, &response)
+}
+
+fn round_km(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn kind_names(kinds: Option<&[ListingKind]>) -> Option<Vec<&'static str>> {
+    kinds.map(|kinds| kinds.iter().map(|kind| kind.as_str()).collect())
+}
+
+/// `kind` takes one or more comma-separated listing kinds; an empty value
+/// means no filter.
+fn parse_kind_filter(value: &str) -> Result<Option<Vec<ListingKind>>, ApiError> {
+    let decoded = percent_decode(value, "kind")?;
+    if decoded.trim().is_empty() {
+        return Ok(None);
+    }
+    ListingKind::parse_filter(&decoded, "kind").map(Some)
+}
+
+/// Resolves up to [`MAX_BATCH_IDS`] listings in one query. Ids the caller may
+/// not see are dropped rather than failing the batch.
+pub async fn get_listings_by_ids(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let query = parse_batch_listings_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let items = repo::listing::find_visible_by_ids(
+        &client,
+        &query.ids,
+        user_id,
+        request_locale(request).as_deref(),
+    )
+    .await?;
+    let response = BatchListingsResponse { items };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        requested_count = query.ids.len(),
+        returned_count = response.items.len(),
+        sparse_fields = !query.projection.is_full(),
+        "Fetched listings by id"
+    );
+
+    json_response(200, &query.projection.apply(&response)?)
+}
+
+fn parse_batch_listings_query(query: Option<&str>) -> Result<BatchListingsQuery, ApiError> {
+    let mut ids: Vec<Uuid> = Vec::new();
+    let mut fields: Option<&str> = None;
+    let mut view: Option<&str> = None;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            match key {
+                "ids" => {
+                    for raw_id in value
+                        .split(',')
+                        .flat_map(|part| part.split("%2C"))
+                        .flat_map(|part| part.split("%2c"))
+                        .map(str::trim)
+                        .filter(|raw_id| !raw_id.is_empty())
+                    {
+                        let id = parse_uuid(raw_id, "ids")?;
+                        if !ids.contains(&id) {
+                            ids.push(id);
+                        }
+                    }
+                }
+                "fields" => fields = Some(value),
+                "view" => view = Some(value),
+                _ => {}
+            }
+        }
+    }
+
+    if ids.is_empty() {
+        return Err(ApiError::invalid_field(
+            "ids",
+            "required",
+            "ids is required",
+        ));
+    }
+    if ids.len() > MAX_BATCH_IDS {
+        return Err(ApiError::invalid_field(
+            "ids",
+            "too_many_ids",
+            format!("ids accepts at most {MAX_BATCH_IDS} listing ids"),
+        ));
+    }
+
+    Ok(BatchListingsQuery {
+        ids,
+        projection: ListingProjection::parse(fields, view)?,
+    })
+}
+
+#[allow(clippy::too_many_lines)]
+fn parse_discover_listings_query(query: Option<&str>) -> Result<DiscoverListingsQuery, ApiError> {
     let mut geo_key: Option<String> = None;
     let mut status = "active".to_string();
+    let mut kinds: Option<Vec<ListingKind>> = None;
     let mut radius_km: Option<f64> = None;
     let mut radius_miles: Option<f64> = None;
     let mut limit: i64 = 20;
     let mut offset: i64 = 0;
+    let mut fields: Option<&str> = None;
+    let mut view: Option<&str> = None;
 
     if let Some(raw_query) = query {
         for pair in raw_query.split('&') {
@@ -120,10 +484,16 @@ fn parse_discover_listings_query(
                 "geoKey" => {
                     let normalized = value.trim().to_ascii_lowercase();
                     if normalized.is_empty() {
-                        return Err(lambda_http::Error::from("geoKey is required"));
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
+                            "geoKey is required",
+                        ));
                     }
-                    if !is_valid_geo_key(&normalized) {
-                        return Err(lambda_http::Error::from(
+                    if !location::is_valid_geo_key(&normalized) {
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
                             "geoKey must be a valid geohash (1-12 chars, base32)",
                         ));
                     }
@@ -134,180 +504,265 @@ fn parse_discover_listings_query(
                         continue;
                     }
                     if !ALLOWED_DISCOVER_STATUS.contains(&value) {
-                        return Err(lambda_http::Error::from(format!(
-                            "Invalid listing status '{}'. Allowed values: {}",
-                            value,
-                            ALLOWED_DISCOVER_STATUS.join(", ")
-                        )));
+                        return Err(ApiError::invalid_field(
+                            "status",
+                            "invalid_enum",
+                            format!(
+                                "Invalid listing status '{}'. Allowed values: {}",
+                                value,
+                                ALLOWED_DISCOVER_STATUS.join(", ")
+                            ),
+                        ));
                     }
                     status = value.to_string();
                 }
+                "kind" => kinds = parse_kind_filter(value)?,
                 "radiusMiles" => {
                     let parsed_miles = parse_positive_radius(value, "radiusMiles")?;
                     radius_miles = Some(parsed_miles);
-                    radius_km = Some(parsed_miles * KM_PER_MILE);
+                    radius_km = Some(parsed_miles * location::KM_PER_MILE);
                 }
                 "limit" => {
                     limit = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid limit. Must be an integer")
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be an integer",
+                        )
                     })?;
                     if !(1..=100).contains(&limit) {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
                             "Invalid limit. Must be between 1 and 100",
                         ));
                     }
                 }
                 "offset" => {
                     offset = value.parse::<i64>().map_err(|_| {
-                        lambda_http::Error::from("Invalid offset. Must be an integer")
+                        ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be an integer",
+                        )
                     })?;
                     if offset < 0 {
-                        return Err(lambda_http::Error::from(
+                        return Err(ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
                             "Invalid offset. Must be greater than or equal to 0",
                         ));
                     }
                 }
+                "fields" => fields = Some(value),
+                "view" => view = Some(value),
                 _ => {}
             }
         }
     }
 
-    let geo_key = geo_key.ok_or_else(|| lambda_http::Error::from("geoKey is required"))?;
+    let geo_key = geo_key.ok_or_else(|| {
+        ApiError::invalid_field("geoKey", "invalid_geo_key", "geoKey is required")
+    })?;
 
     Ok(DiscoverListingsQuery {
         geo_key,
         status,
+        kinds,
         radius_km,
         radius_miles,
         limit,
         offset,
+        projection: ListingProjection::parse(fields, view)?,
     })
 }
 
-fn parse_positive_radius(value: &str, field_name: &str) -> Result<f64, lambda_http::Error> {
-    let parsed = value
-        .parse::<f64>()
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid number")))?;
+/// `month` is `YYYY-MM`; without it the calendar covers the month `today`
+/// falls in.
+fn parse_listing_calendar_query(
+    query: Option<&str>,
+    today: NaiveDate,
+) -> Result<ListingCalendarQuery, ApiError> {
+    let mut geo_key: Option<String> = None;
+    let mut kinds: Option<Vec<ListingKind>> = None;
+    let mut month_start: Option<NaiveDate> = None;
 
-    if !parsed.is_finite() {
-        return Err(lambda_http::Error::from(format!(
-            "{field_name} must be a finite number",
-        )));
-    }
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
 
-    if parsed <= 0.0 {
-        return Err(lambda_http::Error::from(format!(
-            "{field_name} must be greater than 0"
-        )));
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        match key {
+            "geoKey" => {
+                let normalized = value.trim().to_ascii_lowercase();
+                if !location::is_valid_geo_key(&normalized) {
+                    return Err(ApiError::invalid_field(
+                        "geoKey",
+                        "invalid_geo_key",
+                        "geoKey must be a valid geohash (1-12 chars, base32)",
+                    ));
+                }
+                geo_key = Some(normalized);
+            }
+            "kind" => kinds = parse_kind_filter(value)?,
+            "month" => {
+                if value.is_empty() {
+                    continue;
+                }
+                let parsed = NaiveDate::parse_from_str(&format!("{value}-01"), "%Y-%m-%d")
+                    .ok()
+                    .filter(|_| value.len() == 7)
+                    .ok_or_else(|| {
+                        ApiError::invalid_field(
+                            "month",
+                            "invalid_month",
+                            "month must be a calendar month (YYYY-MM)",
+                        )
+                    })?;
+                month_start = Some(parsed);
+            }
+            _ => {}
+        }
     }
 
-    Ok(parsed)
+    let geo_key = geo_key.ok_or_else(|| {
+        ApiError::invalid_field("geoKey", "invalid_geo_key", "geoKey is required")
+    })?;
+    let month_start = month_start.unwrap_or_else(|| today.with_day(1).unwrap_or(today));
+
+    Ok(ListingCalendarQuery {
+        geo_key,
+        kinds,
+        month_start,
+    })
 }
 
-fn derive_geo_prefix(geo_key: &str, radius_km: Option<f64>) -> String {
-    if let Some(radius_km) = radius_km {
-        let precision = geohash_precision_for_radius_km(radius_km);
-        let prefix_len = precision.min(geo_key.len());
-        return geo_key[..prefix_len].to_string();
-    }
+fn parse_along_route_query(query: Option<&str>) -> Result<AlongRouteQuery, ApiError> {
+    let mut route: Option<Vec<RoutePoint>> = None;
+    let mut buffer_miles = DEFAULT_BUFFER_MILES;
+    let mut kinds: Option<Vec<ListingKind>> = None;
+    let mut limit: i64 = 20;
+    let mut offset: i64 = 0;
 
-    geo_key.to_string()
-}
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
 
-fn geohash_precision_for_radius_km(radius_km: f64) -> usize {
-    if radius_km <= 0.61 {
-        6
-    } else if radius_km <= 2.4 {
-        5
-    } else if radius_km <= 20.0 {
-        4
-    } else if radius_km <= 78.0 {
-        3
-    } else if radius_km <= 630.0 {
-        2
-    } else if radius_km <= 2500.0 {
-        1
-    } else {
-        1
-    }
-}
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
 
-fn is_valid_geo_key(value: &str) -> bool {
-    if value.is_empty() || value.len() > 12 {
-        return false;
+            match key {
+                "polyline" => {
+                    let decoded = percent_decode(value, "polyline")?;
+                    let points =
+                        route_corridor::decode_polyline(&decoded).map_err(|error| match error {
+                            PolylineError::Empty => ApiError::invalid_field(
+                                "polyline",
+                                "required",
+                                "polyline is required",
+                            ),
+                            PolylineError::TooLong => ApiError::invalid_field(
+                                "polyline",
+                                "route_too_long",
+                                format!(
+                                    "polyline must be at most {} characters; simplify the route",
+                                    route_corridor::MAX_POLYLINE_CHARS
+                                ),
+                            ),
+                            PolylineError::Malformed | PolylineError::OutOfRange => {
+                                ApiError::invalid_field(
+                                    "polyline",
+                                    "invalid_polyline",
+                                    "polyline must be an encoded polyline at precision 5",
+                                )
+                            }
+                        })?;
+                    route = Some(points);
+                }
+                "bufferMiles" => {
+                    buffer_miles = parse_positive_radius(value, "bufferMiles")?;
+                    if buffer_miles > MAX_BUFFER_MILES {
+                        return Err(ApiError::invalid_field(
+                            "bufferMiles",
+                            "invalid_radius",
+                            format!("bufferMiles must be at most {MAX_BUFFER_MILES}"),
+                        ));
+                    }
+                }
+                "kind" => kinds = parse_kind_filter(value)?,
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| {
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be an integer",
+                        )
+                    })?;
+                    if !(1..=100).contains(&limit) {
+                        return Err(ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be between 1 and 100",
+                        ));
+                    }
+                }
+                "offset" => {
+                    offset = value.parse::<i64>().map_err(|_| {
+                        ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be an integer",
+                        )
+                    })?;
+                    if offset < 0 {
+                        return Err(ApiError::invalid_field(
+                            "offset",
+                            "invalid_offset",
+                            "Invalid offset. Must be greater than or equal to 0",
+                        ));
+                    }
+                }
+                _ => {}
+            }
+        }
     }
 
-    value
-        .chars()
-        .all(|ch| matches!(ch, '0'..='9' | 'b'..='h' | 'j'..='k' | 'm'..='n' | 'p'..='z'))
-}
+    let route = route
+        .ok_or_else(|| ApiError::invalid_field("polyline", "required", "polyline is required"))?;
 
-fn row_to_listing_item(row: &Row) -> ListingItem {
-    ListingItem {
-        id: row.get::<_, Uuid>("id").to_string(),
-        user_id: row.get::<_, Uuid>("user_id").to_string(),
-        grower_crop_id: row
-            .get::<_, Option<Uuid>>("grower_crop_id")
-            .map(|id| id.to_string()),
-        crop_id: row.get::<_, Uuid>("crop_id").to_string(),
-        variety_id: row
-            .get::<_, Option<Uuid>>("variety_id")
-            .map(|id| id.to_string()),
-        title: row.get("title"),
-        unit: row.get("unit"),
-        quantity_total: row.get("quantity_total"),
-        quantity_remaining: row.get("quantity_remaining"),
-        available_start: row
-            .get::<_, Option<DateTime<Utc>>>("available_start")
-            .map(|value| value.to_rfc3339()),
-        available_end: row
-            .get::<_, Option<DateTime<Utc>>>("available_end")
-            .map(|value| value.to_rfc3339()),
-        status: row.get("status"),
-        pickup_location_text: row.get("pickup_location_text"),
-        pickup_address: row.get("pickup_address"),
-        effective_pickup_address: row.get("effective_pickup_address"),
-        pickup_disclosure_policy: row.get("pickup_disclosure_policy"),
-        pickup_notes: row.get("pickup_notes"),
-        contact_pref: row.get("contact_pref"),
-        geo_key: row.get("geo_key"),
-        lat: row
-            .get::<_, Option<f64>>("lat")
-            .map(location::round_for_response),
-        lng: row
-            .get::<_, Option<f64>>("lng")
-            .map(location::round_for_response),
-        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
-    }
+    Ok(AlongRouteQuery {
+        route,
+        buffer_miles,
+        kinds,
+        limit,
+        offset,
+    })
 }
 
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
+pub fn parse_positive_radius(value: &str, field_name: &str) -> Result<f64, ApiError> {
+    let invalid_radius =
+        |message: String| ApiError::invalid_field(field_name, "invalid_radius", message);
 
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload).map_err(|error| {
-        lambda_http::Error::from(format!("Failed to serialize response: {error}"))
-    })?;
+    let parsed = value
+        .parse::<f64>()
+        .map_err(|_| invalid_radius(format!("{field_name} must be a valid number")))?;
 
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|error| lambda_http::Error::from(error.to_string()))
-}
+    if !parsed.is_finite() {
+        return Err(invalid_radius(format!(
+            "{field_name} must be a finite number"
+        )));
+    }
 
-#[allow(dead_code)]
-fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(
-        status,
-        &ErrorResponse {
-            error: message.to_string(),
-        },
-    )
+    if parsed <= 0.0 {
+        return Err(invalid_radius(format!(
+            "{field_name} must be greater than 0"
+        )));
+    }
+
+    Ok(parsed)
 }
 
 #[cfg(test)]
@@ -315,15 +770,68 @@ fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_h
 mod tests {
     use super::*;
 
+    const LISTING_A: &str = "0b5a1c8e-4f7e-4d8b-9d8a-1f1d5e6a7b01";
+    const LISTING_B: &str = "0b5a1c8e-4f7e-4d8b-9d8a-1f1d5e6a7b02";
+
+    #[test]
+    fn parse_batch_listings_query_dedupes_ids() {
+        let parsed = parse_batch_listings_query(Some(&format!(
+            "ids={LISTING_A},{LISTING_B}%2C{LISTING_A}&view=compact"
+        )))
+        .unwrap();
+        assert_eq!(
+            parsed.ids,
+            vec![
+                Uuid::parse_str(LISTING_A).unwrap(),
+                Uuid::parse_str(LISTING_B).unwrap()
+            ]
+        );
+        assert!(!parsed.projection.is_full());
+    }
+
+    #[test]
+    fn parse_batch_listings_query_requires_valid_ids() {
+        let error = parse_batch_listings_query(None).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+
+        let error = parse_batch_listings_query(Some("ids=")).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+
+        let error = parse_batch_listings_query(Some("ids=not-a-uuid")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_uuid");
+    }
+
+    #[test]
+    fn parse_batch_listings_query_caps_id_count() {
+        let ids = (0..=MAX_BATCH_IDS)
+            .map(|_| Uuid::new_v4().to_string())
+            .collect::<Vec<_>>()
+            .join(",");
+        let error = parse_batch_listings_query(Some(&format!("ids={ids}"))).unwrap_err();
+        assert_eq!(error.error_code(), "too_many_ids");
+    }
+
     #[test]
     fn parse_discover_listings_query_defaults() {
         let parsed = parse_discover_listings_query(Some("geoKey=9q8yyk8")).unwrap();
         assert_eq!(parsed.geo_key, "9q8yyk8");
         assert_eq!(parsed.status, "active");
+        assert_eq!(parsed.kinds, None);
         assert_eq!(parsed.radius_km, None);
         assert_eq!(parsed.radius_miles, None);
         assert_eq!(parsed.limit, 20);
         assert_eq!(parsed.offset, 0);
+        assert!(parsed.projection.is_full());
+    }
+
+    #[test]
+    fn parse_discover_listings_query_accepts_compact_view() {
+        let parsed = parse_discover_listings_query(Some("geoKey=9q8yyk8&view=compact")).unwrap();
+        assert!(!parsed.projection.is_full());
+
+        let error =
+            parse_discover_listings_query(Some("geoKey=9q8yyk8&fields=lat,secret")).unwrap_err();
+        assert_eq!(error.error_code(), "unknown_field");
     }
 
     #[test]
@@ -336,7 +844,7 @@ mod tests {
         assert_eq!(parsed.geo_key, "9q8yyk8");
         assert_eq!(parsed.status, "active");
         assert_eq!(parsed.radius_miles, Some(10.0));
-        assert_eq!(parsed.radius_km, Some(10.0 * KM_PER_MILE));
+        assert_eq!(parsed.radius_km, Some(10.0 * location::KM_PER_MILE));
         assert_eq!(parsed.limit, 10);
         assert_eq!(parsed.offset, 20);
     }
@@ -389,13 +897,150 @@ mod tests {
     }
 
     #[test]
-    fn derive_geo_prefix_uses_radius_precision() {
-        assert_eq!(derive_geo_prefix("9q8yyk8", Some(20.0)), "9q8y");
-        assert_eq!(derive_geo_prefix("9q8yyk8", Some(78.0)), "9q8");
+    fn parse_discover_listings_query_filters_kinds() {
+        let parsed =
+            parse_discover_listings_query(Some("geoKey=9q8yyk8&kind=seeds%2Cseedlings")).unwrap();
+        assert_eq!(
+            parsed.kinds,
+            Some(vec![ListingKind::Seeds, ListingKind::Seedlings])
+        );
+
+        let parsed = parse_discover_listings_query(Some("geoKey=9q8yyk8&kind=")).unwrap();
+        assert_eq!(parsed.kinds, None);
+
+        let error = parse_discover_listings_query(Some("geoKey=9q8yyk8&kind=hay")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+    }
+
+    #[test]
+    fn parse_listing_calendar_query_defaults_to_the_current_month() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 19).unwrap();
+        let parsed = parse_listing_calendar_query(Some("geoKey=9Q8YYK"), today).unwrap();
+        assert_eq!(parsed.geo_key, "9q8yyk");
+        assert_eq!(parsed.kinds, None);
+        assert_eq!(
+            parsed.month_start,
+            NaiveDate::from_ymd_opt(2026, 7, 1).unwrap()
+        );
+
+        let parsed =
+            parse_listing_calendar_query(Some("geoKey=9q8yyk&month=2027-02&kind=seeds"), today)
+                .unwrap();
+        assert_eq!(
+            parsed.month_start,
+            NaiveDate::from_ymd_opt(2027, 2, 1).unwrap()
+        );
+        assert_eq!(parsed.kinds, Some(vec![ListingKind::Seeds]));
+    }
+
+    #[test]
+    fn parse_listing_calendar_query_rejects_bad_input() {
+        let today = NaiveDate::from_ymd_opt(2026, 7, 19).unwrap();
+        let error = parse_listing_calendar_query(Some("month=2026-07"), today).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_geo_key");
+
+        for month in ["2026-13", "2026-7", "July", "2026-07-01"] {
+            let error =
+                parse_listing_calendar_query(Some(&format!("geoKey=9q8yyk&month={month}")), today)
+                    .unwrap_err();
+            assert_eq!(error.error_code(), "invalid_month", "{month}");
+        }
+    }
+
+    #[test]
+    fn parse_along_route_query_filters_kinds() {
+        let parsed =
+            parse_along_route_query(Some("polyline=_p~iF~ps%7CU&kind=tools_loan")).unwrap();
+        assert_eq!(parsed.kinds, Some(vec![ListingKind::ToolsLoan]));
     }
 
     #[test]
-    fn derive_geo_prefix_uses_full_key_when_radius_missing() {
-        assert_eq!(derive_geo_prefix("9q8yyk8", None), "9q8yyk8");
+    fn parse_along_route_query_decodes_escaped_polyline() {
+        let parsed = parse_along_route_query(Some(
+            "polyline=_p~iF~ps%7CU_ulLnnqC_mqNvxq%60%40&bufferMiles=2.5&limit=5",
+        ))
+        .unwrap();
+
+        assert_eq!(parsed.route.len(), 3);
+        assert!((parsed.buffer_miles - 2.5).abs() < f64::EPSILON);
+        assert_eq!(parsed.limit, 5);
+        assert_eq!(parsed.offset, 0);
+    }
+
+    #[test]
+    fn parse_along_route_query_defaults_buffer_and_requires_polyline() {
+        let parsed = parse_along_route_query(Some("polyline=_p~iF~ps%7CU")).unwrap();
+        assert!((parsed.buffer_miles - DEFAULT_BUFFER_MILES).abs() < f64::EPSILON);
+
+        let error = parse_along_route_query(Some("bufferMiles=1")).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+    }
+
+    #[test]
+    fn parse_along_route_query_rejects_bad_polyline_and_wide_buffer() {
+        let error = parse_along_route_query(Some("polyline=_p~iF")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_polyline");
+
+        let error =
+            parse_along_route_query(Some("polyline=_p~iF~ps%7CU&bufferMiles=25")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_radius");
+    }
+
+    #[test]
+    fn guest_listing_item_drops_identity_and_location() {
+        let item = ListingItem {
+            id: LISTING_A.to_string(),
+            user_id: "grower".to_string(),
+            grower_crop_id: Some("grower-crop".to_string()),
+            listing_kind: "produce".to_string(),
+            crop_id: Some("crop".to_string()),
+            variety_id: None,
+            crop_name: None,
+            variety_name: None,
+            title: Some("Tomatoes".to_string()),
+            unit: Some("lb".to_string()),
+            quantity_total: Some("10".to_string()),
+            quantity_remaining: Some("4".to_string()),
+            reserved_quantity: "1".to_string(),
+            available_start: None,
+            available_end: None,
+            return_by: None,
+            publish_at: None,
+            expire_at: None,
+            status: "active".to_string(),
+            pickup_location_text: Some("Side gate".to_string()),
+            pickup_address: Some("12 Elm St".to_string()),
+            effective_pickup_address: Some("12 Elm St".to_string()),
+            pickup_disclosure_policy: "immediate".to_string(),
+            pickup_notes: Some("Ring twice".to_string()),
+            contact_pref: "app_message".to_string(),
+            geo_key: Some("dr5ru7k".to_string()),
+            lat: Some(40.7),
+            lng: Some(-74.0),
+            group_id: Some("group".to_string()),
+            created_at: "2026-10-16T00:00:00+00:00".to_string(),
+            photo_crop_mismatch: None,
+            claim_counts: None,
+        };
+
+        let guest = guest_listing_item(item);
+        assert_eq!(guest.geo_key.as_deref(), Some("dr5ru"));
+        assert_eq!(guest.title.as_deref(), Some("Tomatoes"));
+
+        let json = serde_json::to_value(&guest).unwrap();
+        for hidden in [
+            "userId",
+            "growerCropId",
+            "pickupAddress",
+            "effectivePickupAddress",
+            "pickupLocationText",
+            "pickupNotes",
+            "contactPref",
+            "lat",
+            "lng",
+            "groupId",
+        ] {
+            assert!(json.get(hidden).is_none(), "{hidden} should be hidden");
+        }
     }
 }
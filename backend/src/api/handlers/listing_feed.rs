@@ -0,0 +1,412 @@
+use crate::auth::extract_auth_context;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use crate::location;
+use crate::signed_token;
+use chrono::{DateTime, SecondsFormat, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use std::fmt::Write as _;
+use tokio_postgres::Row;
+use tracing::info;
+use uuid::Uuid;
+
+const FEED_PATH: &str = "/feeds/listings.atom";
+const TOKEN_PURPOSE: &str = "listings-feed";
+
+/// Feeds cover at least a neighborhood-sized geohash cell so a link cannot
+/// be used to watch a single block.
+const MIN_FEED_GEO_PRECISION: usize = 4;
+
+const MAX_FEED_ENTRIES: i64 = 50;
+
+/// Newest active listings under a geohash prefix. Grower identity and
+/// coordinates are never selected; the feed is readable by anyone holding the
+/// link.
+const FEED_LISTINGS: &str = "
+    select l.id,
+           coalesce(l.title, cr.common_name, initcap(replace(l.listing_kind::text, '_', ' '))) as title,
+           l.quantity_remaining::text as quantity_remaining, l.unit,
+           l.available_start, l.available_end,
+           l.pickup_location_text, l.effective_pickup_address,
+           l.pickup_disclosure_policy::text as pickup_disclosure_policy,
+           l.created_at
+    from surplus_listings l
+    left join crops cr on cr.id = l.crop_id
+    where l.deleted_at is null
+      and l.moderation_held_at is null
+      and l.status = 'active'
+      and l.geo_key like $1
+    order by l.created_at desc, l.id desc
+    limit $2
+";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingFeedLinkResponse {
+    pub geo_key: String,
+    pub token: String,
+    pub feed_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FeedEntry {
+    id: Uuid,
+    title: String,
+    quantity_remaining: Option<String>,
+    unit: Option<String>,
+    available_start: Option<DateTime<Utc>>,
+    available_end: Option<DateTime<Utc>>,
+    location: Option<String>,
+    published_at: DateTime<Utc>,
+}
+
+/// Issues a subscription link for the Atom feed of a geohash area.
+pub fn get_listing_feed_link(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let geo_key = parse_feed_geo_key(request.uri().query())?;
+    let secret = signed_token::feed_secret(config)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = auth_context.user_id.as_str(),
+        geo_key = geo_key.as_str(),
+        "Issued listings feed link"
+    );
+
+    json_response(200, &link_response(&secret, geo_key))
+}
+
+/// Serves the Atom feed. Feed readers cannot send bearer tokens, so the route
+/// is public and the signed `token` must match the requested `geoKey`.
+pub async fn get_listing_feed(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let query = request.uri().query();
+    let geo_key = parse_feed_geo_key(query)?;
+    let token = query_value(query, "token")
+        .ok_or_else(|| ApiError::unauthorized("Missing listings feed token"))?;
+    let secret = signed_token::feed_secret(config)?;
+    if !signed_token::verify(&secret, TOKEN_PURPOSE, &geo_key, token) {
+        return Err(ApiError::unauthorized("Invalid listings feed token"));
+    }
+
+    let client = db::connect().await?;
+    let geo_pattern = format!("{geo_key}%");
+    let rows = client
+        .query_timed(
+            "listing_feed::get_listing_feed",
+            FEED_LISTINGS,
+            &[&geo_pattern, &MAX_FEED_ENTRIES],
+        )
+        .await?;
+    let entries = rows.iter().map(row_to_entry).collect::<Vec<_>>();
+
+    info!(
+        correlation_id = correlation_id,
+        geo_key = geo_key.as_str(),
+        entry_count = entries.len(),
+        "Served listings Atom feed"
+    );
+
+    let self_path = format!("{FEED_PATH}?geoKey={geo_key}&token={token}");
+    Response::builder()
+        .status(200)
+        .header("content-type", "application/atom+xml; charset=utf-8")
+        .header("cache-control", "public, max-age=900")
+        .body(Body::from(render_feed(
+            &geo_key,
+            &self_path,
+            &entries,
+            Utc::now(),
+        )))
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+fn row_to_entry(row: &Row) -> FeedEntry {
+    let policy: String = row.get("pickup_disclosure_policy");
+    FeedEntry {
+        id: row.get("id"),
+        title: row.get("title"),
+        quantity_remaining: row.get("quantity_remaining"),
+        unit: row.get("unit"),
+        available_start: row.get("available_start"),
+        available_end: row.get("available_end"),
+        location: public_location(
+            &policy,
+            row.get("effective_pickup_address"),
+            row.get("pickup_location_text"),
+        ),
+        published_at: row.get("created_at"),
+    }
+}
+
+/// A public feed has no confirmed claimer, so the full address is shown only
+/// when the grower chose immediate disclosure.
+fn public_location(
+    policy: &str,
+    address: Option<String>,
+    location_text: Option<String>,
+) -> Option<String> {
+    address
+        .filter(|_| policy == "immediate")
+        .or(location_text)
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn render_feed(
+    geo_key: &str,
+    self_path: &str,
+    entries: &[FeedEntry],
+    now: DateTime<Utc>,
+) -> String {
+    let updated = entries
+        .iter()
+        .map(|entry| entry.published_at)
+        .max()
+        .unwrap_or(now);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    xml.push_str("<feed xmlns=\"http://www.w3.org/2005/Atom\">\n");
+    let _ = writeln!(
+        xml,
+        "  <id>urn:community-garden:listings:{}</id>",
+        escape_xml(geo_key)
+    );
+    let _ = writeln!(
+        xml,
+        "  <title>Community Garden listings near {}</title>",
+        escape_xml(geo_key)
+    );
+    let _ = writeln!(
+        xml,
+        "  <link rel=\"self\" href=\"{}\"/>",
+        escape_xml(self_path)
+    );
+    let _ = writeln!(xml, "  <updated>{}</updated>", format_time(updated));
+    xml.push_str("  <author><name>Community Garden</name></author>\n");
+
+    for entry in entries {
+        xml.push_str("  <entry>\n");
+        let _ = writeln!(xml, "    <id>urn:uuid:{}</id>", entry.id);
+        let _ = writeln!(xml, "    <title>{}</title>", escape_xml(&entry.title));
+        let _ = writeln!(
+            xml,
+            "    <published>{}</published>",
+            format_time(entry.published_at)
+        );
+        let _ = writeln!(
+            xml,
+            "    <updated>{}</updated>",
+            format_time(entry.published_at)
+        );
+        let _ = writeln!(
+            xml,
+            "    <content type=\"text\">{}</content>",
+            escape_xml(&entry_summary(entry))
+        );
+        xml.push_str("  </entry>\n");
+    }
+    xml.push_str("</feed>\n");
+    xml
+}
+
+fn entry_summary(entry: &FeedEntry) -> String {
+    let mut lines = Vec::new();
+    if let Some(quantity) = &entry.quantity_remaining {
+        lines.push(entry.unit.as_ref().map_or_else(
+            || format!("Available: {quantity}"),
+            |unit| format!("Available: {quantity} {unit}"),
+        ));
+    }
+    match (entry.available_start, entry.available_end) {
+        (Some(start), Some(end)) => lines.push(format!(
+            "Pickup window: {} to {}",
+            format_time(start),
+            format_time(end)
+        )),
+        (Some(start), None) => lines.push(format!("Pickup from: {}", format_time(start))),
+        (None, Some(end)) => lines.push(format!("Pickup until: {}", format_time(end))),
+        (None, None) => {}
+    }
+    if let Some(location) = &entry.location {
+        lines.push(format!("Pickup location: {location}"));
+    }
+    lines.join("\n")
+}
+
+fn format_time(value: DateTime<Utc>) -> String {
+    value.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+fn link_response(secret: &str, geo_key: String) -> ListingFeedLinkResponse {
+    let token = signed_token::sign(secret, TOKEN_PURPOSE, &geo_key);
+    ListingFeedLinkResponse {
+        feed_path: format!("{FEED_PATH}?geoKey={geo_key}&token={token}"),
+        geo_key,
+        token,
+    }
+}
+
+fn parse_feed_geo_key(query: Option<&str>) -> Result<String, ApiError> {
+    let geo_key = query_value(query, "geoKey")
+        .map(|value| value.trim().to_ascii_lowercase())
+        .ok_or_else(|| {
+            ApiError::invalid_field("geoKey", "missing_geo_key", "geoKey is required")
+        })?;
+    if !location::is_valid_geo_key(&geo_key) {
+        return Err(ApiError::invalid_field(
+            "geoKey",
+            "invalid_geo_key",
+            "geoKey must be a valid geohash (1-12 chars, base32)",
+        ));
+    }
+    if geo_key.len() < MIN_FEED_GEO_PRECISION {
+        return Err(ApiError::invalid_field(
+            "geoKey",
+            "geo_key_too_broad",
+            format!("geoKey must be at least {MIN_FEED_GEO_PRECISION} characters"),
+        ));
+    }
+    Ok(geo_key)
+}
+
+fn query_value<'q>(query: Option<&'q str>, name: &str) -> Option<&'q str> {
+    query
+        .into_iter()
+        .flat_map(|raw| raw.split('&'))
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value)
+        .filter(|value| !value.is_empty())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, hour, 0, 0).unwrap()
+    }
+
+    fn entry() -> FeedEntry {
+        FeedEntry {
+            id: Uuid::nil(),
+            title: "Tomatoes & basil".to_string(),
+            quantity_remaining: Some("4.000".to_string()),
+            unit: Some("lb".to_string()),
+            available_start: Some(at(9)),
+            available_end: Some(at(17)),
+            location: Some("Front porch".to_string()),
+            published_at: at(8),
+        }
+    }
+
+    #[test]
+    fn parse_feed_geo_key_requires_neighborhood_precision() {
+        assert_eq!(
+            parse_feed_geo_key(Some("geoKey=9Q8YY&token=x")).unwrap(),
+            "9q8yy"
+        );
+        assert_eq!(
+            parse_feed_geo_key(Some("geoKey=9q8"))
+                .unwrap_err()
+                .error_code(),
+            "geo_key_too_broad"
+        );
+        assert_eq!(
+            parse_feed_geo_key(Some("geoKey=9qa8"))
+                .unwrap_err()
+                .error_code(),
+            "invalid_geo_key"
+        );
+        assert_eq!(
+            parse_feed_geo_key(None).unwrap_err().error_code(),
+            "missing_geo_key"
+        );
+    }
+
+    #[test]
+    fn link_token_is_bound_to_geo_key() {
+        let link = link_response("secret", "9q8y".to_string());
+        assert_eq!(
+            link.feed_path,
+            format!("/feeds/listings.atom?geoKey=9q8y&token={}", link.token)
+        );
+        assert!(signed_token::verify(
+            "secret",
+            TOKEN_PURPOSE,
+            "9q8y",
+            &link.token
+        ));
+        assert!(!signed_token::verify(
+            "secret",
+            TOKEN_PURPOSE,
+            "9q8z",
+            &link.token
+        ));
+    }
+
+    #[test]
+    fn public_location_only_reveals_immediate_addresses() {
+        let address = Some("12 Elm St".to_string());
+        let text = Some("Front porch".to_string());
+        assert_eq!(
+            public_location("immediate", address.clone(), text.clone()).as_deref(),
+            Some("12 Elm St")
+        );
+        assert_eq!(
+            public_location("after_confirmed", address.clone(), text).as_deref(),
+            Some("Front porch")
+        );
+        assert_eq!(public_location("after_accepted", address, None), None);
+    }
+
+    #[test]
+    fn render_feed_escapes_and_describes_entries() {
+        let xml = render_feed(
+            "9q8y",
+            "/feeds/listings.atom?geoKey=9q8y&token=t",
+            &[entry()],
+            at(12),
+        );
+
+        assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"utf-8\"?>"));
+        assert!(xml.contains(
+            "<link rel=\"self\" href=\"/feeds/listings.atom?geoKey=9q8y&amp;token=t\"/>"
+        ));
+        assert!(xml.contains("<updated>2026-06-01T08:00:00Z</updated>"));
+        assert!(xml.contains("<id>urn:uuid:00000000-0000-0000-0000-000000000000</id>"));
+        assert!(xml.contains("<title>Tomatoes &amp; basil</title>"));
+        assert!(xml.contains("Available: 4.000 lb"));
+        assert!(xml.contains("Pickup window: 2026-06-01T09:00:00Z to 2026-06-01T17:00:00Z"));
+        assert!(xml.contains("Pickup location: Front porch"));
+        assert!(xml.trim_end().ends_with("</feed>"));
+    }
+
+    #[test]
+    fn render_feed_without_entries_uses_current_time() {
+        let xml = render_feed("9q8y", FEED_PATH, &[], at(12));
+        assert!(xml.contains("<updated>2026-06-01T12:00:00Z</updated>"));
+        assert!(!xml.contains("<entry>"));
+    }
+}
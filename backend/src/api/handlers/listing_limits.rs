@@ -0,0 +1,138 @@
+//! Per-grower caps on new listings, so one account cannot flood its
+//! neighborhood's feed. Checked by `POST /listings` and listing templates
+//! before the insert; updates and idempotent replays are never limited.
+
+use crate::db::TimedQuery;
+use crate::error::ApiError;
+use tokio_postgres::Client;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListingLimitsConfig {
+    /// Scheduled, active and pending listings a grower may have at once.
+    /// Zero turns the cap off.
+    pub max_live_per_user: i64,
+    /// Listings a grower may create in any rolling hour, deleted ones
+    /// included. Zero turns the throttle off.
+    pub max_created_per_hour: i64,
+}
+
+/// A grower's listings as the limits count them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct ListingUsage {
+    live: i64,
+    created_last_hour: i64,
+}
+
+/// Counts live listings and listings created in the last hour. Deleted
+/// listings count toward the hour, so deleting and reposting does not get
+/// around the throttle.
+const LISTING_USAGE_SQL: &str = "
+    select count(*) filter (
+               where deleted_at is null
+                 and status in ('scheduled'::listing_status,
+                                'active'::listing_status,
+                                'pending'::listing_status)
+           ) as live,
+           count(*) filter (where created_at >= now() - interval '1 hour') as created_last_hour,
+           coalesce(bool_or(id = $2), false) as replay
+      from surplus_listings
+     where user_id = $1
+    ";
+
+/// Refuses a new listing that would take the grower past either limit.
+/// `listing_id` is the id the listing will get; a listing that already
+/// exists is an idempotent replay and passes. Concurrent creates can pass
+/// together, so the caps are soft by at most a request or two.
+pub async fn enforce(
+    client: &Client,
+    limits: &ListingLimitsConfig,
+    user_id: Uuid,
+    listing_id: Uuid,
+) -> Result<(), ApiError> {
+    if limits.max_live_per_user == 0 && limits.max_created_per_hour == 0 {
+        return Ok(());
+    }
+
+    let row = client
+        .query_one_timed(
+            "listing_limits::enforce",
+            LISTING_USAGE_SQL,
+            &[&user_id, &listing_id],
+        )
+        .await?;
+    if row.get::<_, bool>("replay") {
+        return Ok(());
+    }
+
+    check(
+        limits,
+        ListingUsage {
+            live: row.get("live"),
+            created_last_hour: row.get("created_last_hour"),
+        },
+    )
+}
+
+fn check(limits: &ListingLimitsConfig, usage: ListingUsage) -> Result<(), ApiError> {
+    if limits.max_created_per_hour > 0 && usage.created_last_hour >= limits.max_created_per_hour {
+        return Err(ApiError::too_many_requests(
+            "listing_rate_limited",
+            format!(
+                "At most {} listings can be created per hour; try again later",
+                limits.max_created_per_hour
+            ),
+        ));
+    }
+    if limits.max_live_per_user > 0 && usage.live >= limits.max_live_per_user {
+        return Err(ApiError::conflict(
+            "listing_limit_reached",
+            format!(
+                "At most {} listings can be live at once; complete or delete one first",
+                limits.max_live_per_user
+            ),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const LIMITS: ListingLimitsConfig = ListingLimitsConfig {
+        max_live_per_user: 20,
+        max_created_per_hour: 5,
+    };
+
+    fn usage(live: i64, created_last_hour: i64) -> ListingUsage {
+        ListingUsage {
+            live,
+            created_last_hour,
+        }
+    }
+
+    #[test]
+    fn check_allows_listings_under_both_limits() {
+        assert!(check(&LIMITS, usage(19, 4)).is_ok());
+    }
+
+    #[test]
+    fn check_throttles_before_capping() {
+        let error = check(&LIMITS, usage(20, 5)).unwrap_err();
+        assert_eq!(error.error_code(), "listing_rate_limited");
+
+        let error = check(&LIMITS, usage(20, 0)).unwrap_err();
+        assert_eq!(error.error_code(), "listing_limit_reached");
+    }
+
+    #[test]
+    fn check_skips_limits_set_to_zero() {
+        let off = ListingLimitsConfig {
+            max_live_per_user: 0,
+            max_created_per_hour: 0,
+        };
+        assert!(check(&off, usage(500, 500)).is_ok());
+    }
+}
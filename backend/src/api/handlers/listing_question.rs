@@ -0,0 +1,489 @@
+//! Public questions and answers on listings. Anyone who can see a listing can
+//! read its Q&A and, while it is open for claims, ask a question; only the
+//! listing owner, or a co-manager of their garden, answers. New questions and
+//! answers are sent through the moderation worker, which can hold a question,
+//! or just its answer, until a reviewer clears it. A held question is still
+//! shown to the person who asked it, and a held answer to the listing owner.
+
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::events::{self, ListingQuestionEventDetail};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{Client, Row};
+use tracing::{error, info};
+use uuid::Uuid;
+
+const MAX_QUESTION_CHARS: usize = 500;
+const MAX_ANSWER_CHARS: usize = 1000;
+/// Unanswered questions one person may have on a listing at a time, so a
+/// single asker cannot fill the thread before the owner gets to it.
+const MAX_OPEN_QUESTIONS_PER_ASKER: i64 = 3;
+
+const QUESTION_COLUMNS: &str = "q.id, q.listing_id, q.asker_id, q.body, q.created_at";
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AskQuestionRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AnswerQuestionRequest {
+    pub answer: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingQuestionResponse {
+    pub id: String,
+    pub listing_id: String,
+    pub asker_id: String,
+    pub body: String,
+    pub answer: Option<String>,
+    pub answered_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListQuestionsResponse {
+    pub items: Vec<ListingQuestionResponse>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug)]
+struct PageQuery {
+    limit: i64,
+    offset: i64,
+}
+
+/// The parts of a listing that decide who may read and ask.
+#[derive(Debug)]
+struct QuestionListing {
+    owner_id: Uuid,
+    accepts_questions: bool,
+}
+
+pub async fn list_questions(
+    request: &Request,
+    correlation_id: &str,
+    listing_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let listing_id = parse_uuid(listing_id, "listingId")?;
+    let query = parse_page_query(request.uri().query())?;
+    let fetch_limit = query.limit + 1;
+
+    let client = db::connect().await?;
+    load_visible_listing(&client, listing_id, user_id).await?;
+
+    let sql = format!(
+        "
+        select {QUESTION_COLUMNS},
+               case when q.answer_held_at is null or l.user_id = $2 then q.answer end as answer,
+               case when q.answer_held_at is null or l.user_id = $2 then q.answered_at end
+                 as answered_at
+        from listing_questions q
+        join surplus_listings l on l.id = q.listing_id
+        where q.listing_id = $1
+          and (q.moderation_held_at is null or q.asker_id = $2)
+        order by q.created_at desc, q.id desc
+        limit $3 offset $4
+        "
+    );
+    let rows = client
+        .query_timed(
+            "listing_question::list_questions",
+            &sql,
+            &[&listing_id, &user_id, &fetch_limit, &query.offset],
+        )
+        .await?;
+
+    let limit = usize::try_from(query.limit).map_err(|_| invalid_limit())?;
+    let has_more = rows.len() > limit;
+    let items = rows
+        .iter()
+        .take(limit)
+        .map(row_to_question_response)
+        .collect::<Vec<_>>();
+
+    let response = ListQuestionsResponse {
+        items,
+        limit: query.limit,
+        offset: query.offset,
+        has_more,
+        next_offset: compute_next_offset(query.offset, query.limit, has_more),
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        listing_id = %listing_id,
+        returned_count = response.items.len(),
+        has_more = response.has_more,
+        "Listed listing questions"
+    );
+
+    json_response(200, &response)
+}
+
+pub async fn ask_question(
+    request: &Request,
+    correlation_id: &str,
+    listing_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let listing_id = parse_uuid(listing_id, "listingId")?;
+    let payload: AskQuestionRequest = parse_json_body(request)?;
+    let body = normalize_text(&payload.body, "body", MAX_QUESTION_CHARS)?;
+
+    let client = db::connect().await?;
+    let listing = load_visible_listing(&client, listing_id, user_id).await?;
+    if listing.owner_id == user_id {
+        return Err(ApiError::forbidden(
+            "own_listing",
+            "Answer questions on your own listing instead of asking them",
+        ));
+    }
+    if !listing.accepts_questions {
+        return Err(ApiError::conflict(
+            "listing_not_active",
+            "Questions can only be asked on active listings",
+        ));
+    }
+
+    let open: i64 = client
+        .query_one_timed(
+            "listing_question::ask_question",
+            "
+            select count(*) as count
+            from listing_questions
+            where listing_id = $1 and asker_id = $2 and answer is null
+            ",
+            &[&listing_id, &user_id],
+        )
+        .await?
+        .get("count");
+    if open >= MAX_OPEN_QUESTIONS_PER_ASKER {
+        return Err(ApiError::conflict(
+            "too_many_open_questions",
+            format!(
+                "You already have {MAX_OPEN_QUESTIONS_PER_ASKER} unanswered questions on this listing"
+            ),
+        ));
+    }
+
+    let sql = format!(
+        "
+        insert into listing_questions as q (listing_id, asker_id, body)
+        values ($1, $2, $3)
+        returning {QUESTION_COLUMNS}, q.answer, q.answered_at
+        "
+    );
+    let row = client
+        .query_one_timed(
+            "listing_question::ask_question",
+            &sql,
+            &[&listing_id, &user_id, &body],
+        )
+        .await?;
+
+    let question = row_to_question_response(&row);
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        listing_id = %listing_id,
+        question_id = question.id.as_str(),
+        "Asked listing question"
+    );
+
+    emit_question_event_best_effort(
+        events::LISTING_QUESTION_CREATED,
+        &question,
+        listing.owner_id,
+        correlation_id,
+    )
+    .await;
+
+    json_response(201, &question)
+}
+
+/// Sets or replaces the answer to a question on a listing the caller owns or
+/// co-manages.
+pub async fn answer_question(
+    request: &Request,
+    correlation_id: &str,
+    listing_id: &str,
+    question_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let listing_id = parse_uuid(listing_id, "listingId")?;
+    let question_id = parse_uuid(question_id, "questionId")?;
+    let payload: AnswerQuestionRequest = parse_json_body(request)?;
+    let answer = normalize_text(&payload.answer, "answer", MAX_ANSWER_CHARS)?;
+
+    let client = db::connect().await?;
+    let sql = format!(
+        "
+        update listing_questions q
+           set answer = $4,
+               answered_at = now()
+          from surplus_listings l
+         where q.id = $1
+           and q.listing_id = $2
+           and q.moderation_held_at is null
+           and l.id = q.listing_id
+           and (
+             l.user_id = $3
+             or exists (
+               select 1 from grower_co_managers m
+               where m.owner_id = l.user_id
+                 and m.co_manager_id = $3
+                 and m.status = 'accepted'
+             )
+           )
+           and l.deleted_at is null
+        returning {QUESTION_COLUMNS}, q.answer, q.answered_at
+        "
+    );
+    let row = client
+        .query_opt_timed(
+            "listing_question::answer_question",
+            &sql,
+            &[&question_id, &listing_id, &user_id, &answer],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("question_not_found", "Question not found"))?;
+
+    let question = row_to_question_response(&row);
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        listing_id = %listing_id,
+        question_id = question.id.as_str(),
+        "Answered listing question"
+    );
+
+    emit_question_event_best_effort(
+        events::LISTING_QUESTION_ANSWERED,
+        &question,
+        user_id,
+        correlation_id,
+    )
+    .await;
+
+    json_response(200, &question)
+}
+
+/// Loads a listing the caller may see: their own, an active one that is not
+/// held, or one they have claimed from. Anything else is a 404.
+async fn load_visible_listing(
+    client: &Client,
+    listing_id: Uuid,
+    user_id: Uuid,
+) -> Result<QuestionListing, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "listing_question::load_visible_listing",
+            "
+            select l.user_id,
+                   l.status = 'active' and l.moderation_held_at is null as accepts_questions
+            from surplus_listings l
+            where l.id = $1
+              and l.deleted_at is null
+              and (
+                l.user_id = $2
+                or (l.status = 'active' and l.moderation_held_at is null)
+                or exists (
+                  select 1 from claims c
+                  where c.listing_id = l.id
+                    and c.claimer_id = $2
+                )
+              )
+            ",
+            &[&listing_id, &user_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))?;
+
+    Ok(QuestionListing {
+        owner_id: row.get("user_id"),
+        accepts_questions: row.get("accepts_questions"),
+    })
+}
+
+fn normalize_text(value: &str, field_name: &str, max_chars: usize) -> Result<String, ApiError> {
+    let trimmed = value.trim();
+    if trimmed.is_empty() {
+        return Err(ApiError::invalid_field(
+            field_name,
+            "required",
+            format!("{field_name} is required"),
+        ));
+    }
+    if trimmed.chars().count() > max_chars {
+        return Err(ApiError::invalid_field(
+            field_name,
+            "too_long",
+            format!("{field_name} must be at most {max_chars} characters"),
+        ));
+    }
+    Ok(trimmed.to_string())
+}
+
+fn parse_page_query(query: Option<&str>) -> Result<PageQuery, ApiError> {
+    let mut limit: i64 = 20;
+    let mut offset: i64 = 0;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            match key {
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| invalid_limit())?;
+                    if !(1..=100).contains(&limit) {
+                        return Err(invalid_limit());
+                    }
+                }
+                "offset" => {
+                    offset = value
+                        .parse::<i64>()
+                        .ok()
+                        .filter(|offset| *offset >= 0)
+                        .ok_or_else(|| {
+                            ApiError::invalid_field(
+                                "offset",
+                                "invalid_offset",
+                                "Invalid offset. Must be an integer greater than or equal to 0",
+                            )
+                        })?;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(PageQuery { limit, offset })
+}
+
+const fn compute_next_offset(offset: i64, limit: i64, has_more: bool) -> Option<i64> {
+    if has_more {
+        offset.checked_add(limit)
+    } else {
+        None
+    }
+}
+
+fn row_to_question_response(row: &Row) -> ListingQuestionResponse {
+    ListingQuestionResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        listing_id: row.get::<_, Uuid>("listing_id").to_string(),
+        asker_id: row.get::<_, Uuid>("asker_id").to_string(),
+        body: row.get("body"),
+        answer: row.get("answer"),
+        answered_at: row
+            .get::<_, Option<DateTime<Utc>>>("answered_at")
+            .map(|answered_at| answered_at.to_rfc3339()),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    }
+}
+
+async fn emit_question_event_best_effort(
+    detail_type: &str,
+    question: &ListingQuestionResponse,
+    listing_owner_id: Uuid,
+    correlation_id: &str,
+) {
+    let detail = ListingQuestionEventDetail::new(
+        question.id.clone(),
+        question.listing_id.clone(),
+        question.asker_id.clone(),
+        listing_owner_id.to_string(),
+        correlation_id,
+    );
+
+    if let Err(event_error) = events::publish(detail_type, &detail).await {
+        error!(
+            correlation_id = correlation_id,
+            question_id = question.id.as_str(),
+            error = %event_error,
+            "Failed to emit listing question event after successful write"
+        );
+    }
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+fn invalid_limit() -> ApiError {
+    ApiError::invalid_field(
+        "limit",
+        "invalid_limit",
+        "Invalid limit. Must be an integer between 1 and 100",
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn normalize_text_trims_and_bounds_length() {
+        assert_eq!(
+            normalize_text("  Are these sprayed?  ", "body", MAX_QUESTION_CHARS).unwrap(),
+            "Are these sprayed?"
+        );
+        assert_eq!(
+            normalize_text("   ", "body", MAX_QUESTION_CHARS)
+                .unwrap_err()
+                .error_code(),
+            "required"
+        );
+        assert!(normalize_text(&"é".repeat(MAX_ANSWER_CHARS), "answer", MAX_ANSWER_CHARS).is_ok());
+        assert_eq!(
+            normalize_text(
+                &"a".repeat(MAX_QUESTION_CHARS + 1),
+                "body",
+                MAX_QUESTION_CHARS
+            )
+            .unwrap_err()
+            .error_code(),
+            "too_long"
+        );
+    }
+
+    #[test]
+    fn parse_page_query_defaults_and_validates() {
+        let query = parse_page_query(None).unwrap();
+        assert_eq!((query.limit, query.offset), (20, 0));
+
+        let query = parse_page_query(Some("limit=5&offset=10")).unwrap();
+        assert_eq!((query.limit, query.offset), (5, 10));
+        assert_eq!(compute_next_offset(10, 5, true), Some(15));
+        assert_eq!(compute_next_offset(10, 5, false), None);
+
+        assert_eq!(
+            parse_page_query(Some("limit=0")).unwrap_err().error_code(),
+            "invalid_limit"
+        );
+        assert_eq!(
+            parse_page_query(Some("offset=-1"))
+                .unwrap_err()
+                .error_code(),
+            "invalid_offset"
+        );
+    }
+}
@@ -0,0 +1,432 @@
+use crate::auth::extract_auth_context;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::handlers::listing::{self, UpsertListingRequest};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::listing_kind::ListingKind;
+use chrono::{DateTime, Duration, Utc};
+use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_TEMPLATES_PER_GROWER: i64 = 50;
+const MAX_TEMPLATE_NAME_CHARS: usize = 80;
+
+/// A listing payload without its availability window. Stored as is and
+/// merged with the dates sent to `POST /listings/from-template/{id}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingTemplateDefaults {
+    pub title: String,
+    pub listing_kind: Option<String>,
+    pub crop_id: Option<String>,
+    pub variety_id: Option<String>,
+    pub quantity_total: Decimal,
+    pub unit: String,
+    pub pickup_location_text: Option<String>,
+    pub pickup_address: Option<String>,
+    pub pickup_disclosure_policy: Option<String>,
+    pub pickup_notes: Option<String>,
+    pub contact_pref: Option<String>,
+    pub group_id: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateListingTemplateRequest {
+    pub name: String,
+    pub defaults: ListingTemplateDefaults,
+}
+
+/// Dates for a listing made from a template. `quantityTotal` overrides the
+/// template's amount for this one listing.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateListingFromTemplateRequest {
+    pub available_start: String,
+    pub available_end: String,
+    /// Required when the template is a tool loan.
+    pub return_by: Option<String>,
+    pub quantity_total: Option<Decimal>,
+    pub status: Option<String>,
+    pub publish_at: Option<String>,
+    pub expire_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingTemplateResponse {
+    pub id: String,
+    pub name: String,
+    pub defaults: ListingTemplateDefaults,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListListingTemplatesResponse {
+    pub items: Vec<ListingTemplateResponse>,
+}
+
+pub async fn list_listing_templates(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "listing_template::list_listing_templates",
+            "
+            select id, name, defaults, last_used_at, created_at
+            from listing_templates
+            where user_id = $1
+            order by lower(name) asc, id asc
+            ",
+            &[&user_id],
+        )
+        .await?;
+    let items = rows
+        .iter()
+        .map(row_to_template_response)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_count = items.len(),
+        "Listed listing templates"
+    );
+
+    json_response(200, &ListListingTemplatesResponse { items })
+}
+
+/// Saves named listing defaults. They are checked the way a listing would
+/// be, so a template that saves can always be listed once dates are given.
+pub async fn create_listing_template(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let payload: CreateListingTemplateRequest = parse_json_body(request)?;
+    let name = validate_template(&payload, Utc::now())?;
+    let defaults = serde_json::to_value(&payload.defaults)
+        .map_err(|error| ApiError::internal(error.to_string()))?;
+
+    let client = db::connect().await?;
+    let existing: i64 = client
+        .query_one_timed(
+            "listing_template::create_listing_template",
+            "select count(*) from listing_templates where user_id = $1",
+            &[&user_id],
+        )
+        .await?
+        .get(0);
+    if existing >= MAX_TEMPLATES_PER_GROWER {
+        return Err(ApiError::conflict(
+            "template_limit_reached",
+            format!("A grower can save at most {MAX_TEMPLATES_PER_GROWER} listing templates"),
+        ));
+    }
+
+    let row = client
+        .query_opt_timed(
+            "listing_template::create_listing_template",
+            "
+            insert into listing_templates (user_id, name, defaults)
+            values ($1, $2, $3)
+            on conflict (user_id, lower(name)) do nothing
+            returning id, name, defaults, last_used_at, created_at
+            ",
+            &[&user_id, &name, &defaults],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::conflict(
+                "template_name_taken",
+                "You already have a listing template with this name",
+            )
+        })?;
+    let response = row_to_template_response(&row)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_id = response.id.as_str(),
+        "Created listing template"
+    );
+
+    json_response(201, &response)
+}
+
+pub async fn delete_listing_template(
+    request: &Request,
+    correlation_id: &str,
+    template_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let id = parse_uuid(template_id, "templateId")?;
+
+    let client = db::connect().await?;
+    let deleted = client
+        .execute_timed(
+            "listing_template::delete_listing_template",
+            "delete from listing_templates where id = $1 and user_id = $2",
+            &[&id, &user_id],
+        )
+        .await?;
+    if deleted == 0 {
+        return Err(template_not_found());
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_id = %id,
+        "Deleted listing template"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// Creates a listing from a saved template plus the dates in the body. The
+/// listing is built and checked exactly as `POST /listings` would, including
+/// `Idempotency-Key`.
+pub async fn create_listing_from_template(
+    request: &Request,
+    correlation_id: &str,
+    template_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let id = parse_uuid(template_id, "templateId")?;
+    let dates: CreateListingFromTemplateRequest = parse_json_body(request)?;
+
+    let client = db::connect().await?;
+    let row = client
+        .query_opt_timed(
+            "listing_template::create_listing_from_template",
+            "
+            update listing_templates
+            set last_used_at = now()
+            where id = $1 and user_id = $2
+            returning defaults
+            ",
+            &[&id, &user_id],
+        )
+        .await?
+        .ok_or_else(template_not_found)?;
+    let defaults: ListingTemplateDefaults =
+        serde_json::from_value(row.get("defaults")).map_err(|error| {
+            ApiError::internal(format!("Stored listing template is unreadable: {error}"))
+        })?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_id = %id,
+        "Creating listing from template"
+    );
+
+    let payload = listing_request(defaults, dates);
+    listing::create_listing_from_payload(
+        request,
+        &auth_context,
+        &payload,
+        &config.listing_limits,
+        correlation_id,
+    )
+    .await
+}
+
+fn listing_request(
+    defaults: ListingTemplateDefaults,
+    dates: CreateListingFromTemplateRequest,
+) -> UpsertListingRequest {
+    UpsertListingRequest {
+        title: defaults.title,
+        listing_kind: defaults.listing_kind,
+        crop_id: defaults.crop_id,
+        variety_id: defaults.variety_id,
+        quantity_total: dates.quantity_total.unwrap_or(defaults.quantity_total),
+        unit: defaults.unit,
+        available_start: dates.available_start,
+        available_end: dates.available_end,
+        pickup_location_text: defaults.pickup_location_text,
+        pickup_address: defaults.pickup_address,
+        pickup_disclosure_policy: defaults.pickup_disclosure_policy,
+        pickup_notes: defaults.pickup_notes,
+        contact_pref: defaults.contact_pref,
+        status: dates.status,
+        group_id: defaults.group_id,
+        return_by: dates.return_by,
+        publish_at: dates.publish_at,
+        expire_at: dates.expire_at,
+        harvest_id: None,
+    }
+}
+
+/// Checks the name and runs the listing checks against a placeholder
+/// window, with a placeholder return date for tool loans. Returns the
+/// trimmed name.
+fn validate_template(
+    payload: &CreateListingTemplateRequest,
+    now: DateTime<Utc>,
+) -> Result<String, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        errors.add("name", "required", "name is required");
+    } else if name.chars().count() > MAX_TEMPLATE_NAME_CHARS {
+        errors.add(
+            "name",
+            "too_long",
+            format!("name must be at most {MAX_TEMPLATE_NAME_CHARS} characters"),
+        );
+    }
+
+    let is_tool_loan = payload
+        .defaults
+        .listing_kind
+        .as_deref()
+        .and_then(ListingKind::parse)
+        .is_some_and(|kind| kind.has_return_by());
+    let placeholder = CreateListingFromTemplateRequest {
+        available_start: now.to_rfc3339(),
+        available_end: now.to_rfc3339(),
+        return_by: is_tool_loan.then(|| (now + Duration::days(1)).to_rfc3339()),
+        quantity_total: None,
+        status: None,
+        publish_at: None,
+        expire_at: None,
+    };
+    errors.capture(listing::validate_payload(&listing_request(
+        payload.defaults.clone(),
+        placeholder,
+    )));
+
+    errors.into_result()?;
+    Ok(name.to_string())
+}
+
+fn row_to_template_response(row: &Row) -> Result<ListingTemplateResponse, ApiError> {
+    let defaults = serde_json::from_value(row.get("defaults")).map_err(|error| {
+        ApiError::internal(format!("Stored listing template is unreadable: {error}"))
+    })?;
+    Ok(ListingTemplateResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        name: row.get("name"),
+        defaults,
+        last_used_at: row
+            .get::<_, Option<DateTime<Utc>>>("last_used_at")
+            .map(|value| value.to_rfc3339()),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    })
+}
+
+fn template_not_found() -> ApiError {
+    ApiError::not_found("listing_template_not_found", "Listing template not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> ListingTemplateDefaults {
+        ListingTemplateDefaults {
+            title: "Friday egg share".to_string(),
+            listing_kind: None,
+            crop_id: Some("5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string()),
+            variety_id: None,
+            quantity_total: Decimal::new(12, 0),
+            unit: "count".to_string(),
+            pickup_location_text: Some("Cooler by the gate".to_string()),
+            pickup_address: None,
+            pickup_disclosure_policy: None,
+            pickup_notes: None,
+            contact_pref: None,
+            group_id: None,
+        }
+    }
+
+    fn template(name: &str, defaults: ListingTemplateDefaults) -> CreateListingTemplateRequest {
+        CreateListingTemplateRequest {
+            name: name.to_string(),
+            defaults,
+        }
+    }
+
+    #[test]
+    fn validate_template_trims_the_name() {
+        let name = validate_template(&template("  Friday eggs ", defaults()), Utc::now()).unwrap();
+        assert_eq!(name, "Friday eggs");
+    }
+
+    #[test]
+    fn validate_template_applies_listing_rules() {
+        let error = validate_template(&template(" ", defaults()), Utc::now()).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+
+        let mut no_crop = defaults();
+        no_crop.crop_id = None;
+        let error = validate_template(&template("Eggs", no_crop), Utc::now()).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+
+        let mut bad_pref = defaults();
+        bad_pref.contact_pref = Some("fax".to_string());
+        let error = validate_template(&template("Eggs", bad_pref), Utc::now()).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+    }
+
+    #[test]
+    fn validate_template_accepts_tool_loans_without_a_return_date() {
+        let mut tools = defaults();
+        tools.listing_kind = Some("tools_loan".to_string());
+        tools.crop_id = None;
+        tools.unit = "item".to_string();
+        assert!(validate_template(&template("Loppers", tools), Utc::now()).is_ok());
+    }
+
+    #[test]
+    fn listing_request_takes_dates_and_quantity_override() {
+        let request = listing_request(
+            defaults(),
+            CreateListingFromTemplateRequest {
+                available_start: "2026-10-16T08:00:00Z".to_string(),
+                available_end: "2026-10-16T18:00:00Z".to_string(),
+                return_by: None,
+                quantity_total: Some(Decimal::new(6, 0)),
+                status: None,
+                publish_at: None,
+                expire_at: None,
+            },
+        );
+        assert_eq!(request.title, "Friday egg share");
+        assert_eq!(request.available_start, "2026-10-16T08:00:00Z");
+        assert_eq!(request.quantity_total, Decimal::new(6, 0));
+        assert_eq!(
+            request.pickup_location_text.as_deref(),
+            Some("Cooler by the gate")
+        );
+        assert!(request.harvest_id.is_none());
+    }
+}
@@ -0,0 +1,417 @@
+//! Listing status changes under `POST /listings/{listingId}/transition`.
+//! Growers move listings between the statuses they own (active, pending and
+//! completed); claimed, expired and paused are set by the claim flow, the
+//! moderation tools and away mode, and scheduled listings are published by
+//! the listing schedule worker unless the grower publishes them early.
+//! `PUT /listings/{listingId}` checks a changed status against the same
+//! rules.
+
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::events::{self, ListingEventDetail};
+use crate::handlers::co_manager;
+use crate::http_util::{json_response, parse_json_body, parse_uuid, request_locale};
+use crate::repo;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Deserialize;
+use tokio_postgres::Row;
+use tracing::{error, info};
+use uuid::Uuid;
+
+const ALLOWED_LISTING_STATUSES: [&str; 7] = [
+    "scheduled",
+    "active",
+    "pending",
+    "claimed",
+    "expired",
+    "completed",
+    "paused",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransitionListingRequest {
+    pub status: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListingStatus {
+    /// Waiting for its `publishAt`; hidden from discovery and unclaimable.
+    Scheduled,
+    Active,
+    Pending,
+    Claimed,
+    Expired,
+    Completed,
+    Paused,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ListingActorRole {
+    Owner,
+    /// An accepted co-manager acting for the owner.
+    CoManager,
+}
+
+impl ListingStatus {
+    pub fn parse(value: &str) -> Result<Self, ApiError> {
+        match value {
+            "scheduled" => Ok(Self::Scheduled),
+            "active" => Ok(Self::Active),
+            "pending" => Ok(Self::Pending),
+            "claimed" => Ok(Self::Claimed),
+            "expired" => Ok(Self::Expired),
+            "completed" => Ok(Self::Completed),
+            "paused" => Ok(Self::Paused),
+            _ => Err(ApiError::invalid_field(
+                "status",
+                "invalid_enum",
+                format!(
+                    "Invalid listing status '{}'. Allowed values: {}",
+                    value,
+                    ALLOWED_LISTING_STATUSES.join(", ")
+                ),
+            )),
+        }
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Scheduled => "scheduled",
+            Self::Active => "active",
+            Self::Pending => "pending",
+            Self::Claimed => "claimed",
+            Self::Expired => "expired",
+            Self::Completed => "completed",
+            Self::Paused => "paused",
+        }
+    }
+}
+
+impl ListingActorRole {
+    pub const fn from_delegated(delegated: bool) -> Self {
+        if delegated {
+            Self::CoManager
+        } else {
+            Self::Owner
+        }
+    }
+}
+
+/// Checks a grower-requested status change. `window_open` says whether the
+/// listing's availability window and expiry have not yet passed, which
+/// reopening an expired listing needs. Moving to the current status is a
+/// no-op.
+pub fn evaluate_transition(
+    current: ListingStatus,
+    target: ListingStatus,
+    role: ListingActorRole,
+    window_open: bool,
+) -> Result<(), ApiError> {
+    if current == target {
+        return Ok(());
+    }
+
+    if current == ListingStatus::Paused {
+        return Err(ApiError::conflict(
+            "listing_paused",
+            "Listing is paused while its grower is away; end the away period to change it",
+        ));
+    }
+
+    match (current, target) {
+        (ListingStatus::Active, ListingStatus::Pending)
+        | (ListingStatus::Pending | ListingStatus::Scheduled, ListingStatus::Active) => Ok(()),
+        (ListingStatus::Expired, ListingStatus::Active) => {
+            if window_open {
+                Ok(())
+            } else {
+                Err(ApiError::invalid_field(
+                    "status",
+                    "availability_ended",
+                    "Extend availableEnd and expireAt before reopening an expired listing",
+                ))
+            }
+        }
+        (
+            ListingStatus::Scheduled
+            | ListingStatus::Active
+            | ListingStatus::Pending
+            | ListingStatus::Claimed
+            | ListingStatus::Expired,
+            ListingStatus::Completed,
+        ) => {
+            if role != ListingActorRole::Owner {
+                return Err(ApiError::forbidden(
+                    "listing_owner_only",
+                    "Forbidden: Only the listing owner can complete a listing",
+                ));
+            }
+            Ok(())
+        }
+        _ => Err(ApiError::invalid_field(
+            "status",
+            "invalid_transition",
+            format!(
+                "Invalid listing transition from '{}' to '{}'",
+                current.as_str(),
+                target.as_str()
+            ),
+        )),
+    }
+}
+
+/// Listings without an end date or expiry stay open.
+pub fn window_open(
+    available_end: Option<DateTime<Utc>>,
+    expire_at: Option<DateTime<Utc>>,
+    now: DateTime<Utc>,
+) -> bool {
+    available_end.is_none_or(|end| end > now) && expire_at.is_none_or(|expire| expire > now)
+}
+
+pub async fn transition_listing(
+    request: &Request,
+    correlation_id: &str,
+    listing_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let id = parse_uuid(listing_id, "listingId")?;
+
+    let payload: TransitionListingRequest = parse_json_body(request)?;
+    let target = ListingStatus::parse(payload.status.trim())?;
+
+    let mut client = db::connect().await?;
+    let managed = co_manager::resolve_managed_listing(&client, id, user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))?;
+    let role = ListingActorRole::from_delegated(managed.delegated);
+
+    let tx = client.transaction().await?;
+    let current_row = tx
+        .query_opt_timed(
+            "listing_transition::transition_listing",
+            "
+            select status::text as status, available_end, expire_at
+            from surplus_listings
+            where id = $1
+              and user_id = $2
+              and deleted_at is null
+            for update
+            ",
+            &[&id, &managed.owner_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))?;
+
+    let current = ListingStatus::parse(&current_row.get::<_, String>("status"))?;
+    let available_end: Option<DateTime<Utc>> = current_row.get("available_end");
+    let expire_at: Option<DateTime<Utc>> = current_row.get("expire_at");
+    evaluate_transition(
+        current,
+        target,
+        role,
+        window_open(available_end, expire_at, Utc::now()),
+    )?;
+
+    if current == target {
+        db::commit(tx).await?;
+    } else {
+        let updated = tx
+            .query_one_timed(
+                "listing_transition::transition_listing",
+                "
+                update surplus_listings
+                set status = $1::text::listing_status
+                where id = $2
+                returning id, user_id, crop_id, status::text as status, geo_key
+                ",
+                &[&target.as_str(), &id],
+            )
+            .await?;
+
+        if managed.delegated {
+            audit::record(
+                &tx,
+                &AuditEntry {
+                    actor: Actor::from_auth(&auth_context),
+                    action: audit::LISTING_TRANSITIONED_BY_CO_MANAGER,
+                    target_type: "listing",
+                    target_id: id.to_string(),
+                    before: Some(serde_json::json!({ "status": current.as_str() })),
+                    after: Some(serde_json::json!({ "status": target.as_str() })),
+                    correlation_id,
+                },
+            )
+            .await?;
+        }
+
+        db::commit(tx).await?;
+        emit_status_changed_best_effort(&updated, current, correlation_id).await;
+    }
+
+    let listing = repo::listing::find_by_owner(
+        &client,
+        id,
+        managed.owner_id,
+        request_locale(request).as_deref(),
+    )
+    .await?
+    .ok_or_else(|| ApiError::not_found("listing_not_found", "Listing not found"))?;
+
+    info!(
+        correlation_id = correlation_id,
+        listing_id = %id,
+        actor_user_id = %user_id,
+        previous_status = current.as_str(),
+        new_status = target.as_str(),
+        "Transitioned listing status"
+    );
+
+    json_response(200, &listing)
+}
+
+/// Publishes `listing.status_changed` for a listing row carrying `id`,
+/// `user_id`, `crop_id`, `status` and `geo_key`.
+pub async fn emit_status_changed_best_effort(
+    listing_row: &Row,
+    previous: ListingStatus,
+    correlation_id: &str,
+) {
+    let listing_id: Uuid = listing_row.get("id");
+    let detail = ListingEventDetail::new(
+        listing_id.to_string(),
+        listing_row.get::<_, Uuid>("user_id").to_string(),
+        listing_row
+            .get::<_, Option<Uuid>>("crop_id")
+            .map(|id| id.to_string()),
+        listing_row.get::<_, String>("status"),
+        listing_row.get::<_, Option<String>>("geo_key"),
+        correlation_id,
+    )
+    .with_previous_status(previous.as_str().to_string());
+
+    if let Err(error) = events::publish(events::LISTING_STATUS_CHANGED, &detail).await {
+        error!(
+            correlation_id = correlation_id,
+            listing_id = %listing_id,
+            error = %error,
+            "Failed to emit listing status change after successful write"
+        );
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_round_trips_every_status() {
+        for value in ALLOWED_LISTING_STATUSES {
+            assert_eq!(ListingStatus::parse(value).unwrap().as_str(), value);
+        }
+        let error = ListingStatus::parse("archived").unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+    }
+
+    #[test]
+    fn evaluate_transition_allows_grower_moves() {
+        use ListingStatus::{Active, Claimed, Completed, Expired, Pending, Scheduled};
+        for (current, target) in [
+            (Scheduled, Active),
+            (Scheduled, Completed),
+            (Active, Pending),
+            (Pending, Active),
+            (Expired, Active),
+            (Active, Completed),
+            (Claimed, Completed),
+            (Expired, Completed),
+            (Completed, Completed),
+        ] {
+            assert!(
+                evaluate_transition(current, target, ListingActorRole::Owner, true).is_ok(),
+                "{current:?} -> {target:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn evaluate_transition_rejects_system_statuses_and_reopening_completed() {
+        use ListingStatus::{Active, Claimed, Completed, Expired, Paused, Pending, Scheduled};
+        for (current, target) in [
+            (Active, Scheduled),
+            (Scheduled, Pending),
+            (Completed, Active),
+            (Completed, Pending),
+            (Active, Claimed),
+            (Active, Expired),
+            (Pending, Paused),
+            (Claimed, Active),
+        ] {
+            let error =
+                evaluate_transition(current, target, ListingActorRole::Owner, true).unwrap_err();
+            assert_eq!(error.error_code(), "invalid_transition");
+        }
+    }
+
+    #[test]
+    fn evaluate_transition_needs_an_open_window_to_reopen() {
+        let error = evaluate_transition(
+            ListingStatus::Expired,
+            ListingStatus::Active,
+            ListingActorRole::Owner,
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(error.error_code(), "availability_ended");
+    }
+
+    #[test]
+    fn window_open_closes_at_either_end_or_expiry() {
+        let now = Utc::now();
+        let later = now + chrono::Duration::hours(1);
+        let earlier = now - chrono::Duration::hours(1);
+        assert!(window_open(None, None, now));
+        assert!(window_open(Some(later), Some(later), now));
+        assert!(!window_open(Some(earlier), None, now));
+        assert!(!window_open(Some(later), Some(earlier), now));
+    }
+
+    #[test]
+    fn evaluate_transition_leaves_completion_to_the_owner() {
+        let error = evaluate_transition(
+            ListingStatus::Active,
+            ListingStatus::Completed,
+            ListingActorRole::CoManager,
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(error.error_code(), "listing_owner_only");
+        assert!(evaluate_transition(
+            ListingStatus::Active,
+            ListingStatus::Pending,
+            ListingActorRole::CoManager,
+            true,
+        )
+        .is_ok());
+    }
+
+    #[test]
+    fn evaluate_transition_refuses_paused_listings() {
+        let error = evaluate_transition(
+            ListingStatus::Paused,
+            ListingStatus::Active,
+            ListingActorRole::Owner,
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(error.error_code(), "listing_paused");
+    }
+}
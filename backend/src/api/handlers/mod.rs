@@ -1,14 +1,49 @@
+pub mod admin_moderation;
+pub mod admin_ops;
+pub mod admin_signals;
 pub mod agent_task;
 pub mod ai_copilot;
+pub mod ai_usage;
 pub mod analytics;
+pub mod announcement;
+pub mod api_key;
+pub mod audit_log;
+pub mod away;
 pub mod billing;
 pub mod catalog;
 pub mod claim;
 pub mod claim_read;
+pub mod co_manager;
+pub mod community_event;
+pub mod conversation;
 pub mod crop;
+pub mod crop_plan;
+pub mod dashboard;
+pub mod delivery;
+pub mod donation_receipt;
 pub mod feed;
+pub mod feed_feedback;
+pub mod follow;
+pub mod garden;
+pub mod group;
+pub mod harvest;
+pub mod health;
+pub mod impersonation;
 pub mod listing;
 pub mod listing_discovery;
+pub mod listing_feed;
+pub mod listing_limits;
+pub mod listing_question;
+pub mod listing_template;
+pub mod listing_transition;
+pub mod organization;
+pub mod organization_webhook;
+pub mod planting;
 pub mod reminder;
 pub mod request;
+pub mod request_template;
+pub mod schedule;
+pub mod search;
+pub mod stats;
+pub mod suggested_listing;
 pub mod user;
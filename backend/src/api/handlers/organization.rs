@@ -0,0 +1,555 @@
+use crate::audit::{self, Actor, AuditEntry};
+use crate::auth::{extract_auth_context, AuthContext};
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::http_util::{json_response, parse_json_body};
+use crate::service_area::{self, Anchor, GeoSetError, PolygonError, Vertex};
+use lambda_http::{Body, Request, Response};
+use serde::{Deserialize, Serialize};
+use tokio_postgres::{Client, GenericClient};
+use uuid::Uuid;
+
+const MAX_SERVICE_AREAS: usize = 20;
+const MAX_SERVICE_AREA_NAME_CHARS: usize = 100;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrganizationResponse {
+    pub id: String,
+    pub name: String,
+    pub service_user_id: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReplaceServiceAreasRequest {
+    pub areas: Vec<ServiceAreaInput>,
+}
+
+/// One area: exactly one of `geoKeys` or `polygon`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAreaInput {
+    pub name: String,
+    pub geo_keys: Option<Vec<String>>,
+    pub polygon: Option<Vec<Vertex>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAreasResponse {
+    pub organization_id: String,
+    pub areas: Vec<ServiceAreaResponse>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceAreaResponse {
+    pub id: String,
+    pub name: String,
+    pub kind: String,
+    pub geo_keys: Option<Vec<String>>,
+    pub polygon: Option<Vec<Vertex>>,
+    /// Where requests filed for the organization are placed when this is its
+    /// first area.
+    pub anchor_geo_key: String,
+    /// Number of geohash cells the area is matched against.
+    pub cell_count: i64,
+}
+
+/// A validated area ready to store.
+#[derive(Debug)]
+struct NormalizedServiceArea {
+    name: String,
+    shape: ServiceAreaShape,
+    cells: Vec<String>,
+    anchor: Anchor,
+}
+
+#[derive(Debug)]
+enum ServiceAreaShape {
+    Polygon(Vec<Vertex>),
+    GeoSet(Vec<String>),
+}
+
+impl ServiceAreaShape {
+    const fn kind(&self) -> &'static str {
+        match self {
+            Self::Polygon(_) => "polygon",
+            Self::GeoSet(_) => "geo_set",
+        }
+    }
+}
+
+/// Creates a partner organization together with the non-human service user
+/// its API keys act as. The service user is a gatherer so it can file
+/// requests and claims through the existing ownership rules.
+pub async fn create_organization(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let admin_id = Uuid::parse_str(&auth.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+
+    let payload: CreateOrganizationRequest = parse_json_body(request)?;
+    let name = payload.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::invalid_field(
+            "name",
+            "required",
+            "name is required",
+        ));
+    }
+
+    let mut client = db::connect().await?;
+    let transaction = client.transaction().await?;
+
+    let service_user_id = Uuid::new_v4();
+    transaction
+        .execute_timed(
+            "organization::create_organization",
+            "
+            insert into users (id, display_name, user_type, onboarding_completed)
+            values ($1, $2, 'gatherer', true)
+            ",
+            &[&service_user_id, &name],
+        )
+        .await?;
+
+    let row = transaction
+        .query_one_timed(
+            "organization::create_organization",
+            "
+            insert into organizations (name, service_user_id, created_by)
+            values ($1, $2, $3)
+            returning id, name, service_user_id, created_at
+            ",
+            &[&name, &service_user_id, &admin_id],
+        )
+        .await?;
+
+    let response = OrganizationResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        name: row.get("name"),
+        service_user_id: row.get::<_, Uuid>("service_user_id").to_string(),
+        created_at: row
+            .get::<_, chrono::DateTime<chrono::Utc>>("created_at")
+            .to_rfc3339(),
+    };
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::ORGANIZATION_CREATED,
+            target_type: "organization",
+            target_id: response.id.clone(),
+            before: None,
+            after: serde_json::to_value(&response).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        admin_id = %admin_id,
+        organization_id = response.id.as_str(),
+        "Created partner organization"
+    );
+
+    json_response(201, &response)
+}
+
+/// Lists the calling organization's service areas in order.
+pub async fn get_service_areas(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let client = db::connect().await?;
+    let organization_id = resolve_organization(&client, &auth).await?;
+    let areas = load_service_areas(&client, organization_id).await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        organization_id = %organization_id,
+        area_count = areas.len(),
+        "Listed organization service areas"
+    );
+
+    json_response(
+        200,
+        &ServiceAreasResponse {
+            organization_id: organization_id.to_string(),
+            areas,
+        },
+    )
+}
+
+/// Replaces the calling organization's service areas. The full set is sent
+/// each time; an empty list clears it. Each area is stored with the geohash
+/// cells covering it so listing alerts can match on a prefix.
+pub async fn replace_service_areas(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth = extract_auth_context(request)?;
+    let payload: ReplaceServiceAreasRequest = parse_json_body(request)?;
+    let areas = normalize_service_areas(&payload.areas)?;
+
+    let mut client = db::connect().await?;
+    let organization_id = resolve_organization(&client, &auth).await?;
+    let before = load_service_areas(&client, organization_id).await?;
+
+    let transaction = client.transaction().await?;
+    transaction
+        .execute_timed(
+            "organization::replace_service_areas",
+            "delete from organization_service_areas where organization_id = $1",
+            &[&organization_id],
+        )
+        .await?;
+
+    for (position, area) in areas.iter().enumerate() {
+        let position = i32::try_from(position)
+            .map_err(|_| ApiError::internal("Service area position out of range"))?;
+        let (polygon, geo_keys) = match &area.shape {
+            ServiceAreaShape::Polygon(vertices) => (serde_json::to_value(vertices).ok(), None),
+            ServiceAreaShape::GeoSet(keys) => (None, Some(keys)),
+        };
+        let area_id: Uuid = transaction
+            .query_one_timed(
+                "organization::replace_service_areas",
+                "
+                insert into organization_service_areas
+                    (organization_id, name, position, kind, polygon, geo_keys,
+                     anchor_lat, anchor_lng, anchor_geo_key)
+                values ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                returning id
+                ",
+                &[
+                    &organization_id,
+                    &area.name,
+                    &position,
+                    &area.shape.kind(),
+                    &polygon,
+                    &geo_keys,
+                    &area.anchor.lat,
+                    &area.anchor.lng,
+                    &area.anchor.geo_key,
+                ],
+            )
+            .await?
+            .get("id");
+        transaction
+            .execute_timed(
+                "organization::replace_service_areas",
+                "
+                insert into organization_service_area_cells (service_area_id, geo_prefix)
+                select $1, unnest($2::text[])
+                ",
+                &[&area_id, &area.cells],
+            )
+            .await?;
+    }
+
+    let areas = load_service_areas(&transaction, organization_id).await?;
+    let response = ServiceAreasResponse {
+        organization_id: organization_id.to_string(),
+        areas,
+    };
+
+    audit::record(
+        &transaction,
+        &AuditEntry {
+            actor: Actor::from_auth(&auth),
+            action: audit::ORGANIZATION_SERVICE_AREAS_REPLACED,
+            target_type: "organization",
+            target_id: response.organization_id.clone(),
+            before: serde_json::to_value(&before).ok(),
+            after: serde_json::to_value(&response.areas).ok(),
+            correlation_id,
+        },
+    )
+    .await?;
+
+    transaction.commit().await?;
+
+    tracing::info!(
+        correlation_id = correlation_id,
+        organization_id = %organization_id,
+        area_count = response.areas.len(),
+        "Replaced organization service areas"
+    );
+
+    json_response(200, &response)
+}
+
+/// The organization the caller acts for: the key's organization for API-key
+/// calls, otherwise the organization whose service user is signed in.
+pub async fn resolve_organization(client: &Client, auth: &AuthContext) -> Result<Uuid, ApiError> {
+    let organization_id = auth
+        .api_key
+        .as_ref()
+        .map(|principal| Uuid::parse_str(&principal.organization_id))
+        .transpose()
+        .map_err(|_| ApiError::unauthorized("Invalid organization ID format"))?;
+    let user_id = Uuid::parse_str(&auth.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+
+    let row = client
+        .query_opt_timed(
+            "organization::resolve_organization",
+            "
+            select id
+            from organizations
+            where deleted_at is null
+              and (id = $1 or ($1::uuid is null and service_user_id = $2))
+            ",
+            &[&organization_id, &user_id],
+        )
+        .await?;
+
+    row.map(|row| row.get("id")).ok_or_else(|| {
+        ApiError::not_found(
+            "organization_not_found",
+            "No partner organization is associated with this caller",
+        )
+    })
+}
+
+async fn load_service_areas<C: GenericClient + Sync>(
+    client: &C,
+    organization_id: Uuid,
+) -> Result<Vec<ServiceAreaResponse>, ApiError> {
+    let rows = client
+        .query_timed(
+            "organization::load_service_areas",
+            "
+            select a.id, a.name, a.kind, a.geo_keys, a.polygon, a.anchor_geo_key,
+                   (select count(*) from organization_service_area_cells c
+                    where c.service_area_id = a.id) as cell_count
+            from organization_service_areas a
+            where a.organization_id = $1
+            order by a.position asc
+            ",
+            &[&organization_id],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| ServiceAreaResponse {
+            id: row.get::<_, Uuid>("id").to_string(),
+            name: row.get("name"),
+            kind: row.get("kind"),
+            geo_keys: row.get("geo_keys"),
+            polygon: row
+                .get::<_, Option<serde_json::Value>>("polygon")
+                .and_then(|value| serde_json::from_value(value).ok()),
+            anchor_geo_key: row.get("anchor_geo_key"),
+            cell_count: row.get("cell_count"),
+        })
+        .collect())
+}
+
+fn normalize_service_areas(
+    areas: &[ServiceAreaInput],
+) -> Result<Vec<NormalizedServiceArea>, ApiError> {
+    let mut errors = ValidationErrors::new();
+    if areas.len() > MAX_SERVICE_AREAS {
+        errors.add(
+            "areas",
+            "too_many",
+            format!("At most {MAX_SERVICE_AREAS} service areas are allowed"),
+        );
+        errors.into_result()?;
+    }
+
+    let mut normalized = Vec::with_capacity(areas.len());
+    for (index, area) in areas.iter().enumerate() {
+        if let Some(area) = errors.capture(normalize_service_area(index, area)) {
+            normalized.push(area);
+        }
+    }
+    errors.into_result()?;
+    Ok(normalized)
+}
+
+fn normalize_service_area(
+    index: usize,
+    area: &ServiceAreaInput,
+) -> Result<NormalizedServiceArea, ApiError> {
+    let mut errors = ValidationErrors::new();
+    let field = |name: &str| format!("areas[{index}].{name}");
+
+    let name = area.name.trim();
+    if name.is_empty() {
+        errors.add(&field("name"), "required", "name is required");
+    } else if name.chars().count() > MAX_SERVICE_AREA_NAME_CHARS {
+        errors.add(
+            &field("name"),
+            "too_long",
+            format!("name must be at most {MAX_SERVICE_AREA_NAME_CHARS} characters"),
+        );
+    }
+
+    let shape = match (&area.geo_keys, &area.polygon) {
+        (Some(keys), None) => match service_area::normalize_geo_set(keys) {
+            Ok(keys) => Some(ServiceAreaShape::GeoSet(keys)),
+            Err(error) => {
+                let message = match error {
+                    GeoSetError::Empty => "geoKeys must not be empty".to_string(),
+                    GeoSetError::TooMany => format!(
+                        "geoKeys may have at most {} entries",
+                        service_area::MAX_GEO_SET_KEYS
+                    ),
+                    GeoSetError::Invalid(key) => {
+                        format!("'{key}' is not a valid geohash (1-12 chars, base32)")
+                    }
+                    GeoSetError::TooCoarse(key) => format!(
+                        "'{key}' is too coarse; geoKeys must be at least {} characters",
+                        service_area::MIN_GEO_SET_PREFIX_LEN
+                    ),
+                };
+                errors.add(&field("geoKeys"), "invalid_geo_keys", message);
+                None
+            }
+        },
+        (None, Some(polygon)) => match service_area::validate_polygon(polygon) {
+            Ok(ring) => Some(ServiceAreaShape::Polygon(ring)),
+            Err(error) => {
+                let message = match error {
+                    PolygonError::TooFewVertices => {
+                        "polygon must have at least 3 distinct vertices".to_string()
+                    }
+                    PolygonError::TooManyVertices => format!(
+                        "polygon may have at most {} vertices",
+                        service_area::MAX_POLYGON_VERTICES
+                    ),
+                    PolygonError::OutOfRange => {
+                        "polygon vertices must have lat in [-90, 90] and lng in [-180, 180]"
+                            .to_string()
+                    }
+                };
+                errors.add(&field("polygon"), "invalid_polygon", message);
+                None
+            }
+        },
+        _ => {
+            errors.add(
+                &field("geoKeys"),
+                "invalid_shape",
+                "Provide exactly one of geoKeys or polygon",
+            );
+            None
+        }
+    };
+
+    errors.into_result()?;
+    let shape = shape.ok_or_else(|| ApiError::internal("Service area shape missing"))?;
+    let (cells, anchor) = match &shape {
+        ServiceAreaShape::Polygon(ring) => (
+            service_area::polygon_covering_prefixes(ring),
+            service_area::polygon_anchor(ring),
+        ),
+        ServiceAreaShape::GeoSet(keys) => (keys.clone(), service_area::geo_set_anchor(keys)),
+    };
+    let anchor = anchor.filter(|_| !cells.is_empty()).ok_or_else(|| {
+        ApiError::invalid_field(
+            &field("polygon"),
+            "invalid_polygon",
+            "polygon does not cover any area",
+        )
+    })?;
+
+    Ok(NormalizedServiceArea {
+        name: name.to_string(),
+        shape,
+        cells,
+        anchor,
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn geo_set(name: &str, keys: &[&str]) -> ServiceAreaInput {
+        ServiceAreaInput {
+            name: name.to_string(),
+            geo_keys: Some(keys.iter().map(ToString::to_string).collect()),
+            polygon: None,
+        }
+    }
+
+    const fn vertex(lat: f64, lng: f64) -> Vertex {
+        Vertex { lat, lng }
+    }
+
+    #[test]
+    fn normalizes_geo_sets_and_polygons() {
+        let polygon = ServiceAreaInput {
+            name: " Downtown ".to_string(),
+            geo_keys: None,
+            polygon: Some(vec![
+                vertex(42.87, -78.89),
+                vertex(42.87, -78.86),
+                vertex(42.90, -78.86),
+                vertex(42.87, -78.89),
+            ]),
+        };
+        let areas =
+            normalize_service_areas(&[geo_set("North", &["DR5RU", "dr5ru7"]), polygon]).unwrap();
+
+        assert_eq!(areas[0].cells, vec!["dr5ru".to_string()]);
+        assert_eq!(areas[0].shape.kind(), "geo_set");
+        assert!(areas[0].anchor.geo_key.starts_with("dr5ru"));
+        assert_eq!(areas[1].name, "Downtown");
+        assert_eq!(areas[1].shape.kind(), "polygon");
+        assert!(!areas[1].cells.is_empty());
+    }
+
+    #[test]
+    fn rejects_ambiguous_and_invalid_areas() {
+        let both = ServiceAreaInput {
+            name: "Both".to_string(),
+            geo_keys: Some(vec!["dr5ru".to_string()]),
+            polygon: Some(vec![]),
+        };
+        let neither = ServiceAreaInput {
+            name: "Neither".to_string(),
+            geo_keys: None,
+            polygon: None,
+        };
+        for (area, code) in [
+            (both, "invalid_shape"),
+            (neither, "invalid_shape"),
+            (geo_set("", &["dr5ru"]), "required"),
+            (geo_set("Coarse", &["dr"]), "invalid_geo_keys"),
+            (geo_set("Bad", &["ab!"]), "invalid_geo_keys"),
+        ] {
+            let error = normalize_service_areas(&[area]).unwrap_err();
+            assert_eq!(error.error_code(), code);
+        }
+
+        let too_many: Vec<_> = (0..=MAX_SERVICE_AREAS)
+            .map(|_| geo_set("Area", &["dr5ru"]))
+            .collect();
+        let error = normalize_service_areas(&too_many).unwrap_err();
+        assert_eq!(error.error_code(), "too_many");
+        assert!(normalize_service_areas(&[]).unwrap().is_empty());
+    }
+}
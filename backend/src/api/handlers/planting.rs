@@ -0,0 +1,607 @@
+use crate::ai::{FulfillmentContext, SummaryGenerator};
+use crate::auth::extract_auth_context;
+use crate::config::{AiConfig, Config};
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::handlers::feed::row_to_signal;
+use crate::hardiness::{self, Hardiness};
+use crate::http_util::json_response;
+use crate::middleware::{ai_guardrails, entitlements};
+use crate::models::crop::{PlantingRecommendation, PlantingRecommendationsResponse};
+use crate::models::feed::DerivedFeedSignal;
+use crate::tips_framework::season_from_month;
+use chrono::{Datelike, Utc};
+use lambda_http::{Body, Request, Response};
+use std::collections::HashMap;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+/// Planting decisions play out over weeks, so they read the longest window.
+const SIGNAL_WINDOW_DAYS: i32 = 30;
+const MAX_RECOMMENDATIONS: usize = 10;
+const SCARCITY_WEIGHT: f64 = 0.5;
+const LIBRARY_WEIGHT: f64 = 0.3;
+const SEASON_WEIGHT: f64 = 0.2;
+/// Mid-July: frost-free seasons are treated as centred on this day of year.
+const MIDSUMMER_DAY: i32 = 196;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum SeasonFit {
+    Fits,
+    TooLate,
+    Unknown,
+}
+
+impl SeasonFit {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Fits => "fits",
+            Self::TooLate => "too_late",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    const fn weight(self) -> f64 {
+        match self {
+            Self::Fits => 1.0,
+            Self::TooLate => 0.0,
+            Self::Unknown => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+struct PlantingCandidate {
+    crop_id: String,
+    crop_name: String,
+    library_status: Option<String>,
+    scarcity_score: f64,
+    request_count: i32,
+    days_to_maturity: Option<i32>,
+}
+
+/// Ranks crops for the grower from their library, local scarcity, and how
+/// much of their zone's frost-free season is left. The ranking is always
+/// deterministic; premium growers also get a model-written summary when the
+/// AI provider is available, and a templated one otherwise.
+#[allow(clippy::too_many_lines)]
+pub async fn get_planting_recommendations(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+
+    let client = db::connect().await?;
+
+    let profile = client
+        .query_opt_timed(
+            "planting::get_planting_recommendations",
+            "select home_zone, geo_key, lat, lng from grower_profiles where user_id = $1",
+            &[&user_id],
+        )
+        .await?;
+    let climate = profile.as_ref().and_then(|row| {
+        let lat = row.get::<_, Option<f64>>("lat")?;
+        let lng = row.get::<_, Option<f64>>("lng")?;
+        hardiness::lookup(lat, lng)
+    });
+    let home_zone = profile
+        .as_ref()
+        .and_then(|row| row.get::<_, Option<String>>("home_zone"))
+        .filter(|zone| !zone.trim().is_empty())
+        .or_else(|| climate.as_ref().map(|found| found.zone.to_string()));
+    let geo_prefix = profile
+        .as_ref()
+        .and_then(|row| row.get::<_, Option<String>>("geo_key"))
+        .map(|geo_key| geo_key.trim().to_ascii_lowercase())
+        .filter(|geo_key| geo_key.len() >= 4)
+        .map(|geo_key| geo_key[..4].to_string());
+
+    let signals = match geo_prefix.as_deref() {
+        Some(prefix) => client
+            .query_timed(
+                "planting::get_planting_recommendations",
+                "
+                select
+                  geo_boundary_key,
+                  crop_id,
+                  window_days::int as window_days,
+                  listing_count,
+                  request_count,
+                  supply_quantity::text as supply_quantity,
+                  demand_quantity::text as demand_quantity,
+                  scarcity_score::float8 as scarcity_score,
+                  abundance_score::float8 as abundance_score,
+                  computed_at,
+                  expires_at
+                from list_latest_derived_supply_signals($1, $2, 1, 50, now())
+                where crop_id is not null
+                order by scarcity_score desc
+                ",
+                &[&prefix, &SIGNAL_WINDOW_DAYS],
+            )
+            .await?
+            .iter()
+            .map(row_to_signal)
+            .collect::<Vec<_>>(),
+        None => Vec::new(),
+    };
+
+    let signal_crop_ids = signals
+        .iter()
+        .filter_map(|signal| signal.crop_id.as_deref())
+        .filter_map(|crop_id| Uuid::parse_str(crop_id).ok())
+        .collect::<Vec<_>>();
+
+    let crop_rows = client
+        .query_timed(
+            "planting::get_planting_recommendations",
+            "
+            with library as (
+              select distinct on (crop_id) crop_id, variety_id, status::text as status
+              from grower_crop_library
+              where user_id = $1 and status <> 'paused'
+              order by crop_id, updated_at desc
+            )
+            select
+              c.id as crop_id,
+              c.common_name,
+              l.status as library_status,
+              p.days_to_maturity_min,
+              p.days_to_maturity_max
+            from crops c
+            left join library l on l.crop_id = c.id
+            left join lateral (
+              select cp.days_to_maturity_min, cp.days_to_maturity_max
+              from crop_profiles cp
+              where cp.crop_id = c.id
+                and (cp.variety_id is null or cp.variety_id = l.variety_id)
+              order by cp.variety_id is null
+              limit 1
+            ) p on true
+            where l.crop_id is not null or c.id = any($2)
+            ",
+            &[&user_id, &signal_crop_ids],
+        )
+        .await?;
+
+    let local_demand = local_demand_by_crop(&signals);
+    let candidates = crop_rows
+        .iter()
+        .map(|row| {
+            let crop_id = row.get::<_, Uuid>("crop_id").to_string();
+            let (scarcity_score, request_count) =
+                local_demand.get(&crop_id).copied().unwrap_or((0.0, 0));
+            PlantingCandidate {
+                crop_name: row.get("common_name"),
+                library_status: row.get("library_status"),
+                scarcity_score,
+                request_count,
+                days_to_maturity: row
+                    .get::<_, Option<i32>>("days_to_maturity_max")
+                    .or_else(|| row.get("days_to_maturity_min")),
+                crop_id,
+            }
+        })
+        .collect::<Vec<_>>();
+
+    let today = Utc::now();
+    let season = season_from_month(today.month());
+    let growing_days_remaining = season_days_remaining(
+        climate.as_ref(),
+        home_zone.as_deref(),
+        ordinal_day(today.ordinal()),
+    );
+    let frost_window = climate.as_ref().and_then(|found| found.frost_window);
+    let recommendations = rank_candidates(candidates, growing_days_remaining);
+
+    let mut source = "deterministic";
+    let mut model_id = None;
+    let mut summary = deterministic_summary(home_zone.as_deref(), season, &recommendations);
+
+    let ranked_signals = signals_for(&signals, &recommendations);
+    if let Some(prefix) = geo_prefix.as_deref() {
+        if !ranked_signals.is_empty()
+            && entitlements::require_entitlement(&client, user_id, "ai.copilot.weekly_grow_plan")
+                .await
+                .is_ok()
+        {
+            match generate_ai_summary(&client, &config.ai, user_id, prefix, &ranked_signals).await {
+                Ok(Some((text, model))) => {
+                    source = "ai";
+                    summary = text;
+                    model_id = Some(model);
+                }
+                Ok(None) => {}
+                Err(error) => {
+                    warn!(
+                        correlation_id = correlation_id,
+                        error = %error,
+                        "Planting summary generation failed; using deterministic summary"
+                    );
+                }
+            }
+        }
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = auth_context.user_id.as_str(),
+        home_zone = ?home_zone,
+        recommendation_count = recommendations.len(),
+        signal_count = signals.len(),
+        source = source,
+        "Returned planting recommendations"
+    );
+
+    let response = PlantingRecommendationsResponse {
+        home_zone,
+        season: season.to_string(),
+        growing_days_remaining,
+        last_spring_frost: frost_window.map(hardiness::FrostWindow::last_spring_frost),
+        first_fall_frost: frost_window.map(hardiness::FrostWindow::first_fall_frost),
+        source: source.to_string(),
+        summary,
+        model_id,
+        recommendations,
+    };
+    json_response(200, &response)
+}
+
+/// Returns `None` when guardrails block the call so the caller keeps the
+/// deterministic summary without treating it as a failure.
+async fn generate_ai_summary(
+    client: &tokio_postgres::Client,
+    ai: &AiConfig,
+    user_id: Uuid,
+    geo_prefix: &str,
+    signals: &[DerivedFeedSignal],
+) -> Result<Option<(String, String)>, lambda_http::Error> {
+    let model_id = &ai.model.model_id;
+    let guardrails =
+        ai_guardrails::enforce_and_record(client, user_id, "ai.copilot.weekly_grow_plan", model_id)
+            .await?;
+    if !guardrails.allowed {
+        return Ok(None);
+    }
+
+    let artifact = SummaryGenerator::from_config(ai)
+        .generate(
+            client,
+            "ai.copilot.weekly_grow_plan",
+            geo_prefix,
+            SIGNAL_WINDOW_DAYS,
+            signals,
+            &FulfillmentContext::default(),
+        )
+        .await?;
+    Ok(Some((artifact.summary_text, artifact.model_id)))
+}
+
+/// Collapses per-geohash signals to one `(max scarcity, total requests)` pair
+/// per crop.
+fn local_demand_by_crop(signals: &[DerivedFeedSignal]) -> HashMap<String, (f64, i32)> {
+    let mut by_crop = HashMap::<String, (f64, i32)>::new();
+    for signal in signals {
+        let Some(crop_id) = signal.crop_id.as_ref() else {
+            continue;
+        };
+        let entry = by_crop.entry(crop_id.clone()).or_insert((0.0, 0));
+        entry.0 = entry.0.max(signal.scarcity_score);
+        entry.1 = entry.1.saturating_add(signal.request_count);
+    }
+    by_crop
+}
+
+/// Signals for the recommended crops only, so the model describes the plan
+/// rather than the whole area.
+fn signals_for(
+    signals: &[DerivedFeedSignal],
+    recommendations: &[PlantingRecommendation],
+) -> Vec<DerivedFeedSignal> {
+    signals
+        .iter()
+        .filter(|signal| {
+            recommendations
+                .iter()
+                .any(|rec| signal.crop_id.as_deref() == Some(rec.crop_id.as_str()))
+        })
+        .cloned()
+        .collect()
+}
+
+/// Reads the numeric part of a USDA hardiness zone such as `"8a"` or `"10b"`.
+fn parse_zone(home_zone: &str) -> Option<u8> {
+    let digits = home_zone
+        .trim()
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect::<String>();
+    digits
+        .parse::<u8>()
+        .ok()
+        .filter(|zone| (1..=13).contains(zone))
+}
+
+/// Prefers the frost dates for the grower's location, which track the local
+/// season far better than a zone average, and falls back to the zone.
+fn season_days_remaining(
+    climate: Option<&Hardiness>,
+    home_zone: Option<&str>,
+    day_of_year: i32,
+) -> Option<i32> {
+    match climate {
+        Some(Hardiness {
+            frost_window: Some(window),
+            ..
+        }) => Some(window.growing_days_remaining(day_of_year)),
+        Some(Hardiness {
+            frost_window: None, ..
+        }) => Some(365),
+        None => home_zone
+            .and_then(parse_zone)
+            .map(|zone| growing_days_remaining(zone, day_of_year)),
+    }
+}
+
+/// Typical frost-free days per zone. These are rough national averages; local
+/// microclimates vary by weeks.
+const fn frost_free_days(zone: u8) -> i32 {
+    match zone {
+        0..=2 => 90,
+        3 => 105,
+        4 => 130,
+        5 => 160,
+        6 => 185,
+        7 => 210,
+        8 => 240,
+        9 => 275,
+        _ => 365,
+    }
+}
+
+/// Days a crop planted today has before the first fall frost. Before the last
+/// spring frost the whole season is still ahead; after the first fall frost
+/// nothing is left until next year.
+fn growing_days_remaining(zone: u8, day_of_year: i32) -> i32 {
+    let season_days = frost_free_days(zone);
+    if season_days >= 365 {
+        return 365;
+    }
+    let last_spring_frost = MIDSUMMER_DAY - season_days / 2;
+    let first_fall_frost = last_spring_frost + season_days;
+    if day_of_year <= last_spring_frost {
+        season_days
+    } else {
+        (first_fall_frost - day_of_year).max(0)
+    }
+}
+
+fn ordinal_day(ordinal: u32) -> i32 {
+    i32::try_from(ordinal).unwrap_or(MIDSUMMER_DAY)
+}
+
+const fn season_fit(
+    days_to_maturity: Option<i32>,
+    growing_days_remaining: Option<i32>,
+) -> SeasonFit {
+    match (days_to_maturity, growing_days_remaining) {
+        (Some(needed), Some(remaining)) if needed <= remaining => SeasonFit::Fits,
+        (Some(_), Some(_)) => SeasonFit::TooLate,
+        _ => SeasonFit::Unknown,
+    }
+}
+
+fn rank_candidates(
+    candidates: Vec<PlantingCandidate>,
+    growing_days_remaining: Option<i32>,
+) -> Vec<PlantingRecommendation> {
+    let mut scored = candidates
+        .into_iter()
+        .map(|candidate| {
+            let fit = season_fit(candidate.days_to_maturity, growing_days_remaining);
+            let library: f64 = if candidate.library_status.is_some() {
+                1.0
+            } else {
+                0.0
+            };
+            let score = candidate.scarcity_score.clamp(0.0, 1.0).mul_add(
+                SCARCITY_WEIGHT,
+                library.mul_add(LIBRARY_WEIGHT, fit.weight() * SEASON_WEIGHT),
+            );
+            (candidate, fit, score)
+        })
+        .collect::<Vec<_>>();
+
+    scored.sort_by(|(a, _, a_score), (b, _, b_score)| {
+        b_score
+            .total_cmp(a_score)
+            .then_with(|| b.request_count.cmp(&a.request_count))
+            .then_with(|| a.crop_name.cmp(&b.crop_name))
+    });
+
+    scored
+        .into_iter()
+        .take(MAX_RECOMMENDATIONS)
+        .enumerate()
+        .map(|(index, (candidate, fit, score))| PlantingRecommendation {
+            rank: index + 1,
+            reasons: reasons_for(&candidate, fit, growing_days_remaining),
+            crop_id: candidate.crop_id,
+            crop_name: candidate.crop_name,
+            library_status: candidate.library_status,
+            scarcity_score: candidate.scarcity_score,
+            request_count: candidate.request_count,
+            days_to_maturity: candidate.days_to_maturity,
+            season_fit: fit.as_str().to_string(),
+            score: (score * 1000.0).round() / 1000.0,
+        })
+        .collect()
+}
+
+fn reasons_for(
+    candidate: &PlantingCandidate,
+    fit: SeasonFit,
+    growing_days_remaining: Option<i32>,
+) -> Vec<String> {
+    let mut reasons = Vec::new();
+    if let Some(status) = candidate.library_status.as_deref() {
+        reasons.push(format!("In your crop library ({status})"));
+    }
+    if candidate.request_count > 0 {
+        reasons.push(format!(
+            "{} neighbor requests in the last {SIGNAL_WINDOW_DAYS} days",
+            candidate.request_count
+        ));
+    }
+    match (fit, candidate.days_to_maturity, growing_days_remaining) {
+        (SeasonFit::Fits, Some(days), Some(remaining)) => reasons.push(format!(
+            "Matures in about {days} days; {remaining} frost-free days remain"
+        )),
+        (SeasonFit::TooLate, Some(days), Some(remaining)) => reasons.push(format!(
+            "Needs about {days} days but only {remaining} frost-free days remain"
+        )),
+        _ => {}
+    }
+    reasons
+}
+
+fn deterministic_summary(
+    home_zone: Option<&str>,
+    season: &str,
+    recommendations: &[PlantingRecommendation],
+) -> String {
+    let place = home_zone.map_or_else(|| "your area".to_string(), |zone| format!("zone {zone}"));
+    let top = recommendations
+        .iter()
+        .filter(|rec| rec.season_fit != SeasonFit::TooLate.as_str())
+        .take(3)
+        .map(|rec| rec.crop_name.as_str())
+        .collect::<Vec<_>>();
+
+    if top.is_empty() {
+        format!(
+            "No {season} planting picks for {place} yet. Add crops to your library or set your hardiness zone to get a plan."
+        )
+    } else {
+        format!(
+            "Top {season} picks for {place}: {}. Ranked by local demand, your crop library, and the time left before frost.",
+            top.join(", ")
+        )
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn candidate(
+        name: &str,
+        scarcity: f64,
+        library: bool,
+        maturity: Option<i32>,
+    ) -> PlantingCandidate {
+        PlantingCandidate {
+            crop_id: format!("{name}-id"),
+            crop_name: name.to_string(),
+            library_status: library.then(|| "growing".to_string()),
+            scarcity_score: scarcity,
+            request_count: 0,
+            days_to_maturity: maturity,
+        }
+    }
+
+    #[test]
+    fn parse_zone_reads_leading_number() {
+        assert_eq!(parse_zone("8a"), Some(8));
+        assert_eq!(parse_zone(" 10b "), Some(10));
+        assert_eq!(parse_zone("zone 7"), None);
+        assert_eq!(parse_zone("0"), None);
+        assert_eq!(parse_zone(""), None);
+    }
+
+    #[test]
+    fn growing_days_remaining_follows_frost_window() {
+        // Zone 7: last spring frost around day 91, first fall frost day 301.
+        assert_eq!(growing_days_remaining(7, 30), 210);
+        assert_eq!(growing_days_remaining(7, 201), 100);
+        assert_eq!(growing_days_remaining(7, 330), 0);
+        assert_eq!(growing_days_remaining(11, 330), 365);
+    }
+
+    #[test]
+    fn season_days_prefer_location_frost_dates() {
+        // Buffalo's frost window (Apr 30 - Oct 20) is shorter than the zone 6
+        // average.
+        let buffalo = hardiness::lookup(42.89, -78.88);
+        assert_eq!(
+            season_days_remaining(buffalo.as_ref(), Some("6b"), 30),
+            Some(173)
+        );
+        assert_eq!(season_days_remaining(None, Some("6b"), 30), Some(185));
+
+        let honolulu = hardiness::lookup(21.31, -157.86);
+        assert_eq!(
+            season_days_remaining(honolulu.as_ref(), None, 200),
+            Some(365)
+        );
+        assert_eq!(season_days_remaining(None, None, 200), None);
+    }
+
+    #[test]
+    fn season_fit_needs_zone_and_maturity() {
+        assert_eq!(season_fit(Some(60), Some(90)), SeasonFit::Fits);
+        assert_eq!(season_fit(Some(120), Some(90)), SeasonFit::TooLate);
+        assert_eq!(season_fit(None, Some(90)), SeasonFit::Unknown);
+        assert_eq!(season_fit(Some(60), None), SeasonFit::Unknown);
+    }
+
+    #[test]
+    fn rank_candidates_blends_scarcity_library_and_season() {
+        let ranked = rank_candidates(
+            vec![
+                candidate("Squash", 0.9, false, Some(120)),
+                candidate("Beans", 0.4, true, Some(55)),
+                candidate("Kale", 0.9, false, Some(50)),
+            ],
+            Some(80),
+        );
+
+        let names = ranked
+            .iter()
+            .map(|rec| rec.crop_name.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(names, vec!["Beans", "Kale", "Squash"]);
+        assert_eq!(ranked[0].rank, 1);
+        assert_eq!(ranked[2].season_fit, "too_late");
+        assert!((ranked[0].score - 0.7).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rank_candidates_caps_results() {
+        let candidates = (0..15)
+            .map(|index| candidate(&format!("crop-{index:02}"), 0.5, false, None))
+            .collect();
+        assert_eq!(rank_candidates(candidates, None).len(), MAX_RECOMMENDATIONS);
+    }
+
+    #[test]
+    fn deterministic_summary_skips_crops_that_miss_the_season() {
+        let ranked = rank_candidates(
+            vec![
+                candidate("Squash", 0.9, true, Some(120)),
+                candidate("Radish", 0.1, false, Some(25)),
+            ],
+            Some(40),
+        );
+        let summary = deterministic_summary(Some("6b"), "fall", &ranked);
+        assert!(summary.starts_with("Top fall picks for zone 6b: Radish."));
+
+        let empty = deterministic_summary(None, "spring", &[]);
+        assert!(empty.contains("your area"));
+    }
+}
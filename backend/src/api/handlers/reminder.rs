@@ -1,5 +1,7 @@
 use crate::auth::extract_auth_context;
-use crate::db;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, parse_json_body};
 use lambda_http::{Body, Request, Response};
 use serde::{Deserialize, Serialize};
 use tokio_postgres::Row;
@@ -45,12 +47,13 @@ pub struct ReminderListResponse {
 pub async fn list_reminders(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request)?;
     let client = db::connect().await?;
 
     let rows = client
-        .query(
+        .query_timed(
+            "reminder::list_reminders",
             "
             select id, title, reminder_type, cadence_days, start_date::text as start_date,
                    timezone, status, next_run_at, last_run_at, created_at
@@ -61,8 +64,7 @@ pub async fn list_reminders(
             ",
             &[&user_id],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     tracing::info!(
         correlation_id = correlation_id,
@@ -82,37 +84,49 @@ pub async fn list_reminders(
 pub async fn create_reminder(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request)?;
     let payload: CreateReminderRequest = parse_json_body(request)?;
 
     if payload.title.trim().is_empty() {
-        return error_response(400, "title is required");
+        return Err(ApiError::invalid_field(
+            "title",
+            "required",
+            "title is required",
+        ));
     }
 
     if !matches!(
         payload.reminder_type.as_str(),
         "watering" | "harvest" | "checkin" | "custom"
     ) {
-        return error_response(
-            400,
+        return Err(ApiError::invalid_field(
+            "reminderType",
+            "invalid_enum",
             "reminderType must be one of watering|harvest|checkin|custom",
-        );
+        ));
     }
 
     if !(1..=365).contains(&payload.cadence_days) {
-        return error_response(400, "cadenceDays must be between 1 and 365");
+        return Err(ApiError::invalid_field(
+            "cadenceDays",
+            "out_of_range",
+            "cadenceDays must be between 1 and 365",
+        ));
     }
 
-    let start_date = chrono::NaiveDate::parse_from_str(&payload.start_date, "%Y-%m-%d")
-        .map_err(|_| lambda_http::Error::from("startDate must use YYYY-MM-DD"))?;
+    let start_date =
+        chrono::NaiveDate::parse_from_str(&payload.start_date, "%Y-%m-%d").map_err(|_| {
+            ApiError::invalid_field("startDate", "invalid_date", "startDate must use YYYY-MM-DD")
+        })?;
     let timezone = payload.timezone.unwrap_or_else(|| "UTC".to_string());
 
     let next_run_at = calculate_next_run_at(start_date, payload.cadence_days);
 
     let client = db::connect().await?;
     let row = client
-        .query_one(
+        .query_one_timed(
+            "reminder::create_reminder",
             "
             insert into reminder_rules (
               user_id, title, reminder_type, cadence_days, start_date, timezone, status, next_run_at
@@ -131,8 +145,7 @@ pub async fn create_reminder(
                 &next_run_at,
             ],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     tracing::info!(
         correlation_id = correlation_id,
@@ -148,19 +161,25 @@ pub async fn update_reminder_status(
     request: &Request,
     correlation_id: &str,
     reminder_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request)?;
-    let reminder_uuid = Uuid::parse_str(reminder_id)
-        .map_err(|_| lambda_http::Error::from("Invalid reminder id"))?;
+    let reminder_uuid = Uuid::parse_str(reminder_id).map_err(|_| {
+        ApiError::invalid_field("reminderId", "invalid_uuid", "Invalid reminder id")
+    })?;
     let payload: UpdateReminderStatusRequest = parse_json_body(request)?;
 
     if !matches!(payload.status.as_str(), "active" | "paused") {
-        return error_response(400, "status must be active or paused");
+        return Err(ApiError::invalid_field(
+            "status",
+            "invalid_enum",
+            "status must be active or paused",
+        ));
     }
 
     let client = db::connect().await?;
     let row = client
-        .query_opt(
+        .query_opt_timed(
+            "reminder::update_reminder_status",
             "
             update reminder_rules
                set status = $3,
@@ -173,11 +192,13 @@ pub async fn update_reminder_status(
             ",
             &[&reminder_uuid, &user_id, &payload.status],
         )
-        .await
-        .map_err(|e| db_error(&e))?;
+        .await?;
 
     let Some(row) = row else {
-        return error_response(404, "Reminder not found");
+        return Err(ApiError::not_found(
+            "reminder_not_found",
+            "Reminder not found",
+        ));
     };
 
     tracing::info!(
@@ -238,45 +259,9 @@ fn row_to_response(row: &Row) -> ReminderResponse {
     }
 }
 
-fn extract_user_id(request: &Request) -> Result<Uuid, lambda_http::Error> {
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
     let auth = extract_auth_context(request)?;
-    Uuid::parse_str(&auth.user_id).map_err(|_| lambda_http::Error::from("Invalid user ID format"))
-}
-
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(status, &serde_json::json!({ "error": message }))
-}
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
 }
 
 #[cfg(test)]
@@ -1,10 +1,13 @@
-use crate::auth::{extract_auth_context, require_user_type, UserType};
-use crate::db;
-use crate::models::crop::ErrorResponse;
-use aws_config::BehaviorVersion;
-use aws_sdk_eventbridge::types::PutEventsRequestEntry;
+use crate::auth::{extract_auth_context, AuthContext};
+use crate::coordinate_privacy::LocatedEntity;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::events::{self, RequestEventDetail};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use crate::quantity;
 use chrono::{DateTime, Duration, Utc};
 use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
 use tokio_postgres::{Client, Row};
@@ -19,7 +22,7 @@ pub struct UpsertRequestPayload {
     pub crop_id: String,
     pub variety_id: Option<String>,
     pub unit: Option<String>,
-    pub quantity: f64,
+    pub quantity: Decimal,
     pub needed_by: String,
     pub notes: Option<String>,
     pub status: Option<String>,
@@ -30,7 +33,7 @@ struct NormalizedRequestInput {
     crop_id: Uuid,
     variety_id: Option<Uuid>,
     unit: Option<String>,
-    quantity: f64,
+    quantity: Decimal,
     needed_by: DateTime<Utc>,
     notes: Option<String>,
     status: Option<String>,
@@ -64,14 +67,23 @@ pub struct RequestWriteResponse {
 pub async fn create_request(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let auth_context = extract_auth_context(request)?;
-    require_user_type(&auth_context, &UserType::Gatherer)?;
+    let payload: UpsertRequestPayload = parse_json_body(request)?;
+    create_request_from_payload(request, &auth_context, &payload, correlation_id).await
+}
 
+/// Everything after the body is parsed, so requests built from a template
+/// go through the same checks and idempotency as `POST /requests`.
+pub async fn create_request_from_payload(
+    request: &Request,
+    auth_context: &AuthContext,
+    payload: &UpsertRequestPayload,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
-    let payload: UpsertRequestPayload = parse_json_body(request)?;
-    let normalized = normalize_payload(&payload)?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let normalized = normalize_payload(payload)?;
     let idempotency_key = extract_idempotency_key(request);
     let request_id = idempotency_key.as_deref().map_or_else(Uuid::new_v4, |key| {
         derive_deterministic_request_id(user_id, key)
@@ -86,7 +98,7 @@ pub async fn create_request(
     let geo_context = load_gatherer_geo_context(&client, user_id).await?;
 
     let maybe_inserted_row = client
-        .query_opt(
+        .query_opt_timed("request::create_request", 
             "
             insert into requests
                 (id, user_id, crop_id, variety_id, unit, quantity, needed_by, notes, geo_key, lat, lng, status)
@@ -113,14 +125,14 @@ pub async fn create_request(
                 &status,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     let (row, is_new_row) = if let Some(inserted_row) = maybe_inserted_row {
         (inserted_row, true)
     } else {
         let existing_row = client
-            .query_opt(
+            .query_opt_timed(
+                "request::create_request",
                 "
                 select id, user_id, crop_id, variety_id, unit,
                        quantity::text as quantity,
@@ -133,16 +145,18 @@ pub async fn create_request(
                 ",
                 &[&request_id, &user_id],
             )
-            .await
-            .map_err(|error| db_error(&error))?;
+            .await?;
         let Some(existing_row) = existing_row else {
-            return error_response(409, "Idempotency key collision with an existing request");
+            return Err(ApiError::conflict(
+                "idempotency_key_conflict",
+                "Idempotency key collision with an existing request",
+            ));
         };
         (existing_row, false)
     };
 
     if is_new_row {
-        emit_request_event_best_effort("request.created", &row, correlation_id).await;
+        emit_request_event_best_effort(events::REQUEST_CREATED, &row, correlation_id).await;
     }
 
     info!(
@@ -160,12 +174,11 @@ pub async fn update_request(
     request: &Request,
     correlation_id: &str,
     request_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let auth_context = extract_auth_context(request)?;
-    require_user_type(&auth_context, &UserType::Gatherer)?;
 
     let user_id = Uuid::parse_str(&auth_context.user_id)
-        .map_err(|_| lambda_http::Error::from("Invalid user ID format"))?;
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
     let id = parse_uuid(request_id, "requestId")?;
 
     let payload: UpsertRequestPayload = parse_json_body(request)?;
@@ -176,7 +189,8 @@ pub async fn update_request(
     let geo_context = load_gatherer_geo_context(&client, user_id).await?;
 
     let maybe_row = client
-        .query_opt(
+        .query_opt_timed(
+            "request::update_request",
             "
             update requests
             set crop_id = $1,
@@ -212,11 +226,10 @@ pub async fn update_request(
                 &user_id,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if let Some(row) = maybe_row {
-        emit_request_event_best_effort("request.updated", &row, correlation_id).await;
+        emit_request_event_best_effort(events::REQUEST_UPDATED, &row, correlation_id).await;
 
         info!(
             correlation_id = correlation_id,
@@ -228,51 +241,89 @@ pub async fn update_request(
         return json_response(200, &row_to_write_response(&row));
     }
 
-    error_response(404, "Request not found")
+    Err(ApiError::not_found(
+        "request_not_found",
+        "Request not found",
+    ))
 }
 
-fn normalize_payload(
-    payload: &UpsertRequestPayload,
-) -> Result<NormalizedRequestInput, lambda_http::Error> {
-    if payload.quantity <= 0.0 {
-        return Err(lambda_http::Error::from("quantity must be greater than 0"));
+fn normalize_payload(payload: &UpsertRequestPayload) -> Result<NormalizedRequestInput, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let quantity = errors.capture(quantity::validate(payload.quantity, "quantity"));
+    if quantity.is_some_and(|value| value <= Decimal::ZERO) {
+        errors.add(
+            "quantity",
+            "must_be_positive",
+            "quantity must be greater than 0",
+        );
     }
 
     let now = Utc::now();
-    let needed_by = parse_datetime(&payload.needed_by, "neededBy")?;
-    if needed_by < now {
-        return Err(lambda_http::Error::from(
-            "neededBy must be a current or future timestamp",
-        ));
-    }
-    if needed_by > now + Duration::days(365) {
-        return Err(lambda_http::Error::from(
-            "neededBy must be within the next 365 days",
-        ));
+    let needed_by = errors.capture(parse_datetime(&payload.needed_by, "neededBy"));
+    if let Some(needed_by) = needed_by {
+        if needed_by < now {
+            errors.add(
+                "neededBy",
+                "out_of_range",
+                "neededBy must be a current or future timestamp",
+            );
+        } else if needed_by > now + Duration::days(365) {
+            errors.add(
+                "neededBy",
+                "out_of_range",
+                "neededBy must be within the next 365 days",
+            );
+        }
     }
 
     let status = payload.status.clone();
     if let Some(status_value) = &status {
         if !ALLOWED_REQUEST_STATUS.contains(&status_value.as_str()) {
-            return Err(lambda_http::Error::from(format!(
-                "Invalid status '{}'. Allowed values: {}",
-                status_value,
-                ALLOWED_REQUEST_STATUS.join(", ")
-            )));
+            errors.add(
+                "status",
+                "invalid_enum",
+                format!(
+                    "Invalid status '{}'. Allowed values: {}",
+                    status_value,
+                    ALLOWED_REQUEST_STATUS.join(", ")
+                ),
+            );
         }
     }
 
+    let crop_id = errors.capture(parse_uuid(&payload.crop_id, "cropId"));
+    let variety_id = errors.capture(parse_optional_uuid(
+        payload.variety_id.as_deref(),
+        "varietyId",
+    ));
+
+    errors.into_result()?;
+    let (Some(crop_id), Some(variety_id), Some(needed_by), Some(quantity)) =
+        (crop_id, variety_id, needed_by, quantity)
+    else {
+        return Err(ApiError::internal(
+            "request validation passed with missing fields",
+        ));
+    };
+
     Ok(NormalizedRequestInput {
-        crop_id: parse_uuid(&payload.crop_id, "cropId")?,
-        variety_id: parse_optional_uuid(payload.variety_id.as_deref(), "varietyId")?,
+        crop_id,
+        variety_id,
         unit: normalize_optional_text(payload.unit.as_deref()),
-        quantity: payload.quantity,
+        quantity,
         needed_by,
         notes: normalize_optional_text(payload.notes.as_deref()),
         status,
     })
 }
 
+/// Runs the field checks of a create without touching the database, for
+/// payloads that are stored now and turned into requests later.
+pub fn validate_payload(payload: &UpsertRequestPayload) -> Result<(), ApiError> {
+    normalize_payload(payload).map(|_| ())
+}
+
 fn extract_idempotency_key(request: &Request) -> Option<String> {
     request
         .headers()
@@ -311,9 +362,10 @@ fn normalize_optional_text(value: Option<&str>) -> Option<String> {
 async fn load_gatherer_geo_context(
     client: &Client,
     user_id: Uuid,
-) -> Result<GathererGeoContext, lambda_http::Error> {
+) -> Result<GathererGeoContext, ApiError> {
     let row = client
-        .query_opt(
+        .query_opt_timed(
+            "request::load_gatherer_geo_context",
             "
             select geo_key, lat, lng
             from gatherer_profiles
@@ -321,8 +373,7 @@ async fn load_gatherer_geo_context(
             ",
             &[&user_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if let Some(gatherer) = row {
         return Ok(GathererGeoContext {
@@ -332,8 +383,34 @@ async fn load_gatherer_geo_context(
         });
     }
 
-    Err(lambda_http::Error::from(
-        "Gatherer profile location is required before managing requests".to_string(),
+    // Organization service users have no gatherer profile; their requests
+    // are placed at the anchor of the organization's first service area.
+    let area = client
+        .query_opt_timed(
+            "request::load_gatherer_geo_context",
+            "
+            select a.anchor_geo_key, a.anchor_lat, a.anchor_lng
+            from organizations o
+            join organization_service_areas a on a.organization_id = o.id
+            where o.service_user_id = $1 and o.deleted_at is null
+            order by a.position asc
+            limit 1
+            ",
+            &[&user_id],
+        )
+        .await?;
+
+    if let Some(area) = area {
+        return Ok(GathererGeoContext {
+            geo_key: area.get("anchor_geo_key"),
+            lat: area.get("anchor_lat"),
+            lng: area.get("anchor_lng"),
+        });
+    }
+
+    Err(ApiError::bad_request(
+        "gatherer_location_required",
+        "Gatherer profile location is required before managing requests",
     ))
 }
 
@@ -341,35 +418,39 @@ async fn validate_catalog_links(
     client: &Client,
     crop_id: Uuid,
     variety_id: Option<Uuid>,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let crop_exists = client
-        .query_one(
+        .query_one_timed(
+            "request::validate_catalog_links",
             "select exists(select 1 from crops where id = $1)",
             &[&crop_id],
         )
-        .await
-        .map_err(|error| db_error(&error))?
+        .await?
         .get::<_, bool>(0);
 
     if !crop_exists {
-        return Err(lambda_http::Error::from(
-            "cropId does not reference an existing catalog crop".to_string(),
+        return Err(ApiError::invalid_field(
+            "cropId",
+            "unknown_crop",
+            "cropId does not reference an existing catalog crop",
         ));
     }
 
     if let Some(variety) = variety_id {
         let matches = client
-            .query_one(
+            .query_one_timed(
+                "request::validate_catalog_links",
                 "select exists(select 1 from crop_varieties where id = $1 and crop_id = $2)",
                 &[&variety, &crop_id],
             )
-            .await
-            .map_err(|error| db_error(&error))?
+            .await?
             .get::<_, bool>(0);
 
         if !matches {
-            return Err(lambda_http::Error::from(
-                "varietyId must belong to the specified cropId".to_string(),
+            return Err(ApiError::invalid_field(
+                "varietyId",
+                "variety_crop_mismatch",
+                "varietyId must belong to the specified cropId",
             ));
         }
     }
@@ -382,40 +463,16 @@ async fn emit_request_event(
     request_row: &Row,
     correlation_id: &str,
 ) -> Result<(), lambda_http::Error> {
-    let event_bus_name = std::env::var("EVENT_BUS_NAME").unwrap_or_else(|_| "default".to_string());
-
-    let detail = serde_json::json!({
-        "requestId": request_row.get::<_, Uuid>("id").to_string(),
-        "userId": request_row.get::<_, Uuid>("user_id").to_string(),
-        "status": request_row.get::<_, String>("status"),
-        "correlationId": correlation_id,
-        "occurredAt": Utc::now().to_rfc3339(),
-    });
-
-    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
-    let client = aws_sdk_eventbridge::Client::new(&config);
-
-    let entry = PutEventsRequestEntry::builder()
-        .event_bus_name(event_bus_name)
-        .source("community-garden.api")
-        .detail_type(detail_type)
-        .detail(detail.to_string())
-        .build();
-
-    let response = client
-        .put_events()
-        .entries(entry)
-        .send()
-        .await
-        .map_err(|e| lambda_http::Error::from(format!("Failed to emit request event: {e}")))?;
-
-    if response.failed_entry_count() > 0 {
-        return Err(lambda_http::Error::from(
-            "Failed to emit request event: one or more entries were rejected",
-        ));
-    }
+    let detail = RequestEventDetail::new(
+        request_row.get::<_, Uuid>("id").to_string(),
+        request_row.get::<_, Uuid>("user_id").to_string(),
+        request_row.get::<_, Uuid>("crop_id").to_string(),
+        request_row.get::<_, String>("status"),
+        request_row.get::<_, Option<String>>("geo_key"),
+        correlation_id,
+    );
 
-    Ok(())
+    events::publish(detail_type, &detail).await
 }
 
 async fn emit_request_event_best_effort(
@@ -434,40 +491,23 @@ async fn emit_request_event_best_effort(
     }
 }
 
-fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, lambda_http::Error> {
-    Uuid::parse_str(value)
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid UUID")))
-}
-
-fn parse_optional_uuid(
-    value: Option<&str>,
-    field_name: &str,
-) -> Result<Option<Uuid>, lambda_http::Error> {
+fn parse_optional_uuid(value: Option<&str>, field_name: &str) -> Result<Option<Uuid>, ApiError> {
     value.map_or(Ok(None), |v| parse_uuid(v, field_name).map(Some))
 }
 
-fn parse_datetime(value: &str, field_name: &str) -> Result<DateTime<Utc>, lambda_http::Error> {
+fn parse_datetime(value: &str, field_name: &str) -> Result<DateTime<Utc>, ApiError> {
     let parsed = DateTime::parse_from_rfc3339(value).map_err(|_| {
-        lambda_http::Error::from(format!("{field_name} must be a valid RFC3339 timestamp"))
+        ApiError::invalid_field(
+            field_name,
+            "invalid_timestamp",
+            format!("{field_name} must be a valid RFC3339 timestamp"),
+        )
     })?;
     Ok(parsed.with_timezone(&Utc))
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
 fn row_to_write_response(row: &Row) -> RequestWriteResponse {
+    let (lat, lng) = LocatedEntity::Request.optional_point(row, "lat", "lng");
     RequestWriteResponse {
         id: row.get::<_, Uuid>("id").to_string(),
         user_id: row.get::<_, Uuid>("user_id").to_string(),
@@ -480,40 +520,13 @@ fn row_to_write_response(row: &Row) -> RequestWriteResponse {
         needed_by: row.get::<_, DateTime<Utc>>("needed_by").to_rfc3339(),
         notes: row.get("notes"),
         geo_key: row.get("geo_key"),
-        lat: row.get("lat"),
-        lng: row.get("lng"),
+        lat,
+        lng,
         status: row.get("status"),
         created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
     }
 }
 
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
-pub fn error_response(status: u16, message: &str) -> Result<Response<Body>, lambda_http::Error> {
-    json_response(
-        status,
-        &ErrorResponse {
-            error: message.to_string(),
-        },
-    )
-}
-
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
@@ -524,7 +537,7 @@ mod tests {
             crop_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
             variety_id: None,
             unit: Some("lb".to_string()),
-            quantity: 12.5,
+            quantity: Decimal::new(125, 1),
             needed_by: (Utc::now() + Duration::days(2)).to_rfc3339(),
             notes: Some("Need for Saturday pickup".to_string()),
             status: Some("open".to_string()),
@@ -536,7 +549,7 @@ mod tests {
         let payload = valid_payload();
         let normalized = normalize_payload(&payload).unwrap();
         assert_eq!(normalized.status.as_deref(), Some("open"));
-        assert!((normalized.quantity - 12.5).abs() < f64::EPSILON);
+        assert_eq!(normalized.quantity, Decimal::new(125, 1));
         assert_eq!(normalized.unit.as_deref(), Some("lb"));
     }
 
@@ -551,7 +564,7 @@ mod tests {
     #[test]
     fn normalize_payload_rejects_non_positive_quantity() {
         let mut payload = valid_payload();
-        payload.quantity = 0.0;
+        payload.quantity = Decimal::ZERO;
         let result = normalize_payload(&payload);
         assert!(result.is_err());
         assert!(result.unwrap_err().to_string().contains("quantity"));
@@ -575,6 +588,27 @@ mod tests {
         assert!(result.unwrap_err().to_string().contains("Invalid status"));
     }
 
+    #[test]
+    fn normalize_payload_rejects_excess_precision() {
+        let mut payload = valid_payload();
+        payload.quantity = Decimal::new(100_005, 4);
+        let error = normalize_payload(&payload).unwrap_err();
+        assert_eq!(error.error_code(), "too_many_decimal_places");
+    }
+
+    #[test]
+    fn normalize_payload_reports_quantity_and_crop_together() {
+        let mut payload = valid_payload();
+        payload.quantity = Decimal::NEGATIVE_ONE;
+        payload.crop_id = "not-a-uuid".to_string();
+
+        let error = normalize_payload(&payload).unwrap_err();
+        assert_eq!(error.error_code(), "validation_failed");
+        let message = error.to_string();
+        assert!(message.contains("quantity must be greater than 0"));
+        assert!(message.contains("cropId must be a valid UUID"));
+    }
+
     #[test]
     fn derive_deterministic_request_id_is_stable_per_user_and_key() {
         let user_id = Uuid::parse_str("6b7a6e9d-e31d-4ac2-b688-15f0490adf9b").unwrap();
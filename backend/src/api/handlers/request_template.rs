@@ -0,0 +1,368 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::handlers::request::{self, UpsertRequestPayload};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
+use chrono::{DateTime, Duration, Utc};
+use lambda_http::{Body, Request, Response};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio_postgres::Row;
+use tracing::info;
+use uuid::Uuid;
+
+const MAX_TEMPLATES_PER_GATHERER: i64 = 50;
+const MAX_TEMPLATE_NAME_CHARS: usize = 80;
+
+/// A request payload without its `neededBy` date or status. Stored as is and
+/// merged with the date sent to `POST /requests/from-template/{id}`.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestTemplateDefaults {
+    pub crop_id: String,
+    pub variety_id: Option<String>,
+    pub unit: Option<String>,
+    pub quantity: Decimal,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRequestTemplateRequest {
+    pub name: String,
+    pub defaults: RequestTemplateDefaults,
+}
+
+/// The date for a request made from a template. `quantity` and `notes`
+/// override the template's for this one request.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateRequestFromTemplateRequest {
+    pub needed_by: String,
+    pub quantity: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RequestTemplateResponse {
+    pub id: String,
+    pub name: String,
+    pub defaults: RequestTemplateDefaults,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListRequestTemplatesResponse {
+    pub items: Vec<RequestTemplateResponse>,
+}
+
+pub async fn list_request_templates(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+
+    let client = db::connect().await?;
+    let rows = client
+        .query_timed(
+            "request_template::list_request_templates",
+            "
+            select id, name, defaults, last_used_at, created_at
+            from request_templates
+            where user_id = $1
+            order by lower(name) asc, id asc
+            ",
+            &[&user_id],
+        )
+        .await?;
+    let items = rows
+        .iter()
+        .map(row_to_template_response)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_count = items.len(),
+        "Listed request templates"
+    );
+
+    json_response(200, &ListRequestTemplatesResponse { items })
+}
+
+/// Saves named request defaults. They are checked the way a request would
+/// be, so a template that saves can always be requested once a date is
+/// given.
+pub async fn create_request_template(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let payload: CreateRequestTemplateRequest = parse_json_body(request)?;
+    let name = validate_template(&payload, Utc::now())?;
+    let defaults = serde_json::to_value(&payload.defaults)
+        .map_err(|error| ApiError::internal(error.to_string()))?;
+
+    let client = db::connect().await?;
+    let existing: i64 = client
+        .query_one_timed(
+            "request_template::create_request_template",
+            "select count(*) from request_templates where user_id = $1",
+            &[&user_id],
+        )
+        .await?
+        .get(0);
+    if existing >= MAX_TEMPLATES_PER_GATHERER {
+        return Err(ApiError::conflict(
+            "template_limit_reached",
+            format!("A gatherer can save at most {MAX_TEMPLATES_PER_GATHERER} request templates"),
+        ));
+    }
+
+    let row = client
+        .query_opt_timed(
+            "request_template::create_request_template",
+            "
+            insert into request_templates (user_id, name, defaults)
+            values ($1, $2, $3)
+            on conflict (user_id, lower(name)) do nothing
+            returning id, name, defaults, last_used_at, created_at
+            ",
+            &[&user_id, &name, &defaults],
+        )
+        .await?
+        .ok_or_else(|| {
+            ApiError::conflict(
+                "template_name_taken",
+                "You already have a request template with this name",
+            )
+        })?;
+    let response = row_to_template_response(&row)?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_id = response.id.as_str(),
+        "Created request template"
+    );
+
+    json_response(201, &response)
+}
+
+pub async fn delete_request_template(
+    request: &Request,
+    correlation_id: &str,
+    template_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let id = parse_uuid(template_id, "templateId")?;
+
+    let client = db::connect().await?;
+    let deleted = client
+        .execute_timed(
+            "request_template::delete_request_template",
+            "delete from request_templates where id = $1 and user_id = $2",
+            &[&id, &user_id],
+        )
+        .await?;
+    if deleted == 0 {
+        return Err(template_not_found());
+    }
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_id = %id,
+        "Deleted request template"
+    );
+
+    Response::builder()
+        .status(204)
+        .body(Body::Empty)
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+/// Creates a request from a saved template plus the `neededBy` in the body.
+/// The request is built and checked exactly as `POST /requests` would,
+/// including `Idempotency-Key`.
+pub async fn create_request_from_template(
+    request: &Request,
+    correlation_id: &str,
+    template_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let id = parse_uuid(template_id, "templateId")?;
+    let date: CreateRequestFromTemplateRequest = parse_json_body(request)?;
+
+    let client = db::connect().await?;
+    let row = client
+        .query_opt_timed(
+            "request_template::create_request_from_template",
+            "
+            update request_templates
+            set last_used_at = now()
+            where id = $1 and user_id = $2
+            returning defaults
+            ",
+            &[&id, &user_id],
+        )
+        .await?
+        .ok_or_else(template_not_found)?;
+    let defaults: RequestTemplateDefaults =
+        serde_json::from_value(row.get("defaults")).map_err(|error| {
+            ApiError::internal(format!("Stored request template is unreadable: {error}"))
+        })?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        template_id = %id,
+        "Creating request from template"
+    );
+
+    let payload = request_payload(defaults, date);
+    request::create_request_from_payload(request, &auth_context, &payload, correlation_id).await
+}
+
+fn request_payload(
+    defaults: RequestTemplateDefaults,
+    date: CreateRequestFromTemplateRequest,
+) -> UpsertRequestPayload {
+    UpsertRequestPayload {
+        crop_id: defaults.crop_id,
+        variety_id: defaults.variety_id,
+        unit: defaults.unit,
+        quantity: date.quantity.unwrap_or(defaults.quantity),
+        needed_by: date.needed_by,
+        notes: date.notes.or(defaults.notes),
+        status: None,
+    }
+}
+
+/// Checks the name and runs the request checks against a placeholder
+/// `neededBy` a day out. Returns the trimmed name.
+fn validate_template(
+    payload: &CreateRequestTemplateRequest,
+    now: DateTime<Utc>,
+) -> Result<String, ApiError> {
+    let mut errors = ValidationErrors::new();
+
+    let name = payload.name.trim();
+    if name.is_empty() {
+        errors.add("name", "required", "name is required");
+    } else if name.chars().count() > MAX_TEMPLATE_NAME_CHARS {
+        errors.add(
+            "name",
+            "too_long",
+            format!("name must be at most {MAX_TEMPLATE_NAME_CHARS} characters"),
+        );
+    }
+
+    let placeholder = CreateRequestFromTemplateRequest {
+        needed_by: (now + Duration::days(1)).to_rfc3339(),
+        quantity: None,
+        notes: None,
+    };
+    errors.capture(request::validate_payload(&request_payload(
+        payload.defaults.clone(),
+        placeholder,
+    )));
+
+    errors.into_result()?;
+    Ok(name.to_string())
+}
+
+fn row_to_template_response(row: &Row) -> Result<RequestTemplateResponse, ApiError> {
+    let defaults = serde_json::from_value(row.get("defaults")).map_err(|error| {
+        ApiError::internal(format!("Stored request template is unreadable: {error}"))
+    })?;
+    Ok(RequestTemplateResponse {
+        id: row.get::<_, Uuid>("id").to_string(),
+        name: row.get("name"),
+        defaults,
+        last_used_at: row
+            .get::<_, Option<DateTime<Utc>>>("last_used_at")
+            .map(|value| value.to_rfc3339()),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+    })
+}
+
+fn template_not_found() -> ApiError {
+    ApiError::not_found("request_template_not_found", "Request template not found")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn defaults() -> RequestTemplateDefaults {
+        RequestTemplateDefaults {
+            crop_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
+            variety_id: None,
+            unit: Some("lb".to_string()),
+            quantity: Decimal::new(40, 0),
+            notes: Some("Onions and carrots for the Thursday soup".to_string()),
+        }
+    }
+
+    fn template(name: &str, defaults: RequestTemplateDefaults) -> CreateRequestTemplateRequest {
+        CreateRequestTemplateRequest {
+            name: name.to_string(),
+            defaults,
+        }
+    }
+
+    #[test]
+    fn validate_template_trims_the_name() {
+        let name =
+            validate_template(&template(" Weekly soup order ", defaults()), Utc::now()).unwrap();
+        assert_eq!(name, "Weekly soup order");
+    }
+
+    #[test]
+    fn validate_template_applies_request_rules() {
+        let error = validate_template(&template(" ", defaults()), Utc::now()).unwrap_err();
+        assert_eq!(error.error_code(), "required");
+
+        let mut no_quantity = defaults();
+        no_quantity.quantity = Decimal::ZERO;
+        let error = validate_template(&template("Soup", no_quantity), Utc::now()).unwrap_err();
+        assert_eq!(error.error_code(), "must_be_positive");
+
+        let mut bad_crop = defaults();
+        bad_crop.crop_id = "kale".to_string();
+        let error = validate_template(&template("Soup", bad_crop), Utc::now()).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_uuid");
+    }
+
+    #[test]
+    fn request_payload_takes_the_date_and_overrides() {
+        let payload = request_payload(
+            defaults(),
+            CreateRequestFromTemplateRequest {
+                needed_by: "2026-10-22T17:00:00Z".to_string(),
+                quantity: Some(Decimal::new(25, 0)),
+                notes: None,
+            },
+        );
+        assert_eq!(payload.needed_by, "2026-10-22T17:00:00Z");
+        assert_eq!(payload.quantity, Decimal::new(25, 0));
+        assert_eq!(
+            payload.notes.as_deref(),
+            Some("Onions and carrots for the Thursday soup")
+        );
+        assert!(payload.status.is_none());
+    }
+}
@@ -0,0 +1,548 @@
+use crate::auth::extract_auth_context;
+use crate::config::Config;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use crate::signed_token;
+use chrono::{DateTime, Duration, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tokio_postgres::{Client, Row};
+use tracing::info;
+use uuid::Uuid;
+
+const FEED_PATH: &str = "/me/schedule.ics";
+const PRODID: &str = "-//Community Garden//Pickup Schedule//EN";
+
+/// Finished pickups stay on the calendar this long so recent history does not
+/// vanish the moment a window closes.
+const PAST_WINDOW_DAYS: i64 = 30;
+
+/// Used when a listing has a start but no end.
+const DEFAULT_EVENT_MINUTES: i64 = 60;
+
+/// RFC 5545 content lines are folded at 75 octets.
+const MAX_LINE_OCTETS: usize = 75;
+
+/// Confirmed claims on either side: pickups the caller will make and pickups
+/// from the caller's own listings.
+const CONFIRMED_CLAIM_EVENTS: &str = "
+    select c.id, l.user_id as listing_owner_id,
+           coalesce(l.title, cr.common_name, initcap(replace(l.listing_kind::text, '_', ' '))) as title,
+           c.quantity_claimed::text as quantity, l.unit,
+           coalesce(l.available_start, c.confirmed_at) as starts_at,
+           l.available_end as ends_at,
+           l.pickup_location_text, l.effective_pickup_address,
+           l.pickup_disclosure_policy::text as pickup_disclosure_policy,
+           l.pickup_notes
+    from claims c
+    join surplus_listings l on l.id = c.listing_id
+    left join crops cr on cr.id = l.crop_id
+    where c.status = 'confirmed'
+      and (c.claimer_id = $1 or l.user_id = $1)
+      and l.deleted_at is null
+      and coalesce(l.available_end, l.available_start, c.confirmed_at) >= $2
+    order by starts_at asc nulls last
+    limit 200
+";
+
+const LISTING_WINDOW_EVENTS: &str = "
+    select l.id,
+           coalesce(l.title, cr.common_name, initcap(replace(l.listing_kind::text, '_', ' '))) as title,
+           l.quantity_remaining::text as quantity, l.unit,
+           l.available_start as starts_at, l.available_end as ends_at,
+           l.pickup_location_text, l.effective_pickup_address, l.pickup_notes
+    from surplus_listings l
+    left join crops cr on cr.id = l.crop_id
+    where l.user_id = $1
+      and l.deleted_at is null
+      and l.status in ('active', 'pending', 'claimed')
+      and l.available_start is not null
+      and coalesce(l.available_end, l.available_start) >= $2
+    order by l.available_start asc
+    limit 200
+";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleLinkResponse {
+    pub token: String,
+    pub feed_path: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct CalendarEvent {
+    uid: String,
+    summary: String,
+    starts_at: DateTime<Utc>,
+    ends_at: DateTime<Utc>,
+    location: Option<String>,
+    description: Option<String>,
+}
+
+/// Returns the caller's current subscription link for `GET /me/schedule.ics`.
+pub async fn get_schedule_link(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let secret = signed_token::feed_secret(config)?;
+
+    let client = db::connect().await?;
+    let version = feed_version(&client, user_id).await?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        "Issued schedule feed link"
+    );
+
+    json_response(200, &link_response(&secret, user_id, version))
+}
+
+/// Revokes every earlier link by bumping the feed version, then returns the
+/// new link.
+pub async fn rotate_schedule_link(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let user_id = extract_user_id(request)?;
+    let secret = signed_token::feed_secret(config)?;
+
+    let client = db::connect().await?;
+    let version: i32 = client
+        .query_opt_timed(
+            "schedule::rotate_schedule_link",
+            "
+            update users
+            set schedule_feed_version = schedule_feed_version + 1
+            where id = $1 and deleted_at is null
+            returning schedule_feed_version
+            ",
+            &[&user_id],
+        )
+        .await?
+        .ok_or_else(|| ApiError::not_found("user_not_found", "User not found"))?
+        .get("schedule_feed_version");
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        version = version,
+        "Rotated schedule feed link"
+    );
+
+    json_response(200, &link_response(&secret, user_id, version))
+}
+
+/// Serves the iCalendar feed. Calendar apps cannot send bearer tokens, so the
+/// route is public and the signed `token` query parameter identifies the user.
+pub async fn get_schedule_feed(
+    request: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, ApiError> {
+    let secret = signed_token::feed_secret(config)?;
+    let token = parse_token_query(request.uri().query())?;
+    let (user_id, version) = verify_token(&secret, &token)?;
+
+    let client = db::connect().await?;
+    if feed_version(&client, user_id).await? != version {
+        return Err(invalid_token());
+    }
+
+    let now = Utc::now();
+    let since = now - Duration::days(PAST_WINDOW_DAYS);
+    let claim_rows = client
+        .query_timed(
+            "schedule::claim_events",
+            CONFIRMED_CLAIM_EVENTS,
+            &[&user_id, &since],
+        )
+        .await?;
+    let listing_rows = client
+        .query_timed(
+            "schedule::listing_events",
+            LISTING_WINDOW_EVENTS,
+            &[&user_id, &since],
+        )
+        .await?;
+
+    let mut events = claim_rows
+        .iter()
+        .filter_map(|row| claim_event(row, user_id))
+        .collect::<Vec<_>>();
+    events.extend(listing_rows.iter().filter_map(listing_event));
+    events.sort_by_key(|event| event.starts_at);
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        claim_event_count = claim_rows.len(),
+        listing_event_count = listing_rows.len(),
+        "Served schedule feed"
+    );
+
+    Response::builder()
+        .status(200)
+        .header("content-type", "text/calendar; charset=utf-8")
+        .header("content-disposition", "inline; filename=\"schedule.ics\"")
+        .header("cache-control", "private, max-age=900")
+        .body(Body::from(render_calendar(&events, now)))
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+fn claim_event(row: &Row, user_id: Uuid) -> Option<CalendarEvent> {
+    let starts_at: DateTime<Utc> = row.get::<_, Option<DateTime<Utc>>>("starts_at")?;
+    let id: Uuid = row.get("id");
+    let owner_id: Uuid = row.get("listing_owner_id");
+    let title: String = row.get("title");
+    let is_owner = owner_id == user_id;
+    let policy: String = row.get("pickup_disclosure_policy");
+
+    Some(CalendarEvent {
+        uid: format!("claim-{id}@community-garden"),
+        summary: if is_owner {
+            format!("Pickup from you: {title}")
+        } else {
+            format!("Pickup: {title}")
+        },
+        starts_at,
+        ends_at: event_end(starts_at, row.get("ends_at")),
+        location: pickup_location(
+            row.get("effective_pickup_address"),
+            row.get("pickup_location_text"),
+            is_owner || policy != "after_accepted",
+        ),
+        description: describe(
+            row.get("quantity"),
+            row.get("unit"),
+            row.get("pickup_notes"),
+        ),
+    })
+}
+
+fn listing_event(row: &Row) -> Option<CalendarEvent> {
+    let starts_at: DateTime<Utc> = row.get::<_, Option<DateTime<Utc>>>("starts_at")?;
+    let id: Uuid = row.get("id");
+    let title: String = row.get("title");
+
+    Some(CalendarEvent {
+        uid: format!("listing-{id}@community-garden"),
+        summary: format!("Available for pickup: {title}"),
+        starts_at,
+        ends_at: event_end(starts_at, row.get("ends_at")),
+        location: pickup_location(
+            row.get("effective_pickup_address"),
+            row.get("pickup_location_text"),
+            true,
+        ),
+        description: describe(
+            row.get("quantity"),
+            row.get("unit"),
+            row.get("pickup_notes"),
+        ),
+    })
+}
+
+fn event_end(starts_at: DateTime<Utc>, ends_at: Option<DateTime<Utc>>) -> DateTime<Utc> {
+    ends_at
+        .filter(|end| *end > starts_at)
+        .unwrap_or_else(|| starts_at + Duration::minutes(DEFAULT_EVENT_MINUTES))
+}
+
+/// The full address only appears once the listing's disclosure policy allows
+/// it; otherwise the grower's general pickup description is used.
+fn pickup_location(
+    address: Option<String>,
+    location_text: Option<String>,
+    reveal_address: bool,
+) -> Option<String> {
+    address
+        .filter(|_| reveal_address)
+        .or(location_text)
+        .filter(|value| !value.trim().is_empty())
+}
+
+fn describe(
+    quantity: Option<String>,
+    unit: Option<String>,
+    notes: Option<String>,
+) -> Option<String> {
+    let quantity = quantity.map(|quantity| {
+        unit.map_or_else(
+            || format!("Quantity: {quantity}"),
+            |unit| format!("Quantity: {quantity} {unit}"),
+        )
+    });
+    let parts = [quantity, notes.filter(|notes| !notes.trim().is_empty())]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+    (!parts.is_empty()).then(|| parts.join("\n"))
+}
+
+fn render_calendar(events: &[CalendarEvent], now: DateTime<Utc>) -> String {
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{PRODID}"),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Community Garden pickups".to_string(),
+    ];
+    for event in events {
+        lines.push("BEGIN:VEVENT".to_string());
+        lines.push(format!("UID:{}", event.uid));
+        lines.push(format!("DTSTAMP:{}", format_timestamp(now)));
+        lines.push(format!("DTSTART:{}", format_timestamp(event.starts_at)));
+        lines.push(format!("DTEND:{}", format_timestamp(event.ends_at)));
+        lines.push(format!("SUMMARY:{}", escape_text(&event.summary)));
+        if let Some(location) = &event.location {
+            lines.push(format!("LOCATION:{}", escape_text(location)));
+        }
+        if let Some(description) = &event.description {
+            lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+        }
+        lines.push("END:VEVENT".to_string());
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut body = String::new();
+    for line in &lines {
+        body.push_str(&fold_line(line));
+        body.push_str("\r\n");
+    }
+    body
+}
+
+fn format_timestamp(value: DateTime<Utc>) -> String {
+    value.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+fn escape_text(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace(['\n', '\r'], "\\n")
+}
+
+/// Splits a content line into 75-octet chunks without breaking a UTF-8
+/// character; continuation lines start with a single space.
+fn fold_line(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut line_octets = 0;
+    for ch in line.chars() {
+        if line_octets + ch.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            line_octets = 1;
+        }
+        folded.push(ch);
+        line_octets += ch.len_utf8();
+    }
+    folded
+}
+
+fn link_response(secret: &str, user_id: Uuid, version: i32) -> ScheduleLinkResponse {
+    let token = sign_token(secret, user_id, version);
+    ScheduleLinkResponse {
+        feed_path: format!("{FEED_PATH}?token={token}"),
+        token,
+    }
+}
+
+const TOKEN_PURPOSE: &str = "schedule-feed";
+
+fn sign_token(secret: &str, user_id: Uuid, version: i32) -> String {
+    let signature = signed_token::sign(secret, TOKEN_PURPOSE, &format!("{user_id}:{version}"));
+    format!("{user_id}.{version}.{signature}")
+}
+
+fn verify_token(secret: &str, token: &str) -> Result<(Uuid, i32), ApiError> {
+    let mut parts = token.split('.');
+    let (Some(user_id), Some(version), Some(signature), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return Err(invalid_token());
+    };
+    let user_id = Uuid::parse_str(user_id).map_err(|_| invalid_token())?;
+    let version = version.parse::<i32>().map_err(|_| invalid_token())?;
+
+    if !signed_token::verify(
+        secret,
+        TOKEN_PURPOSE,
+        &format!("{user_id}:{version}"),
+        signature,
+    ) {
+        return Err(invalid_token());
+    }
+    Ok((user_id, version))
+}
+
+fn parse_token_query(query: Option<&str>) -> Result<String, ApiError> {
+    query
+        .into_iter()
+        .flat_map(|raw| raw.split('&'))
+        .find_map(|pair| pair.strip_prefix("token="))
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .ok_or_else(|| ApiError::unauthorized("Missing schedule feed token"))
+}
+
+async fn feed_version(client: &Client, user_id: Uuid) -> Result<i32, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "schedule::feed_version",
+            "select schedule_feed_version from users where id = $1 and deleted_at is null",
+            &[&user_id],
+        )
+        .await?
+        .ok_or_else(invalid_token)?;
+    Ok(row.get("schedule_feed_version"))
+}
+
+fn invalid_token() -> ApiError {
+    ApiError::unauthorized("Invalid or revoked schedule feed token")
+}
+
+fn extract_user_id(request: &Request) -> Result<Uuid, ApiError> {
+    let auth = extract_auth_context(request)?;
+    Uuid::parse_str(&auth.user_id).map_err(|_| ApiError::unauthorized("Invalid user ID format"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    const SECRET: &str = "test-schedule-secret";
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 6, 1, hour, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn signed_token_round_trips() {
+        let user_id = Uuid::new_v4();
+        let token = sign_token(SECRET, user_id, 3);
+        assert_eq!(verify_token(SECRET, &token).unwrap(), (user_id, 3));
+    }
+
+    #[test]
+    fn verify_token_rejects_tampering_and_other_secrets() {
+        let user_id = Uuid::new_v4();
+        let token = sign_token(SECRET, user_id, 1);
+
+        let bumped = token.replacen(".1.", ".2.", 1);
+        assert!(verify_token(SECRET, &bumped).is_err());
+
+        let other_user = token.replacen(&user_id.to_string(), &Uuid::new_v4().to_string(), 1);
+        assert!(verify_token(SECRET, &other_user).is_err());
+
+        assert!(verify_token("other-secret", &token).is_err());
+        assert!(verify_token(SECRET, "not-a-token").is_err());
+        assert!(verify_token(SECRET, &format!("{token}.extra")).is_err());
+    }
+
+    #[test]
+    fn parse_token_query_requires_token() {
+        assert_eq!(
+            parse_token_query(Some("foo=1&token=abc")).unwrap(),
+            "abc".to_string()
+        );
+        assert!(parse_token_query(Some("token=")).is_err());
+        assert!(parse_token_query(None).is_err());
+    }
+
+    #[test]
+    fn link_response_embeds_token_in_feed_path() {
+        let response = link_response(SECRET, Uuid::nil(), 1);
+        assert_eq!(
+            response.feed_path,
+            format!("/me/schedule.ics?token={}", response.token)
+        );
+        let json = serde_json::to_value(&response).unwrap();
+        assert!(json["feedPath"].is_string());
+    }
+
+    #[test]
+    fn event_end_defaults_when_missing_or_inverted() {
+        assert_eq!(event_end(at(9), Some(at(11))), at(11));
+        assert_eq!(event_end(at(9), None), at(10));
+        assert_eq!(event_end(at(9), Some(at(8))), at(10));
+    }
+
+    #[test]
+    fn pickup_location_withholds_address_until_disclosed() {
+        let address = Some("12 Elm St".to_string());
+        let text = Some("Front porch".to_string());
+        assert_eq!(
+            pickup_location(address.clone(), text.clone(), true).as_deref(),
+            Some("12 Elm St")
+        );
+        assert_eq!(
+            pickup_location(address, text, false).as_deref(),
+            Some("Front porch")
+        );
+        assert_eq!(pickup_location(None, Some("  ".to_string()), true), None);
+    }
+
+    #[test]
+    fn describe_joins_quantity_and_notes() {
+        assert_eq!(
+            describe(
+                Some("2.500".to_string()),
+                Some("lb".to_string()),
+                Some("Bring a bag".to_string())
+            )
+            .as_deref(),
+            Some("Quantity: 2.500 lb\nBring a bag")
+        );
+        assert_eq!(describe(None, None, None), None);
+    }
+
+    #[test]
+    fn escape_text_escapes_rfc5545_specials() {
+        assert_eq!(
+            escape_text("Tomatoes, basil; herbs\\greens\nline"),
+            "Tomatoes\\, basil\\; herbs\\\\greens\\nline"
+        );
+    }
+
+    #[test]
+    fn fold_line_limits_octets_and_keeps_utf8_intact() {
+        let line = format!("SUMMARY:{}", "é".repeat(80));
+        let folded = fold_line(&line);
+        for physical in folded.split("\r\n") {
+            assert!(physical.len() <= MAX_LINE_OCTETS, "{physical}");
+        }
+        assert_eq!(folded.replace("\r\n ", ""), line);
+        assert_eq!(fold_line("SHORT"), "SHORT");
+    }
+
+    #[test]
+    fn render_calendar_produces_vevents_with_crlf() {
+        let events = vec![CalendarEvent {
+            uid: "claim-1@community-garden".to_string(),
+            summary: "Pickup: Tomato".to_string(),
+            starts_at: at(9),
+            ends_at: at(10),
+            location: Some("12 Elm St".to_string()),
+            description: None,
+        }];
+        let body = render_calendar(&events, at(8));
+
+        assert!(body.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(body.ends_with("END:VCALENDAR\r\n"));
+        assert!(body.contains("UID:claim-1@community-garden\r\n"));
+        assert!(body.contains("DTSTAMP:20260601T080000Z\r\n"));
+        assert!(body.contains("DTSTART:20260601T090000Z\r\nDTEND:20260601T100000Z\r\n"));
+        assert!(body.contains("LOCATION:12 Elm St\r\n"));
+        assert!(!body.contains("DESCRIPTION"));
+    }
+}
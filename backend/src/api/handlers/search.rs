@@ -0,0 +1,316 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::{json_response, percent_decode, request_locale};
+use crate::location;
+use crate::models::search::{
+    SearchCropResult, SearchGrowerResult, SearchRequestResult, SearchResponse,
+};
+use crate::repo;
+use chrono::{DateTime, Utc};
+use lambda_http::{Body, Request, Response};
+use tokio_postgres::Client;
+use tracing::info;
+use uuid::Uuid;
+
+const MIN_QUERY_CHARS: usize = 2;
+const MAX_QUERY_CHARS: usize = 100;
+const DEFAULT_BUCKET_LIMIT: i64 = 5;
+const MAX_BUCKET_LIMIT: i64 = 20;
+
+#[derive(Debug)]
+struct SearchQuery {
+    q: String,
+    geo_key: Option<String>,
+    limit: i64,
+}
+
+/// One search box across listings, open requests, catalog crops and grower
+/// display names. Each bucket is matched and limited on its own; `geoKey`
+/// scopes everything but crops, which are not tied to a place.
+pub async fn search(request: &Request, correlation_id: &str) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+    let query = parse_search_query(request.uri().query())?;
+    let pattern = contains_pattern(&query.q);
+    let geo_key = query.geo_key.as_deref();
+    let locale = request_locale(request);
+
+    let client = db::connect().await?;
+    let (listings, requests, crops, growers) = tokio::try_join!(
+        repo::listing::search_active(&client, &pattern, geo_key, query.limit, locale.as_deref()),
+        search_requests(&client, &pattern, geo_key, query.limit),
+        search_crops(&client, &query.q, &pattern, query.limit),
+        search_growers(&client, &pattern, geo_key, query.limit),
+    )?;
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = auth_context.user_id.as_str(),
+        query_chars = query.q.chars().count(),
+        geo_key = ?query.geo_key,
+        limit = query.limit,
+        listing_count = listings.len(),
+        request_count = requests.len(),
+        crop_count = crops.len(),
+        grower_count = growers.len(),
+        "Searched listings, requests, crops and growers"
+    );
+
+    json_response(
+        200,
+        &SearchResponse {
+            query: query.q,
+            geo_key: query.geo_key,
+            listings,
+            requests,
+            crops,
+            growers,
+        },
+    )
+}
+
+/// Open, undeleted requests whose crop or variety name matches.
+async fn search_requests(
+    client: &Client,
+    pattern: &str,
+    geo_prefix: Option<&str>,
+    limit: i64,
+) -> Result<Vec<SearchRequestResult>, ApiError> {
+    let geo_pattern = geo_prefix.map(|prefix| format!("{prefix}%"));
+    let rows = client
+        .query_timed(
+            "search::search_requests",
+            "
+            select r.id, r.crop_id, c.common_name as crop_name, v.name as variety_name,
+                   r.unit, r.quantity::text as quantity, r.needed_by, r.geo_key, r.created_at
+            from requests r
+            join crops c on c.id = r.crop_id
+            left join crop_varieties v on v.id = r.variety_id
+            where r.deleted_at is null
+              and r.status = 'open'
+              and ($2::text is null or r.geo_key like $2)
+              and (c.common_name ilike $1 or v.name ilike $1)
+            order by r.created_at desc, r.id desc
+            limit $3
+            ",
+            &[&pattern, &geo_pattern, &limit],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SearchRequestResult {
+            id: row.get::<_, Uuid>("id").to_string(),
+            crop_id: row.get::<_, Uuid>("crop_id").to_string(),
+            crop_name: row.get("crop_name"),
+            variety_name: row.get("variety_name"),
+            unit: row.get("unit"),
+            quantity: row.get("quantity"),
+            needed_by: row
+                .get::<_, Option<DateTime<Utc>>>("needed_by")
+                .map(|value| value.to_rfc3339()),
+            geo_key: row.get("geo_key"),
+            created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        })
+        .collect())
+}
+
+/// Catalog crops by common or scientific name, exact name matches first.
+async fn search_crops(
+    client: &Client,
+    term: &str,
+    pattern: &str,
+    limit: i64,
+) -> Result<Vec<SearchCropResult>, ApiError> {
+    let rows = client
+        .query_timed(
+            "search::search_crops",
+            "
+            select id, slug, common_name, scientific_name, category
+            from crops
+            where common_name ilike $1 or scientific_name ilike $1
+            order by lower(common_name) = lower($2) desc, common_name asc
+            limit $3
+            ",
+            &[&pattern, &term, &limit],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SearchCropResult {
+            id: row.get::<_, Uuid>("id").to_string(),
+            slug: row.get("slug"),
+            common_name: row.get("common_name"),
+            scientific_name: row.get("scientific_name"),
+            category: row.get("category"),
+        })
+        .collect())
+}
+
+/// Growers a participant could already find through their public profile:
+/// onboarded, not deleted or suspended, and with a display name to match.
+async fn search_growers(
+    client: &Client,
+    pattern: &str,
+    geo_prefix: Option<&str>,
+    limit: i64,
+) -> Result<Vec<SearchGrowerResult>, ApiError> {
+    let geo_pattern = geo_prefix.map(|prefix| format!("{prefix}%"));
+    let rows = client
+        .query_timed(
+            "search::search_growers",
+            "
+            select u.id, u.display_name, gp.home_zone
+            from users u
+            join grower_profiles gp on gp.user_id = u.id
+            where u.deleted_at is null
+              and u.suspended_at is null
+              and u.user_type = 'grower'
+              and u.display_name is not null
+              and u.display_name ilike $1
+              and ($2::text is null or gp.geo_key like $2)
+            order by u.display_name asc, u.id asc
+            limit $3
+            ",
+            &[&pattern, &geo_pattern, &limit],
+        )
+        .await?;
+
+    Ok(rows
+        .iter()
+        .map(|row| SearchGrowerResult {
+            id: row.get::<_, Uuid>("id").to_string(),
+            display_name: row.get("display_name"),
+            home_zone: row.get("home_zone"),
+        })
+        .collect())
+}
+
+/// An `ilike` pattern matching `term` anywhere, with the term's own `%`, `_`
+/// and `\` taken literally.
+fn contains_pattern(term: &str) -> String {
+    let mut pattern = String::with_capacity(term.len() + 2);
+    pattern.push('%');
+    for ch in term.chars() {
+        if matches!(ch, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(ch);
+    }
+    pattern.push('%');
+    pattern
+}
+
+fn parse_search_query(query: Option<&str>) -> Result<SearchQuery, ApiError> {
+    let mut q: Option<String> = None;
+    let mut geo_key: Option<String> = None;
+    let mut limit = DEFAULT_BUCKET_LIMIT;
+
+    for pair in query.unwrap_or_default().split('&') {
+        if pair.is_empty() {
+            continue;
+        }
+
+        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+        match key {
+            "q" => {
+                // Search boxes submit form-encoded, so `+` is a space.
+                let decoded = percent_decode(&value.replace('+', " "), "q")?;
+                let trimmed = decoded.split_whitespace().collect::<Vec<_>>().join(" ");
+                let chars = trimmed.chars().count();
+                if !(MIN_QUERY_CHARS..=MAX_QUERY_CHARS).contains(&chars) {
+                    return Err(ApiError::invalid_field(
+                        "q",
+                        "invalid_query",
+                        format!(
+                            "q must be between {MIN_QUERY_CHARS} and {MAX_QUERY_CHARS} characters"
+                        ),
+                    ));
+                }
+                q = Some(trimmed);
+            }
+            "geoKey" => {
+                let normalized = value.trim().to_ascii_lowercase();
+                if normalized.is_empty() {
+                    continue;
+                }
+                if !location::is_valid_geo_key(&normalized) {
+                    return Err(ApiError::invalid_field(
+                        "geoKey",
+                        "invalid_geo_key",
+                        "geoKey must be a valid geohash (1-12 chars, base32)",
+                    ));
+                }
+                geo_key = Some(normalized);
+            }
+            "limit" => {
+                limit = value.parse::<i64>().map_err(|_| {
+                    ApiError::invalid_field(
+                        "limit",
+                        "invalid_limit",
+                        "Invalid limit. Must be an integer",
+                    )
+                })?;
+                if !(1..=MAX_BUCKET_LIMIT).contains(&limit) {
+                    return Err(ApiError::invalid_field(
+                        "limit",
+                        "invalid_limit",
+                        format!("Invalid limit. Must be between 1 and {MAX_BUCKET_LIMIT}"),
+                    ));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let q = q.ok_or_else(|| ApiError::invalid_field("q", "invalid_query", "q is required"))?;
+
+    Ok(SearchQuery { q, geo_key, limit })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_search_query_decodes_and_defaults() {
+        let query = parse_search_query(Some("q=green+beans%21&geoKey=DR5R")).unwrap();
+        assert_eq!(query.q, "green beans!");
+        assert_eq!(query.geo_key.as_deref(), Some("dr5r"));
+        assert_eq!(query.limit, DEFAULT_BUCKET_LIMIT);
+
+        let query = parse_search_query(Some("q=%20%20kale%20&geoKey=")).unwrap();
+        assert_eq!(query.q, "kale");
+        assert!(query.geo_key.is_none());
+    }
+
+    #[test]
+    fn parse_search_query_requires_a_usable_term() {
+        for raw in [None, Some(""), Some("q="), Some("q=a"), Some("q=+k+")] {
+            assert!(parse_search_query(raw).is_err(), "{raw:?}");
+        }
+        let long = format!("q={}", "a".repeat(MAX_QUERY_CHARS + 1));
+        assert!(parse_search_query(Some(&long)).is_err());
+        assert!(parse_search_query(Some("q=%ZZ")).is_err());
+    }
+
+    #[test]
+    fn parse_search_query_validates_geo_key_and_limit() {
+        assert!(parse_search_query(Some("q=kale&geoKey=ab!")).is_err());
+        assert!(parse_search_query(Some("q=kale&limit=0")).is_err());
+        assert!(parse_search_query(Some("q=kale&limit=21")).is_err());
+        assert_eq!(
+            parse_search_query(Some("q=kale&limit=20")).unwrap().limit,
+            20
+        );
+    }
+
+    #[test]
+    fn contains_pattern_escapes_like_wildcards() {
+        assert_eq!(contains_pattern("kale"), "%kale%");
+        assert_eq!(contains_pattern("50%_off\\"), "%50\\%\\_off\\\\%");
+    }
+}
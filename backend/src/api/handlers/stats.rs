@@ -0,0 +1,308 @@
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::http_util::json_response;
+use crate::location;
+use chrono::{Datelike, Duration, NaiveDate, Utc};
+use lambda_http::{Body, Request, Response};
+use serde::Serialize;
+use tracing::info;
+
+/// Geohash precision the impact report worker rolls up to. Finer keys are
+/// truncated; coarser keys sum every report area underneath them.
+const REPORT_GEO_PRECISION: usize = 4;
+
+/// Areas with fewer active growers than this report no figures, so a single
+/// household's sharing cannot be read off a public dashboard.
+const MIN_REPORTABLE_GROWERS: i64 = 3;
+
+const ALLOWED_PERIODS: [&str; 5] = ["week", "month", "quarter", "year", "all"];
+
+/// Sums weekly impact reports under a geohash prefix. Weekly unique-grower
+/// counts cannot be de-duplicated across weeks, so the period reports the
+/// busiest week instead of a misleading sum.
+const IMPACT_TOTALS: &str = "
+    with weekly as (
+        select week_start,
+               sum(completed_claim_count)::bigint as completed_claims,
+               sum(quantity_shared) as quantity_shared,
+               sum(unique_growers)::bigint as active_growers
+        from impact_reports
+        where geo_boundary_key like $1
+          and ($2::date is null or week_start >= $2)
+        group by week_start
+    )
+    select count(*) as weeks_reported,
+           coalesce(sum(completed_claims), 0)::bigint as completed_claims,
+           coalesce(sum(quantity_shared), 0)::text as quantity_shared,
+           coalesce(max(active_growers), 0)::bigint as active_growers,
+           min(week_start) as first_week_start,
+           max(week_start) as last_week_start
+    from weekly
+";
+
+/// Produce shared per nutritional group over the same weeks, largest first.
+/// Groups are summed across report areas and weeks like the totals.
+const NUTRITION_GROUP_TOTALS: &str = "
+    select g.value->>'group' as nutrition_group,
+           sum((g.value->>'quantity')::numeric)::text as quantity,
+           sum((g.value->>'claimCount')::bigint)::bigint as claim_count
+    from impact_reports r
+    cross join lateral jsonb_array_elements(r.nutrition_groups) g
+    where r.geo_boundary_key like $1
+      and ($2::date is null or r.week_start >= $2)
+    group by 1
+    order by sum((g.value->>'quantity')::numeric) desc, 1
+";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ImpactPeriod {
+    Week,
+    Month,
+    Quarter,
+    Year,
+    All,
+}
+
+#[derive(Debug)]
+struct ImpactStatsQuery {
+    geo_prefix: String,
+    period: ImpactPeriod,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImpactStatsResponse {
+    pub geo_key: String,
+    pub period: String,
+    pub period_start: Option<String>,
+    pub weeks_reported: i64,
+    pub suppressed: bool,
+    pub completed_claims: Option<i64>,
+    pub quantity_shared: Option<String>,
+    pub active_growers: Option<i64>,
+    pub first_week_start: Option<String>,
+    pub last_week_start: Option<String>,
+    /// Produce shared per nutritional group, for funder reporting. Crops
+    /// the catalog has not grouped are counted under `other`.
+    pub nutrition_groups: Option<Vec<NutritionGroupTotal>>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NutritionGroupTotal {
+    pub group: String,
+    pub quantity: String,
+    pub claim_count: i64,
+}
+
+/// Public, unauthenticated impact totals for embedding on community and
+/// municipal dashboards. Reads only the weekly rollups, never claims.
+pub async fn get_impact_stats(
+    request: &Request,
+    correlation_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let query = parse_impact_stats_query(request.uri().query())?;
+    let period_start = period_start(query.period, Utc::now().date_naive());
+    let geo_pattern = format!("{}%", query.geo_prefix);
+
+    let client = db::connect().await?;
+    let row = client
+        .query_one_timed(
+            "stats::get_impact_stats",
+            IMPACT_TOTALS,
+            &[&geo_pattern, &period_start],
+        )
+        .await?;
+
+    let active_growers: i64 = row.get("active_growers");
+    let suppressed = active_growers < MIN_REPORTABLE_GROWERS;
+    let nutrition_groups = if suppressed {
+        None
+    } else {
+        let rows = client
+            .query_timed(
+                "stats::get_impact_stats",
+                NUTRITION_GROUP_TOTALS,
+                &[&geo_pattern, &period_start],
+            )
+            .await?;
+        Some(
+            rows.iter()
+                .map(|row| NutritionGroupTotal {
+                    group: row.get("nutrition_group"),
+                    quantity: row.get("quantity"),
+                    claim_count: row.get("claim_count"),
+                })
+                .collect(),
+        )
+    };
+    let response = ImpactStatsResponse {
+        geo_key: query.geo_prefix,
+        period: query.period.as_str().to_string(),
+        period_start: period_start.map(|date| date.to_string()),
+        weeks_reported: row.get("weeks_reported"),
+        suppressed,
+        completed_claims: (!suppressed).then(|| row.get("completed_claims")),
+        quantity_shared: (!suppressed).then(|| row.get("quantity_shared")),
+        active_growers: (!suppressed).then_some(active_growers),
+        first_week_start: row
+            .get::<_, Option<NaiveDate>>("first_week_start")
+            .map(|date| date.to_string()),
+        last_week_start: row
+            .get::<_, Option<NaiveDate>>("last_week_start")
+            .map(|date| date.to_string()),
+        nutrition_groups,
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        geo_key = response.geo_key.as_str(),
+        period = response.period.as_str(),
+        weeks_reported = response.weeks_reported,
+        suppressed = suppressed,
+        "Served public impact statistics"
+    );
+
+    let mut http_response = json_response(200, &response)?;
+    if let Ok(value) = "public, max-age=900".parse() {
+        http_response.headers_mut().insert("cache-control", value);
+    }
+    Ok(http_response)
+}
+
+fn parse_impact_stats_query(query: Option<&str>) -> Result<ImpactStatsQuery, ApiError> {
+    let mut geo_key: Option<String> = None;
+    let mut period = ImpactPeriod::Month;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            match key {
+                "geoKey" => {
+                    let normalized = value.trim().to_ascii_lowercase();
+                    if !location::is_valid_geo_key(&normalized) {
+                        return Err(ApiError::invalid_field(
+                            "geoKey",
+                            "invalid_geo_key",
+                            "geoKey must be a valid geohash (1-12 chars, base32)",
+                        ));
+                    }
+                    geo_key = Some(normalized);
+                }
+                "period" => period = ImpactPeriod::parse(value)?,
+                _ => {}
+            }
+        }
+    }
+
+    let geo_key = geo_key.ok_or_else(|| {
+        ApiError::invalid_field("geoKey", "invalid_geo_key", "geoKey is required")
+    })?;
+    let geo_prefix = geo_key.chars().take(REPORT_GEO_PRECISION).collect();
+
+    Ok(ImpactStatsQuery { geo_prefix, period })
+}
+
+/// First report week included in `period`, counting back from the last
+/// fully completed ISO week (the newest one the worker has rolled up).
+fn period_start(period: ImpactPeriod, today: NaiveDate) -> Option<NaiveDate> {
+    let weeks = period.weeks()?;
+    let current_week_start =
+        today - Duration::days(i64::from(today.weekday().num_days_from_monday()));
+    let last_complete_week = current_week_start - Duration::weeks(1);
+    Some(last_complete_week - Duration::weeks(weeks - 1))
+}
+
+impl ImpactPeriod {
+    fn parse(value: &str) -> Result<Self, ApiError> {
+        match value {
+            "week" => Ok(Self::Week),
+            "month" => Ok(Self::Month),
+            "quarter" => Ok(Self::Quarter),
+            "year" => Ok(Self::Year),
+            "all" => Ok(Self::All),
+            _ => Err(ApiError::invalid_field(
+                "period",
+                "invalid_enum",
+                format!(
+                    "Invalid period '{}'. Allowed values: {}",
+                    value,
+                    ALLOWED_PERIODS.join(", ")
+                ),
+            )),
+        }
+    }
+
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Week => "week",
+            Self::Month => "month",
+            Self::Quarter => "quarter",
+            Self::Year => "year",
+            Self::All => "all",
+        }
+    }
+
+    const fn weeks(self) -> Option<i64> {
+        match self {
+            Self::Week => Some(1),
+            Self::Month => Some(4),
+            Self::Quarter => Some(13),
+            Self::Year => Some(52),
+            Self::All => None,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_query_truncates_geo_key_to_report_precision() {
+        let parsed = parse_impact_stats_query(Some("geoKey=9Q8YYK&period=year")).unwrap();
+        assert_eq!(parsed.geo_prefix, "9q8y");
+        assert_eq!(parsed.period, ImpactPeriod::Year);
+
+        let coarse = parse_impact_stats_query(Some("geoKey=9q")).unwrap();
+        assert_eq!(coarse.geo_prefix, "9q");
+        assert_eq!(coarse.period, ImpactPeriod::Month);
+    }
+
+    #[test]
+    fn parse_query_requires_geo_key_and_known_period() {
+        assert!(parse_impact_stats_query(None).is_err());
+        let error = parse_impact_stats_query(Some("geoKey=9q8y&period=decade")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+    }
+
+    #[test]
+    fn period_start_counts_back_from_last_complete_week() {
+        // Thursday 2026-10-15: current week began Monday 10-12, last complete
+        // week began 10-05.
+        let today = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        assert_eq!(
+            period_start(ImpactPeriod::Week, today),
+            NaiveDate::from_ymd_opt(2026, 10, 5)
+        );
+        assert_eq!(
+            period_start(ImpactPeriod::Month, today),
+            NaiveDate::from_ymd_opt(2026, 9, 14)
+        );
+        assert_eq!(period_start(ImpactPeriod::All, today), None);
+    }
+
+    #[test]
+    fn totals_query_reads_only_rollups() {
+        assert!(IMPACT_TOTALS.contains("from impact_reports"));
+        assert!(!IMPACT_TOTALS.contains("claims c"));
+        assert!(NUTRITION_GROUP_TOTALS.contains("from impact_reports r"));
+        assert!(!NUTRITION_GROUP_TOTALS.contains("claims c"));
+    }
+}
@@ -0,0 +1,292 @@
+use crate::auth::extract_auth_context;
+use crate::db::{self, TimedQuery};
+use crate::error::ApiError;
+use crate::handlers::listing_discovery::parse_positive_radius;
+use crate::http_util::{json_response, parse_uuid, request_locale};
+use crate::location;
+use crate::models::listing::{ListingItem, SuggestedListing, SuggestedListingsResponse};
+use crate::repo;
+use lambda_http::{Body, Request, Response};
+use tracing::info;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: i64 = 10;
+const MAX_LIMIT: i64 = 50;
+const DEFAULT_RADIUS_MILES: f64 = 10.0;
+/// Nearest neighbours fetched before re-ranking by distance.
+const CANDIDATE_LIMIT: i64 = 100;
+const SIMILARITY_WEIGHT: f64 = 0.7;
+const PROXIMITY_WEIGHT: f64 = 0.3;
+
+#[derive(Debug)]
+struct SuggestedListingsQuery {
+    limit: i64,
+    radius_km: f64,
+}
+
+#[derive(Debug)]
+struct RequestOrigin {
+    geo_key: Option<String>,
+    lat: Option<f64>,
+    lng: Option<f64>,
+    embedded: bool,
+}
+
+/// Ranks active listings by semantic similarity to the caller's request,
+/// blended with distance, so free-text notes can match listings filed under a
+/// different catalog crop.
+pub async fn list_suggested_listings(
+    request: &Request,
+    correlation_id: &str,
+    request_id: &str,
+) -> Result<Response<Body>, ApiError> {
+    let auth_context = extract_auth_context(request)?;
+
+    let user_id = Uuid::parse_str(&auth_context.user_id)
+        .map_err(|_| ApiError::unauthorized("Invalid user ID format"))?;
+    let id = parse_uuid(request_id, "requestId")?;
+    let query = parse_suggested_listings_query(request.uri().query())?;
+
+    let client = db::connect().await?;
+    let origin = load_request_origin(&client, id, user_id)
+        .await?
+        .ok_or_else(|| ApiError::not_found("request_not_found", "Request not found"))?;
+
+    let items = if origin.embedded {
+        let geo_prefix = origin
+            .geo_key
+            .as_deref()
+            .map(|geo_key| location::geo_prefix_for_radius(geo_key, Some(query.radius_km)));
+        let candidates = repo::listing::list_semantic_candidates(
+            &client,
+            id,
+            user_id,
+            geo_prefix.as_deref(),
+            CANDIDATE_LIMIT,
+            request_locale(request).as_deref(),
+        )
+        .await?;
+        rank_suggestions(
+            candidates,
+            origin.lat.zip(origin.lng),
+            query.radius_km,
+            query.limit,
+        )
+    } else {
+        Vec::new()
+    };
+
+    let response = SuggestedListingsResponse {
+        request_id: id.to_string(),
+        embedding_ready: origin.embedded,
+        items,
+    };
+
+    info!(
+        correlation_id = correlation_id,
+        user_id = %user_id,
+        request_id = %id,
+        embedding_ready = response.embedding_ready,
+        radius_km = query.radius_km,
+        returned_count = response.items.len(),
+        "Listed suggested listings for request"
+    );
+
+    json_response(200, &response)
+}
+
+async fn load_request_origin(
+    client: &tokio_postgres::Client,
+    request_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<RequestOrigin>, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "suggested_listing::load_request_origin",
+            "
+            select r.geo_key, r.lat, r.lng,
+                   exists (
+                     select 1 from request_embeddings re where re.request_id = r.id
+                   ) as embedded
+            from requests r
+            where r.id = $1
+              and r.user_id = $2
+              and r.deleted_at is null
+            ",
+            &[&request_id, &user_id],
+        )
+        .await?;
+
+    Ok(row.map(|row| RequestOrigin {
+        geo_key: row.get("geo_key"),
+        lat: row.get("lat"),
+        lng: row.get("lng"),
+        embedded: row.get("embedded"),
+    }))
+}
+
+/// Scores each candidate as weighted similarity plus proximity, where
+/// proximity falls from 1 at the request's location to 0.5 at `radius_km`.
+/// Candidates without coordinates on either side get no proximity credit.
+fn rank_suggestions(
+    candidates: Vec<(ListingItem, f64)>,
+    origin: Option<(f64, f64)>,
+    radius_km: f64,
+    limit: i64,
+) -> Vec<SuggestedListing> {
+    let mut ranked = candidates
+        .into_iter()
+        .map(|(listing, similarity)| {
+            let distance_km = origin.zip(listing.lat.zip(listing.lng)).map(
+                |((lat, lng), (listing_lat, listing_lng))| {
+                    location::haversine_km(lat, lng, listing_lat, listing_lng)
+                },
+            );
+            let proximity = distance_km.map_or(0.0, |km| 1.0 / (1.0 + km / radius_km));
+            SuggestedListing {
+                listing,
+                similarity,
+                distance_km: distance_km.map(|km| (km * 10.0).round() / 10.0),
+                score: SIMILARITY_WEIGHT.mul_add(similarity, PROXIMITY_WEIGHT * proximity),
+            }
+        })
+        .collect::<Vec<_>>();
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score));
+    ranked.truncate(usize::try_from(limit).unwrap_or(0));
+    ranked
+}
+
+fn parse_suggested_listings_query(query: Option<&str>) -> Result<SuggestedListingsQuery, ApiError> {
+    let mut limit = DEFAULT_LIMIT;
+    let mut radius_km = DEFAULT_RADIUS_MILES * location::KM_PER_MILE;
+
+    if let Some(raw_query) = query {
+        for pair in raw_query.split('&') {
+            if pair.is_empty() {
+                continue;
+            }
+
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+
+            match key {
+                "limit" => {
+                    limit = value.parse::<i64>().map_err(|_| {
+                        ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            "Invalid limit. Must be an integer",
+                        )
+                    })?;
+                    if !(1..=MAX_LIMIT).contains(&limit) {
+                        return Err(ApiError::invalid_field(
+                            "limit",
+                            "invalid_limit",
+                            format!("Invalid limit. Must be between 1 and {MAX_LIMIT}"),
+                        ));
+                    }
+                }
+                "radiusMiles" => {
+                    radius_km =
+                        parse_positive_radius(value, "radiusMiles")? * location::KM_PER_MILE;
+                }
+                _ => {}
+            }
+        }
+    }
+
+    Ok(SuggestedListingsQuery { limit, radius_km })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn listing(id: &str, lat: Option<f64>, lng: Option<f64>) -> ListingItem {
+        ListingItem {
+            id: id.to_string(),
+            user_id: String::new(),
+            grower_crop_id: None,
+            listing_kind: "produce".to_string(),
+            crop_id: None,
+            variety_id: None,
+            crop_name: None,
+            variety_name: None,
+            title: None,
+            unit: None,
+            quantity_total: None,
+            quantity_remaining: None,
+            reserved_quantity: "0.000".to_string(),
+            available_start: None,
+            available_end: None,
+            return_by: None,
+            publish_at: None,
+            expire_at: None,
+            status: "active".to_string(),
+            pickup_location_text: None,
+            pickup_address: None,
+            effective_pickup_address: None,
+            pickup_disclosure_policy: "immediate".to_string(),
+            pickup_notes: None,
+            contact_pref: "app_message".to_string(),
+            geo_key: None,
+            lat,
+            lng,
+            group_id: None,
+            created_at: String::new(),
+            photo_crop_mismatch: None,
+            claim_counts: None,
+        }
+    }
+
+    #[test]
+    fn parse_suggested_listings_query_defaults_and_bounds() {
+        let parsed = parse_suggested_listings_query(None).unwrap();
+        assert_eq!(parsed.limit, DEFAULT_LIMIT);
+        assert!((parsed.radius_km - 16.093_44).abs() < 1e-9);
+
+        let parsed = parse_suggested_listings_query(Some("limit=5&radiusMiles=2")).unwrap();
+        assert_eq!(parsed.limit, 5);
+
+        let error = parse_suggested_listings_query(Some("limit=51")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_limit");
+    }
+
+    #[test]
+    fn rank_suggestions_blends_similarity_and_distance() {
+        let origin = Some((37.77, -122.42));
+        let candidates = vec![
+            (listing("far-close-match", Some(38.3), Some(-122.42)), 0.82),
+            (
+                listing("near-close-match", Some(37.78), Some(-122.42)),
+                0.80,
+            ),
+            (listing("near-weak-match", Some(37.77), Some(-122.42)), 0.30),
+        ];
+
+        let ranked = rank_suggestions(candidates, origin, 16.0, 10);
+        let ids = ranked
+            .iter()
+            .map(|item| item.listing.id.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            vec!["near-close-match", "far-close-match", "near-weak-match"]
+        );
+        assert_eq!(ranked[0].distance_km, Some(1.1));
+    }
+
+    #[test]
+    fn rank_suggestions_without_coordinates_uses_similarity_and_limit() {
+        let candidates = vec![
+            (listing("a", None, None), 0.4),
+            (listing("b", None, None), 0.9),
+        ];
+
+        let ranked = rank_suggestions(candidates, None, 16.0, 1);
+        assert_eq!(ranked.len(), 1);
+        assert_eq!(ranked[0].listing.id, "b");
+        assert_eq!(ranked[0].distance_km, None);
+    }
+}
@@ -1,9 +1,15 @@
+use crate::achievements;
+use crate::audit::{self, Actor, AuditEntry};
 use crate::badge_cabinet;
-use crate::db;
+use crate::coordinate_privacy::LocatedEntity;
+use crate::db::{self, TimedQuery};
+use crate::error::{ApiError, ValidationErrors};
+use crate::events::{self, ProfileUpdatedEventDetail};
 use crate::gardener_tier;
+use crate::hardiness::{self, Hardiness, HardinessZone};
+use crate::http_util::{json_response, parse_json_body, parse_uuid};
 use crate::location;
 use crate::middleware::entitlements;
-use crate::models::crop::ErrorResponse;
 use crate::models::profile::{
     GathererProfileInput, GrowerProfile, GrowerProfileInput, MeProfileResponse, PublicUserResponse,
     PutMeRequest, SeasonalTimelineEntry, SubscriptionMetadata, UserRatingSummary, UserType,
@@ -11,48 +17,98 @@ use crate::models::profile::{
 use crate::tips_framework::{
     recommend_curated_tips, season_from_month, ExperienceLevel, ExperienceSignals,
 };
-use aws_config::BehaviorVersion;
-use aws_sdk_eventbridge::types::PutEventsRequestEntry;
 use chrono::Datelike;
 use lambda_http::{Body, Request, RequestExt, Response};
-use serde::Serialize;
 use tokio_postgres::Row;
-use tracing::error;
+use tracing::{error, info};
 use uuid::Uuid;
 
 const KM_PER_MILE: f64 = 1.609_344;
+const CLAIM_ALERT_MODES: [&str; 3] = ["immediate", "daily_digest", "off"];
+
+/// A stated `homeZone` may differ from the one derived for the address by
+/// this many half zones (one full zone) before it is refused; growers often
+/// know their microclimate better than the nearest reference station.
+const HOME_ZONE_TOLERANCE_HALF_ZONES: u8 = 2;
+
+/// Everything GET /me reads from the user's own tables, in one round trip.
+/// `has_*` flags distinguish a missing joined row from null columns.
+const ME_PROFILE_QUERY: &str = "
+    select u.id, u.email::text as email, u.display_name, u.is_verified, u.user_type,
+           u.onboarding_completed, u.volunteer_driver, u.tier, u.subscription_status,
+           u.premium_expires_at, u.created_at,
+           gp.user_id is not null as has_grower_profile,
+           gp.home_zone as grower_home_zone, gp.address as grower_address,
+           gp.geo_key as grower_geo_key, gp.lat as grower_lat, gp.lng as grower_lng,
+           gp.share_radius_km::text as grower_share_radius_km,
+           gp.enforce_share_radius as grower_enforce_share_radius,
+           gp.claim_alerts as grower_claim_alerts,
+           gp.units::text as grower_units, gp.locale as grower_locale,
+           ga.user_id is not null as has_gatherer_profile,
+           coalesce(ga.address, '') as gatherer_address, ga.geo_key as gatherer_geo_key,
+           ga.lat as gatherer_lat, ga.lng as gatherer_lng,
+           ga.search_radius_km::text as gatherer_search_radius_km,
+           ga.organization_affiliation as gatherer_organization_affiliation,
+           ga.units::text as gatherer_units, ga.locale as gatherer_locale,
+           rs.user_id is not null as has_rating_summary,
+           rs.avg_score::text as rating_avg_score, rs.rating_count,
+           el.experience_level::text as experience_level, el.signals as experience_signals
+      from users u
+      left join grower_profiles gp on gp.user_id = u.id
+      left join gatherer_profiles ga on ga.user_id = u.id
+      left join user_rating_summary rs on rs.user_id = u.id
+      left join user_experience_levels el on el.user_id = u.id
+     where u.id = $1 and u.deleted_at is null
+";
+
+/// Public subset of `ME_PROFILE_QUERY`, using the same column aliases so the
+/// row mappers are shared.
+const PUBLIC_USER_QUERY: &str = "
+    select u.id, u.display_name, u.created_at,
+           gp.user_id is not null as has_grower_profile,
+           gp.home_zone as grower_home_zone, gp.address as grower_address,
+           gp.geo_key as grower_geo_key, gp.lat as grower_lat, gp.lng as grower_lng,
+           gp.share_radius_km::text as grower_share_radius_km,
+           gp.enforce_share_radius as grower_enforce_share_radius,
+           gp.claim_alerts as grower_claim_alerts,
+           gp.units::text as grower_units, gp.locale as grower_locale,
+           rs.user_id is not null as has_rating_summary,
+           rs.avg_score::text as rating_avg_score, rs.rating_count
+      from users u
+      left join grower_profiles gp on gp.user_id = u.id
+      left join user_rating_summary rs on rs.user_id = u.id
+     where u.id = $1 and u.deleted_at is null
+";
 
 pub async fn get_current_user(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request, correlation_id)?;
-    let client = db::connect().await?;
-
-    let user_row = client
-        .query_opt(
-            "select id, email::text as email, display_name, is_verified, user_type, onboarding_completed, tier, subscription_status, premium_expires_at, created_at from users where id = $1 and deleted_at is null",
-            &[&user_id],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
 
-    if let Some(row) = user_row {
-        return json_response(200, &to_me_response(&client, row).await?);
-    }
+    let response = db::retry("get_current_user", move || async move {
+        let client = db::connect().await?;
+        let user_row = client
+            .query_opt_timed("user::get_current_user", ME_PROFILE_QUERY, &[&user_id])
+            .await?;
+
+        match user_row {
+            Some(row) => to_me_response(&client, row).await,
+            None => Err(ApiError::not_found(
+                "user_not_found",
+                "User profile not found",
+            )),
+        }
+    })
+    .await?;
 
-    json_response(
-        404,
-        &ErrorResponse {
-            error: "User profile not found".to_string(),
-        },
-    )
+    json_response(200, &response)
 }
 
 pub async fn upsert_current_user(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request, correlation_id)?;
     let auth_email = extract_authorizer_field(request, "email");
     let payload: PutMeRequest = parse_json_body(request)?;
@@ -63,14 +119,17 @@ pub async fn upsert_current_user(
     let should_complete_onboarding = should_mark_onboarding_complete(&payload);
 
     client
-        .execute(
+        .execute_timed(
+            "user::upsert_current_user",
             "
-            insert into users (id, email, display_name, user_type, onboarding_completed)
-            values ($1, $2, $3, $4, $5)
+            insert into users
+                (id, email, display_name, user_type, onboarding_completed, volunteer_driver)
+            values ($1, $2, $3, $4, $5, coalesce($6, false))
             on conflict (id) do update
             set email = coalesce(excluded.email, users.email),
                 display_name = coalesce(excluded.display_name, users.display_name),
                 user_type = coalesce(excluded.user_type, users.user_type),
+                volunteer_driver = coalesce($6, users.volunteer_driver),
                 onboarding_completed = case
                     when excluded.onboarding_completed = true then true
                     else users.onboarding_completed
@@ -86,10 +145,10 @@ pub async fn upsert_current_user(
                     UserType::Gatherer => "gatherer",
                 }),
                 &should_complete_onboarding,
+                &payload.volunteer_driver,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
 
     if let Some(grower_profile) = payload.grower_profile {
         upsert_grower_profile(&client, user_id, grower_profile, correlation_id).await?;
@@ -105,50 +164,54 @@ pub async fn upsert_current_user(
     Response::builder()
         .status(204)
         .body(Body::Empty)
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
+        .map_err(|e| ApiError::internal(e.to_string()))
 }
 
 pub async fn get_current_entitlements(
     request: &Request,
     correlation_id: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
+) -> Result<Response<Body>, ApiError> {
     let user_id = extract_user_id(request, correlation_id)?;
     let client = db::connect().await?;
     let snapshot = entitlements::get_entitlements_snapshot(&client, user_id).await?;
     json_response(200, &snapshot)
 }
 
-pub async fn get_public_user(user_id: &str) -> Result<Response<Body>, lambda_http::Error> {
+pub async fn get_public_user(user_id: &str) -> Result<Response<Body>, ApiError> {
     let user_uuid = parse_uuid(user_id, "user id")?;
     let client = db::connect().await?;
 
     let row = client
-        .query_opt(
-            "select id, display_name, created_at from users where id = $1 and deleted_at is null",
-            &[&user_uuid],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .query_opt_timed("user::get_public_user", PUBLIC_USER_QUERY, &[&user_uuid])
+        .await?;
 
     if let Some(user_row) = row {
+        let achievements = match achievements::load_achievements(&client, user_uuid).await {
+            Ok(achievements) => achievements,
+            Err(error) => {
+                error!(
+                    user_id = %user_uuid,
+                    reason = %error,
+                    "Failed to load achievements; using safe defaults"
+                );
+                vec![]
+            }
+        };
+
         let response = PublicUserResponse {
             id: user_row.get::<_, Uuid>("id").to_string(),
             display_name: user_row.get("display_name"),
             created_at: user_row
                 .get::<_, chrono::DateTime<chrono::Utc>>("created_at")
                 .to_rfc3339(),
-            grower_profile: load_grower_profile(&client, user_uuid).await?,
-            rating_summary: load_rating_summary(&client, user_uuid).await?,
+            grower_profile: grower_profile_from_row(&user_row),
+            rating_summary: rating_summary_from_row(&user_row),
+            achievements,
         };
         return json_response(200, &response);
     }
 
-    json_response(
-        404,
-        &ErrorResponse {
-            error: "User not found".to_string(),
-        },
-    )
+    Err(ApiError::not_found("user_not_found", "User not found"))
 }
 
 async fn upsert_grower_profile(
@@ -156,19 +219,37 @@ async fn upsert_grower_profile(
     user_id: Uuid,
     profile: GrowerProfileInput,
     correlation_id: &str,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let address = location::normalize_address(&profile.address);
-    let geocoded = location::geocode_address(&address, correlation_id).await?;
+    let geocoded = location::geocode_street_address(&address, "address", correlation_id).await?;
+    let derived = hardiness::lookup(geocoded.lat, geocoded.lng);
+    let home_zone = resolve_home_zone(profile.home_zone.as_deref(), derived.as_ref())?;
+    if let Some(derived) = &derived {
+        info!(
+            correlation_id = correlation_id,
+            user_id = %user_id,
+            home_zone = home_zone.as_str(),
+            derived_zone = %derived.zone,
+            station = derived.station,
+            station_distance_km = derived.distance_km,
+            "Resolved grower hardiness zone"
+        );
+    }
 
     let share_radius_km = miles_to_km(profile.share_radius_miles);
 
-    client
-        .execute(
+    let row = client
+        .query_one_timed("user::upsert_grower_profile", 
             "
+            with previous as (
+                select address from grower_profiles where user_id = $1
+            )
             insert into grower_profiles
-                (user_id, home_zone, address, geo_key, lat, lng, share_radius_km, units, locale)
+                (user_id, home_zone, address, geo_key, lat, lng, share_radius_km, units, locale,
+                 enforce_share_radius, claim_alerts)
             values
-                ($1, $2, $3, $4, $5, $6, $7, coalesce($8::text::units_system, 'imperial'::units_system), $9)
+                ($1, $2, $3, $4, $5, $6, $7, coalesce($8::text::units_system, 'imperial'::units_system), $9,
+                 coalesce($10, false), coalesce($11, 'immediate'))
             on conflict (user_id) do update
             set home_zone = excluded.home_zone,
                 address = excluded.address,
@@ -178,11 +259,14 @@ async fn upsert_grower_profile(
                 share_radius_km = excluded.share_radius_km,
                 units = excluded.units,
                 locale = excluded.locale,
+                enforce_share_radius = coalesce($10, grower_profiles.enforce_share_radius),
+                claim_alerts = coalesce($11, grower_profiles.claim_alerts),
                 updated_at = now()
+            returning (select address from previous) as previous_address
             ",
             &[
                 &user_id,
-                &profile.home_zone,
+                &home_zone,
                 &address,
                 &geocoded.geo_key,
                 &geocoded.lat,
@@ -190,10 +274,21 @@ async fn upsert_grower_profile(
                 &share_radius_km,
                 &profile.units,
                 &profile.locale,
+                &profile.enforce_share_radius,
+                &profile.claim_alerts,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
+
+    record_address_change(
+        client,
+        user_id,
+        "grower_profile",
+        row.get("previous_address"),
+        &address,
+        correlation_id,
+    )
+    .await;
 
     Ok(())
 }
@@ -203,14 +298,17 @@ async fn upsert_gatherer_profile(
     user_id: Uuid,
     profile: GathererProfileInput,
     correlation_id: &str,
-) -> Result<(), lambda_http::Error> {
+) -> Result<(), ApiError> {
     let address = location::normalize_address(&profile.address);
     let geocoded = location::geocode_address(&address, correlation_id).await?;
     let search_radius_km = miles_to_km(profile.search_radius_miles);
 
-    client
-        .execute(
+    let row = client
+        .query_one_timed("user::upsert_gatherer_profile", 
             "
+            with previous as (
+                select address from gatherer_profiles where user_id = $1
+            )
             insert into gatherer_profiles
                 (user_id, address, geo_key, lat, lng, search_radius_km, organization_affiliation, units, locale)
             values
@@ -225,6 +323,7 @@ async fn upsert_gatherer_profile(
                 units = excluded.units,
                 locale = excluded.locale,
                 updated_at = now()
+            returning (select address from previous) as previous_address
             ",
             &[
                 &user_id,
@@ -238,48 +337,62 @@ async fn upsert_gatherer_profile(
                 &profile.locale,
             ],
         )
-        .await
-        .map_err(|error| db_error(&error))?;
+        .await?;
+
+    record_address_change(
+        client,
+        user_id,
+        "gatherer_profile",
+        row.get("previous_address"),
+        &address,
+        correlation_id,
+    )
+    .await;
 
     Ok(())
 }
 
+/// First-time addresses set during onboarding are not audited; only
+/// replacing an existing address is.
+async fn record_address_change(
+    client: &tokio_postgres::Client,
+    user_id: Uuid,
+    profile_type: &'static str,
+    previous_address: Option<String>,
+    address: &str,
+    correlation_id: &str,
+) {
+    let Some(previous_address) = previous_address else {
+        return;
+    };
+    if previous_address == address {
+        return;
+    }
+
+    audit::record_best_effort(
+        client,
+        &AuditEntry {
+            actor: Actor {
+                user_id: Some(user_id),
+                api_key_id: None,
+            },
+            action: audit::PROFILE_ADDRESS_CHANGED,
+            target_type: profile_type,
+            target_id: user_id.to_string(),
+            before: Some(serde_json::json!({ "address": previous_address })),
+            after: Some(serde_json::json!({ "address": address })),
+            correlation_id,
+        },
+    )
+    .await;
+}
+
 async fn emit_profile_updated_event(
     user_id: &str,
     correlation_id: &str,
 ) -> Result<(), lambda_http::Error> {
-    let event_bus_name = std::env::var("EVENT_BUS_NAME").unwrap_or_else(|_| "default".to_string());
-
-    let detail = serde_json::json!({
-        "userId": user_id,
-        "correlationId": correlation_id,
-        "occurredAt": chrono::Utc::now().to_rfc3339(),
-    });
-
-    let config = aws_config::defaults(BehaviorVersion::latest()).load().await;
-    let eb_client = aws_sdk_eventbridge::Client::new(&config);
-
-    let entry = PutEventsRequestEntry::builder()
-        .event_bus_name(event_bus_name)
-        .source("community-garden.api")
-        .detail_type("user.profile.updated")
-        .detail(detail.to_string())
-        .build();
-
-    let response = eb_client
-        .put_events()
-        .entries(entry)
-        .send()
-        .await
-        .map_err(|e| lambda_http::Error::from(format!("Failed to emit profile event: {e}")))?;
-
-    if response.failed_entry_count() > 0 {
-        return Err(lambda_http::Error::from(
-            "Failed to emit profile event: entry rejected",
-        ));
-    }
-
-    Ok(())
+    let detail = ProfileUpdatedEventDetail::new(user_id, correlation_id);
+    events::publish(events::USER_PROFILE_UPDATED, &detail).await
 }
 
 async fn emit_profile_updated_event_best_effort(user_id: &str, correlation_id: &str) {
@@ -293,13 +406,13 @@ async fn emit_profile_updated_event_best_effort(user_id: &str, correlation_id: &
     }
 }
 
-fn extract_user_id(request: &Request, correlation_id: &str) -> Result<Uuid, lambda_http::Error> {
+fn extract_user_id(request: &Request, correlation_id: &str) -> Result<Uuid, ApiError> {
     let user_id = extract_authorizer_field(request, "userId").ok_or_else(|| {
         error!(
             correlation_id = correlation_id,
             "Missing userId in authorizer context"
         );
-        lambda_http::Error::from("Missing userId in authorizer context".to_string())
+        ApiError::unauthorized("Missing userId in authorizer context")
     })?;
 
     parse_uuid(&user_id, "userId")
@@ -314,27 +427,34 @@ fn extract_authorizer_field(request: &Request, field_name: &str) -> Option<Strin
         .map(ToString::to_string)
 }
 
-fn validate_put_me_payload(payload: &PutMeRequest) -> Result<(), lambda_http::Error> {
+fn validate_put_me_payload(payload: &PutMeRequest) -> Result<(), ApiError> {
     if payload.grower_profile.is_some() && payload.gatherer_profile.is_some() {
-        return Err(lambda_http::Error::from(
-            "Cannot provide both growerProfile and gathererProfile in the same request".to_string(),
+        return Err(ApiError::bad_request(
+            "conflicting_profiles",
+            "Cannot provide both growerProfile and gathererProfile in the same request",
         ));
     }
 
+    let mut errors = ValidationErrors::new();
+
     if let Some(user_type) = &payload.user_type {
         match user_type {
             UserType::Grower => {
                 if payload.gatherer_profile.is_some() {
-                    return Err(lambda_http::Error::from(
-                        "Cannot provide gathererProfile when userType is 'grower'".to_string(),
-                    ));
+                    errors.add(
+                        "gathererProfile",
+                        "profile_user_type_mismatch",
+                        "Cannot provide gathererProfile when userType is 'grower'",
+                    );
                 }
             }
             UserType::Gatherer => {
                 if payload.grower_profile.is_some() {
-                    return Err(lambda_http::Error::from(
-                        "Cannot provide growerProfile when userType is 'gatherer'".to_string(),
-                    ));
+                    errors.add(
+                        "growerProfile",
+                        "profile_user_type_mismatch",
+                        "Cannot provide growerProfile when userType is 'gatherer'",
+                    );
                 }
             }
         }
@@ -342,47 +462,109 @@ fn validate_put_me_payload(payload: &PutMeRequest) -> Result<(), lambda_http::Er
 
     if let Some(grower) = &payload.grower_profile {
         if grower.share_radius_miles <= 0.0 {
-            return Err(lambda_http::Error::from(
-                "shareRadiusMiles must be greater than 0".to_string(),
-            ));
+            errors.add(
+                "shareRadiusMiles",
+                "must_be_positive",
+                "shareRadiusMiles must be greater than 0",
+            );
         }
 
         if grower.units != "imperial" && grower.units != "metric" {
-            return Err(lambda_http::Error::from(
-                "units must be one of: imperial, metric".to_string(),
-            ));
+            errors.add(
+                "units",
+                "invalid_enum",
+                "units must be one of: imperial, metric",
+            );
+        }
+
+        if grower
+            .claim_alerts
+            .as_deref()
+            .is_some_and(|alerts| !CLAIM_ALERT_MODES.contains(&alerts))
+        {
+            errors.add(
+                "claimAlerts",
+                "invalid_enum",
+                "claimAlerts must be one of: immediate, daily_digest, off",
+            );
         }
 
-        if grower.home_zone.trim().is_empty() {
-            return Err(lambda_http::Error::from(
-                "homeZone cannot be empty".to_string(),
-            ));
+        if let Some(zone) = stated_home_zone(grower.home_zone.as_deref()) {
+            if HardinessZone::parse(zone).is_none() {
+                errors.add(
+                    "homeZone",
+                    "invalid_format",
+                    "homeZone must be a USDA hardiness zone such as 7b",
+                );
+            }
         }
 
         if grower.address.trim().is_empty() {
-            return Err(lambda_http::Error::from("address is required".to_string()));
+            errors.add("address", "required", "address is required");
         }
     }
 
     if let Some(gatherer) = &payload.gatherer_profile {
         if gatherer.search_radius_miles <= 0.0 {
-            return Err(lambda_http::Error::from(
-                "searchRadiusMiles must be greater than 0".to_string(),
-            ));
+            errors.add(
+                "searchRadiusMiles",
+                "must_be_positive",
+                "searchRadiusMiles must be greater than 0",
+            );
         }
 
         if gatherer.units != "imperial" && gatherer.units != "metric" {
-            return Err(lambda_http::Error::from(
-                "units must be one of: imperial, metric".to_string(),
-            ));
+            errors.add(
+                "units",
+                "invalid_enum",
+                "units must be one of: imperial, metric",
+            );
         }
 
         if gatherer.address.trim().is_empty() {
-            return Err(lambda_http::Error::from("address is required".to_string()));
+            errors.add("address", "required", "address is required");
         }
     }
 
-    Ok(())
+    errors.into_result()
+}
+
+/// A blank `homeZone` is treated as omitted.
+fn stated_home_zone(home_zone: Option<&str>) -> Option<&str> {
+    home_zone.map(str::trim).filter(|zone| !zone.is_empty())
+}
+
+/// The zone to store for a grower: the stated one when it is close to the
+/// zone derived from their address, otherwise the derived one. Growers
+/// outside the bundled dataset's coverage must state a zone.
+fn resolve_home_zone(
+    stated: Option<&str>,
+    derived: Option<&Hardiness>,
+) -> Result<String, ApiError> {
+    let stated = stated_home_zone(stated).and_then(HardinessZone::parse);
+    match (stated, derived) {
+        (Some(stated), Some(derived))
+            if stated.half_zones_from(derived.zone) > HOME_ZONE_TOLERANCE_HALF_ZONES =>
+        {
+            Err(ApiError::unprocessable(
+                "homeZone",
+                "home_zone_mismatch",
+                format!(
+                    "homeZone {stated} does not match zone {} for this address",
+                    derived.zone
+                ),
+                vec![derived.zone.to_string()],
+            ))
+        }
+        (Some(stated), _) => Ok(stated.to_string()),
+        (None, Some(derived)) => Ok(derived.zone.to_string()),
+        (None, None) => Err(ApiError::unprocessable(
+            "homeZone",
+            "home_zone_unknown",
+            "No hardiness zone is known for this address; provide homeZone",
+            Vec::new(),
+        )),
+    }
 }
 
 fn should_mark_onboarding_complete(payload: &PutMeRequest) -> bool {
@@ -390,9 +572,7 @@ fn should_mark_onboarding_complete(payload: &PutMeRequest) -> bool {
         match user_type {
             UserType::Grower => {
                 if let Some(grower) = &payload.grower_profile {
-                    return !grower.home_zone.trim().is_empty()
-                        && !grower.address.trim().is_empty()
-                        && grower.share_radius_miles > 0.0;
+                    return !grower.address.trim().is_empty() && grower.share_radius_miles > 0.0;
                 }
             }
             UserType::Gatherer => {
@@ -409,7 +589,7 @@ fn should_mark_onboarding_complete(payload: &PutMeRequest) -> bool {
 async fn to_me_response(
     client: &tokio_postgres::Client,
     user_row: Row,
-) -> Result<MeProfileResponse, lambda_http::Error> {
+) -> Result<MeProfileResponse, ApiError> {
     let user_id = user_row.get::<_, Uuid>("id");
 
     let user_type = user_row
@@ -432,20 +612,8 @@ async fn to_me_response(
         }
     };
 
-    let (experience_level, experience_signals) =
-        match load_experience_level_read_only(client, user_id).await {
-            Ok(result) => result,
-            Err(error) => {
-                error!(
-                    user_id = %user_id,
-                    reason = %error,
-                    "Failed to load experience level; using safe defaults"
-                );
-                (ExperienceLevel::Beginner, ExperienceSignals::default())
-            }
-        };
-
-    let grower_profile = load_grower_profile(client, user_id).await?;
+    let (experience_level, experience_signals) = experience_from_row(&user_row);
+    let grower_profile = grower_profile_from_row(&user_row);
 
     let now = chrono::Utc::now();
     let season = season_from_month(now.month());
@@ -490,6 +658,7 @@ async fn to_me_response(
         is_verified: user_row.get("is_verified"),
         user_type,
         onboarding_completed: user_row.get("onboarding_completed"),
+        volunteer_driver: user_row.get("volunteer_driver"),
         created_at: user_row
             .get::<_, chrono::DateTime<chrono::Utc>>("created_at")
             .to_rfc3339(),
@@ -507,117 +676,75 @@ async fn to_me_response(
         experience_signals,
         curated_tips,
         grower_profile,
-        gatherer_profile: load_gatherer_profile(client, user_id).await?,
-        rating_summary: load_rating_summary(client, user_id).await?,
+        gatherer_profile: gatherer_profile_from_row(&user_row),
+        rating_summary: rating_summary_from_row(&user_row),
     })
 }
 
-async fn load_grower_profile(
-    client: &tokio_postgres::Client,
-    user_id: Uuid,
-) -> Result<Option<GrowerProfile>, lambda_http::Error> {
-    let row = client
-        .query_opt(
-            "select home_zone, address, geo_key, lat, lng, share_radius_km::text as share_radius_km, units::text as units, locale from grower_profiles where user_id = $1",
-            &[&user_id],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
-
-    Ok(row.map(|grower| GrowerProfile {
-        home_zone: grower.get("home_zone"),
-        address: grower.get("address"),
-        geo_key: grower.get("geo_key"),
-        lat: grower
-            .get::<_, Option<f64>>("lat")
-            .map(location::round_for_response),
-        lng: grower
-            .get::<_, Option<f64>>("lng")
-            .map(location::round_for_response),
-        share_radius_miles: km_text_to_miles_text(&grower.get::<_, String>("share_radius_km")),
-        units: grower.get("units"),
-        locale: grower.get("locale"),
-    }))
-}
+fn grower_profile_from_row(row: &Row) -> Option<GrowerProfile> {
+    if !row.get::<_, bool>("has_grower_profile") {
+        return None;
+    }
 
-async fn load_gatherer_profile(
-    client: &tokio_postgres::Client,
-    user_id: Uuid,
-) -> Result<Option<crate::models::profile::GathererProfile>, lambda_http::Error> {
-    let row = client
-        .query_opt(
-            "select coalesce(address, '') as address, geo_key, lat, lng, search_radius_km::text as search_radius_km, organization_affiliation, units::text as units, locale from gatherer_profiles where user_id = $1",
-            &[&user_id],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
-
-    Ok(row.map(|gatherer| crate::models::profile::GathererProfile {
-        address: gatherer.get("address"),
-        geo_key: gatherer.get("geo_key"),
-        lat: location::round_for_response(gatherer.get("lat")),
-        lng: location::round_for_response(gatherer.get("lng")),
-        search_radius_miles: km_text_to_miles_text(&gatherer.get::<_, String>("search_radius_km")),
-        organization_affiliation: gatherer.get("organization_affiliation"),
-        units: gatherer.get("units"),
-        locale: gatherer.get("locale"),
-    }))
+    let (lat, lng) = LocatedEntity::GrowerProfile.optional_point(row, "grower_lat", "grower_lng");
+    Some(GrowerProfile {
+        home_zone: row.get("grower_home_zone"),
+        address: row.get("grower_address"),
+        geo_key: row.get("grower_geo_key"),
+        lat,
+        lng,
+        share_radius_miles: km_text_to_miles_text(&row.get::<_, String>("grower_share_radius_km")),
+        enforce_share_radius: row.get("grower_enforce_share_radius"),
+        claim_alerts: row.get("grower_claim_alerts"),
+        units: row.get("grower_units"),
+        locale: row.get("grower_locale"),
+    })
 }
 
-async fn load_rating_summary(
-    client: &tokio_postgres::Client,
-    user_id: Uuid,
-) -> Result<Option<UserRatingSummary>, lambda_http::Error> {
-    let row = client
-        .query_opt(
-            "select avg_score::text as avg_score, rating_count from user_rating_summary where user_id = $1",
-            &[&user_id],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
+fn gatherer_profile_from_row(row: &Row) -> Option<crate::models::profile::GathererProfile> {
+    if !row.get::<_, bool>("has_gatherer_profile") {
+        return None;
+    }
 
-    Ok(row.map(|rating| UserRatingSummary {
-        avg_score: rating.get("avg_score"),
-        rating_count: rating.get("rating_count"),
-    }))
+    let (lat, lng) = LocatedEntity::GathererProfile.point(row, "gatherer_lat", "gatherer_lng");
+    Some(crate::models::profile::GathererProfile {
+        address: row.get("gatherer_address"),
+        geo_key: row.get("gatherer_geo_key"),
+        lat,
+        lng,
+        search_radius_miles: km_text_to_miles_text(
+            &row.get::<_, String>("gatherer_search_radius_km"),
+        ),
+        organization_affiliation: row.get("gatherer_organization_affiliation"),
+        units: row.get("gatherer_units"),
+        locale: row.get("gatherer_locale"),
+    })
 }
 
-/// Read pre-computed experience level and signals from `user_experience_levels`.
-/// Returns defaults (beginner, zero signals) when no row exists.
-async fn load_experience_level_read_only(
-    client: &tokio_postgres::Client,
-    user_id: Uuid,
-) -> Result<(ExperienceLevel, ExperienceSignals), lambda_http::Error> {
-    let row = client
-        .query_opt(
-            "select experience_level::text as experience_level, signals from user_experience_levels where user_id = $1",
-            &[&user_id],
-        )
-        .await
-        .map_err(|error| db_error(&error))?;
-
-    #[allow(clippy::option_if_let_else)]
-    match row {
-        Some(row) => {
-            let level_str: String = row.get("experience_level");
-            let level = match level_str.as_str() {
-                "intermediate" => ExperienceLevel::Intermediate,
-                "advanced" => ExperienceLevel::Advanced,
-                _ => ExperienceLevel::Beginner,
-            };
-            let signals_json: serde_json::Value = row.get("signals");
-            let signals: ExperienceSignals =
-                serde_json::from_value(signals_json).unwrap_or_default();
-            Ok((level, signals))
-        }
-        None => Ok((ExperienceLevel::Beginner, ExperienceSignals::default())),
+fn rating_summary_from_row(row: &Row) -> Option<UserRatingSummary> {
+    if !row.get::<_, bool>("has_rating_summary") {
+        return None;
     }
+
+    Some(UserRatingSummary {
+        avg_score: row.get("rating_avg_score"),
+        rating_count: row.get("rating_count"),
+    })
 }
 
-fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, lambda_http::Error> {
-    let normalized = value.trim();
-    Uuid::parse_str(normalized)
-        .map_err(|_| lambda_http::Error::from(format!("{field_name} must be a valid UUID")))
+/// Pre-computed experience level and signals from `user_experience_levels`.
+/// Defaults to beginner with zero signals when no row exists.
+fn experience_from_row(row: &Row) -> (ExperienceLevel, ExperienceSignals) {
+    let level = match row.get::<_, Option<String>>("experience_level").as_deref() {
+        Some("intermediate") => ExperienceLevel::Intermediate,
+        Some("advanced") => ExperienceLevel::Advanced,
+        _ => ExperienceLevel::Beginner,
+    };
+    let signals = row
+        .get::<_, Option<serde_json::Value>>("experience_signals")
+        .and_then(|signals| serde_json::from_value(signals).ok())
+        .unwrap_or_default();
+    (level, signals)
 }
 
 fn miles_to_km(miles: f64) -> f64 {
@@ -649,47 +776,6 @@ fn km_text_to_miles_text(km_text: &str) -> String {
         .map_or_else(|_| km_text.to_string(), normalize_radius_text)
 }
 
-fn parse_json_body<T: serde::de::DeserializeOwned>(
-    request: &Request,
-) -> Result<T, lambda_http::Error> {
-    match request.body() {
-        Body::Text(text) => serde_json::from_str::<T>(text)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Binary(bytes) => serde_json::from_slice::<T>(bytes)
-            .map_err(|e| lambda_http::Error::from(format!("Invalid JSON body: {e}"))),
-        Body::Empty => Err(lambda_http::Error::from(
-            "Request body is required".to_string(),
-        )),
-    }
-}
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    if let Some(db_error) = error.as_db_error() {
-        let detail = db_error.detail().unwrap_or("none");
-        return lambda_http::Error::from(format!(
-            "Database query error: {} (detail: {})",
-            db_error.message(),
-            detail
-        ));
-    }
-
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
-
-fn json_response<T: Serialize>(
-    status: u16,
-    payload: &T,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let body = serde_json::to_string(payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
-
-    Response::builder()
-        .status(status)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
-
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::panic)]
 mod tests {
@@ -702,9 +788,11 @@ mod tests {
             display_name: Some("Test User".to_string()),
             user_type: Some(UserType::Grower),
             grower_profile: Some(GrowerProfileInput {
-                home_zone: "8a".to_string(),
+                home_zone: Some("8a".to_string()),
                 address: "123 Main St".to_string(),
                 share_radius_miles: 5.0,
+                enforce_share_radius: None,
+                claim_alerts: None,
                 units: "imperial".to_string(),
                 locale: "en-US".to_string(),
             }),
@@ -715,6 +803,7 @@ mod tests {
                 units: "metric".to_string(),
                 locale: "en-US".to_string(),
             }),
+            volunteer_driver: None,
         };
 
         let result = validate_put_me_payload(&payload);
@@ -738,6 +827,7 @@ mod tests {
                 units: "metric".to_string(),
                 locale: "en-US".to_string(),
             }),
+            volunteer_driver: None,
         };
 
         let result = validate_put_me_payload(&payload);
@@ -754,13 +844,16 @@ mod tests {
             display_name: Some("Test User".to_string()),
             user_type: Some(UserType::Grower),
             grower_profile: Some(GrowerProfileInput {
-                home_zone: "8a".to_string(),
+                home_zone: Some("8a".to_string()),
                 address: "   ".to_string(),
                 share_radius_miles: 5.0,
+                enforce_share_radius: None,
+                claim_alerts: None,
                 units: "imperial".to_string(),
                 locale: "en-US".to_string(),
             }),
             gatherer_profile: None,
+            volunteer_driver: None,
         };
 
         let result = validate_put_me_payload(&payload);
@@ -784,6 +877,7 @@ mod tests {
                 units: "metric".to_string(),
                 locale: "en-US".to_string(),
             }),
+            volunteer_driver: None,
         };
 
         let result = validate_put_me_payload(&payload);
@@ -794,25 +888,122 @@ mod tests {
             .contains("address is required"));
     }
 
+    #[test]
+    fn test_validate_grower_reports_all_invalid_fields() {
+        let payload = PutMeRequest {
+            display_name: Some("Test User".to_string()),
+            user_type: Some(UserType::Grower),
+            grower_profile: Some(GrowerProfileInput {
+                home_zone: Some("zone eight".to_string()),
+                address: String::new(),
+                share_radius_miles: 0.0,
+                enforce_share_radius: None,
+                claim_alerts: Some("hourly".to_string()),
+                units: "cubits".to_string(),
+                locale: "en-US".to_string(),
+            }),
+            gatherer_profile: None,
+            volunteer_driver: None,
+        };
+
+        let error = validate_put_me_payload(&payload).unwrap_err();
+        let fields: Vec<String> = match &error {
+            ApiError::Validation { issues } => {
+                issues.iter().map(|issue| issue.field.clone()).collect()
+            }
+            _ => Vec::new(),
+        };
+        assert_eq!(
+            fields,
+            vec![
+                "shareRadiusMiles",
+                "units",
+                "claimAlerts",
+                "homeZone",
+                "address"
+            ]
+        );
+    }
+
     #[test]
     fn test_validate_valid_grower_profile() {
         let payload = PutMeRequest {
             display_name: Some("Test User".to_string()),
             user_type: Some(UserType::Grower),
             grower_profile: Some(GrowerProfileInput {
-                home_zone: "8a".to_string(),
+                home_zone: Some("8a".to_string()),
                 address: "123 Main St".to_string(),
                 share_radius_miles: 5.0,
+                enforce_share_radius: None,
+                claim_alerts: None,
                 units: "imperial".to_string(),
                 locale: "en-US".to_string(),
             }),
             gatherer_profile: None,
+            volunteer_driver: None,
         };
 
         let result = validate_put_me_payload(&payload);
         assert!(result.is_ok());
     }
 
+    #[test]
+    fn test_validate_grower_home_zone_is_optional() {
+        let payload = PutMeRequest {
+            display_name: None,
+            user_type: Some(UserType::Grower),
+            grower_profile: Some(GrowerProfileInput {
+                home_zone: None,
+                address: "123 Main St".to_string(),
+                share_radius_miles: 5.0,
+                enforce_share_radius: None,
+                claim_alerts: None,
+                units: "imperial".to_string(),
+                locale: "en-US".to_string(),
+            }),
+            gatherer_profile: None,
+            volunteer_driver: None,
+        };
+
+        assert!(validate_put_me_payload(&payload).is_ok());
+        assert!(should_mark_onboarding_complete(&payload));
+    }
+
+    fn derived_zone(zone: &str) -> Hardiness {
+        Hardiness {
+            zone: HardinessZone::parse(zone).unwrap(),
+            frost_window: None,
+            station: "Test Station",
+            distance_km: 10.0,
+        }
+    }
+
+    #[test]
+    fn test_resolve_home_zone_fills_in_and_checks_against_address() {
+        let derived = derived_zone("6b");
+        assert_eq!(resolve_home_zone(None, Some(&derived)).unwrap(), "6b");
+        assert_eq!(resolve_home_zone(Some(" "), Some(&derived)).unwrap(), "6b");
+        assert_eq!(
+            resolve_home_zone(Some("Zone 7B"), Some(&derived)).unwrap(),
+            "7b"
+        );
+        assert_eq!(resolve_home_zone(Some("9"), None).unwrap(), "9");
+
+        match resolve_home_zone(Some("9a"), Some(&derived)).unwrap_err() {
+            ApiError::Unprocessable {
+                code, suggestions, ..
+            } => {
+                assert_eq!(code, "home_zone_mismatch");
+                assert_eq!(suggestions, vec!["6b".to_string()]);
+            }
+            other => panic!("unexpected error: {other:?}"),
+        }
+        assert_eq!(
+            resolve_home_zone(None, None).unwrap_err().error_code(),
+            "home_zone_unknown"
+        );
+    }
+
     #[test]
     fn test_validate_valid_gatherer_profile() {
         let payload = PutMeRequest {
@@ -826,6 +1017,7 @@ mod tests {
                 units: "metric".to_string(),
                 locale: "en-US".to_string(),
             }),
+            volunteer_driver: None,
         };
 
         let result = validate_put_me_payload(&payload);
@@ -838,13 +1030,16 @@ mod tests {
             display_name: Some("Test User".to_string()),
             user_type: Some(UserType::Grower),
             grower_profile: Some(GrowerProfileInput {
-                home_zone: "8a".to_string(),
+                home_zone: Some("8a".to_string()),
                 address: "123 Main St".to_string(),
                 share_radius_miles: 5.0,
+                enforce_share_radius: None,
+                claim_alerts: None,
                 units: "imperial".to_string(),
                 locale: "en-US".to_string(),
             }),
             gatherer_profile: None,
+            volunteer_driver: None,
         };
 
         assert!(should_mark_onboarding_complete(&payload));
@@ -863,6 +1058,7 @@ mod tests {
                 units: "metric".to_string(),
                 locale: "en-US".to_string(),
             }),
+            volunteer_driver: None,
         };
 
         assert!(should_mark_onboarding_complete(&payload));
@@ -906,4 +1102,38 @@ mod tests {
         assert_eq!(json["varietyBreadth"], 0);
         assert_eq!(json["badgeCredibility"], 0);
     }
+
+    #[test]
+    fn me_profile_query_loads_every_source_in_one_round_trip() {
+        assert!(!ME_PROFILE_QUERY.contains(';'));
+        for table in [
+            "from users u",
+            "left join grower_profiles gp",
+            "left join gatherer_profiles ga",
+            "left join user_rating_summary rs",
+            "left join user_experience_levels el",
+        ] {
+            assert!(ME_PROFILE_QUERY.contains(table), "missing {table}");
+        }
+    }
+
+    #[test]
+    fn public_user_query_shares_profile_column_aliases() {
+        for alias in [
+            "has_grower_profile",
+            "grower_home_zone",
+            "grower_share_radius_km",
+            "grower_enforce_share_radius",
+            "grower_claim_alerts",
+            "grower_locale",
+            "has_rating_summary",
+            "rating_avg_score",
+            "rs.rating_count",
+        ] {
+            assert!(ME_PROFILE_QUERY.contains(alias), "/me missing {alias}");
+            assert!(PUBLIC_USER_QUERY.contains(alias), "public missing {alias}");
+        }
+        assert!(!PUBLIC_USER_QUERY.contains("gatherer_profiles"));
+        assert!(!PUBLIC_USER_QUERY.contains("email"));
+    }
 }
@@ -0,0 +1,297 @@
+//! USDA hardiness zone and average frost dates for a location, read from the
+//! reference stations bundled in `data/hardiness`. A point takes the values
+//! of its nearest station, so results are a regional default: good enough to
+//! fill in or sanity-check a grower's `home_zone`, not a site survey.
+
+use crate::location;
+use chrono::{Datelike, NaiveDate};
+use serde::Deserialize;
+use std::fmt;
+use std::sync::OnceLock;
+
+/// Points farther than this from every station get no result rather than a
+/// guess from another climate.
+const MAX_STATION_DISTANCE_KM: f64 = 350.0;
+
+/// Frost dates are stored without a year; day-of-year math uses a non-leap
+/// year so dates stay comparable across years.
+const REFERENCE_YEAR: i32 = 2001;
+
+/// A USDA zone such as `7b`. The half-zone letter is optional because many
+/// growers only know the number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct HardinessZone {
+    number: u8,
+    half: Option<char>,
+}
+
+impl HardinessZone {
+    /// Accepts `7`, `7b`, `7B`, or `Zone 7b`; zones run from 1 to 13.
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim().to_ascii_lowercase();
+        let value = value.strip_prefix("zone").unwrap_or(&value).trim_start();
+        let (digits, half) = match value.strip_suffix(['a', 'b']) {
+            Some(digits) => (digits, value.chars().last()),
+            None => (value, None),
+        };
+        if digits.is_empty() || digits.len() > 2 || !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let number = digits.parse::<u8>().ok().filter(|n| (1..=13).contains(n))?;
+        Some(Self { number, half })
+    }
+
+    /// Distance in half zones; a zone without a letter counts as its `a`
+    /// half.
+    pub const fn half_zones_from(self, other: Self) -> u8 {
+        self.half_zone_index().abs_diff(other.half_zone_index())
+    }
+
+    const fn half_zone_index(self) -> u8 {
+        let upper = match self.half {
+            Some('b') => 1,
+            _ => 0,
+        };
+        self.number * 2 + upper
+    }
+}
+
+impl fmt::Display for HardinessZone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.half {
+            Some(half) => write!(f, "{}{half}", self.number),
+            None => write!(f, "{}", self.number),
+        }
+    }
+}
+
+/// Average last spring and first fall frost, as days of the year.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrostWindow {
+    last_spring: u32,
+    first_fall: u32,
+}
+
+impl FrostWindow {
+    /// Days a crop planted on `day_of_year` has before the first fall frost.
+    /// Before the last spring frost the whole season is still ahead.
+    pub fn growing_days_remaining(self, day_of_year: i32) -> i32 {
+        let last_spring = i32::try_from(self.last_spring).unwrap_or(0);
+        let first_fall = i32::try_from(self.first_fall).unwrap_or(0);
+        if day_of_year <= last_spring {
+            first_fall - last_spring
+        } else {
+            (first_fall - day_of_year).max(0)
+        }
+    }
+
+    /// `MM-DD`.
+    pub fn last_spring_frost(self) -> String {
+        format_day(self.last_spring)
+    }
+
+    /// `MM-DD`.
+    pub fn first_fall_frost(self) -> String {
+        format_day(self.first_fall)
+    }
+}
+
+/// The derived climate for a point.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Hardiness {
+    pub zone: HardinessZone,
+    /// `None` where frost is rare enough to treat the season as year-round.
+    pub frost_window: Option<FrostWindow>,
+    pub station: &'static str,
+    pub distance_km: f64,
+}
+
+#[derive(Debug)]
+struct Station {
+    name: String,
+    lat: f64,
+    lng: f64,
+    zone: HardinessZone,
+    frost_window: Option<FrostWindow>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StationRecord {
+    name: String,
+    lat: f64,
+    lng: f64,
+    zone: String,
+    last_spring_frost: Option<String>,
+    first_fall_frost: Option<String>,
+}
+
+/// Zone and frost dates from the nearest reference station, or `None` when
+/// no station is close enough.
+pub fn lookup(lat: f64, lng: f64) -> Option<Hardiness> {
+    stations()
+        .iter()
+        .map(|station| {
+            (
+                station,
+                location::haversine_km(lat, lng, station.lat, station.lng),
+            )
+        })
+        .filter(|(_, distance_km)| *distance_km <= MAX_STATION_DISTANCE_KM)
+        .min_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(station, distance_km)| Hardiness {
+            zone: station.zone,
+            frost_window: station.frost_window,
+            station: station.name.as_str(),
+            distance_km,
+        })
+}
+
+#[allow(clippy::panic)]
+fn stations() -> &'static [Station] {
+    static STATIONS: OnceLock<Vec<Station>> = OnceLock::new();
+
+    STATIONS
+        .get_or_init(|| {
+            let raw = include_str!("../../../data/hardiness/reference_stations.v1.json");
+            let records: Vec<StationRecord> = match serde_json::from_str(raw) {
+                Ok(records) => records,
+                Err(error) => {
+                    panic!("hardiness station JSON should parse during startup: {error}");
+                }
+            };
+
+            match records
+                .into_iter()
+                .map(station_from_record)
+                .collect::<Result<Vec<_>, _>>()
+            {
+                Ok(stations) => stations,
+                Err(error) => {
+                    panic!("hardiness station JSON must contain valid stations: {error}");
+                }
+            }
+        })
+        .as_slice()
+}
+
+fn station_from_record(record: StationRecord) -> Result<Station, String> {
+    if !(-90.0..=90.0).contains(&record.lat) || !(-180.0..=180.0).contains(&record.lng) {
+        return Err(format!("station {} has invalid coordinates", record.name));
+    }
+    let zone = HardinessZone::parse(&record.zone)
+        .ok_or_else(|| format!("station {} has invalid zone {}", record.name, record.zone))?;
+
+    let frost_window = match (&record.last_spring_frost, &record.first_fall_frost) {
+        (Some(last_spring), Some(first_fall)) => {
+            let last_spring = parse_day(last_spring)
+                .ok_or_else(|| format!("station {} has invalid lastSpringFrost", record.name))?;
+            let first_fall = parse_day(first_fall)
+                .ok_or_else(|| format!("station {} has invalid firstFallFrost", record.name))?;
+            if first_fall <= last_spring {
+                return Err(format!(
+                    "station {} has firstFallFrost before lastSpringFrost",
+                    record.name
+                ));
+            }
+            Some(FrostWindow {
+                last_spring,
+                first_fall,
+            })
+        }
+        (None, None) => None,
+        _ => {
+            return Err(format!(
+                "station {} must have both frost dates or neither",
+                record.name
+            ))
+        }
+    };
+
+    Ok(Station {
+        name: record.name,
+        lat: record.lat,
+        lng: record.lng,
+        zone,
+        frost_window,
+    })
+}
+
+/// Day of year for an `MM-DD` date.
+fn parse_day(value: &str) -> Option<u32> {
+    let (month, day) = value.split_once('-')?;
+    NaiveDate::from_ymd_opt(REFERENCE_YEAR, month.parse().ok()?, day.parse().ok()?)
+        .map(|date| date.ordinal())
+}
+
+fn format_day(day_of_year: u32) -> String {
+    NaiveDate::from_yo_opt(REFERENCE_YEAR, day_of_year)
+        .map_or_else(String::new, |date| date.format("%m-%d").to_string())
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zones_parse_loosely_and_display_normalized() {
+        assert_eq!(HardinessZone::parse("7b").unwrap().to_string(), "7b");
+        assert_eq!(
+            HardinessZone::parse(" Zone 10A ").unwrap().to_string(),
+            "10a"
+        );
+        assert_eq!(HardinessZone::parse("6").unwrap().to_string(), "6");
+        assert_eq!(HardinessZone::parse("0a"), None);
+        assert_eq!(HardinessZone::parse("14"), None);
+        assert_eq!(HardinessZone::parse("7c"), None);
+        assert_eq!(HardinessZone::parse("warm"), None);
+        assert_eq!(HardinessZone::parse(""), None);
+    }
+
+    #[test]
+    fn half_zone_distance_treats_bare_numbers_as_a() {
+        let zone = |value| HardinessZone::parse(value).unwrap();
+        assert_eq!(zone("7a").half_zones_from(zone("7b")), 1);
+        assert_eq!(zone("6b").half_zones_from(zone("8a")), 3);
+        assert_eq!(zone("7").half_zones_from(zone("7a")), 0);
+    }
+
+    #[test]
+    fn bundled_stations_load() {
+        assert!(stations().len() > 100);
+    }
+
+    #[test]
+    fn lookup_uses_the_nearest_station_in_range() {
+        let buffalo = lookup(42.90, -78.85).unwrap();
+        assert_eq!(buffalo.station, "Buffalo, NY");
+        assert_eq!(buffalo.zone.to_string(), "6b");
+        let window = buffalo.frost_window.unwrap();
+        assert_eq!(window.last_spring_frost(), "04-30");
+        assert_eq!(window.first_fall_frost(), "10-20");
+
+        assert!(lookup(21.30, -157.85).unwrap().frost_window.is_none());
+        // Mid-Atlantic Ocean.
+        assert_eq!(lookup(35.0, -45.0), None);
+    }
+
+    #[test]
+    fn growing_days_follow_the_frost_window() {
+        let window = FrostWindow {
+            last_spring: parse_day("04-30").unwrap(),
+            first_fall: parse_day("10-20").unwrap(),
+        };
+        assert_eq!(window.growing_days_remaining(60), 173);
+        assert_eq!(window.growing_days_remaining(200), 93);
+        assert_eq!(window.growing_days_remaining(320), 0);
+    }
+
+    #[test]
+    fn frost_days_round_trip() {
+        assert_eq!(parse_day("03-01"), Some(60));
+        assert_eq!(format_day(60), "03-01");
+        assert_eq!(parse_day("02-30"), None);
+        assert_eq!(parse_day("march"), None);
+    }
+}
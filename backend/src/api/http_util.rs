@@ -0,0 +1,267 @@
+//! Request parsing and response building shared by every handler.
+
+use crate::config;
+use crate::error::{ApiError, ValidationErrors};
+use lambda_http::{Body, Request, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::warn;
+use uuid::Uuid;
+
+/// Deserializes a write payload. Keys no field accepts are logged, and with
+/// `STRICT_REQUEST_FIELDS` set they fail the request with one
+/// `unknown_field` issue per key, so a typo such as `pickupAdress` is not
+/// silently dropped.
+pub fn parse_json_body<T: DeserializeOwned>(request: &Request) -> Result<T, ApiError> {
+    parse_json_body_with(request, config::get().strict_request_fields)
+}
+
+fn parse_json_body_with<T: DeserializeOwned>(
+    request: &Request,
+    reject_unknown_fields: bool,
+) -> Result<T, ApiError> {
+    let bytes = match request.body() {
+        Body::Text(text) => text.as_bytes(),
+        Body::Binary(bytes) => bytes,
+        Body::Empty => {
+            return Err(ApiError::bad_request(
+                "invalid_body",
+                "Request body is required",
+            ))
+        }
+    };
+    let invalid_body = |e: serde_json::Error| {
+        ApiError::bad_request("invalid_body", format!("Invalid JSON body: {e}"))
+    };
+
+    let mut unknown_fields = Vec::new();
+    let mut deserializer = serde_json::Deserializer::from_slice(bytes);
+    let payload: T = serde_ignored::deserialize(&mut deserializer, |path| {
+        unknown_fields.push(path.to_string());
+    })
+    .map_err(invalid_body)?;
+    deserializer.end().map_err(invalid_body)?;
+
+    if unknown_fields.is_empty() {
+        return Ok(payload);
+    }
+    if !reject_unknown_fields {
+        warn!(unknown_fields = ?unknown_fields, "Ignoring unknown request body fields");
+        return Ok(payload);
+    }
+    let mut errors = ValidationErrors::new();
+    for field in &unknown_fields {
+        errors.add(field, "unknown_field", format!("Unknown field '{field}'"));
+    }
+    errors.into_result()?;
+    Ok(payload)
+}
+
+/// Parses an id from a body field or query parameter, tolerating surrounding
+/// whitespace. Path ids are already checked by the router.
+pub fn parse_uuid(value: &str, field_name: &str) -> Result<Uuid, ApiError> {
+    Uuid::parse_str(value.trim()).map_err(|_| {
+        ApiError::invalid_field(
+            field_name,
+            "invalid_uuid",
+            format!("{field_name} must be a valid UUID"),
+        )
+    })
+}
+
+/// Decodes `%XX` escapes in a raw query value. Handlers split the query
+/// string themselves, so values that may contain reserved characters (such
+/// as encoded polylines) pass through here.
+pub fn percent_decode(value: &str, field_name: &str) -> Result<String, ApiError> {
+    let invalid = || {
+        ApiError::invalid_field(
+            field_name,
+            "invalid_encoding",
+            format!("{field_name} has an invalid percent-encoding"),
+        )
+    };
+
+    let bytes = value.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = bytes.get(index + 1..index + 3).ok_or_else(invalid)?;
+            let hex = std::str::from_utf8(hex).map_err(|_| invalid())?;
+            decoded.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).map_err(|_| invalid())
+}
+
+/// The caller's preferred locale from `Accept-Language`, lowercased: the
+/// tag with the highest `q`, earliest first on ties. None when the header is
+/// missing, only `*`, or malformed, in which case catalog names are used.
+pub fn request_locale(request: &Request) -> Option<String> {
+    let header = request.headers().get("Accept-Language")?.to_str().ok()?;
+    preferred_locale(header)
+}
+
+fn preferred_locale(header: &str) -> Option<String> {
+    let mut best: Option<(f32, &str)> = None;
+    for entry in header.split(',') {
+        let mut parts = entry.split(';').map(str::trim);
+        let tag = parts.next().unwrap_or_default();
+        let quality = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .map_or(Some(1.0), |q| q.parse::<f32>().ok());
+        let Some(quality) = quality.filter(|q| *q > 0.0) else {
+            continue;
+        };
+        if !is_language_tag(tag) {
+            continue;
+        }
+        if best.is_none_or(|(best_quality, _)| quality > best_quality) {
+            best = Some((quality, tag));
+        }
+    }
+    best.map(|(_, tag)| tag.to_ascii_lowercase())
+}
+
+fn is_language_tag(tag: &str) -> bool {
+    let mut subtags = tag.split('-');
+    let primary = subtags.next().unwrap_or_default();
+    (2..=3).contains(&primary.len())
+        && primary.chars().all(|c| c.is_ascii_alphabetic())
+        && subtags.all(|subtag| {
+            (2..=8).contains(&subtag.len()) && subtag.chars().all(|c| c.is_ascii_alphanumeric())
+        })
+}
+
+pub fn json_response<T: Serialize>(status: u16, payload: &T) -> Result<Response<Body>, ApiError> {
+    let body = serde_json::to_string(payload)
+        .map_err(|e| ApiError::internal(format!("Failed to serialize response: {e}")))?;
+
+    Response::builder()
+        .status(status)
+        .header("content-type", "application/json")
+        .body(Body::from(body))
+        .map_err(|e| ApiError::internal(e.to_string()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, serde::Deserialize)]
+    struct Payload {
+        name: String,
+    }
+
+    #[derive(Debug, serde::Deserialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Override {
+        pickup_address: Option<String>,
+        profile: Option<Payload>,
+    }
+
+    #[test]
+    fn parse_json_body_requires_body() {
+        let request = Request::new(Body::Empty);
+        let error = parse_json_body::<Payload>(&request).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_body");
+        assert_eq!(error.to_string(), "Request body is required");
+    }
+
+    #[test]
+    fn parse_json_body_reads_text_and_binary() {
+        let text = Request::new(Body::from(r#"{"name":"kale"}"#));
+        assert_eq!(parse_json_body::<Payload>(&text).unwrap().name, "kale");
+
+        let binary = Request::new(Body::from(br#"{"name":"chard"}"#.to_vec()));
+        assert_eq!(parse_json_body::<Payload>(&binary).unwrap().name, "chard");
+    }
+
+    #[test]
+    fn unknown_fields_are_ignored_unless_strict() {
+        let body = r#"{"pickupAdress":"12 Elm St","profile":{"name":"kale","nmae":"x"}}"#;
+        let request = Request::new(Body::from(body));
+
+        let lenient = parse_json_body_with::<Override>(&request, false).unwrap();
+        assert!(lenient.pickup_address.is_none());
+        assert_eq!(lenient.profile.unwrap().name, "kale");
+
+        let error = parse_json_body_with::<Override>(&request, true).unwrap_err();
+        assert_eq!(error.error_code(), "validation_failed");
+        let ApiError::Validation { issues } = error else {
+            panic!("expected validation issues, got {error:?}");
+        };
+        let fields: Vec<&str> = issues.iter().map(|issue| issue.field.as_str()).collect();
+        assert_eq!(fields, ["pickupAdress", "profile.nmae"]);
+
+        let known = Request::new(Body::from(r#"{"pickupAddress":"12 Elm St"}"#));
+        assert!(parse_json_body_with::<Override>(&known, true).is_ok());
+    }
+
+    #[test]
+    fn strict_parsing_reports_a_single_unknown_field_by_its_own_code() {
+        let request = Request::new(Body::from(r#"{"name":"kale","colour":"green"}"#));
+        let error = parse_json_body_with::<Payload>(&request, true).unwrap_err();
+        assert_eq!(error.error_code(), "unknown_field");
+        assert_eq!(error.to_string(), "Unknown field 'colour'");
+    }
+
+    #[test]
+    fn parse_json_body_rejects_trailing_data() {
+        let request = Request::new(Body::from(r#"{"name":"kale"} {}"#));
+        let error = parse_json_body::<Payload>(&request).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_body");
+    }
+
+    #[test]
+    fn parse_uuid_trims_and_names_field() {
+        assert!(parse_uuid(" 5df666d4-f6b1-4e6f-97d6-321e531ad7ca ", "listingId").is_ok());
+
+        let error = parse_uuid("nope", "listingId").unwrap_err();
+        assert_eq!(error.error_code(), "invalid_uuid");
+        assert_eq!(error.to_string(), "listingId must be a valid UUID");
+    }
+
+    #[test]
+    fn percent_decode_handles_escapes_and_rejects_truncated_ones() {
+        assert_eq!(
+            percent_decode("_p~iF~ps%7CU_ulLnnqC_mqNvxq%60%40", "polyline").unwrap(),
+            "_p~iF~ps|U_ulLnnqC_mqNvxq`@"
+        );
+        assert_eq!(percent_decode("plain", "q").unwrap(), "plain");
+        let error = percent_decode("abc%7", "polyline").unwrap_err();
+        assert_eq!(error.error_code(), "invalid_encoding");
+        assert!(percent_decode("%zz", "polyline").is_err());
+    }
+
+    #[test]
+    fn preferred_locale_picks_highest_quality_tag() {
+        assert_eq!(
+            preferred_locale("es-MX,es;q=0.9,en;q=0.8").as_deref(),
+            Some("es-mx")
+        );
+        assert_eq!(
+            preferred_locale("en;q=0.5, fr-CA").as_deref(),
+            Some("fr-ca")
+        );
+        assert_eq!(preferred_locale("de, fr").as_deref(), Some("de"));
+        assert_eq!(preferred_locale("*"), None);
+        assert_eq!(preferred_locale("es;q=0, x_y"), None);
+        assert_eq!(preferred_locale(""), None);
+    }
+
+    #[test]
+    fn json_response_sets_status_and_content_type() {
+        let response = json_response(201, &serde_json::json!({ "ok": true })).unwrap();
+        assert_eq!(response.status().as_u16(), 201);
+        assert_eq!(
+            response.headers().get("content-type").unwrap(),
+            "application/json"
+        );
+    }
+}
@@ -0,0 +1,91 @@
+//! Synchronous invocation of the Node workers that own shared pipeline logic.
+//!
+//! There is no Lambda SDK client in this binary, so the `Invoke` call is made
+//! directly against the service endpoint through [`aws_signing`].
+
+use crate::aws_signing;
+use crate::telemetry;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::time::Duration;
+use tracing::Instrument;
+
+const INVOKE_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Invokes `function_name` with a JSON payload and waits for its result.
+/// A function that throws comes back as an error carrying its message.
+pub async fn invoke_json<T: Serialize + Sync, R: DeserializeOwned>(
+    function_name: &str,
+    payload: &T,
+) -> Result<R, lambda_http::Error> {
+    let body = serde_json::to_vec(payload)
+        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize payload: {e}")))?;
+
+    let request = aws_signing::signed_json_post(
+        "lambda",
+        |region| invoke_url(region, function_name),
+        body,
+        INVOKE_TIMEOUT,
+    )
+    .await?;
+
+    let span = telemetry::client_span("lambda", function_name);
+    let response = request.send().instrument(span.clone()).await?;
+    let status = response.status();
+    let function_error = response.headers().contains_key("x-amz-function-error");
+    let text = response.text().instrument(span).await?;
+
+    if !status.is_success() {
+        return Err(lambda_http::Error::from(format!(
+            "Invoke of {function_name} failed with status {status}: {text}"
+        )));
+    }
+    if function_error {
+        return Err(lambda_http::Error::from(format!(
+            "{function_name} returned an error: {}",
+            function_error_message(&text)
+        )));
+    }
+
+    serde_json::from_str(&text).map_err(|e| {
+        lambda_http::Error::from(format!("Invalid response from {function_name}: {e}"))
+    })
+}
+
+fn invoke_url(region: &str, function_name: &str) -> String {
+    format!(
+        "https://lambda.{region}.amazonaws.com/2015-03-31/functions/{function_name}/invocations"
+    )
+}
+
+/// Node runtime errors arrive as `{ errorType, errorMessage, trace }`.
+fn function_error_message(body: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(body)
+        .ok()
+        .and_then(|value| value.get("errorMessage")?.as_str().map(str::to_string))
+        .unwrap_or_else(|| body.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invoke_url_targets_regional_endpoint() {
+        assert_eq!(
+            invoke_url("us-east-1", "signal-recompute"),
+            "https://lambda.us-east-1.amazonaws.com/2015-03-31/functions/signal-recompute/invocations"
+        );
+    }
+
+    #[test]
+    fn function_error_message_prefers_node_error_message() {
+        assert_eq!(
+            function_error_message(
+                r#"{"errorType":"Error","errorMessage":"Unsupported window: 3","trace":[]}"#
+            ),
+            "Unsupported window: 3"
+        );
+        assert_eq!(function_error_message("boom"), "boom");
+    }
+}
@@ -0,0 +1,107 @@
+//! What a surplus listing offers. Listings started out as produce only, so
+//! `produce` is the default wherever a kind is not given.
+
+use crate::error::ApiError;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ListingKind {
+    Produce,
+    Seeds,
+    Seedlings,
+    ToolsLoan,
+    Compost,
+}
+
+impl ListingKind {
+    pub const ALL: [Self; 5] = [
+        Self::Produce,
+        Self::Seeds,
+        Self::Seedlings,
+        Self::ToolsLoan,
+        Self::Compost,
+    ];
+
+    pub fn parse(value: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.as_str() == value)
+    }
+
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Produce => "produce",
+            Self::Seeds => "seeds",
+            Self::Seedlings => "seedlings",
+            Self::ToolsLoan => "tools_loan",
+            Self::Compost => "compost",
+        }
+    }
+
+    /// Seeds and seedlings are of a catalog crop just as produce is; tools
+    /// and compost are not.
+    pub const fn has_crop(self) -> bool {
+        matches!(self, Self::Produce | Self::Seeds | Self::Seedlings)
+    }
+
+    /// Loans are the only kind that comes back to the grower.
+    pub const fn has_return_by(self) -> bool {
+        matches!(self, Self::ToolsLoan)
+    }
+
+    /// Parses a comma-separated `kind` filter, ignoring duplicates.
+    pub fn parse_filter(value: &str, field: &str) -> Result<Vec<Self>, ApiError> {
+        let mut kinds = Vec::new();
+        for part in value
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+        {
+            let kind = Self::parse(part).ok_or_else(|| invalid_kind(field, part))?;
+            if !kinds.contains(&kind) {
+                kinds.push(kind);
+            }
+        }
+        if kinds.is_empty() {
+            return Err(invalid_kind(field, value));
+        }
+        Ok(kinds)
+    }
+}
+
+pub fn invalid_kind(field: &str, value: &str) -> ApiError {
+    ApiError::invalid_field(
+        field,
+        "invalid_enum",
+        format!(
+            "Invalid {field} '{value}'. Allowed values: {}",
+            ListingKind::ALL.map(ListingKind::as_str).join(", ")
+        ),
+    )
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn kinds_round_trip() {
+        for kind in ListingKind::ALL {
+            assert_eq!(ListingKind::parse(kind.as_str()), Some(kind));
+        }
+        assert_eq!(ListingKind::parse("Produce"), None);
+    }
+
+    #[test]
+    fn filter_dedupes_and_rejects_unknown_kinds() {
+        assert_eq!(
+            ListingKind::parse_filter("seeds, seedlings,seeds", "kind").unwrap(),
+            vec![ListingKind::Seeds, ListingKind::Seedlings]
+        );
+        assert_eq!(
+            ListingKind::parse_filter("seeds,hay", "kind")
+                .unwrap_err()
+                .error_code(),
+            "invalid_enum"
+        );
+        assert!(ListingKind::parse_filter(",", "kind").is_err());
+    }
+}
@@ -0,0 +1,258 @@
+//! Sparse fieldsets for listing collections. Discovery, the derived feed, and
+//! my-listings accept `fields=a,b,c` or `view=compact` and trim each entry of
+//! the response's `items` array to the selected keys.
+
+use crate::error::ApiError;
+use serde::Serialize;
+use serde_json::Value;
+
+/// Serialized `ListingItem` keys a client may select.
+pub const LISTING_FIELDS: &[&str] = &[
+    "id",
+    "userId",
+    "growerCropId",
+    "listingKind",
+    "cropId",
+    "varietyId",
+    "cropName",
+    "varietyName",
+    "title",
+    "unit",
+    "quantityTotal",
+    "quantityRemaining",
+    "reservedQuantity",
+    "availableStart",
+    "availableEnd",
+    "returnBy",
+    "publishAt",
+    "expireAt",
+    "status",
+    "pickupLocationText",
+    "pickupAddress",
+    "effectivePickupAddress",
+    "pickupDisclosurePolicy",
+    "pickupNotes",
+    "contactPref",
+    "geoKey",
+    "lat",
+    "lng",
+    "groupId",
+    "createdAt",
+    "photoCropMismatch",
+    "claimCounts",
+];
+
+/// What a list card needs: enough to render and to fetch the detail view.
+pub const COMPACT_LISTING_FIELDS: &[&str] = &[
+    "id",
+    "listingKind",
+    "cropId",
+    "varietyId",
+    "cropName",
+    "varietyName",
+    "title",
+    "unit",
+    "quantityRemaining",
+    "availableStart",
+    "availableEnd",
+    "status",
+    "geoKey",
+    "createdAt",
+];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListingProjection {
+    Full,
+    /// Always includes `id`.
+    Fields(Vec<&'static str>),
+}
+
+impl ListingProjection {
+    /// Builds the projection from raw `fields` and `view` query values.
+    pub fn parse(fields: Option<&str>, view: Option<&str>) -> Result<Self, ApiError> {
+        let fields = fields.filter(|value| !value.is_empty());
+        let view = view.filter(|value| !value.is_empty());
+
+        match (fields, view) {
+            (Some(_), Some(_)) => Err(ApiError::invalid_field(
+                "fields",
+                "conflicting_projection",
+                "Use either fields or view, not both",
+            )),
+            (Some(fields), None) => parse_fields(fields),
+            (None, Some("compact")) => Ok(Self::Fields(COMPACT_LISTING_FIELDS.to_vec())),
+            (None, Some("full") | None) => Ok(Self::Full),
+            (None, Some(other)) => Err(ApiError::invalid_field(
+                "view",
+                "invalid_enum",
+                format!("Invalid view '{other}'. Allowed values: compact, full"),
+            )),
+        }
+    }
+
+    pub const fn is_full(&self) -> bool {
+        matches!(self, Self::Full)
+    }
+
+    /// Serializes `response` and trims every object in its top-level `items`
+    /// array to the selected fields.
+    pub fn apply<T: Serialize>(&self, response: &T) -> Result<Value, ApiError> {
+        let mut value = serde_json::to_value(response)
+            .map_err(|e| ApiError::internal(format!("Failed to serialize response: {e}")))?;
+        let Self::Fields(fields) = self else {
+            return Ok(value);
+        };
+
+        if let Some(items) = value.get_mut("items").and_then(Value::as_array_mut) {
+            for item in items {
+                if let Some(object) = item.as_object_mut() {
+                    object.retain(|key, _| fields.contains(&key.as_str()));
+                }
+            }
+        }
+        Ok(value)
+    }
+}
+
+fn parse_fields(raw: &str) -> Result<ListingProjection, ApiError> {
+    let mut selected = vec!["id"];
+    for name in raw
+        .split(',')
+        .flat_map(|part| part.split("%2C"))
+        .flat_map(|part| part.split("%2c"))
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+    {
+        let Some(field) = LISTING_FIELDS.iter().find(|field| **field == name) else {
+            return Err(ApiError::invalid_field(
+                "fields",
+                "unknown_field",
+                format!(
+                    "Unknown listing field '{name}'. Allowed values: {}",
+                    LISTING_FIELDS.join(", ")
+                ),
+            ));
+        };
+        if !selected.contains(field) {
+            selected.push(field);
+        }
+    }
+    Ok(ListingProjection::Fields(selected))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use crate::models::listing::ListingItem;
+    use serde_json::json;
+
+    #[test]
+    fn parse_defaults_to_full() {
+        assert_eq!(
+            ListingProjection::parse(None, None).unwrap(),
+            ListingProjection::Full
+        );
+        assert_eq!(
+            ListingProjection::parse(Some(""), Some("full")).unwrap(),
+            ListingProjection::Full
+        );
+    }
+
+    #[test]
+    fn parse_fields_always_includes_id_and_dedupes() {
+        assert_eq!(
+            ListingProjection::parse(Some("title,quantityRemaining,title"), None).unwrap(),
+            ListingProjection::Fields(vec!["id", "title", "quantityRemaining"])
+        );
+        assert_eq!(
+            ListingProjection::parse(Some("title%2Cunit"), None).unwrap(),
+            ListingProjection::Fields(vec!["id", "title", "unit"])
+        );
+    }
+
+    #[test]
+    fn parse_rejects_unknown_fields_and_views() {
+        let error = ListingProjection::parse(Some("title,secret"), None).unwrap_err();
+        assert_eq!(error.error_code(), "unknown_field");
+
+        let error = ListingProjection::parse(None, Some("tiny")).unwrap_err();
+        assert_eq!(error.error_code(), "invalid_enum");
+
+        let error = ListingProjection::parse(Some("title"), Some("compact")).unwrap_err();
+        assert_eq!(error.error_code(), "conflicting_projection");
+    }
+
+    #[test]
+    fn listing_fields_match_serialized_listing_item() {
+        let item = ListingItem {
+            id: String::new(),
+            user_id: String::new(),
+            grower_crop_id: None,
+            listing_kind: String::new(),
+            crop_id: None,
+            variety_id: None,
+            crop_name: None,
+            variety_name: None,
+            title: None,
+            unit: None,
+            quantity_total: None,
+            quantity_remaining: None,
+            reserved_quantity: String::new(),
+            available_start: None,
+            available_end: None,
+            return_by: None,
+            publish_at: None,
+            expire_at: None,
+            status: String::new(),
+            pickup_location_text: None,
+            pickup_address: None,
+            effective_pickup_address: None,
+            pickup_disclosure_policy: String::new(),
+            pickup_notes: None,
+            contact_pref: String::new(),
+            geo_key: None,
+            lat: None,
+            lng: None,
+            group_id: None,
+            created_at: String::new(),
+            photo_crop_mismatch: None,
+            claim_counts: None,
+        };
+        let value = serde_json::to_value(item).unwrap();
+        let mut keys = value
+            .as_object()
+            .unwrap()
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>();
+        let mut expected = LISTING_FIELDS
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+        keys.sort();
+        expected.sort();
+        assert_eq!(keys, expected);
+    }
+
+    #[test]
+    fn compact_fields_are_listing_fields() {
+        for field in COMPACT_LISTING_FIELDS {
+            assert!(LISTING_FIELDS.contains(field), "{field}");
+        }
+    }
+
+    #[test]
+    fn apply_trims_items_only() {
+        let response = json!({
+            "items": [{ "id": "1", "title": "Kale", "pickupAddress": "12 Elm St" }],
+            "hasMore": false,
+        });
+        let projection = ListingProjection::parse(Some("title"), None).unwrap();
+        assert_eq!(
+            projection.apply(&response).unwrap(),
+            json!({ "items": [{ "id": "1", "title": "Kale" }], "hasMore": false })
+        );
+        assert_eq!(ListingProjection::Full.apply(&response).unwrap(), response);
+    }
+}
@@ -1,11 +1,21 @@
-use serde::Deserialize;
+use crate::config;
+use crate::error::{ApiError, ValidationErrors};
+use crate::geocoding::{self, GeocodeCandidate, GeocodingProvider};
+use crate::metrics;
+use crate::telemetry;
 use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
-use std::time::Duration;
-use tracing::{error, info, warn};
+use tracing::{error, info, warn, Instrument};
 
 const STORAGE_COORD_PRECISION: i32 = 5;
-const RESPONSE_COORD_PRECISION: i32 = 2;
+const LOG_COORD_PRECISION: i32 = 2;
+
+/// Length of the geohash stored as `geo_key` on profiles, listings and
+/// requests. The geo-key recalculation worker re-derives stored keys at its
+/// `GEO_KEY_PRECISION`, which must be changed alongside this.
+const GEO_KEY_PRECISION: usize = 7;
+pub const KM_PER_MILE: f64 = 1.609_344;
+const EARTH_RADIUS_KM: f64 = 6371.0;
 
 #[derive(Debug)]
 pub struct GeocodedPoint {
@@ -14,12 +24,6 @@ pub struct GeocodedPoint {
     pub geo_key: String,
 }
 
-#[derive(Debug, Deserialize)]
-struct NominatimSearchResult {
-    lat: String,
-    lon: String,
-}
-
 pub fn normalize_address(address: &str) -> String {
     address.split_whitespace().collect::<Vec<_>>().join(" ")
 }
@@ -35,12 +39,90 @@ pub fn normalize_optional_address(address: Option<&str>) -> Option<String> {
     })
 }
 
-pub fn round_for_response(value: f64) -> f64 {
-    round_coordinate(value, RESPONSE_COORD_PRECISION)
+/// Geocodes `address`, answering from `geocode_cache` when an unexpired
+/// entry exists and asking the configured provider otherwise. Any match is
+/// accepted, which suits search areas and event venues; pickup addresses go
+/// through [`geocode_street_address`].
+pub async fn geocode_address(
+    address: &str,
+    correlation_id: &str,
+) -> Result<GeocodedPoint, lambda_http::Error> {
+    geocode(address, None, correlation_id).await
 }
 
-pub async fn geocode_address(
+/// Geocodes an address goods are picked up from. The address must pass
+/// [`validate_street_address`], and a best match below the configured
+/// confidence is refused with a 422 listing the provider's candidates, so a
+/// typo cannot quietly place a listing in the wrong cell. `field` names the
+/// request field in errors.
+pub async fn geocode_street_address(
+    address: &str,
+    field: &str,
+    correlation_id: &str,
+) -> Result<GeocodedPoint, lambda_http::Error> {
+    validate_street_address(field, &normalize_address(address))?;
+    geocode(address, Some(field), correlation_id).await
+}
+
+/// Structural checks for a street address: a street line with a number and
+/// a name, then a locality and a postal code in later comma-separated parts.
+/// Every missing part is reported at once.
+pub fn validate_street_address(field: &str, normalized_address: &str) -> Result<(), ApiError> {
+    let mut parts = normalized_address
+        .split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty());
+    let street = parts.next().unwrap_or_default();
+    let rest = parts.flat_map(str::split_whitespace).collect::<Vec<_>>();
+
+    let mut errors = ValidationErrors::new();
+    let street_words = street.split_whitespace().collect::<Vec<_>>();
+    if !street_words
+        .iter()
+        .any(|word| word.starts_with(|ch: char| ch.is_ascii_digit()))
+        || !street_words.iter().any(|word| is_place_word(word))
+    {
+        errors.add(
+            field,
+            "address_missing_street",
+            format!("{field} must begin with a street number and street name"),
+        );
+    }
+    if !rest.iter().any(|word| is_place_word(word)) {
+        errors.add(
+            field,
+            "address_missing_locality",
+            format!("{field} must include a city or town after the street, separated by a comma"),
+        );
+    }
+    if !rest.iter().any(|word| is_postal_code(word)) {
+        errors.add(
+            field,
+            "address_missing_postal_code",
+            format!("{field} must include a postal code"),
+        );
+    }
+    errors.into_result()
+}
+
+fn is_place_word(word: &str) -> bool {
+    word.chars().filter(|ch| ch.is_alphabetic()).count() >= 2
+        && !word.chars().any(|ch| ch.is_ascii_digit())
+}
+
+/// Covers US ZIP and ZIP+4, and the alphanumeric halves of UK and Canadian
+/// codes ("SW1A", "K1A", "0B1").
+fn is_postal_code(word: &str) -> bool {
+    (3..=10).contains(&word.len())
+        && word.chars().any(|ch| ch.is_ascii_digit())
+        && word
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '-')
+}
+
+async fn geocode(
     address: &str,
+    street_field: Option<&str>,
     correlation_id: &str,
 ) -> Result<GeocodedPoint, lambda_http::Error> {
     let normalized_address = normalize_address(address);
@@ -48,117 +130,202 @@ pub async fn geocode_address(
         return Err(lambda_http::Error::from("address is required".to_string()));
     }
 
+    let geocoder = &config::get().geocoder;
     let address_fingerprint = hash_address(&normalized_address);
-    info!(
-        correlation_id = correlation_id,
-        address_fingerprint = address_fingerprint,
-        "Attempting to geocode address"
-    );
+    let provider = geocoding::provider();
+    let cache_key = geocoding::cache_key(&normalized_address);
+    let precise_enough =
+        |confidence: f64| street_field.is_none() || confidence >= geocoder.min_confidence;
 
-    let base_url = std::env::var("GEOCODER_BASE_URL")
-        .unwrap_or_else(|_| "https://nominatim.openstreetmap.org".to_string());
-    let user_agent = std::env::var("GEOCODER_USER_AGENT").unwrap_or_else(|_| {
-        "community-garden/0.1 (+https://github.com/allenheltondev/community-garden)".to_string()
-    });
-    let timeout_ms = std::env::var("GEOCODER_TIMEOUT_MS")
-        .ok()
-        .and_then(|value| value.parse::<u64>().ok())
-        .unwrap_or(3_000);
-
-    let client = reqwest::Client::builder()
-        .timeout(Duration::from_millis(timeout_ms))
-        .user_agent(user_agent)
-        .build()
-        .map_err(|error| {
-            lambda_http::Error::from(format!("Failed to build geocoder client: {error}"))
-        })?;
-
-    let request_url = format!("{}/search", base_url.trim_end_matches('/'));
-    let response = client
-        .get(request_url)
-        .query(&[
-            ("format", "jsonv2"),
-            ("limit", "1"),
-            ("addressdetails", "0"),
-            ("q", normalized_address.as_str()),
-        ])
-        .send()
+    let cached = geocoding::lookup_cached(&cache_key)
         .await
-        .map_err(|error| {
-            error!(
-                correlation_id = correlation_id,
-                address_fingerprint = address_fingerprint,
-                error = %error,
-                "Geocoding request failed"
-            );
-            geocode_dependency_error()
-        })?;
-
-    if !response.status().is_success() {
-        warn!(
+        .filter(|cached| precise_enough(cached.confidence));
+    let (coordinates, cache_hit) = if let Some(cached) = cached {
+        (cached.point, true)
+    } else {
+        info!(
             correlation_id = correlation_id,
             address_fingerprint = address_fingerprint,
-            status = response.status().as_u16(),
-            "Geocoding request returned non-success status"
+            provider = provider.name(),
+            "Attempting to geocode address"
         );
-        return Err(geocode_dependency_error());
-    }
+        let candidates = search_provider(
+            provider,
+            &normalized_address,
+            correlation_id,
+            address_fingerprint,
+        )
+        .await?;
+        let best = &candidates[0];
+        // Cached at storage precision, so hits and misses store identical values.
+        let coordinates = geocoding::Coordinates {
+            lat: round_coordinate(best.point.lat, STORAGE_COORD_PRECISION),
+            lng: round_coordinate(best.point.lng, STORAGE_COORD_PRECISION),
+        };
+        // Stored even when too imprecise for this caller; a lenient caller
+        // can still use it, and a strict one re-checks the confidence.
+        geocoding::store_cached(
+            &cache_key,
+            provider.name(),
+            geocoding::CachedPoint {
+                point: coordinates,
+                confidence: best.confidence,
+            },
+            geocoder.cache_ttl,
+        )
+        .await;
 
-    let results = response
-        .json::<Vec<NominatimSearchResult>>()
-        .await
-        .map_err(|error| {
-            error!(
+        if let Some(field) = street_field.filter(|_| !precise_enough(best.confidence)) {
+            warn!(
                 correlation_id = correlation_id,
                 address_fingerprint = address_fingerprint,
-                error = %error,
-                "Failed to parse geocoding response"
+                provider = provider.name(),
+                confidence = best.confidence,
+                "Geocoding match below confidence threshold"
             );
-            geocode_dependency_error()
-        })?;
-
-    let (lat, lng) = parse_geocoded_coordinates(results)?;
+            return Err(ApiError::unprocessable(
+                field,
+                "address_low_confidence",
+                format!("{field} could not be matched to a precise location"),
+                suggestions(&normalized_address, &candidates),
+            )
+            .into());
+        }
+        (coordinates, false)
+    };
+    metrics::record_geocode_lookup(provider.name(), cache_hit);
 
-    let lat = round_coordinate(lat, STORAGE_COORD_PRECISION);
-    let lng = round_coordinate(lng, STORAGE_COORD_PRECISION);
-    let geo_key = geohash::encode(geohash::Coord { x: lng, y: lat }, 7)
+    let geocoding::Coordinates { lat, lng } = coordinates;
+    let geo_key = geohash::encode(geohash::Coord { x: lng, y: lat }, GEO_KEY_PRECISION)
         .unwrap_or_else(|_| String::from("unknown"));
 
     info!(
         correlation_id = correlation_id,
         address_fingerprint = address_fingerprint,
+        cache_hit = cache_hit,
         geo_key = geo_key,
-        lat = round_for_response(lat),
-        lng = round_for_response(lng),
+        lat = round_coordinate(lat, LOG_COORD_PRECISION),
+        lng = round_coordinate(lng, LOG_COORD_PRECISION),
         "Geocoding succeeded"
     );
 
     Ok(GeocodedPoint { lat, lng, geo_key })
 }
 
-fn geocode_error() -> lambda_http::Error {
-    lambda_http::Error::from("Address could not be geocoded".to_string())
+/// Candidate labels to offer instead of `normalized_address`, best first,
+/// without repeats or the address as entered.
+fn suggestions(normalized_address: &str, candidates: &[GeocodeCandidate]) -> Vec<String> {
+    let mut suggestions: Vec<String> = Vec::new();
+    for label in candidates.iter().filter_map(|c| c.label.as_deref()) {
+        let label = normalize_address(label);
+        if !label.eq_ignore_ascii_case(normalized_address)
+            && !suggestions
+                .iter()
+                .any(|seen| seen.eq_ignore_ascii_case(&label))
+        {
+            suggestions.push(label);
+        }
+    }
+    suggestions
 }
 
-fn geocode_dependency_error() -> lambda_http::Error {
-    lambda_http::Error::from("Geocoding service unavailable".to_string())
+/// Returns at least one candidate, best first.
+async fn search_provider(
+    provider: &dyn GeocodingProvider,
+    normalized_address: &str,
+    correlation_id: &str,
+    address_fingerprint: u64,
+) -> Result<Vec<GeocodeCandidate>, lambda_http::Error> {
+    let outcome = provider
+        .search(normalized_address)
+        .instrument(telemetry::client_span("geocoder", "search"))
+        .await;
+
+    match outcome {
+        Ok(candidates) if !candidates.is_empty() => Ok(candidates),
+        Ok(_) => {
+            warn!(
+                correlation_id = correlation_id,
+                address_fingerprint = address_fingerprint,
+                provider = provider.name(),
+                "Geocoding found no match"
+            );
+            Err(geocode_error())
+        }
+        Err(error) => {
+            error!(
+                correlation_id = correlation_id,
+                address_fingerprint = address_fingerprint,
+                provider = provider.name(),
+                error = %error,
+                "Geocoding request failed"
+            );
+            Err(geocode_dependency_error())
+        }
+    }
+}
+
+/// Geohash base32 (no a, i, l, o), 1-12 characters, already lowercased.
+pub fn is_valid_geo_key(value: &str) -> bool {
+    if value.is_empty() || value.len() > 12 {
+        return false;
+    }
+
+    value
+        .chars()
+        .all(|ch| matches!(ch, '0'..='9' | 'b'..='h' | 'j'..='k' | 'm'..='n' | 'p'..='z'))
 }
 
-fn parse_geocoded_coordinates(
-    results: Vec<NominatimSearchResult>,
-) -> Result<(f64, f64), lambda_http::Error> {
-    let top_result = results.into_iter().next().ok_or_else(geocode_error)?;
-    let lat = top_result.lat.parse::<f64>().map_err(|_| geocode_error())?;
-    let lng = top_result.lon.parse::<f64>().map_err(|_| geocode_error())?;
+/// Truncates `geo_key` to the cell size that covers `radius_km`, so a
+/// `like 'prefix%'` scan finds everything nearby. Without a radius the full
+/// key is used.
+pub fn geo_prefix_for_radius(geo_key: &str, radius_km: Option<f64>) -> String {
+    if let Some(radius_km) = radius_km {
+        let precision = geohash_precision_for_radius_km(radius_km);
+        let prefix_len = precision.min(geo_key.len());
+        return geo_key[..prefix_len].to_string();
+    }
+
+    geo_key.to_string()
+}
+
+/// Great-circle distance between two coordinates in kilometers.
+pub fn haversine_km(lat1: f64, lng1: f64, lat2: f64, lng2: f64) -> f64 {
+    let d_lat = (lat2 - lat1).to_radians();
+    let d_lng = (lng2 - lng1).to_radians();
+    let a = (lat1.to_radians().cos() * lat2.to_radians().cos())
+        .mul_add((d_lng / 2.0).sin().powi(2), (d_lat / 2.0).sin().powi(2));
+    2.0 * EARTH_RADIUS_KM * a.sqrt().asin()
+}
 
-    if !(-90.0..=90.0).contains(&lat) || !(-180.0..=180.0).contains(&lng) {
-        return Err(geocode_error());
+/// Longest geohash whose cells are at least `radius_km` across.
+pub fn geohash_precision_for_radius_km(radius_km: f64) -> usize {
+    if radius_km <= 0.61 {
+        6
+    } else if radius_km <= 2.4 {
+        5
+    } else if radius_km <= 20.0 {
+        4
+    } else if radius_km <= 78.0 {
+        3
+    } else if radius_km <= 630.0 {
+        2
+    } else if radius_km <= 2500.0 {
+        1
+    } else {
+        1
     }
+}
+
+fn geocode_error() -> lambda_http::Error {
+    lambda_http::Error::from("Address could not be geocoded".to_string())
+}
 
-    Ok((lat, lng))
+fn geocode_dependency_error() -> lambda_http::Error {
+    lambda_http::Error::from("Geocoding service unavailable".to_string())
 }
 
-fn round_coordinate(value: f64, precision: i32) -> f64 {
+pub fn round_coordinate(value: f64, precision: i32) -> f64 {
     let factor = 10_f64.powi(precision);
     (value * factor).round() / factor
 }
@@ -170,10 +337,18 @@ fn hash_address(address: &str) -> u64 {
 }
 
 #[cfg(test)]
-#[allow(clippy::float_cmp)]
+#[allow(clippy::float_cmp, clippy::panic)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn haversine_km_matches_known_distance() {
+        assert!(haversine_km(37.77, -122.42, 37.77, -122.42).abs() < f64::EPSILON);
+        // San Francisco to Oakland is roughly 13 km.
+        let distance = haversine_km(37.7749, -122.4194, 37.8044, -122.2712);
+        assert!((12.0..14.5).contains(&distance), "{distance}");
+    }
+
     #[test]
     fn normalize_address_collapses_whitespace() {
         assert_eq!(normalize_address("  123   Main   St  "), "123 Main St");
@@ -189,8 +364,83 @@ mod tests {
     }
 
     #[test]
-    fn round_for_response_uses_low_precision() {
-        assert_eq!(round_for_response(37.77493), 37.77);
-        assert_eq!(round_for_response(-122.41942), -122.42);
+    fn validate_street_address_accepts_complete_addresses() {
+        for address in [
+            "123 Main St, Portland, OR 97201",
+            "12 Elm St, Apt 4, Buffalo, NY 14222-1234",
+            "Hauptstrasse 5, 10827 Berlin",
+            "10 Downing Street, London, SW1A 2AA",
+        ] {
+            assert_eq!(
+                validate_street_address("address", address),
+                Ok(()),
+                "{address}"
+            );
+        }
+    }
+
+    #[test]
+    fn validate_street_address_reports_every_missing_part() {
+        let Err(ApiError::Validation { issues }) =
+            validate_street_address("pickupAddress", "Main Street")
+        else {
+            panic!("expected validation issues");
+        };
+        let codes = issues.iter().map(|issue| issue.code).collect::<Vec<_>>();
+        assert_eq!(
+            codes,
+            vec![
+                "address_missing_street",
+                "address_missing_locality",
+                "address_missing_postal_code"
+            ]
+        );
+        assert!(issues.iter().all(|issue| issue.field == "pickupAddress"));
+
+        let Err(ApiError::Validation { issues }) =
+            validate_street_address("address", "123 Main St, Portland")
+        else {
+            panic!("expected validation issues");
+        };
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].code, "address_missing_postal_code");
+    }
+
+    #[test]
+    fn suggestions_skip_the_entered_address_and_repeats() {
+        let point = geocoding::Coordinates { lat: 0.0, lng: 0.0 };
+        let candidate = |label: &str| GeocodeCandidate {
+            point,
+            label: Some(label.to_string()),
+            confidence: 0.3,
+        };
+        let candidates = [
+            candidate("12 elm st, buffalo, ny 14222"),
+            candidate("12 Elm Ave,  Buffalo, NY 14222"),
+            candidate("12 Elm Ave, Buffalo, NY 14222"),
+        ];
+        assert_eq!(
+            suggestions("12 Elm St, Buffalo, NY 14222", &candidates),
+            vec!["12 Elm Ave, Buffalo, NY 14222".to_string()]
+        );
+    }
+
+    #[test]
+    fn geo_prefix_for_radius_uses_radius_precision() {
+        assert_eq!(geo_prefix_for_radius("9q8yyk8", Some(20.0)), "9q8y");
+        assert_eq!(geo_prefix_for_radius("9q8yyk8", Some(78.0)), "9q8");
+    }
+
+    #[test]
+    fn geo_prefix_for_radius_uses_full_key_when_radius_missing() {
+        assert_eq!(geo_prefix_for_radius("9q8yyk8", None), "9q8yyk8");
+    }
+
+    #[test]
+    fn is_valid_geo_key_rejects_non_geohash_characters() {
+        assert!(is_valid_geo_key("9q8yyk8"));
+        assert!(!is_valid_geo_key("abc!"));
+        assert!(!is_valid_geo_key(""));
+        assert!(!is_valid_geo_key("9q8yyk8zzzzzz"));
     }
 }
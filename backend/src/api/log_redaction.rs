@@ -0,0 +1,226 @@
+//! Redacts personal data from log lines before they are written.
+//!
+//! The JSON formatter writes each event as one complete line, so
+//! [`RedactingWriter`] parses the line, rewrites the fields named in
+//! [`LoggingConfig`] wherever they appear (event fields and every span in the
+//! span list), and prints the result to stdout. Lines that are not JSON or
+//! hold no sensitive field are written unchanged.
+
+use crate::config::LoggingConfig;
+use serde_json::{Number, Value};
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+use tracing_subscriber::fmt::MakeWriter;
+
+/// Hex characters of the SHA-256 kept in a hashed value. Enough to tell
+/// values apart in one log group, too short to be a useful lookup key.
+const HASH_PREFIX_LEN: usize = 12;
+
+#[derive(Debug, Clone)]
+pub struct Redactor {
+    hashed: Vec<String>,
+    coarse: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(config: &LoggingConfig) -> Self {
+        let enabled = |fields: &[String]| -> Vec<String> {
+            fields
+                .iter()
+                .filter(|field| {
+                    !config
+                        .unredacted_fields
+                        .iter()
+                        .any(|kept| kept.eq_ignore_ascii_case(field))
+                })
+                .map(|field| field.to_ascii_lowercase())
+                .collect()
+        };
+        Self {
+            hashed: enabled(&config.hashed_fields),
+            coarse: enabled(&config.coarse_fields),
+        }
+    }
+
+    /// Returns `line` with sensitive fields rewritten.
+    pub fn redact_line(&self, line: &[u8]) -> Vec<u8> {
+        let Ok(mut record) = serde_json::from_slice::<Value>(line) else {
+            return line.to_vec();
+        };
+        if !self.redact(&mut record) {
+            return line.to_vec();
+        }
+        let Ok(mut redacted) = serde_json::to_vec(&record) else {
+            return line.to_vec();
+        };
+        redacted.push(b'\n');
+        redacted
+    }
+
+    /// Rewrites matching fields in place and reports whether any matched.
+    fn redact(&self, value: &mut Value) -> bool {
+        match value {
+            Value::Object(fields) => {
+                let mut changed = false;
+                for (key, field) in fields.iter_mut() {
+                    let key = key.to_ascii_lowercase();
+                    if field.is_null() {
+                        continue;
+                    }
+                    if self.hashed.contains(&key) {
+                        *field = hash_value(field);
+                        changed = true;
+                    } else if self.coarse.contains(&key) {
+                        *field = coarsen_value(field);
+                        changed = true;
+                    } else {
+                        changed |= self.redact(field);
+                    }
+                }
+                changed
+            }
+            Value::Array(items) => items
+                .iter_mut()
+                .fold(false, |changed, item| self.redact(item) | changed),
+            _ => false,
+        }
+    }
+}
+
+fn hash_value(value: &Value) -> Value {
+    let text = value
+        .as_str()
+        .map_or_else(|| value.to_string(), str::to_string);
+    let digest = hex::encode(Sha256::digest(text.as_bytes()));
+    Value::String(format!("sha256:{}", &digest[..HASH_PREFIX_LEN]))
+}
+
+/// Rounds coordinates to two decimal places. Anything that is not a number
+/// is hashed instead, so an unexpected shape never leaks.
+fn coarsen_value(value: &Value) -> Value {
+    let coordinate = value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|text| text.parse::<f64>().ok()));
+    coordinate
+        .and_then(|coordinate| Number::from_f64((coordinate * 100.0).round() / 100.0))
+        .map_or_else(|| hash_value(value), Value::Number)
+}
+
+/// [`MakeWriter`] that runs every formatted line through a [`Redactor`].
+#[derive(Debug)]
+pub struct RedactingWriter {
+    redactor: Redactor,
+}
+
+impl RedactingWriter {
+    pub fn new(config: &LoggingConfig) -> Self {
+        Self {
+            redactor: Redactor::new(config),
+        }
+    }
+}
+
+impl<'a> MakeWriter<'a> for RedactingWriter {
+    type Writer = RedactingLine<'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        RedactingLine {
+            redactor: &self.redactor,
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct RedactingLine<'a> {
+    redactor: &'a Redactor,
+}
+
+impl Write for RedactingLine<'_> {
+    /// The formatter hands over a whole line in one call.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        io::stdout()
+            .lock()
+            .write_all(&self.redactor.redact_line(buf))?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn redact(config: &LoggingConfig, record: &Value) -> Value {
+        let line = format!("{record}\n");
+        let redacted = Redactor::new(config).redact_line(line.as_bytes());
+        serde_json::from_slice(&redacted).unwrap()
+    }
+
+    #[test]
+    fn hashes_and_coarsens_fields_in_events_and_spans() {
+        let record = json!({
+            "level": "INFO",
+            "fields": {
+                "message": "Geocoding succeeded",
+                "Email": "a@example.com",
+                "lat": 45.523_06
+            },
+            "spans": [{"name": "request", "effective_pickup_address": "1 Main St"}]
+        });
+        let redacted = redact(&LoggingConfig::default(), &record);
+
+        let email = redacted["fields"]["Email"].as_str().unwrap();
+        assert!(email.starts_with("sha256:"));
+        assert_eq!(email.len(), "sha256:".len() + HASH_PREFIX_LEN);
+        assert_eq!(redacted["fields"]["lat"], json!(45.52));
+        assert_eq!(redacted["fields"]["message"], "Geocoding succeeded");
+        assert!(redacted["spans"][0]["effective_pickup_address"]
+            .as_str()
+            .unwrap()
+            .starts_with("sha256:"));
+    }
+
+    #[test]
+    fn equal_values_hash_equally() {
+        let redacted = redact(
+            &LoggingConfig::default(),
+            &json!({"fields": {"email": "a@example.com"}, "span": {"email": "a@example.com"}}),
+        );
+        assert_eq!(redacted["fields"]["email"], redacted["span"]["email"]);
+    }
+
+    #[test]
+    fn unredacted_fields_pass_through() {
+        let config = LoggingConfig {
+            unredacted_fields: vec!["lat".to_string()],
+            ..LoggingConfig::default()
+        };
+        let redacted = redact(&config, &json!({"fields": {"lat": 45.523_06}}));
+        assert_eq!(redacted["fields"]["lat"], json!(45.523_06));
+    }
+
+    #[test]
+    fn non_numeric_coordinates_are_hashed() {
+        let redacted = redact(
+            &LoggingConfig::default(),
+            &json!({"fields": {"lng": "west"}}),
+        );
+        assert!(redacted["fields"]["lng"]
+            .as_str()
+            .unwrap()
+            .starts_with("sha256:"));
+    }
+
+    #[test]
+    fn lines_without_sensitive_fields_are_unchanged() {
+        let redactor = Redactor::new(&LoggingConfig::default());
+        let line = b"{\"z\":1,\"a\":{\"user_id\":\"u1\"}}\n";
+        assert_eq!(redactor.redact_line(line), line.to_vec());
+        assert_eq!(redactor.redact_line(b"not json\n"), b"not json\n".to_vec());
+    }
+}
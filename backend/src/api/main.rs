@@ -1,22 +1,53 @@
 use lambda_http::{run, service_fn, Body, Error, Request, Response};
 
+// `local.rs` declares the same modules for `api-local`.
+mod achievements;
 mod ai;
 mod ai_model_config;
+mod audit;
 mod auth;
+mod aws_signing;
 mod badge_cabinet;
 mod badge_evidence;
+mod config;
+mod coordinate_privacy;
 mod db;
+#[cfg(test)]
+mod e2e;
+mod error;
+mod events;
 mod gardener_tier;
+mod geocoding;
 mod handlers;
+mod hardiness;
+mod http_util;
+mod lambda_invoke;
+mod listing_kind;
+mod listing_projection;
 mod location;
+mod log_redaction;
+mod metrics;
 mod middleware;
 mod models;
+mod openapi;
+mod pg_tls;
+mod quantity;
+mod repo;
+mod route_corridor;
 mod router;
+mod service_area;
+mod signed_token;
 mod structured_json;
+mod telemetry;
 mod tips_framework;
+mod units;
 
-async fn function_handler(event: Request) -> Result<Response<Body>, Error> {
-    router::route_request(&event).await
+async fn function_handler(
+    event: Request,
+    config: &config::Config,
+) -> Result<Response<Body>, Error> {
+    let event = middleware::correlation::attach_correlation_id(event);
+    router::route_request(&event, config).await
 }
 
 fn install_rustls_crypto_provider() {
@@ -25,10 +56,24 @@ fn install_rustls_crypto_provider() {
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     install_rustls_crypto_provider();
+
+    // Loaded before logging so redaction applies from the first line; an
+    // invalid config still logs with the default redaction lists.
+    let config = config::init();
+    let logging = config.as_ref().map_or_else(
+        |_| config::LoggingConfig::default(),
+        |config| config.logging.clone(),
+    );
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        // Span close events carry each span's busy time; see `telemetry`.
+        .with_span_events(tracing_subscriber::fmt::format::FmtSpan::CLOSE)
+        .with_writer(log_redaction::RedactingWriter::new(&logging))
         .json()
         .init();
 
-    run(service_fn(function_handler)).await
+    // Failing here fails the Lambda init phase, so a bad deploy never serves.
+    let config = config?;
+
+    run(service_fn(|event| function_handler(event, config))).await
 }
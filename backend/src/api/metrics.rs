@@ -0,0 +1,212 @@
+use crate::config;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use uuid::Uuid;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Count,
+    Milliseconds,
+}
+
+impl Unit {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Count => "Count",
+            Self::Milliseconds => "Milliseconds",
+        }
+    }
+}
+
+/// Writes one `CloudWatch` Embedded Metric Format record to stdout. Lambda ships
+/// stdout to `CloudWatch` Logs, which extracts the metrics without an agent.
+pub fn emit(
+    dimensions: &[(&str, &str)],
+    metrics: &[(&str, f64, Unit)],
+    properties: &[(&str, &str)],
+) {
+    let record = build_record(
+        &config::get().metrics_namespace,
+        chrono::Utc::now().timestamp_millis(),
+        dimensions,
+        metrics,
+        properties,
+    );
+    println!("{record}");
+}
+
+pub fn record_request(
+    route: &str,
+    method: &str,
+    status: u16,
+    latency: Duration,
+    correlation_id: &str,
+) {
+    let status_class = format!("{}xx", status / 100);
+    emit(
+        &[
+            ("Service", "api"),
+            ("Route", route),
+            ("Method", method),
+            ("StatusClass", status_class.as_str()),
+        ],
+        &[
+            ("Invocations", 1.0, Unit::Count),
+            ("Latency", duration_ms(latency), Unit::Milliseconds),
+        ],
+        &[("correlationId", correlation_id)],
+    );
+}
+
+pub fn record_db_connect(latency: Duration, succeeded: bool) {
+    emit(
+        &[("Service", "api")],
+        &[
+            ("DbConnectLatency", duration_ms(latency), Unit::Milliseconds),
+            (
+                "DbConnectFailures",
+                if succeeded { 0.0 } else { 1.0 },
+                Unit::Count,
+            ),
+        ],
+        &[],
+    );
+}
+
+pub fn record_db_pool_checkout(reused: bool, idle_connections: usize) {
+    emit(
+        &[("Service", "api")],
+        &[
+            ("DbPoolHits", if reused { 1.0 } else { 0.0 }, Unit::Count),
+            ("DbPoolMisses", if reused { 0.0 } else { 1.0 }, Unit::Count),
+            (
+                "DbPoolIdleConnections",
+                f64::from(u32::try_from(idle_connections).unwrap_or(u32::MAX)),
+                Unit::Count,
+            ),
+        ],
+        &[],
+    );
+}
+
+pub fn record_event_emit_failure(detail_type: &str) {
+    emit(
+        &[("Service", "api"), ("DetailType", detail_type)],
+        &[("EventEmitFailures", 1.0, Unit::Count)],
+        &[],
+    );
+}
+
+/// One forward geocode. Cache misses are the calls that reach the provider
+/// and cost money.
+pub fn record_geocode_lookup(provider: &str, cache_hit: bool) {
+    emit(
+        &[("Service", "api"), ("GeocoderProvider", provider)],
+        &[
+            (
+                "GeocodeCacheHits",
+                if cache_hit { 1.0 } else { 0.0 },
+                Unit::Count,
+            ),
+            (
+                "GeocodeCacheMisses",
+                if cache_hit { 0.0 } else { 1.0 },
+                Unit::Count,
+            ),
+        ],
+        &[],
+    );
+}
+
+/// Collapses id-like path segments so routes stay low-cardinality dimensions,
+/// e.g. `/listings/8b5a…/` becomes `/listings/{id}`.
+pub fn route_template(path: &str) -> String {
+    path.split('/')
+        .map(|segment| {
+            if Uuid::parse_str(segment).is_ok() || segment.chars().any(|c| c.is_ascii_digit()) {
+                "{id}"
+            } else {
+                segment
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+fn duration_ms(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+fn build_record(
+    namespace: &str,
+    timestamp_ms: i64,
+    dimensions: &[(&str, &str)],
+    metrics: &[(&str, f64, Unit)],
+    properties: &[(&str, &str)],
+) -> Value {
+    let mut record = Map::new();
+
+    for (key, value) in properties {
+        record.insert((*key).to_string(), json!(value));
+    }
+    for (key, value) in dimensions {
+        record.insert((*key).to_string(), json!(value));
+    }
+    for (name, value, _) in metrics {
+        record.insert((*name).to_string(), json!(value));
+    }
+
+    record.insert(
+        "_aws".to_string(),
+        json!({
+            "Timestamp": timestamp_ms,
+            "CloudWatchMetrics": [{
+                "Namespace": namespace,
+                "Dimensions": [dimensions.iter().map(|(key, _)| *key).collect::<Vec<_>>()],
+                "Metrics": metrics
+                    .iter()
+                    .map(|(name, _, unit)| json!({ "Name": name, "Unit": unit.as_str() }))
+                    .collect::<Vec<_>>(),
+            }],
+        }),
+    );
+
+    Value::Object(record)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_record_matches_emf_shape() {
+        let record = build_record(
+            "CommunityGarden",
+            1_700_000_000_000,
+            &[("Service", "api"), ("Route", "/listings")],
+            &[("Latency", 12.5, Unit::Milliseconds)],
+            &[("correlationId", "corr-1")],
+        );
+
+        assert_eq!(record["Route"], "/listings");
+        assert_eq!(record["Latency"], 12.5);
+        assert_eq!(record["correlationId"], "corr-1");
+        let directive = &record["_aws"]["CloudWatchMetrics"][0];
+        assert_eq!(directive["Namespace"], "CommunityGarden");
+        assert_eq!(directive["Dimensions"][0], json!(["Service", "Route"]));
+        assert_eq!(directive["Metrics"][0]["Unit"], "Milliseconds");
+    }
+
+    #[test]
+    fn route_template_collapses_ids() {
+        assert_eq!(
+            route_template("/listings/8b5a1a3e-d7ad-4ca4-9f56-2f188db4e6ef"),
+            "/listings/{id}"
+        );
+        assert_eq!(
+            route_template("/catalog/crops/42/varieties"),
+            "/catalog/crops/{id}/varieties"
+        );
+        assert_eq!(route_template("/feed/derived"), "/feed/derived");
+    }
+}
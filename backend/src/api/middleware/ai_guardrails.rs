@@ -1,6 +1,10 @@
+use crate::config;
+use crate::db::TimedQuery;
+use crate::error::ApiError;
 use tokio_postgres::Client;
 use uuid::Uuid;
 
+#[derive(Debug, Clone)]
 pub struct GuardrailsConfig {
     pub max_daily_requests_per_user: i64,
     pub max_daily_tokens_per_user: i64,
@@ -13,33 +17,17 @@ pub struct GuardrailsDecision {
     pub estimated_tokens: i32,
 }
 
-pub fn load_config() -> GuardrailsConfig {
-    GuardrailsConfig {
-        max_daily_requests_per_user: std::env::var("AI_MAX_DAILY_REQUESTS_PER_USER")
-            .ok()
-            .and_then(|v| v.parse::<i64>().ok())
-            .unwrap_or(30),
-        max_daily_tokens_per_user: std::env::var("AI_MAX_DAILY_TOKENS_PER_USER")
-            .ok()
-            .and_then(|v| v.parse::<i64>().ok())
-            .unwrap_or(60000),
-        default_estimated_tokens: std::env::var("AI_DEFAULT_ESTIMATED_TOKENS")
-            .ok()
-            .and_then(|v| v.parse::<i32>().ok())
-            .unwrap_or(1200),
-    }
-}
-
 pub async fn enforce_and_record(
     client: &Client,
     user_id: Uuid,
     feature_key: &str,
     model_id: &str,
 ) -> Result<GuardrailsDecision, lambda_http::Error> {
-    let cfg = load_config();
+    let cfg = &config::get().ai.guardrails;
 
     let row = client
-        .query_one(
+        .query_one_timed(
+            "middleware::ai_guardrails::enforce_and_record",
             "
             select
               count(*)::bigint as request_count,
@@ -52,7 +40,7 @@ pub async fn enforce_and_record(
             &[&user_id],
         )
         .await
-        .map_err(|e| db_error(&e))?;
+        .map_err(ApiError::from)?;
 
     let request_count: i64 = row.get("request_count");
     let token_sum: i64 = row.get("token_sum");
@@ -68,7 +56,8 @@ pub async fn enforce_and_record(
     };
 
     client
-        .execute(
+        .execute_timed(
+            "middleware::ai_guardrails::enforce_and_record",
             "
             insert into ai_usage_events (
               user_id, feature_key, model_id, estimated_tokens, estimated_cost_usd, status, reason
@@ -85,7 +74,7 @@ pub async fn enforce_and_record(
             ],
         )
         .await
-        .map_err(|e| db_error(&e))?;
+        .map_err(ApiError::from)?;
 
     Ok(GuardrailsDecision {
         allowed,
@@ -93,7 +82,3 @@ pub async fn enforce_and_record(
         estimated_tokens: cfg.default_estimated_tokens,
     })
 }
-
-fn db_error(error: &tokio_postgres::Error) -> lambda_http::Error {
-    lambda_http::Error::from(format!("Database query error: {error}"))
-}
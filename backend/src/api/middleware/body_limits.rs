@@ -0,0 +1,119 @@
+use crate::error::ApiError;
+use lambda_http::{Body, Request};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyLimitsConfig {
+    pub max_body_bytes: usize,
+    pub max_json_depth: usize,
+}
+
+/// Rejects oversized or deeply nested bodies before any handler calls
+/// `parse_json_body`, so serde never sees a pathological payload.
+pub fn enforce(request: &Request, config: &BodyLimitsConfig) -> Result<(), ApiError> {
+    enforce_with_config(request.body(), config)
+}
+
+fn enforce_with_config(body: &Body, config: &BodyLimitsConfig) -> Result<(), ApiError> {
+    let bytes: &[u8] = match body {
+        Body::Empty => return Ok(()),
+        Body::Text(text) => text.as_bytes(),
+        Body::Binary(bytes) => bytes,
+    };
+
+    if bytes.len() > config.max_body_bytes {
+        return Err(ApiError::payload_too_large(format!(
+            "Request body exceeds {} bytes",
+            config.max_body_bytes
+        )));
+    }
+
+    if json_depth_exceeds(bytes, config.max_json_depth) {
+        return Err(ApiError::bad_request(
+            "json_too_deep",
+            format!(
+                "Request body nesting exceeds {} levels",
+                config.max_json_depth
+            ),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Counts bracket nesting outside of string literals. Malformed JSON is left
+/// for serde to report; this only bounds how deep it will recurse.
+fn json_depth_exceeds(bytes: &[u8], max_depth: usize) -> bool {
+    let mut depth = 0usize;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for &byte in bytes {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match byte {
+            b'"' => in_string = true,
+            b'{' | b'[' => {
+                depth += 1;
+                if depth > max_depth {
+                    return true;
+                }
+            }
+            b'}' | b']' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+
+    false
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    const CONFIG: BodyLimitsConfig = BodyLimitsConfig {
+        max_body_bytes: 64,
+        max_json_depth: 3,
+    };
+
+    #[test]
+    fn accepts_small_shallow_body() {
+        let body = Body::from(r#"{"a":{"b":[1,2]}}"#);
+        assert!(enforce_with_config(&body, &CONFIG).is_ok());
+    }
+
+    #[test]
+    fn accepts_empty_body() {
+        assert!(enforce_with_config(&Body::Empty, &CONFIG).is_ok());
+    }
+
+    #[test]
+    fn rejects_oversized_body_with_413() {
+        let body = Body::from("x".repeat(65));
+        let error = enforce_with_config(&body, &CONFIG).unwrap_err();
+        assert_eq!(error.status().as_u16(), 413);
+        assert_eq!(error.error_code(), "payload_too_large");
+    }
+
+    #[test]
+    fn rejects_deep_nesting_with_400() {
+        let body = Body::from("[[[[1]]]]");
+        let error = enforce_with_config(&body, &CONFIG).unwrap_err();
+        assert_eq!(error.status().as_u16(), 400);
+        assert_eq!(error.error_code(), "json_too_deep");
+    }
+
+    #[test]
+    fn ignores_brackets_inside_strings() {
+        let body = Body::from(r#"{"note":"[[[[[[\"{{{{"}"#);
+        assert!(enforce_with_config(&body, &CONFIG).is_ok());
+    }
+}
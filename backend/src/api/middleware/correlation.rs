@@ -1,20 +1,17 @@
-use lambda_http::{Request, Response};
+use lambda_http::http::header::CONTENT_TYPE;
+use lambda_http::{Body, Request, Response};
 use uuid::Uuid;
 
 /// Header name for correlation ID
 pub const CORRELATION_ID_HEADER: &str = "x-correlation-id";
 
-/// Extract correlation ID from request headers or generate a new one
-///
-/// This function looks for the X-Correlation-Id header in the request.
-/// If found, it returns the value. If not found or invalid, it generates
-/// a new UUID v4.
-///
-/// # Arguments
-/// * `request` - The incoming HTTP request
-///
-/// # Returns
-/// A correlation ID string (either from header or newly generated)
+/// Longest caller-supplied correlation ID we accept before generating our own
+const MAX_CORRELATION_ID_LENGTH: usize = 128;
+
+/// Correlation ID stored in request extensions by `attach_correlation_id`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorrelationId(pub String);
+
 /// Extract correlation ID from request headers or generate a new one
 ///
 /// This function looks for the X-Correlation-Id header in the request.
@@ -31,21 +28,49 @@ pub fn extract_or_generate_correlation_id(request: &Request) -> String {
         .headers()
         .get(CORRELATION_ID_HEADER)
         .and_then(|v| v.to_str().ok())
-        .filter(|s| !s.is_empty())
+        .filter(|s| is_valid_correlation_id(s))
         .map_or_else(|| Uuid::new_v4().to_string(), ToString::to_string)
 }
 
-/// Add correlation ID to response headers
+/// Resolve the correlation ID once and store it in the request extensions
 ///
-/// This function adds the X-Correlation-Id header to the response,
-/// enabling end-to-end request tracing.
+/// Downstream code reads it back with `correlation_id`, so the generated ID
+/// stays the same for every log line and the response echo.
 ///
 /// # Arguments
-/// * `response` - The HTTP response builder
-/// * `correlation_id` - The correlation ID to add
+/// * `request` - The incoming HTTP request
 ///
 /// # Returns
-/// The response builder with the correlation ID header added
+/// The request with a `CorrelationId` extension attached
+pub fn attach_correlation_id(mut request: Request) -> Request {
+    let correlation_id = extract_or_generate_correlation_id(&request);
+    request
+        .extensions_mut()
+        .insert(CorrelationId(correlation_id));
+    request
+}
+
+/// Read the correlation ID attached to the request
+///
+/// Falls back to the header (or a new UUID) when the request did not pass
+/// through `attach_correlation_id`, e.g. in tests.
+pub fn correlation_id(request: &Request) -> String {
+    request.extensions().get::<CorrelationId>().map_or_else(
+        || extract_or_generate_correlation_id(request),
+        |id| id.0.clone(),
+    )
+}
+
+/// Caller-supplied IDs end up in logs and headers, so only short
+/// printable tokens are accepted.
+fn is_valid_correlation_id(value: &str) -> bool {
+    !value.is_empty()
+        && value.len() <= MAX_CORRELATION_ID_LENGTH
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))
+}
+
 /// Add correlation ID to response headers
 ///
 /// This function adds the X-Correlation-Id header to the response,
@@ -69,6 +94,49 @@ pub fn add_correlation_id_to_response<T>(
     response
 }
 
+/// Add correlation ID to JSON error payloads
+///
+/// Error responses (status 400 and above) with a JSON object body get a
+/// `correlationId` field so users can quote it in support requests. Other
+/// responses are returned unchanged.
+///
+/// # Arguments
+/// * `response` - The HTTP response
+/// * `correlation_id` - The correlation ID to add
+///
+/// # Returns
+/// The response with the correlation ID added to its error body
+pub fn add_correlation_id_to_error_body(
+    response: Response<Body>,
+    correlation_id: &str,
+) -> Response<Body> {
+    let is_json = response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|v| v.starts_with("application/json"));
+    if response.status().as_u16() < 400 || !is_json {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let parsed = match &body {
+        Body::Text(text) => serde_json::from_str::<serde_json::Value>(text).ok(),
+        Body::Binary(bytes) => serde_json::from_slice::<serde_json::Value>(bytes).ok(),
+        Body::Empty => None,
+    };
+
+    let Some(serde_json::Value::Object(mut payload)) = parsed else {
+        return Response::from_parts(parts, body);
+    };
+    payload
+        .entry("correlationId")
+        .or_insert_with(|| serde_json::Value::String(correlation_id.to_string()));
+
+    let body = serde_json::to_string(&payload).map_or(body, Body::from);
+    Response::from_parts(parts, body)
+}
+
 #[cfg(test)]
 #[allow(clippy::unwrap_used, clippy::expect_used)]
 mod tests {
@@ -163,6 +231,65 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_generate_correlation_id_when_header_is_unsafe() {
+        let mut request = Request::default();
+        request.headers_mut().insert(
+            CORRELATION_ID_HEADER,
+            HeaderValue::from_static("abc def\"injected"),
+        );
+
+        let correlation_id = extract_or_generate_correlation_id(&request);
+        assert!(Uuid::parse_str(&correlation_id).is_ok());
+
+        let long_id = "a".repeat(MAX_CORRELATION_ID_LENGTH + 1);
+        request.headers_mut().insert(
+            CORRELATION_ID_HEADER,
+            HeaderValue::from_str(&long_id).unwrap(),
+        );
+        assert_ne!(extract_or_generate_correlation_id(&request), long_id);
+    }
+
+    #[test]
+    fn test_attached_correlation_id_is_stable() {
+        let request = attach_correlation_id(Request::default());
+
+        let first = correlation_id(&request);
+        let second = correlation_id(&request);
+        assert_eq!(first, second);
+        assert!(Uuid::parse_str(&first).is_ok());
+    }
+
+    #[test]
+    fn test_add_correlation_id_to_error_body() {
+        let response = Response::builder()
+            .status(404)
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"error":"Listing not found"}"#))
+            .unwrap();
+
+        let response = add_correlation_id_to_error_body(response, "corr-1");
+        let text = match response.body() {
+            Body::Text(text) => text.as_str(),
+            _ => "",
+        };
+        let json: serde_json::Value = serde_json::from_str(text).unwrap();
+        assert_eq!(json["correlationId"], "corr-1");
+        assert_eq!(json["error"], "Listing not found");
+    }
+
+    #[test]
+    fn test_add_correlation_id_skips_success_body() {
+        let response = Response::builder()
+            .status(200)
+            .header("content-type", "application/json")
+            .body(Body::from(r#"{"ok":true}"#))
+            .unwrap();
+
+        let response = add_correlation_id_to_error_body(response, "corr-1");
+        assert!(matches!(response.body(), Body::Text(text) if text == r#"{"ok":true}"#));
+    }
+
     #[test]
     fn test_correlation_id_header_name_is_lowercase() {
         // HTTP headers are case-insensitive, but we use lowercase by convention
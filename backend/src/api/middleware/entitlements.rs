@@ -1,3 +1,5 @@
+use crate::db::TimedQuery;
+use crate::error::ApiError;
 use crate::models::entitlements::{
     EntitlementsPolicy, EntitlementsResponse, FeatureLockedErrorResponse,
 };
@@ -11,6 +13,7 @@ const ENTITLEMENTS_CONFIG_JSON: &str =
     include_str!("../../../../config/entitlements/v1.tiers.json");
 const DEFAULT_TIER: &str = "free";
 const PREMIUM_TIER: &str = "premium";
+const FEATURE_LOCKED: &str = "feature_locked";
 
 static ENTITLEMENTS_CONFIG: OnceLock<Result<EntitlementsConfig, String>> = OnceLock::new();
 
@@ -48,7 +51,9 @@ pub struct FeatureLockedError {
 impl FeatureLockedError {
     pub fn to_response(&self) -> FeatureLockedErrorResponse {
         FeatureLockedErrorResponse {
-            error: "feature_locked".to_string(),
+            error: FEATURE_LOCKED.to_string(),
+            error_code: FEATURE_LOCKED.to_string(),
+            code: FEATURE_LOCKED.to_ascii_uppercase(),
             entitlement_key: self.entitlement_key.clone(),
             required_tier: PREMIUM_TIER.to_string(),
             upgrade_hint_key: "upgrade.premium".to_string(),
@@ -172,12 +177,13 @@ fn collect_entitlements_recursive(
 
 async fn load_user_tier(client: &Client, user_id: Uuid) -> Result<String, lambda_http::Error> {
     let row = client
-        .query_opt(
+        .query_opt_timed(
+            "middleware::entitlements::load_user_tier",
             "select tier from users where id = $1 and deleted_at is null",
             &[&user_id],
         )
         .await
-        .map_err(|e| lambda_http::Error::from(format!("Database query error: {e}")))?;
+        .map_err(ApiError::from)?;
 
     Ok(row
         .and_then(|r| r.get::<_, Option<String>>("tier"))
@@ -271,6 +277,8 @@ mod tests {
         .to_response();
 
         assert_eq!(response.error, "feature_locked");
+        assert_eq!(response.error_code, "feature_locked");
+        assert_eq!(response.code, "FEATURE_LOCKED");
         assert_eq!(response.entitlement_key, "ai.feed_insights.read");
         assert_eq!(response.required_tier, "premium");
         assert_eq!(response.upgrade_hint_key, "upgrade.premium");
@@ -1,3 +1,4 @@
 pub mod ai_guardrails;
+pub mod body_limits;
 pub mod correlation;
 pub mod entitlements;
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateAwayPeriodRequest {
+    /// RFC3339; defaults to now.
+    pub starts_at: Option<String>,
+    /// RFC3339; listings are reactivated at this time.
+    pub ends_at: String,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AwayPeriod {
+    pub id: String,
+    pub starts_at: String,
+    pub ends_at: String,
+    pub note: Option<String>,
+    /// `scheduled` until `startsAt`, then `active`.
+    pub status: String,
+    /// Listings paused for this period; zero while it is scheduled.
+    pub paused_listing_count: i64,
+    pub created_at: String,
+}
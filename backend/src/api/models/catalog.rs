@@ -1,6 +1,7 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct SourceAttribution {
     pub source: String,
     pub source_id: Option<String>,
@@ -12,18 +13,20 @@ pub struct SourceAttribution {
     pub last_verified_at: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CatalogCrop {
     pub id: String,
     pub slug: String,
     pub common_name: String,
     pub scientific_name: Option<String>,
     pub category: Option<String>,
+    /// Broad group impact reports roll produce up by, such as `leafy_greens`.
+    pub nutrition_group: Option<String>,
     pub description: Option<String>,
     pub source_attribution: SourceAttribution,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct CatalogVariety {
     pub id: String,
     pub crop_id: String,
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 pub struct GrowerCropItem {
     pub id: String,
     pub user_id: String,
@@ -12,11 +13,13 @@ pub struct GrowerCropItem {
     pub nickname: Option<String>,
     pub default_unit: Option<String>,
     pub notes: Option<String>,
+    /// Garden bed the crop is planted in, if any.
+    pub bed_id: Option<String>,
     pub created_at: String,
     pub updated_at: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 pub struct UpsertGrowerCropRequest {
     pub crop_id: String,
     pub variety_id: Option<String>,
@@ -26,9 +29,44 @@ pub struct UpsertGrowerCropRequest {
     pub nickname: Option<String>,
     pub default_unit: Option<String>,
     pub notes: Option<String>,
+    /// One of the grower's garden beds; omit or send null for none.
+    pub bed_id: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
-pub struct ErrorResponse {
-    pub error: String,
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantingRecommendation {
+    pub rank: usize,
+    pub crop_id: String,
+    pub crop_name: String,
+    /// Library status when the crop is already in the grower's library.
+    pub library_status: Option<String>,
+    pub scarcity_score: f64,
+    pub request_count: i32,
+    pub days_to_maturity: Option<i32>,
+    /// `fits`, `too_late`, or `unknown` when the zone or maturity is missing.
+    pub season_fit: String,
+    pub score: f64,
+    pub reasons: Vec<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantingRecommendationsResponse {
+    pub home_zone: Option<String>,
+    pub season: String,
+    /// Days left before the first fall frost, from the grower's location when
+    /// it is covered and otherwise the zone; null when neither is known.
+    pub growing_days_remaining: Option<i32>,
+    /// Average last spring frost for the grower's location, `MM-DD`; null
+    /// outside coverage or where frost is rare.
+    pub last_spring_frost: Option<String>,
+    /// Average first fall frost for the grower's location, `MM-DD`.
+    pub first_fall_frost: Option<String>,
+    /// `ai` when a model wrote the summary, otherwise `deterministic`. The
+    /// ranking itself is always deterministic.
+    pub source: String,
+    pub summary: String,
+    pub model_id: Option<String>,
+    pub recommendations: Vec<PlantingRecommendation>,
 }
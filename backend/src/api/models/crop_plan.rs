@@ -0,0 +1,41 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct CropPlanItem {
+    pub id: String,
+    pub crop_id: String,
+    pub variety_id: Option<String>,
+    /// `spring`, `summer`, `fall`, or `winter`.
+    pub season: String,
+    /// Winter takes the year of the December it starts in.
+    pub season_year: i32,
+    /// First day of the season, `YYYY-MM-DD`.
+    pub season_starts_on: String,
+    pub expected_quantity: String,
+    pub unit: String,
+    pub notes: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertCropPlanRequest {
+    pub crop_id: String,
+    pub variety_id: Option<String>,
+    pub season: String,
+    pub season_year: i32,
+    #[schema(value_type = f64)]
+    pub expected_quantity: Decimal,
+    pub unit: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListCropPlansResponse {
+    pub items: Vec<CropPlanItem>,
+}
@@ -1,13 +1,14 @@
 use serde::Serialize;
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EntitlementsPolicy {
     pub ai_is_premium_only: bool,
     pub free_reminders_deterministic_only: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct EntitlementsResponse {
     pub tier: String,
@@ -16,11 +17,13 @@ pub struct EntitlementsResponse {
     pub policy: EntitlementsPolicy,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct FeatureLockedErrorResponse {
     pub error: String,
+    pub error_code: String,
+    pub code: String,
     pub entitlement_key: String,
     pub required_tier: String,
     pub upgrade_hint_key: String,
@@ -1,10 +1,13 @@
 use crate::models::listing::ListingItem;
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DerivedFeedSignal {
     pub geo_boundary_key: String,
+    /// Display name for `geo_boundary_key`, e.g. "Elmwood, Buffalo".
+    pub geo_label: Option<String>,
     pub crop_id: Option<String>,
     pub window_days: i32,
     pub listing_count: i32,
@@ -17,7 +20,7 @@ pub struct DerivedFeedSignal {
     pub expires_at: String,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DerivedFeedFreshness {
     pub as_of: String,
@@ -26,9 +29,11 @@ pub struct DerivedFeedFreshness {
     pub stale_reason: Option<String>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DerivedFeedAiSummary {
+    /// Pass as `targetId` with `targetType: summary` to `POST /feed/feedback`.
+    pub id: String,
     pub summary_text: String,
     pub model_id: String,
     pub model_version: String,
@@ -37,7 +42,7 @@ pub struct DerivedFeedAiSummary {
     pub from_cache: bool,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowerGuidanceSignalRef {
     pub geo_boundary_key: String,
@@ -48,7 +53,7 @@ pub struct GrowerGuidanceSignalRef {
     pub request_count: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowerGuidanceExplanation {
     pub season: String,
@@ -59,18 +64,87 @@ pub struct GrowerGuidanceExplanation {
     pub strongest_abundance_signal: Option<GrowerGuidanceSignalRef>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowerGuidance {
+    /// Pass as `targetId` with `targetType: guidance` to `POST /feed/feedback`.
+    pub id: String,
     pub guidance_text: String,
     pub explanation: GrowerGuidanceExplanation,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedFeedForecast {
+    pub geo_boundary_key: String,
+    pub geo_label: Option<String>,
+    pub crop_id: Option<String>,
+    pub window_days: i32,
+    pub horizon_days: i32,
+    pub target_date: String,
+    pub projected_scarcity_score: f64,
+    pub projected_abundance_score: f64,
+    pub model: String,
+    pub generated_at: String,
+}
+
+/// Many growers in the feed's area plan the same crop for one season. Only
+/// counts are exposed, and only once enough growers share the plan that no
+/// one of them can be picked out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PlantingConcentrationWarning {
+    pub crop_id: String,
+    pub crop_name: String,
+    pub season: String,
+    pub season_year: i32,
+    /// Growers planning this crop for the season.
+    pub grower_count: i32,
+    /// Growers with any plan for the season.
+    pub planner_count: i32,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedAnnouncement {
+    pub id: String,
+    pub author_id: String,
+    pub author_display_name: Option<String>,
+    pub category: String,
+    pub title: String,
+    pub body: String,
+    pub geo_key: String,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AiFeedbackResponse {
+    pub id: String,
+    pub target_type: String,
+    pub target_id: String,
+    pub rating: String,
+    pub reason: Option<String>,
+    pub model_id: String,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DerivedFeedResponse {
+    /// The geohash prefix the feed was scoped to, and its display name.
+    pub geo_boundary_key: String,
+    pub geo_label: Option<String>,
     pub items: Vec<ListingItem>,
+    pub announcements: Vec<FeedAnnouncement>,
     pub signals: Vec<DerivedFeedSignal>,
+    pub forecast: Vec<DerivedFeedForecast>,
+    /// Crops a large share of nearby growers plan for the current or a
+    /// coming season, most-planned first.
+    pub planting_warnings: Vec<PlantingConcentrationWarning>,
     pub freshness: DerivedFeedFreshness,
     pub ai_summary: Option<DerivedFeedAiSummary>,
     pub grower_guidance: Option<GrowerGuidance>,
@@ -0,0 +1,59 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GardenPlot {
+    pub id: String,
+    pub name: String,
+    pub notes: Option<String>,
+    pub beds: Vec<GardenBed>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GardenBed {
+    pub id: String,
+    pub plot_id: String,
+    pub name: String,
+    /// Planted area in square feet, as a decimal string.
+    pub area_sq_ft: Option<String>,
+    pub notes: Option<String>,
+    /// Crop library entries currently planted in this bed.
+    pub grower_crop_ids: Vec<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertGardenPlotRequest {
+    pub name: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertGardenBedRequest {
+    pub name: String,
+    #[schema(value_type = Option<f64>)]
+    pub area_sq_ft: Option<Decimal>,
+    pub notes: Option<String>,
+}
+
+/// One stretch a crop spent in a bed. `endedAt` is null while it is still
+/// planted there.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BedPlanting {
+    /// Null once the crop library entry has been removed.
+    pub grower_crop_id: Option<String>,
+    pub crop_id: String,
+    pub crop_name: String,
+    pub variety_id: Option<String>,
+    pub started_at: String,
+    pub ended_at: Option<String>,
+}
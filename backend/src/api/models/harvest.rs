@@ -0,0 +1,58 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestItem {
+    pub id: String,
+    /// Null once the crop library entry has been removed; `cropId` is kept.
+    pub grower_crop_id: Option<String>,
+    pub crop_id: String,
+    /// `YYYY-MM-DD`.
+    pub harvested_on: String,
+    pub quantity: String,
+    pub unit: String,
+    pub notes: Option<String>,
+    /// The listing created from this harvest, if any.
+    pub listing_id: Option<String>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UpsertHarvestRequest {
+    pub grower_crop_id: String,
+    pub harvested_on: String,
+    #[schema(value_type = f64)]
+    pub quantity: Decimal,
+    /// Defaults to the crop library entry's `defaultUnit`.
+    pub unit: Option<String>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListHarvestsResponse {
+    pub items: Vec<HarvestItem>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+/// A `POST /listings` body prefilled from a harvest. Clients may edit any
+/// field before posting; `harvestId` links the new listing back.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct HarvestListingDraft {
+    pub harvest_id: String,
+    pub title: String,
+    pub crop_id: String,
+    pub variety_id: Option<String>,
+    pub quantity_total: String,
+    pub unit: String,
+    pub available_start: String,
+    pub available_end: String,
+}
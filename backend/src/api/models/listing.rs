@@ -1,19 +1,34 @@
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListingItem {
     pub id: String,
     pub user_id: String,
     pub grower_crop_id: Option<String>,
-    pub crop_id: String,
+    pub listing_kind: String,
+    /// Null for kinds without a crop (`tools_loan`, `compost`).
+    pub crop_id: Option<String>,
     pub variety_id: Option<String>,
+    /// Crop and variety names in the caller's `Accept-Language`, falling
+    /// back to the catalog names.
+    pub crop_name: Option<String>,
+    pub variety_name: Option<String>,
     pub title: Option<String>,
     pub unit: Option<String>,
     pub quantity_total: Option<String>,
     pub quantity_remaining: Option<String>,
+    /// Part of `quantityRemaining` held by pending claims awaiting review.
+    pub reserved_quantity: String,
     pub available_start: Option<String>,
     pub available_end: Option<String>,
+    /// Set only for `tools_loan` listings.
+    pub return_by: Option<String>,
+    /// When a `scheduled` listing goes active.
+    pub publish_at: Option<String>,
+    /// When the listing expires if still active or pending.
+    pub expire_at: Option<String>,
     pub status: String,
     pub pickup_location_text: Option<String>,
     pub pickup_address: Option<String>,
@@ -24,10 +39,33 @@ pub struct ListingItem {
     pub geo_key: Option<String>,
     pub lat: Option<f64>,
     pub lng: Option<f64>,
+    pub group_id: Option<String>,
     pub created_at: String,
+    pub photo_crop_mismatch: Option<PhotoCropMismatch>,
+    /// Set only when the owner reads their own listings.
+    pub claim_counts: Option<ListingClaimCounts>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Claims on a listing that still need the grower: pending ones to review
+/// and confirmed ones to hand over.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingClaimCounts {
+    pub pending: i64,
+    pub confirmed: i64,
+}
+
+/// Set when the crop photo worker identified a different catalog crop in the
+/// listing's photos than the one selected.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct PhotoCropMismatch {
+    pub suggested_crop_id: String,
+    /// Model confidence between 0 and 1.
+    pub confidence: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ListMyListingsResponse {
     pub items: Vec<ListingItem>,
@@ -37,12 +75,124 @@ pub struct ListMyListingsResponse {
     pub next_offset: Option<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedListing {
+    #[serde(flatten)]
+    pub listing: ListingItem,
+    /// Cosine similarity between the listing and request embeddings.
+    pub similarity: f64,
+    pub distance_km: Option<f64>,
+    /// Blend of similarity and proximity the results are ordered by.
+    pub score: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SuggestedListingsResponse {
+    pub request_id: String,
+    /// False until the embedding worker has processed the request; `items`
+    /// is empty in that case.
+    pub embedding_ready: bool,
+    pub items: Vec<SuggestedListing>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct RouteListing {
+    #[serde(flatten)]
+    pub listing: ListingItem,
+    /// Distance from the listing to the nearest point on the route.
+    pub distance_km: f64,
+    /// How far along the route, from its start, that nearest point is.
+    pub along_route_km: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingCalendarResponse {
+    /// The geohash prefix counted, and its display name.
+    pub geo_boundary_key: String,
+    pub geo_label: Option<String>,
+    /// `YYYY-MM`.
+    pub month: String,
+    /// Every day of the month in order, including days with no listings.
+    pub days: Vec<ListingCalendarDay>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ListingCalendarDay {
+    /// `YYYY-MM-DD`, a UTC day.
+    pub date: String,
+    pub listing_count: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct AlongRouteListingsResponse {
+    /// Listings within the buffer, in the order they are passed when
+    /// travelling the route.
+    pub items: Vec<RouteListing>,
+    pub route_km: f64,
+    pub buffer_miles: f64,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchListingsResponse {
+    /// Requested listings the caller may see, newest first. Ids that are
+    /// unknown, deleted, or not visible to the caller are omitted.
+    pub items: Vec<ListingItem>,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct DiscoverListingsResponse {
+    /// The geohash prefix searched, and its display name.
+    pub geo_boundary_key: String,
+    pub geo_label: Option<String>,
     pub items: Vec<ListingItem>,
     pub limit: i64,
     pub offset: i64,
     pub has_more: bool,
     pub next_offset: Option<i64>,
 }
+
+/// `GET /listings/discover` for anonymous callers. Items carry no grower
+/// identity, pickup details or coordinates, and the geo key is coarsened to
+/// about five kilometers.
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestDiscoverListingsResponse {
+    pub geo_boundary_key: String,
+    pub geo_label: Option<String>,
+    pub items: Vec<GuestListingItem>,
+    pub limit: i64,
+    pub offset: i64,
+    pub has_more: bool,
+    pub next_offset: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct GuestListingItem {
+    pub id: String,
+    pub listing_kind: String,
+    pub crop_id: Option<String>,
+    pub variety_id: Option<String>,
+    pub crop_name: Option<String>,
+    pub variety_name: Option<String>,
+    pub title: Option<String>,
+    pub unit: Option<String>,
+    pub quantity_remaining: Option<String>,
+    pub available_start: Option<String>,
+    pub available_end: Option<String>,
+    pub return_by: Option<String>,
+    pub geo_key: Option<String>,
+    pub created_at: String,
+}
@@ -1,6 +1,11 @@
+pub mod away;
 pub mod catalog;
 pub mod crop;
+pub mod crop_plan;
 pub mod entitlements;
 pub mod feed;
+pub mod garden;
+pub mod harvest;
 pub mod listing;
 pub mod profile;
+pub mod search;
@@ -1,16 +1,18 @@
+use crate::achievements::AchievementEntry;
 use crate::badge_cabinet::BadgeCabinetEntry;
 use crate::gardener_tier::GardenerTierProfile;
 use crate::tips_framework::{ExperienceLevel, ExperienceSignals, GardeningTip};
 use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, ToSchema)]
 #[serde(rename_all = "lowercase")]
 pub enum UserType {
     Grower,
     Gatherer,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowerProfile {
     pub home_zone: Option<String>,
@@ -19,11 +21,15 @@ pub struct GrowerProfile {
     pub lat: Option<f64>,
     pub lng: Option<f64>,
     pub share_radius_miles: String,
+    /// Claims from gatherers located outside the share radius are rejected.
+    pub enforce_share_radius: bool,
+    /// `immediate`, `daily_digest`, or `off`.
+    pub claim_alerts: String,
     pub units: String,
     pub locale: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GathererProfile {
     pub address: String,
@@ -36,13 +42,13 @@ pub struct GathererProfile {
     pub locale: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct UserRatingSummary {
     pub avg_score: String,
     pub rating_count: i32,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SubscriptionMetadata {
     pub tier: String,
@@ -50,7 +56,7 @@ pub struct SubscriptionMetadata {
     pub premium_expires_at: Option<String>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct MeProfileResponse {
     pub id: String,
@@ -59,6 +65,7 @@ pub struct MeProfileResponse {
     pub is_verified: bool,
     pub user_type: Option<UserType>,
     pub onboarding_completed: bool,
+    pub volunteer_driver: bool,
     pub created_at: String,
     pub subscription: SubscriptionMetadata,
     pub gardener_tier: GardenerTierProfile,
@@ -72,7 +79,7 @@ pub struct MeProfileResponse {
     pub rating_summary: Option<UserRatingSummary>,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct SeasonalTimelineEntry {
     pub badge_key: String,
@@ -80,26 +87,37 @@ pub struct SeasonalTimelineEntry {
     pub earned_at: String,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, ToSchema)]
 pub struct PublicUserResponse {
     pub id: String,
     pub display_name: Option<String>,
     pub created_at: String,
     pub grower_profile: Option<GrowerProfile>,
     pub rating_summary: Option<UserRatingSummary>,
+    pub achievements: Vec<AchievementEntry>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GrowerProfileInput {
-    pub home_zone: String,
+    /// Derived from the address when omitted.
+    #[serde(default)]
+    pub home_zone: Option<String>,
     pub address: String,
     pub share_radius_miles: f64,
+    /// Reject claims from gatherers outside the share radius. Left as is
+    /// when omitted.
+    #[serde(default)]
+    pub enforce_share_radius: Option<bool>,
+    /// `immediate` notifies on each claim, `daily_digest` sends one summary a
+    /// day, and `off` sends neither. Left as is when omitted.
+    #[serde(default)]
+    pub claim_alerts: Option<String>,
     pub units: String,
     pub locale: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GathererProfileInput {
     pub address: String,
@@ -109,13 +127,14 @@ pub struct GathererProfileInput {
     pub locale: String,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct PutMeRequest {
     pub display_name: Option<String>,
     pub user_type: Option<UserType>,
     pub grower_profile: Option<GrowerProfileInput>,
     pub gatherer_profile: Option<GathererProfileInput>,
+    pub volunteer_driver: Option<bool>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -0,0 +1,51 @@
+use crate::models::listing::ListingItem;
+use serde::Serialize;
+use utoipa::ToSchema;
+
+/// Results for one search term, bucketed by what matched. Each bucket is
+/// limited independently, so an empty bucket means no matches of that type.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResponse {
+    pub query: String,
+    /// The geohash prefix listings, requests and growers were limited to.
+    pub geo_key: Option<String>,
+    pub listings: Vec<ListingItem>,
+    pub requests: Vec<SearchRequestResult>,
+    pub crops: Vec<SearchCropResult>,
+    pub growers: Vec<SearchGrowerResult>,
+}
+
+/// An open request. Who made it and where they are is left out; the app
+/// reaches the requester through a conversation about the request.
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchRequestResult {
+    pub id: String,
+    pub crop_id: String,
+    pub crop_name: String,
+    pub variety_name: Option<String>,
+    pub unit: Option<String>,
+    pub quantity: Option<String>,
+    pub needed_by: Option<String>,
+    pub geo_key: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchCropResult {
+    pub id: String,
+    pub slug: String,
+    pub common_name: String,
+    pub scientific_name: Option<String>,
+    pub category: Option<String>,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchGrowerResult {
+    pub id: String,
+    pub display_name: String,
+    pub home_zone: Option<String>,
+}
@@ -0,0 +1,679 @@
+//! `OpenAPI` 3.1 document generated from the router's route table and the
+//! `ToSchema` models, so the published contract cannot drift from the routes
+//! that actually exist. Served at `GET /openapi.json`.
+
+use crate::achievements::AchievementEntry;
+use crate::badge_cabinet::BadgeCabinetEntry;
+use crate::gardener_tier::{
+    GardenerTier, GardenerTierDecision, GardenerTierProfile, GardenerTierScoreBreakdown,
+};
+use crate::models::catalog::{CatalogCrop, CatalogVariety, SourceAttribution};
+use crate::models::crop::{
+    GrowerCropItem, PlantingRecommendation, PlantingRecommendationsResponse,
+    UpsertGrowerCropRequest,
+};
+use crate::models::crop_plan::{CropPlanItem, ListCropPlansResponse, UpsertCropPlanRequest};
+use crate::models::entitlements::{
+    EntitlementsPolicy, EntitlementsResponse, FeatureLockedErrorResponse,
+};
+use crate::models::feed::{
+    AiFeedbackResponse, DerivedFeedAiSummary, DerivedFeedForecast, DerivedFeedFreshness,
+    DerivedFeedResponse, DerivedFeedSignal, FeedAnnouncement, GrowerGuidance,
+    GrowerGuidanceExplanation, GrowerGuidanceSignalRef, PlantingConcentrationWarning,
+};
+use crate::models::garden::{
+    BedPlanting, GardenBed, GardenPlot, UpsertGardenBedRequest, UpsertGardenPlotRequest,
+};
+use crate::models::harvest::{
+    HarvestItem, HarvestListingDraft, ListHarvestsResponse, UpsertHarvestRequest,
+};
+use crate::models::listing::{
+    AlongRouteListingsResponse, BatchListingsResponse, DiscoverListingsResponse,
+    GuestDiscoverListingsResponse, GuestListingItem, ListMyListingsResponse, ListingCalendarDay,
+    ListingCalendarResponse, ListingClaimCounts, ListingItem, PhotoCropMismatch, RouteListing,
+    SuggestedListing, SuggestedListingsResponse,
+};
+use crate::models::profile::{
+    GathererProfile, GathererProfileInput, GrowerProfile, GrowerProfileInput, MeProfileResponse,
+    PublicUserResponse, PutMeRequest, SeasonalTimelineEntry, SubscriptionMetadata,
+    UserRatingSummary, UserType,
+};
+use crate::models::search::{
+    SearchCropResult, SearchGrowerResult, SearchRequestResult, SearchResponse,
+};
+use crate::tips_framework::{ExperienceLevel, ExperienceSignals, GardeningTip, TipCategory};
+use std::fmt::Write as _;
+use utoipa::openapi::path::{
+    HttpMethod, Operation, OperationBuilder, ParameterBuilder, ParameterIn,
+};
+use utoipa::openapi::request_body::RequestBodyBuilder;
+use utoipa::openapi::schema::{Array, KnownFormat, ObjectBuilder, SchemaFormat, Type};
+use utoipa::openapi::security::{
+    ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityRequirement, SecurityScheme,
+};
+use utoipa::openapi::{
+    Components, ContentBuilder, Paths, Ref, RefOr, Required, ResponseBuilder, Schema,
+};
+use utoipa::OpenApi;
+
+const BEARER_SCHEME: &str = "bearerAuth";
+const API_KEY_SCHEME: &str = "apiKeyAuth";
+const ERROR_SCHEMA: &str = "ErrorResponse";
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "Community Garden API",
+        description = "REST API for the community food coordination platform. Generated from the \
+                       route table; operations without a documented body return handler-specific JSON."
+    ),
+    components(schemas(
+        AchievementEntry,
+        AiFeedbackResponse,
+        AlongRouteListingsResponse,
+        BadgeCabinetEntry,
+        BatchListingsResponse,
+        BedPlanting,
+        CatalogCrop,
+        CatalogVariety,
+        CropPlanItem,
+        DerivedFeedAiSummary,
+        DerivedFeedForecast,
+        DerivedFeedFreshness,
+        DerivedFeedResponse,
+        DerivedFeedSignal,
+        DiscoverListingsResponse,
+        EntitlementsPolicy,
+        EntitlementsResponse,
+        ExperienceLevel,
+        ExperienceSignals,
+        FeatureLockedErrorResponse,
+        FeedAnnouncement,
+        GardenBed,
+        GardenPlot,
+        GardenerTier,
+        GardenerTierDecision,
+        GardenerTierProfile,
+        GardenerTierScoreBreakdown,
+        GardeningTip,
+        GathererProfile,
+        GathererProfileInput,
+        GrowerCropItem,
+        GrowerGuidance,
+        GrowerGuidanceExplanation,
+        GrowerGuidanceSignalRef,
+        GrowerProfile,
+        GrowerProfileInput,
+        GuestDiscoverListingsResponse,
+        GuestListingItem,
+        HarvestItem,
+        HarvestListingDraft,
+        ListCropPlansResponse,
+        ListHarvestsResponse,
+        ListMyListingsResponse,
+        ListingCalendarDay,
+        ListingCalendarResponse,
+        ListingClaimCounts,
+        ListingItem,
+        MeProfileResponse,
+        PhotoCropMismatch,
+        PlantingConcentrationWarning,
+        PlantingRecommendation,
+        PlantingRecommendationsResponse,
+        PublicUserResponse,
+        PutMeRequest,
+        RouteListing,
+        SearchCropResult,
+        SearchGrowerResult,
+        SearchRequestResult,
+        SearchResponse,
+        SeasonalTimelineEntry,
+        SourceAttribution,
+        SubscriptionMetadata,
+        SuggestedListing,
+        SuggestedListingsResponse,
+        TipCategory,
+        UpsertCropPlanRequest,
+        UpsertGardenBedRequest,
+        UpsertGardenPlotRequest,
+        UpsertGrowerCropRequest,
+        UpsertHarvestRequest,
+        UserRatingSummary,
+        UserType,
+    ))
+)]
+struct ApiDoc;
+
+/// What the generator needs to know about one router entry.
+#[derive(Debug, Clone, Copy)]
+pub struct RouteDoc {
+    pub method: &'static str,
+    pub pattern: &'static str,
+    pub role: &'static str,
+    pub public: bool,
+    /// Open without credentials, with a reduced response for anonymous callers.
+    pub guest: bool,
+    pub api_key_scope: Option<&'static str>,
+}
+
+/// Body shape of one operation: `(method, pattern, status, schema, is_array)`.
+/// Patterns are written exactly as in the route table.
+const RESPONSE_BODIES: &[(&str, &str, &str, &str, bool)] = &[
+    ("GET", "/me", "200", "MeProfileResponse", false),
+    (
+        "GET",
+        "/me/entitlements",
+        "200",
+        "EntitlementsResponse",
+        false,
+    ),
+    (
+        "GET",
+        "/me/planting-recommendations",
+        "200",
+        "PlantingRecommendationsResponse",
+        false,
+    ),
+    ("GET", "/me/harvests", "200", "ListHarvestsResponse", false),
+    ("POST", "/me/harvests", "201", "HarvestItem", false),
+    (
+        "GET",
+        "/me/harvests/{harvestId:uuid}",
+        "200",
+        "HarvestItem",
+        false,
+    ),
+    (
+        "PUT",
+        "/me/harvests/{harvestId:uuid}",
+        "200",
+        "HarvestItem",
+        false,
+    ),
+    (
+        "GET",
+        "/me/harvests/{harvestId:uuid}/listing-draft",
+        "200",
+        "HarvestListingDraft",
+        false,
+    ),
+    (
+        "GET",
+        "/me/crop-plans",
+        "200",
+        "ListCropPlansResponse",
+        false,
+    ),
+    ("POST", "/me/crop-plans", "201", "CropPlanItem", false),
+    (
+        "PUT",
+        "/me/crop-plans/{planId:uuid}",
+        "200",
+        "CropPlanItem",
+        false,
+    ),
+    (
+        "GET",
+        "/users/{userId:uuid}",
+        "200",
+        "PublicUserResponse",
+        false,
+    ),
+    ("GET", "/crops", "200", "GrowerCropItem", true),
+    ("POST", "/crops", "201", "GrowerCropItem", false),
+    (
+        "GET",
+        "/crops/{cropLibraryId:uuid}",
+        "200",
+        "GrowerCropItem",
+        false,
+    ),
+    (
+        "PUT",
+        "/crops/{cropLibraryId:uuid}",
+        "200",
+        "GrowerCropItem",
+        false,
+    ),
+    (
+        "GET",
+        "/my/listings",
+        "200",
+        "ListMyListingsResponse",
+        false,
+    ),
+    (
+        "GET",
+        "/my/listings/{listingId:uuid}",
+        "200",
+        "ListingItem",
+        false,
+    ),
+    (
+        "GET",
+        "/listings/discover",
+        "200",
+        "DiscoverListingsResponse",
+        false,
+    ),
+    (
+        "GET",
+        "/listings/along-route",
+        "200",
+        "AlongRouteListingsResponse",
+        false,
+    ),
+    (
+        "GET",
+        "/listings/calendar",
+        "200",
+        "ListingCalendarResponse",
+        false,
+    ),
+    ("GET", "/listings", "200", "BatchListingsResponse", false),
+    ("GET", "/search", "200", "SearchResponse", false),
+    (
+        "GET",
+        "/requests/{requestId:uuid}/suggested-listings",
+        "200",
+        "SuggestedListingsResponse",
+        false,
+    ),
+    ("GET", "/feed/derived", "200", "DerivedFeedResponse", false),
+    ("POST", "/feed/feedback", "200", "AiFeedbackResponse", false),
+    ("POST", "/announcements", "201", "FeedAnnouncement", false),
+    ("GET", "/catalog/crops", "200", "CatalogCrop", true),
+    (
+        "GET",
+        "/catalog/crops/{cropId:uuid}/varieties",
+        "200",
+        "CatalogVariety",
+        true,
+    ),
+];
+
+/// Request body schema per operation: `(method, pattern, schema)`.
+const REQUEST_BODIES: &[(&str, &str, &str)] = &[
+    ("PUT", "/me", "PutMeRequest"),
+    ("POST", "/me/plots", "UpsertGardenPlotRequest"),
+    ("PUT", "/me/plots/{plotId:uuid}", "UpsertGardenPlotRequest"),
+    (
+        "POST",
+        "/me/plots/{plotId:uuid}/beds",
+        "UpsertGardenBedRequest",
+    ),
+    (
+        "PUT",
+        "/me/plots/{plotId:uuid}/beds/{bedId:uuid}",
+        "UpsertGardenBedRequest",
+    ),
+    ("POST", "/me/harvests", "UpsertHarvestRequest"),
+    (
+        "PUT",
+        "/me/harvests/{harvestId:uuid}",
+        "UpsertHarvestRequest",
+    ),
+    ("POST", "/me/crop-plans", "UpsertCropPlanRequest"),
+    (
+        "PUT",
+        "/me/crop-plans/{planId:uuid}",
+        "UpsertCropPlanRequest",
+    ),
+    ("POST", "/crops", "UpsertGrowerCropRequest"),
+    (
+        "PUT",
+        "/crops/{cropLibraryId:uuid}",
+        "UpsertGrowerCropRequest",
+    ),
+];
+
+/// Builds the full document: every route becomes an operation, whether or
+/// not its body types are modeled yet.
+pub fn document(routes: &[RouteDoc]) -> utoipa::openapi::OpenApi {
+    let mut doc = ApiDoc::openapi();
+
+    let mut paths = Paths::new();
+    for route in routes {
+        let (path, _) = openapi_path(route.pattern);
+        paths.add_path_operation(path, vec![http_method(route.method)], operation(route));
+    }
+    doc.paths = paths;
+
+    let components = doc.components.get_or_insert_with(Components::new);
+    components
+        .schemas
+        .insert(ERROR_SCHEMA.to_string(), RefOr::T(error_schema()));
+    components.add_security_scheme(
+        BEARER_SCHEME,
+        SecurityScheme::Http(
+            HttpBuilder::new()
+                .scheme(HttpAuthScheme::Bearer)
+                .bearer_format("JWT")
+                .build(),
+        ),
+    );
+    components.add_security_scheme(
+        API_KEY_SCHEME,
+        SecurityScheme::ApiKey(ApiKey::Header(ApiKeyValue::new("X-Api-Key"))),
+    );
+
+    doc
+}
+
+fn operation(route: &RouteDoc) -> Operation {
+    let (path, params) = openapi_path(route.pattern);
+    let mut builder = OperationBuilder::new()
+        .operation_id(Some(operation_id(route.method, &path)))
+        .tag(tag_for(&path))
+        .description(Some(access_description(route)))
+        .securities(Some(security_for(route)));
+
+    for (name, is_uuid) in params {
+        let mut schema = ObjectBuilder::new().schema_type(Type::String);
+        if is_uuid {
+            schema = schema.format(Some(SchemaFormat::KnownFormat(KnownFormat::Uuid)));
+        }
+        builder = builder.parameter(
+            ParameterBuilder::new()
+                .name(name)
+                .parameter_in(ParameterIn::Path)
+                .required(Required::True)
+                .schema(Some(schema.build())),
+        );
+    }
+
+    if let Some((_, _, schema)) = REQUEST_BODIES
+        .iter()
+        .find(|(method, pattern, _)| *method == route.method && *pattern == route.pattern)
+    {
+        builder = builder.request_body(Some(
+            RequestBodyBuilder::new()
+                .required(Some(Required::True))
+                .content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name(*schema)))
+                        .build(),
+                )
+                .build(),
+        ));
+    }
+
+    builder = match RESPONSE_BODIES
+        .iter()
+        .find(|(method, pattern, ..)| *method == route.method && *pattern == route.pattern)
+    {
+        Some((_, _, status, schema, is_array)) => {
+            let body: RefOr<Schema> = if *is_array {
+                RefOr::T(Schema::Array(Array::new(Ref::from_schema_name(*schema))))
+            } else {
+                Ref::from_schema_name(*schema).into()
+            };
+            builder.response(
+                *status,
+                ResponseBuilder::new()
+                    .description("Success")
+                    .content(
+                        "application/json",
+                        ContentBuilder::new().schema(Some(body)).build(),
+                    )
+                    .build(),
+            )
+        }
+        None => builder.response(
+            "2XX",
+            ResponseBuilder::new()
+                .description("Success; body not yet modeled in `models/`")
+                .build(),
+        ),
+    };
+
+    builder
+        .response(
+            "default",
+            ResponseBuilder::new()
+                .description("Error")
+                .content(
+                    "application/json",
+                    ContentBuilder::new()
+                        .schema(Some(Ref::from_schema_name(ERROR_SCHEMA)))
+                        .build(),
+                )
+                .build(),
+        )
+        .build()
+}
+
+/// Converts a route pattern to an `OpenAPI` path, returning each path
+/// parameter and whether it is typed as a UUID.
+fn openapi_path(pattern: &str) -> (String, Vec<(String, bool)>) {
+    let mut params = Vec::new();
+    let segments = pattern
+        .split('/')
+        .map(|segment| {
+            let Some(inner) = segment
+                .strip_prefix('{')
+                .and_then(|rest| rest.strip_suffix('}'))
+            else {
+                return segment.to_string();
+            };
+            let (name, kind) = inner.split_once(':').unwrap_or((inner, ""));
+            params.push((name.to_string(), kind == "uuid"));
+            format!("{{{name}}}")
+        })
+        .collect::<Vec<_>>();
+    (segments.join("/"), params)
+}
+
+/// `GET /users/{userId}/follow` -> `getUsersByUserIdFollow`.
+fn operation_id(method: &str, path: &str) -> String {
+    let mut id = method.to_ascii_lowercase();
+    for segment in path.split('/').filter(|segment| !segment.is_empty()) {
+        if let Some(param) = segment
+            .strip_prefix('{')
+            .and_then(|rest| rest.strip_suffix('}'))
+        {
+            id.push_str("By");
+            id.push_str(&capitalize(param));
+        } else {
+            for word in segment.split(['-', '.', '_']) {
+                id.push_str(&capitalize(word));
+            }
+        }
+    }
+    id
+}
+
+fn tag_for(path: &str) -> String {
+    path.split('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or("root")
+        .to_string()
+}
+
+fn access_description(route: &RouteDoc) -> String {
+    let mut description = if route.public {
+        "No authentication required.".to_string()
+    } else {
+        format!("Requires role: {}.", route.role)
+    };
+    if route.guest {
+        description.push_str(" Anonymous callers get a reduced guest response.");
+    }
+    match route.api_key_scope {
+        Some(scope) => {
+            let _ = write!(description, " Partner API keys need scope `{scope}`.");
+        }
+        None if !route.public => description.push_str(" Not available to partner API keys."),
+        None => {}
+    }
+    description
+}
+
+fn security_for(route: &RouteDoc) -> Vec<SecurityRequirement> {
+    if route.public {
+        return Vec::new();
+    }
+    let mut requirements = vec![SecurityRequirement::new(
+        BEARER_SCHEME,
+        Vec::<String>::new(),
+    )];
+    if let Some(scope) = route.api_key_scope {
+        requirements.push(SecurityRequirement::new(API_KEY_SCHEME, [scope]));
+    }
+    if route.guest {
+        // An empty requirement marks credentials as optional.
+        requirements.push(SecurityRequirement::default());
+    }
+    requirements
+}
+
+fn http_method(method: &str) -> HttpMethod {
+    match method {
+        "POST" => HttpMethod::Post,
+        "PUT" => HttpMethod::Put,
+        "DELETE" => HttpMethod::Delete,
+        "PATCH" => HttpMethod::Patch,
+        _ => HttpMethod::Get,
+    }
+}
+
+/// Mirrors the JSON body built by `ApiError::into_response`.
+fn error_schema() -> Schema {
+    let string = || ObjectBuilder::new().schema_type(Type::String).build();
+    let issue = ObjectBuilder::new()
+        .property("field", string())
+        .required("field")
+        .property("code", string())
+        .required("code")
+        .property("message", string())
+        .required("message")
+        .build();
+
+    Schema::Object(
+        ObjectBuilder::new()
+            .property("error", string())
+            .required("error")
+            .property("errorCode", string())
+            .required("errorCode")
+            .property("code", string())
+            .required("code")
+            .property("field", string())
+            .property("message", string())
+            .property("details", Schema::Array(Array::new(issue)))
+            .property("suggestions", Schema::Array(Array::new(string())))
+            .build(),
+    )
+}
+
+fn capitalize(value: &str) -> String {
+    let mut chars = value.chars();
+    chars.next().map_or_else(String::new, |first| {
+        first.to_uppercase().chain(chars).collect()
+    })
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::literal_string_with_formatting_args)]
+mod tests {
+    use super::*;
+
+    const ROUTES: &[RouteDoc] = &[
+        RouteDoc {
+            method: "GET",
+            pattern: "/users/{userId:uuid}",
+            role: "Authenticated",
+            public: false,
+            guest: false,
+            api_key_scope: None,
+        },
+        RouteDoc {
+            method: "POST",
+            pattern: "/users/{userId:uuid}/follow",
+            role: "Participant",
+            public: false,
+            guest: false,
+            api_key_scope: None,
+        },
+        RouteDoc {
+            method: "GET",
+            pattern: "/feed/derived",
+            role: "Participant",
+            public: false,
+            guest: false,
+            api_key_scope: Some("feed:read"),
+        },
+        RouteDoc {
+            method: "GET",
+            pattern: "/stats/impact",
+            role: "Public",
+            public: true,
+            guest: false,
+            api_key_scope: None,
+        },
+    ];
+
+    #[test]
+    fn openapi_path_strips_parameter_types() {
+        let (path, params) = openapi_path("/groups/{groupId:uuid}/members/{userId:uuid}");
+        assert_eq!(path, "/groups/{groupId}/members/{userId}");
+        assert_eq!(
+            params,
+            vec![("groupId".to_string(), true), ("userId".to_string(), true)]
+        );
+        assert_eq!(openapi_path("/me").0, "/me");
+    }
+
+    #[test]
+    fn operation_id_is_camel_case_and_unique_per_method() {
+        assert_eq!(
+            operation_id("POST", "/users/{userId}/follow"),
+            "postUsersByUserIdFollow"
+        );
+        assert_eq!(operation_id("GET", "/me/schedule.ics"), "getMeScheduleIcs");
+        assert_eq!(operation_id("GET", "/agent-tasks"), "getAgentTasks");
+    }
+
+    #[test]
+    fn document_is_openapi_3_1_with_every_route() {
+        let doc = document(ROUTES);
+        let json = serde_json::to_value(doc).unwrap();
+
+        assert!(json["openapi"].as_str().unwrap().starts_with("3.1"));
+        assert!(json["paths"]["/users/{userId}"]["get"].is_object());
+        assert!(json["paths"]["/users/{userId}/follow"]["post"].is_object());
+        assert_eq!(
+            json["paths"]["/users/{userId}"]["get"]["responses"]["200"]["content"]
+                ["application/json"]["schema"]["$ref"],
+            "#/components/schemas/PublicUserResponse"
+        );
+        assert!(json["components"]["schemas"]["DerivedFeedResponse"].is_object());
+        assert!(json["components"]["schemas"][ERROR_SCHEMA].is_object());
+    }
+
+    #[test]
+    fn document_marks_public_and_scoped_security() {
+        let json = serde_json::to_value(document(ROUTES)).unwrap();
+
+        assert_eq!(
+            json["paths"]["/stats/impact"]["get"]["security"],
+            serde_json::json!([])
+        );
+        assert_eq!(
+            json["paths"]["/feed/derived"]["get"]["security"],
+            serde_json::json!([{ "bearerAuth": [] }, { "apiKeyAuth": ["feed:read"] }])
+        );
+    }
+
+    #[test]
+    fn body_tables_name_registered_schemas() {
+        let json = serde_json::to_value(ApiDoc::openapi()).unwrap();
+        let schemas = &json["components"]["schemas"];
+        for (.., schema, _) in RESPONSE_BODIES {
+            assert!(schemas[*schema].is_object(), "missing schema {schema}");
+        }
+        for (.., schema) in REQUEST_BODIES {
+            assert!(schemas[*schema].is_object(), "missing schema {schema}");
+        }
+    }
+}
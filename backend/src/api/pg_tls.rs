@@ -0,0 +1,236 @@
+//! Postgres TLS settings shared by the API and the authorizer.
+//!
+//! `tokio_postgres` only understands `sslmode=disable|prefer|require` and
+//! rejects `sslrootcert`, while RDS and Neon connection strings commonly use
+//! `verify-full` and a CA bundle path. Those options are stripped from
+//! `DATABASE_URL` here and applied to the rustls connector instead.
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::CertificateDer;
+use rustls::{ClientConfig, RootCertStore};
+use std::str::FromStr;
+use tokio_postgres::config::{ChannelBinding, Config, SslMode as PgSslMode};
+use tokio_postgres_rustls::MakeRustlsConnect;
+
+/// Overrides the CA bundle when `sslrootcert` is not in the URL.
+const ROOT_CERT_ENV: &str = "DATABASE_SSL_ROOT_CERT";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SslMode {
+    Disable,
+    Prefer,
+    Require,
+    VerifyCa,
+    VerifyFull,
+}
+
+impl SslMode {
+    fn parse(raw: &str) -> Result<Self, String> {
+        match raw {
+            "disable" => Ok(Self::Disable),
+            "prefer" | "allow" => Ok(Self::Prefer),
+            "require" => Ok(Self::Require),
+            "verify-ca" => Ok(Self::VerifyCa),
+            "verify-full" => Ok(Self::VerifyFull),
+            other => Err(format!("Unsupported sslmode {other}")),
+        }
+    }
+
+    /// rustls always checks the chain and hostname, so every TLS mode is
+    /// enforced at least as strictly as `verify-full`.
+    const fn to_tokio_postgres(self) -> PgSslMode {
+        match self {
+            Self::Disable => PgSslMode::Disable,
+            Self::Prefer => PgSslMode::Prefer,
+            Self::Require | Self::VerifyCa | Self::VerifyFull => PgSslMode::Require,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RootCerts {
+    Native,
+    PemFile(String),
+}
+
+#[derive(Debug)]
+pub struct ConnectSettings {
+    pub config: Config,
+    pub ssl_mode: SslMode,
+    root_certs: RootCerts,
+}
+
+impl ConnectSettings {
+    pub fn from_database_url(database_url: &str) -> Result<Self, String> {
+        let (stripped_url, options) = extract_tls_options(database_url);
+
+        let ssl_mode = options
+            .ssl_mode
+            .as_deref()
+            .map_or(Ok(SslMode::Prefer), SslMode::parse)?;
+        let root_certs = options
+            .root_cert
+            .or_else(|| std::env::var(ROOT_CERT_ENV).ok())
+            .filter(|path| !path.is_empty() && path != "system")
+            .map_or(RootCerts::Native, RootCerts::PemFile);
+
+        let mut config =
+            Config::from_str(&stripped_url).map_err(|e| format!("Invalid DATABASE_URL: {e}"))?;
+        config.ssl_mode(ssl_mode.to_tokio_postgres());
+        if matches!(config.get_channel_binding(), ChannelBinding::Require) {
+            config.channel_binding(ChannelBinding::Prefer);
+        }
+
+        Ok(Self {
+            config,
+            ssl_mode,
+            root_certs,
+        })
+    }
+
+    pub fn tls_connector(&self) -> Result<MakeRustlsConnect, String> {
+        let mut root_store = RootCertStore::empty();
+
+        if self.ssl_mode != SslMode::Disable {
+            let added = match &self.root_certs {
+                RootCerts::Native => {
+                    let cert_result = rustls_native_certs::load_native_certs();
+                    root_store.add_parsable_certificates(cert_result.certs).0
+                }
+                RootCerts::PemFile(path) => {
+                    let certs = CertificateDer::pem_file_iter(path)
+                        .map_err(|e| format!("Unable to read sslrootcert {path}: {e}"))?
+                        .collect::<Result<Vec<_>, _>>()
+                        .map_err(|e| format!("Invalid certificate in {path}: {e}"))?;
+                    root_store.add_parsable_certificates(certs).0
+                }
+            };
+
+            if added == 0 {
+                return Err("No root certificates available for Postgres TLS".to_string());
+            }
+        }
+
+        let tls_config = ClientConfig::builder()
+            .with_root_certificates(root_store)
+            .with_no_client_auth();
+        Ok(MakeRustlsConnect::new(tls_config))
+    }
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+struct TlsOptions {
+    ssl_mode: Option<String>,
+    root_cert: Option<String>,
+}
+
+/// Removes `sslmode` and `sslrootcert` from either URL (`postgres://...?a=b`)
+/// or key/value (`host=... sslmode=...`) connection strings.
+fn extract_tls_options(database_url: &str) -> (String, TlsOptions) {
+    let mut options = TlsOptions::default();
+    let mut take = |key: &str, value: &str| match key {
+        "sslmode" => {
+            options.ssl_mode = Some(value.to_string());
+            true
+        }
+        "sslrootcert" => {
+            options.root_cert = Some(value.to_string());
+            true
+        }
+        _ => false,
+    };
+
+    let stripped = if database_url.contains("://") {
+        match database_url.split_once('?') {
+            Some((base, query)) => {
+                let kept: Vec<&str> = query
+                    .split('&')
+                    .filter(|pair| {
+                        let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                        !take(key, value)
+                    })
+                    .collect();
+                if kept.is_empty() {
+                    base.to_string()
+                } else {
+                    format!("{base}?{}", kept.join("&"))
+                }
+            }
+            None => database_url.to_string(),
+        }
+    } else {
+        database_url
+            .split_whitespace()
+            .filter(|pair| {
+                let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+                !take(key, value)
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    (stripped, options)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extract_tls_options_strips_url_query_params() {
+        let (url, options) = extract_tls_options(
+            "postgres://u:p@db.example.com:5432/app?sslmode=verify-full&sslrootcert=/opt/rds.pem&application_name=api",
+        );
+        assert_eq!(
+            url,
+            "postgres://u:p@db.example.com:5432/app?application_name=api"
+        );
+        assert_eq!(options.ssl_mode.as_deref(), Some("verify-full"));
+        assert_eq!(options.root_cert.as_deref(), Some("/opt/rds.pem"));
+    }
+
+    #[test]
+    fn extract_tls_options_drops_empty_query() {
+        let (url, options) = extract_tls_options("postgres://u@host/app?sslmode=require");
+        assert_eq!(url, "postgres://u@host/app");
+        assert_eq!(options.ssl_mode.as_deref(), Some("require"));
+    }
+
+    #[test]
+    fn extract_tls_options_handles_key_value_strings() {
+        let (url, options) = extract_tls_options("host=db user=app sslmode=verify-ca dbname=app");
+        assert_eq!(url, "host=db user=app dbname=app");
+        assert_eq!(options.ssl_mode.as_deref(), Some("verify-ca"));
+    }
+
+    #[test]
+    fn settings_map_verify_modes_to_required_tls() {
+        let settings =
+            ConnectSettings::from_database_url("postgres://u@host/app?sslmode=verify-full")
+                .unwrap();
+        assert_eq!(settings.ssl_mode, SslMode::VerifyFull);
+        assert_eq!(settings.config.get_ssl_mode(), PgSslMode::Require);
+    }
+
+    #[test]
+    fn settings_default_to_prefer() {
+        let settings = ConnectSettings::from_database_url("postgres://u@host/app").unwrap();
+        assert_eq!(settings.ssl_mode, SslMode::Prefer);
+    }
+
+    #[test]
+    fn settings_reject_unknown_sslmode() {
+        let error = ConnectSettings::from_database_url("postgres://u@host/app?sslmode=sometimes")
+            .unwrap_err();
+        assert!(error.contains("sometimes"));
+    }
+
+    #[test]
+    fn disabled_tls_does_not_need_root_certs() {
+        let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+        let settings =
+            ConnectSettings::from_database_url("postgres://u@host/app?sslmode=disable").unwrap();
+        assert!(settings.tls_connector().is_ok());
+    }
+}
@@ -0,0 +1,76 @@
+//! Quantity validation for listings, requests, and claims. Every quantity
+//! column is `numeric(12,3)`, so payloads are held as [`Decimal`] end to end
+//! and anything Postgres would round or overflow is rejected up front.
+
+use crate::error::ApiError;
+use rust_decimal::Decimal;
+
+const MAX_SCALE: u32 = 3;
+/// Smallest whole number that no longer fits `numeric(12,3)`.
+const QUANTITY_LIMIT: i64 = 1_000_000_000;
+
+/// Checks precision and range, returning the value with trailing zeros
+/// removed. Positivity is left to callers, which report it under their own
+/// error codes.
+pub fn validate(value: Decimal, field: &str) -> Result<Decimal, ApiError> {
+    let normalized = value.normalize();
+
+    if normalized.scale() > MAX_SCALE {
+        return Err(ApiError::invalid_field(
+            field,
+            "too_many_decimal_places",
+            format!("{field} must have at most {MAX_SCALE} decimal places"),
+        ));
+    }
+
+    if normalized.abs() >= Decimal::from(QUANTITY_LIMIT) {
+        return Err(ApiError::invalid_field(
+            field,
+            "quantity_too_large",
+            format!("{field} must be less than {QUANTITY_LIMIT}"),
+        ));
+    }
+
+    Ok(normalized)
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn accepts_up_to_three_decimal_places() {
+        assert_eq!(validate(dec("12.125"), "quantity").unwrap(), dec("12.125"));
+        assert_eq!(validate(dec("2.5000"), "quantity").unwrap(), dec("2.5"));
+    }
+
+    #[test]
+    fn rejects_more_than_three_decimal_places() {
+        let error = validate(dec("0.0001"), "quantityClaimed").unwrap_err();
+        assert_eq!(error.error_code(), "too_many_decimal_places");
+        assert_eq!(
+            error.to_string(),
+            "quantityClaimed must have at most 3 decimal places"
+        );
+    }
+
+    #[test]
+    fn rejects_values_that_overflow_the_column() {
+        assert!(validate(dec("999999999.999"), "quantityTotal").is_ok());
+        let error = validate(dec("1000000000"), "quantityTotal").unwrap_err();
+        assert_eq!(error.error_code(), "quantity_too_large");
+    }
+
+    #[test]
+    fn tenths_sum_exactly() {
+        let remaining = dec("0.3");
+        let claimed = dec("0.1") + dec("0.2");
+        assert_eq!(remaining - claimed, Decimal::ZERO);
+    }
+}
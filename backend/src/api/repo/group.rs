@@ -0,0 +1,29 @@
+use crate::db::TimedQuery;
+use crate::error::ApiError;
+use tokio_postgres::Client;
+use uuid::Uuid;
+
+const MEMBER_ROLE: &str = "
+    select gm.role::text as role
+    from group_members gm
+    join groups g on g.id = gm.group_id
+    where gm.group_id = $1
+      and gm.user_id = $2
+      and g.deleted_at is null";
+
+/// The caller's role in an undeleted group, or `None` when they are not a
+/// member (or the group does not exist).
+pub async fn member_role(
+    client: &Client,
+    group_id: Uuid,
+    user_id: Uuid,
+) -> Result<Option<String>, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "repo::group::member_role",
+            MEMBER_ROLE,
+            &[&group_id, &user_id],
+        )
+        .await?;
+    Ok(row.map(|row| row.get("role")))
+}
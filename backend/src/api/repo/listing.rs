@@ -0,0 +1,588 @@
+use crate::coordinate_privacy::LocatedEntity;
+use crate::db::TimedQuery;
+use crate::error::ApiError;
+use crate::listing_kind::ListingKind;
+use crate::models::listing::{ListingClaimCounts, ListingItem, PhotoCropMismatch};
+use chrono::{DateTime, NaiveDate, Utc};
+use tokio_postgres::{Client, Row};
+use uuid::Uuid;
+
+/// Columns read by [`row_to_listing_item`]. A macro rather than a `const` so
+/// it can be spliced into static SQL with `concat!`. `$locale` is the
+/// placeholder holding the caller's locale for crop and variety names.
+macro_rules! listing_item_columns {
+    ($locale:literal) => {
+        concat!(
+            "id, user_id, grower_crop_id, crop_id, variety_id, title, unit,
+             quantity_total::text as quantity_total,
+             quantity_remaining::text as quantity_remaining,
+             reserved_quantity::text as reserved_quantity,
+             available_start, available_end, status::text as status,
+             pickup_location_text, pickup_address, effective_pickup_address,
+             pickup_disclosure_policy::text as pickup_disclosure_policy,
+             pickup_notes, contact_pref::text as contact_pref,
+             geo_key, lat, lng, group_id, created_at,
+             photo_crop_id, photo_crop_confidence,
+             listing_kind::text as listing_kind, return_by,
+             publish_at, expire_at,
+             crop_display_name(crop_id, ",
+            $locale,
+            ") as crop_name,
+             variety_display_name(variety_id, ",
+            $locale,
+            ") as variety_name"
+        )
+    };
+}
+
+/// Joins pending and confirmed claim counts onto `surplus_listings` as `cc`
+/// and selects the columns [`row_to_owned_listing_item`] reads. `$user` is
+/// the placeholder holding the owner's id; counting only their listings
+/// keeps the grouped subquery small.
+macro_rules! owner_claim_counts {
+    ($user:literal) => {
+        concat!(
+            ",
+            coalesce(cc.pending, 0) as pending_claim_count,
+            coalesce(cc.confirmed, 0) as confirmed_claim_count
+            from surplus_listings
+            left join (
+              select c.listing_id,
+                     count(*) filter (where c.status = 'pending')::bigint as pending,
+                     count(*) filter (where c.status = 'confirmed')::bigint as confirmed
+              from claims c
+              join surplus_listings owned on owned.id = c.listing_id
+              where owned.user_id = ",
+            $user,
+            "
+                and c.status in ('pending', 'confirmed')
+              group by c.listing_id
+            ) cc on cc.listing_id = surplus_listings.id"
+        )
+    };
+}
+
+const LIST_BY_OWNER: &str = concat!(
+    "select ",
+    listing_item_columns!("$5"),
+    owner_claim_counts!("$1"),
+    "
+    where user_id = $1
+      and deleted_at is null
+      and ($2::text is null or status = $2::text::listing_status)
+    order by created_at desc, id desc
+    limit $3 offset $4"
+);
+
+const FIND_BY_OWNER: &str = concat!(
+    "select ",
+    listing_item_columns!("$3"),
+    owner_claim_counts!("$2"),
+    "
+    where id = $1
+      and user_id = $2
+      and deleted_at is null"
+);
+
+const FIND_VISIBLE_BY_IDS: &str = concat!(
+    "select ",
+    listing_item_columns!("$3"),
+    "
+    from surplus_listings l
+    where id = any($1)
+      and deleted_at is null
+      and (
+        user_id = $2
+        or (status = 'active' and moderation_held_at is null)
+        or exists (
+          select 1 from claims c
+          where c.listing_id = l.id
+            and c.claimer_id = $2
+        )
+      )
+    order by created_at desc, id desc"
+);
+
+const LIST_SEMANTIC_CANDIDATES: &str = concat!(
+    "select ",
+    listing_item_columns!("$5"),
+    ",
+    1 - (le.embedding <=> re.embedding) as similarity
+    from request_embeddings re
+    join listing_embeddings le on le.model_id = re.model_id
+    join surplus_listings l on l.id = le.listing_id
+    where re.request_id = $1
+      and l.deleted_at is null
+      and l.moderation_held_at is null
+      and l.status = 'active'
+      and l.user_id <> $2
+      and ($3::text is null or l.geo_key like $3)
+    order by le.embedding <=> re.embedding
+    limit $4"
+);
+
+const LIST_BY_GEO_PREFIX: &str = concat!(
+    "select ",
+    listing_item_columns!("$6"),
+    "
+    from surplus_listings
+    where deleted_at is null
+      and moderation_held_at is null
+      and status = $1::text::listing_status
+      and geo_key is not null
+      and geo_key like $2
+      and ($5::text[] is null or listing_kind::text = any($5))
+    order by created_at desc, id desc
+    limit $3 offset $4"
+);
+
+const LIST_BY_GEO_PREFIXES: &str = concat!(
+    "select ",
+    listing_item_columns!("$5"),
+    "
+    from surplus_listings
+    where deleted_at is null
+      and moderation_held_at is null
+      and status = $1::text::listing_status
+      and geo_key is not null
+      and geo_key like any($2)
+      and ($4::text[] is null or listing_kind::text = any($4))
+    order by created_at desc, id desc
+    limit $3"
+);
+
+const LIST_BY_GROUP: &str = concat!(
+    "select ",
+    listing_item_columns!("$5"),
+    "
+    from surplus_listings
+    where group_id = $1
+      and deleted_at is null
+      and moderation_held_at is null
+      and status = $2::text::listing_status
+    order by created_at desc, id desc
+    limit $3 offset $4"
+);
+
+const SEARCH_ACTIVE: &str = concat!(
+    "select ",
+    listing_item_columns!("$4"),
+    "
+    from surplus_listings l
+    where deleted_at is null
+      and moderation_held_at is null
+      and status = 'active'
+      and ($2::text is null or geo_key like $2)
+      and (
+        title ilike $1
+        or exists (
+          select 1 from crops c
+          where c.id = l.crop_id
+            and c.common_name ilike $1
+        )
+      )
+    order by created_at desc, id desc
+    limit $3"
+);
+
+const COUNT_AVAILABLE_BY_DAY: &str = "
+    with days as (
+      select d::date as day, d at time zone 'UTC' as day_start
+      from generate_series($2::date, $3::date, interval '1 day') as d
+    )
+    select days.day, count(l.id)::bigint as listing_count
+    from days
+    left join surplus_listings l
+      on l.deleted_at is null
+     and l.moderation_held_at is null
+     and l.status = 'active'
+     and l.geo_key like $1
+     and ($4::text[] is null or l.listing_kind::text = any($4))
+     and coalesce(l.available_start, l.created_at) < days.day_start + interval '1 day'
+     and (l.available_end is null or l.available_end >= days.day_start)
+    group by days.day
+    order by days.day";
+
+/// Listings owned by `user_id`, newest first, optionally filtered by status.
+pub async fn list_by_owner(
+    client: &Client,
+    user_id: Uuid,
+    status: Option<&str>,
+    limit: i64,
+    offset: i64,
+    locale: Option<&str>,
+) -> Result<Vec<ListingItem>, ApiError> {
+    let rows = client
+        .query_timed(
+            "repo::listing::list_by_owner",
+            LIST_BY_OWNER,
+            &[&user_id, &status, &limit, &offset, &locale],
+        )
+        .await?;
+    Ok(rows.iter().map(row_to_owned_listing_item).collect())
+}
+
+pub async fn find_by_owner(
+    client: &Client,
+    listing_id: Uuid,
+    user_id: Uuid,
+    locale: Option<&str>,
+) -> Result<Option<ListingItem>, ApiError> {
+    let row = client
+        .query_opt_timed(
+            "repo::listing::find_by_owner",
+            FIND_BY_OWNER,
+            &[&listing_id, &user_id, &locale],
+        )
+        .await?;
+    Ok(row.as_ref().map(row_to_owned_listing_item))
+}
+
+/// The subset of `listing_ids` that `viewer_id` may read: their own listings
+/// in any status, active listings not held by moderation, and listings they
+/// have claimed.
+pub async fn find_visible_by_ids(
+    client: &Client,
+    listing_ids: &[Uuid],
+    viewer_id: Uuid,
+    locale: Option<&str>,
+) -> Result<Vec<ListingItem>, ApiError> {
+    let rows = client
+        .query_timed(
+            "repo::listing::find_visible_by_ids",
+            FIND_VISIBLE_BY_IDS,
+            &[&listing_ids, &viewer_id, &locale],
+        )
+        .await?;
+    Ok(rows.iter().map(row_to_listing_item).collect())
+}
+
+/// Active listings from other users paired with their cosine similarity to
+/// the request's embedding, most similar first. Empty when the request has
+/// not been embedded yet.
+pub async fn list_semantic_candidates(
+    client: &Client,
+    request_id: Uuid,
+    viewer_id: Uuid,
+    geo_prefix: Option<&str>,
+    limit: i64,
+    locale: Option<&str>,
+) -> Result<Vec<(ListingItem, f64)>, ApiError> {
+    let geo_pattern = geo_prefix.map(|prefix| format!("{prefix}%"));
+    let rows = client
+        .query_timed(
+            "repo::listing::list_semantic_candidates",
+            LIST_SEMANTIC_CANDIDATES,
+            &[&request_id, &viewer_id, &geo_pattern, &limit, &locale],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| (row_to_listing_item(row), row.get::<_, f64>("similarity")))
+        .collect())
+}
+
+/// Undeleted, unheld listings in `status` whose geohash starts with
+/// `geo_prefix`, limited to `kinds` when given.
+pub async fn list_by_geo_prefix(
+    client: &Client,
+    status: &str,
+    geo_prefix: &str,
+    kinds: Option<&[ListingKind]>,
+    limit: i64,
+    offset: i64,
+    locale: Option<&str>,
+) -> Result<Vec<ListingItem>, ApiError> {
+    let geo_pattern = format!("{geo_prefix}%");
+    let kinds = kind_names(kinds);
+    let rows = client
+        .query_timed(
+            "repo::listing::list_by_geo_prefix",
+            LIST_BY_GEO_PREFIX,
+            &[&status, &geo_pattern, &limit, &offset, &kinds, &locale],
+        )
+        .await?;
+    Ok(rows.iter().map(row_to_listing_item).collect())
+}
+
+/// Undeleted, unheld listings in `status` whose geohash starts with any of
+/// `geo_prefixes`, limited to `kinds` when given, newest first, each with its
+/// stored coordinates at full precision (the item's own are rounded for
+/// display).
+pub async fn list_by_geo_prefixes(
+    client: &Client,
+    status: &str,
+    geo_prefixes: &[String],
+    kinds: Option<&[ListingKind]>,
+    limit: i64,
+    locale: Option<&str>,
+) -> Result<Vec<(ListingItem, Option<(f64, f64)>)>, ApiError> {
+    let geo_patterns = geo_prefixes
+        .iter()
+        .map(|prefix| format!("{prefix}%"))
+        .collect::<Vec<_>>();
+    let kinds = kind_names(kinds);
+    let rows = client
+        .query_timed(
+            "repo::listing::list_by_geo_prefixes",
+            LIST_BY_GEO_PREFIXES,
+            &[&status, &geo_patterns, &limit, &kinds, &locale],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let point = row
+                .get::<_, Option<f64>>("lat")
+                .zip(row.get::<_, Option<f64>>("lng"));
+            (row_to_listing_item(row), point)
+        })
+        .collect())
+}
+
+/// Active, unheld listings whose title or crop name matches `pattern` (an
+/// `ilike` pattern), within `geo_prefix` when given, newest first.
+pub async fn search_active(
+    client: &Client,
+    pattern: &str,
+    geo_prefix: Option<&str>,
+    limit: i64,
+    locale: Option<&str>,
+) -> Result<Vec<ListingItem>, ApiError> {
+    let geo_pattern = geo_prefix.map(|prefix| format!("{prefix}%"));
+    let rows = client
+        .query_timed(
+            "repo::listing::search_active",
+            SEARCH_ACTIVE,
+            &[&pattern, &geo_pattern, &limit, &locale],
+        )
+        .await?;
+    Ok(rows.iter().map(row_to_listing_item).collect())
+}
+
+/// For each UTC day from `first_day` to `last_day`, how many active, unheld
+/// listings under `geo_prefix` are available at some point that day. A
+/// listing without a start is available from when it was posted, and one
+/// without an end stays available.
+pub async fn count_available_by_day(
+    client: &Client,
+    geo_prefix: &str,
+    kinds: Option<&[ListingKind]>,
+    first_day: NaiveDate,
+    last_day: NaiveDate,
+) -> Result<Vec<(NaiveDate, i64)>, ApiError> {
+    let geo_pattern = format!("{geo_prefix}%");
+    let kinds = kind_names(kinds);
+    let rows = client
+        .query_timed(
+            "repo::listing::count_available_by_day",
+            COUNT_AVAILABLE_BY_DAY,
+            &[&geo_pattern, &first_day, &last_day, &kinds],
+        )
+        .await?;
+    Ok(rows
+        .iter()
+        .map(|row| (row.get("day"), row.get("listing_count")))
+        .collect())
+}
+
+/// Undeleted, unheld listings in `status` posted under `group_id`, newest
+/// first.
+pub async fn list_by_group(
+    client: &Client,
+    group_id: Uuid,
+    status: &str,
+    limit: i64,
+    offset: i64,
+    locale: Option<&str>,
+) -> Result<Vec<ListingItem>, ApiError> {
+    let rows = client
+        .query_timed(
+            "repo::listing::list_by_group",
+            LIST_BY_GROUP,
+            &[&group_id, &status, &limit, &offset, &locale],
+        )
+        .await?;
+    Ok(rows.iter().map(row_to_listing_item).collect())
+}
+
+fn kind_names(kinds: Option<&[ListingKind]>) -> Option<Vec<&'static str>> {
+    kinds.map(|kinds| kinds.iter().map(|kind| kind.as_str()).collect())
+}
+
+pub fn row_to_listing_item(row: &Row) -> ListingItem {
+    let (lat, lng) = LocatedEntity::Listing.optional_point(row, "lat", "lng");
+    ListingItem {
+        id: row.get::<_, Uuid>("id").to_string(),
+        user_id: row.get::<_, Uuid>("user_id").to_string(),
+        grower_crop_id: row
+            .get::<_, Option<Uuid>>("grower_crop_id")
+            .map(|id| id.to_string()),
+        listing_kind: row.get("listing_kind"),
+        crop_id: row
+            .get::<_, Option<Uuid>>("crop_id")
+            .map(|id| id.to_string()),
+        variety_id: row
+            .get::<_, Option<Uuid>>("variety_id")
+            .map(|id| id.to_string()),
+        crop_name: row.get("crop_name"),
+        variety_name: row.get("variety_name"),
+        title: row.get("title"),
+        unit: row.get("unit"),
+        quantity_total: row.get("quantity_total"),
+        quantity_remaining: row.get("quantity_remaining"),
+        reserved_quantity: row.get("reserved_quantity"),
+        available_start: row
+            .get::<_, Option<DateTime<Utc>>>("available_start")
+            .map(|value| value.to_rfc3339()),
+        available_end: row
+            .get::<_, Option<DateTime<Utc>>>("available_end")
+            .map(|value| value.to_rfc3339()),
+        return_by: row
+            .get::<_, Option<DateTime<Utc>>>("return_by")
+            .map(|value| value.to_rfc3339()),
+        publish_at: row
+            .get::<_, Option<DateTime<Utc>>>("publish_at")
+            .map(|value| value.to_rfc3339()),
+        expire_at: row
+            .get::<_, Option<DateTime<Utc>>>("expire_at")
+            .map(|value| value.to_rfc3339()),
+        status: row.get("status"),
+        pickup_location_text: row.get("pickup_location_text"),
+        pickup_address: row.get("pickup_address"),
+        effective_pickup_address: row.get("effective_pickup_address"),
+        pickup_disclosure_policy: row.get("pickup_disclosure_policy"),
+        pickup_notes: row.get("pickup_notes"),
+        contact_pref: row.get("contact_pref"),
+        geo_key: row.get("geo_key"),
+        lat,
+        lng,
+        group_id: row
+            .get::<_, Option<Uuid>>("group_id")
+            .map(|id| id.to_string()),
+        created_at: row.get::<_, DateTime<Utc>>("created_at").to_rfc3339(),
+        photo_crop_mismatch: photo_crop_mismatch(
+            row.get("crop_id"),
+            row.get("photo_crop_id"),
+            row.get("photo_crop_confidence"),
+        ),
+        claim_counts: None,
+    }
+}
+
+/// [`row_to_listing_item`] plus the claim counts only owner reads select.
+fn row_to_owned_listing_item(row: &Row) -> ListingItem {
+    ListingItem {
+        claim_counts: Some(ListingClaimCounts {
+            pending: row.get("pending_claim_count"),
+            confirmed: row.get("confirmed_claim_count"),
+        }),
+        ..row_to_listing_item(row)
+    }
+}
+
+/// A photo guess only warns when it names a different crop; listings without
+/// a crop, such as tool loans, never warn.
+fn photo_crop_mismatch(
+    crop_id: Option<Uuid>,
+    photo_crop_id: Option<Uuid>,
+    confidence: Option<f64>,
+) -> Option<PhotoCropMismatch> {
+    let crop_id = crop_id?;
+    photo_crop_id
+        .filter(|photo_crop_id| *photo_crop_id != crop_id)
+        .map(|photo_crop_id| PhotoCropMismatch {
+            suggested_crop_id: photo_crop_id.to_string(),
+            confidence: confidence.unwrap_or(0.0),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn queries_share_aliased_column_list() {
+        for sql in [
+            LIST_BY_OWNER,
+            FIND_BY_OWNER,
+            FIND_VISIBLE_BY_IDS,
+            LIST_SEMANTIC_CANDIDATES,
+            LIST_BY_GEO_PREFIX,
+            LIST_BY_GROUP,
+        ] {
+            assert!(sql.starts_with("select id, user_id, grower_crop_id"));
+            assert!(sql.contains("status::text as status"));
+            assert!(sql.contains("pickup_disclosure_policy::text as pickup_disclosure_policy"));
+            assert!(sql.contains("contact_pref::text as contact_pref"));
+            assert!(sql.contains("group_id, created_at"));
+            assert!(sql.contains("photo_crop_id, photo_crop_confidence"));
+            assert!(sql.contains("listing_kind::text as listing_kind, return_by"));
+            assert!(sql.contains("crop_display_name(crop_id, $"));
+            assert!(sql.contains("variety_display_name(variety_id, $"));
+            assert!(sql.contains("deleted_at is null"));
+        }
+    }
+
+    #[test]
+    fn find_visible_by_ids_limits_to_owned_active_or_claimed() {
+        assert!(FIND_VISIBLE_BY_IDS.contains("id = any($1)"));
+        assert!(FIND_VISIBLE_BY_IDS.contains("user_id = $2"));
+        assert!(
+            FIND_VISIBLE_BY_IDS.contains("or (status = 'active' and moderation_held_at is null)")
+        );
+        assert!(FIND_VISIBLE_BY_IDS.contains("c.claimer_id = $2"));
+    }
+
+    #[test]
+    fn public_reads_skip_moderation_holds() {
+        for sql in [
+            LIST_BY_GEO_PREFIX,
+            LIST_BY_GROUP,
+            FIND_VISIBLE_BY_IDS,
+            LIST_SEMANTIC_CANDIDATES,
+        ] {
+            assert!(sql.contains("moderation_held_at is null"));
+        }
+        assert!(!LIST_BY_OWNER.contains("moderation_held_at"));
+    }
+
+    #[test]
+    fn photo_crop_mismatch_only_when_crops_differ() {
+        let crop_id = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        assert_eq!(photo_crop_mismatch(Some(crop_id), None, None), None);
+        assert_eq!(
+            photo_crop_mismatch(Some(crop_id), Some(crop_id), Some(0.9)),
+            None
+        );
+        assert_eq!(photo_crop_mismatch(None, Some(other), Some(0.9)), None);
+        assert_eq!(
+            photo_crop_mismatch(Some(crop_id), Some(other), Some(0.9)),
+            Some(PhotoCropMismatch {
+                suggested_crop_id: other.to_string(),
+                confidence: 0.9,
+            })
+        );
+    }
+
+    #[test]
+    fn geo_reads_kind_filter_is_optional() {
+        assert!(LIST_BY_GEO_PREFIX.contains("$5::text[] is null or listing_kind::text = any($5)"));
+        assert!(LIST_BY_GEO_PREFIXES.contains("$4::text[] is null or listing_kind::text = any($4)"));
+    }
+
+    #[test]
+    fn names_use_the_locale_after_each_querys_own_parameters() {
+        assert!(LIST_BY_OWNER.contains("crop_display_name(crop_id, $5)"));
+        assert!(FIND_VISIBLE_BY_IDS.contains("variety_display_name(variety_id, $3)"));
+        assert!(LIST_BY_GEO_PREFIX.contains("crop_display_name(crop_id, $6)"));
+        assert!(SEARCH_ACTIVE.contains("crop_display_name(crop_id, $4)"));
+    }
+
+    #[test]
+    fn list_by_owner_status_filter_is_optional() {
+        assert!(LIST_BY_OWNER.contains("$2::text is null or status = $2::text::listing_status"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Typed queries shared across handlers. Each submodule owns the column list
+//! and row mapping for one table so handlers never repeat raw SELECTs.
+
+pub mod group;
+pub mod listing;
@@ -0,0 +1,349 @@
+//! Geometry for "listings along my route": decoding an encoded polyline,
+//! covering its buffer with geohash prefixes so listings can be fetched with
+//! the same `geo_key like` scans as area discovery, and measuring how far a
+//! listing sits from the line and how far along it.
+
+use crate::location;
+use std::collections::BTreeSet;
+
+/// Polylines longer than this are refused before decoding.
+pub const MAX_POLYLINE_CHARS: usize = 10_000;
+const MAX_ROUTE_POINTS: usize = 2_000;
+
+/// Upper bound on covering prefixes. Long routes fall back to coarser cells
+/// rather than failing; the exact distance check still applies.
+const MAX_COVERING_PREFIXES: usize = 250;
+
+const KM_PER_DEGREE: f64 = 111.195;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoutePoint {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+/// Where a location sits relative to a route.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RoutePosition {
+    /// Shortest distance to the line.
+    pub distance_km: f64,
+    /// Distance from the start of the route to the nearest point on it.
+    pub along_km: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolylineError {
+    Empty,
+    TooLong,
+    Malformed,
+    OutOfRange,
+}
+
+/// Decodes a Google encoded polyline at precision 5, the format returned by
+/// most routing APIs. A single point is accepted and treated as a stop.
+pub fn decode_polyline(encoded: &str) -> Result<Vec<RoutePoint>, PolylineError> {
+    if encoded.is_empty() {
+        return Err(PolylineError::Empty);
+    }
+    if encoded.len() > MAX_POLYLINE_CHARS {
+        return Err(PolylineError::TooLong);
+    }
+
+    let mut bytes = encoded.bytes();
+    let mut points = Vec::new();
+    let (mut lat, mut lng) = (0_i64, 0_i64);
+    loop {
+        let Some(lat_delta) = next_value(&mut bytes)? else {
+            break;
+        };
+        let lng_delta = next_value(&mut bytes)?.ok_or(PolylineError::Malformed)?;
+        lat += lat_delta;
+        lng += lng_delta;
+
+        let point = RoutePoint {
+            lat: to_degrees(lat),
+            lng: to_degrees(lng),
+        };
+        if !(-90.0..=90.0).contains(&point.lat) || !(-180.0..=180.0).contains(&point.lng) {
+            return Err(PolylineError::OutOfRange);
+        }
+        points.push(point);
+        if points.len() > MAX_ROUTE_POINTS {
+            return Err(PolylineError::TooLong);
+        }
+    }
+    Ok(points)
+}
+
+/// Reads one zigzag-encoded value; `Ok(None)` at the end of input.
+fn next_value(bytes: &mut impl Iterator<Item = u8>) -> Result<Option<i64>, PolylineError> {
+    let mut result = 0_i64;
+    let mut shift = 0_u32;
+    let mut started = false;
+    for byte in bytes.by_ref() {
+        if !(63..=126).contains(&byte) || shift > 30 {
+            return Err(PolylineError::Malformed);
+        }
+        started = true;
+        let chunk = i64::from(byte - 63);
+        result |= (chunk & 0x1f) << shift;
+        shift += 5;
+        if chunk < 0x20 {
+            let value = if result & 1 == 1 {
+                !(result >> 1)
+            } else {
+                result >> 1
+            };
+            return Ok(Some(value));
+        }
+    }
+    if started {
+        Err(PolylineError::Malformed)
+    } else {
+        Ok(None)
+    }
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn to_degrees(value: i64) -> f64 {
+    value as f64 / 1e5
+}
+
+/// Geohash prefixes that together cover every point within `buffer_km` of
+/// the route, sorted. Each sample along the line contributes its cell and
+/// the eight around it, and cells are at least `buffer_km` across, so the
+/// buffer never reaches past the neighbours.
+pub fn covering_prefixes(route: &[RoutePoint], buffer_km: f64) -> Vec<String> {
+    let mut precision = location::geohash_precision_for_radius_km(buffer_km);
+    loop {
+        let cells = cells_at(route, precision);
+        if cells.len() <= MAX_COVERING_PREFIXES || precision == 1 {
+            return cells.into_iter().collect();
+        }
+        precision -= 1;
+    }
+}
+
+fn cells_at(route: &[RoutePoint], precision: usize) -> BTreeSet<String> {
+    let mut cells = BTreeSet::new();
+    let Some(first) = route.first() else {
+        return cells;
+    };
+    let step_km = sample_step_km(*first, precision);
+
+    let mut add = |point: RoutePoint| {
+        let Ok(cell) = geohash::encode(
+            geohash::Coord {
+                x: point.lng,
+                y: point.lat,
+            },
+            precision,
+        ) else {
+            return;
+        };
+        if let Ok(around) = geohash::neighbors(&cell) {
+            cells.extend([
+                around.n, around.ne, around.e, around.se, around.s, around.sw, around.w, around.nw,
+            ]);
+        }
+        cells.insert(cell);
+    };
+
+    add(*first);
+    for pair in route.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let length_km = location::haversine_km(from.lat, from.lng, to.lat, to.lng);
+        let steps = sample_count(length_km, step_km);
+        for step in 1..=steps {
+            let t = f64::from(step) / f64::from(steps);
+            add(RoutePoint {
+                lat: (to.lat - from.lat).mul_add(t, from.lat),
+                lng: (to.lng - from.lng).mul_add(t, from.lng),
+            });
+        }
+    }
+    cells
+}
+
+/// Half the narrower side of a cell at this precision near `point`, so
+/// consecutive samples never skip a cell.
+fn sample_step_km(point: RoutePoint, precision: usize) -> f64 {
+    let fallback = 0.1;
+    let Ok(cell) = geohash::encode(
+        geohash::Coord {
+            x: point.lng,
+            y: point.lat,
+        },
+        precision,
+    ) else {
+        return fallback;
+    };
+    let Ok((_, lng_error, lat_error)) = geohash::decode(&cell) else {
+        return fallback;
+    };
+    let height_km = lat_error * 2.0 * KM_PER_DEGREE;
+    let width_km = lng_error * 2.0 * KM_PER_DEGREE * point.lat.to_radians().cos();
+    (height_km.min(width_km) / 2.0).max(fallback)
+}
+
+#[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+fn sample_count(length_km: f64, step_km: f64) -> u32 {
+    // Capped so a single absurd segment cannot stall the request; the prefix
+    // cap then coarsens the cells anyway.
+    (length_km / step_km).ceil().clamp(1.0, 10_000.0) as u32
+}
+
+/// Nearest point on the route to (`lat`, `lng`). Segments are treated as
+/// straight lines on a local flat projection, which is accurate to well under
+/// the buffer sizes used for corridor search.
+pub fn position_on_route(route: &[RoutePoint], lat: f64, lng: f64) -> Option<RoutePosition> {
+    let first = route.first()?;
+    let mut best = RoutePosition {
+        distance_km: location::haversine_km(lat, lng, first.lat, first.lng),
+        along_km: 0.0,
+    };
+
+    let mut travelled_km = 0.0;
+    for pair in route.windows(2) {
+        let (from, to) = (pair[0], pair[1]);
+        let length_km = location::haversine_km(from.lat, from.lng, to.lat, to.lng);
+
+        let scale = lat.to_radians().cos();
+        let (ax, ay) = ((from.lng - lng) * scale, from.lat - lat);
+        let (bx, by) = ((to.lng - lng) * scale, to.lat - lat);
+        let (dx, dy) = (bx - ax, by - ay);
+        let span = dx.mul_add(dx, dy * dy);
+        let t = if span > 0.0 {
+            (-ax.mul_add(dx, ay * dy) / span).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let (nx, ny) = (dx.mul_add(t, ax), dy.mul_add(t, ay));
+        let distance_km = nx.hypot(ny) * KM_PER_DEGREE;
+
+        if distance_km < best.distance_km {
+            best = RoutePosition {
+                distance_km,
+                along_km: length_km.mul_add(t, travelled_km),
+            };
+        }
+        travelled_km += length_km;
+    }
+    Some(best)
+}
+
+/// Total length of the route.
+pub fn route_length_km(route: &[RoutePoint]) -> f64 {
+    route
+        .windows(2)
+        .map(|pair| location::haversine_km(pair[0].lat, pair[0].lng, pair[1].lat, pair[1].lng))
+        .sum()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    // The worked example from the encoded polyline format documentation.
+    const EXAMPLE: &str = "_p~iF~ps|U_ulLnnqC_mqNvxq`@";
+
+    #[test]
+    fn decodes_reference_polyline() {
+        let points = decode_polyline(EXAMPLE).unwrap();
+        assert_eq!(
+            points,
+            vec![
+                RoutePoint {
+                    lat: 38.5,
+                    lng: -120.2
+                },
+                RoutePoint {
+                    lat: 40.7,
+                    lng: -120.95
+                },
+                RoutePoint {
+                    lat: 43.252,
+                    lng: -126.453
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_polylines() {
+        assert_eq!(decode_polyline(""), Err(PolylineError::Empty));
+        assert_eq!(decode_polyline("_p~iF"), Err(PolylineError::Malformed));
+        assert_eq!(decode_polyline("_p~iF~ps|"), Err(PolylineError::Malformed));
+        assert_eq!(decode_polyline("abc def"), Err(PolylineError::Malformed));
+        assert_eq!(
+            decode_polyline(&"?".repeat(MAX_POLYLINE_CHARS + 1)),
+            Err(PolylineError::TooLong)
+        );
+    }
+
+    #[test]
+    fn position_measures_distance_and_progress() {
+        // Two points about 11.1 km apart along a meridian.
+        let route = [
+            RoutePoint {
+                lat: 42.8,
+                lng: -78.8,
+            },
+            RoutePoint {
+                lat: 42.9,
+                lng: -78.8,
+            },
+        ];
+        let beside = position_on_route(&route, 42.85, -78.79).unwrap();
+        assert!((beside.distance_km - 0.815).abs() < 0.01, "{beside:?}");
+        assert!((beside.along_km - 5.56).abs() < 0.05, "{beside:?}");
+
+        let past_end = position_on_route(&route, 43.0, -78.8).unwrap();
+        assert!((past_end.distance_km - 11.12).abs() < 0.05, "{past_end:?}");
+        assert!((past_end.along_km - route_length_km(&route)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn covering_prefixes_include_cells_beside_the_line() {
+        let route = [
+            RoutePoint {
+                lat: 42.8,
+                lng: -78.8,
+            },
+            RoutePoint {
+                lat: 42.9,
+                lng: -78.8,
+            },
+        ];
+        let prefixes = covering_prefixes(&route, 1.0);
+        assert!(prefixes.iter().all(|prefix| prefix.len() == 5));
+
+        for (lat, lng) in [(42.85, -78.79), (42.8, -78.812), (42.905, -78.8)] {
+            let key = geohash::encode(geohash::Coord { x: lng, y: lat }, 7).unwrap();
+            assert!(
+                prefixes
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix.as_str())),
+                "{key} not covered"
+            );
+        }
+    }
+
+    #[test]
+    fn long_routes_coarsen_instead_of_growing_without_bound() {
+        let route = [
+            RoutePoint {
+                lat: 40.0,
+                lng: -80.0,
+            },
+            RoutePoint {
+                lat: 45.0,
+                lng: -70.0,
+            },
+        ];
+        let prefixes = covering_prefixes(&route, 0.3);
+        assert!(prefixes.len() <= MAX_COVERING_PREFIXES);
+        assert!(prefixes[0].len() < 6);
+    }
+}
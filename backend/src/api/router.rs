@@ -1,18 +1,48 @@
+use crate::auth::{
+    extract_auth_context, extract_auth_context_with_fallback, is_anonymous, require_admin,
+    require_api_scope, require_grower, require_not_suspended, require_participant_user_type,
+    require_read_only_impersonation, require_user_type, AuthContext, UserType,
+};
+use crate::config::Config;
+use crate::error::{ApiError, REQUEST_TIMEOUT};
 use crate::handlers::{
-    agent_task, ai_copilot, analytics, billing, catalog, claim, claim_read, crop, feed, listing,
-    listing_discovery, reminder, request, user,
+    admin_moderation, admin_ops, admin_signals, agent_task, ai_copilot, ai_usage, analytics,
+    announcement, api_key, audit_log, away, billing, catalog, claim, claim_read, co_manager,
+    community_event, conversation, crop, crop_plan, dashboard, delivery, donation_receipt, feed,
+    feed_feedback, follow, garden, group, harvest, health, impersonation, listing,
+    listing_discovery, listing_feed, listing_question, listing_template, listing_transition,
+    organization, organization_webhook, planting, reminder, request, request_template, schedule,
+    search, stats, suggested_listing, user,
 };
+use crate::http_util::json_response;
+use crate::metrics;
+use crate::middleware::body_limits;
 use crate::middleware::correlation::{
-    add_correlation_id_to_response, extract_or_generate_correlation_id,
+    add_correlation_id_to_error_body, add_correlation_id_to_response, correlation_id,
 };
-use lambda_http::{Body, Request, Response};
-use serde::Serialize;
-use std::env;
-use tracing::{error, info};
-
-fn add_cors_headers(mut response: Response<Body>) -> Response<Body> {
-    let origin = env::var("ORIGIN").unwrap_or_else(|_| "http://localhost:5173".to_string());
-
+use crate::openapi::{self, RouteDoc};
+use lambda_http::{Body, Request, RequestExt, Response};
+use std::future::Future;
+use std::pin::Pin;
+use std::time::{Duration, Instant};
+use tracing::{error, field, info, info_span, Instrument, Span};
+use uuid::Uuid;
+
+/// Scopes an admin may grant to a partner API key. Each is declared on the
+/// routes it opens in `ROUTES`; routes without a scope are closed to API keys.
+pub const ALLOWED_API_KEY_SCOPES: &[&str] = &[
+    "listings:read",
+    "feed:read",
+    "requests:write",
+    "claims:read",
+    "claims:write",
+    "catalog:read",
+    "profile:read",
+    "receipts:read",
+    "organization:write",
+];
+
+fn add_cors_headers(mut response: Response<Body>, origin: &str) -> Response<Body> {
     let headers = response.headers_mut();
 
     if let Ok(value) = origin.parse() {
@@ -21,7 +51,7 @@ fn add_cors_headers(mut response: Response<Body>) -> Response<Body> {
     if let Ok(value) = "GET,POST,PUT,DELETE,OPTIONS".parse() {
         headers.insert("Access-Control-Allow-Methods", value);
     }
-    if let Ok(value) = "Content-Type,Authorization,Idempotency-Key,Stripe-Signature,X-Correlation-Id,X-Amz-Date,X-Api-Key,X-Amz-Security-Token".parse() {
+    if let Ok(value) = "Content-Type,Authorization,Idempotency-Key,Stripe-Signature,X-Correlation-Id,X-Amz-Date,X-Api-Key,X-Impersonation-Token,X-Amz-Security-Token".parse() {
         headers.insert("Access-Control-Allow-Headers", value);
     }
     if let Ok(value) = "3600".parse() {
@@ -41,8 +71,37 @@ fn normalize_route_path(path: &str) -> &str {
     }
 }
 
-pub async fn route_request(event: &Request) -> Result<Response<Body>, lambda_http::Error> {
-    let correlation_id = extract_or_generate_correlation_id(event);
+pub async fn route_request(
+    event: &Request,
+    config: &Config,
+) -> Result<Response<Body>, lambda_http::Error> {
+    let correlation_id = correlation_id(event);
+    let xray_trace_id = event
+        .lambda_context_ref()
+        .and_then(|context| context.xray_trace_id.as_deref());
+    let span = info_span!(
+        "request",
+        otel.kind = "server",
+        correlation_id = correlation_id.as_str(),
+        xray_trace_id = xray_trace_id,
+        http.method = event.method().as_str(),
+        http.route = field::Empty,
+        http.status_code = field::Empty
+    );
+
+    let response = dispatch(event, &correlation_id, config)
+        .instrument(span)
+        .await?;
+    Ok(add_correlation_id_to_error_body(response, &correlation_id))
+}
+
+async fn dispatch(
+    event: &Request,
+    correlation_id: &str,
+    config: &Config,
+) -> Result<Response<Body>, lambda_http::Error> {
+    let started_at = Instant::now();
+    let correlation_id = correlation_id.to_string();
 
     let request_path = normalize_route_path(event.uri().path());
 
@@ -61,69 +120,40 @@ pub async fn route_request(event: &Request) -> Result<Response<Body>, lambda_htt
             .map_err(|e| lambda_http::Error::from(e.to_string()))?;
 
         return Ok(add_correlation_id_to_response(
-            add_cors_headers(response),
+            add_cors_headers(response, &config.origin),
             &correlation_id,
         ));
     }
 
-    let response = match (event.method().as_str(), request_path) {
-        ("GET", "/me") => handle(user::get_current_user(event, &correlation_id).await)?,
-        ("PUT", "/me") => handle(user::upsert_current_user(event, &correlation_id).await)?,
-        ("GET", "/me/entitlements") => {
-            handle(user::get_current_entitlements(event, &correlation_id).await)?
-        }
-
-        ("POST", "/billing/checkout-session") => {
-            handle(billing::create_checkout_session(event, &correlation_id).await)?
-        }
-        ("POST", "/billing/webhook") => {
-            handle(billing::handle_webhook(event, &correlation_id).await)?
-        }
-
-        ("POST", "/ai/copilot/weekly-plan") => {
-            handle(ai_copilot::generate_weekly_plan(event, &correlation_id).await)?
-        }
-
-        ("POST", "/analytics/premium/events") => {
-            handle(analytics::track_premium_event(event, &correlation_id).await)?
-        }
-        ("GET", "/analytics/premium/kpis") => {
-            handle(analytics::get_premium_kpis(event, &correlation_id).await)?
-        }
-
-        ("GET", "/agent-tasks") => {
-            handle(agent_task::list_agent_tasks(event, &correlation_id).await)?
-        }
-        ("POST", "/agent-tasks") => {
-            handle(agent_task::create_agent_task(event, &correlation_id).await)?
-        }
-
-        ("GET", "/crops") => handle(crop::list_my_crops(event, &correlation_id).await)?,
-        ("POST", "/crops") => handle(crop::create_my_crop(event, &correlation_id).await)?,
-
-        ("GET", "/my/listings") => handle(listing::list_my_listings(event, &correlation_id).await)?,
-        ("GET", "/listings/discover") => {
-            handle(listing_discovery::discover_listings(event, &correlation_id).await)?
+    let response = match match_route(event.method().as_str(), request_path) {
+        RouteMatch::Found { route, params } => {
+            Span::current().record("http.route", route.pattern);
+            let context = RouteContext {
+                event,
+                correlation_id: &correlation_id,
+                params: &params,
+                config,
+            };
+            handle(run_route(route, context).await)
         }
-        ("GET", "/feed/derived") => handle(feed::get_derived_feed(event, &correlation_id).await)?,
-        ("POST", "/listings") => handle(listing::create_listing(event, &correlation_id).await)?,
-        ("POST", "/requests") => handle(request::create_request(event, &correlation_id).await)?,
-        ("GET", "/claims") => handle(claim_read::list_claims(event, &correlation_id).await)?,
-        ("POST", "/claims") => handle(claim::create_claim(event, &correlation_id).await)?,
-
-        ("GET", "/reminders") => handle(reminder::list_reminders(event, &correlation_id).await)?,
-        ("POST", "/reminders") => handle(reminder::create_reminder(event, &correlation_id).await)?,
-
-        ("GET", "/catalog/crops") => handle(catalog::list_catalog_crops().await)?,
-
-        _ => route_dynamic_routes(event, &correlation_id, request_path).await?,
+        RouteMatch::MethodNotAllowed { allowed } => handle(method_not_allowed(&allowed)),
+        RouteMatch::NotFound => not_found()?,
     };
 
-    let response_with_cors = add_cors_headers(response);
+    let response_with_cors = add_cors_headers(response, &config.origin);
     let response_with_correlation =
         add_correlation_id_to_response(response_with_cors, &correlation_id);
 
     let response_status = response_with_correlation.status().as_u16();
+    Span::current().record("http.status_code", response_status);
+
+    metrics::record_request(
+        &metrics::route_template(request_path),
+        event.method().as_str(),
+        response_status,
+        started_at.elapsed(),
+        &correlation_id,
+    );
 
     if response_status >= 500 {
         error!(
@@ -146,327 +176,1424 @@ pub async fn route_request(event: &Request) -> Result<Response<Body>, lambda_htt
     Ok(response_with_correlation)
 }
 
-async fn route_dynamic_routes(
-    event: &Request,
-    correlation_id: &str,
-    request_path: &str,
-) -> Result<Response<Body>, lambda_http::Error> {
-    if let Some(crop_library_id) = request_path.strip_prefix("/crops/") {
-        let result = match event.method().as_str() {
-            "GET" => crop::get_my_crop(event, correlation_id, crop_library_id).await,
-            "PUT" => crop::update_my_crop(event, correlation_id, crop_library_id).await,
-            "DELETE" => crop::delete_my_crop(event, correlation_id, crop_library_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+async fn run_route(route: &Route, context: RouteContext<'_>) -> Result<Response<Body>, ApiError> {
+    validate_path_params(context.params)?;
+    body_limits::enforce(context.event, &context.config.body_limits)?;
+    authorize_route(route, &context).await?;
+
+    let deadline = route.deadline.unwrap_or(context.config.request_deadline);
+    tokio::time::timeout(deadline, (route.handler)(context))
+        .await
+        .unwrap_or_else(|_| {
+            Err(ApiError::unavailable(
+                REQUEST_TIMEOUT,
+                format!(
+                    "{} {} exceeded its {}ms deadline",
+                    route.method,
+                    route.pattern,
+                    deadline.as_millis()
+                ),
+            ))
+        })
+}
+
+/// Applies the route's declared API-key scope and role. Handlers only read
+/// identity from the auth context; every permission check lives here.
+async fn authorize_route(route: &Route, context: &RouteContext<'_>) -> Result<(), ApiError> {
+    let event = context.event;
+    if route.role == RequiredRole::Public
+        || (route.role == RequiredRole::Guest && is_anonymous(event))
+    {
+        return Ok(());
     }
 
-    if let Some(listing_id) = request_path.strip_prefix("/my/listings/") {
-        let result = match event.method().as_str() {
-            "GET" => listing::get_listing(event, correlation_id, listing_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+    let auth = if route.role.needs_user_type() {
+        extract_auth_context_with_fallback(event).await?
+    } else {
+        extract_auth_context(event)?
+    };
+
+    if auth.impersonation.is_some() {
+        impersonation::record_impersonated_request(
+            &auth,
+            route.method,
+            normalize_route_path(event.uri().path()),
+            context.correlation_id,
+        )
+        .await;
     }
 
-    if let Some(listing_id) = request_path.strip_prefix("/listings/") {
-        let result = match event.method().as_str() {
-            "PUT" => listing::update_listing(event, correlation_id, listing_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+    require_not_suspended(&auth, route.method)?;
+    require_read_only_impersonation(&auth, route.method)?;
+
+    if auth.api_key.is_some() {
+        let scope = route.api_key_scope.ok_or_else(|| {
+            ApiError::forbidden(
+                "api_key_route_not_allowed",
+                "Forbidden: This route is not available to API keys",
+            )
+        })?;
+        require_api_scope(&auth, scope)?;
     }
 
-    if let Some(request_id) = request_path.strip_prefix("/requests/") {
-        let result = match event.method().as_str() {
-            "PUT" => request::update_request(event, correlation_id, request_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+    route.role.check(&auth)
+}
+
+type HandlerFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<Response<Body>, ApiError>> + Send + 'a>>;
+type Handler = for<'a> fn(RouteContext<'a>) -> HandlerFuture<'a>;
+
+/// Role the caller must hold before the route's handler runs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RequiredRole {
+    /// Anyone, signed in or not. The authorizer lets these through without
+    /// credentials, so handlers must not read the auth context.
+    Public,
+    /// Any signed-in caller, including users who have not finished onboarding.
+    Authenticated,
+    /// Onboarded growers and gatherers.
+    Participant,
+    /// Like `Participant` for signed-in callers, but also open without
+    /// credentials; the handler serves anonymous callers a reduced response.
+    Guest,
+    Grower,
+    Gatherer,
+    Admin,
+}
+
+impl RequiredRole {
+    const fn needs_user_type(self) -> bool {
+        matches!(
+            self,
+            Self::Participant | Self::Guest | Self::Grower | Self::Gatherer
+        )
     }
 
-    if let Some(reminder_id) = request_path.strip_prefix("/reminders/") {
-        let result = match event.method().as_str() {
-            "PUT" => reminder::update_reminder_status(event, correlation_id, reminder_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Public => "Public",
+            Self::Authenticated => "Authenticated",
+            Self::Participant | Self::Guest => "Participant",
+            Self::Grower => "Grower",
+            Self::Gatherer => "Gatherer",
+            Self::Admin => "Admin",
+        }
     }
 
-    if let Some(task_id) = request_path.strip_prefix("/agent-tasks/") {
-        let result = match event.method().as_str() {
-            "PUT" => agent_task::update_agent_task_status(event, correlation_id, task_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+    fn check(self, auth: &AuthContext) -> Result<(), ApiError> {
+        match self {
+            Self::Public | Self::Authenticated => Ok(()),
+            Self::Participant | Self::Guest => {
+                require_participant_user_type(auth.user_type.as_ref())
+            }
+            Self::Grower => require_grower(auth),
+            Self::Gatherer => require_user_type(auth, &UserType::Gatherer),
+            Self::Admin => require_admin(auth),
+        }
     }
+}
 
-    if let Some(claim_id) = request_path.strip_prefix("/claims/") {
-        let result = match event.method().as_str() {
-            "PUT" => claim::transition_claim(event, correlation_id, claim_id).await,
-            _ => method_not_allowed(),
-        };
-        return handle(result);
+/// One row of the route table. Path parameters are written `{name}` or
+/// `{name:uuid}`; typed parameters are validated before the handler runs.
+/// `api_key_scope` is the scope a partner API key needs; `None` closes the
+/// route to API keys entirely. `deadline` overrides `REQUEST_DEADLINE_MS`.
+struct Route {
+    method: &'static str,
+    pattern: &'static str,
+    role: RequiredRole,
+    api_key_scope: Option<&'static str>,
+    deadline: Option<Duration>,
+    handler: Handler,
+}
+
+impl Route {
+    const fn with_deadline(self, deadline: Duration) -> Self {
+        Self {
+            deadline: Some(deadline),
+            ..self
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+struct RouteContext<'a> {
+    event: &'a Request,
+    correlation_id: &'a str,
+    params: &'a [PathParam<'a>],
+    config: &'a Config,
+}
+
+impl<'a> RouteContext<'a> {
+    /// Patterns guarantee every declared parameter is present and non-empty.
+    fn param(&self, name: &str) -> &'a str {
+        self.params
+            .iter()
+            .find(|param| param.name == name)
+            .map_or("", |param| param.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct PathParam<'a> {
+    name: &'static str,
+    kind: ParamKind,
+    value: &'a str,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ParamKind {
+    Text,
+    Uuid,
+}
+
+enum RouteMatch<'p> {
+    Found {
+        route: &'static Route,
+        params: Vec<PathParam<'p>>,
+    },
+    MethodNotAllowed {
+        allowed: Vec<&'static str>,
+    },
+    NotFound,
+}
+
+macro_rules! route {
+    ($method:literal, $pattern:literal, $role:ident, |$ctx:ident| $call:expr) => {
+        Route {
+            method: $method,
+            pattern: $pattern,
+            role: RequiredRole::$role,
+            api_key_scope: None,
+            deadline: None,
+            handler: |$ctx| Box::pin($call),
+        }
+    };
+    ($method:literal, $pattern:literal, $role:ident, $scope:literal, |$ctx:ident| $call:expr) => {
+        Route {
+            method: $method,
+            pattern: $pattern,
+            role: RequiredRole::$role,
+            api_key_scope: Some($scope),
+            deadline: None,
+            handler: |$ctx| Box::pin($call),
+        }
+    };
+}
+
+/// Aggregation-heavy reads give up early instead of holding the Lambda for
+/// its full duration.
+const AGGREGATION_DEADLINE: Duration = Duration::from_millis(2500);
+
+/// Literal routes are listed before parameterised routes that could shadow
+/// them (e.g. `/listings/discover` before `/listings/{listingId}`); the first
+/// match wins.
+static ROUTES: &[Route] = &[
+    route!("GET", "/me", Authenticated, "profile:read", |ctx| {
+        user::get_current_user(ctx.event, ctx.correlation_id)
+    }),
+    route!("PUT", "/me", Authenticated, |ctx| {
+        user::upsert_current_user(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/entitlements", Authenticated, |ctx| {
+        user::get_current_entitlements(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/planting-recommendations", Grower, |ctx| {
+        planting::get_planting_recommendations(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("GET", "/me/schedule.ics", Public, |ctx| {
+        schedule::get_schedule_feed(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("GET", "/me/schedule-link", Authenticated, |ctx| {
+        schedule::get_schedule_link(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("POST", "/me/schedule-link", Authenticated, |ctx| {
+        schedule::rotate_schedule_link(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("GET", "/me/pickups", Gatherer, |ctx| {
+        claim_read::list_my_pickups(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/dashboard", Grower, |ctx| {
+        dashboard::get_my_dashboard(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/impersonations", Authenticated, |ctx| {
+        impersonation::list_my_impersonations(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/plots", Grower, |ctx| {
+        garden::list_plots(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/plots", Grower, |ctx| {
+        garden::create_plot(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/plots/{plotId:uuid}", Grower, |ctx| {
+        garden::get_plot(ctx.event, ctx.correlation_id, ctx.param("plotId"))
+    }),
+    route!("PUT", "/me/plots/{plotId:uuid}", Grower, |ctx| {
+        garden::update_plot(ctx.event, ctx.correlation_id, ctx.param("plotId"))
+    }),
+    route!("DELETE", "/me/plots/{plotId:uuid}", Grower, |ctx| {
+        garden::delete_plot(ctx.event, ctx.correlation_id, ctx.param("plotId"))
+    }),
+    route!("POST", "/me/plots/{plotId:uuid}/beds", Grower, |ctx| {
+        garden::create_bed(ctx.event, ctx.correlation_id, ctx.param("plotId"))
+    }),
+    route!(
+        "PUT",
+        "/me/plots/{plotId:uuid}/beds/{bedId:uuid}",
+        Grower,
+        |ctx| {
+            garden::update_bed(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("plotId"),
+                ctx.param("bedId"),
+            )
+        }
+    ),
+    route!(
+        "DELETE",
+        "/me/plots/{plotId:uuid}/beds/{bedId:uuid}",
+        Grower,
+        |ctx| {
+            garden::delete_bed(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("plotId"),
+                ctx.param("bedId"),
+            )
+        }
+    ),
+    route!(
+        "GET",
+        "/me/plots/{plotId:uuid}/beds/{bedId:uuid}/history",
+        Grower,
+        |ctx| {
+            garden::list_bed_history(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("plotId"),
+                ctx.param("bedId"),
+            )
+        }
+    ),
+    route!("GET", "/me/harvests", Grower, |ctx| {
+        harvest::list_harvests(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/harvests", Grower, |ctx| {
+        harvest::create_harvest(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/harvests/{harvestId:uuid}", Grower, |ctx| {
+        harvest::get_harvest(ctx.event, ctx.correlation_id, ctx.param("harvestId"))
+    }),
+    route!("PUT", "/me/harvests/{harvestId:uuid}", Grower, |ctx| {
+        harvest::update_harvest(ctx.event, ctx.correlation_id, ctx.param("harvestId"))
+    }),
+    route!("DELETE", "/me/harvests/{harvestId:uuid}", Grower, |ctx| {
+        harvest::delete_harvest(ctx.event, ctx.correlation_id, ctx.param("harvestId"))
+    }),
+    route!(
+        "GET",
+        "/me/harvests/{harvestId:uuid}/listing-draft",
+        Grower,
+        |ctx| harvest::get_listing_draft(ctx.event, ctx.correlation_id, ctx.param("harvestId"))
+    ),
+    route!("GET", "/me/away", Grower, |ctx| {
+        away::get_away_period(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/away", Grower, |ctx| {
+        away::create_away_period(ctx.event, ctx.correlation_id)
+    }),
+    route!("DELETE", "/me/away", Grower, |ctx| {
+        away::end_away_period(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/me/co-managers", Grower, |ctx| {
+        co_manager::list_co_managers(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/co-managers", Grower, |ctx| {
+        co_manager::invite_co_manager(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "DELETE",
+        "/me/co-managers/{coManagerId:uuid}",
+        Grower,
+        |ctx| co_manager::remove_co_manager(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("coManagerId")
+        )
+    ),
+    route!("GET", "/me/co-manager-invitations", Grower, |ctx| {
+        co_manager::list_invitations(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "POST",
+        "/me/co-manager-invitations/{invitationId:uuid}/accept",
+        Grower,
+        |ctx| co_manager::accept_invitation(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("invitationId")
+        )
+    ),
+    route!(
+        "DELETE",
+        "/me/co-manager-invitations/{invitationId:uuid}",
+        Grower,
+        |ctx| co_manager::leave_invitation(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("invitationId")
+        )
+    ),
+    route!("GET", "/me/crop-plans", Grower, |ctx| {
+        crop_plan::list_crop_plans(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/crop-plans", Grower, |ctx| {
+        crop_plan::create_crop_plan(ctx.event, ctx.correlation_id)
+    }),
+    route!("PUT", "/me/crop-plans/{planId:uuid}", Grower, |ctx| {
+        crop_plan::update_crop_plan(ctx.event, ctx.correlation_id, ctx.param("planId"))
+    }),
+    route!("DELETE", "/me/crop-plans/{planId:uuid}", Grower, |ctx| {
+        crop_plan::delete_crop_plan(ctx.event, ctx.correlation_id, ctx.param("planId"))
+    }),
+    route!("GET", "/me/listing-templates", Grower, |ctx| {
+        listing_template::list_listing_templates(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/listing-templates", Grower, |ctx| {
+        listing_template::create_listing_template(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "DELETE",
+        "/me/listing-templates/{templateId:uuid}",
+        Grower,
+        |ctx| listing_template::delete_listing_template(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("templateId")
+        )
+    ),
+    route!("GET", "/me/request-templates", Gatherer, |ctx| {
+        request_template::list_request_templates(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/me/request-templates", Gatherer, |ctx| {
+        request_template::create_request_template(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "DELETE",
+        "/me/request-templates/{templateId:uuid}",
+        Gatherer,
+        |ctx| request_template::delete_request_template(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("templateId")
+        )
+    ),
+    route!("GET", "/users/{userId:uuid}", Authenticated, |ctx| {
+        user::get_public_user(ctx.param("userId"))
+    }),
+    route!("POST", "/users/{userId:uuid}/follow", Participant, |ctx| {
+        follow::follow_grower(ctx.event, ctx.correlation_id, ctx.param("userId"))
+    }),
+    route!(
+        "DELETE",
+        "/users/{userId:uuid}/follow",
+        Participant,
+        |ctx| follow::unfollow_grower(ctx.event, ctx.correlation_id, ctx.param("userId"))
+    ),
+    route!("POST", "/billing/checkout-session", Authenticated, |ctx| {
+        billing::create_checkout_session(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("POST", "/billing/webhook", Authenticated, |ctx| {
+        billing::handle_webhook(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("POST", "/ai/copilot/weekly-plan", Authenticated, |ctx| {
+        ai_copilot::generate_weekly_plan(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("POST", "/analytics/premium/events", Authenticated, |ctx| {
+        analytics::track_premium_event(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/analytics/premium/kpis", Authenticated, |ctx| {
+        analytics::get_premium_kpis(ctx.event, ctx.correlation_id)
+    })
+    .with_deadline(AGGREGATION_DEADLINE),
+    route!("GET", "/agent-tasks", Authenticated, |ctx| {
+        agent_task::list_agent_tasks(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/agent-tasks", Authenticated, |ctx| {
+        agent_task::create_agent_task(ctx.event, ctx.correlation_id)
+    }),
+    route!("PUT", "/agent-tasks/{taskId:uuid}", Authenticated, |ctx| {
+        agent_task::update_agent_task_status(ctx.event, ctx.correlation_id, ctx.param("taskId"))
+    }),
+    route!("GET", "/crops", Grower, |ctx| {
+        crop::list_my_crops(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/crops", Grower, |ctx| {
+        crop::create_my_crop(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/crops/{cropLibraryId:uuid}", Grower, |ctx| {
+        crop::get_my_crop(ctx.event, ctx.correlation_id, ctx.param("cropLibraryId"))
+    }),
+    route!("PUT", "/crops/{cropLibraryId:uuid}", Grower, |ctx| {
+        crop::update_my_crop(ctx.event, ctx.correlation_id, ctx.param("cropLibraryId"))
+    }),
+    route!("DELETE", "/crops/{cropLibraryId:uuid}", Grower, |ctx| {
+        crop::delete_my_crop(ctx.event, ctx.correlation_id, ctx.param("cropLibraryId"))
+    }),
+    route!("GET", "/my/listings", Grower, |ctx| {
+        listing::list_my_listings(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/my/listings/{listingId:uuid}", Grower, |ctx| {
+        listing::get_listing(ctx.event, ctx.correlation_id, ctx.param("listingId"))
+    }),
+    route!("GET", "/feeds/listings.atom", Public, |ctx| {
+        listing_feed::get_listing_feed(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("GET", "/feeds/listings-link", Authenticated, |ctx| async {
+        listing_feed::get_listing_feed_link(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("GET", "/listings/discover", Guest, "listings:read", |ctx| {
+        listing_discovery::discover_listings(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "GET",
+        "/listings/along-route",
+        Participant,
+        "listings:read",
+        |ctx| listing_discovery::list_listings_along_route(ctx.event, ctx.correlation_id)
+    ),
+    route!(
+        "GET",
+        "/listings/calendar",
+        Participant,
+        "listings:read",
+        |ctx| listing_discovery::get_listing_calendar(ctx.event, ctx.correlation_id)
+    ),
+    route!("GET", "/search", Participant, "listings:read", |ctx| {
+        search::search(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/listings", Participant, "listings:read", |ctx| {
+        listing_discovery::get_listings_by_ids(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/listings", Grower, |ctx| {
+        listing::create_listing(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!(
+        "POST",
+        "/listings/from-template/{templateId:uuid}",
+        Grower,
+        |ctx| listing_template::create_listing_from_template(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("templateId"),
+            ctx.config
+        )
+    ),
+    route!("PUT", "/listings/{listingId:uuid}", Grower, |ctx| {
+        listing::update_listing(ctx.event, ctx.correlation_id, ctx.param("listingId"))
+    }),
+    route!(
+        "POST",
+        "/listings/{listingId:uuid}/transition",
+        Grower,
+        |ctx| listing_transition::transition_listing(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("listingId")
+        )
+    ),
+    route!(
+        "GET",
+        "/listings/{listingId:uuid}/questions",
+        Participant,
+        "listings:read",
+        |ctx| listing_question::list_questions(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("listingId")
+        )
+    ),
+    route!(
+        "POST",
+        "/listings/{listingId:uuid}/questions",
+        Participant,
+        |ctx| {
+            listing_question::ask_question(ctx.event, ctx.correlation_id, ctx.param("listingId"))
+        }
+    ),
+    route!(
+        "PUT",
+        "/listings/{listingId:uuid}/questions/{questionId:uuid}/answer",
+        Grower,
+        |ctx| listing_question::answer_question(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("listingId"),
+            ctx.param("questionId")
+        )
+    ),
+    route!("GET", "/feed/derived", Participant, "feed:read", |ctx| {
+        feed::get_derived_feed(ctx.event, ctx.correlation_id, ctx.config)
+    })
+    .with_deadline(AGGREGATION_DEADLINE),
+    route!("POST", "/feed/feedback", Participant, |ctx| {
+        feed_feedback::submit_feed_feedback(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/announcements", Authenticated, |ctx| {
+        announcement::create_announcement(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "DELETE",
+        "/announcements/{announcementId:uuid}",
+        Authenticated,
+        |ctx| {
+            announcement::delete_announcement(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("announcementId"),
+            )
+        }
+    ),
+    route!("POST", "/requests", Gatherer, "requests:write", |ctx| {
+        request::create_request(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "POST",
+        "/requests/from-template/{templateId:uuid}",
+        Gatherer,
+        "requests:write",
+        |ctx| request_template::create_request_from_template(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("templateId")
+        )
+    ),
+    route!(
+        "PUT",
+        "/requests/{requestId:uuid}",
+        Gatherer,
+        "requests:write",
+        |ctx| request::update_request(ctx.event, ctx.correlation_id, ctx.param("requestId"))
+    ),
+    route!(
+        "GET",
+        "/requests/{requestId:uuid}/suggested-listings",
+        Gatherer,
+        "listings:read",
+        |ctx| {
+            suggested_listing::list_suggested_listings(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("requestId"),
+            )
+        }
+    ),
+    route!("GET", "/claims", Participant, "claims:read", |ctx| {
+        claim_read::list_claims(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/claims", Gatherer, "claims:write", |ctx| {
+        claim::create_claim(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!(
+        "PUT",
+        "/claims/{claimId:uuid}",
+        Participant,
+        "claims:write",
+        |ctx| claim::transition_claim(ctx.event, ctx.correlation_id, ctx.param("claimId"))
+    ),
+    route!("GET", "/org/receipts", Gatherer, "receipts:read", |ctx| {
+        donation_receipt::list_receipts(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "GET",
+        "/org/service-areas",
+        Gatherer,
+        "profile:read",
+        |ctx| organization::get_service_areas(ctx.event, ctx.correlation_id)
+    ),
+    route!(
+        "PUT",
+        "/org/service-areas",
+        Gatherer,
+        "organization:write",
+        |ctx| organization::replace_service_areas(ctx.event, ctx.correlation_id)
+    ),
+    route!("GET", "/org/webhooks", Gatherer, "profile:read", |ctx| {
+        organization_webhook::list_webhooks(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "POST",
+        "/org/webhooks",
+        Gatherer,
+        "organization:write",
+        |ctx| organization_webhook::create_webhook(ctx.event, ctx.correlation_id)
+    ),
+    route!(
+        "DELETE",
+        "/org/webhooks/{webhookId:uuid}",
+        Gatherer,
+        "organization:write",
+        |ctx| organization_webhook::delete_webhook(
+            ctx.event,
+            ctx.correlation_id,
+            ctx.param("webhookId")
+        )
+    ),
+    route!("GET", "/stats/impact", Public, |ctx| {
+        stats::get_impact_stats(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/openapi.json", Public, |_ctx| async {
+        serve_openapi()
+    }),
+    route!("GET", "/health", Public, |ctx| async move {
+        health::get_health(ctx.config)
+    }),
+    route!("GET", "/health/deep", Authenticated, |ctx| {
+        health::get_deep_health(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!("GET", "/deliveries/open", Participant, |ctx| {
+        delivery::list_open_deliveries(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "GET",
+        "/claims/{claimId:uuid}/delivery",
+        Participant,
+        |ctx| delivery::get_delivery(ctx.event, ctx.correlation_id, ctx.param("claimId"))
+    ),
+    route!(
+        "POST",
+        "/claims/{claimId:uuid}/delivery",
+        Participant,
+        |ctx| delivery::offer_delivery(ctx.event, ctx.correlation_id, ctx.param("claimId"))
+    ),
+    route!(
+        "PUT",
+        "/claims/{claimId:uuid}/delivery",
+        Participant,
+        |ctx| delivery::update_delivery(ctx.event, ctx.correlation_id, ctx.param("claimId"))
+    ),
+    route!(
+        "POST",
+        "/claims/{claimId:uuid}/delivery/accept",
+        Participant,
+        |ctx| delivery::accept_delivery(ctx.event, ctx.correlation_id, ctx.param("claimId"))
+    ),
+    route!("GET", "/conversations", Participant, |ctx| {
+        conversation::list_conversations(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/conversations", Participant, |ctx| {
+        conversation::start_conversation(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "GET",
+        "/conversations/{conversationId:uuid}/messages",
+        Participant,
+        |ctx| {
+            conversation::list_messages(ctx.event, ctx.correlation_id, ctx.param("conversationId"))
+        }
+    ),
+    route!(
+        "POST",
+        "/conversations/{conversationId:uuid}/messages",
+        Participant,
+        |ctx| {
+            conversation::send_message(ctx.event, ctx.correlation_id, ctx.param("conversationId"))
+        }
+    ),
+    route!(
+        "POST",
+        "/conversations/{conversationId:uuid}/read",
+        Participant,
+        |ctx| {
+            conversation::mark_conversation_read(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("conversationId"),
+            )
+        }
+    ),
+    route!("GET", "/events/discover", Participant, |ctx| {
+        community_event::discover_events(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/events", Participant, |ctx| {
+        community_event::create_event(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/events/{eventId:uuid}", Participant, |ctx| {
+        community_event::get_event(ctx.event, ctx.correlation_id, ctx.param("eventId"))
+    }),
+    route!("PUT", "/events/{eventId:uuid}", Participant, |ctx| {
+        community_event::update_event(ctx.event, ctx.correlation_id, ctx.param("eventId"))
+    }),
+    route!("POST", "/events/{eventId:uuid}/rsvp", Participant, |ctx| {
+        community_event::rsvp_event(ctx.event, ctx.correlation_id, ctx.param("eventId"))
+    }),
+    route!(
+        "DELETE",
+        "/events/{eventId:uuid}/rsvp",
+        Participant,
+        |ctx| community_event::cancel_rsvp(ctx.event, ctx.correlation_id, ctx.param("eventId"))
+    ),
+    route!("GET", "/groups", Participant, |ctx| {
+        group::list_my_groups(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/groups", Participant, |ctx| {
+        group::create_group(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/groups/join", Participant, |ctx| {
+        group::join_group(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/groups/{groupId:uuid}", Authenticated, |ctx| {
+        group::get_group_page(ctx.event, ctx.correlation_id, ctx.param("groupId"))
+    }),
+    route!(
+        "POST",
+        "/groups/{groupId:uuid}/invite-code",
+        Participant,
+        |ctx| group::rotate_invite_code(ctx.event, ctx.correlation_id, ctx.param("groupId"))
+    ),
+    route!(
+        "PUT",
+        "/groups/{groupId:uuid}/members/{userId:uuid}",
+        Participant,
+        |ctx| {
+            group::update_member_role(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("groupId"),
+                ctx.param("userId"),
+            )
+        }
+    ),
+    route!(
+        "DELETE",
+        "/groups/{groupId:uuid}/members/{userId:uuid}",
+        Participant,
+        |ctx| {
+            group::remove_member(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("groupId"),
+                ctx.param("userId"),
+            )
+        }
+    ),
+    route!("GET", "/reminders", Authenticated, |ctx| {
+        reminder::list_reminders(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/reminders", Authenticated, |ctx| {
+        reminder::create_reminder(ctx.event, ctx.correlation_id)
+    }),
+    route!(
+        "PUT",
+        "/reminders/{reminderId:uuid}",
+        Authenticated,
+        |ctx| {
+            reminder::update_reminder_status(ctx.event, ctx.correlation_id, ctx.param("reminderId"))
+        }
+    ),
+    route!(
+        "GET",
+        "/catalog/crops",
+        Authenticated,
+        "catalog:read",
+        |_ctx| catalog::list_catalog_crops()
+    ),
+    route!(
+        "GET",
+        "/catalog/crops/{cropId:uuid}/varieties",
+        Authenticated,
+        "catalog:read",
+        |ctx| catalog::list_catalog_varieties(ctx.param("cropId"))
+    ),
+    route!("POST", "/admin/organizations", Admin, |ctx| {
+        organization::create_organization(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/admin/api-keys", Admin, |ctx| {
+        api_key::list_api_keys(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/admin/api-keys", Admin, |ctx| {
+        api_key::create_api_key(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/admin/audit-log", Admin, |ctx| {
+        audit_log::list_audit_log(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/admin/ai-usage", Admin, |ctx| {
+        ai_usage::get_ai_usage_rollup(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/admin/stats", Admin, |ctx| {
+        admin_ops::get_admin_stats(ctx.event, ctx.correlation_id)
+    }),
+    route!("GET", "/admin/recent-errors", Admin, |ctx| {
+        admin_ops::list_recent_errors(ctx.event, ctx.correlation_id)
+    }),
+    route!("POST", "/admin/signals/recompute", Admin, |ctx| {
+        admin_signals::recompute_signals(ctx.event, ctx.correlation_id, ctx.config)
+    }),
+    route!(
+        "POST",
+        "/admin/listings/{listingId:uuid}/hide",
+        Admin,
+        |ctx| {
+            admin_moderation::hide_listing(ctx.event, ctx.correlation_id, ctx.param("listingId"))
+        }
+    ),
+    route!(
+        "POST",
+        "/admin/listings/{listingId:uuid}/expire",
+        Admin,
+        |ctx| {
+            admin_moderation::expire_listing(ctx.event, ctx.correlation_id, ctx.param("listingId"))
+        }
+    ),
+    route!("POST", "/admin/users/{userId:uuid}/suspend", Admin, |ctx| {
+        admin_moderation::suspend_user(ctx.event, ctx.correlation_id, ctx.param("userId"))
+    }),
+    route!(
+        "DELETE",
+        "/admin/users/{userId:uuid}/suspend",
+        Admin,
+        |ctx| {
+            admin_moderation::lift_user_suspension(
+                ctx.event,
+                ctx.correlation_id,
+                ctx.param("userId"),
+            )
+        }
+    ),
+    route!(
+        "POST",
+        "/admin/users/{userId:uuid}/impersonation",
+        Admin,
+        |ctx| {
+            impersonation::start_impersonation(ctx.event, ctx.correlation_id, ctx.param("userId"))
+        }
+    ),
+    route!(
+        "DELETE",
+        "/admin/impersonation/{sessionId:uuid}",
+        Admin,
+        |ctx| {
+            impersonation::end_impersonation(ctx.event, ctx.correlation_id, ctx.param("sessionId"))
+        }
+    ),
+    route!("DELETE", "/admin/api-keys/{apiKeyId:uuid}", Admin, |ctx| {
+        api_key::revoke_api_key(ctx.event, ctx.correlation_id, ctx.param("apiKeyId"))
+    }),
+];
+
+fn route_docs() -> Vec<RouteDoc> {
+    ROUTES
+        .iter()
+        .map(|route| RouteDoc {
+            method: route.method,
+            pattern: route.pattern,
+            role: route.role.label(),
+            public: route.role == RequiredRole::Public,
+            guest: route.role == RequiredRole::Guest,
+            api_key_scope: route.api_key_scope,
+        })
+        .collect()
+}
+
+/// Serves the `OpenAPI` document generated from `ROUTES`.
+fn serve_openapi() -> Result<Response<Body>, ApiError> {
+    let document = openapi::document(&route_docs());
+    let mut response = json_response(200, &document)?;
+    if let Ok(value) = "public, max-age=3600".parse() {
+        response.headers_mut().insert("cache-control", value);
     }
+    Ok(response)
+}
 
-    if let Some(user_id) = request_path.strip_prefix("/users/") {
-        return if event.method().as_str() == "GET" {
-            handle(user::get_public_user(user_id).await)
-        } else {
-            method_not_allowed()
+fn match_route<'p>(method: &str, path: &'p str) -> RouteMatch<'p> {
+    let mut allowed = Vec::new();
+
+    for route in ROUTES {
+        let Some(params) = match_pattern(route.pattern, path) else {
+            continue;
         };
+        if route.method == method {
+            return RouteMatch::Found { route, params };
+        }
+        if !allowed.contains(&route.method) {
+            allowed.push(route.method);
+        }
     }
 
-    if let Some(crop_id) = request_path.strip_prefix("/catalog/crops/") {
-        if let Some(crop_id) = crop_id.strip_suffix("/varieties") {
-            return if event.method().as_str() == "GET" {
-                handle(catalog::list_catalog_varieties(crop_id).await)
-            } else {
-                method_not_allowed()
-            };
+    if allowed.is_empty() {
+        RouteMatch::NotFound
+    } else {
+        RouteMatch::MethodNotAllowed { allowed }
+    }
+}
+
+fn match_pattern<'p>(pattern: &'static str, path: &'p str) -> Option<Vec<PathParam<'p>>> {
+    let mut pattern_segments = pattern.trim_start_matches('/').split('/');
+    let mut path_segments = path.trim_start_matches('/').split('/');
+    let mut params = Vec::new();
+
+    loop {
+        match (pattern_segments.next(), path_segments.next()) {
+            (None, None) => return Some(params),
+            (Some(expected), Some(actual)) => {
+                if let Some(spec) = expected
+                    .strip_prefix('{')
+                    .and_then(|rest| rest.strip_suffix('}'))
+                {
+                    if actual.is_empty() {
+                        return None;
+                    }
+                    let (name, kind) = match spec.split_once(':') {
+                        Some((name, "uuid")) => (name, ParamKind::Uuid),
+                        Some((name, _)) => (name, ParamKind::Text),
+                        None => (spec, ParamKind::Text),
+                    };
+                    params.push(PathParam {
+                        name,
+                        kind,
+                        value: actual,
+                    });
+                } else if expected != actual {
+                    return None;
+                }
+            }
+            _ => return None,
         }
     }
+}
+
+fn validate_path_params(params: &[PathParam<'_>]) -> Result<(), ApiError> {
+    for param in params {
+        if param.kind == ParamKind::Uuid && Uuid::parse_str(param.value).is_err() {
+            return Err(ApiError::invalid_field(
+                param.name,
+                "invalid_uuid",
+                format!("{} must be a valid UUID", param.name),
+            ));
+        }
+    }
+
+    Ok(())
+}
 
+fn not_found() -> Result<Response<Body>, lambda_http::Error> {
     Response::builder()
         .status(404)
         .header("content-type", "application/json")
-        .body(Body::from(r#"{"error":"Not Found"}"#))
+        .body(Body::from(
+            r#"{"error":"Not Found","errorCode":"route_not_found","code":"ROUTE_NOT_FOUND"}"#,
+        ))
         .map_err(|e| lambda_http::Error::from(e.to_string()))
 }
 
-fn method_not_allowed() -> Result<Response<Body>, lambda_http::Error> {
+fn method_not_allowed(allowed: &[&str]) -> Result<Response<Body>, ApiError> {
     Response::builder()
         .status(405)
         .header("content-type", "application/json")
-        .body(Body::from(r#"{"error":"Method Not Allowed"}"#))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
+        .header("allow", allowed.join(", "))
+        .body(Body::from(
+            r#"{"error":"Method Not Allowed","errorCode":"method_not_allowed","code":"METHOD_NOT_ALLOWED"}"#,
+        ))
+        .map_err(|e| ApiError::internal(e.to_string()))
 }
 
-fn handle(
-    result: Result<Response<Body>, lambda_http::Error>,
-) -> Result<Response<Body>, lambda_http::Error> {
+fn handle(result: Result<Response<Body>, ApiError>) -> Response<Body> {
     match result {
-        Ok(response) => Ok(response),
+        Ok(response) => response,
         Err(error) => {
-            error!(error = %error, "Request handler returned error");
-            map_api_error_to_response(&error)
+            error!(
+                error = %error,
+                error_code = error.error_code(),
+                status = error.status().as_u16(),
+                "Request handler returned error"
+            );
+            error.into_response()
         }
     }
 }
 
-fn map_api_error_to_response(
-    error: &lambda_http::Error,
-) -> Result<Response<Body>, lambda_http::Error> {
-    let message = error.to_string();
-
-    if message.contains("Invalid JSON body")
-        || message.contains("must be a valid UUID")
-        || message.contains("Invalid status")
-        || message.contains("Invalid claim status")
-        || message.contains("Invalid claim transition")
-        || message.contains("Invalid visibility")
-        || message.contains("Invalid listing status")
-        || message.contains("Invalid limit")
-        || message.contains("Invalid offset")
-        || message.contains("Invalid pickupDisclosurePolicy")
-        || message.contains("Invalid contactPref")
-        || message.contains("quantityTotal")
-        || message.contains("quantity must be greater than 0")
-        || message.contains("quantityClaimed must be greater than 0")
-        || message.contains("availableStart")
-        || message.contains("availableEnd")
-        || message.contains("neededBy must be")
-        || message.contains("title is required")
-        || message.contains("unit is required")
-        || message.contains("does not reference an existing catalog crop")
-        || message.contains("must belong to the specified crop_id")
-        || message.contains("must belong to the specified cropId")
-        || message.contains("Request body is required")
-        || message.contains("units must be one of")
-        || message.contains("homeZone")
-        || message.contains("address is required")
-        || message.contains("pickupAddress is required because grower profile address is missing")
-        || message.contains("geoKey")
-        || message.contains("windowDays")
-        || message.contains("radiusMiles")
-        || message.contains("shareRadiusMiles")
-        || message.contains("searchRadiusMiles")
-        || message.contains("Gatherer profile location is required")
-        || message.contains("Listing is not claimable")
-        || message.contains("requestId must reference an open request")
-        || message.contains("requestId crop must match listing crop")
-    {
-        return crop::error_response(400, &message);
+#[cfg(test)]
+#[allow(clippy::unwrap_used, clippy::literal_string_with_formatting_args)]
+mod tests {
+    use super::{
+        handle, match_pattern, match_route, normalize_route_path, serve_openapi,
+        validate_path_params, ParamKind, RequiredRole, Route, RouteMatch, ALLOWED_API_KEY_SCOPES,
+        ROUTES,
+    };
+    use crate::auth::{AuthContext, UserType};
+    use crate::config::Config;
+    use crate::error::ApiError;
+    use lambda_http::{Body, Response};
+    use std::time::Duration;
+
+    fn body_json(response: &Response<Body>) -> serde_json::Value {
+        let body = match response.body() {
+            Body::Text(text) => text.as_str(),
+            Body::Binary(bytes) => std::str::from_utf8(bytes).unwrap(),
+            Body::Empty => "",
+        };
+        serde_json::from_str(body).unwrap()
     }
 
-    if message.contains("Insufficient quantity remaining") {
-        return crop::error_response(409, &message);
+    #[test]
+    fn normalize_route_path_strips_api_stage_prefix() {
+        assert_eq!(normalize_route_path("/api/crops"), "/crops");
+        assert_eq!(normalize_route_path("/api/catalog/crops"), "/catalog/crops");
     }
 
-    if message.contains("Request not found")
-        || message.contains("Claim not found")
-        || message.contains("Listing not found")
-    {
-        return crop::error_response(404, &message);
+    #[test]
+    fn normalize_route_path_leaves_non_stage_paths_unchanged() {
+        assert_eq!(normalize_route_path("/crops"), "/crops");
+        assert_eq!(normalize_route_path("/catalog/crops"), "/catalog/crops");
+        assert_eq!(normalize_route_path("/api"), "/");
     }
 
-    if message.contains("Geocoding service unavailable") {
-        return crop::error_response(503, &message);
+    fn route(method: &str, path: &str) -> &'static Route {
+        let RouteMatch::Found { route, .. } = match_route(method, path) else {
+            unreachable!("{method} {path} should match a route");
+        };
+        route
     }
 
-    if message.contains("is not configured") {
-        return crop::error_response(503, "Service not configured in this environment");
+    fn auth(user_type: Option<UserType>) -> AuthContext {
+        AuthContext {
+            user_id: "5df666d4-f6b1-4e6f-97d6-321e531ad7ca".to_string(),
+            user_type,
+            tier: "free".to_string(),
+            email: None,
+            is_admin: false,
+            is_suspended: false,
+            api_key: None,
+            impersonation: None,
+        }
     }
 
-    if message.contains("Address could not be geocoded") {
-        return crop::error_response(400, &message);
+    #[test]
+    fn api_key_scopes_are_declared_on_partner_routes() {
+        assert_eq!(
+            route("GET", "/feed/derived").api_key_scope,
+            Some("feed:read")
+        );
+        assert_eq!(
+            route("PUT", "/claims/5df666d4-f6b1-4e6f-97d6-321e531ad7ca").api_key_scope,
+            Some("claims:write")
+        );
+        assert_eq!(
+            route("GET", "/catalog/crops/abc/varieties").api_key_scope,
+            Some("catalog:read")
+        );
+        assert_eq!(route("PUT", "/me").api_key_scope, None);
+        assert_eq!(route("POST", "/admin/api-keys").api_key_scope, None);
     }
 
-    if message.contains("Missing userId in authorizer context") {
-        return crop::error_response(401, &message);
+    #[test]
+    fn every_route_scope_is_grantable() {
+        for route in ROUTES {
+            if let Some(scope) = route.api_key_scope {
+                assert!(
+                    ALLOWED_API_KEY_SCOPES.contains(&scope),
+                    "{} {} declares unknown scope {scope}",
+                    route.method,
+                    route.pattern
+                );
+            }
+        }
     }
 
-    if message.contains("user type not set")
-        || message.contains("onboarding may be incomplete")
-        || message.contains("Please complete onboarding")
-    {
-        return onboarding_incomplete_response();
+    #[test]
+    fn every_allowed_scope_opens_a_route() {
+        for scope in ALLOWED_API_KEY_SCOPES {
+            assert!(
+                ROUTES
+                    .iter()
+                    .any(|route| route.api_key_scope == Some(scope)),
+                "{scope} is grantable but no route accepts it"
+            );
+        }
     }
 
-    if message.contains("Forbidden:") {
-        return crop::error_response(403, &message);
+    #[test]
+    fn grower_routes_reject_gatherers() {
+        for (method, path) in [
+            ("GET", "/crops"),
+            ("POST", "/listings"),
+            ("GET", "/my/listings"),
+            ("GET", "/me/planting-recommendations"),
+        ] {
+            let role = route(method, path).role;
+            assert_eq!(role, RequiredRole::Grower, "{method} {path}");
+            assert!(role.check(&auth(Some(UserType::Grower))).is_ok());
+            let error = role.check(&auth(Some(UserType::Gatherer))).unwrap_err();
+            assert_eq!(error.error_code(), "grower_only");
+        }
     }
 
-    crop::error_response(500, &message)
-}
+    #[test]
+    fn gatherer_routes_reject_growers() {
+        for (method, path) in [("POST", "/requests"), ("POST", "/claims")] {
+            let role = route(method, path).role;
+            assert_eq!(role, RequiredRole::Gatherer, "{method} {path}");
+            let error = role.check(&auth(Some(UserType::Grower))).unwrap_err();
+            assert_eq!(error.error_code(), "user_type_required");
+        }
+    }
 
-#[derive(Serialize)]
-struct OnboardingIncompleteError {
-    error: String,
-    message: String,
-}
+    #[test]
+    fn participant_routes_require_onboarding() {
+        for (method, path) in [
+            ("GET", "/listings/along-route"),
+            ("GET", "/listings"),
+            ("GET", "/feed/derived"),
+            ("POST", "/feed/feedback"),
+            ("GET", "/claims"),
+            ("PUT", "/claims/5df666d4-f6b1-4e6f-97d6-321e531ad7ca"),
+        ] {
+            let role = route(method, path).role;
+            assert_eq!(role, RequiredRole::Participant, "{method} {path}");
+            assert!(role.check(&auth(Some(UserType::Grower))).is_ok());
+            assert!(role.check(&auth(Some(UserType::Gatherer))).is_ok());
+            let error = role.check(&auth(None)).unwrap_err();
+            assert_eq!(error.status().as_u16(), 403);
+        }
+    }
 
-fn onboarding_incomplete_response() -> Result<Response<Body>, lambda_http::Error> {
-    let payload = OnboardingIncompleteError {
-        error: "onboarding_incomplete".to_string(),
-        message:
-            "User type is not configured. Set userType via PUT /me before calling this endpoint."
-                .to_string(),
-    };
+    #[test]
+    fn guest_routes_require_onboarding_once_signed_in() {
+        let guest = ROUTES
+            .iter()
+            .filter(|route| route.role == RequiredRole::Guest)
+            .map(|route| (route.method, route.pattern))
+            .collect::<Vec<_>>();
+        assert_eq!(guest, vec![("GET", "/listings/discover")]);
+
+        let role = RequiredRole::Guest;
+        assert!(role.needs_user_type());
+        assert!(role.check(&auth(Some(UserType::Grower))).is_ok());
+        assert!(role.check(&auth(Some(UserType::Gatherer))).is_ok());
+        let error = role.check(&auth(None)).unwrap_err();
+        assert_eq!(error.status().as_u16(), 403);
+    }
 
-    let body = serde_json::to_string(&payload)
-        .map_err(|e| lambda_http::Error::from(format!("Failed to serialize response: {e}")))?;
+    #[test]
+    fn onboarding_routes_do_not_require_user_type() {
+        assert_eq!(route("GET", "/me").role, RequiredRole::Authenticated);
+        assert_eq!(route("PUT", "/me").role, RequiredRole::Authenticated);
+        assert!(RequiredRole::Authenticated.check(&auth(None)).is_ok());
+    }
 
-    Response::builder()
-        .status(403)
-        .header("content-type", "application/json")
-        .body(Body::from(body))
-        .map_err(|e| lambda_http::Error::from(e.to_string()))
-}
+    #[test]
+    fn match_route_prefers_literal_segments() {
+        let RouteMatch::Found { route, params } = match_route("GET", "/listings/discover") else {
+            unreachable!("discover route should match");
+        };
+        assert_eq!(route.pattern, "/listings/discover");
+        assert!(params.is_empty());
+    }
 
-#[cfg(test)]
-#[allow(clippy::unwrap_used)]
-mod tests {
-    use super::{map_api_error_to_response, normalize_route_path};
-    use lambda_http::Body;
+    #[test]
+    fn match_route_extracts_typed_params() {
+        let path = "/catalog/crops/5df666d4-f6b1-4e6f-97d6-321e531ad7ca/varieties";
+        let RouteMatch::Found { route, params } = match_route("GET", path) else {
+            unreachable!("varieties route should match");
+        };
+        assert_eq!(route.pattern, "/catalog/crops/{cropId:uuid}/varieties");
+        assert_eq!(params[0].name, "cropId");
+        assert_eq!(params[0].kind, ParamKind::Uuid);
+        assert_eq!(params[0].value, "5df666d4-f6b1-4e6f-97d6-321e531ad7ca");
+    }
 
     #[test]
-    fn normalize_route_path_strips_api_stage_prefix() {
-        assert_eq!(normalize_route_path("/api/crops"), "/crops");
-        assert_eq!(normalize_route_path("/api/catalog/crops"), "/catalog/crops");
+    fn match_route_reports_allowed_methods_for_known_path() {
+        let RouteMatch::MethodNotAllowed { allowed } = match_route("POST", "/crops/abc") else {
+            unreachable!("known path with wrong method should be 405");
+        };
+        assert_eq!(allowed, vec!["GET", "PUT", "DELETE"]);
     }
 
     #[test]
-    fn normalize_route_path_leaves_non_stage_paths_unchanged() {
-        assert_eq!(normalize_route_path("/crops"), "/crops");
-        assert_eq!(normalize_route_path("/catalog/crops"), "/catalog/crops");
-        assert_eq!(normalize_route_path("/api"), "/");
+    fn match_route_returns_not_found_for_unknown_path() {
+        assert!(matches!(match_route("GET", "/nope"), RouteMatch::NotFound));
+        assert!(matches!(
+            match_route("GET", "/crops/"),
+            RouteMatch::NotFound
+        ));
+        assert!(matches!(
+            match_route("GET", "/me/extra"),
+            RouteMatch::NotFound
+        ));
     }
 
     #[test]
-    fn map_api_error_maps_share_radius_miles_validation_to_400() {
-        let error = lambda_http::Error::from("shareRadiusMiles must be greater than 0".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
-        assert_eq!(response.status().as_u16(), 400);
+    fn validate_path_params_rejects_malformed_uuid() {
+        let params = match_pattern("/claims/{claimId:uuid}", "/claims/not-a-uuid").unwrap();
+        let error = validate_path_params(&params).unwrap_err();
+        assert_eq!(error.status().as_u16(), 400);
+        assert_eq!(error.error_code(), "invalid_uuid");
+        assert_eq!(error.to_string(), "claimId must be a valid UUID");
     }
 
     #[test]
-    fn map_api_error_maps_search_radius_miles_validation_to_400() {
-        let error =
-            lambda_http::Error::from("searchRadiusMiles must be greater than 0".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
-        assert_eq!(response.status().as_u16(), 400);
+    fn route_table_has_no_duplicate_routes() {
+        for (index, route) in ROUTES.iter().enumerate() {
+            assert!(
+                !ROUTES[index + 1..]
+                    .iter()
+                    .any(|other| other.method == route.method && other.pattern == route.pattern),
+                "duplicate route {} {}",
+                route.method,
+                route.pattern
+            );
+        }
+    }
+
+    #[test]
+    fn aggregation_routes_have_shorter_deadlines() {
+        for (method, path) in [("GET", "/analytics/premium/kpis"), ("GET", "/feed/derived")] {
+            let deadline = route(method, path).deadline.unwrap();
+            assert!(deadline < Config::for_tests().request_deadline);
+        }
+        assert!(route("GET", "/me").deadline.is_none());
+    }
+
+    #[test]
+    fn public_routes_are_limited_to_allowlist() {
+        let public = ROUTES
+            .iter()
+            .filter(|route| route.role == RequiredRole::Public)
+            .map(|route| (route.method, route.pattern))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            public,
+            vec![
+                ("GET", "/me/schedule.ics"),
+                ("GET", "/feeds/listings.atom"),
+                ("GET", "/stats/impact"),
+                ("GET", "/openapi.json"),
+                ("GET", "/health"),
+            ]
+        );
+        assert!(RequiredRole::Public.check(&auth(None)).is_ok());
+        assert!(!RequiredRole::Public.needs_user_type());
+    }
+
+    #[test]
+    fn openapi_document_covers_every_route() {
+        let response = serve_openapi().unwrap();
+        assert_eq!(response.status(), 200);
+        let document = body_json(&response);
+        for route in ROUTES {
+            let path = route
+                .pattern
+                .split('/')
+                .map(|segment| {
+                    segment
+                        .split_once(':')
+                        .map_or_else(|| segment.to_string(), |(name, _)| format!("{name}}}"))
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            assert!(
+                document["paths"][path.as_str()][route.method.to_ascii_lowercase()].is_object(),
+                "{} {} missing from OpenAPI document",
+                route.method,
+                route.pattern
+            );
+        }
+    }
+
+    #[test]
+    fn admin_routes_require_admin_role() {
+        for route in ROUTES {
+            assert_eq!(
+                route.pattern.starts_with("/admin/"),
+                route.role == RequiredRole::Admin,
+                "{} {}",
+                route.method,
+                route.pattern
+            );
+        }
     }
 
     #[test]
-    fn map_api_error_maps_request_needed_by_validation_to_400() {
-        let error =
-            lambda_http::Error::from("neededBy must be within the next 365 days".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
+    fn handle_maps_share_radius_miles_validation_to_400() {
+        let response = handle(Err(ApiError::invalid_field(
+            "shareRadiusMiles",
+            "must_be_positive",
+            "shareRadiusMiles must be greater than 0",
+        )));
         assert_eq!(response.status().as_u16(), 400);
+
+        let json = body_json(&response);
+        assert_eq!(json["errorCode"], "must_be_positive");
+        assert_eq!(json["field"], "shareRadiusMiles");
     }
 
     #[test]
-    fn map_api_error_maps_insufficient_quantity_to_409() {
-        let error = lambda_http::Error::from("Insufficient quantity remaining".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
-        assert_eq!(response.status().as_u16(), 409);
+    fn handle_maps_request_needed_by_validation_to_400() {
+        let response = handle(Err(ApiError::invalid_field(
+            "neededBy",
+            "out_of_range",
+            "neededBy must be within the next 365 days",
+        )));
+        assert_eq!(response.status().as_u16(), 400);
     }
 
     #[test]
-    fn map_api_error_maps_request_not_found_to_404() {
-        let error = lambda_http::Error::from("Request not found".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
-        assert_eq!(response.status().as_u16(), 404);
+    fn handle_maps_insufficient_quantity_to_409() {
+        let response = handle(Err(ApiError::conflict(
+            "insufficient_quantity",
+            "Insufficient quantity remaining",
+        )));
+        assert_eq!(response.status().as_u16(), 409);
+        assert_eq!(body_json(&response)["errorCode"], "insufficient_quantity");
+        assert_eq!(body_json(&response)["code"], "INSUFFICIENT_QUANTITY");
     }
 
     #[test]
-    fn map_api_error_maps_listing_not_found_to_404() {
-        let error = lambda_http::Error::from("Listing not found".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
+    fn handle_maps_not_found_to_404() {
+        let response = handle(Err(ApiError::not_found(
+            "listing_not_found",
+            "Listing not found",
+        )));
         assert_eq!(response.status().as_u16(), 404);
+        assert_eq!(body_json(&response)["error"], "Listing not found");
     }
 
     #[test]
-    fn map_api_error_maps_missing_user_type_to_403() {
-        let error =
-            lambda_http::Error::from("user type not set, onboarding may be incomplete".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
+    fn handle_maps_untyped_missing_user_type_to_403() {
+        let error = ApiError::from(lambda_http::Error::from(
+            "user type not set, onboarding may be incomplete".to_string(),
+        ));
+        let response = handle(Err(error));
         assert_eq!(response.status().as_u16(), 403);
     }
 
     #[test]
-    fn map_api_error_maps_not_configured_to_503() {
-        let error = lambda_http::Error::from("STRIPE_SECRET_KEY is not configured".to_string());
-        let response = map_api_error_to_response(&error).unwrap();
+    fn handle_maps_untyped_not_configured_to_503() {
+        let error = ApiError::from(lambda_http::Error::from(
+            "STRIPE_SECRET_KEY is not configured".to_string(),
+        ));
+        let response = handle(Err(error));
         assert_eq!(response.status().as_u16(), 503);
 
-        let body = match response.body() {
-            Body::Text(text) => text.as_str(),
-            _ => "",
-        };
-        assert!(
-            body.contains("Service not configured"),
+        let json = body_json(&response);
+        assert_eq!(
+            json["error"], "Service not configured in this environment",
             "503 body should use generic message, not leak env var names"
         );
+        assert_eq!(json["errorCode"], "not_configured");
     }
 
     #[test]
-    fn map_api_error_missing_user_type_returns_onboarding_code_and_message() {
-        let error = lambda_http::Error::from(
+    fn handle_missing_user_type_returns_onboarding_code_and_message() {
+        let error = ApiError::from(lambda_http::Error::from(
             "Forbidden: User type not set. Please complete onboarding.".to_string(),
-        );
-        let response = map_api_error_to_response(&error).unwrap();
+        ));
+        let response = handle(Err(error));
 
         assert_eq!(response.status().as_u16(), 403);
 
-        let body = match response.body() {
-            Body::Text(text) => text,
-            Body::Binary(bytes) => std::str::from_utf8(bytes).unwrap(),
-            Body::Empty => "",
-        };
-
-        let json: serde_json::Value = serde_json::from_str(body).unwrap();
+        let json = body_json(&response);
         assert_eq!(
             json.get("error").and_then(serde_json::Value::as_str),
             Some("onboarding_incomplete")
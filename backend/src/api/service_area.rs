@@ -0,0 +1,457 @@
+//! Organization service areas. An area is either a polygon or a set of
+//! geohash prefixes; both are stored as the geohash cells that cover them, so
+//! "is this listing in the area" is the same prefix match discovery uses.
+//! Polygons are treated as planar in latitude and longitude and may not
+//! cross the antimeridian.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+
+pub const MAX_POLYGON_VERTICES: usize = 100;
+pub const MAX_GEO_SET_KEYS: usize = 100;
+/// Shortest prefix a geo set may name; coarser cells span hundreds of km.
+pub const MIN_GEO_SET_PREFIX_LEN: usize = 3;
+
+/// Upper bound on cells per polygon. Large polygons fall back to coarser
+/// cells rather than failing.
+const MAX_COVERING_PREFIXES: usize = 250;
+/// Finest cells tried for a polygon, roughly 1.2 by 0.6 km.
+const FINEST_PRECISION: usize = 6;
+/// Grid cells checked at one precision before giving up on it.
+const MAX_CANDIDATE_CELLS: usize = 20_000;
+/// Precision of the anchor's geo key, matching stored profile locations.
+const ANCHOR_GEO_KEY_PRECISION: usize = 7;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Vertex {
+    pub lat: f64,
+    pub lng: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PolygonError {
+    TooFewVertices,
+    TooManyVertices,
+    OutOfRange,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GeoSetError {
+    Empty,
+    TooMany,
+    Invalid(String),
+    TooCoarse(String),
+}
+
+/// A representative point for an area: where requests filed for the
+/// organization are placed.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Anchor {
+    pub lat: f64,
+    pub lng: f64,
+    pub geo_key: String,
+}
+
+/// Checks vertex count and ranges. A closing vertex equal to the first is
+/// dropped, so rings may be given open or closed.
+pub fn validate_polygon(polygon: &[Vertex]) -> Result<Vec<Vertex>, PolygonError> {
+    let mut ring = polygon.to_vec();
+    if ring.len() > 1 && ring.first() == ring.last() {
+        ring.pop();
+    }
+    if ring.len() < 3 {
+        return Err(PolygonError::TooFewVertices);
+    }
+    if ring.len() > MAX_POLYGON_VERTICES {
+        return Err(PolygonError::TooManyVertices);
+    }
+    let in_range = |vertex: &Vertex| {
+        vertex.lat.is_finite()
+            && vertex.lng.is_finite()
+            && (-90.0..=90.0).contains(&vertex.lat)
+            && (-180.0..=180.0).contains(&vertex.lng)
+    };
+    if !ring.iter().all(in_range) {
+        return Err(PolygonError::OutOfRange);
+    }
+    Ok(ring)
+}
+
+/// Lowercases and validates a geo set, then drops keys already covered by a
+/// shorter key in the set. Sorted.
+pub fn normalize_geo_set(keys: &[String]) -> Result<Vec<String>, GeoSetError> {
+    if keys.is_empty() {
+        return Err(GeoSetError::Empty);
+    }
+    if keys.len() > MAX_GEO_SET_KEYS {
+        return Err(GeoSetError::TooMany);
+    }
+
+    let mut normalized = BTreeSet::new();
+    for key in keys {
+        let key = key.trim().to_ascii_lowercase();
+        if !crate::location::is_valid_geo_key(&key) {
+            return Err(GeoSetError::Invalid(key));
+        }
+        if key.len() < MIN_GEO_SET_PREFIX_LEN {
+            return Err(GeoSetError::TooCoarse(key));
+        }
+        normalized.insert(key);
+    }
+
+    let covered = |key: &String| {
+        normalized
+            .iter()
+            .any(|other| other.len() < key.len() && key.starts_with(other.as_str()))
+    };
+    Ok(normalized
+        .iter()
+        .filter(|key| !covered(key))
+        .cloned()
+        .collect())
+}
+
+/// Geohash prefixes whose cells together cover the polygon, sorted. Every
+/// cell touching the polygon is included, so the covering can reach slightly
+/// past its edges.
+pub fn polygon_covering_prefixes(polygon: &[Vertex]) -> Vec<String> {
+    let mut precision = FINEST_PRECISION;
+    loop {
+        let cells = cells_at(polygon, precision);
+        match cells {
+            Some(cells) if cells.len() <= MAX_COVERING_PREFIXES || precision == 1 => {
+                return cells.into_iter().collect();
+            }
+            None if precision == 1 => return Vec::new(),
+            _ => precision -= 1,
+        }
+    }
+}
+
+/// The mean of the polygon's vertices.
+#[allow(clippy::cast_precision_loss)]
+pub fn polygon_anchor(polygon: &[Vertex]) -> Option<Anchor> {
+    if polygon.is_empty() {
+        return None;
+    }
+    let count = polygon.len() as f64;
+    let lat = polygon.iter().map(|vertex| vertex.lat).sum::<f64>() / count;
+    let lng = polygon.iter().map(|vertex| vertex.lng).sum::<f64>() / count;
+    anchor_at(lat, lng)
+}
+
+/// The center of the set's first cell.
+pub fn geo_set_anchor(keys: &[String]) -> Option<Anchor> {
+    let (center, _, _) = geohash::decode(keys.first()?).ok()?;
+    anchor_at(center.y, center.x)
+}
+
+fn anchor_at(lat: f64, lng: f64) -> Option<Anchor> {
+    let geo_key =
+        geohash::encode(geohash::Coord { x: lng, y: lat }, ANCHOR_GEO_KEY_PRECISION).ok()?;
+    Some(Anchor { lat, lng, geo_key })
+}
+
+/// Cells at `precision` that touch the polygon, or `None` when its bounding
+/// box spans too many cells to check.
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+fn cells_at(polygon: &[Vertex], precision: usize) -> Option<BTreeSet<String>> {
+    if polygon.is_empty() {
+        return None;
+    }
+    let min_lat = polygon
+        .iter()
+        .map(|vertex| vertex.lat)
+        .fold(f64::INFINITY, f64::min);
+    let max_lat = polygon
+        .iter()
+        .map(|vertex| vertex.lat)
+        .fold(f64::NEG_INFINITY, f64::max);
+    let min_lng = polygon
+        .iter()
+        .map(|vertex| vertex.lng)
+        .fold(f64::INFINITY, f64::min);
+    let max_lng = polygon
+        .iter()
+        .map(|vertex| vertex.lng)
+        .fold(f64::NEG_INFINITY, f64::max);
+
+    let (height, width) = cell_size_degrees(precision);
+    // The geohash grid is aligned to (-90, -180), so walk it from the cell
+    // holding the south-west corner of the bounding box.
+    let first_row = ((min_lat + 90.0) / height).floor();
+    let first_col = ((min_lng + 180.0) / width).floor();
+    let rows = ((max_lat + 90.0) / height).floor() - first_row + 1.0;
+    let cols = ((max_lng + 180.0) / width).floor() - first_col + 1.0;
+    if rows * cols > MAX_CANDIDATE_CELLS as f64 {
+        return None;
+    }
+
+    let mut cells = BTreeSet::new();
+    for row in 0..rows as usize {
+        let south = (first_row + row as f64).mul_add(height, -90.0);
+        for col in 0..cols as usize {
+            let west = (first_col + col as f64).mul_add(width, -180.0);
+            let cell = Rect {
+                south,
+                west,
+                north: south + height,
+                east: west + width,
+            };
+            if !polygon_touches_rect(polygon, &cell) {
+                continue;
+            }
+            let center = geohash::Coord {
+                x: west + width / 2.0,
+                y: south + height / 2.0,
+            };
+            if let Ok(key) = geohash::encode(center, precision) {
+                cells.insert(key);
+            }
+        }
+    }
+    Some(cells)
+}
+
+/// Height and width in degrees of a geohash cell. Each character adds five
+/// bits, alternating longitude first.
+fn cell_size_degrees(precision: usize) -> (f64, f64) {
+    let bits = 5 * precision;
+    let lng_bits = (bits + 1) / 2;
+    let lat_bits = bits / 2;
+    (
+        180.0 / f64::from(1_u32 << lat_bits),
+        360.0 / f64::from(1_u32 << lng_bits),
+    )
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Rect {
+    south: f64,
+    west: f64,
+    north: f64,
+    east: f64,
+}
+
+impl Rect {
+    fn contains(&self, vertex: Vertex) -> bool {
+        (self.south..=self.north).contains(&vertex.lat)
+            && (self.west..=self.east).contains(&vertex.lng)
+    }
+
+    const fn corners(&self) -> [Vertex; 4] {
+        [
+            Vertex {
+                lat: self.south,
+                lng: self.west,
+            },
+            Vertex {
+                lat: self.south,
+                lng: self.east,
+            },
+            Vertex {
+                lat: self.north,
+                lng: self.east,
+            },
+            Vertex {
+                lat: self.north,
+                lng: self.west,
+            },
+        ]
+    }
+}
+
+/// True when the two overlap at all: one holds a point of the other, or
+/// their edges cross.
+fn polygon_touches_rect(polygon: &[Vertex], rect: &Rect) -> bool {
+    let corners = rect.corners();
+    if polygon.iter().any(|vertex| rect.contains(*vertex))
+        || corners
+            .iter()
+            .any(|corner| contains_point(polygon, *corner))
+    {
+        return true;
+    }
+    edges(polygon).any(|(a, b)| edges(&corners).any(|(c, d)| segments_intersect(a, b, c, d)))
+}
+
+fn edges(ring: &[Vertex]) -> impl Iterator<Item = (Vertex, Vertex)> + '_ {
+    ring.iter()
+        .zip(ring.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Even-odd ray casting.
+fn contains_point(polygon: &[Vertex], point: Vertex) -> bool {
+    let mut inside = false;
+    for (a, b) in edges(polygon) {
+        if (a.lat > point.lat) != (b.lat > point.lat) {
+            let crossing_lng =
+                ((b.lng - a.lng) / (b.lat - a.lat)).mul_add(point.lat - a.lat, a.lng);
+            if point.lng < crossing_lng {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+fn orientation(a: Vertex, b: Vertex, c: Vertex) -> f64 {
+    (b.lng - a.lng).mul_add(c.lat - a.lat, -((b.lat - a.lat) * (c.lng - a.lng)))
+}
+
+fn on_segment(a: Vertex, b: Vertex, point: Vertex) -> bool {
+    point.lng >= a.lng.min(b.lng)
+        && point.lng <= a.lng.max(b.lng)
+        && point.lat >= a.lat.min(b.lat)
+        && point.lat <= a.lat.max(b.lat)
+}
+
+fn segments_intersect(a: Vertex, b: Vertex, c: Vertex, d: Vertex) -> bool {
+    let abc = orientation(a, b, c);
+    let abd = orientation(a, b, d);
+    let cda = orientation(c, d, a);
+    let cdb = orientation(c, d, b);
+
+    if ((abc > 0.0 && abd < 0.0) || (abc < 0.0 && abd > 0.0))
+        && ((cda > 0.0 && cdb < 0.0) || (cda < 0.0 && cdb > 0.0))
+    {
+        return true;
+    }
+
+    (abc == 0.0 && on_segment(a, b, c))
+        || (abd == 0.0 && on_segment(a, b, d))
+        || (cda == 0.0 && on_segment(c, d, a))
+        || (cdb == 0.0 && on_segment(c, d, b))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    fn vertex(lat: f64, lng: f64) -> Vertex {
+        Vertex { lat, lng }
+    }
+
+    /// Roughly downtown Buffalo, NY.
+    fn square() -> Vec<Vertex> {
+        vec![
+            vertex(42.87, -78.89),
+            vertex(42.87, -78.86),
+            vertex(42.90, -78.86),
+            vertex(42.90, -78.89),
+        ]
+    }
+
+    #[test]
+    fn validate_polygon_accepts_closed_rings_and_checks_ranges() {
+        let mut closed = square();
+        closed.push(closed[0]);
+        assert_eq!(validate_polygon(&closed).unwrap(), square());
+
+        assert_eq!(
+            validate_polygon(&square()[..2]),
+            Err(PolygonError::TooFewVertices)
+        );
+        let mut out_of_range = square();
+        out_of_range[1].lat = 91.0;
+        assert_eq!(
+            validate_polygon(&out_of_range),
+            Err(PolygonError::OutOfRange)
+        );
+        let many = (0..=MAX_POLYGON_VERTICES)
+            .map(|i| vertex(f64::from(u32::try_from(i).unwrap()) / 1000.0, 0.0))
+            .collect::<Vec<_>>();
+        assert_eq!(validate_polygon(&many), Err(PolygonError::TooManyVertices));
+    }
+
+    #[test]
+    fn normalize_geo_set_dedupes_and_drops_covered_keys() {
+        let keys = ["DR5R", "dr5ru", "dr5x", "dr5r"].map(String::from);
+        assert_eq!(normalize_geo_set(&keys).unwrap(), vec!["dr5r", "dr5x"]);
+
+        assert_eq!(normalize_geo_set(&[]), Err(GeoSetError::Empty));
+        assert_eq!(
+            normalize_geo_set(&["dr".to_string()]),
+            Err(GeoSetError::TooCoarse("dr".to_string()))
+        );
+        assert_eq!(
+            normalize_geo_set(&["dra!".to_string()]),
+            Err(GeoSetError::Invalid("dra!".to_string()))
+        );
+    }
+
+    #[test]
+    fn polygon_covering_includes_every_point_inside() {
+        let polygon = square();
+        let prefixes = polygon_covering_prefixes(&polygon);
+        assert!(!prefixes.is_empty());
+        assert!(prefixes.len() <= MAX_COVERING_PREFIXES);
+
+        for (lat, lng) in [(42.88, -78.88), (42.8701, -78.8899), (42.8999, -78.8601)] {
+            let key = geohash::encode(geohash::Coord { x: lng, y: lat }, 7).unwrap();
+            assert!(
+                prefixes
+                    .iter()
+                    .any(|prefix| key.starts_with(prefix.as_str())),
+                "{key}"
+            );
+        }
+
+        let far = geohash::encode(
+            geohash::Coord {
+                x: -78.70,
+                y: 42.95,
+            },
+            7,
+        )
+        .unwrap();
+        assert!(!prefixes
+            .iter()
+            .any(|prefix| far.starts_with(prefix.as_str())));
+    }
+
+    #[test]
+    fn large_polygons_fall_back_to_coarser_cells() {
+        let state = vec![
+            vertex(40.5, -79.8),
+            vertex(40.5, -73.3),
+            vertex(45.0, -73.3),
+            vertex(45.0, -79.8),
+        ];
+        let prefixes = polygon_covering_prefixes(&state);
+        assert!(!prefixes.is_empty());
+        assert!(prefixes.len() <= MAX_COVERING_PREFIXES);
+        assert!(prefixes
+            .iter()
+            .all(|prefix| prefix.len() < FINEST_PRECISION));
+    }
+
+    #[test]
+    fn contains_point_handles_concave_rings() {
+        let notch = vec![
+            vertex(0.0, 0.0),
+            vertex(0.0, 4.0),
+            vertex(4.0, 4.0),
+            vertex(2.0, 2.0),
+            vertex(4.0, 0.0),
+        ];
+        assert!(contains_point(&notch, vertex(1.0, 1.0)));
+        assert!(!contains_point(&notch, vertex(3.5, 2.0)));
+    }
+
+    #[test]
+    fn anchors_sit_inside_their_area() {
+        let anchor = polygon_anchor(&square()).unwrap();
+        assert!((anchor.lat - 42.885).abs() < 1e-9);
+        assert!(anchor.geo_key.starts_with("dr"));
+
+        let anchor = geo_set_anchor(&["dr5r".to_string()]).unwrap();
+        assert!(anchor.geo_key.starts_with("dr5r"));
+    }
+}
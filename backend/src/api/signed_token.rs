@@ -0,0 +1,56 @@
+//! HMAC signatures for links that must work without a bearer token, such as
+//! calendar and RSS feed subscriptions. Each caller passes a distinct
+//! `purpose` so a signature minted for one feed cannot open another.
+
+use crate::config::Config;
+use crate::error::ApiError;
+use hmac::Mac;
+use sha2::Sha256;
+
+type HmacSha256 = hmac::Hmac<Sha256>;
+
+/// Loads the signing key shared by all feed links.
+pub fn feed_secret(config: &Config) -> Result<String, ApiError> {
+    config
+        .feed_signing_secret
+        .as_ref()
+        .map(|secret| secret.expose().to_string())
+        .ok_or_else(|| {
+            ApiError::unavailable("not_configured", "FEED_SIGNING_SECRET is not configured")
+        })
+}
+
+/// Hex-encoded HMAC-SHA256 of `purpose:payload`.
+pub fn sign(secret: &str, purpose: &str, payload: &str) -> String {
+    hex::encode(mac(secret, purpose, payload).finalize().into_bytes())
+}
+
+/// Constant-time check of a hex signature produced by [`sign`].
+pub fn verify(secret: &str, purpose: &str, payload: &str, signature: &str) -> bool {
+    hex::decode(signature)
+        .is_ok_and(|bytes| mac(secret, purpose, payload).verify_slice(&bytes).is_ok())
+}
+
+fn mac(secret: &str, purpose: &str, payload: &str) -> HmacSha256 {
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes())
+        .unwrap_or_else(|_| unreachable!("HMAC accepts any key length"));
+    mac.update(purpose.as_bytes());
+    mac.update(b":");
+    mac.update(payload.as_bytes());
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signatures_are_bound_to_secret_purpose_and_payload() {
+        let signature = sign("secret", "schedule-feed", "user:1");
+        assert!(verify("secret", "schedule-feed", "user:1", &signature));
+        assert!(!verify("other", "schedule-feed", "user:1", &signature));
+        assert!(!verify("secret", "listings-feed", "user:1", &signature));
+        assert!(!verify("secret", "schedule-feed", "user:2", &signature));
+        assert!(!verify("secret", "schedule-feed", "user:1", "zz"));
+    }
+}
@@ -0,0 +1,41 @@
+//! Spans around the calls a request makes out of the function.
+//!
+//! Each span nests under the router's `request` span, which carries the
+//! correlation id and X-Ray trace id. `main` enables span close events, so
+//! every span logs its busy and idle time when it ends and the JSON record
+//! lists the parent spans' fields, which is enough to see where a slow
+//! request spent its time. Database spans are at debug level and only appear
+//! when `RUST_LOG` asks for them.
+
+use tracing::Span;
+
+/// Span for one call to an external dependency. `service` names the
+/// dependency (`eventbridge`, `geocoder`, ...) and `operation` the call made.
+pub fn client_span(service: &'static str, operation: &str) -> Span {
+    tracing::info_span!(
+        "client",
+        otel.kind = "client",
+        peer.service = service,
+        operation = operation
+    )
+}
+
+/// Span for one database statement, labelled the same way as the query log.
+pub fn db_span(label: &'static str, statement: &str) -> Span {
+    tracing::debug_span!(
+        "db",
+        otel.kind = "client",
+        db.system = "postgresql",
+        db.operation = label,
+        db.statement = statement
+    )
+}
+
+/// X-Ray trace header of the current invocation. The Lambda runtime sets
+/// `_X_AMZN_TRACE_ID` per invocation, so this is not configuration and is
+/// read on each call.
+pub fn xray_trace_header() -> Option<String> {
+    std::env::var("_X_AMZN_TRACE_ID")
+        .ok()
+        .filter(|header| !header.is_empty())
+}
@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
+use utoipa::ToSchema;
 
 pub const TIP_SCHEMA_VERSION_V1: &str = "tips.v1";
 
@@ -9,7 +10,7 @@ fn default_tip_schema_version() -> String {
     TIP_SCHEMA_VERSION_V1.to_string()
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum ExperienceLevel {
     Beginner,
@@ -17,7 +18,7 @@ pub enum ExperienceLevel {
     Advanced,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "snake_case")]
 pub enum TipCategory {
     Watering,
@@ -28,7 +29,7 @@ pub enum TipCategory {
     Harvest,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Default, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct ExperienceSignals {
     pub completed_grows: u32,
@@ -48,7 +49,7 @@ pub struct TipTargeting {
     pub zone_tags: Vec<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 pub struct GardeningTip {
     #[serde(default = "default_tip_schema_version")]
@@ -0,0 +1,181 @@
+//! Units catalog for listing and request quantities. Units stay free text on
+//! both; the catalog only recognizes common ones and what they measure, so a
+//! claim linking a request to a listing can tell `lb` and `kg` apart from
+//! `dozen` and `lb`, and convert between the first pair.
+
+use rust_decimal::Decimal;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Gram,
+    Kilogram,
+    Ounce,
+    Pound,
+    Milliliter,
+    Liter,
+    Cup,
+    Pint,
+    Quart,
+    Gallon,
+    Each,
+    Dozen,
+    Bunch,
+    Bag,
+    Box,
+    Bushel,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Dimension {
+    Mass,
+    Volume,
+    Count,
+}
+
+/// How a listing's unit relates to a request's.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnitMatch {
+    /// One side has no unit, so there is nothing to compare.
+    Unspecified,
+    Same,
+    /// Both measure the same thing; a quantity in the first unit times this
+    /// factor is the quantity in the second.
+    Convertible(Decimal),
+    /// Catalog units measuring different things, such as `dozen` and `lb`,
+    /// or containers such as `bag` that only match themselves.
+    Incompatible,
+    /// Different units at least one of which the catalog does not know.
+    Unknown,
+}
+
+impl Unit {
+    /// Recognizes catalog units by their usual spellings, ignoring case,
+    /// surrounding whitespace and a trailing period.
+    pub fn parse(value: &str) -> Option<Self> {
+        let normalized = value.trim().trim_end_matches('.').to_ascii_lowercase();
+        let unit = match normalized.as_str() {
+            "g" | "gram" | "grams" => Self::Gram,
+            "kg" | "kgs" | "kilo" | "kilos" | "kilogram" | "kilograms" => Self::Kilogram,
+            "oz" | "ounce" | "ounces" => Self::Ounce,
+            "lb" | "lbs" | "pound" | "pounds" => Self::Pound,
+            "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => Self::Milliliter,
+            "l" | "liter" | "liters" | "litre" | "litres" => Self::Liter,
+            "cup" | "cups" => Self::Cup,
+            "pt" | "pint" | "pints" => Self::Pint,
+            "qt" | "quart" | "quarts" => Self::Quart,
+            "gal" | "gallon" | "gallons" => Self::Gallon,
+            "each" | "ea" | "item" | "items" | "piece" | "pieces" | "pc" | "pcs" => Self::Each,
+            "dozen" | "doz" | "dz" => Self::Dozen,
+            "bunch" | "bunches" => Self::Bunch,
+            "bag" | "bags" => Self::Bag,
+            "box" | "boxes" => Self::Box,
+            "bushel" | "bushels" | "bu" => Self::Bushel,
+            _ => return None,
+        };
+        Some(unit)
+    }
+
+    /// What the unit measures and its size in grams, milliliters or items.
+    /// Containers vary too much in what they hold to convert.
+    fn measure(self) -> Option<(Dimension, Decimal)> {
+        let measure = match self {
+            Self::Gram => (Dimension::Mass, Decimal::ONE),
+            Self::Kilogram => (Dimension::Mass, Decimal::ONE_THOUSAND),
+            Self::Ounce => (Dimension::Mass, Decimal::new(28_349_523_125, 9)),
+            Self::Pound => (Dimension::Mass, Decimal::new(45_359_237, 5)),
+            Self::Milliliter => (Dimension::Volume, Decimal::ONE),
+            Self::Liter => (Dimension::Volume, Decimal::ONE_THOUSAND),
+            Self::Cup => (Dimension::Volume, Decimal::new(2_365_882_365, 7)),
+            Self::Pint => (Dimension::Volume, Decimal::new(473_176_473, 6)),
+            Self::Quart => (Dimension::Volume, Decimal::new(946_352_946, 6)),
+            Self::Gallon => (Dimension::Volume, Decimal::new(3_785_411_784, 6)),
+            Self::Each => (Dimension::Count, Decimal::ONE),
+            Self::Dozen => (Dimension::Count, Decimal::new(12, 0)),
+            Self::Bunch | Self::Bag | Self::Box | Self::Bushel => return None,
+        };
+        Some(measure)
+    }
+}
+
+/// Compares the unit a quantity is given in with the unit it is wanted in.
+pub fn compare(from: Option<&str>, to: Option<&str>) -> UnitMatch {
+    let (Some(from), Some(to)) = (specified(from), specified(to)) else {
+        return UnitMatch::Unspecified;
+    };
+    if from.eq_ignore_ascii_case(to) {
+        return UnitMatch::Same;
+    }
+
+    let (Some(from), Some(to)) = (Unit::parse(from), Unit::parse(to)) else {
+        return UnitMatch::Unknown;
+    };
+    if from == to {
+        return UnitMatch::Same;
+    }
+    match (from.measure(), to.measure()) {
+        (Some((from_dimension, from_size)), Some((to_dimension, to_size)))
+            if from_dimension == to_dimension =>
+        {
+            UnitMatch::Convertible(from_size / to_size)
+        }
+        _ => UnitMatch::Incompatible,
+    }
+}
+
+/// Applies a [`UnitMatch::Convertible`] factor, rounded to the three decimal
+/// places quantity columns hold.
+pub fn convert(quantity: Decimal, factor: Decimal) -> Decimal {
+    (quantity * factor).round_dp(3).normalize()
+}
+
+/// Listings and requests may leave the unit empty or say `unspecified`.
+fn specified(unit: Option<&str>) -> Option<&str> {
+    unit.map(str::trim)
+        .filter(|unit| !unit.is_empty() && !unit.eq_ignore_ascii_case("unspecified"))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn dec(value: &str) -> Decimal {
+        Decimal::from_str(value).unwrap()
+    }
+
+    #[test]
+    fn parse_accepts_common_spellings() {
+        assert_eq!(Unit::parse(" LBS "), Some(Unit::Pound));
+        assert_eq!(Unit::parse("oz."), Some(Unit::Ounce));
+        assert_eq!(Unit::parse("item"), Some(Unit::Each));
+        assert_eq!(Unit::parse("flat"), None);
+    }
+
+    #[test]
+    fn compare_converts_within_a_dimension() {
+        let UnitMatch::Convertible(factor) = compare(Some("kg"), Some("lb")) else {
+            panic!("kg and lb should convert");
+        };
+        assert_eq!(convert(dec("2"), factor), dec("4.409"));
+
+        let UnitMatch::Convertible(factor) = compare(Some("each"), Some("dozen")) else {
+            panic!("each and dozen should convert");
+        };
+        assert_eq!(convert(dec("18"), factor), dec("1.5"));
+    }
+
+    #[test]
+    fn compare_flags_mismatches() {
+        assert_eq!(compare(Some("dozen"), Some("lb")), UnitMatch::Incompatible);
+        assert_eq!(compare(Some("bag"), Some("box")), UnitMatch::Incompatible);
+        assert_eq!(compare(Some("flat"), Some("lb")), UnitMatch::Unknown);
+        assert_eq!(compare(Some("Lb"), Some("pounds")), UnitMatch::Same);
+        assert_eq!(compare(Some("crate"), Some("CRATE")), UnitMatch::Same);
+        assert_eq!(compare(None, Some("lb")), UnitMatch::Unspecified);
+        assert_eq!(
+            compare(Some("unspecified"), Some("lb")),
+            UnitMatch::Unspecified
+        );
+    }
+}
@@ -2,14 +2,33 @@ use aws_lambda_events::event::apigw::ApiGatewayCustomAuthorizerRequestTypeReques
 use aws_sdk_cognitoidentityprovider::Client as CognitoClient;
 use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
 use lambda_runtime::{run, service_fn, Error, LambdaEvent};
-use rustls::{ClientConfig, RootCertStore};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
-use std::str::FromStr;
-use tokio_postgres::config::{ChannelBinding, Config};
-use tokio_postgres_rustls::MakeRustlsConnect;
+use tokio_postgres::Client;
 use tracing::{error, warn};
 use uuid::Uuid;
+
+#[path = "../api/pg_tls.rs"]
+mod pg_tls;
+
+use pg_tls::ConnectSettings;
+
+const ADMIN_GROUP: &str = "admin";
+
+/// Routes callable without credentials; must match the API's `Public` and
+/// `Guest` routes. Requests that do send credentials are still authenticated.
+/// The calendar and listings feeds check their own signed tokens because
+/// calendar apps and feed readers cannot send headers.
+const PUBLIC_ROUTES: &[(&str, &str)] = &[
+    ("GET", "/stats/impact"),
+    ("GET", "/openapi.json"),
+    ("GET", "/me/schedule.ics"),
+    ("GET", "/feeds/listings.atom"),
+    ("GET", "/health"),
+    ("GET", "/listings/discover"),
+];
+
 #[derive(Clone)]
 struct AppState {
     cognito: CognitoClient,
@@ -108,7 +127,21 @@ async fn handle_authorization(
     event: &ApiGatewayCustomAuthorizerRequestTypeRequest,
     state: &AppState,
 ) -> Result<PolicyResponse, Error> {
-    let auth_header = get_authorization_header(event).ok_or("No Authorization header provided")?;
+    let Some(auth_header) = get_authorization_header(event) else {
+        if let Some(api_key) = get_api_key_header(event) {
+            return handle_api_key_auth(&api_key, event, state).await;
+        }
+        if is_public_route(
+            event.http_method.as_ref().map(reqwest::Method::as_str),
+            event.path.as_deref(),
+        ) {
+            // Scope the policy to this exact method and resource rather than
+            // the whole API.
+            let method_arn = event.method_arn.as_deref().unwrap_or_default();
+            return Ok(generate_policy("anonymous", "Allow", method_arn, None));
+        }
+        return Err("No Authorization header provided".into());
+    };
 
     if !auth_header.starts_with("Bearer ") {
         return Err("Invalid authorization header format".into());
@@ -129,6 +162,96 @@ fn get_authorization_header(
         .map(ToString::to_string)
 }
 
+fn get_api_key_header(event: &ApiGatewayCustomAuthorizerRequestTypeRequest) -> Option<String> {
+    event
+        .headers
+        .get("x-api-key")
+        .or_else(|| event.headers.get("X-Api-Key"))
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+fn get_impersonation_header(
+    event: &ApiGatewayCustomAuthorizerRequestTypeRequest,
+) -> Option<String> {
+    event
+        .headers
+        .get("x-impersonation-token")
+        .or_else(|| event.headers.get("X-Impersonation-Token"))
+        .and_then(|value| value.to_str().ok())
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(ToString::to_string)
+}
+
+/// Impersonation tokens are stored the same way as API keys.
+fn hash_api_key(api_key: &str) -> String {
+    hex::encode(Sha256::digest(api_key.as_bytes()))
+}
+
+async fn handle_api_key_auth(
+    api_key: &str,
+    event: &ApiGatewayCustomAuthorizerRequestTypeRequest,
+    state: &AppState,
+) -> Result<PolicyResponse, Error> {
+    let client = connect_db(&state.database_url)
+        .await
+        .ok_or("Database unavailable for API key lookup")?;
+
+    let row = client
+        .query_opt(
+            "
+            select k.id, k.organization_id, k.scopes, o.service_user_id, u.user_type,
+                   u.suspended_at is not null as suspended
+              from api_keys k
+              join organizations o on o.id = k.organization_id and o.deleted_at is null
+              join users u on u.id = o.service_user_id and u.deleted_at is null
+             where k.key_hash = $1
+               and k.revoked_at is null
+               and (k.expires_at is null or k.expires_at > now())
+            ",
+            &[&hash_api_key(api_key)],
+        )
+        .await?
+        .ok_or("Unknown, expired, or revoked API key")?;
+
+    let key_id: Uuid = row.get("id");
+    let organization_id: Uuid = row.get("organization_id");
+    let service_user_id: Uuid = row.get("service_user_id");
+    let scopes: Vec<String> = row.get("scopes");
+    let suspended: bool = row.get("suspended");
+    let user_type = row
+        .get::<_, Option<String>>("user_type")
+        .and_then(|raw| normalize_user_type(&raw));
+
+    if let Err(err) = client
+        .execute(
+            "update api_keys set last_used_at = now() where id = $1",
+            &[&key_id],
+        )
+        .await
+    {
+        warn!(error = %err, api_key_id = %key_id, "Failed to record API key usage");
+    }
+
+    let principal_id = service_user_id.to_string();
+    let api_arn = get_api_arn_pattern(event.method_arn.as_deref().unwrap_or_default());
+    let context = build_context([
+        ("userId", Some(principal_id.clone())),
+        ("userType", user_type),
+        ("tier", Some("neighbor".to_string())),
+        ("principalType", Some("api_key".to_string())),
+        ("apiKeyId", Some(key_id.to_string())),
+        ("organizationId", Some(organization_id.to_string())),
+        ("scopes", Some(scopes.join(","))),
+        ("suspended", Some(suspended.to_string())),
+    ]);
+
+    Ok(generate_policy(&principal_id, "Allow", &api_arn, context))
+}
+
 async fn handle_jwt_auth(
     token: &str,
     event: &ApiGatewayCustomAuthorizerRequestTypeRequest,
@@ -146,17 +269,91 @@ async fn handle_jwt_auth(
     let principal_uuid = Uuid::parse_str(&principal_id).map_err(|_| "Invalid sub claim format")?;
     let principal_id = principal_uuid.to_string();
 
-    let tier = get_user_tier(&state.cognito, &state.user_pool_id, &principal_id).await;
-    let user_type = get_user_type_from_db(&state.database_url, &principal_uuid).await;
+    let groups = get_user_groups(&state.cognito, &state.user_pool_id, &principal_id).await;
+    let tier = Some(tier_from_groups(&groups));
+    let is_admin = groups.iter().any(|group| group == ADMIN_GROUP);
+
+    if let Some(impersonation_token) = get_impersonation_header(event) {
+        if !is_admin {
+            return Err("Impersonation token sent by a non-admin principal".into());
+        }
+        return handle_impersonation_auth(&impersonation_token, &principal_uuid, event, state)
+            .await;
+    }
+
+    let user_state = get_user_state_from_db(&state.database_url, &principal_uuid).await;
 
     let api_arn = get_api_arn_pattern(event.method_arn.as_deref().unwrap_or_default());
     let context = build_context([
         ("userId", Some(principal_id.clone())),
-        ("userType", user_type),
+        ("userType", user_state.user_type),
         ("email", user_info.get("email").cloned()),
         ("firstName", user_info.get("given_name").cloned()),
         ("lastName", user_info.get("family_name").cloned()),
         ("tier", tier),
+        ("principalType", Some("user".to_string())),
+        ("isAdmin", Some(is_admin.to_string())),
+        ("suspended", Some(user_state.suspended.to_string())),
+    ]);
+
+    Ok(generate_policy(&principal_id, "Allow", &api_arn, context))
+}
+
+/// Resolves an admin's support session to the impersonated user's context.
+/// The admin flag is dropped so the request sees exactly what the user sees;
+/// the API enforces read-only access and audits each request.
+async fn handle_impersonation_auth(
+    token: &str,
+    admin_id: &Uuid,
+    event: &ApiGatewayCustomAuthorizerRequestTypeRequest,
+    state: &AppState,
+) -> Result<PolicyResponse, Error> {
+    let client = connect_db(&state.database_url)
+        .await
+        .ok_or("Database unavailable for impersonation lookup")?;
+
+    let row = client
+        .query_opt(
+            "
+            select s.id, s.target_user_id, u.user_type,
+                   u.suspended_at is not null as suspended
+              from impersonation_sessions s
+              join users u on u.id = s.target_user_id and u.deleted_at is null
+             where s.token_hash = $1
+               and s.admin_user_id = $2
+               and s.ended_at is null
+               and s.expires_at > now()
+            ",
+            &[&hash_api_key(token), admin_id],
+        )
+        .await?
+        .ok_or("Unknown, expired, or ended impersonation session")?;
+
+    let session_id: Uuid = row.get("id");
+    let target_user_id: Uuid = row.get("target_user_id");
+    let suspended: bool = row.get("suspended");
+    let user_type = row
+        .get::<_, Option<String>>("user_type")
+        .and_then(|raw| normalize_user_type(&raw));
+
+    let target_groups = get_user_groups(
+        &state.cognito,
+        &state.user_pool_id,
+        &target_user_id.to_string(),
+    )
+    .await;
+
+    let principal_id = target_user_id.to_string();
+    let api_arn = get_api_arn_pattern(event.method_arn.as_deref().unwrap_or_default());
+    let context = build_context([
+        ("userId", Some(principal_id.clone())),
+        ("userType", user_type),
+        ("tier", Some(tier_from_groups(&target_groups))),
+        ("principalType", Some("impersonation".to_string())),
+        ("isAdmin", Some("false".to_string())),
+        ("suspended", Some(suspended.to_string())),
+        ("impersonatorId", Some(admin_id.to_string())),
+        ("impersonationSessionId", Some(session_id.to_string())),
     ]);
 
     Ok(generate_policy(&principal_id, "Allow", &api_arn, context))
@@ -179,11 +376,11 @@ async fn get_user_attributes(
     }
 }
 
-async fn get_user_tier(
+async fn get_user_groups(
     client: &CognitoClient,
     user_pool_id: &str,
     username: &str,
-) -> Option<String> {
+) -> Vec<String> {
     match client
         .admin_list_groups_for_user()
         .user_pool_id(user_pool_id)
@@ -191,75 +388,54 @@ async fn get_user_tier(
         .send()
         .await
     {
-        Ok(response) => {
-            let groups = response.groups();
-            // Map tier groups to tier values
-            // Groups are defined in SAM template: neighbor-tier, supporter-tier, caretaker-tier
-            if groups
-                .iter()
-                .any(|g| g.group_name() == Some("caretaker-tier"))
-            {
-                Some("caretaker".to_string())
-            } else if groups
-                .iter()
-                .any(|g| g.group_name() == Some("supporter-tier"))
-            {
-                Some("supporter".to_string())
-            } else {
-                // Default to neighbor for neighbor-tier or no tier group
-                Some("neighbor".to_string())
-            }
-        }
+        Ok(response) => response
+            .groups()
+            .iter()
+            .filter_map(|group| group.group_name().map(ToString::to_string))
+            .collect(),
         Err(err) => {
             error!(error = %err, "Error fetching user groups");
-            // Default to neighbor on error
-            Some("neighbor".to_string())
+            Vec::new()
         }
     }
 }
-async fn get_user_type_from_db(database_url: &str, user_id: &Uuid) -> Option<String> {
-    let mut config = match Config::from_str(database_url) {
-        Ok(config) => config,
+
+// Groups are defined in SAM template: neighbor-tier, supporter-tier, caretaker-tier.
+// Anything else (including no groups or a lookup failure) defaults to neighbor.
+fn tier_from_groups(groups: &[String]) -> String {
+    if groups.iter().any(|g| g == "caretaker-tier") {
+        "caretaker".to_string()
+    } else if groups.iter().any(|g| g == "supporter-tier") {
+        "supporter".to_string()
+    } else {
+        "neighbor".to_string()
+    }
+}
+
+async fn connect_db(database_url: &str) -> Option<Client> {
+    let settings = match ConnectSettings::from_database_url(database_url) {
+        Ok(settings) => settings,
         Err(err) => {
             error!(error = %err, "Invalid DATABASE_URL in authorizer");
             return None;
         }
     };
 
-    if matches!(config.get_channel_binding(), ChannelBinding::Require) {
-        warn!(
-            "DATABASE_URL requested channel_binding=require; downgrading to prefer in authorizer"
-        );
-        config.channel_binding(ChannelBinding::Prefer);
-    }
-
-    let cert_result = rustls_native_certs::load_native_certs();
-    if !cert_result.errors.is_empty() {
-        error!(
-            error_count = cert_result.errors.len(),
-            "Errors occurred while loading native root certificates for userType lookup"
-        );
-    }
-
-    let mut root_store = RootCertStore::empty();
-    let (added, _) = root_store.add_parsable_certificates(cert_result.certs);
-    if added == 0 {
-        error!("No native root certificates available for userType lookup");
-        return None;
-    }
-
-    let tls_config = ClientConfig::builder()
-        .with_root_certificates(root_store)
-        .with_no_client_auth();
-    let tls = MakeRustlsConnect::new(tls_config);
+    let tls = match settings.tls_connector() {
+        Ok(tls) => tls,
+        Err(err) => {
+            error!(error = %err, "Unable to configure Postgres TLS for authorizer lookup");
+            return None;
+        }
+    };
 
-    let (client, connection) = match config.connect(tls).await {
+    let (client, connection) = match settings.config.connect(tls).await {
         Ok(parts) => parts,
         Err(err) => {
             error!(
                 error = %err,
                 error_debug = ?err,
-                "Failed to connect to database for userType lookup"
+                "Failed to connect to database for authorizer lookup"
             );
             return None;
         }
@@ -271,20 +447,41 @@ async fn get_user_type_from_db(database_url: &str, user_id: &Uuid) -> Option<Str
         }
     });
 
+    Some(client)
+}
+
+#[derive(Debug, Default)]
+struct UserState {
+    user_type: Option<String>,
+    suspended: bool,
+}
+
+async fn get_user_state_from_db(database_url: &str, user_id: &Uuid) -> UserState {
+    let Some(client) = connect_db(database_url).await else {
+        return UserState::default();
+    };
+
     match client
         .query_opt(
-            "select user_type from users where id = $1 and deleted_at is null",
+            "
+            select user_type, suspended_at is not null as suspended
+              from users
+             where id = $1 and deleted_at is null
+            ",
             &[user_id],
         )
         .await
     {
-        Ok(Some(row)) => row
-            .get::<_, Option<String>>("user_type")
-            .and_then(|raw| normalize_user_type(raw.as_str())),
-        Ok(None) => None,
+        Ok(Some(row)) => UserState {
+            user_type: row
+                .get::<_, Option<String>>("user_type")
+                .and_then(|raw| normalize_user_type(raw.as_str())),
+            suspended: row.get("suspended"),
+        },
+        Ok(None) => UserState::default(),
         Err(err) => {
-            error!(error = %err, user_id = %user_id, "Failed to query userType from database");
-            None
+            error!(error = %err, user_id = %user_id, "Failed to query user state from database");
+            UserState::default()
         }
     }
 }
@@ -402,6 +599,19 @@ fn generate_policy(
     }
 }
 
+fn is_public_route(method: Option<&str>, path: Option<&str>) -> bool {
+    let (Some(method), Some(path)) = (method, path) else {
+        return false;
+    };
+    let path = path
+        .strip_prefix("/api")
+        .filter(|normalized| normalized.starts_with('/'))
+        .unwrap_or(path);
+    PUBLIC_ROUTES
+        .iter()
+        .any(|(public_method, public_path)| *public_method == method && *public_path == path)
+}
+
 fn get_api_arn_pattern(method_arn: &str) -> String {
     let mut parts = method_arn.split('/');
     let first = parts.next();
@@ -431,6 +641,26 @@ mod tests {
         assert_eq!(get_api_arn_pattern(arn), arn);
     }
 
+    #[test]
+    fn public_routes_match_method_and_path_exactly() {
+        assert!(is_public_route(Some("GET"), Some("/stats/impact")));
+        assert!(is_public_route(Some("GET"), Some("/api/stats/impact")));
+        assert!(is_public_route(Some("GET"), Some("/openapi.json")));
+        assert!(is_public_route(Some("GET"), Some("/me/schedule.ics")));
+        assert!(is_public_route(Some("GET"), Some("/feeds/listings.atom")));
+        assert!(!is_public_route(Some("GET"), Some("/feeds/listings-link")));
+        assert!(is_public_route(Some("GET"), Some("/health")));
+        assert!(is_public_route(Some("GET"), Some("/listings/discover")));
+        assert!(!is_public_route(Some("GET"), Some("/listings/along-route")));
+        assert!(!is_public_route(Some("GET"), Some("/health/deep")));
+        assert!(!is_public_route(Some("GET"), Some("/me/schedule-link")));
+        assert!(!is_public_route(Some("POST"), Some("/openapi.json")));
+        assert!(!is_public_route(Some("POST"), Some("/stats/impact")));
+        assert!(!is_public_route(Some("GET"), Some("/stats/impact/extra")));
+        assert!(!is_public_route(Some("GET"), Some("/me")));
+        assert!(!is_public_route(None, Some("/stats/impact")));
+    }
+
     // Helper function to extract tier mapping logic for testing
     fn map_group_to_tier(group_names: &[&str]) -> String {
         if group_names.contains(&"caretaker-tier") {
@@ -498,6 +728,24 @@ mod tests {
         assert_eq!(map_group_to_tier(&groups), "caretaker");
     }
 
+    #[test]
+    fn tier_from_groups_matches_group_precedence() {
+        let groups = vec!["neighbor-tier".to_string(), "caretaker-tier".to_string()];
+        assert_eq!(tier_from_groups(&groups), "caretaker");
+        assert_eq!(tier_from_groups(&["admin".to_string()]), "neighbor");
+        assert_eq!(tier_from_groups(&[]), "neighbor");
+    }
+
+    #[test]
+    fn hash_api_key_is_stable_lowercase_hex() {
+        let hash = hash_api_key("cgk_example");
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_api_key("cgk_example"));
+        assert!(hash
+            .chars()
+            .all(|c| c.is_ascii_hexdigit() && !c.is_ascii_uppercase()));
+    }
+
     #[test]
     fn normalize_user_type_accepts_supported_values_case_insensitive() {
         assert_eq!(normalize_user_type("grower"), Some("grower".to_string()));
@@ -0,0 +1,51 @@
+//! Applies the SQL files in `db/migrations`, embedded at compile time, so a
+//! build of this crate always carries the schema it expects. Shares the
+//! `schema_migrations` table with `db/migrate.sh`; either can run against a
+//! database the other has migrated.
+
+use tokio_postgres::Client;
+use tracing::info;
+
+#[path = "../api/pg_tls.rs"]
+mod pg_tls;
+mod runner;
+
+use pg_tls::ConnectSettings;
+use runner::Error;
+
+fn install_rustls_crypto_provider() {
+    let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+}
+
+#[tokio::main]
+async fn main() -> Result<(), Error> {
+    install_rustls_crypto_provider();
+    tracing_subscriber::fmt()
+        .with_env_filter(
+            tracing_subscriber::EnvFilter::try_from_default_env()
+                .unwrap_or_else(|_| tracing_subscriber::EnvFilter::new("info")),
+        )
+        .init();
+
+    let database_url =
+        std::env::var("DATABASE_URL").map_err(|_| "DATABASE_URL must be set".to_string())?;
+    let mut client = connect(&database_url).await?;
+
+    let applied = runner::migrate(&mut client).await?;
+    info!(applied, "Migrations complete");
+    Ok(())
+}
+
+async fn connect(database_url: &str) -> Result<Client, Error> {
+    let settings = ConnectSettings::from_database_url(database_url)?;
+    let tls = settings.tls_connector()?;
+    let (client, connection) = settings.config.connect(tls).await?;
+
+    tokio::spawn(async move {
+        if let Err(err) = connection.await {
+            tracing::error!(error = %err, "Postgres connection error during migrations");
+        }
+    });
+
+    Ok(client)
+}
@@ -0,0 +1,260 @@
+//! The embedded migration list and the code that applies it. Shared by the
+//! `migrations` binary and the API's Postgres-backed tests, so both always
+//! run the same schema.
+
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use tokio_postgres::Client;
+use tracing::{info, warn};
+
+pub type Error = Box<dyn std::error::Error + Send + Sync>;
+
+/// Held for the whole run so concurrent deploys cannot interleave migrations.
+const MIGRATION_LOCK_KEY: i64 = 7_362_110_934_551;
+
+const VERSION_TABLE_SQL: &str = "
+    create table if not exists schema_migrations (
+      version text primary key,
+      applied_at timestamptz not null default now()
+    );
+    alter table schema_migrations add column if not exists checksum text;
+";
+
+#[derive(Debug)]
+struct Migration {
+    version: &'static str,
+    sql: &'static str,
+}
+
+macro_rules! migration {
+    ($file:literal) => {
+        Migration {
+            version: $file,
+            sql: include_str!(concat!("../../db/migrations/", $file)),
+        }
+    };
+}
+
+/// Numbered migrations only; the `test_*.sql` verification scripts stay with
+/// `migrate.sh`. New files must be added here in filename order.
+static MIGRATIONS: &[Migration] = &[
+    // `0001_init.sql` is only a psql `\ir` of `db/ddl.sql`, which the server
+    // cannot follow, so the included file is embedded in its place.
+    Migration {
+        version: "0001_init.sql",
+        sql: include_str!("../../db/ddl.sql"),
+    },
+    migration!("0002_user_onboarding.sql"),
+    migration!("0003_my_listings_read_indexes.sql"),
+    migration!("0004_safe_location_handling.sql"),
+    migration!("0005_listing_discovery_read_indexes.sql"),
+    migration!("0006_derived_supply_signals.sql"),
+    migration!("0007_derived_signal_summaries.sql"),
+    migration!("0008_perf_cost_hardening.sql"),
+    migration!("0009_user_tier_subscription_state.sql"),
+    migration!("0010_stripe_billing_foundation.sql"),
+    migration!("0011_stripe_webhook_hardening.sql"),
+    migration!("0012_deterministic_reminders.sql"),
+    migration!("0013_agentic_task_runner.sql"),
+    migration!("0014_ai_guardrails_usage_budget.sql"),
+    migration!("0015_premium_analytics_events.sql"),
+    migration!("0016_catalog_source_provenance.sql"),
+    migration!("0017_badge_evidence_pipeline.sql"),
+    migration!("0018_gardener_tier_badges.sql"),
+    migration!("0019_first_harvest_badge_uniqueness.sql"),
+    migration!("0020_gardener_season_ladder.sql"),
+    migration!("0021_user_experience_levels.sql"),
+    migration!("0022_radius_columns_to_double_precision.sql"),
+    migration!("0023_impact_reports.sql"),
+    migration!("0024_pipeline_checkpoints.sql"),
+    migration!("0025_feed_geo_access.sql"),
+    migration!("0026_derived_signal_forecasts.sql"),
+    migration!("0027_signal_anomalies.sql"),
+    migration!("0028_organizations_and_api_keys.sql"),
+    migration!("0029_audit_log.sql"),
+    migration!("0030_soft_delete_purge_indexes.sql"),
+    migration!("0031_conversations.sql"),
+    migration!("0032_groups.sql"),
+    migration!("0033_community_events.sql"),
+    migration!("0034_volunteer_delivery.sql"),
+    migration!("0035_donation_receipts.sql"),
+    migration!("0036_user_achievements.sql"),
+    migration!("0037_announcements.sql"),
+    migration!("0038_user_follows.sql"),
+    migration!("0039_schedule_feed_tokens.sql"),
+    migration!("0040_embeddings.sql"),
+    migration!("0041_moderation_queue.sql"),
+    migration!("0042_listing_photo_crop_check.sql"),
+    migration!("0043_ai_feedback.sql"),
+    migration!("0044_ai_usage.sql"),
+    migration!("0045_admin_moderation_actions.sql"),
+    migration!("0046_retention_policies.sql"),
+    migration!("0047_impersonation_sessions.sql"),
+    migration!("0048_geocode_cache.sql"),
+    migration!("0049_geo_labels.sql"),
+    migration!("0050_geocode_cache_confidence.sql"),
+    migration!("0051_harvests.sql"),
+    migration!("0052_garden_plots.sql"),
+    migration!("0053_listing_kinds.sql"),
+    migration!("0054_claim_reservation_holds.sql"),
+    migration!("0055_search_trigram_indexes.sql"),
+    migration!("0056_organization_service_areas.sql"),
+    migration!("0057_grower_enforce_share_radius.sql"),
+    migration!("0058_listing_templates.sql"),
+    migration!("0059_crop_plans.sql"),
+    migration!("0060_claim_digests.sql"),
+    migration!("0061_organization_webhooks.sql"),
+    migration!("0062_grower_away_periods.sql"),
+    migration!("0063_listing_questions.sql"),
+    migration!("0064_grower_co_managers.sql"),
+    migration!("0065_crop_name_translations.sql"),
+    migration!("0066_claim_request_quantity.sql"),
+    migration!("0067_claims_updated_at.sql"),
+    migration!("0068_crop_nutrition_groups.sql"),
+    migration!("0069_listing_schedule.sql"),
+    migration!("0070_request_templates.sql"),
+    migration!("0071_grower_dashboard_indexes.sql"),
+];
+
+/// Applies every migration not yet recorded in `schema_migrations`, holding
+/// the migration lock throughout. Returns how many were applied.
+pub async fn migrate(client: &mut Client) -> Result<usize, Error> {
+    client.batch_execute(VERSION_TABLE_SQL).await?;
+    client
+        .execute("select pg_advisory_lock($1)", &[&MIGRATION_LOCK_KEY])
+        .await?;
+    let result = apply_pending(client).await;
+    client
+        .execute("select pg_advisory_unlock($1)", &[&MIGRATION_LOCK_KEY])
+        .await?;
+    result
+}
+
+async fn apply_pending(client: &mut Client) -> Result<usize, Error> {
+    let applied: HashMap<String, Option<String>> = client
+        .query("select version, checksum from schema_migrations", &[])
+        .await?
+        .into_iter()
+        .map(|row| (row.get("version"), row.get("checksum")))
+        .collect();
+
+    let mut applied_count = 0;
+    for migration in MIGRATIONS {
+        let checksum = checksum(migration.sql);
+
+        if let Some(recorded) = applied.get(migration.version) {
+            if recorded
+                .as_deref()
+                .is_some_and(|recorded| recorded != checksum)
+            {
+                warn!(
+                    version = migration.version,
+                    "Migration file changed after it was applied; not re-running"
+                );
+            }
+            continue;
+        }
+
+        info!(version = migration.version, "Applying migration");
+        apply(client, migration, &checksum)
+            .await
+            .map_err(|err| format!("Migration {} failed: {err}", migration.version))?;
+        applied_count += 1;
+    }
+
+    Ok(applied_count)
+}
+
+/// Files with their own `begin;`/`commit;` run as written, like under psql;
+/// the rest run in a transaction together with their version row.
+async fn apply(client: &mut Client, migration: &Migration, checksum: &str) -> Result<(), Error> {
+    let record = "insert into schema_migrations (version, checksum) values ($1, $2)";
+
+    if manages_own_transaction(migration.sql) {
+        client.batch_execute(migration.sql).await?;
+        client
+            .execute(record, &[&migration.version, &checksum])
+            .await?;
+        return Ok(());
+    }
+
+    let tx = client.transaction().await?;
+    tx.batch_execute(migration.sql).await?;
+    tx.execute(record, &[&migration.version, &checksum]).await?;
+    tx.commit().await?;
+    Ok(())
+}
+
+fn manages_own_transaction(sql: &str) -> bool {
+    sql.lines().any(|line| {
+        let line = line.trim().to_ascii_lowercase();
+        line == "begin;" || line == "begin transaction;"
+    })
+}
+
+fn checksum(sql: &str) -> String {
+    hex::encode(Sha256::digest(sql.as_bytes()))
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn embedded_migrations_match_directory() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/db/migrations");
+        let mut on_disk: Vec<String> = std::fs::read_dir(dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .filter(|name| {
+                std::path::Path::new(name)
+                    .extension()
+                    .is_some_and(|ext| ext == "sql")
+                    && !name.starts_with("test_")
+            })
+            .collect();
+        on_disk.sort();
+
+        let embedded: Vec<&str> = MIGRATIONS.iter().map(|m| m.version).collect();
+        assert_eq!(embedded, on_disk);
+    }
+
+    #[test]
+    fn embedded_migrations_have_no_psql_meta_commands() {
+        for migration in MIGRATIONS {
+            assert!(
+                !migration
+                    .sql
+                    .lines()
+                    .any(|line| line.trim_start().starts_with('\\')),
+                "{}",
+                migration.version
+            );
+        }
+    }
+
+    #[test]
+    fn migrations_are_in_filename_order() {
+        for pair in MIGRATIONS.windows(2) {
+            assert!(pair[0].version < pair[1].version, "{}", pair[1].version);
+        }
+    }
+
+    #[test]
+    fn detects_self_managed_transactions() {
+        assert!(manages_own_transaction(
+            "-- x\nbegin;\ncreate table t ();\ncommit;\n"
+        ));
+        assert!(manages_own_transaction("BEGIN;\nselect 1;\nCOMMIT;"));
+        assert!(!manages_own_transaction(
+            "do $$\nbegin\n  perform 1;\nend $$;"
+        ));
+    }
+
+    #[test]
+    fn checksum_is_stable_hex() {
+        assert_eq!(checksum("select 1;"), checksum("select 1;"));
+        assert_eq!(checksum("select 1;").len(), 64);
+    }
+}